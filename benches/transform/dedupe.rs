@@ -1,14 +1,18 @@
 use core::fmt;
-use std::{num::NonZeroUsize, time::Duration};
+use std::{collections::HashMap, num::NonZeroUsize, time::Duration};
 
 use criterion::{
     criterion_group, measurement::WallTime, BatchSize, BenchmarkGroup, BenchmarkId, Criterion,
     SamplingMode, Throughput,
 };
+use futures::executor::block_on_stream;
 use vector::transforms::dedupe::{CacheConfig, Dedupe, DedupeConfig, FieldMatchConfig};
-use vector_lib::transform::Transform;
+use vector_lib::{
+    config::{DataType, TransformOutput},
+    transform::{SyncTransform, TransformOutputsBuf},
+};
 
-use crate::common::{consume, FixedLogStream};
+use crate::common::FixedLogStream;
 
 #[derive(Debug)]
 struct Param {
@@ -44,6 +48,10 @@ fn dedupe(c: &mut Criterion) {
             dedupe_config: DedupeConfig {
                 fields: Some(FieldMatchConfig::IgnoreFields(vec!["message".into()])),
                 cache: cache.clone(),
+                time_settings: None,
+                flush_to_disk: false,
+                data_dir: None,
+                reroute_duplicates: false,
             },
         },
         // Modification of previous where field "message" is matched.
@@ -53,6 +61,10 @@ fn dedupe(c: &mut Criterion) {
             dedupe_config: DedupeConfig {
                 fields: Some(FieldMatchConfig::MatchFields(vec!["message".into()])),
                 cache: cache.clone(),
+                time_settings: None,
+                flush_to_disk: false,
+                data_dir: None,
+                reroute_duplicates: false,
             },
         },
         // Measurement where ignore fields do not exist in the event.
@@ -68,6 +80,10 @@ fn dedupe(c: &mut Criterion) {
                     "cdeab".into(),
                     "bcdea".into(),
                 ])),
+                time_settings: None,
+                flush_to_disk: false,
+                data_dir: None,
+                reroute_duplicates: false,
             },
         },
         // Modification of previous where match fields do not exist in the
@@ -84,6 +100,10 @@ fn dedupe(c: &mut Criterion) {
                     "cdeab".into(),
                     "bcdea".into(),
                 ])),
+                time_settings: None,
+                flush_to_disk: false,
+                data_dir: None,
+                reroute_duplicates: false,
             },
         },
     ] {
@@ -91,13 +111,17 @@ fn dedupe(c: &mut Criterion) {
         group.bench_with_input(BenchmarkId::new("transform", param), &param, |b, param| {
             b.iter_batched(
                 || {
-                    let dedupe =
-                        Transform::event_task(Dedupe::new(param.dedupe_config.clone())).into_task();
-                    (Box::new(dedupe), Box::pin(param.input.clone()))
+                    let dedupe = Dedupe::new(param.dedupe_config.clone(), None);
+                    let output = TransformOutputsBuf::new_with_capacity(
+                        vec![TransformOutput::new(DataType::Log, HashMap::new())],
+                        param.input.len(),
+                    );
+                    (dedupe, param.input.clone(), output)
                 },
-                |(dedupe, input)| {
-                    let output = dedupe.transform_events(input);
-                    consume(output)
+                |(mut dedupe, input, mut output)| {
+                    for event in block_on_stream(input) {
+                        dedupe.transform(event, &mut output);
+                    }
                 },
                 BatchSize::SmallInput,
             )
@@ -50,6 +50,11 @@ fn reduce(c: &mut Criterion) {
                 ends_when: None,
                 starts_when: None,
                 max_events: None,
+                max_groups: None,
+                max_bytes: None,
+                eviction_policy: Default::default(),
+                persist_state: false,
+                data_dir: None,
             },
         },
     ] {
@@ -59,7 +64,7 @@ fn reduce(c: &mut Criterion) {
                 .iter_batched(
                     || {
                         let reduce = Transform::event_task(
-                            Reduce::new(&param.reduce_config, &Default::default()).unwrap(),
+                            Reduce::new(&param.reduce_config, &Default::default(), None).unwrap(),
                         )
                         .into_task();
                         (Box::new(reduce), Box::pin(param.input.clone()))
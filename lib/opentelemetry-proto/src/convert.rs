@@ -1,17 +1,32 @@
+use std::ops::RangeInclusive;
+
 use bytes::Bytes;
 use chrono::{DateTime, TimeZone, Utc};
-use lookup::path;
+use lookup::{event_path, path};
 use ordered_float::NotNan;
 use vector_core::{
     config::{log_schema, LegacyKey, LogNamespace},
-    event::{Event, LogEvent},
+    event::{
+        metric::{Bucket, MetricSketch, Quantile},
+        Event, LogEvent, Metric, MetricKind, MetricTags, MetricValue, TraceEvent,
+    },
+    metrics::AgentDDSketch,
 };
 use vrl::value::{ObjectMap, Value};
 
 use super::proto::{
-    common::v1::{any_value::Value as PBValue, KeyValue},
+    common::v1::{any_value::Value as PBValue, InstrumentationScope, KeyValue},
     logs::v1::{LogRecord, ResourceLogs, SeverityNumber},
+    metrics::v1::{
+        exponential_histogram_data_point::Buckets, metric::Data, number_data_point,
+        AggregationTemporality, DataPointFlags, ExponentialHistogramDataPoint, HistogramDataPoint,
+        Metric as OtelMetric, NumberDataPoint, ResourceMetrics, SummaryDataPoint,
+    },
     resource::v1::Resource,
+    trace::v1::{
+        span::{Event as SpanEvent, Link},
+        ResourceSpans, Span, Status as SpanStatus,
+    },
 };
 
 const SOURCE_NAME: &str = "opentelemetry";
@@ -26,6 +41,13 @@ pub const OBSERVED_TIMESTAMP_KEY: &str = "observed_timestamp";
 pub const DROPPED_ATTRIBUTES_COUNT_KEY: &str = "dropped_attributes_count";
 pub const FLAGS_KEY: &str = "flags";
 
+/// Tag key prefix used for resource attributes on converted metrics.
+pub const RESOURCE_TAG_PREFIX: &str = "resource.";
+/// Tag key used for the instrumentation scope name on converted metrics.
+pub const SCOPE_NAME_TAG: &str = "scope.name";
+/// Tag key used for the instrumentation scope version on converted metrics.
+pub const SCOPE_VERSION_TAG: &str = "scope.version";
+
 impl ResourceLogs {
     pub fn into_event_iter(self, log_namespace: LogNamespace) -> impl Iterator<Item = Event> {
         let resource = self.resource;
@@ -224,3 +246,462 @@ impl ResourceLog {
         log.into()
     }
 }
+
+impl ResourceMetrics {
+    pub fn into_event_iter(self) -> impl Iterator<Item = Event> {
+        let mut resource_tags = MetricTags::default();
+        if let Some(resource) = self.resource {
+            insert_attribute_tags(&mut resource_tags, RESOURCE_TAG_PREFIX, resource.attributes);
+        }
+
+        self.scope_metrics
+            .into_iter()
+            .flat_map(move |scope_metrics| {
+                let mut tags = resource_tags.clone();
+                if let Some(scope) = scope_metrics.scope {
+                    insert_scope_tags(&mut tags, scope);
+                }
+
+                scope_metrics
+                    .metrics
+                    .into_iter()
+                    .flat_map(move |metric| metric_into_events(metric, &tags))
+            })
+    }
+}
+
+fn insert_attribute_tags(tags: &mut MetricTags, prefix: &str, attributes: Vec<KeyValue>) {
+    for kv in attributes {
+        if let Some(value) = kv.value.and_then(|av| av.value) {
+            tags.replace(format!("{prefix}{}", kv.key), any_value_into_tag(value));
+        }
+    }
+}
+
+fn insert_scope_tags(tags: &mut MetricTags, scope: InstrumentationScope) {
+    if !scope.name.is_empty() {
+        tags.replace(SCOPE_NAME_TAG.to_string(), scope.name);
+    }
+    if !scope.version.is_empty() {
+        tags.replace(SCOPE_VERSION_TAG.to_string(), scope.version);
+    }
+}
+
+fn any_value_into_tag(value: PBValue) -> String {
+    match value {
+        PBValue::StringValue(v) => v,
+        PBValue::BytesValue(v) => hex::encode(v),
+        other => Value::from(other).to_string(),
+    }
+}
+
+const fn temporality_into_kind(aggregation_temporality: i32) -> MetricKind {
+    if aggregation_temporality == AggregationTemporality::Delta as i32 {
+        MetricKind::Incremental
+    } else {
+        MetricKind::Absolute
+    }
+}
+
+const fn has_no_recorded_value(flags: u32) -> bool {
+    flags & DataPointFlags::NoRecordedValueMask as u32 != 0
+}
+
+// https://github.com/open-telemetry/opentelemetry-specification/blob/v1.15.0/specification/metrics/data-model.md
+fn metric_into_events(metric: OtelMetric, tags: &MetricTags) -> Vec<Event> {
+    let name = metric.name;
+
+    match metric.data {
+        Some(Data::Gauge(gauge)) => gauge
+            .data_points
+            .into_iter()
+            .filter_map(|point| {
+                let value = number_point_value(&point)?;
+                Some(new_metric_event(
+                    &name,
+                    MetricKind::Absolute,
+                    MetricValue::Gauge { value },
+                    point.attributes,
+                    point.time_unix_nano,
+                    tags,
+                ))
+            })
+            .collect(),
+        Some(Data::Sum(sum)) => {
+            let kind = temporality_into_kind(sum.aggregation_temporality);
+            let is_monotonic = sum.is_monotonic;
+            sum.data_points
+                .into_iter()
+                .filter_map(|point| {
+                    let value = number_point_value(&point)?;
+                    // Only monotonic sums map to counters, since a counter can never decrease
+                    // (except by being reset to zero).
+                    let value = if is_monotonic {
+                        MetricValue::Counter { value }
+                    } else {
+                        MetricValue::Gauge { value }
+                    };
+                    Some(new_metric_event(
+                        &name,
+                        kind,
+                        value,
+                        point.attributes,
+                        point.time_unix_nano,
+                        tags,
+                    ))
+                })
+                .collect()
+        }
+        Some(Data::Histogram(histogram)) => {
+            let kind = temporality_into_kind(histogram.aggregation_temporality);
+            histogram
+                .data_points
+                .into_iter()
+                .filter(|point| !has_no_recorded_value(point.flags))
+                .map(|point| {
+                    let value = histogram_point_value(&point);
+                    new_metric_event(
+                        &name,
+                        kind,
+                        value,
+                        point.attributes,
+                        point.time_unix_nano,
+                        tags,
+                    )
+                })
+                .collect()
+        }
+        Some(Data::ExponentialHistogram(histogram)) => {
+            let kind = temporality_into_kind(histogram.aggregation_temporality);
+            histogram
+                .data_points
+                .into_iter()
+                .filter(|point| !has_no_recorded_value(point.flags))
+                .filter_map(|point| {
+                    let value = exponential_histogram_point_value(&point)?;
+                    Some(new_metric_event(
+                        &name,
+                        kind,
+                        value,
+                        point.attributes,
+                        point.time_unix_nano,
+                        tags,
+                    ))
+                })
+                .collect()
+        }
+        Some(Data::Summary(summary)) => summary
+            .data_points
+            .into_iter()
+            .filter(|point| !has_no_recorded_value(point.flags))
+            .map(|point| {
+                let value = summary_point_value(&point);
+                // Summaries are always cumulative in OTLP.
+                new_metric_event(
+                    &name,
+                    MetricKind::Absolute,
+                    value,
+                    point.attributes,
+                    point.time_unix_nano,
+                    tags,
+                )
+            })
+            .collect(),
+        None => Vec::new(),
+    }
+}
+
+fn new_metric_event(
+    name: &str,
+    kind: MetricKind,
+    value: MetricValue,
+    attributes: Vec<KeyValue>,
+    time_unix_nano: u64,
+    tags: &MetricTags,
+) -> Event {
+    let mut tags = tags.clone();
+    insert_attribute_tags(&mut tags, "", attributes);
+
+    // A timestamp of 0 represents a missing or unknown timestamp.
+    let timestamp = (time_unix_nano > 0).then(|| Utc.timestamp_nanos(time_unix_nano as i64));
+
+    Metric::new(name, kind, value)
+        .with_tags(tags.as_option())
+        .with_timestamp(timestamp)
+        .into()
+}
+
+fn number_point_value(point: &NumberDataPoint) -> Option<f64> {
+    if has_no_recorded_value(point.flags) {
+        return None;
+    }
+
+    match point.value? {
+        number_data_point::Value::AsDouble(value) => Some(value),
+        number_data_point::Value::AsInt(value) => Some(value as f64),
+    }
+}
+
+fn histogram_point_value(point: &HistogramDataPoint) -> MetricValue {
+    // There is always one more bucket count than there are explicit bounds, with the last bucket
+    // covering everything above the highest bound.
+    let buckets = point
+        .bucket_counts
+        .iter()
+        .enumerate()
+        .map(|(i, count)| Bucket {
+            upper_limit: point
+                .explicit_bounds
+                .get(i)
+                .copied()
+                .unwrap_or(f64::INFINITY),
+            count: *count,
+        })
+        .collect();
+
+    MetricValue::AggregatedHistogram {
+        buckets,
+        count: point.count,
+        sum: point.sum.unwrap_or(0.0),
+    }
+}
+
+/// The range of scales allowed by the OTLP specification for exponential histograms, apart from
+/// -10, whose bucket base of `2^1024` doesn't fit in an `f64`. Points outside of it are dropped.
+const EXPONENTIAL_HISTOGRAM_SCALES: RangeInclusive<i32> = -9..=20;
+
+fn exponential_histogram_point_value(point: &ExponentialHistogramDataPoint) -> Option<MetricValue> {
+    if !EXPONENTIAL_HISTOGRAM_SCALES.contains(&point.scale) {
+        return None;
+    }
+
+    // The base of the buckets is `2^(2^-scale)`, so the bound at `index` is `2^(index * 2^-scale)`.
+    let exponent = 2f64.powi(-point.scale);
+    let mut sketch = AgentDDSketch::with_agent_defaults();
+
+    if point.zero_count > 0 {
+        sketch.insert_n(0.0, u32::try_from(point.zero_count).unwrap_or(u32::MAX));
+    }
+    if let Some(positive) = &point.positive {
+        insert_exponential_buckets(&mut sketch, exponent, positive, 1.0)?;
+    }
+    if let Some(negative) = &point.negative {
+        insert_exponential_buckets(&mut sketch, exponent, negative, -1.0)?;
+    }
+
+    // The bucket midpoints only approximate the observations, so prefer the exact summary values
+    // reported by the point whenever they're present.
+    let count = u32::try_from(point.count).unwrap_or(u32::MAX);
+    let sum = point.sum.or_else(|| sketch.sum()).unwrap_or(0.0);
+    let min = point.min.or_else(|| sketch.min()).unwrap_or(f64::MAX);
+    let max = point.max.or_else(|| sketch.max()).unwrap_or(f64::MIN);
+    let avg = if count > 0 {
+        sum / f64::from(count)
+    } else {
+        0.0
+    };
+    let (keys, counts) = sketch.bin_map().into_parts();
+    let sketch = AgentDDSketch::from_raw(count, min, max, sum, avg, &keys, &counts)?;
+
+    Some(MetricValue::Sketch {
+        sketch: MetricSketch::AgentDDSketch(sketch),
+    })
+}
+
+/// Inserts the observations of the given buckets into the sketch.
+///
+/// Returns `None` if the bounds of a bucket with observations don't fit in an `f64`.
+fn insert_exponential_buckets(
+    sketch: &mut AgentDDSketch,
+    exponent: f64,
+    buckets: &Buckets,
+    sign: f64,
+) -> Option<()> {
+    for (i, count) in buckets.bucket_counts.iter().enumerate() {
+        if *count == 0 {
+            continue;
+        }
+
+        // The bucket at `index` covers the range `(base^index, base^(index + 1)]`, so we
+        // approximate all of its observations with the midpoint of that range.
+        let index = f64::from(buckets.offset) + i as f64;
+        let lower = (index * exponent).exp2();
+        let upper = ((index + 1.0) * exponent).exp2();
+        if !upper.is_finite() {
+            return None;
+        }
+        let midpoint = lower / 2.0 + upper / 2.0;
+        sketch.insert_n(sign * midpoint, u32::try_from(*count).unwrap_or(u32::MAX));
+    }
+    Some(())
+}
+
+fn summary_point_value(point: &SummaryDataPoint) -> MetricValue {
+    MetricValue::AggregatedSummary {
+        quantiles: point
+            .quantile_values
+            .iter()
+            .map(|q| Quantile {
+                quantile: q.quantile,
+                value: q.value,
+            })
+            .collect(),
+        count: point.count,
+        sum: point.sum,
+    }
+}
+
+impl ResourceSpans {
+    pub fn into_event_iter(self) -> impl Iterator<Item = Event> {
+        let resource = self.resource;
+        let now = Utc::now();
+
+        self.scope_spans.into_iter().flat_map(move |scope_spans| {
+            let resource = resource.clone();
+            let scope = scope_spans.scope;
+            scope_spans.spans.into_iter().map(move |span| {
+                ResourceSpan {
+                    resource: resource.clone(),
+                    scope: scope.clone(),
+                    span,
+                }
+                .into_event(now)
+            })
+        })
+    }
+}
+
+struct ResourceSpan {
+    resource: Option<Resource>,
+    scope: Option<InstrumentationScope>,
+    span: Span,
+}
+
+// https://github.com/open-telemetry/opentelemetry-specification/blob/v1.15.0/specification/trace/api.md
+impl ResourceSpan {
+    fn into_event(self, now: DateTime<Utc>) -> Event {
+        let span = self.span;
+        let mut trace = TraceEvent::default();
+
+        trace.insert(event_path!(TRACE_ID_KEY), hex::encode(span.trace_id));
+        trace.insert(event_path!(SPAN_ID_KEY), hex::encode(span.span_id));
+        trace.insert(
+            event_path!("parent_span_id"),
+            hex::encode(span.parent_span_id),
+        );
+        trace.insert(event_path!("trace_state"), span.trace_state);
+        trace.insert(event_path!("name"), span.name);
+        trace.insert(event_path!("kind"), span.kind);
+        trace.insert(
+            event_path!("start_time_unix_nano"),
+            Value::from(Utc.timestamp_nanos(span.start_time_unix_nano as i64)),
+        );
+        trace.insert(
+            event_path!("end_time_unix_nano"),
+            Value::from(Utc.timestamp_nanos(span.end_time_unix_nano as i64)),
+        );
+        if !span.attributes.is_empty() {
+            trace.insert(
+                event_path!(ATTRIBUTES_KEY),
+                kv_list_into_value(span.attributes),
+            );
+        }
+        trace.insert(
+            event_path!(DROPPED_ATTRIBUTES_COUNT_KEY),
+            span.dropped_attributes_count,
+        );
+        if !span.events.is_empty() {
+            trace.insert(
+                event_path!("events"),
+                span.events
+                    .into_iter()
+                    .map(span_event_into_value)
+                    .collect::<Vec<Value>>(),
+            );
+        }
+        trace.insert(
+            event_path!("dropped_events_count"),
+            span.dropped_events_count,
+        );
+        if !span.links.is_empty() {
+            trace.insert(
+                event_path!("links"),
+                span.links
+                    .into_iter()
+                    .map(span_link_into_value)
+                    .collect::<Vec<Value>>(),
+            );
+        }
+        trace.insert(event_path!("dropped_links_count"), span.dropped_links_count);
+        if let Some(status) = span.status {
+            trace.insert(event_path!("status"), span_status_into_value(status));
+        }
+
+        if let Some(resource) = self.resource {
+            if !resource.attributes.is_empty() {
+                trace.insert(
+                    event_path!(RESOURCE_KEY),
+                    kv_list_into_value(resource.attributes),
+                );
+            }
+        }
+        if let Some(scope) = self.scope {
+            trace.insert(event_path!("scope"), scope_into_value(scope));
+        }
+
+        trace.insert(event_path!("ingest_timestamp"), now);
+        trace.insert(
+            event_path!("source_type"),
+            Bytes::from_static(SOURCE_NAME.as_bytes()),
+        );
+
+        trace.into()
+    }
+}
+
+fn span_event_into_value(event: SpanEvent) -> Value {
+    Value::Object(ObjectMap::from([
+        ("name".into(), event.name.into()),
+        (
+            "time_unix_nano".into(),
+            Value::from(Utc.timestamp_nanos(event.time_unix_nano as i64)),
+        ),
+        (ATTRIBUTES_KEY.into(), kv_list_into_value(event.attributes)),
+        (
+            DROPPED_ATTRIBUTES_COUNT_KEY.into(),
+            event.dropped_attributes_count.into(),
+        ),
+    ]))
+}
+
+fn span_link_into_value(link: Link) -> Value {
+    Value::Object(ObjectMap::from([
+        (TRACE_ID_KEY.into(), hex::encode(link.trace_id).into()),
+        (SPAN_ID_KEY.into(), hex::encode(link.span_id).into()),
+        ("trace_state".into(), link.trace_state.into()),
+        (ATTRIBUTES_KEY.into(), kv_list_into_value(link.attributes)),
+        (
+            DROPPED_ATTRIBUTES_COUNT_KEY.into(),
+            link.dropped_attributes_count.into(),
+        ),
+    ]))
+}
+
+fn span_status_into_value(status: SpanStatus) -> Value {
+    Value::Object(ObjectMap::from([
+        ("message".into(), status.message.into()),
+        ("code".into(), status.code.into()),
+    ]))
+}
+
+fn scope_into_value(scope: InstrumentationScope) -> Value {
+    Value::Object(ObjectMap::from([
+        ("name".into(), scope.name.into()),
+        ("version".into(), scope.version.into()),
+        (ATTRIBUTES_KEY.into(), kv_list_into_value(scope.attributes)),
+        (
+            DROPPED_ATTRIBUTES_COUNT_KEY.into(),
+            scope.dropped_attributes_count.into(),
+        ),
+    ]))
+}
@@ -0,0 +1,930 @@
+//! Conversion of Vector events into OTLP export requests.
+//!
+//! This is the inverse of [`crate::convert`]: events that were received by the `opentelemetry`
+//! source round-trip back into the equivalent OTLP messages, while events from any other source
+//! are mapped on a best-effort basis.
+
+use chrono::{DateTime, Utc};
+use lookup::{event_path, metadata_path};
+use vector_core::{
+    config::{log_schema, LogNamespace},
+    event::{
+        metric::{Bucket, MetricSketch, Quantile},
+        LogEvent, Metric, MetricKind, MetricValue, TraceEvent,
+    },
+};
+use vrl::value::{ObjectMap, Value};
+
+use super::{
+    convert::{
+        ATTRIBUTES_KEY, DROPPED_ATTRIBUTES_COUNT_KEY, FLAGS_KEY, OBSERVED_TIMESTAMP_KEY,
+        RESOURCE_KEY, RESOURCE_TAG_PREFIX, SCOPE_NAME_TAG, SCOPE_VERSION_TAG, SEVERITY_NUMBER_KEY,
+        SEVERITY_TEXT_KEY, SPAN_ID_KEY, TRACE_ID_KEY,
+    },
+    proto::{
+        collector::{
+            logs::v1::ExportLogsServiceRequest, metrics::v1::ExportMetricsServiceRequest,
+            trace::v1::ExportTraceServiceRequest,
+        },
+        common::v1::{
+            any_value::Value as PBValue, AnyValue, ArrayValue, InstrumentationScope, KeyValue,
+            KeyValueList,
+        },
+        logs::v1::{LogRecord, ResourceLogs, ScopeLogs},
+        metrics::v1::{
+            metric::Data, number_data_point, summary_data_point::ValueAtQuantile,
+            AggregationTemporality, Gauge, Histogram, HistogramDataPoint, Metric as OtelMetric,
+            NumberDataPoint, ResourceMetrics, ScopeMetrics, Sum, Summary, SummaryDataPoint,
+        },
+        resource::v1::Resource,
+        trace::v1::{
+            span::{Event as SpanEvent, Link},
+            ResourceSpans, ScopeSpans, Span, Status as SpanStatus,
+        },
+    },
+};
+
+const SOURCE_NAME: &str = "opentelemetry";
+
+/// Quantiles reported when a sketch is exported as an OTLP summary.
+const SKETCH_QUANTILES: [f64; 5] = [0.5, 0.75, 0.9, 0.95, 0.99];
+
+/// Builds a single export request out of the given logs, grouping the records by resource.
+pub fn logs_into_request(logs: impl IntoIterator<Item = LogEvent>) -> ExportLogsServiceRequest {
+    let records = logs.into_iter().map(|log| {
+        let (resource, record) = log_into_record(log);
+        ((resource, None), record)
+    });
+
+    ExportLogsServiceRequest {
+        resource_logs: group_by_resource_and_scope(records)
+            .into_iter()
+            .map(|(resource, scopes)| ResourceLogs {
+                resource,
+                scope_logs: scopes
+                    .into_iter()
+                    .map(|(scope, log_records)| ScopeLogs {
+                        scope,
+                        log_records,
+                        schema_url: String::new(),
+                    })
+                    .collect(),
+                schema_url: String::new(),
+            })
+            .collect(),
+    }
+}
+
+/// Builds a single export request out of the given metrics, grouping them by resource and
+/// instrumentation scope.
+pub fn metrics_into_request(
+    metrics: impl IntoIterator<Item = Metric>,
+) -> ExportMetricsServiceRequest {
+    let now = Utc::now();
+    let metrics = metrics
+        .into_iter()
+        .filter_map(|metric| metric_into_parts(metric, now));
+
+    ExportMetricsServiceRequest {
+        resource_metrics: group_by_resource_and_scope(metrics)
+            .into_iter()
+            .map(|(resource, scopes)| ResourceMetrics {
+                resource,
+                scope_metrics: scopes
+                    .into_iter()
+                    .map(|(scope, metrics)| ScopeMetrics {
+                        scope,
+                        metrics,
+                        schema_url: String::new(),
+                    })
+                    .collect(),
+                schema_url: String::new(),
+            })
+            .collect(),
+    }
+}
+
+/// Builds a single export request out of the given traces, grouping the spans by resource and
+/// instrumentation scope.
+pub fn traces_into_request(
+    traces: impl IntoIterator<Item = TraceEvent>,
+) -> ExportTraceServiceRequest {
+    let spans = traces.into_iter().map(trace_into_parts);
+
+    ExportTraceServiceRequest {
+        resource_spans: group_by_resource_and_scope(spans)
+            .into_iter()
+            .map(|(resource, scopes)| ResourceSpans {
+                resource,
+                scope_spans: scopes
+                    .into_iter()
+                    .map(|(scope, spans)| ScopeSpans {
+                        scope,
+                        spans,
+                        schema_url: String::new(),
+                    })
+                    .collect(),
+                schema_url: String::new(),
+            })
+            .collect(),
+    }
+}
+
+type ResourceAndScope = (Option<Resource>, Option<InstrumentationScope>);
+
+type Grouped<T> = Vec<(
+    Option<Resource>,
+    Vec<(Option<InstrumentationScope>, Vec<T>)>,
+)>;
+
+/// Groups items by their resource, and then by their scope, preserving the order in which each
+/// resource and scope was first seen.
+///
+/// Neither resources nor scopes are hashable, but batches typically only contain a handful of
+/// distinct ones, so a linear search is cheap enough.
+fn group_by_resource_and_scope<T>(
+    items: impl Iterator<Item = (ResourceAndScope, T)>,
+) -> Grouped<T> {
+    let mut grouped: Grouped<T> = Vec::new();
+
+    for ((resource, scope), item) in items {
+        let scopes = match grouped.iter().position(|(r, _)| *r == resource) {
+            Some(index) => &mut grouped[index].1,
+            None => {
+                grouped.push((resource, Vec::new()));
+                &mut grouped.last_mut().expect("just pushed").1
+            }
+        };
+
+        match scopes.iter_mut().find(|(s, _)| *s == scope) {
+            Some((_, items)) => items.push(item),
+            None => scopes.push((scope, vec![item])),
+        }
+    }
+
+    grouped
+}
+
+fn value_into_any_value(value: Value) -> AnyValue {
+    let value = match value {
+        Value::Bytes(bytes) => Some(PBValue::StringValue(
+            String::from_utf8_lossy(&bytes).into_owned(),
+        )),
+        Value::Regex(regex) => Some(PBValue::StringValue(regex.to_string())),
+        Value::Integer(int) => Some(PBValue::IntValue(int)),
+        Value::Float(float) => Some(PBValue::DoubleValue(float.into_inner())),
+        Value::Boolean(boolean) => Some(PBValue::BoolValue(boolean)),
+        Value::Timestamp(timestamp) => Some(PBValue::StringValue(timestamp.to_rfc3339())),
+        Value::Object(object) => Some(PBValue::KvlistValue(KeyValueList {
+            values: object_into_key_values(object),
+        })),
+        Value::Array(array) => Some(PBValue::ArrayValue(ArrayValue {
+            values: array.into_iter().map(value_into_any_value).collect(),
+        })),
+        Value::Null => None,
+    };
+
+    AnyValue { value }
+}
+
+fn object_into_key_values(object: ObjectMap) -> Vec<KeyValue> {
+    object
+        .into_iter()
+        .map(|(key, value)| KeyValue {
+            key: key.into(),
+            value: Some(value_into_any_value(value)),
+        })
+        .collect()
+}
+
+fn value_into_key_values(value: Option<Value>) -> Vec<KeyValue> {
+    match value {
+        Some(Value::Object(object)) => object_into_key_values(object),
+        _ => Vec::new(),
+    }
+}
+
+fn string_key_value(key: impl Into<String>, value: String) -> KeyValue {
+    KeyValue {
+        key: key.into(),
+        value: Some(AnyValue {
+            value: Some(PBValue::StringValue(value)),
+        }),
+    }
+}
+
+fn value_into_string(value: Option<Value>) -> String {
+    match value {
+        Some(Value::Bytes(bytes)) => String::from_utf8_lossy(&bytes).into_owned(),
+        Some(Value::Null) | None => String::new(),
+        Some(other) => other.to_string(),
+    }
+}
+
+/// Decodes a hex-encoded trace or span ID, as produced by the `opentelemetry` source.
+fn value_into_id(value: Option<Value>) -> Vec<u8> {
+    match value {
+        Some(Value::Bytes(bytes)) => hex::decode(bytes).unwrap_or_default(),
+        _ => Vec::new(),
+    }
+}
+
+fn value_into_i64(value: Option<Value>) -> Option<i64> {
+    match value {
+        Some(Value::Integer(int)) => Some(int),
+        Some(Value::Float(float)) => Some(float.into_inner() as i64),
+        _ => None,
+    }
+}
+
+fn value_into_u32(value: Option<Value>) -> u32 {
+    value_into_i64(value)
+        .and_then(|int| u32::try_from(int).ok())
+        .unwrap_or_default()
+}
+
+fn timestamp_into_nanos(timestamp: DateTime<Utc>) -> u64 {
+    timestamp
+        .timestamp_nanos_opt()
+        .and_then(|nanos| u64::try_from(nanos).ok())
+        .unwrap_or_default()
+}
+
+fn value_into_nanos(value: Option<Value>) -> u64 {
+    match value {
+        Some(Value::Timestamp(timestamp)) => timestamp_into_nanos(timestamp),
+        Some(Value::Integer(nanos)) => u64::try_from(nanos).unwrap_or_default(),
+        _ => 0,
+    }
+}
+
+fn value_into_resource(value: Option<Value>) -> Option<Resource> {
+    let attributes = value_into_key_values(value);
+    (!attributes.is_empty()).then_some(Resource {
+        attributes,
+        dropped_attributes_count: 0,
+    })
+}
+
+/// Takes one of the fields set by the `opentelemetry` source, which is stored in the event itself
+/// for the legacy namespace and in the event metadata for the Vector namespace.
+fn take_field(log: &mut LogEvent, log_namespace: LogNamespace, key: &str) -> Option<Value> {
+    match log_namespace {
+        LogNamespace::Vector => log.remove(metadata_path!(SOURCE_NAME, key)),
+        LogNamespace::Legacy => log.remove(event_path!(key)),
+    }
+}
+
+// https://github.com/open-telemetry/opentelemetry-specification/blob/v1.15.0/specification/logs/data-model.md
+fn log_into_record(mut log: LogEvent) -> (Option<Resource>, LogRecord) {
+    let log_namespace = log.namespace();
+
+    let resource = value_into_resource(take_field(&mut log, log_namespace, RESOURCE_KEY));
+    let attributes = take_field(&mut log, log_namespace, ATTRIBUTES_KEY);
+    let trace_id = value_into_id(take_field(&mut log, log_namespace, TRACE_ID_KEY));
+    let span_id = value_into_id(take_field(&mut log, log_namespace, SPAN_ID_KEY));
+    let severity_text = value_into_string(take_field(&mut log, log_namespace, SEVERITY_TEXT_KEY));
+    let severity_number = value_into_i64(take_field(&mut log, log_namespace, SEVERITY_NUMBER_KEY))
+        .and_then(|number| i32::try_from(number).ok())
+        .unwrap_or_default();
+    let flags = value_into_u32(take_field(&mut log, log_namespace, FLAGS_KEY));
+    let dropped_attributes_count = value_into_u32(take_field(
+        &mut log,
+        log_namespace,
+        DROPPED_ATTRIBUTES_COUNT_KEY,
+    ));
+    let observed_time_unix_nano =
+        value_into_nanos(take_field(&mut log, log_namespace, OBSERVED_TIMESTAMP_KEY));
+    let time_unix_nano = value_into_nanos(log.remove_timestamp());
+
+    let mut attributes = match attributes {
+        Some(Value::Object(object)) => object,
+        _ => ObjectMap::new(),
+    };
+    let body = match log_namespace {
+        LogNamespace::Vector => log.into_parts().0,
+        LogNamespace::Legacy => {
+            if let Some(path) = log_schema().source_type_key_target_path() {
+                log.remove(path);
+            }
+            let message = log_schema()
+                .message_key_target_path()
+                .and_then(|path| log.remove(path))
+                .unwrap_or(Value::Null);
+
+            // Any remaining fields would otherwise be lost, so we keep them as attributes.
+            if let Value::Object(fields) = log.into_parts().0 {
+                for (key, value) in fields {
+                    attributes.entry(key).or_insert(value);
+                }
+            }
+
+            message
+        }
+    };
+
+    let record = LogRecord {
+        time_unix_nano,
+        observed_time_unix_nano,
+        severity_number,
+        severity_text,
+        body: (!body.is_null()).then(|| value_into_any_value(body)),
+        attributes: object_into_key_values(attributes),
+        dropped_attributes_count,
+        flags,
+        trace_id,
+        span_id,
+    };
+
+    (resource, record)
+}
+
+/// Splits a metric into the resource and scope it belongs to, and the OTLP metric itself.
+///
+/// Resource attributes and the instrumentation scope are recovered from the tags set by the
+/// `opentelemetry` source, and all other tags become data point attributes.
+fn metric_into_parts(metric: Metric, now: DateTime<Utc>) -> Option<(ResourceAndScope, OtelMetric)> {
+    let (series, data, _) = metric.into_parts();
+
+    let name = match series.name.namespace {
+        Some(namespace) => format!("{}.{}", namespace, series.name.name),
+        None => series.name.name,
+    };
+
+    let mut resource_attributes = Vec::new();
+    let mut scope_name = None;
+    let mut scope_version = None;
+    let mut attributes = Vec::new();
+    for (key, value) in series
+        .tags
+        .into_iter()
+        .flat_map(|tags| tags.into_iter_single())
+    {
+        if let Some(key) = key.strip_prefix(RESOURCE_TAG_PREFIX) {
+            resource_attributes.push(string_key_value(key, value));
+        } else if key == SCOPE_NAME_TAG {
+            scope_name = Some(value);
+        } else if key == SCOPE_VERSION_TAG {
+            scope_version = Some(value);
+        } else {
+            attributes.push(string_key_value(key, value));
+        }
+    }
+
+    let resource = (!resource_attributes.is_empty()).then_some(Resource {
+        attributes: resource_attributes,
+        dropped_attributes_count: 0,
+    });
+    let scope = (scope_name.is_some() || scope_version.is_some()).then(|| InstrumentationScope {
+        name: scope_name.unwrap_or_default(),
+        version: scope_version.unwrap_or_default(),
+        attributes: Vec::new(),
+        dropped_attributes_count: 0,
+    });
+
+    let time_unix_nano = timestamp_into_nanos(data.time.timestamp.unwrap_or(now));
+    let temporality = match data.kind {
+        MetricKind::Incremental => AggregationTemporality::Delta,
+        MetricKind::Absolute => AggregationTemporality::Cumulative,
+    } as i32;
+    let number_point = |value: f64| NumberDataPoint {
+        attributes: attributes.clone(),
+        start_time_unix_nano: 0,
+        time_unix_nano,
+        exemplars: Vec::new(),
+        flags: 0,
+        value: Some(number_data_point::Value::AsDouble(value)),
+    };
+
+    let data = match data.value {
+        MetricValue::Counter { value } => Data::Sum(Sum {
+            data_points: vec![number_point(value)],
+            aggregation_temporality: temporality,
+            is_monotonic: true,
+        }),
+        // An incremental gauge is a delta that has to be added to the current value, which is
+        // exactly what a non-monotonic delta sum represents.
+        MetricValue::Gauge { value } if data.kind == MetricKind::Incremental => Data::Sum(Sum {
+            data_points: vec![number_point(value)],
+            aggregation_temporality: temporality,
+            is_monotonic: false,
+        }),
+        MetricValue::Gauge { value } => Data::Gauge(Gauge {
+            data_points: vec![number_point(value)],
+        }),
+        // OTLP has no notion of sets, so we report their cardinality instead.
+        MetricValue::Set { values } => Data::Gauge(Gauge {
+            data_points: vec![number_point(values.len() as f64)],
+        }),
+        MetricValue::Distribution { samples, .. } => {
+            let (buckets, count, sum) =
+                vector_core::event::metric::samples_to_buckets(&samples, &DISTRIBUTION_BUCKETS);
+            Data::Histogram(Histogram {
+                data_points: vec![histogram_point(
+                    attributes,
+                    time_unix_nano,
+                    &buckets,
+                    count,
+                    sum,
+                )],
+                aggregation_temporality: temporality,
+            })
+        }
+        MetricValue::AggregatedHistogram {
+            buckets,
+            count,
+            sum,
+        } => Data::Histogram(Histogram {
+            data_points: vec![histogram_point(
+                attributes,
+                time_unix_nano,
+                &buckets,
+                count,
+                sum,
+            )],
+            aggregation_temporality: temporality,
+        }),
+        MetricValue::AggregatedSummary {
+            quantiles,
+            count,
+            sum,
+        } => Data::Summary(Summary {
+            data_points: vec![summary_point(
+                attributes,
+                time_unix_nano,
+                &quantiles,
+                count,
+                sum,
+            )],
+        }),
+        MetricValue::Sketch { sketch } => {
+            let MetricSketch::AgentDDSketch(sketch) = sketch;
+            if sketch.is_empty() {
+                return None;
+            }
+
+            let quantiles = SKETCH_QUANTILES
+                .iter()
+                .filter_map(|&quantile| {
+                    sketch
+                        .quantile(quantile)
+                        .map(|value| Quantile { quantile, value })
+                })
+                .collect::<Vec<_>>();
+            Data::Summary(Summary {
+                data_points: vec![summary_point(
+                    attributes,
+                    time_unix_nano,
+                    &quantiles,
+                    u64::from(sketch.count()),
+                    sketch.sum().unwrap_or_default(),
+                )],
+            })
+        }
+    };
+
+    Some((
+        (resource, scope),
+        OtelMetric {
+            name,
+            description: String::new(),
+            unit: String::new(),
+            data: Some(data),
+        },
+    ))
+}
+
+/// Bucket bounds used when exporting raw distributions as OTLP histograms.
+///
+/// These match the default explicit bucket boundaries of the OpenTelemetry SDKs.
+const DISTRIBUTION_BUCKETS: [f64; 15] = [
+    0.0, 5.0, 10.0, 25.0, 50.0, 75.0, 100.0, 250.0, 500.0, 750.0, 1000.0, 2500.0, 5000.0, 7500.0,
+    10000.0,
+];
+
+fn histogram_point(
+    attributes: Vec<KeyValue>,
+    time_unix_nano: u64,
+    buckets: &[Bucket],
+    count: u64,
+    sum: f64,
+) -> HistogramDataPoint {
+    let mut bucket_counts = buckets
+        .iter()
+        .map(|bucket| bucket.count)
+        .collect::<Vec<_>>();
+    let mut explicit_bounds = buckets
+        .iter()
+        .map(|bucket| bucket.upper_limit)
+        .collect::<Vec<_>>();
+
+    // OTLP always has an implicit overflow bucket above the highest explicit bound.
+    if explicit_bounds.last().map_or(false, |bound| {
+        bound.is_infinite() && bound.is_sign_positive()
+    }) {
+        explicit_bounds.pop();
+    } else {
+        let counted = bucket_counts.iter().sum::<u64>();
+        bucket_counts.push(count.saturating_sub(counted));
+    }
+
+    HistogramDataPoint {
+        attributes,
+        start_time_unix_nano: 0,
+        time_unix_nano,
+        count,
+        sum: Some(sum),
+        bucket_counts,
+        explicit_bounds,
+        exemplars: Vec::new(),
+        flags: 0,
+        min: None,
+        max: None,
+    }
+}
+
+fn summary_point(
+    attributes: Vec<KeyValue>,
+    time_unix_nano: u64,
+    quantiles: &[Quantile],
+    count: u64,
+    sum: f64,
+) -> SummaryDataPoint {
+    SummaryDataPoint {
+        attributes,
+        start_time_unix_nano: 0,
+        time_unix_nano,
+        count,
+        sum,
+        quantile_values: quantiles
+            .iter()
+            .map(|quantile| ValueAtQuantile {
+                quantile: quantile.quantile,
+                value: quantile.value,
+            })
+            .collect(),
+        flags: 0,
+    }
+}
+
+fn take_object(object: &mut ObjectMap, key: &str) -> ObjectMap {
+    match object.remove(key) {
+        Some(Value::Object(object)) => object,
+        _ => ObjectMap::new(),
+    }
+}
+
+// https://github.com/open-telemetry/opentelemetry-specification/blob/v1.15.0/specification/trace/api.md
+fn trace_into_parts(trace: TraceEvent) -> (ResourceAndScope, Span) {
+    let (mut fields, _) = trace.into_parts();
+
+    let resource = value_into_resource(fields.remove(RESOURCE_KEY));
+    let scope = match fields.remove("scope") {
+        Some(Value::Object(mut scope)) => Some(InstrumentationScope {
+            name: value_into_string(scope.remove("name")),
+            version: value_into_string(scope.remove("version")),
+            attributes: object_into_key_values(take_object(&mut scope, ATTRIBUTES_KEY)),
+            dropped_attributes_count: value_into_u32(scope.remove(DROPPED_ATTRIBUTES_COUNT_KEY)),
+        }),
+        _ => None,
+    };
+
+    let events = match fields.remove("events") {
+        Some(Value::Array(events)) => events
+            .into_iter()
+            .filter_map(|event| match event {
+                Value::Object(mut event) => Some(SpanEvent {
+                    time_unix_nano: value_into_nanos(event.remove("time_unix_nano")),
+                    name: value_into_string(event.remove("name")),
+                    attributes: object_into_key_values(take_object(&mut event, ATTRIBUTES_KEY)),
+                    dropped_attributes_count: value_into_u32(
+                        event.remove(DROPPED_ATTRIBUTES_COUNT_KEY),
+                    ),
+                }),
+                _ => None,
+            })
+            .collect(),
+        _ => Vec::new(),
+    };
+
+    let links = match fields.remove("links") {
+        Some(Value::Array(links)) => links
+            .into_iter()
+            .filter_map(|link| match link {
+                Value::Object(mut link) => Some(Link {
+                    trace_id: value_into_id(link.remove(TRACE_ID_KEY)),
+                    span_id: value_into_id(link.remove(SPAN_ID_KEY)),
+                    trace_state: value_into_string(link.remove("trace_state")),
+                    attributes: object_into_key_values(take_object(&mut link, ATTRIBUTES_KEY)),
+                    dropped_attributes_count: value_into_u32(
+                        link.remove(DROPPED_ATTRIBUTES_COUNT_KEY),
+                    ),
+                }),
+                _ => None,
+            })
+            .collect(),
+        _ => Vec::new(),
+    };
+
+    let status = match fields.remove("status") {
+        Some(Value::Object(mut status)) => Some(SpanStatus {
+            message: value_into_string(status.remove("message")),
+            code: value_into_i64(status.remove("code"))
+                .and_then(|code| i32::try_from(code).ok())
+                .unwrap_or_default(),
+        }),
+        _ => None,
+    };
+
+    let trace_id = value_into_id(fields.remove(TRACE_ID_KEY));
+    let span_id = value_into_id(fields.remove(SPAN_ID_KEY));
+    let trace_state = value_into_string(fields.remove("trace_state"));
+    let parent_span_id = value_into_id(fields.remove("parent_span_id"));
+    let name = value_into_string(fields.remove("name"));
+    let kind = value_into_i64(fields.remove("kind"))
+        .and_then(|kind| i32::try_from(kind).ok())
+        .unwrap_or_default();
+    let start_time_unix_nano = value_into_nanos(fields.remove("start_time_unix_nano"));
+    let end_time_unix_nano = value_into_nanos(fields.remove("end_time_unix_nano"));
+    let dropped_attributes_count = value_into_u32(fields.remove(DROPPED_ATTRIBUTES_COUNT_KEY));
+    let dropped_events_count = value_into_u32(fields.remove("dropped_events_count"));
+    let dropped_links_count = value_into_u32(fields.remove("dropped_links_count"));
+    let mut attributes = take_object(&mut fields, ATTRIBUTES_KEY);
+
+    // These are added by the `opentelemetry` source and have no OTLP counterpart.
+    fields.remove("ingest_timestamp");
+    fields.remove("source_type");
+    // Any remaining fields would otherwise be lost, so we keep them as attributes.
+    for (key, value) in fields {
+        attributes.entry(key).or_insert(value);
+    }
+
+    let span = Span {
+        trace_id,
+        span_id,
+        trace_state,
+        parent_span_id,
+        name,
+        kind,
+        start_time_unix_nano,
+        end_time_unix_nano,
+        attributes: object_into_key_values(attributes),
+        dropped_attributes_count,
+        events,
+        dropped_events_count,
+        links,
+        dropped_links_count,
+        status,
+    };
+
+    ((resource, scope), span)
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+    use chrono::TimeZone;
+    use ordered_float::NotNan;
+    use vector_core::event::{Event, MetricTags};
+
+    use super::*;
+    use crate::proto::{
+        common::v1::any_value,
+        metrics::v1::{ResourceMetrics as PBResourceMetrics, ScopeMetrics as PBScopeMetrics},
+    };
+
+    fn string_attribute(key: &str, value: &str) -> KeyValue {
+        string_key_value(key, value.to_string())
+    }
+
+    #[test]
+    fn metrics_round_trip() {
+        let request = ExportMetricsServiceRequest {
+            resource_metrics: vec![PBResourceMetrics {
+                resource: Some(Resource {
+                    attributes: vec![string_attribute("service.name", "checkout")],
+                    dropped_attributes_count: 0,
+                }),
+                scope_metrics: vec![PBScopeMetrics {
+                    scope: Some(InstrumentationScope {
+                        name: "meter".into(),
+                        version: "1.0".into(),
+                        attributes: vec![],
+                        dropped_attributes_count: 0,
+                    }),
+                    metrics: vec![OtelMetric {
+                        name: "requests".into(),
+                        description: String::new(),
+                        unit: String::new(),
+                        data: Some(Data::Sum(Sum {
+                            data_points: vec![NumberDataPoint {
+                                attributes: vec![string_attribute("route", "/cart")],
+                                start_time_unix_nano: 0,
+                                time_unix_nano: 1_000,
+                                exemplars: vec![],
+                                flags: 0,
+                                value: Some(number_data_point::Value::AsDouble(3.0)),
+                            }],
+                            aggregation_temporality: AggregationTemporality::Delta as i32,
+                            is_monotonic: true,
+                        })),
+                    }],
+                    schema_url: String::new(),
+                }],
+                schema_url: String::new(),
+            }],
+        };
+
+        let metrics = request
+            .resource_metrics
+            .clone()
+            .into_iter()
+            .flat_map(|resource_metrics| resource_metrics.into_event_iter())
+            .map(Event::into_metric);
+
+        assert_eq!(metrics_into_request(metrics), request);
+    }
+
+    #[test]
+    fn metrics_without_otlp_tags_have_no_resource_or_scope() {
+        let metric = Metric::new(
+            "latency",
+            MetricKind::Absolute,
+            MetricValue::AggregatedHistogram {
+                buckets: vec![
+                    Bucket {
+                        upper_limit: 1.0,
+                        count: 2,
+                    },
+                    Bucket {
+                        upper_limit: 5.0,
+                        count: 3,
+                    },
+                ],
+                count: 6,
+                sum: 20.0,
+            },
+        )
+        .with_namespace(Some("app"))
+        .with_tags(Some(MetricTags::from_iter([(
+            "host".to_string(),
+            "localhost".to_string(),
+        )])))
+        .with_timestamp(Some(Utc.timestamp_nanos(5)));
+
+        let request = metrics_into_request([metric]);
+        assert_eq!(request.resource_metrics.len(), 1);
+
+        let resource_metrics = &request.resource_metrics[0];
+        assert_eq!(resource_metrics.resource, None);
+        assert_eq!(resource_metrics.scope_metrics[0].scope, None);
+
+        let metric = &resource_metrics.scope_metrics[0].metrics[0];
+        assert_eq!(metric.name, "app.latency");
+        let Some(Data::Histogram(histogram)) = &metric.data else {
+            panic!("expected a histogram");
+        };
+        assert_eq!(
+            histogram.aggregation_temporality,
+            AggregationTemporality::Cumulative as i32
+        );
+        let point = &histogram.data_points[0];
+        assert_eq!(point.explicit_bounds, vec![1.0, 5.0]);
+        // The observation that didn't fit any bucket ends up in the overflow bucket.
+        assert_eq!(point.bucket_counts, vec![2, 3, 1]);
+        assert_eq!(point.time_unix_nano, 5);
+        assert_eq!(
+            point.attributes,
+            vec![string_attribute("host", "localhost")]
+        );
+    }
+
+    #[test]
+    fn legacy_logs_keep_unknown_fields_as_attributes() {
+        let mut log = LogEvent::from("hello world");
+        log.insert(event_path!("host"), "localhost");
+        log.insert(event_path!(SEVERITY_TEXT_KEY), "info");
+        log.insert(
+            event_path!(TRACE_ID_KEY),
+            "4ac52aadf321c2e531db005df08792f5",
+        );
+        log.insert(
+            event_path!(RESOURCE_KEY),
+            Value::Object(ObjectMap::from([("service.name".into(), "web".into())])),
+        );
+
+        let request = logs_into_request([log]);
+        let resource_logs = &request.resource_logs[0];
+        assert_eq!(
+            resource_logs.resource.as_ref().unwrap().attributes,
+            vec![string_attribute("service.name", "web")]
+        );
+
+        let record = &resource_logs.scope_logs[0].log_records[0];
+        assert_eq!(
+            record.body.as_ref().unwrap().value,
+            Some(any_value::Value::StringValue("hello world".into()))
+        );
+        assert_eq!(record.severity_text, "info");
+        assert_eq!(
+            hex::encode(&record.trace_id),
+            "4ac52aadf321c2e531db005df08792f5"
+        );
+        assert_eq!(
+            record.attributes,
+            vec![string_attribute("host", "localhost")]
+        );
+    }
+
+    #[test]
+    fn traces_round_trip() {
+        let request = ExportTraceServiceRequest {
+            resource_spans: vec![ResourceSpans {
+                resource: Some(Resource {
+                    attributes: vec![string_attribute("service.name", "checkout")],
+                    dropped_attributes_count: 0,
+                }),
+                scope_spans: vec![ScopeSpans {
+                    scope: None,
+                    spans: vec![Span {
+                        trace_id: hex::decode("4ac52aadf321c2e531db005df08792f5").unwrap(),
+                        span_id: hex::decode("0b9e4bda2a55530d").unwrap(),
+                        trace_state: String::new(),
+                        parent_span_id: Vec::new(),
+                        name: "GET /cart".into(),
+                        kind: 2,
+                        start_time_unix_nano: 1,
+                        end_time_unix_nano: 2,
+                        attributes: vec![string_attribute("http.method", "GET")],
+                        dropped_attributes_count: 0,
+                        events: vec![],
+                        dropped_events_count: 0,
+                        links: vec![],
+                        dropped_links_count: 0,
+                        status: Some(SpanStatus {
+                            message: String::new(),
+                            code: 1,
+                        }),
+                    }],
+                    schema_url: String::new(),
+                }],
+                schema_url: String::new(),
+            }],
+        };
+
+        let traces = request
+            .resource_spans
+            .clone()
+            .into_iter()
+            .flat_map(|resource_spans| resource_spans.into_event_iter())
+            .map(Event::into_trace);
+
+        assert_eq!(traces_into_request(traces), request);
+    }
+
+    #[test]
+    fn traces_keep_unknown_fields_as_attributes() {
+        let mut trace = TraceEvent::default();
+        trace.insert(event_path!("name"), "GET /cart");
+        trace.insert(event_path!("source_type"), "opentelemetry");
+        trace.insert(event_path!("host"), "localhost");
+        trace.insert(
+            event_path!(ATTRIBUTES_KEY),
+            Value::Object(ObjectMap::from([("host".into(), "web-1".into())])),
+        );
+        trace.insert(event_path!("retries"), 2);
+
+        let request = traces_into_request([trace]);
+        let span = &request.resource_spans[0].scope_spans[0].spans[0];
+        assert_eq!(span.name, "GET /cart");
+        assert_eq!(
+            span.attributes,
+            vec![
+                string_attribute("host", "web-1"),
+                KeyValue {
+                    key: "retries".into(),
+                    value: Some(AnyValue {
+                        value: Some(any_value::Value::IntValue(2))
+                    }),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn null_values_have_no_value() {
+        assert_eq!(value_into_any_value(Value::Null), AnyValue { value: None });
+        assert_eq!(
+            value_into_any_value(Value::Float(NotNan::new(1.5).unwrap())),
+            AnyValue {
+                value: Some(any_value::Value::DoubleValue(1.5))
+            }
+        );
+        assert_eq!(
+            value_into_any_value(Value::Bytes(Bytes::from_static(b"foo"))),
+            AnyValue {
+                value: Some(any_value::Value::StringValue("foo".into()))
+            }
+        );
+    }
+}
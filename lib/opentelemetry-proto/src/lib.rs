@@ -1,3 +1,4 @@
 pub mod convert;
+pub mod export;
 #[allow(warnings)] // Ignore some clippy warnings
 pub mod proto;
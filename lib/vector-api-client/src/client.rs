@@ -1,8 +1,9 @@
 use anyhow::Context;
 use graphql_client::GraphQLQuery;
+use reqwest::header::AUTHORIZATION;
 use url::Url;
 
-use crate::gql::HealthQueryExt;
+use crate::{gql::HealthQueryExt, ConnectOptions};
 
 /// Wrapped `Result` type, that returns deserialized GraphQL response data.
 pub type QueryResult<T> =
@@ -12,12 +13,44 @@ pub type QueryResult<T> =
 #[derive(Debug)]
 pub struct Client {
     url: Url,
+    client: reqwest::Client,
+    authorization: Option<String>,
 }
 
 impl Client {
     /// Returns a new GraphQL query client, bound to the provided URL.
     pub fn new(url: Url) -> Self {
-        Self { url }
+        Self {
+            url,
+            client: reqwest::Client::new(),
+            authorization: None,
+        }
+    }
+
+    /// Returns a new GraphQL query client, bound to the provided URL, that authenticates and
+    /// verifies the server certificate according to `options`.
+    pub fn with_options(url: Url, options: &ConnectOptions) -> anyhow::Result<Self> {
+        let mut builder = reqwest::Client::builder();
+        if let Some(pem) = options.ca_certificate()? {
+            let certificate = reqwest::Certificate::from_pem(&pem)
+                .context("Couldn't parse the CA certificate")?;
+            builder = builder.add_root_certificate(certificate);
+        }
+
+        Ok(Self {
+            url,
+            client: builder.build().context("Couldn't build the HTTP client")?,
+            authorization: options.authorization(),
+        })
+    }
+
+    /// Sends the given bearer token with every request, as required for mutations.
+    ///
+    /// This replaces any credentials set through [`ConnectOptions`], as both are sent in the
+    /// `Authorization` header.
+    pub fn with_bearer_token(mut self, token: impl Into<String>) -> Self {
+        self.authorization = Some(format!("Bearer {}", token.into()));
+        self
     }
 
     /// Send a health query
@@ -31,11 +64,12 @@ impl Client {
         &self,
         request_body: &graphql_client::QueryBody<T::Variables>,
     ) -> QueryResult<T> {
-        let client = reqwest::Client::new();
+        let mut request = self.client.post(self.url.clone()).json(request_body);
+        if let Some(authorization) = &self.authorization {
+            request = request.header(AUTHORIZATION, authorization);
+        }
 
-        client
-            .post(self.url.clone())
-            .json(request_body)
+        request
             .send()
             .await
             .with_context(|| {
@@ -0,0 +1,108 @@
+//! Mutations for controlling a running Vector instance.
+
+use async_trait::async_trait;
+use graphql_client::GraphQLQuery;
+
+/// ReloadConfigMutation reloads the configuration of the Vector instance from disk.
+#[derive(GraphQLQuery, Debug, Copy, Clone)]
+#[graphql(
+    schema_path = "graphql/schema.json",
+    query_path = "graphql/mutations/reload_config.graphql",
+    response_derives = "Debug"
+)]
+pub struct ReloadConfigMutation;
+
+/// PauseComponentMutation holds back the events flowing through a component.
+#[derive(GraphQLQuery, Debug, Copy, Clone)]
+#[graphql(
+    schema_path = "graphql/schema.json",
+    query_path = "graphql/mutations/pause_component.graphql",
+    response_derives = "Debug"
+)]
+pub struct PauseComponentMutation;
+
+/// ResumeComponentMutation releases the events held back by a paused component.
+#[derive(GraphQLQuery, Debug, Copy, Clone)]
+#[graphql(
+    schema_path = "graphql/schema.json",
+    query_path = "graphql/mutations/resume_component.graphql",
+    response_derives = "Debug"
+)]
+pub struct ResumeComponentMutation;
+
+/// FlushSinkMutation restarts a sink, forcing it to send any partially filled batches.
+#[derive(GraphQLQuery, Debug, Copy, Clone)]
+#[graphql(
+    schema_path = "graphql/schema.json",
+    query_path = "graphql/mutations/flush_sink.graphql",
+    response_derives = "Debug"
+)]
+pub struct FlushSinkMutation;
+
+/// Extension methods for control mutations.
+///
+/// The Vector instance only accepts these from a client set up with its `api.mutation_token`,
+/// through [`crate::Client::with_bearer_token`], or with its `api.auth` credentials.
+#[async_trait]
+pub trait ControlMutationExt {
+    /// Executes a reload config mutation.
+    async fn reload_config(&self) -> crate::QueryResult<ReloadConfigMutation>;
+
+    /// Executes a pause component mutation.
+    async fn pause_component(
+        &self,
+        component_id: &str,
+    ) -> crate::QueryResult<PauseComponentMutation>;
+
+    /// Executes a resume component mutation.
+    async fn resume_component(
+        &self,
+        component_id: &str,
+    ) -> crate::QueryResult<ResumeComponentMutation>;
+
+    /// Executes a flush sink mutation.
+    async fn flush_sink(&self, component_id: &str) -> crate::QueryResult<FlushSinkMutation>;
+}
+
+#[async_trait]
+impl ControlMutationExt for crate::Client {
+    /// Executes a reload config mutation.
+    async fn reload_config(&self) -> crate::QueryResult<ReloadConfigMutation> {
+        self.query::<ReloadConfigMutation>(&ReloadConfigMutation::build_query(
+            reload_config_mutation::Variables,
+        ))
+        .await
+    }
+
+    /// Executes a pause component mutation.
+    async fn pause_component(
+        &self,
+        component_id: &str,
+    ) -> crate::QueryResult<PauseComponentMutation> {
+        let request_body =
+            PauseComponentMutation::build_query(pause_component_mutation::Variables {
+                component_id: component_id.to_string(),
+            });
+        self.query::<PauseComponentMutation>(&request_body).await
+    }
+
+    /// Executes a resume component mutation.
+    async fn resume_component(
+        &self,
+        component_id: &str,
+    ) -> crate::QueryResult<ResumeComponentMutation> {
+        let request_body =
+            ResumeComponentMutation::build_query(resume_component_mutation::Variables {
+                component_id: component_id.to_string(),
+            });
+        self.query::<ResumeComponentMutation>(&request_body).await
+    }
+
+    /// Executes a flush sink mutation.
+    async fn flush_sink(&self, component_id: &str) -> crate::QueryResult<FlushSinkMutation> {
+        let request_body = FlushSinkMutation::build_query(flush_sink_mutation::Variables {
+            component_id: component_id.to_string(),
+        });
+        self.query::<FlushSinkMutation>(&request_body).await
+    }
+}
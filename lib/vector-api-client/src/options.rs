@@ -0,0 +1,55 @@
+use std::{fs, path::PathBuf};
+
+use anyhow::Context;
+use base64::Engine;
+
+/// Options for connecting to a Vector API server that requires authentication or uses a
+/// certificate that isn't signed by a well-known authority.
+#[derive(clap::Args, Clone, Debug, Default)]
+pub struct ConnectOptions {
+    /// Bearer token to authenticate with, as set in the `api.auth` option of the Vector instance
+    #[arg(long, env = "VECTOR_API_TOKEN")]
+    pub token: Option<String>,
+
+    /// Username to authenticate with, using basic authentication. Takes precedence over `--token`
+    #[arg(long, requires = "password")]
+    pub user: Option<String>,
+
+    /// Password to authenticate with, using basic authentication. Only used along with `--user`
+    #[arg(long, env = "VECTOR_API_PASSWORD")]
+    pub password: Option<String>,
+
+    /// Path to a PEM file with the certificate authority to trust, in addition to the well-known
+    /// ones, when connecting over HTTPS
+    #[arg(long)]
+    pub ca_file: Option<PathBuf>,
+}
+
+impl ConnectOptions {
+    /// Returns the value of the `Authorization` header to send with every request, if any.
+    pub fn authorization(&self) -> Option<String> {
+        match (&self.user, &self.password, &self.token) {
+            (Some(user), password, _) => {
+                let credentials = format!("{}:{}", user, password.as_deref().unwrap_or_default());
+                Some(format!(
+                    "Basic {}",
+                    base64::engine::general_purpose::STANDARD.encode(credentials)
+                ))
+            }
+            (None, _, Some(token)) => Some(format!("Bearer {}", token)),
+            (None, _, None) => None,
+        }
+    }
+
+    /// Reads the certificate authority from `ca_file`, if set.
+    pub fn ca_certificate(&self) -> anyhow::Result<Option<Vec<u8>>> {
+        self.ca_file
+            .as_ref()
+            .map(|path| {
+                fs::read(path).with_context(|| {
+                    format!("Couldn't read the CA certificate from {}", path.display())
+                })
+            })
+            .transpose()
+    }
+}
@@ -1,5 +1,6 @@
 use std::{
     collections::HashMap,
+    io,
     pin::Pin,
     sync::{Arc, Mutex},
 };
@@ -13,10 +14,20 @@ use tokio::sync::{
     mpsc, oneshot,
 };
 use tokio_stream::{wrappers::BroadcastStream, Stream, StreamExt};
-use tokio_tungstenite::{connect_async, tungstenite::Message};
+use tokio_tungstenite::{
+    connect_async_tls_with_config,
+    tungstenite::{
+        client::IntoClientRequest,
+        http::{header::AUTHORIZATION, HeaderValue},
+        Message,
+    },
+    Connector,
+};
 use url::Url;
 use uuid::Uuid;
 
+use crate::ConnectOptions;
+
 /// Subscription GraphQL response, returned from an active stream.
 pub type BoxedSubscription<T> = Pin<
     Box<
@@ -176,7 +187,29 @@ impl SubscriptionClient {
 pub async fn connect_subscription_client(
     url: Url,
 ) -> Result<SubscriptionClient, tokio_tungstenite::tungstenite::Error> {
-    let (ws, _) = connect_async(url).await?;
+    connect_subscription_client_with_options(url, &ConnectOptions::default()).await
+}
+
+/// Connect to a new WebSocket GraphQL server endpoint, as with [`connect_subscription_client`],
+/// authenticating and verifying the server certificate according to `options`.
+pub async fn connect_subscription_client_with_options(
+    url: Url,
+    options: &ConnectOptions,
+) -> Result<SubscriptionClient, tokio_tungstenite::tungstenite::Error> {
+    let mut request = url.as_str().into_client_request()?;
+    if let Some(authorization) = options.authorization() {
+        request
+            .headers_mut()
+            .insert(AUTHORIZATION, HeaderValue::from_str(&authorization)?);
+    }
+
+    let connector = options
+        .ca_certificate()
+        .map_err(|error| io::Error::new(io::ErrorKind::InvalidInput, error))?
+        .map(|pem| tls_connector(&pem))
+        .transpose()?;
+
+    let (ws, _) = connect_async_tls_with_config(request, None, false, connector).await?;
     let (mut ws_tx, mut ws_rx) = futures::StreamExt::split(ws);
 
     let (send_tx, mut send_rx) = mpsc::unbounded_channel::<Payload>();
@@ -202,3 +235,27 @@ pub async fn connect_subscription_client(
 
     Ok(SubscriptionClient::new(send_tx, recv_rx))
 }
+
+/// Returns a TLS connector that trusts the given PEM encoded certificate authorities, in addition
+/// to the well-known ones.
+fn tls_connector(pem: &[u8]) -> io::Result<Connector> {
+    let mut roots = rustls::RootCertStore::empty();
+    roots.add_trust_anchors(webpki_roots::TLS_SERVER_ROOTS.iter().map(|anchor| {
+        rustls::OwnedTrustAnchor::from_subject_spki_name_constraints(
+            anchor.subject,
+            anchor.spki,
+            anchor.name_constraints,
+        )
+    }));
+    for certificate in rustls_pemfile::certs(&mut &pem[..])? {
+        roots
+            .add(&rustls::Certificate(certificate))
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+    }
+
+    let config = rustls::ClientConfig::builder()
+        .with_safe_defaults()
+        .with_root_certificates(roots)
+        .with_no_client_auth();
+    Ok(Connector::Rustls(Arc::new(config)))
+}
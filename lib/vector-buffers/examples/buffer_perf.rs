@@ -273,6 +273,8 @@ where
             BufferType::DiskV2 {
                 max_size: max_size_bytes,
                 when_full,
+                encryption: None,
+                compression: None,
             }
         }
         s => panic!(
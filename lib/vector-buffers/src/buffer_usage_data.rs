@@ -11,7 +11,10 @@ use tracing::{Instrument, Span};
 use vector_common::internal_event::emit;
 
 use crate::{
-    internal_events::{BufferCreated, BufferEventsDropped, BufferEventsReceived, BufferEventsSent},
+    internal_events::{
+        BufferCreated, BufferEventsDropped, BufferEventsReceived, BufferEventsSent,
+        BufferRecordsCompressed,
+    },
     spawn_named,
 };
 
@@ -79,6 +82,55 @@ impl CategoryMetrics {
     }
 }
 
+/// Snapshot of compression metrics.
+struct CompressionSnapshot {
+    uncompressed_byte_size: u64,
+    compressed_byte_size: u64,
+}
+
+impl CompressionSnapshot {
+    /// Returns `true` if any of the values are non-zero.
+    fn has_updates(&self) -> bool {
+        self.uncompressed_byte_size > 0 || self.compressed_byte_size > 0
+    }
+}
+
+/// Compression metrics.
+///
+/// This tracks the size of records written by buffers that compress them, both before and after compression, which
+/// allows calculating the effective compression ratio of the buffer.
+#[derive(Debug, Default)]
+struct CompressionMetrics {
+    uncompressed_byte_size: AtomicU64,
+    compressed_byte_size: AtomicU64,
+}
+
+impl CompressionMetrics {
+    /// Increments the uncompressed and compressed byte sizes by the given amounts.
+    fn increment(&self, uncompressed_byte_size: u64, compressed_byte_size: u64) {
+        self.uncompressed_byte_size
+            .fetch_add(uncompressed_byte_size, Ordering::Relaxed);
+        self.compressed_byte_size
+            .fetch_add(compressed_byte_size, Ordering::Relaxed);
+    }
+
+    /// Gets a snapshot of the uncompressed and compressed byte sizes.
+    fn get(&self) -> CompressionSnapshot {
+        CompressionSnapshot {
+            uncompressed_byte_size: self.uncompressed_byte_size.load(Ordering::Acquire),
+            compressed_byte_size: self.compressed_byte_size.load(Ordering::Acquire),
+        }
+    }
+
+    /// Gets a snapshot of the uncompressed and compressed byte sizes by "consuming" the values.
+    fn consume(&self) -> CompressionSnapshot {
+        CompressionSnapshot {
+            uncompressed_byte_size: self.uncompressed_byte_size.swap(0, Ordering::AcqRel),
+            compressed_byte_size: self.compressed_byte_size.swap(0, Ordering::AcqRel),
+        }
+    }
+}
+
 /// Handle to buffer usage metrics for a specific buffer stage.
 #[derive(Clone, Debug)]
 pub struct BufferUsageHandle {
@@ -127,6 +179,26 @@ impl BufferUsageHandle {
         self.state.sent.increment(count, byte_size);
     }
 
+    /// Increments the number of bytes written by this buffer component, both before and after compression.
+    ///
+    /// This is only tracked by buffer components that compress the records written to them.
+    pub fn increment_compressed_byte_size(
+        &self,
+        uncompressed_byte_size: u64,
+        compressed_byte_size: u64,
+    ) {
+        self.state
+            .compression
+            .increment(uncompressed_byte_size, compressed_byte_size);
+    }
+
+    /// Increments the number of events (and their total size) evicted by this buffer component.
+    ///
+    /// This represents the oldest events in the buffer being intentionally dropped to make space for newer events.
+    pub fn increment_evicted_event_count_and_byte_size(&self, count: u64, byte_size: u64) {
+        self.state.evicted.increment(count, byte_size);
+    }
+
     /// Increment the number of dropped events (and their total size) for this buffer component.
     pub fn increment_dropped_event_count_and_byte_size(
         &self,
@@ -149,7 +221,9 @@ struct BufferUsageData {
     sent: CategoryMetrics,
     dropped: CategoryMetrics,
     dropped_intentional: CategoryMetrics,
+    evicted: CategoryMetrics,
     max_size: CategoryMetrics,
+    compression: CompressionMetrics,
 }
 
 impl BufferUsageData {
@@ -165,7 +239,9 @@ impl BufferUsageData {
         let sent = self.sent.get();
         let dropped = self.dropped.get();
         let dropped_intentional = self.dropped_intentional.get();
+        let evicted = self.evicted.get();
         let max_size = self.max_size.get();
+        let compression = self.compression.get();
 
         BufferUsageSnapshot {
             received_event_count: received.event_count,
@@ -176,11 +252,15 @@ impl BufferUsageData {
             dropped_event_byte_size: dropped.event_byte_size,
             dropped_event_count_intentional: dropped_intentional.event_count,
             dropped_event_byte_size_intentional: dropped_intentional.event_byte_size,
+            evicted_event_count: evicted.event_count,
+            evicted_event_byte_size: evicted.event_byte_size,
             max_size_bytes: max_size.event_byte_size,
             max_size_events: max_size
                 .event_count
                 .try_into()
                 .expect("should never be bigger than `usize`"),
+            uncompressed_byte_size: compression.uncompressed_byte_size,
+            compressed_byte_size: compression.compressed_byte_size,
         }
     }
 }
@@ -196,8 +276,12 @@ pub struct BufferUsageSnapshot {
     pub dropped_event_byte_size: u64,
     pub dropped_event_count_intentional: u64,
     pub dropped_event_byte_size_intentional: u64,
+    pub evicted_event_count: u64,
+    pub evicted_event_byte_size: u64,
     pub max_size_bytes: u64,
     pub max_size_events: usize,
+    pub uncompressed_byte_size: u64,
+    pub compressed_byte_size: u64,
 }
 
 /// Builder for tracking buffer usage metrics.
@@ -300,6 +384,26 @@ impl BufferUsage {
                             byte_size: dropped_intentional.event_byte_size,
                         });
                     }
+
+                    let evicted = stage.evicted.consume();
+                    if evicted.has_updates() {
+                        emit(BufferEventsDropped {
+                            idx: stage.idx,
+                            intentional: true,
+                            reason: "drop_oldest",
+                            count: evicted.event_count,
+                            byte_size: evicted.event_byte_size,
+                        });
+                    }
+
+                    let compression = stage.compression.consume();
+                    if compression.has_updates() {
+                        emit(BufferRecordsCompressed {
+                            idx: stage.idx,
+                            uncompressed_byte_size: compression.uncompressed_byte_size,
+                            compressed_byte_size: compression.compressed_byte_size,
+                        });
+                    }
                 }
             }
         };
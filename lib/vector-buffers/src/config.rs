@@ -1,14 +1,17 @@
 use std::{
     fmt,
-    num::{NonZeroU64, NonZeroUsize},
+    num::{NonZeroU32, NonZeroU64, NonZeroUsize},
     path::{Path, PathBuf},
     slice,
 };
 
+use base64::Engine;
 use serde::{de, Deserialize, Deserializer, Serialize};
 use snafu::{ResultExt, Snafu};
 use tracing::Span;
-use vector_common::{config::ComponentKey, finalization::Finalizable};
+use vector_common::{
+    config::ComponentKey, finalization::Finalizable, sensitive_string::SensitiveString,
+};
 use vector_config::configurable_component;
 
 use crate::{
@@ -16,7 +19,13 @@ use crate::{
         builder::{TopologyBuilder, TopologyError},
         channel::{BufferReceiver, BufferSender},
     },
-    variants::{DiskV2Buffer, MemoryBuffer},
+    variants::{
+        disk_v2::{
+            get_disk_v2_data_dir_path, BufferInspector, LedgerLoadCreateError, RecordCipher,
+            RecordCompression, KEY_LEN,
+        },
+        DiskV2Buffer, MemoryBuffer,
+    },
     Bufferable, WhenFull,
 };
 
@@ -28,6 +37,10 @@ pub enum BufferBuildError {
     FailedToBuildTopology { source: TopologyError },
     #[snafu(display("`max_events` must be greater than zero"))]
     InvalidMaxEvents,
+    #[snafu(display("invalid encryption key for key version {}: {}", version, reason))]
+    InvalidEncryptionKey { version: u32, reason: String },
+    #[snafu(display("failed to open buffer for inspection: {}", source))]
+    FailedToOpenBuffer { source: LedgerLoadCreateError },
 }
 
 #[derive(Deserialize, Serialize)]
@@ -38,7 +51,14 @@ enum BufferTypeKind {
     DiskV2,
 }
 
-const ALL_FIELDS: [&str; 4] = ["type", "max_events", "max_size", "when_full"];
+const ALL_FIELDS: [&str; 6] = [
+    "type",
+    "max_events",
+    "max_size",
+    "when_full",
+    "encryption",
+    "compression",
+];
 
 struct BufferTypeVisitor;
 
@@ -51,6 +71,8 @@ impl BufferTypeVisitor {
         let mut max_events: Option<NonZeroUsize> = None;
         let mut max_size: Option<NonZeroU64> = None;
         let mut when_full: Option<WhenFull> = None;
+        let mut encryption: Option<DiskBufferEncryption> = None;
+        let mut compression: Option<DiskBufferCompression> = None;
         while let Some(key) = map.next_key::<String>()? {
             match key.as_str() {
                 "type" => {
@@ -77,6 +99,18 @@ impl BufferTypeVisitor {
                     }
                     when_full = Some(map.next_value()?);
                 }
+                "encryption" => {
+                    if encryption.is_some() {
+                        return Err(de::Error::duplicate_field("encryption"));
+                    }
+                    encryption = Some(map.next_value()?);
+                }
+                "compression" => {
+                    if compression.is_some() {
+                        return Err(de::Error::duplicate_field("compression"));
+                    }
+                    compression = Some(map.next_value()?);
+                }
                 other => {
                     return Err(de::Error::unknown_field(other, &ALL_FIELDS));
                 }
@@ -92,6 +126,18 @@ impl BufferTypeVisitor {
                         &["type", "max_events", "when_full"],
                     ));
                 }
+                if encryption.is_some() {
+                    return Err(de::Error::unknown_field(
+                        "encryption",
+                        &["type", "max_events", "when_full"],
+                    ));
+                }
+                if compression.is_some() {
+                    return Err(de::Error::unknown_field(
+                        "compression",
+                        &["type", "max_events", "when_full"],
+                    ));
+                }
                 Ok(BufferType::Memory {
                     max_events: max_events.unwrap_or_else(memory_buffer_default_max_events),
                     when_full,
@@ -101,12 +147,14 @@ impl BufferTypeVisitor {
                 if max_events.is_some() {
                     return Err(de::Error::unknown_field(
                         "max_events",
-                        &["type", "max_size", "when_full"],
+                        &["type", "max_size", "when_full", "encryption", "compression"],
                     ));
                 }
                 Ok(BufferType::DiskV2 {
                     max_size: max_size.ok_or_else(|| de::Error::missing_field("max_size"))?,
                     when_full,
+                    encryption,
+                    compression,
                 })
             }
         }
@@ -175,9 +223,121 @@ impl DiskUsage {
     }
 }
 
+/// Encryption at rest for disk buffers.
+///
+/// Record payloads are encrypted with XChaCha20-Poly1305 before being written to disk. Each record
+/// carries the version of the key it was encrypted with, so that keys can be rotated without losing
+/// the records that are still waiting in the buffer.
+#[configurable_component]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct DiskBufferEncryption {
+    /// The version of `key`.
+    ///
+    /// When rotating keys, increment the version, and move the previous key to `previous_keys`
+    /// until all of the records encrypted with it have been read.
+    pub key_version: NonZeroU32,
+
+    /// The key used to encrypt new records, as 32 bytes encoded with base64.
+    ///
+    /// Use a secret reference, such as `SECRET[backend.disk_buffer_key]`, to avoid storing the key
+    /// in the configuration itself.
+    #[configurable(metadata(docs::examples = "SECRET[backend.disk_buffer_key]"))]
+    pub key: SensitiveString,
+
+    /// Previous keys, which are only used to decrypt records written before the key was rotated.
+    ///
+    /// Vector refuses to start if the buffer holds unread records encrypted with a key version
+    /// that is neither `key_version` nor listed here.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub previous_keys: Vec<DiskBufferKey>,
+}
+
+/// A versioned disk buffer encryption key.
+#[configurable_component]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct DiskBufferKey {
+    /// The version of the key.
+    pub version: NonZeroU32,
+
+    /// The key, as 32 bytes encoded with base64.
+    pub key: SensitiveString,
+}
+
+impl DiskBufferEncryption {
+    fn build_cipher(&self) -> Result<RecordCipher, BufferBuildError> {
+        let key_version = self.key_version.get();
+        let key = decode_key(key_version, &self.key)?;
+
+        let mut previous_keys = Vec::with_capacity(self.previous_keys.len());
+        for previous in &self.previous_keys {
+            let version = previous.version.get();
+            if version == key_version
+                || previous_keys
+                    .iter()
+                    .any(|(existing, _)| *existing == version)
+            {
+                return Err(BufferBuildError::InvalidEncryptionKey {
+                    version,
+                    reason: "key version is configured more than once".to_string(),
+                });
+            }
+            previous_keys.push((version, decode_key(version, &previous.key)?));
+        }
+
+        Ok(RecordCipher::new(key_version, &key, previous_keys))
+    }
+}
+
+fn decode_key(version: u32, key: &SensitiveString) -> Result<[u8; KEY_LEN], BufferBuildError> {
+    let decoded = base64::engine::general_purpose::STANDARD
+        .decode(key.inner())
+        .map_err(|e| BufferBuildError::InvalidEncryptionKey {
+            version,
+            reason: e.to_string(),
+        })?;
+
+    decoded
+        .try_into()
+        .map_err(|decoded: Vec<u8>| BufferBuildError::InvalidEncryptionKey {
+            version,
+            reason: format!("key must be {} bytes, got {}", KEY_LEN, decoded.len()),
+        })
+}
+
+/// Compression algorithm for disk buffers.
+#[configurable_component]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DiskBufferCompression {
+    /// [Zstandard][zstd] compression.
+    ///
+    /// Favors compression ratio over speed.
+    ///
+    /// [zstd]: https://facebook.github.io/zstd/
+    Zstd,
+
+    /// [LZ4][lz4] compression.
+    ///
+    /// Favors speed over compression ratio.
+    ///
+    /// [lz4]: https://lz4.github.io/lz4/
+    Lz4,
+}
+
+impl From<DiskBufferCompression> for RecordCompression {
+    fn from(compression: DiskBufferCompression) -> Self {
+        match compression {
+            DiskBufferCompression::Zstd => RecordCompression::Zstd,
+            DiskBufferCompression::Lz4 => RecordCompression::Lz4,
+        }
+    }
+}
+
 /// A specific type of buffer stage.
 #[configurable_component(no_deser)]
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 #[serde(rename_all = "snake_case", tag = "type")]
 #[configurable(metadata(docs::enum_tag_description = "The type of buffer to use."))]
 pub enum BufferType {
@@ -218,6 +378,21 @@ pub enum BufferType {
         #[configurable(derived)]
         #[serde(default)]
         when_full: WhenFull,
+
+        /// Encrypts the buffered events at rest.
+        ///
+        /// When not set, events are written to disk in plaintext.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        encryption: Option<DiskBufferEncryption>,
+
+        /// Compresses the buffered events before writing them to disk.
+        ///
+        /// Records that would not get any smaller are written uncompressed. Records already in the
+        /// buffer remain readable when this is changed, regardless of how they were written.
+        ///
+        /// When not set, events are written to disk uncompressed.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        compression: Option<DiskBufferCompression>,
     },
 }
 
@@ -250,10 +425,7 @@ impl BufferType {
             Some(global_data_dir) => match self {
                 Self::Memory { .. } => None,
                 Self::DiskV2 { max_size, .. } => {
-                    let data_dir = crate::variants::disk_v2::get_disk_v2_data_dir_path(
-                        &global_data_dir,
-                        id.id(),
-                    );
+                    let data_dir = get_disk_v2_data_dir_path(&global_data_dir, id.id());
 
                     Some(DiskUsage::new(id.clone(), data_dir, *max_size))
                 }
@@ -276,24 +448,69 @@ impl BufferType {
     where
         T: Bufferable + Clone + Finalizable,
     {
-        match *self {
+        match self {
             BufferType::Memory {
                 when_full,
                 max_events,
             } => {
-                builder.stage(MemoryBuffer::new(max_events), when_full);
+                builder.stage(MemoryBuffer::new(*max_events), *when_full);
             }
             BufferType::DiskV2 {
                 when_full,
                 max_size,
+                encryption,
+                compression,
             } => {
                 let data_dir = data_dir.ok_or(BufferBuildError::RequiresDataDir)?;
-                builder.stage(DiskV2Buffer::new(id, data_dir, max_size), when_full);
+                let cipher = encryption
+                    .as_ref()
+                    .map(DiskBufferEncryption::build_cipher)
+                    .transpose()?;
+                builder.stage(
+                    DiskV2Buffer::new(id, data_dir, *max_size, cipher, compression.map(Into::into)),
+                    *when_full,
+                );
             }
         };
 
         Ok(())
     }
+
+    /// Opens the buffer for read-only inspection, if supported.
+    ///
+    /// For buffer types that write to disk, `Some(value)` is returned with an inspector over the
+    /// records currently in the buffer.  The buffer itself is never modified by the inspector.
+    ///
+    /// Otherwise, `None` is returned.
+    ///
+    /// # Errors
+    ///
+    /// If a required parameter is missing, or if the buffer does not exist or is currently in use,
+    /// an error variant will be returned describing the error.
+    pub async fn inspect<T>(
+        &self,
+        global_data_dir: Option<PathBuf>,
+        id: &ComponentKey,
+    ) -> Result<Option<BufferInspector<T>>, BufferBuildError>
+    where
+        T: Bufferable,
+    {
+        match self {
+            BufferType::Memory { .. } => Ok(None),
+            BufferType::DiskV2 { encryption, .. } => {
+                let global_data_dir = global_data_dir.ok_or(BufferBuildError::RequiresDataDir)?;
+                let data_dir = get_disk_v2_data_dir_path(&global_data_dir, id.id());
+                let cipher = encryption
+                    .as_ref()
+                    .map(DiskBufferEncryption::build_cipher)
+                    .transpose()?;
+                BufferInspector::open(&data_dir, cipher)
+                    .await
+                    .map(Some)
+                    .context(FailedToOpenBufferSnafu)
+            }
+        }
+    }
 }
 
 /// Buffer configuration.
@@ -387,8 +604,11 @@ impl BufferConfig {
 
 #[cfg(test)]
 mod test {
-    use std::num::{NonZeroU64, NonZeroUsize};
+    use std::num::{NonZeroU32, NonZeroU64, NonZeroUsize};
 
+    use vector_common::sensitive_string::SensitiveString;
+
+    use super::{BufferBuildError, DiskBufferCompression, DiskBufferEncryption, DiskBufferKey};
     use crate::{BufferConfig, BufferType, WhenFull};
 
     fn check_single_stage(source: &str, expected: BufferType) {
@@ -500,6 +720,17 @@ max_events: 42
             },
         );
 
+        check_single_stage(
+            r"
+          type: memory
+          when_full: drop_oldest
+          ",
+            BufferType::Memory {
+                max_events: NonZeroUsize::new(500).unwrap(),
+                when_full: WhenFull::DropOldest,
+            },
+        );
+
         check_single_stage(
             r"
           type: memory
@@ -519,7 +750,124 @@ max_events: 42
             BufferType::DiskV2 {
                 max_size: NonZeroU64::new(1024).unwrap(),
                 when_full: WhenFull::Block,
+                encryption: None,
+                compression: None,
             },
         );
     }
+
+    #[test]
+    fn parse_disk_compression() {
+        check_single_stage(
+            r"
+          type: disk
+          max_size: 1024
+          compression: zstd
+          ",
+            BufferType::DiskV2 {
+                max_size: NonZeroU64::new(1024).unwrap(),
+                when_full: WhenFull::Block,
+                encryption: None,
+                compression: Some(DiskBufferCompression::Zstd),
+            },
+        );
+
+        check_single_stage(
+            r"
+          type: disk
+          max_size: 1024
+          compression: lz4
+          ",
+            BufferType::DiskV2 {
+                max_size: NonZeroU64::new(1024).unwrap(),
+                when_full: WhenFull::Block,
+                encryption: None,
+                compression: Some(DiskBufferCompression::Lz4),
+            },
+        );
+    }
+
+    #[test]
+    fn parse_memory_compression_rejected() {
+        let source = r"
+          type: memory
+          compression: zstd
+          ";
+        let error = serde_yaml::from_str::<BufferConfig>(source).unwrap_err();
+        assert_eq!(error.to_string(), BUFFER_CONFIG_NO_MATCH_ERR);
+    }
+
+    #[test]
+    fn parse_disk_encryption() {
+        check_single_stage(
+            r"
+          type: disk
+          max_size: 1024
+          encryption:
+            key_version: 2
+            key: AAECAwQFBgcICQoLDA0ODxAREhMUFRYXGBkaGxwdHh8=
+            previous_keys:
+              - version: 1
+                key: HxwdHhsaGRgXFhUUExIREA8ODQwLCgkIBwYFBAMCAQA=
+          ",
+            BufferType::DiskV2 {
+                max_size: NonZeroU64::new(1024).unwrap(),
+                when_full: WhenFull::Block,
+                encryption: Some(DiskBufferEncryption {
+                    key_version: NonZeroU32::new(2).unwrap(),
+                    key: "AAECAwQFBgcICQoLDA0ODxAREhMUFRYXGBkaGxwdHh8="
+                        .to_string()
+                        .into(),
+                    previous_keys: vec![DiskBufferKey {
+                        version: NonZeroU32::new(1).unwrap(),
+                        key: "HxwdHhsaGRgXFhUUExIREA8ODQwLCgkIBwYFBAMCAQA="
+                            .to_string()
+                            .into(),
+                    }],
+                }),
+                compression: None,
+            },
+        );
+    }
+
+    #[test]
+    fn parse_memory_encryption_rejected() {
+        let source = r"
+          type: memory
+          encryption:
+            key_version: 1
+            key: AAECAwQFBgcICQoLDA0ODxAREhMUFRYXGBkaGxwdHh8=
+          ";
+        let error = serde_yaml::from_str::<BufferConfig>(source).unwrap_err();
+        assert_eq!(error.to_string(), BUFFER_CONFIG_NO_MATCH_ERR);
+    }
+
+    #[test]
+    fn invalid_encryption_keys() {
+        let encryption = DiskBufferEncryption {
+            key_version: NonZeroU32::new(1).unwrap(),
+            key: "dG9vIHNob3J0".to_string().into(),
+            previous_keys: Vec::new(),
+        };
+        assert!(matches!(
+            encryption.build_cipher(),
+            Err(BufferBuildError::InvalidEncryptionKey { version: 1, .. })
+        ));
+
+        let key: SensitiveString = "AAECAwQFBgcICQoLDA0ODxAREhMUFRYXGBkaGxwdHh8="
+            .to_string()
+            .into();
+        let encryption = DiskBufferEncryption {
+            key_version: NonZeroU32::new(1).unwrap(),
+            key: key.clone(),
+            previous_keys: vec![DiskBufferKey {
+                version: NonZeroU32::new(1).unwrap(),
+                key,
+            }],
+        };
+        assert!(matches!(
+            encryption.build_cipher(),
+            Err(BufferBuildError::InvalidEncryptionKey { version: 1, .. })
+        ));
+    }
 }
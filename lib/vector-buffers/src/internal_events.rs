@@ -56,6 +56,19 @@ impl InternalEvent for BufferEventsSent {
     }
 }
 
+pub struct BufferRecordsCompressed {
+    pub idx: usize,
+    pub uncompressed_byte_size: u64,
+    pub compressed_byte_size: u64,
+}
+
+impl InternalEvent for BufferRecordsCompressed {
+    fn emit(self) {
+        counter!("buffer_uncompressed_bytes_total", self.uncompressed_byte_size, "stage" => self.idx.to_string());
+        counter!("buffer_compressed_bytes_total", self.compressed_byte_size, "stage" => self.idx.to_string());
+    }
+}
+
 pub struct BufferEventsDropped {
     pub idx: usize,
     pub count: u64,
@@ -31,6 +31,7 @@ pub mod test;
 pub mod topology;
 
 pub(crate) mod variants;
+pub use variants::disk_v2::{BufferInspector, InspectedRecord};
 
 use std::fmt::Debug;
 
@@ -58,6 +59,17 @@ pub enum WhenFull {
     /// slowdown in the acceptance/consumption of events.
     DropNewest,
 
+    /// Drops the oldest events in the buffer to make room for the event.
+    ///
+    /// The evicted events will be intentionally dropped. This mode is typically used when the
+    /// freshest data is the most valuable, such as for live-tailing or metrics, and it is preferable
+    /// to lose older events that a stuck sink has not yet processed rather than newer ones.
+    ///
+    /// Disk buffers evict whole data files at a time, starting with the oldest data file that has
+    /// not yet been read from. If the only data files left are the one being read from and the one
+    /// being written to, the event is dropped instead.
+    DropOldest,
+
     /// Overflows to the next stage in the buffer topology.
     ///
     /// If the current buffer stage is full, attempt to send this event to the next buffer stage.
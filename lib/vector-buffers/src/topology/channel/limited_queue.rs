@@ -163,6 +163,50 @@ impl<T: InMemoryBufferable> LimitedSender<T> {
 
         Ok(())
     }
+
+    /// Sends an item into the channel, evicting the oldest items in the channel to make room for it.
+    ///
+    /// The evicted items are returned, and are no longer in the channel.  If there is no room for
+    /// the item even after evicting every item in the channel, which can only happen if other
+    /// senders are concurrently sending, then this waits for room like [`LimitedSender::send`].
+    ///
+    /// # Errors
+    ///
+    /// If the receiver has disconnected (does not exist anymore), then `Err(SendError)` be returned
+    /// with the given `item`.
+    pub async fn send_drop_oldest(&mut self, item: T) -> Result<Vec<T>, SendError<T>> {
+        let permits_required = self.get_required_permits_for_item(&item);
+        let mut evicted = Vec::new();
+        let permits = loop {
+            match self
+                .inner
+                .limiter
+                .clone()
+                .try_acquire_many_owned(permits_required)
+            {
+                Ok(permits) => break permits,
+                Err(TryAcquireError::Closed) => return Err(SendError(item)),
+                Err(TryAcquireError::NoPermits) => match self.inner.data.pop() {
+                    // Evicting the item releases its permits back to the channel.
+                    Some((_permits, old_item)) => evicted.push(old_item),
+                    None => return self.send(item).await.map(|()| evicted),
+                },
+            }
+        };
+
+        self.inner
+            .data
+            .push((permits, item))
+            .unwrap_or_else(|_| unreachable!("acquired permits but channel reported being full"));
+        self.inner.read_waker.notify_one();
+
+        trace!(
+            evicted = evicted.len(),
+            "Sent item after evicting oldest items."
+        );
+
+        Ok(evicted)
+    }
 }
 
 impl<T> Clone for LimitedSender<T> {
@@ -595,4 +639,42 @@ mod tests {
 
         assert_eq!(2, tx.available_capacity());
     }
+
+    #[test]
+    fn send_drop_oldest_evicts_oldest_items() {
+        let (mut tx, mut rx) = limited(3);
+
+        let msgs = vec![
+            MultiEventRecord::new(1),
+            MultiEventRecord::new(1),
+            MultiEventRecord::new(1),
+        ];
+        let msg2 = MultiEventRecord::new(2);
+
+        // Fill up the channel, which shouldn't need to evict anything.
+        for msg in msgs.clone() {
+            let mut send = spawn(async { tx.send_drop_oldest(msg).await });
+            assert_eq!(Ok(Vec::new()), assert_ready!(send.poll()));
+        }
+        assert_eq!(0, tx.available_capacity());
+
+        // Now send an item that needs two slots, which should evict the two oldest items instead of
+        // waiting for capacity.
+        let mut send2 = spawn(async { tx.send_drop_oldest(msg2.clone()).await });
+        assert_eq!(Ok(msgs[..2].to_vec()), assert_ready!(send2.poll()));
+        drop(send2);
+
+        assert_eq!(0, tx.available_capacity());
+
+        // The remaining items should be read back in order.
+        let mut recv = spawn(async { rx.next().await });
+        assert_eq!(Some(msgs[2].clone()), assert_ready!(recv.poll()));
+        drop(recv);
+
+        let mut recv = spawn(async { rx.next().await });
+        assert_eq!(Some(msg2), assert_ready!(recv.poll()));
+        drop(recv);
+
+        assert_eq!(3, tx.available_capacity());
+    }
 }
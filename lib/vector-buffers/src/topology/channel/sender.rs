@@ -83,6 +83,27 @@ where
         }
     }
 
+    pub(crate) async fn send_drop_oldest(&mut self, item: T) -> crate::Result<Vec<T>> {
+        match self {
+            Self::InMemory(tx) => tx.send_drop_oldest(item).await.map_err(Into::into),
+            Self::DiskV2(writer) => {
+                let mut writer = writer.lock().await;
+
+                // The disk buffer evicts entire data files rather than individual items, and tracks
+                // the evicted events itself, so all we get back is the item if it had to be dropped.
+                writer
+                    .write_record_evicting_oldest(item)
+                    .await
+                    .map(|item| item.into_iter().collect())
+                    .map_err(|e| {
+                        error!("Disk buffer writer has encountered an unrecoverable error.");
+
+                        e.into()
+                    })
+            }
+        }
+    }
+
     pub(crate) async fn flush(&mut self) -> crate::Result<()> {
         match self {
             Self::InMemory(_) => Ok(()),
@@ -112,16 +133,19 @@ where
 /// events when the internal channel is full.
 ///
 /// When creating a buffer sender/receiver pair, callers can specify the "when full" behavior of the
-/// sender.  This controls how events are handled when the internal channel is full.  Three modes
+/// sender.  This controls how events are handled when the internal channel is full.  Four modes
 /// are possible:
 /// - block
 /// - drop newest
+/// - drop oldest
 /// - overflow
 ///
 /// In "block" mode, callers are simply forced to wait until the channel has enough capacity to
 /// accept the event.  In "drop newest" mode, any event being sent when the channel is full will be
-/// dropped and proceed no further. In "overflow" mode, events will be sent to another buffer
-/// sender.  Callers can specify the overflow sender to use when constructing their buffers initially.
+/// dropped and proceed no further. In "drop oldest" mode, the oldest events in the channel will be
+/// dropped to make room for the event being sent. In "overflow" mode, events will be sent to another
+/// buffer sender.  Callers can specify the overflow sender to use when constructing their buffers
+/// initially.
 ///
 /// TODO: We should eventually rework `BufferSender`/`BufferReceiver` so that they contain a vector
 /// of the fields we already have here, but instead of cascading via calling into `overflow`, we'd
@@ -204,6 +228,7 @@ impl<T: Bufferable> BufferSender<T> {
 
         let mut sent_to_base = true;
         let mut was_dropped = false;
+        let mut evicted = Vec::new();
         match self.when_full {
             WhenFull::Block => self.base.send(item).await?,
             WhenFull::DropNewest => {
@@ -211,6 +236,7 @@ impl<T: Bufferable> BufferSender<T> {
                     was_dropped = true;
                 }
             }
+            WhenFull::DropOldest => evicted = self.base.send_drop_oldest(item).await?,
             WhenFull::Overflow => {
                 if let Some(item) = self.base.try_send(item).await? {
                     sent_to_base = false;
@@ -248,6 +274,17 @@ impl<T: Bufferable> BufferSender<T> {
                     );
                 }
             }
+
+            let (evicted_count, evicted_size) =
+                evicted.iter().fold((0, 0), |(count, size), item| {
+                    (count + item.event_count(), size + item.size_of())
+                });
+            if evicted_count > 0 {
+                instrumentation.increment_evicted_event_count_and_byte_size(
+                    evicted_count as u64,
+                    evicted_size as u64,
+                );
+            }
         }
 
         Ok(())
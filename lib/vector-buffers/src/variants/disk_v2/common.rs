@@ -7,6 +7,8 @@ use crc32fast::Hasher;
 use snafu::Snafu;
 
 use super::{
+    compression::RecordCompression,
+    encryption::{RecordCipher, ENCRYPTION_OVERHEAD},
     io::{Filesystem, ProductionFilesystem},
     ledger::LEDGER_LEN,
     record::RECORD_HEADER_LEN,
@@ -153,6 +155,20 @@ pub struct DiskBufferConfig<FS> {
     /// amount of data written since the last flush would be lost.
     pub(crate) flush_interval: Duration,
 
+    /// Cipher used to encrypt record payloads at rest.
+    ///
+    /// When set, new records are encrypted with the active key of the cipher, and existing records
+    /// are decrypted with whichever key they were encrypted with.  When not set, new records are
+    /// written in plaintext, and any existing encrypted records cannot be read.
+    pub(crate) encryption: Option<RecordCipher>,
+
+    /// Algorithm used to compress record payloads.
+    ///
+    /// When set, new records are compressed before being written (and before being encrypted, if
+    /// encryption is enabled).  Existing records are decompressed based on the algorithm they were
+    /// written with, regardless of this setting.
+    pub(crate) compression: Option<RecordCompression>,
+
     /// Filesystem implementation for opening data files.
     ///
     /// We allow parameterizing the filesystem implementation for ease of testing.  The "filesystem"
@@ -173,6 +189,8 @@ where
     pub(crate) max_record_size: Option<usize>,
     pub(crate) write_buffer_size: Option<usize>,
     pub(crate) flush_interval: Option<Duration>,
+    pub(crate) encryption: Option<RecordCipher>,
+    pub(crate) compression: Option<RecordCompression>,
     pub(crate) filesystem: FS,
 }
 
@@ -188,6 +206,8 @@ impl DiskBufferConfigBuilder {
             max_record_size: None,
             write_buffer_size: None,
             flush_interval: None,
+            encryption: None,
+            compression: None,
             filesystem: ProductionFilesystem,
         }
     }
@@ -268,6 +288,30 @@ where
         self
     }
 
+    /// Sets the cipher used to encrypt record payloads at rest.
+    ///
+    /// When set, new records are encrypted with the active key of the cipher, and existing records
+    /// are decrypted with whichever key they were encrypted with.
+    ///
+    /// Defaults to no encryption.
+    #[allow(dead_code)]
+    pub fn encryption(mut self, cipher: RecordCipher) -> Self {
+        self.encryption = Some(cipher);
+        self
+    }
+
+    /// Sets the algorithm used to compress record payloads.
+    ///
+    /// Payloads that would not get any smaller are stored uncompressed.  Existing records are always
+    /// readable, regardless of which algorithm, if any, they were written with.
+    ///
+    /// Defaults to no compression.
+    #[allow(dead_code)]
+    pub fn compression(mut self, compression: RecordCompression) -> Self {
+        self.compression = Some(compression);
+        self
+    }
+
     /// Filesystem implementation for opening data files.
     ///
     /// We allow parameterizing the filesystem implementation for ease of testing.  The "filesystem"
@@ -287,6 +331,8 @@ where
             max_record_size: self.max_record_size,
             write_buffer_size: self.write_buffer_size,
             flush_interval: self.flush_interval,
+            encryption: self.encryption,
+            compression: self.compression,
             filesystem,
         }
     }
@@ -301,6 +347,8 @@ where
         let max_record_size = self.max_record_size.unwrap_or(DEFAULT_MAX_RECORD_SIZE);
         let write_buffer_size = self.write_buffer_size.unwrap_or(DEFAULT_WRITE_BUFFER_SIZE);
         let flush_interval = self.flush_interval.unwrap_or(DEFAULT_FLUSH_INTERVAL);
+        let encryption = self.encryption;
+        let compression = self.compression;
         let filesystem = self.filesystem;
 
         // Validate the input parameters.
@@ -344,6 +392,17 @@ where
             });
         }
 
+        if encryption.is_some() && max_record_size <= MINIMUM_MAX_RECORD_SIZE + ENCRYPTION_OVERHEAD
+        {
+            return Err(BuildError::InvalidParameter {
+                param_name: "max_record_size",
+                reason: format!(
+                    "must be greater than {} bytes when encryption is enabled",
+                    MINIMUM_MAX_RECORD_SIZE + ENCRYPTION_OVERHEAD
+                ),
+            });
+        }
+
         let Ok(max_record_size_converted) = u64::try_from(max_record_size) else {
             return Err(BuildError::InvalidParameter {
                 param_name: "max_record_size",
@@ -412,6 +471,8 @@ where
             max_record_size,
             write_buffer_size,
             flush_interval,
+            encryption,
+            compression,
             filesystem,
         })
     }
@@ -0,0 +1,153 @@
+use std::io;
+
+use snafu::Snafu;
+
+/// Record metadata flag marking a record whose payload is compressed with zstd.
+///
+/// Like the encryption flag, this lives in the high bits of the record metadata reserved by the
+/// buffer, and must never be repurposed.
+pub const ZSTD_COMPRESSED_RECORD_FLAG: u32 = 1 << 30;
+
+/// Record metadata flag marking a record whose payload is compressed with LZ4.
+pub const LZ4_COMPRESSED_RECORD_FLAG: u32 = 1 << 29;
+
+/// Compression level used for zstd.
+const ZSTD_COMPRESSION_LEVEL: i32 = zstd::DEFAULT_COMPRESSION_LEVEL;
+
+const UNCOMPRESSED_LEN_LEN: usize = 4;
+
+/// Error that occurred while compressing or decompressing a record payload.
+#[derive(Debug, Snafu)]
+pub enum CompressionError {
+    /// The compressed payload is too short to hold the uncompressed length.
+    #[snafu(display("compressed payload is truncated"))]
+    Truncated,
+
+    /// The compression library failed to compress or decompress the payload.
+    #[snafu(display("{} failed: {}", algorithm, source))]
+    Codec {
+        algorithm: &'static str,
+        source: io::Error,
+    },
+
+    /// The payload decompressed to a different length than it was compressed from.
+    #[snafu(display(
+        "decompressed payload length mismatch (expected {}, got {})",
+        expected,
+        actual
+    ))]
+    LengthMismatch { expected: usize, actual: usize },
+}
+
+/// Compression algorithm applied to record payloads.
+///
+/// Compressed payloads are laid out as `uncompressed_len (uint32 BE) | compressed bytes`.  If
+/// compressing a payload would not make it any smaller, it is stored uncompressed instead, so a
+/// compressed record is never larger than the record would have been without compression.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum RecordCompression {
+    /// Zstandard, favoring compression ratio.
+    Zstd,
+
+    /// LZ4, favoring speed.
+    Lz4,
+}
+
+impl RecordCompression {
+    /// Gets the compression algorithm used for a record, based on its metadata.
+    ///
+    /// Returns `None` if the record payload is not compressed.
+    pub fn from_metadata(metadata: u32) -> Option<Self> {
+        if metadata & ZSTD_COMPRESSED_RECORD_FLAG != 0 {
+            Some(Self::Zstd)
+        } else if metadata & LZ4_COMPRESSED_RECORD_FLAG != 0 {
+            Some(Self::Lz4)
+        } else {
+            None
+        }
+    }
+
+    /// Gets the record metadata flag for this compression algorithm.
+    pub const fn flag(self) -> u32 {
+        match self {
+            Self::Zstd => ZSTD_COMPRESSED_RECORD_FLAG,
+            Self::Lz4 => LZ4_COMPRESSED_RECORD_FLAG,
+        }
+    }
+
+    const fn name(self) -> &'static str {
+        match self {
+            Self::Zstd => "zstd",
+            Self::Lz4 => "lz4",
+        }
+    }
+
+    /// Compresses `src` into `dst`.
+    ///
+    /// Returns `false`, leaving `dst` in an unspecified state, if the compressed payload would not
+    /// be smaller than `src`, in which case `src` should be stored as-is.
+    ///
+    /// # Errors
+    ///
+    /// If the compression library fails to compress the payload, an error is returned.
+    pub fn compress(self, src: &[u8], dst: &mut Vec<u8>) -> Result<bool, CompressionError> {
+        let Ok(uncompressed_len) = u32::try_from(src.len()) else {
+            return Ok(false);
+        };
+
+        let compressed = match self {
+            Self::Zstd => zstd::bulk::compress(src, ZSTD_COMPRESSION_LEVEL),
+            Self::Lz4 => lz4::block::compress(src, None, false),
+        }
+        .map_err(|source| CompressionError::Codec {
+            algorithm: self.name(),
+            source,
+        })?;
+
+        if compressed.len() + UNCOMPRESSED_LEN_LEN >= src.len() {
+            return Ok(false);
+        }
+
+        dst.clear();
+        dst.extend_from_slice(&uncompressed_len.to_be_bytes());
+        dst.extend_from_slice(&compressed);
+        Ok(true)
+    }
+
+    /// Decompresses `payload`.
+    ///
+    /// # Errors
+    ///
+    /// If the payload is malformed, or does not decompress to the length it was compressed from, an
+    /// error is returned.
+    pub fn decompress(self, payload: &[u8]) -> Result<Vec<u8>, CompressionError> {
+        if payload.len() < UNCOMPRESSED_LEN_LEN {
+            return Err(CompressionError::Truncated);
+        }
+
+        let (len_bytes, compressed) = payload.split_at(UNCOMPRESSED_LEN_LEN);
+        let mut expected = [0; UNCOMPRESSED_LEN_LEN];
+        expected.copy_from_slice(len_bytes);
+        let expected = u32::from_be_bytes(expected) as usize;
+
+        let decompressed = match self {
+            Self::Zstd => zstd::bulk::decompress(compressed, expected),
+            Self::Lz4 => i32::try_from(expected)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+                .and_then(|len| lz4::block::decompress(compressed, Some(len))),
+        }
+        .map_err(|source| CompressionError::Codec {
+            algorithm: self.name(),
+            source,
+        })?;
+
+        if decompressed.len() != expected {
+            return Err(CompressionError::LengthMismatch {
+                expected,
+                actual: decompressed.len(),
+            });
+        }
+
+        Ok(decompressed)
+    }
+}
@@ -0,0 +1,198 @@
+use std::{collections::HashMap, fmt, sync::Arc};
+
+use chacha20poly1305::{
+    aead::{Aead, KeyInit, Payload},
+    Key, XChaCha20Poly1305, XNonce,
+};
+use rand::RngCore;
+use snafu::Snafu;
+
+/// Record metadata flag marking a record whose payload is encrypted.
+///
+/// Record metadata is otherwise owned by the encoding of `T`, which only uses the low bits, so the
+/// buffer reserves the high bits for flags describing how the payload itself was stored.  This
+/// flag must never be repurposed, as records written with it may still be sitting on disk.
+pub const ENCRYPTED_RECORD_FLAG: u32 = 1 << 31;
+
+/// Length of a key, in bytes.
+pub const KEY_LEN: usize = 32;
+
+const KEY_VERSION_LEN: usize = 4;
+const NONCE_LEN: usize = 24;
+const TAG_LEN: usize = 16;
+
+/// Number of bytes an encrypted payload takes up beyond the plaintext it carries.
+///
+/// Encrypted payloads are laid out as `key_version (uint32 BE) | nonce (24 bytes) | ciphertext`,
+/// where the ciphertext carries a 16 byte authentication tag.
+pub const ENCRYPTION_OVERHEAD: usize = KEY_VERSION_LEN + NONCE_LEN + TAG_LEN;
+
+/// Error that occurred while encrypting or decrypting a record payload.
+#[derive(Debug, Snafu)]
+pub enum EncryptionError {
+    /// The record was encrypted with a key version that is not configured.
+    #[snafu(display("no key is configured for key version {}", version))]
+    MissingKey { version: u32 },
+
+    /// The encrypted payload is too short to hold the encryption envelope.
+    #[snafu(display("encrypted payload is truncated"))]
+    Truncated,
+
+    /// The payload failed authentication, or could not be encrypted.
+    ///
+    /// When decrypting, this means the payload was altered after being written, or that the key
+    /// configured for its key version is not the key it was encrypted with.
+    #[snafu(display("authenticated encryption failed for key version {}", version))]
+    Failed { version: u32 },
+}
+
+/// Encrypts and decrypts record payloads.
+///
+/// Payloads are encrypted with XChaCha20-Poly1305, using a random nonce per record, and with the
+/// record ID and metadata as associated data so that a payload cannot be moved to another record
+/// without failing authentication.  Every payload records the version of the key it was encrypted
+/// with, so that records written before a key rotation can still be decrypted as long as the key
+/// for their version is still configured.
+#[derive(Clone)]
+pub struct RecordCipher {
+    active_version: u32,
+    keys: Arc<HashMap<u32, XChaCha20Poly1305>>,
+}
+
+impl RecordCipher {
+    /// Creates a new `RecordCipher` that encrypts with `active_key`, and decrypts with any of the
+    /// given keys.
+    pub fn new(
+        active_version: u32,
+        active_key: &[u8; KEY_LEN],
+        previous_keys: impl IntoIterator<Item = (u32, [u8; KEY_LEN])>,
+    ) -> Self {
+        let mut keys = previous_keys
+            .into_iter()
+            .map(|(version, key)| (version, XChaCha20Poly1305::new(Key::from_slice(&key))))
+            .collect::<HashMap<_, _>>();
+        keys.insert(
+            active_version,
+            XChaCha20Poly1305::new(Key::from_slice(active_key)),
+        );
+
+        Self {
+            active_version,
+            keys: Arc::new(keys),
+        }
+    }
+
+    /// Gets the version of the key used to encrypt new records.
+    pub fn active_version(&self) -> u32 {
+        self.active_version
+    }
+
+    /// Whether or not a key is configured for the given key version.
+    pub fn has_key(&self, version: u32) -> bool {
+        self.keys.contains_key(&version)
+    }
+
+    /// Encrypts `plaintext` with the active key, replacing the contents of `dst` with the
+    /// encrypted payload.
+    ///
+    /// # Errors
+    ///
+    /// If the plaintext is too large to be encrypted, an error variant will be returned.
+    pub fn encrypt(
+        &self,
+        id: u64,
+        metadata: u32,
+        plaintext: &[u8],
+        dst: &mut Vec<u8>,
+    ) -> Result<(), EncryptionError> {
+        let version = self.active_version;
+        let cipher = &self.keys[&version];
+
+        let mut nonce = [0; NONCE_LEN];
+        rand::thread_rng().fill_bytes(&mut nonce);
+
+        let aad = associated_data(id, metadata);
+        let ciphertext = cipher
+            .encrypt(
+                XNonce::from_slice(&nonce),
+                Payload {
+                    msg: plaintext,
+                    aad: &aad,
+                },
+            )
+            .map_err(|_| EncryptionError::Failed { version })?;
+
+        dst.clear();
+        dst.reserve(KEY_VERSION_LEN + NONCE_LEN + ciphertext.len());
+        dst.extend_from_slice(&version.to_be_bytes());
+        dst.extend_from_slice(&nonce);
+        dst.extend_from_slice(&ciphertext);
+        Ok(())
+    }
+
+    /// Decrypts an encrypted payload, returning the plaintext.
+    ///
+    /// # Errors
+    ///
+    /// If the key version of the payload is not configured, or the payload fails authentication,
+    /// an error variant will be returned describing the error.
+    pub fn decrypt(
+        &self,
+        id: u64,
+        metadata: u32,
+        payload: &[u8],
+    ) -> Result<Vec<u8>, EncryptionError> {
+        let version = payload_key_version(payload)?;
+        let cipher = self
+            .keys
+            .get(&version)
+            .ok_or(EncryptionError::MissingKey { version })?;
+
+        let (nonce, ciphertext) = payload[KEY_VERSION_LEN..].split_at(NONCE_LEN);
+        let aad = associated_data(id, metadata);
+        cipher
+            .decrypt(
+                XNonce::from_slice(nonce),
+                Payload {
+                    msg: ciphertext,
+                    aad: &aad,
+                },
+            )
+            .map_err(|_| EncryptionError::Failed { version })
+    }
+}
+
+impl fmt::Debug for RecordCipher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut versions = self.keys.keys().collect::<Vec<_>>();
+        versions.sort_unstable();
+
+        f.debug_struct("RecordCipher")
+            .field("active_version", &self.active_version)
+            .field("versions", &versions)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Gets the key version an encrypted payload was encrypted with.
+///
+/// # Errors
+///
+/// If the payload is too short to hold the encryption envelope, an error variant will be returned.
+pub fn payload_key_version(payload: &[u8]) -> Result<u32, EncryptionError> {
+    if payload.len() < ENCRYPTION_OVERHEAD {
+        return Err(EncryptionError::Truncated);
+    }
+
+    let version = payload[..KEY_VERSION_LEN]
+        .try_into()
+        .expect("the slice is the length of a u32");
+    Ok(u32::from_be_bytes(version))
+}
+
+fn associated_data(id: u64, metadata: u32) -> [u8; 12] {
+    let mut aad = [0; 12];
+    aad[..8].copy_from_slice(&id.to_be_bytes());
+    aad[8..].copy_from_slice(&metadata.to_be_bytes());
+    aad
+}
@@ -0,0 +1,211 @@
+use std::{
+    fmt,
+    io::ErrorKind,
+    path::{Path, PathBuf},
+};
+
+use fslock::LockFile;
+
+use super::{
+    common::MAX_FILE_ID,
+    io::{Filesystem, ProductionFilesystem},
+    ledger::{load_ledger_snapshot, LedgerLoadCreateError, LedgerSnapshot},
+    reader::{ReaderError, RecordReader},
+    RecordCipher,
+};
+use crate::Bufferable;
+
+/// A record read by a [`BufferInspector`].
+#[derive(Debug)]
+pub struct InspectedRecord<T> {
+    id: u64,
+    data_file_id: u16,
+    size: u64,
+    item: T,
+}
+
+impl<T> InspectedRecord<T> {
+    /// Gets the ID of the record.
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    /// Gets the ID of the data file the record was read from.
+    pub fn data_file_id(&self) -> u16 {
+        self.data_file_id
+    }
+
+    /// Gets the size of the record on disk, in bytes.
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    /// Gets a reference to the item held by the record.
+    pub fn item(&self) -> &T {
+        &self.item
+    }
+
+    /// Consumes the record, returning the item it held.
+    pub fn into_item(self) -> T {
+        self.item
+    }
+}
+
+/// Reads the unread records in a disk buffer without modifying it.
+///
+/// Unlike [`BufferReader`][super::BufferReader], the inspector never acknowledges records, deletes
+/// data files, or otherwise updates the ledger, so a buffer can be inspected as many times as
+/// needed, and will be read from the same position by Vector afterwards.
+///
+/// The buffer lock is held for as long as the inspector exists, which prevents a Vector process
+/// from opening the buffer while it is being inspected.
+pub struct BufferInspector<T> {
+    filesystem: ProductionFilesystem,
+    data_dir: PathBuf,
+    _lock: LockFile,
+    ledger: LedgerSnapshot,
+    cipher: Option<RecordCipher>,
+    current_data_file_id: u16,
+    reader: Option<RecordReader<<ProductionFilesystem as Filesystem>::File, T>>,
+    done: bool,
+}
+
+impl<T> BufferInspector<T>
+where
+    T: Bufferable,
+{
+    /// Opens the disk buffer in the given directory for inspection.
+    ///
+    /// If the buffer is encrypted, `cipher` must hold the keys its records were encrypted with in
+    /// order for them to be read.
+    ///
+    /// # Errors
+    ///
+    /// If the buffer does not exist, is currently in use by a Vector process, or its ledger can't be
+    /// read, an error variant will be returned describing the error.
+    pub async fn open(
+        data_dir: &Path,
+        cipher: Option<RecordCipher>,
+    ) -> Result<Self, LedgerLoadCreateError> {
+        let filesystem = ProductionFilesystem;
+        let (lock, ledger) = load_ledger_snapshot(&filesystem, data_dir).await?;
+
+        Ok(Self {
+            filesystem,
+            data_dir: data_dir.to_path_buf(),
+            _lock: lock,
+            ledger,
+            cipher,
+            current_data_file_id: ledger.reader_file_id,
+            reader: None,
+            done: false,
+        })
+    }
+
+    /// Gets the total number of unread events in the buffer, according to the ledger.
+    pub fn unread_events(&self) -> u64 {
+        self.ledger.get_total_records()
+    }
+
+    /// Gets the ID of the data file the buffer reader is on, and the ID of the data file the buffer
+    /// writer is on, according to the ledger.
+    pub fn data_file_ids(&self) -> (u16, u16) {
+        (self.ledger.reader_file_id, self.ledger.writer_file_id)
+    }
+
+    fn get_data_file_path(&self, file_id: u16) -> PathBuf {
+        self.data_dir.join(format!("buffer-data-{file_id}.dat"))
+    }
+
+    fn roll_to_next_data_file(&mut self) {
+        self.reader = None;
+        if self.current_data_file_id == self.ledger.writer_file_id {
+            self.done = true;
+        } else {
+            self.current_data_file_id = (self.current_data_file_id + 1) % MAX_FILE_ID;
+        }
+    }
+
+    /// Reads the next unread record in the buffer.
+    ///
+    /// Records are read in the same order that the buffer reader would read them, starting after
+    /// the last record that was acknowledged.  Once all records have been read, `None` is returned.
+    ///
+    /// # Errors
+    ///
+    /// If an error occurs while reading a record, an error variant will be returned describing the
+    /// error.  Reading can always continue with the next call: if the error indicates that the rest
+    /// of the data file can't be read, such as an I/O error or a corrupted record, the inspector
+    /// skips to the next data file.
+    pub async fn next(&mut self) -> Result<Option<InspectedRecord<T>>, ReaderError<T>> {
+        loop {
+            if self.done {
+                return Ok(None);
+            }
+
+            if self.reader.is_none() {
+                let data_file_path = self.get_data_file_path(self.current_data_file_id);
+                match self.filesystem.open_file_readable(&data_file_path).await {
+                    Ok(data_file) => {
+                        self.reader = Some(RecordReader::new(data_file, self.cipher.clone()));
+                    }
+                    Err(e) if e.kind() == ErrorKind::NotFound => {
+                        self.roll_to_next_data_file();
+                        continue;
+                    }
+                    Err(source) => {
+                        self.roll_to_next_data_file();
+                        return Err(ReaderError::Io { source });
+                    }
+                }
+            }
+
+            let data_file_id = self.current_data_file_id;
+            let reader = self.reader.as_mut().expect("reader should exist");
+
+            // Nothing is writing to the buffer while we hold the lock, so every data file is
+            // effectively finalized.
+            let token = match reader.try_next_record(true).await {
+                Ok(Some(token)) => token,
+                Ok(None) => {
+                    self.roll_to_next_data_file();
+                    continue;
+                }
+                Err(e) => {
+                    if e.is_bad_read() || matches!(e, ReaderError::Io { .. }) {
+                        self.roll_to_next_data_file();
+                    }
+                    return Err(e);
+                }
+            };
+
+            // Records are acknowledged as a whole, so any record starting at or before the last
+            // acknowledged record ID has already been read and acknowledged.
+            let id = token.record_id();
+            if id <= self.ledger.last_reader_record_id {
+                continue;
+            }
+
+            let size = token.record_bytes() as u64;
+            let item = reader.read_record(token)?;
+
+            return Ok(Some(InspectedRecord {
+                id,
+                data_file_id,
+                size,
+                item,
+            }));
+        }
+    }
+}
+
+impl<T> fmt::Debug for BufferInspector<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BufferInspector")
+            .field("data_dir", &self.data_dir)
+            .field("ledger", &self.ledger)
+            .field("current_data_file_id", &self.current_data_file_id)
+            .field("done", &self.done)
+            .finish_non_exhaustive()
+    }
+}
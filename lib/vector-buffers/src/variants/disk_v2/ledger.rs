@@ -1,7 +1,7 @@
 use std::{
     fmt, io, mem,
-    path::PathBuf,
-    sync::atomic::{AtomicBool, AtomicU16, AtomicU64, Ordering},
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicBool, AtomicU16, AtomicU32, AtomicU64, Ordering},
     sync::Arc,
     time::Instant,
 };
@@ -13,7 +13,11 @@ use fslock::LockFile;
 use futures::StreamExt;
 use rkyv::{with::Atomic, Archive, Serialize};
 use snafu::{ResultExt, Snafu};
-use tokio::{fs, io::AsyncWriteExt, sync::Notify};
+use tokio::{
+    fs,
+    io::AsyncWriteExt,
+    sync::{Mutex, MutexGuard, Notify},
+};
 use vector_common::finalizer::OrderedFinalizer;
 
 use super::{
@@ -70,6 +74,28 @@ pub enum LedgerLoadCreateError {
     /// buffers required for the serialization step.
     #[snafu(display("failed to serialize ledger to buffer: {}", reason))]
     FailedToSerialize { reason: String },
+
+    /// The buffer holds unread records encrypted with a key version that is not configured.
+    ///
+    /// This occurs when a key is removed from the encryption configuration, or encryption is
+    /// disabled entirely, before all of the records encrypted with that key have been read.
+    #[snafu(display(
+        "buffer holds unread records encrypted with key version {}, but no key is configured for it",
+        version
+    ))]
+    MissingEncryptionKey { version: u32 },
+
+    /// The encryption key was rotated while records encrypted with an even older key remain unread.
+    ///
+    /// The ledger only tracks the key versions of the last two keys in use, so rotating again
+    /// before the records from the oldest of them have been read would leave no record of which
+    /// key they require.
+    #[snafu(display(
+        "cannot rotate to key version {} while records encrypted with key version {} remain unread",
+        version,
+        pending_version
+    ))]
+    KeyRotationPending { version: u32, pending_version: u32 },
 }
 
 /// Ledger state.
@@ -104,6 +130,50 @@ pub struct LedgerState {
     reader_last_record: AtomicU64,
 }
 
+/// Key ledger state.
+///
+/// Tracks which encryption key the unread records in the buffer were encrypted with, so that the
+/// buffer can refuse to load if a key needed to read them is no longer configured.  Stored in its
+/// own file, next to the ledger, so that the layout of [`LedgerState`] is left untouched: buffers
+/// that have never been encrypted never have a key ledger at all.
+///
+/// # Warning
+///
+/// The same rules apply as for [`LedgerState`]: the serialized representation must never change.
+/// If it ever needs to, bump [`KEY_LEDGER_FORMAT_VERSION`] so that older key ledgers are detected.
+#[derive(Archive, Serialize, Debug)]
+#[archive_attr(derive(CheckBytes, Debug))]
+pub struct KeyLedgerState {
+    /// The format version of the key ledger.
+    format_version: u32,
+    /// The version of the key that records are currently encrypted with, or zero if records are
+    /// not encrypted.
+    #[with(Atomic)]
+    writer_key_version: AtomicU32,
+    /// The first record ID written with the current key version.
+    #[with(Atomic)]
+    writer_key_first_record: AtomicU64,
+    /// The version of the key that records before the first record ID written with the current key
+    /// version were encrypted with, or zero if those records are not encrypted.
+    #[with(Atomic)]
+    previous_key_version: AtomicU32,
+}
+
+/// Current format version of the key ledger.
+const KEY_LEDGER_FORMAT_VERSION: u32 = 1;
+
+impl KeyLedgerState {
+    /// Creates the key ledger state for a buffer whose records written so far are not encrypted.
+    fn unencrypted(writer_next_record: u64) -> Self {
+        Self {
+            format_version: KEY_LEDGER_FORMAT_VERSION,
+            writer_key_version: AtomicU32::new(0),
+            writer_key_first_record: AtomicU64::new(writer_next_record),
+            previous_key_version: AtomicU32::new(0),
+        }
+    }
+}
+
 impl Default for LedgerState {
     fn default() -> Self {
         Self {
@@ -201,6 +271,43 @@ impl ArchivedLedgerState {
     }
 }
 
+impl ArchivedKeyLedgerState {
+    fn get_writer_key_version(&self) -> u32 {
+        self.writer_key_version.load(Ordering::Acquire)
+    }
+
+    /// Gets the key version of the unread records written before the current key version, if any.
+    ///
+    /// A key version of zero means the records are not encrypted.
+    fn get_unread_previous_key_version(&self, ledger: &ArchivedLedgerState) -> Option<u32> {
+        let next_unread = ledger.get_last_reader_record_id().wrapping_add(1);
+        let key_first = self.writer_key_first_record.load(Ordering::Acquire);
+        (next_unread < key_first).then(|| self.previous_key_version.load(Ordering::Acquire))
+    }
+
+    /// Gets the key version of the unread records written with the current key version, if any.
+    ///
+    /// A key version of zero means the records are not encrypted.
+    fn get_unread_current_key_version(&self, ledger: &ArchivedLedgerState) -> Option<u32> {
+        let next_unread = ledger.get_last_reader_record_id().wrapping_add(1);
+        let key_first = self.writer_key_first_record.load(Ordering::Acquire);
+        (next_unread.max(key_first) < ledger.get_next_writer_record_id())
+            .then(|| self.get_writer_key_version())
+    }
+
+    /// Switches the key version that new records are encrypted with.
+    ///
+    /// Records written from this point onwards are tracked as being encrypted with `version`, and
+    /// records written before it as being encrypted with the key version in use until now.
+    fn rotate_writer_key_version(&self, ledger: &ArchivedLedgerState, version: u32) {
+        let current = self.get_writer_key_version();
+        self.previous_key_version.store(current, Ordering::Release);
+        self.writer_key_first_record
+            .store(ledger.get_next_writer_record_id(), Ordering::Release);
+        self.writer_key_version.store(version, Ordering::Release);
+    }
+}
+
 /// Tracks the internal state of the buffer.
 pub(crate) struct Ledger<FS>
 where
@@ -213,6 +320,8 @@ where
     lock: LockFile,
     // Ledger state.
     state: BackedArchive<FS::MutableMemoryMap, LedgerState>,
+    // Key ledger state, if the buffer is, or has been, encrypted.
+    key_state: Option<BackedArchive<FS::MutableMemoryMap, KeyLedgerState>>,
     // The total size, in bytes, of all unread records in the buffer.
     total_buffer_size: AtomicU64,
     // Notifier for reader-related progress.
@@ -225,6 +334,10 @@ where
     pending_acks: AtomicU64,
     // The file ID offset of the reader past the acknowledged reader file ID.
     unacked_reader_file_id_offset: AtomicU16,
+    // Number of events evicted by the writer that the reader has yet to skip over.
+    evicted_events: AtomicU64,
+    // Guards against the writer evicting a data file while the reader is opening it.
+    data_file_lock: Mutex<()>,
     // Last flush of all unflushed files: ledger, data file, etc.
     last_flush: AtomicCell<Instant>,
     // Tracks usage data about the buffer.
@@ -388,6 +501,57 @@ where
             .increment_received_event_count_and_byte_size(event_count, record_size);
     }
 
+    /// Tracks the size of a successfully written record before and after compression.
+    pub fn track_compression(&self, uncompressed_size: u64, compressed_size: u64) {
+        self.usage_handle
+            .increment_compressed_byte_size(uncompressed_size, compressed_size);
+    }
+
+    /// Tracks the statistics of a data file evicted by the writer to make space for new records.
+    ///
+    /// The events within the evicted data file are tracked as intentionally dropped, and are
+    /// credited so that the reader does not consider them lost when it skips over them.
+    pub fn track_evicted_data_file(&self, event_count: u64, file_size: u64) {
+        self.decrement_total_buffer_size(file_size);
+        self.evicted_events.fetch_add(event_count, Ordering::AcqRel);
+        self.usage_handle
+            .increment_evicted_event_count_and_byte_size(event_count, file_size);
+    }
+
+    /// Tracks the statistics of a record that was dropped because the buffer was full.
+    ///
+    /// Like the in-memory buffer does when dropping the newest events, the record is tracked as
+    /// received and then intentionally dropped, which keeps the buffer usage gauges balanced.
+    pub fn track_dropped_record(&self, event_count: u64, record_size: u64) {
+        self.usage_handle
+            .increment_received_event_count_and_byte_size(event_count, record_size);
+        self.usage_handle
+            .increment_dropped_event_count_and_byte_size(event_count, record_size, true);
+    }
+
+    /// Consumes up to `amount` events from the count of evicted events.
+    ///
+    /// Returns the number of events that were consumed, which callers should treat as having been
+    /// skipped intentionally rather than lost.
+    pub fn consume_evicted_events(&self, amount: u64) -> u64 {
+        let previous = self
+            .evicted_events
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |n| {
+                Some(n.saturating_sub(amount))
+            })
+            .unwrap_or_else(|n| n);
+        previous.min(amount)
+    }
+
+    /// Acquires the data file lock.
+    ///
+    /// The writer holds this lock while evicting a data file, and the reader holds it while opening
+    /// a data file, which ensures that the reader never opens a data file that is in the process of
+    /// being evicted.
+    pub async fn lock_data_files(&self) -> MutexGuard<'_, ()> {
+        self.data_file_lock.lock().await
+    }
+
     /// Tracks the statistics of multiple successful reads.
     pub fn track_reads(&self, event_count: u64, total_record_size: u64) {
         self.decrement_total_buffer_size(total_record_size);
@@ -508,6 +672,9 @@ where
     /// If there is an error while flushing the ledger to disk, an error variant will be returned
     /// describing the error.
     pub(super) fn flush(&self) -> io::Result<()> {
+        if let Some(key_state) = self.key_state.as_ref() {
+            key_state.get_backing_ref().flush()?;
+        }
         self.state.get_backing_ref().flush()
     }
 
@@ -632,6 +799,8 @@ where
             }
         };
 
+        let key_state = load_or_create_key_ledger(&config, ledger_state.get_archive_ref()).await?;
+
         // Create the ledger object, and synchronize the buffer statistics with the buffer usage
         // handle.  This handles making sure we account for the starting size of the buffer, and
         // what not.
@@ -639,12 +808,15 @@ where
             config,
             lock,
             state: ledger_state,
+            key_state,
             total_buffer_size: AtomicU64::new(0),
             reader_notify: Notify::new(),
             writer_notify: Notify::new(),
             writer_done: AtomicBool::new(false),
             pending_acks: AtomicU64::new(0),
             unacked_reader_file_id_offset: AtomicU16::new(0),
+            evicted_events: AtomicU64::new(0),
+            data_file_lock: Mutex::new(()),
             last_flush: AtomicCell::new(Instant::now()),
             usage_handle,
         };
@@ -713,6 +885,209 @@ where
     }
 }
 
+/// Loads or creates the key ledger for the given [`DiskBufferConfig`], if one is needed.
+///
+/// A key ledger is only created once encryption is configured, and is removed again once
+/// encryption is no longer configured and every encrypted record has been read, so buffers that
+/// are not encrypted keep the same on-disk layout as before encryption was supported.
+///
+/// # Errors
+///
+/// If a key needed to read the unread records in the buffer is not configured, or the key was
+/// rotated while records encrypted with an even older key remain unread, or there is an error
+/// loading or creating the key ledger, an error variant will be returned describing the error.
+async fn load_or_create_key_ledger<FS>(
+    config: &DiskBufferConfig<FS>,
+    ledger: &ArchivedLedgerState,
+) -> Result<Option<BackedArchive<FS::MutableMemoryMap, KeyLedgerState>>, LedgerLoadCreateError>
+where
+    FS: Filesystem,
+    FS::File: Unpin,
+{
+    let key_ledger_path = config.data_dir.join("buffer.keys");
+    if config.encryption.is_none() && !key_ledger_path.exists() {
+        return Ok(None);
+    }
+
+    let mut key_ledger_handle = config
+        .filesystem
+        .open_file_writable(&key_ledger_path)
+        .await
+        .context(IoSnafu)?;
+    let key_ledger_len = key_ledger_handle.metadata().await.context(IoSnafu)?.len();
+    if key_ledger_len == 0 {
+        // Every record written before now was written without encryption.
+        debug!("Key ledger file empty.  Initializing with unencrypted key ledger state.");
+        let writer_next_record = ledger.get_next_writer_record_id();
+        let mut buf = BytesMut::new();
+        loop {
+            match BackedArchive::from_value(
+                &mut buf,
+                KeyLedgerState::unencrypted(writer_next_record),
+            ) {
+                Ok(archive) => {
+                    key_ledger_handle
+                        .write_all(archive.get_backing_ref())
+                        .await
+                        .context(IoSnafu)?;
+                    break;
+                }
+                Err(SerializeError::FailedToSerialize(reason)) => {
+                    return Err(LedgerLoadCreateError::FailedToSerialize { reason })
+                }
+                // Our buffer wasn't big enough, but that's OK!  Resize it and try again.
+                Err(SerializeError::BackingStoreTooSmall(_, min_len)) => buf.resize(min_len, 0),
+            }
+        }
+
+        key_ledger_handle.sync_all().await.context(IoSnafu)?;
+    }
+    drop(key_ledger_handle);
+
+    let key_ledger_mmap = config
+        .filesystem
+        .open_mmap_writable(&key_ledger_path)
+        .await
+        .context(IoSnafu)?;
+    let key_state =
+        BackedArchive::<_, KeyLedgerState>::from_backing(key_ledger_mmap).map_err(|e| {
+            LedgerLoadCreateError::FailedToDeserialize {
+                reason: e.into_inner(),
+            }
+        })?;
+
+    let state: &ArchivedKeyLedgerState = key_state.get_archive_ref();
+    if state.format_version != KEY_LEDGER_FORMAT_VERSION {
+        return Err(LedgerLoadCreateError::FailedToDeserialize {
+            reason: format!(
+                "unsupported key ledger format version {}",
+                state.format_version
+            ),
+        });
+    }
+
+    // Make sure that we have the keys for every unread record in the buffer before going any
+    // further, as the reader would otherwise stall on the first record it can't decrypt.
+    let unread_key_versions = state
+        .get_unread_previous_key_version(ledger)
+        .into_iter()
+        .chain(state.get_unread_current_key_version(ledger));
+    for version in unread_key_versions.filter(|version| *version != 0) {
+        let has_key = config
+            .encryption
+            .as_ref()
+            .map_or(false, |cipher| cipher.has_key(version));
+        if !has_key {
+            return Err(LedgerLoadCreateError::MissingEncryptionKey { version });
+        }
+    }
+
+    // Encryption has been disabled and every encrypted record has been read, so the buffer no
+    // longer needs a key ledger at all.
+    if config.encryption.is_none() {
+        drop(key_state);
+        fs::remove_file(&key_ledger_path).await.context(IoSnafu)?;
+        return Ok(None);
+    }
+
+    // If the configured key has changed, track that records written from now on will use it.
+    let configured_version = config
+        .encryption
+        .as_ref()
+        .map_or(0, |cipher| cipher.active_version());
+    if configured_version != state.get_writer_key_version() {
+        if let Some(pending_version) = state
+            .get_unread_previous_key_version(ledger)
+            .filter(|version| *version != 0)
+        {
+            return Err(LedgerLoadCreateError::KeyRotationPending {
+                version: configured_version,
+                pending_version,
+            });
+        }
+
+        state.rotate_writer_key_version(ledger, configured_version);
+        key_state.get_backing_ref().flush().context(IoSnafu)?;
+    }
+
+    Ok(Some(key_state))
+}
+
+/// Point-in-time view of the reader and writer positions tracked by a ledger.
+#[derive(Clone, Copy, Debug)]
+pub(super) struct LedgerSnapshot {
+    pub reader_file_id: u16,
+    pub writer_file_id: u16,
+    pub last_reader_record_id: u64,
+    pub next_writer_record_id: u64,
+}
+
+impl LedgerSnapshot {
+    fn from_archived_state(state: &ArchivedLedgerState) -> Self {
+        Self {
+            reader_file_id: state.get_current_reader_file_id(),
+            writer_file_id: state.get_current_writer_file_id(),
+            last_reader_record_id: state.get_last_reader_record_id(),
+            next_writer_record_id: state.get_next_writer_record_id(),
+        }
+    }
+
+    /// Gets the total number of unread events in the buffer.
+    pub fn get_total_records(&self) -> u64 {
+        self.next_writer_record_id
+            .wrapping_sub(self.last_reader_record_id)
+            .saturating_sub(1)
+    }
+}
+
+/// Loads a snapshot of the ledger state in the given buffer directory, without modifying it.
+///
+/// The buffer lock is still acquired, and must be held for as long as the buffer is being read, so
+/// that a running Vector process can't modify the buffer in the meantime.
+///
+/// # Errors
+///
+/// If the buffer lock is already held, or there is an error reading or deserializing the ledger, an
+/// error variant will be returned describing the error.
+pub(super) async fn load_ledger_snapshot<FS>(
+    filesystem: &FS,
+    data_dir: &Path,
+) -> Result<(LockFile, LedgerSnapshot), LedgerLoadCreateError>
+where
+    FS: Filesystem,
+{
+    let ledger_path = data_dir.join("buffer.db");
+    if !ledger_path.exists() {
+        return Err(LedgerLoadCreateError::Io {
+            source: io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("no buffer found at {}", data_dir.display()),
+            ),
+        });
+    }
+
+    let ledger_lock_path = data_dir.join("buffer.lock");
+    let mut lock = LockFile::open(&ledger_lock_path).context(IoSnafu)?;
+    if !lock.try_lock().context(IoSnafu)? {
+        return Err(LedgerLoadCreateError::LedgerLockAlreadyHeld);
+    }
+
+    let ledger_mmap = filesystem
+        .open_mmap_readable(&ledger_path)
+        .await
+        .context(IoSnafu)?;
+    let snapshot = match BackedArchive::<_, LedgerState>::from_backing(ledger_mmap) {
+        Ok(state) => LedgerSnapshot::from_archived_state(state.get_archive_ref()),
+        Err(e) => {
+            return Err(LedgerLoadCreateError::FailedToDeserialize {
+                reason: e.into_inner(),
+            })
+        }
+    };
+
+    Ok((lock, snapshot))
+}
+
 impl<FS> fmt::Debug for Ledger<FS>
 where
     FS: Filesystem + fmt::Debug,
@@ -730,6 +1105,10 @@ where
                 "unacked_reader_file_id_offset",
                 &self.unacked_reader_file_id_offset.load(Ordering::Acquire),
             )
+            .field(
+                "evicted_events",
+                &self.evicted_events.load(Ordering::Acquire),
+            )
             .field("writer_done", &self.writer_done.load(Ordering::Acquire))
             .field("last_flush", &self.last_flush.load())
             .finish_non_exhaustive()
@@ -42,6 +42,37 @@
 //!   payload:    uint8[record_len]
 //! ```
 //!
+//! #### Encryption
+//!
+//! When encryption is enabled, the payload is encrypted (XChaCha20-Poly1305) before the record is
+//! checksummed, and the high bit of the record metadata is set to mark the record as encrypted. The
+//! payload then takes the following form, with the record ID and metadata used as associated data:
+//!
+//! ```text
+//! encrypted payload:
+//!   key_version: uint32 (BE)
+//!   nonce:       uint8[24]
+//!   ciphertext:  uint8[payload_len + 16]
+//! ```
+//!
+//! As the checksum covers the encrypted payload, corruption is detected without needing the key.
+//!
+//! #### Compression
+//!
+//! When compression is enabled, the payload is compressed (zstd or LZ4) before being encrypted, and
+//! one of the next two highest bits of the record metadata is set to mark which algorithm was used.
+//! The payload then takes the following form:
+//!
+//! ```text
+//! compressed payload:
+//!   uncompressed_len: uint32 (BE)
+//!   compressed:       uint8[compressed_len]
+//! ```
+//!
+//! Payloads that would not get any smaller are stored uncompressed, without the flag. Records
+//! written without compression, including those written before compression was enabled, can always
+//! be read regardless of whether or not compression is currently enabled.
+//!
 //! We say "pseudo-structure" as a helper serialization library, [`rkyv`][rkyv], is used to handle
 //! serialization, and zero-copy deserialization, of records. This effectively adds some amount of
 //! padding to record fields, due to the need to structure record field data in a way that makes it
@@ -92,6 +123,24 @@
 //!   reader_last_record_id:       uint64
 //! ```
 //!
+//! When encryption is configured, a second, separate file tracks which encryption key the unread
+//! records were encrypted with (zero meaning unencrypted), so that the buffer can refuse to load if
+//! a key needed to read them is no longer configured:
+//!
+//! ```text
+//! buffer.keys:
+//!   format_version:              uint32
+//!   writer_key_version:          uint32
+//!   writer_key_first_record_id:  uint64
+//!   previous_key_version:        uint32
+//! ```
+//!
+//! Keeping this out of `buffer.db` means the ledger layout is unchanged, and buffers that have never
+//! been encrypted never have a `buffer.keys` file at all.  The key ledger is removed again once
+//! encryption is disabled and every encrypted record has been read.  Versions of Vector that predate
+//! encryption ignore `buffer.keys`, so they can load a buffer that still holds encrypted records but
+//! will fail to decode them: the buffer must be drained of encrypted records before downgrading.
+//!
 //! As the disk buffer structure is meant to emulate a ring buffer, most of the bookkeeping resolves
 //! around the writer and reader being able to quickly figure out where they left off. Record and
 //! data file IDs are simply rolled over when they reach the maximum of their data type, and are
@@ -184,6 +233,9 @@ use vector_common::finalization::Finalizable;
 
 mod backed_archive;
 mod common;
+mod compression;
+mod encryption;
+mod inspector;
 mod io;
 mod ledger;
 mod reader;
@@ -197,6 +249,9 @@ mod tests;
 use self::ledger::Ledger;
 pub use self::{
     common::{DiskBufferConfig, DiskBufferConfigBuilder},
+    compression::RecordCompression,
+    encryption::{RecordCipher, KEY_LEN},
+    inspector::{BufferInspector, InspectedRecord},
     io::{Filesystem, ProductionFilesystem},
     ledger::LedgerLoadCreateError,
     reader::{BufferReader, ReaderError},
@@ -301,14 +356,24 @@ pub struct DiskV2Buffer {
     id: String,
     data_dir: PathBuf,
     max_size: NonZeroU64,
+    encryption: Option<RecordCipher>,
+    compression: Option<RecordCompression>,
 }
 
 impl DiskV2Buffer {
-    pub fn new(id: String, data_dir: PathBuf, max_size: NonZeroU64) -> Self {
+    pub fn new(
+        id: String,
+        data_dir: PathBuf,
+        max_size: NonZeroU64,
+        encryption: Option<RecordCipher>,
+        compression: Option<RecordCompression>,
+    ) -> Self {
         Self {
             id,
             data_dir,
             max_size,
+            encryption,
+            compression,
         }
     }
 }
@@ -331,6 +396,8 @@ where
             &self.data_dir,
             self.id.as_str(),
             self.max_size,
+            self.encryption,
+            self.compression,
         )
         .await?;
 
@@ -343,6 +410,8 @@ async fn build_disk_v2_buffer<T>(
     data_dir: &Path,
     id: &str,
     max_size: NonZeroU64,
+    encryption: Option<RecordCipher>,
+    compression: Option<RecordCompression>,
 ) -> Result<
     (
         BufferWriter<T, ProductionFilesystem>,
@@ -356,9 +425,15 @@ where
     usage_handle.set_buffer_limits(Some(max_size.get()), None);
 
     let buffer_path = get_disk_v2_data_dir_path(data_dir, id);
-    let config = DiskBufferConfigBuilder::from_path(buffer_path)
-        .max_buffer_size(max_size.get())
-        .build()?;
+    let mut builder =
+        DiskBufferConfigBuilder::from_path(buffer_path).max_buffer_size(max_size.get());
+    if let Some(cipher) = encryption {
+        builder = builder.encryption(cipher);
+    }
+    if let Some(compression) = compression {
+        builder = builder.compression(compression);
+    }
+    let config = builder.build()?;
     Buffer::from_config(config, usage_handle)
         .await
         .map_err(Into::into)
@@ -1,4 +1,5 @@
 use std::{
+    borrow::Cow,
     cmp, fmt,
     io::{self, ErrorKind},
     marker::PhantomData,
@@ -15,8 +16,12 @@ use vector_common::{finalization::BatchNotifier, finalizer::OrderedFinalizer};
 
 use super::{
     common::create_crc32c_hasher,
+    compression::RecordCompression,
+    encryption::{payload_key_version, EncryptionError, RecordCipher, ENCRYPTED_RECORD_FLAG},
     ledger::Ledger,
-    record::{validate_record_archive, ArchivedRecord, Record, RecordStatus},
+    record::{
+        validate_record_archive, ArchivedRecord, Record, RecordStatus, RESERVED_METADATA_FLAGS,
+    },
     Filesystem,
 };
 use crate::{
@@ -123,13 +128,36 @@ where
     /// writing logic of the buffer, or a record that does not use a symmetrical encoding scheme,
     /// which is also not supported.
     EmptyRecord,
+
+    /// The record is encrypted with a key version that is not configured.
+    ///
+    /// The buffer checks that the keys for all unread records are configured when it is loaded, so
+    /// this represents either a bug with the tracking of key versions, or a data file that was
+    /// altered outside of Vector.
+    #[snafu(display("no key is configured for key version {}", version))]
+    MissingEncryptionKey { version: u32 },
+
+    /// The record failed to decrypt.
+    ///
+    /// As the checksum covers the encrypted payload, this indicates that the record was encrypted
+    /// with a different key than the one configured for its key version.
+    #[snafu(display("failed to decrypt record: {}", reason))]
+    Decryption { reason: String },
+
+    /// The record failed to decompress.
+    ///
+    /// As the checksum covers the compressed payload, this represents either a bug with the
+    /// compression logic of the buffer, or a record that was compressed with a different
+    /// implementation of the compression algorithm that is not compatible.
+    #[snafu(display("failed to decompress record: {}", reason))]
+    Decompression { reason: String },
 }
 
 impl<T> ReaderError<T>
 where
     T: Bufferable,
 {
-    fn is_bad_read(&self) -> bool {
+    pub(super) fn is_bad_read(&self) -> bool {
         matches!(
             self,
             ReaderError::Checksum { .. }
@@ -147,6 +175,9 @@ where
             ReaderError::Incompatible { .. } => "incompatible_record_version",
             ReaderError::PartialWrite => "partial_write",
             ReaderError::EmptyRecord => "empty_record",
+            ReaderError::MissingEncryptionKey { .. } => "missing_encryption_key",
+            ReaderError::Decryption { .. } => "decryption_failed",
+            ReaderError::Decompression { .. } => "decompression_failed",
         }
     }
 
@@ -155,12 +186,16 @@ where
         let error_code = self.as_error_code();
 
         match self {
-            ReaderError::Io { .. } | ReaderError::EmptyRecord => None,
+            ReaderError::Io { .. }
+            | ReaderError::EmptyRecord
+            | ReaderError::MissingEncryptionKey { .. } => None,
             ReaderError::Deserialization { .. }
             | ReaderError::Checksum { .. }
             | ReaderError::Decode { .. }
             | ReaderError::Incompatible { .. }
-            | ReaderError::PartialWrite => Some(BufferReadError { error_code, error }),
+            | ReaderError::PartialWrite
+            | ReaderError::Decryption { .. }
+            | ReaderError::Decompression { .. } => Some(BufferReadError { error_code, error }),
         }
     }
 }
@@ -189,6 +224,17 @@ impl<T: Bufferable> PartialEq for ReaderError<T> {
             (Self::Incompatible { reason: l_reason }, Self::Incompatible { reason: r_reason }) => {
                 l_reason == r_reason
             }
+            (
+                Self::MissingEncryptionKey { version: l_version },
+                Self::MissingEncryptionKey { version: r_version },
+            ) => l_version == r_version,
+            (Self::Decryption { reason: l_reason }, Self::Decryption { reason: r_reason }) => {
+                l_reason == r_reason
+            }
+            (
+                Self::Decompression { reason: l_reason },
+                Self::Decompression { reason: r_reason },
+            ) => l_reason == r_reason,
             _ => core::mem::discriminant(self) == core::mem::discriminant(other),
         }
     }
@@ -199,6 +245,7 @@ pub(super) struct RecordReader<R, T> {
     reader: BufReader<R>,
     aligned_buf: AlignedVec,
     checksummer: Hasher,
+    cipher: Option<RecordCipher>,
     current_record_id: u64,
     _t: PhantomData<T>,
 }
@@ -212,11 +259,14 @@ where
     ///
     /// Internally, the reader is wrapped in a [`BufReader`], so callers should not pass in an
     /// already buffered reader.
-    pub fn new(reader: R) -> Self {
+    ///
+    /// If `cipher` is given, it is used to decrypt encrypted records.
+    pub fn new(reader: R, cipher: Option<RecordCipher>) -> Self {
         Self {
             reader: BufReader::with_capacity(256 * 1024, reader),
             aligned_buf: AlignedVec::new(),
             checksummer: create_crc32c_hasher(),
+            cipher,
             current_record_id: 0,
             _t: PhantomData,
         }
@@ -374,7 +424,7 @@ where
         // - `try_next_record` does all the archive checks, checksum validation, etc
         let record = unsafe { archived_root::<Record<'_>>(&self.aligned_buf) };
 
-        decode_record_payload(record)
+        decode_record_payload(record, self.cipher.as_ref())
     }
 }
 
@@ -387,6 +437,7 @@ where
             .field("reader", &self.reader)
             .field("aligned_buf", &self.aligned_buf)
             .field("checksummer", &self.checksummer)
+            .field("cipher", &self.cipher)
             .field("current_record_id", &self.current_record_id)
             .finish()
     }
@@ -511,40 +562,58 @@ where
         // occur at all, so we're relying on this method to correct the buffer size for us.  This is
         // why `bytes_read` is optional: when it's specified, we calculate a delta for handling
         // partial-read scenarios, otherwise, we just use the entire data file size as is.
-        let data_file = self
+        //
+        // If the data file no longer exists, it was evicted by the writer before we ever opened it,
+        // and the writer already adjusted the buffer size when evicting it.
+        let data_file = match self
             .ledger
             .filesystem()
             .open_file_readable(&data_file_path)
-            .await?;
-        let metadata = data_file.metadata().await?;
-
-        let decrease_amount = bytes_read.map_or_else(
-            || metadata.len(),
-            |bytes_read| {
-                let size_delta = metadata.len() - bytes_read;
-                if size_delta > 0 {
-                    debug!(
-                        actual_file_size = metadata.len(),
-                        bytes_read,
-                        "Data file was only partially read. Adjusting buffer size to compensate.",
-                    );
-                }
+            .await
+        {
+            Ok(data_file) => Some(data_file),
+            Err(e) if e.kind() == ErrorKind::NotFound => None,
+            Err(e) => return Err(e),
+        };
 
-                size_delta
-            },
-        );
+        if let Some(data_file) = data_file {
+            let metadata = data_file.metadata().await?;
+
+            let decrease_amount = bytes_read.map_or_else(
+                || metadata.len(),
+                |bytes_read| {
+                    let size_delta = metadata.len() - bytes_read;
+                    if size_delta > 0 {
+                        debug!(
+                            actual_file_size = metadata.len(),
+                            bytes_read,
+                            "Data file was only partially read. Adjusting buffer size to compensate.",
+                        );
+                    }
 
-        if decrease_amount > 0 {
-            self.ledger.decrement_total_buffer_size(decrease_amount);
-        }
+                    size_delta
+                },
+            );
+
+            if decrease_amount > 0 {
+                self.ledger.decrement_total_buffer_size(decrease_amount);
+            }
 
-        drop(data_file);
+            drop(data_file);
 
-        // Delete the current data file, and increment our actual reader file ID.
-        self.ledger
-            .filesystem()
-            .delete_file(&data_file_path)
-            .await?;
+            // Delete the current data file.
+            self.ledger
+                .filesystem()
+                .delete_file(&data_file_path)
+                .await?;
+        } else {
+            debug!(
+                data_file_path = data_file_path.to_string_lossy().as_ref(),
+                "Data file was already evicted by the writer."
+            );
+        }
+
+        // Increment our actual reader file ID.
         self.ledger.increment_acked_reader_file_id();
         self.ledger.flush()?;
 
@@ -634,9 +703,12 @@ where
                     .add_acknowledgements(records_acknowledged);
             }
 
-            // If any events were skipped, do our logging/metrics for that.
-            if events_skipped > 0 {
-                self.ledger.track_dropped_events(events_skipped);
+            // If any events were skipped, do our logging/metrics for that.  Events that the writer
+            // evicted were already tracked when they were evicted, so we don't count them again.
+            let events_evicted = self.ledger.consume_evicted_events(events_skipped);
+            if events_skipped > events_evicted {
+                self.ledger
+                    .track_dropped_events(events_skipped - events_evicted);
             }
         }
 
@@ -752,7 +824,12 @@ where
         // Try to open the current reader data file.  This might not _yet_ exist, in which case
         // we'll simply wait for the writer to signal to us that progress has been made, which
         // implies a data file existing.
+        //
+        // We hold the data file lock while opening the data file so that the writer can't evict
+        // it out from under us: once we have it open, the writer will no longer consider it for
+        // eviction.
         loop {
+            let data_file_lock = self.ledger.lock_data_files().await;
             let (reader_file_id, writer_file_id) = self.ledger.get_current_reader_writer_file_id();
             let data_file_path = self.ledger.get_current_reader_data_file_path();
             let data_file = match self
@@ -764,12 +841,22 @@ where
                 Ok(data_file) => data_file,
                 Err(e) => match e.kind() {
                     ErrorKind::NotFound => {
+                        drop(data_file_lock);
                         if reader_file_id == writer_file_id {
                             debug!(
                                 data_file_path = data_file_path.to_string_lossy().as_ref(),
                                 "Data file does not yet exist. Waiting for writer to create."
                             );
                             self.ledger.wait_for_writer().await;
+                        } else if self.ready_to_read {
+                            // The writer evicted this data file before we got to it, so move on to
+                            // the next one, while still tracking it so that our acknowledgement
+                            // state for data files stays in order.
+                            debug!(
+                                data_file_path = data_file_path.to_string_lossy().as_ref(),
+                                "Data file was evicted by the writer. Skipping."
+                            );
+                            self.roll_to_next_data_file();
                         } else {
                             self.ledger.increment_acked_reader_file_id();
                         }
@@ -785,7 +872,10 @@ where
                 "Opened data file for reading."
             );
 
-            self.reader = Some(RecordReader::new(data_file));
+            self.reader = Some(RecordReader::new(
+                data_file,
+                self.ledger.config().encryption.clone(),
+            ));
             return Ok(());
         }
     }
@@ -851,7 +941,8 @@ where
                     let record = try_as_record_archive(data_file_mmap.as_ref())
                         .expect("record was already validated");
 
-                    let Ok(item) = decode_record_payload::<T>(record) else {
+                    let cipher = self.ledger.config().encryption.as_ref();
+                    let Ok(item) = decode_record_payload::<T>(record, cipher) else {
                         // If there's an error decoding the item, just fall back to the slow path,
                         // because this file might actually be where we left off, so we don't want
                         // to incorrectly skip ahead or anything.
@@ -1131,10 +1222,15 @@ where
 
 pub(crate) fn decode_record_payload<T: Bufferable>(
     record: &ArchivedRecord<'_>,
+    cipher: Option<&RecordCipher>,
 ) -> Result<T, ReaderError<T>> {
+    // The high bits of the record metadata are reserved by the buffer itself, so mask them off
+    // before handing the metadata to `T`.
+    let record_metadata = record.metadata() & !RESERVED_METADATA_FLAGS;
+
     // Try and convert the raw record metadata into the true metadata type used by `T`, and then
     // also verify that `T` is able to decode records with the metadata used for this record in particular.
-    let metadata = T::Metadata::from_u32(record.metadata()).ok_or(ReaderError::Incompatible {
+    let metadata = T::Metadata::from_u32(record_metadata).ok_or(ReaderError::Incompatible {
         reason: format!("invalid metadata for {}", std::any::type_name::<T>()),
     })?;
 
@@ -1142,11 +1238,44 @@ pub(crate) fn decode_record_payload<T: Bufferable>(
         return Err(ReaderError::Incompatible {
             reason: format!(
                 "record metadata not supported (metadata: {:#036b})",
-                record.metadata()
+                record_metadata
             ),
         });
     }
 
+    // Decrypt the payload first, if it was encrypted, and then decompress it, if it was compressed.
+    let mut payload = Cow::Borrowed(record.payload());
+    if record.metadata() & ENCRYPTED_RECORD_FLAG != 0 {
+        payload = Cow::Owned(decrypt_record_payload(record, cipher)?);
+    }
+    if let Some(compression) = RecordCompression::from_metadata(record.metadata()) {
+        let decompressed =
+            compression
+                .decompress(&payload)
+                .map_err(|e| ReaderError::Decompression {
+                    reason: e.to_string(),
+                })?;
+        payload = Cow::Owned(decompressed);
+    }
+
     // Now we can finally try decoding.
-    T::decode(metadata, record.payload()).context(DecodeSnafu)
+    T::decode(metadata, &payload[..]).context(DecodeSnafu)
+}
+
+fn decrypt_record_payload<T: Bufferable>(
+    record: &ArchivedRecord<'_>,
+    cipher: Option<&RecordCipher>,
+) -> Result<Vec<u8>, ReaderError<T>> {
+    let result = match cipher {
+        Some(cipher) => cipher.decrypt(record.id(), record.metadata(), record.payload()),
+        None => payload_key_version(record.payload())
+            .and_then(|version| Err(EncryptionError::MissingKey { version })),
+    };
+
+    result.map_err(|e| match e {
+        EncryptionError::MissingKey { version } => ReaderError::MissingEncryptionKey { version },
+        e => ReaderError::Decryption {
+            reason: e.to_string(),
+        },
+    })
 }
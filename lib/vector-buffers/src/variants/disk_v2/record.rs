@@ -10,11 +10,18 @@ use rkyv::{
 
 use super::{
     common::align16,
+    compression::{LZ4_COMPRESSED_RECORD_FLAG, ZSTD_COMPRESSED_RECORD_FLAG},
+    encryption::ENCRYPTED_RECORD_FLAG,
     ser::{try_as_archive, DeserializeError},
 };
 
 pub const RECORD_HEADER_LEN: usize = align16(mem::size_of::<ArchivedRecord<'_>>() + 8);
 
+/// All record metadata bits reserved by the buffer, which must be masked off before handing the
+/// metadata to `T`.
+pub const RESERVED_METADATA_FLAGS: u32 =
+    ENCRYPTED_RECORD_FLAG | ZSTD_COMPRESSED_RECORD_FLAG | LZ4_COMPRESSED_RECORD_FLAG;
+
 /// Result of checking if a buffer contained a valid record.
 pub enum RecordStatus {
     /// The record was able to be read from the buffer, and the checksum is valid.
@@ -128,6 +135,11 @@ impl<'a> Record<'a> {
 }
 
 impl<'a> ArchivedRecord<'a> {
+    /// Gets the ID of this record.
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
     /// Gets the metadata of this record.
     pub fn metadata(&self) -> u32 {
         self.metadata
@@ -0,0 +1,192 @@
+use tracing::Instrument;
+
+use super::create_buffer_v2_with_compression;
+use crate::{
+    assert_buffer_is_empty,
+    buffer_usage_data::BufferUsageHandle,
+    test::{acknowledge, install_tracing_helpers, with_temp_dir, SizedRecord},
+    variants::disk_v2::{Buffer, DiskBufferConfigBuilder, RecordCipher, RecordCompression},
+};
+
+#[tokio::test]
+async fn compressed_records_roundtrip() {
+    let _a = install_tracing_helpers();
+
+    for compression in [RecordCompression::Zstd, RecordCompression::Lz4] {
+        let fut = with_temp_dir(|dir| {
+            let data_dir = dir.to_path_buf();
+
+            async move {
+                let (mut writer, mut reader, ledger) =
+                    create_buffer_v2_with_compression(data_dir, Some(compression)).await;
+                assert_buffer_is_empty!(ledger);
+
+                // Write a record, which is a long run of the same byte, and make sure that the data
+                // file ends up being far smaller than the record itself.
+                writer
+                    .write_record(SizedRecord::new(8192))
+                    .await
+                    .expect("write should not fail");
+                writer.flush().await.expect("flush should not fail");
+
+                let data_file = tokio::fs::read(ledger.get_current_writer_data_file_path())
+                    .await
+                    .expect("should not fail to read data file");
+                assert!(data_file.len() < 1024);
+
+                // Now read the record back out, which should give us the original record.
+                writer.close();
+                let record = reader
+                    .next()
+                    .await
+                    .expect("read should not fail")
+                    .expect("read should produce a record");
+                assert_eq!(SizedRecord::new(8192), record);
+                acknowledge(record).await;
+
+                let second_read = reader.next().await.expect("read should not fail");
+                assert_eq!(None, second_read);
+                assert_buffer_is_empty!(ledger);
+            }
+        });
+
+        let parent = trace_span!("compressed_records_roundtrip", ?compression);
+        fut.instrument(parent.or_current()).await;
+    }
+}
+
+#[tokio::test]
+async fn records_readable_after_changing_compression() {
+    let _a = install_tracing_helpers();
+
+    let fut = with_temp_dir(|dir| {
+        let data_dir = dir.to_path_buf();
+
+        async move {
+            // Write a record with each compression setting, reopening the buffer in between, without
+            // reading any of them.
+            let settings = [
+                (None, 1024),
+                (Some(RecordCompression::Zstd), 2048),
+                (Some(RecordCompression::Lz4), 3072),
+                (None, 4096),
+            ];
+            for (compression, record_size) in settings {
+                let (mut writer, reader, ledger) =
+                    create_buffer_v2_with_compression(data_dir.clone(), compression).await;
+                writer
+                    .write_record(SizedRecord::new(record_size))
+                    .await
+                    .expect("write should not fail");
+                writer.flush().await.expect("flush should not fail");
+                ledger.flush().expect("flush should not fail");
+                drop(reader);
+                drop(writer);
+                drop(ledger);
+            }
+
+            // All of the records should be readable, regardless of the current setting.
+            let (writer, mut reader, ledger) =
+                create_buffer_v2_with_compression(data_dir, Some(RecordCompression::Zstd)).await;
+            writer.close();
+
+            for (_, record_size) in settings {
+                let record = reader
+                    .next()
+                    .await
+                    .expect("read should not fail")
+                    .expect("read should produce a record");
+                assert_eq!(SizedRecord::new(record_size), record);
+                acknowledge(record).await;
+            }
+
+            let final_read = reader.next().await.expect("read should not fail");
+            assert_eq!(None, final_read);
+            assert_buffer_is_empty!(ledger);
+        }
+    });
+
+    let parent = trace_span!("records_readable_after_changing_compression");
+    fut.instrument(parent.or_current()).await;
+}
+
+#[tokio::test]
+async fn incompressible_records_stored_uncompressed() {
+    let _a = install_tracing_helpers();
+
+    let fut = with_temp_dir(|dir| {
+        let data_dir = dir.to_path_buf();
+
+        async move {
+            // A tiny record can't get any smaller by compressing it, so it should be written as-is,
+            // and still be readable.
+            let (mut writer, mut reader, ledger) =
+                create_buffer_v2_with_compression(data_dir, Some(RecordCompression::Zstd)).await;
+            writer
+                .write_record(SizedRecord::new(1))
+                .await
+                .expect("write should not fail");
+            writer.flush().await.expect("flush should not fail");
+            writer.close();
+
+            let record = reader
+                .next()
+                .await
+                .expect("read should not fail")
+                .expect("read should produce a record");
+            assert_eq!(SizedRecord::new(1), record);
+            acknowledge(record).await;
+            assert_buffer_is_empty!(ledger);
+        }
+    });
+
+    let parent = trace_span!("incompressible_records_stored_uncompressed");
+    fut.instrument(parent.or_current()).await;
+}
+
+#[tokio::test]
+async fn compressed_and_encrypted_records_roundtrip() {
+    let _a = install_tracing_helpers();
+
+    let fut = with_temp_dir(|dir| {
+        let data_dir = dir.to_path_buf();
+
+        async move {
+            let config = DiskBufferConfigBuilder::from_path(data_dir)
+                .compression(RecordCompression::Lz4)
+                .encryption(RecordCipher::new(1, &[1; 32], []))
+                .build()
+                .expect("creating buffer should not fail");
+            let (mut writer, mut reader, ledger) =
+                Buffer::<SizedRecord>::from_config_inner(config, BufferUsageHandle::noop())
+                    .await
+                    .expect("should not fail to create buffer");
+
+            writer
+                .write_record(SizedRecord::new(8192))
+                .await
+                .expect("write should not fail");
+            writer.flush().await.expect("flush should not fail");
+
+            // The payload should have been compressed before being encrypted, as encrypted data
+            // doesn't compress.
+            let data_file = tokio::fs::read(ledger.get_current_writer_data_file_path())
+                .await
+                .expect("should not fail to read data file");
+            assert!(data_file.len() < 1024);
+
+            writer.close();
+            let record = reader
+                .next()
+                .await
+                .expect("read should not fail")
+                .expect("read should produce a record");
+            assert_eq!(SizedRecord::new(8192), record);
+            acknowledge(record).await;
+            assert_buffer_is_empty!(ledger);
+        }
+    });
+
+    let parent = trace_span!("compressed_and_encrypted_records_roundtrip");
+    fut.instrument(parent.or_current()).await;
+}
@@ -0,0 +1,123 @@
+use tracing::Instrument;
+
+use super::get_minimum_data_file_size_for_record_payload;
+use crate::{
+    assert_buffer_is_empty,
+    buffer_usage_data::BufferUsageHandle,
+    test::{acknowledge, install_tracing_helpers, with_temp_dir, SizedRecord},
+    variants::disk_v2::{ledger::LEDGER_LEN, Buffer, DiskBufferConfigBuilder},
+};
+
+#[tokio::test]
+async fn writer_evicts_oldest_data_files_when_buffer_is_full() {
+    let _a = install_tracing_helpers();
+    let fut = with_temp_dir(|dir| {
+        let data_dir = dir.to_path_buf();
+
+        async move {
+            // Create our buffer such that every data file holds a single record, and only a handful
+            // of data files fit in the buffer, so that we're forced to evict data files in order to
+            // write all of the records.
+            let records = (80..88).map(SizedRecord::new).collect::<Vec<_>>();
+            let last_record = records.last().cloned().unwrap();
+
+            let max_data_file_size = get_minimum_data_file_size_for_record_payload(&last_record);
+            let ledger_len: u64 = LEDGER_LEN.try_into().unwrap();
+            let config = DiskBufferConfigBuilder::from_path(data_dir)
+                .max_record_size(usize::try_from(max_data_file_size).unwrap())
+                .max_data_file_size(max_data_file_size)
+                .max_buffer_size(max_data_file_size * 4 + ledger_len)
+                .build()
+                .expect("creating buffer should not fail");
+            let usage_handle = BufferUsageHandle::noop();
+            let (mut writer, mut reader, ledger) =
+                Buffer::from_config_inner(config, usage_handle.clone())
+                    .await
+                    .expect("should not fail to create buffer");
+
+            // Every write should succeed, as we can always evict a data file that the reader hasn't
+            // gotten to yet.
+            for record in records.iter().cloned() {
+                let result = writer
+                    .write_record_evicting_oldest(record)
+                    .await
+                    .expect("write should not fail");
+                assert_eq!(result, None);
+            }
+            writer.flush().await.expect("flush should not fail");
+            writer.close();
+
+            let snapshot = usage_handle.snapshot();
+            assert!(snapshot.evicted_event_count > 0);
+
+            // The reader should get the very first record, since it was in the data file being
+            // read from, and then every record written after the evicted data files.
+            let mut read_records = Vec::new();
+            while let Some(record) = reader.next().await.expect("read should not fail") {
+                read_records.push(SizedRecord::new(record.0));
+                acknowledge(record).await;
+            }
+
+            let evicted_count = usize::try_from(snapshot.evicted_event_count).unwrap();
+            let mut expected_records = vec![records[0].clone()];
+            expected_records.extend_from_slice(&records[1 + evicted_count..]);
+            assert_eq!(read_records, expected_records);
+
+            // None of the evicted events should be considered lost by the reader.
+            let snapshot = usage_handle.snapshot();
+            assert_eq!(snapshot.dropped_event_count, 0);
+            assert_eq!(snapshot.dropped_event_count_intentional, 0);
+            assert_buffer_is_empty!(ledger);
+        }
+    });
+
+    let parent = trace_span!("writer_evicts_oldest_data_files_when_buffer_is_full");
+    fut.instrument(parent.or_current()).await;
+}
+
+#[tokio::test]
+async fn writer_drops_record_when_nothing_can_be_evicted() {
+    let _a = install_tracing_helpers();
+    let fut = with_temp_dir(|dir| {
+        let data_dir = dir.to_path_buf();
+
+        async move {
+            // Create our buffer such that only a single record fits, which means the reader and
+            // writer are on the same data file, and there's nothing we can evict.
+            let record = SizedRecord::new(96);
+            let max_data_file_size = get_minimum_data_file_size_for_record_payload(&record);
+            let ledger_len: u64 = LEDGER_LEN.try_into().unwrap();
+            let config = DiskBufferConfigBuilder::from_path(data_dir)
+                .max_record_size(usize::try_from(max_data_file_size).unwrap())
+                .max_data_file_size(max_data_file_size)
+                .max_buffer_size(max_data_file_size * 2 + ledger_len)
+                .build()
+                .expect("creating buffer should not fail");
+            let usage_handle = BufferUsageHandle::noop();
+            let (mut writer, _reader, _ledger) =
+                Buffer::from_config_inner(config, usage_handle.clone())
+                    .await
+                    .expect("should not fail to create buffer");
+
+            let first_result = writer
+                .write_record_evicting_oldest(record.clone())
+                .await
+                .expect("write should not fail");
+            assert_eq!(first_result, None);
+            writer.flush().await.expect("flush should not fail");
+
+            let second_result = writer
+                .write_record_evicting_oldest(record.clone())
+                .await
+                .expect("write should not fail");
+            assert_eq!(second_result, Some(record));
+
+            let snapshot = usage_handle.snapshot();
+            assert_eq!(snapshot.evicted_event_count, 0);
+            assert_eq!(snapshot.dropped_event_count_intentional, 1);
+        }
+    });
+
+    let parent = trace_span!("writer_drops_record_when_nothing_can_be_evicted");
+    fut.instrument(parent.or_current()).await;
+}
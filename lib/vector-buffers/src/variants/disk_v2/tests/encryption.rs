@@ -0,0 +1,300 @@
+use std::path::Path;
+
+use tracing::Instrument;
+
+use super::create_buffer_v2_with_encryption;
+use crate::{
+    assert_buffer_is_empty, assert_file_does_not_exist_async, assert_file_exists_async,
+    buffer_usage_data::BufferUsageHandle,
+    test::{acknowledge, install_tracing_helpers, with_temp_dir, SizedRecord},
+    variants::disk_v2::{
+        Buffer, BufferError, DiskBufferConfigBuilder, LedgerLoadCreateError, RecordCipher,
+    },
+};
+
+fn cipher_v1() -> RecordCipher {
+    RecordCipher::new(1, &[1; 32], [])
+}
+
+fn cipher_v2_with_v1() -> RecordCipher {
+    RecordCipher::new(2, &[2; 32], [(1, [1; 32])])
+}
+
+async fn open_buffer_expecting_error(
+    data_dir: &Path,
+    cipher: Option<RecordCipher>,
+) -> BufferError<SizedRecord> {
+    let mut builder = DiskBufferConfigBuilder::from_path(data_dir);
+    if let Some(cipher) = cipher {
+        builder = builder.encryption(cipher);
+    }
+    let config = builder.build().expect("creating buffer should not fail");
+
+    match Buffer::<SizedRecord>::from_config_inner(config, BufferUsageHandle::noop()).await {
+        Ok(_) => panic!("buffer should have failed to load"),
+        Err(e) => e,
+    }
+}
+
+#[tokio::test]
+async fn encrypted_records_roundtrip() {
+    let _a = install_tracing_helpers();
+
+    let fut = with_temp_dir(|dir| {
+        let data_dir = dir.to_path_buf();
+
+        async move {
+            let (mut writer, mut reader, ledger) =
+                create_buffer_v2_with_encryption(data_dir, Some(cipher_v1())).await;
+            assert_buffer_is_empty!(ledger);
+
+            // Write a record, which is a long run of the same byte, and make sure that it doesn't
+            // show up anywhere in the data file.
+            writer
+                .write_record(SizedRecord::new(256))
+                .await
+                .expect("write should not fail");
+            writer.flush().await.expect("flush should not fail");
+
+            let data_file = tokio::fs::read(ledger.get_current_writer_data_file_path())
+                .await
+                .expect("should not fail to read data file");
+            assert!(!data_file
+                .windows(64)
+                .any(|window| window.iter().all(|b| *b == 0x42)));
+
+            // Now read the record back out, which should give us the original record.
+            writer.close();
+            let record = reader
+                .next()
+                .await
+                .expect("read should not fail")
+                .expect("read should produce a record");
+            assert_eq!(SizedRecord::new(256), record);
+            acknowledge(record).await;
+
+            let second_read = reader.next().await.expect("read should not fail");
+            assert_eq!(None, second_read);
+            assert_buffer_is_empty!(ledger);
+        }
+    });
+
+    let parent = trace_span!("encrypted_records_roundtrip");
+    fut.instrument(parent.or_current()).await;
+}
+
+#[tokio::test]
+async fn rotated_key_still_reads_existing_records() {
+    let _a = install_tracing_helpers();
+
+    let fut = with_temp_dir(|dir| {
+        let data_dir = dir.to_path_buf();
+
+        async move {
+            // Write a record with the first key, and close the buffer without reading it.
+            let (mut writer, reader, ledger) =
+                create_buffer_v2_with_encryption(data_dir.clone(), Some(cipher_v1())).await;
+            writer
+                .write_record(SizedRecord::new(32))
+                .await
+                .expect("write should not fail");
+            writer.flush().await.expect("flush should not fail");
+            ledger.flush().expect("flush should not fail");
+            drop(reader);
+            drop(writer);
+            drop(ledger);
+
+            // Rotate to the second key, keeping the first key around, and write another record.
+            let (mut writer, mut reader, ledger) =
+                create_buffer_v2_with_encryption(data_dir, Some(cipher_v2_with_v1())).await;
+            writer
+                .write_record(SizedRecord::new(64))
+                .await
+                .expect("write should not fail");
+            writer.flush().await.expect("flush should not fail");
+            writer.close();
+
+            // Both records should be readable.
+            for expected in [SizedRecord::new(32), SizedRecord::new(64)] {
+                let record = reader
+                    .next()
+                    .await
+                    .expect("read should not fail")
+                    .expect("read should produce a record");
+                assert_eq!(expected, record);
+                acknowledge(record).await;
+            }
+
+            let third_read = reader.next().await.expect("read should not fail");
+            assert_eq!(None, third_read);
+            assert_buffer_is_empty!(ledger);
+        }
+    });
+
+    let parent = trace_span!("rotated_key_still_reads_existing_records");
+    fut.instrument(parent.or_current()).await;
+}
+
+#[tokio::test]
+async fn missing_key_for_unread_records_fails_startup() {
+    let _a = install_tracing_helpers();
+
+    let fut = with_temp_dir(|dir| {
+        let data_dir = dir.to_path_buf();
+
+        async move {
+            // Write a record with the first key, and close the buffer without reading it.
+            let (mut writer, reader, ledger) =
+                create_buffer_v2_with_encryption(data_dir.clone(), Some(cipher_v1())).await;
+            writer
+                .write_record(SizedRecord::new(32))
+                .await
+                .expect("write should not fail");
+            writer.flush().await.expect("flush should not fail");
+            ledger.flush().expect("flush should not fail");
+            drop(reader);
+            drop(writer);
+            drop(ledger);
+
+            // Neither disabling encryption nor rotating without keeping the first key around is
+            // allowed while the record is still unread.
+            let without_key = RecordCipher::new(2, &[2; 32], []);
+            for cipher in [None, Some(without_key)] {
+                match open_buffer_expecting_error(&data_dir, cipher).await {
+                    BufferError::LedgerError {
+                        source: LedgerLoadCreateError::MissingEncryptionKey { version },
+                    } => assert_eq!(1, version),
+                    e => panic!("unexpected error: {}", e),
+                }
+            }
+
+            // With the first key still configured, the record can be read.
+            let (_writer, mut reader, _ledger) =
+                create_buffer_v2_with_encryption(data_dir, Some(cipher_v2_with_v1())).await;
+            let record = reader
+                .next()
+                .await
+                .expect("read should not fail")
+                .expect("read should produce a record");
+            assert_eq!(SizedRecord::new(32), record);
+        }
+    });
+
+    let parent = trace_span!("missing_key_for_unread_records_fails_startup");
+    fut.instrument(parent.or_current()).await;
+}
+
+#[tokio::test]
+async fn plaintext_records_readable_after_enabling_encryption() {
+    let _a = install_tracing_helpers();
+
+    let fut = with_temp_dir(|dir| {
+        let data_dir = dir.to_path_buf();
+
+        async move {
+            // Write a record without encryption, and close the buffer without reading it.
+            let (mut writer, reader, ledger) =
+                create_buffer_v2_with_encryption(data_dir.clone(), None).await;
+            writer
+                .write_record(SizedRecord::new(32))
+                .await
+                .expect("write should not fail");
+            writer.flush().await.expect("flush should not fail");
+            ledger.flush().expect("flush should not fail");
+            drop(reader);
+            drop(writer);
+            drop(ledger);
+
+            // Enable encryption, and write another record.
+            let (mut writer, mut reader, ledger) =
+                create_buffer_v2_with_encryption(data_dir, Some(cipher_v1())).await;
+            writer
+                .write_record(SizedRecord::new(64))
+                .await
+                .expect("write should not fail");
+            writer.flush().await.expect("flush should not fail");
+            writer.close();
+
+            for expected in [SizedRecord::new(32), SizedRecord::new(64)] {
+                let record = reader
+                    .next()
+                    .await
+                    .expect("read should not fail")
+                    .expect("read should produce a record");
+                assert_eq!(expected, record);
+                acknowledge(record).await;
+            }
+
+            let third_read = reader.next().await.expect("read should not fail");
+            assert_eq!(None, third_read);
+            assert_buffer_is_empty!(ledger);
+        }
+    });
+
+    let parent = trace_span!("plaintext_records_readable_after_enabling_encryption");
+    fut.instrument(parent.or_current()).await;
+}
+
+#[tokio::test]
+async fn key_ledger_only_exists_while_encrypted() {
+    let _a = install_tracing_helpers();
+
+    let fut = with_temp_dir(|dir| {
+        let data_dir = dir.to_path_buf();
+        let key_ledger_path = data_dir.join("buffer.keys");
+
+        async move {
+            // An unencrypted buffer never gets a key ledger, so its on-disk layout is unchanged.
+            let (mut writer, reader, ledger) =
+                create_buffer_v2_with_encryption(data_dir.clone(), None).await;
+            writer
+                .write_record(SizedRecord::new(32))
+                .await
+                .expect("write should not fail");
+            writer.flush().await.expect("flush should not fail");
+            ledger.flush().expect("flush should not fail");
+            assert_file_does_not_exist_async!(&key_ledger_path);
+            drop(reader);
+            drop(writer);
+            drop(ledger);
+
+            // Enabling encryption creates the key ledger.  Read everything back out, including a
+            // newly-written encrypted record.
+            let (mut writer, mut reader, ledger) =
+                create_buffer_v2_with_encryption(data_dir.clone(), Some(cipher_v1())).await;
+            assert_file_exists_async!(&key_ledger_path);
+            writer
+                .write_record(SizedRecord::new(64))
+                .await
+                .expect("write should not fail");
+            writer.flush().await.expect("flush should not fail");
+            writer.close();
+
+            for expected in [SizedRecord::new(32), SizedRecord::new(64)] {
+                let record = reader
+                    .next()
+                    .await
+                    .expect("read should not fail")
+                    .expect("read should produce a record");
+                assert_eq!(expected, record);
+                acknowledge(record).await;
+            }
+
+            let third_read = reader.next().await.expect("read should not fail");
+            assert_eq!(None, third_read);
+            assert_buffer_is_empty!(ledger);
+            ledger.flush().expect("flush should not fail");
+            drop(reader);
+            drop(writer);
+            drop(ledger);
+
+            // With every encrypted record read, disabling encryption removes the key ledger again.
+            let (_writer, _reader, ledger) = create_buffer_v2_with_encryption(data_dir, None).await;
+            assert_buffer_is_empty!(ledger);
+            assert_file_does_not_exist_async!(&key_ledger_path);
+        }
+    });
+
+    let parent = trace_span!("key_ledger_only_exists_while_encrypted");
+    fut.instrument(parent.or_current()).await;
+}
@@ -0,0 +1,104 @@
+use tracing::Instrument;
+
+use super::create_default_buffer_v2;
+use crate::{
+    test::{acknowledge, install_tracing_helpers, with_temp_dir, SizedRecord},
+    variants::disk_v2::{BufferInspector, LedgerLoadCreateError},
+};
+
+#[tokio::test]
+async fn inspector_reads_unacknowledged_records_without_modifying_buffer() {
+    let _a = install_tracing_helpers();
+    let fut = with_temp_dir(|dir| {
+        let data_dir = dir.to_path_buf();
+
+        async move {
+            let records = (100..104).map(SizedRecord::new).collect::<Vec<_>>();
+
+            // Write a few records, and read and acknowledge the first one.  We read the second one
+            // without acknowledging it, which also ensures the acknowledgement of the first one has
+            // been applied to the ledger.
+            let (mut writer, mut reader, ledger) = create_default_buffer_v2(data_dir.clone()).await;
+            for record in records.iter().cloned() {
+                writer
+                    .write_record(record)
+                    .await
+                    .expect("write should not fail");
+            }
+            writer.flush().await.expect("flush should not fail");
+
+            let first = reader
+                .next()
+                .await
+                .expect("read should not fail")
+                .expect("read should produce a record");
+            acknowledge(first).await;
+            let second = reader
+                .next()
+                .await
+                .expect("read should not fail")
+                .expect("read should produce a record");
+            assert_eq!(second, records[1]);
+
+            // The buffer can't be inspected while it's open.
+            let result = BufferInspector::<SizedRecord>::open(&data_dir, None).await;
+            assert!(matches!(
+                result,
+                Err(LedgerLoadCreateError::LedgerLockAlreadyHeld)
+            ));
+
+            ledger.flush().expect("flush should not fail");
+            drop(second);
+            drop(reader);
+            drop(writer);
+            drop(ledger);
+
+            // Inspecting the buffer should give us every unacknowledged record, and doing it twice
+            // should give us the same records both times.
+            for _ in 0..2 {
+                let mut inspector = BufferInspector::<SizedRecord>::open(&data_dir, None)
+                    .await
+                    .expect("should not fail to open buffer for inspection");
+                assert_eq!(inspector.unread_events(), 3);
+
+                let mut inspected = Vec::new();
+                while let Some(record) = inspector.next().await.expect("read should not fail") {
+                    assert!(record.size() > 0);
+                    inspected.push(record.into_item());
+                }
+                assert_eq!(inspected, records[1..]);
+            }
+
+            // The buffer should still be read from where it left off.
+            let (writer, mut reader, _ledger) = create_default_buffer_v2(data_dir).await;
+            writer.close();
+
+            let mut read_records = Vec::new();
+            while let Some(record) = reader.next().await.expect("read should not fail") {
+                read_records.push(SizedRecord::new(record.0));
+                acknowledge(record).await;
+            }
+            assert_eq!(read_records, records[1..]);
+        }
+    });
+
+    let parent = trace_span!("inspector_reads_unacknowledged_records_without_modifying_buffer");
+    fut.instrument(parent.or_current()).await;
+}
+
+#[tokio::test]
+async fn inspector_fails_when_buffer_does_not_exist() {
+    let _a = install_tracing_helpers();
+    let fut = with_temp_dir(|dir| {
+        let data_dir = dir.join("missing");
+
+        async move {
+            let result = BufferInspector::<SizedRecord>::open(&data_dir, None).await;
+            assert!(matches!(result, Err(LedgerLoadCreateError::Io { .. })));
+            assert!(!data_dir.exists());
+        }
+    });
+
+    let parent = trace_span!("inspector_fails_when_buffer_does_not_exist");
+    fut.instrument(parent.or_current()).await;
+}
@@ -14,7 +14,8 @@ use super::{
     io::{AsyncFile, Metadata, ProductionFilesystem, ReadableMemoryMap, WritableMemoryMap},
     ledger::LEDGER_LEN,
     record::RECORD_HEADER_LEN,
-    Buffer, BufferReader, BufferWriter, DiskBufferConfigBuilder, Filesystem, Ledger,
+    Buffer, BufferReader, BufferWriter, DiskBufferConfigBuilder, Filesystem, Ledger, RecordCipher,
+    RecordCompression,
 };
 use crate::{
     buffer_usage_data::BufferUsageHandle, encoding::FixedEncodable,
@@ -25,7 +26,11 @@ type FilesystemUnderTest = ProductionFilesystem;
 
 mod acknowledgements;
 mod basic;
+mod compression;
+mod drop_oldest;
+mod encryption;
 mod initialization;
+mod inspector;
 mod invariants;
 mod known_errors;
 mod model;
@@ -363,6 +368,56 @@ where
         .expect("should not fail to create buffer")
 }
 
+/// Creates a disk v2 buffer with the specified cipher, or no encryption if `None`.
+pub(crate) async fn create_buffer_v2_with_encryption<P, R>(
+    data_dir: P,
+    cipher: Option<RecordCipher>,
+) -> (
+    BufferWriter<R, FilesystemUnderTest>,
+    BufferReader<R, FilesystemUnderTest>,
+    Arc<Ledger<FilesystemUnderTest>>,
+)
+where
+    P: AsRef<Path>,
+    R: Bufferable,
+{
+    let mut builder = DiskBufferConfigBuilder::from_path(data_dir);
+    if let Some(cipher) = cipher {
+        builder = builder.encryption(cipher);
+    }
+    let config = builder.build().expect("creating buffer should not fail");
+    let usage_handle = BufferUsageHandle::noop();
+
+    Buffer::from_config_inner(config, usage_handle)
+        .await
+        .expect("should not fail to create buffer")
+}
+
+/// Creates a disk v2 buffer with the specified compression, or no compression if `None`.
+pub(crate) async fn create_buffer_v2_with_compression<P, R>(
+    data_dir: P,
+    compression: Option<RecordCompression>,
+) -> (
+    BufferWriter<R, FilesystemUnderTest>,
+    BufferReader<R, FilesystemUnderTest>,
+    Arc<Ledger<FilesystemUnderTest>>,
+)
+where
+    P: AsRef<Path>,
+    R: Bufferable,
+{
+    let mut builder = DiskBufferConfigBuilder::from_path(data_dir);
+    if let Some(compression) = compression {
+        builder = builder.compression(compression);
+    }
+    let config = builder.build().expect("creating buffer should not fail");
+    let usage_handle = BufferUsageHandle::noop();
+
+    Buffer::from_config_inner(config, usage_handle)
+        .await
+        .expect("should not fail to create buffer")
+}
+
 pub(crate) fn get_corrected_max_record_size<T>(payload: &T) -> usize
 where
     T: FixedEncodable,
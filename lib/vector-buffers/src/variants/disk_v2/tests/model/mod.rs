@@ -542,6 +542,8 @@ impl WriterModel {
             ledger.config().write_buffer_size,
             ledger.config().max_data_file_size,
             ledger.config().max_record_size,
+            None,
+            None,
         );
 
         let mut writer = Self {
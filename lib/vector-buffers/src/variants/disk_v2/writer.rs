@@ -21,10 +21,12 @@ use rkyv::{
     AlignedVec, Infallible,
 };
 use snafu::{ResultExt, Snafu};
-use tokio::io::{AsyncWrite, AsyncWriteExt};
+use tokio::io::{AsyncReadExt, AsyncWrite, AsyncWriteExt};
 
 use super::{
-    common::{create_crc32c_hasher, DiskBufferConfig},
+    common::{create_crc32c_hasher, DiskBufferConfig, MAX_FILE_ID},
+    compression::RecordCompression,
+    encryption::{RecordCipher, ENCRYPTED_RECORD_FLAG, ENCRYPTION_OVERHEAD},
     io::Filesystem,
     ledger::Ledger,
     record::{validate_record_archive, Record, RecordStatus},
@@ -33,7 +35,7 @@ use crate::{
     encoding::{AsMetadata, Encodable},
     variants::disk_v2::{
         io::AsyncFile,
-        reader::decode_record_payload,
+        reader::{decode_record_payload, ReaderError},
         record::{try_as_record_archive, RECORD_HEADER_LEN},
     },
     Bufferable,
@@ -207,6 +209,8 @@ where
 #[derive(Debug)]
 pub(super) struct WriteToken {
     event_count: usize,
+    encoded_len: usize,
+    payload_len: usize,
     serialized_len: usize,
 }
 
@@ -215,6 +219,16 @@ impl WriteToken {
         self.event_count
     }
 
+    /// Length of the encoded record, before compression.
+    pub fn encoded_len(&self) -> usize {
+        self.encoded_len
+    }
+
+    /// Length of the encoded record after compression, but before encryption.
+    pub fn payload_len(&self) -> usize {
+        self.payload_len
+    }
+
     pub fn serialized_len(&self) -> usize {
         self.serialized_len
     }
@@ -360,9 +374,13 @@ impl<W: fmt::Debug> fmt::Debug for TrackingBufWriter<W> {
 pub(super) struct RecordWriter<W, T> {
     writer: TrackingBufWriter<W>,
     encode_buf: Vec<u8>,
+    compress_buf: Vec<u8>,
+    encrypt_buf: Vec<u8>,
     ser_buf: AlignedVec,
     ser_scratch: AlignedVec,
     checksummer: Hasher,
+    cipher: Option<RecordCipher>,
+    compression: Option<RecordCompression>,
     max_record_size: usize,
     current_data_file_size: u64,
     max_data_file_size: u64,
@@ -378,12 +396,17 @@ where
     ///
     /// Internally, the writer is wrapped in a [`BufWriter`], so callers should not pass in an
     /// already buffered writer.
+    ///
+    /// If `cipher` is given, record payloads are encrypted with it before being written.  If
+    /// `compression` is given, record payloads are compressed with it before being encrypted.
     pub fn new(
         writer: W,
         current_data_file_size: u64,
         write_buffer_size: usize,
         max_data_file_size: u64,
         max_record_size: usize,
+        cipher: Option<RecordCipher>,
+        compression: Option<RecordCompression>,
     ) -> Self {
         // These should also be getting checked at a higher level, but we're double-checking them here to be absolutely sure.
         let max_record_size_converted = u64::try_from(max_record_size)
@@ -405,14 +428,21 @@ where
         // This could lead to us reducing the encode buffer size limit by slightly more than necessary, since
         // `RECORD_HEADER_LEN` might be overaligned compared to what it would be necessary when we look at the
         // encoded/serialized record... but that's OK, but it's only going to differ by 8 bytes at most.
-        let max_record_size = max_record_size - RECORD_HEADER_LEN;
+        //
+        // Likewise, encrypting the payload adds a fixed amount of overhead, which we need to leave room for.
+        let encryption_overhead = cipher.as_ref().map_or(0, |_| ENCRYPTION_OVERHEAD);
+        let max_record_size = max_record_size - RECORD_HEADER_LEN - encryption_overhead;
 
         Self {
             writer: TrackingBufWriter::with_capacity(write_buffer_size, writer),
             encode_buf: Vec::with_capacity(16_384),
+            compress_buf: Vec::new(),
+            encrypt_buf: Vec::new(),
             ser_buf: AlignedVec::with_capacity(16_384),
             ser_scratch: AlignedVec::with_capacity(16_384),
             checksummer: create_crc32c_hasher(),
+            cipher,
+            compression,
             max_record_size,
             current_data_file_size,
             max_data_file_size,
@@ -480,9 +510,39 @@ where
             });
         }
 
-        let metadata = T::get_metadata().into_u32();
-        let wrapped_record =
-            Record::with_checksum(id, metadata, &self.encode_buf, &self.checksummer);
+        // If compression is enabled, compress the encoded record and flag the record as such, unless
+        // compressing it wouldn't actually make it any smaller, in which case we store it as-is.
+        let mut metadata = T::get_metadata().into_u32();
+        let mut payload = &self.encode_buf[..];
+        if let Some(compression) = self.compression {
+            let compressed = compression
+                .compress(payload, &mut self.compress_buf)
+                .map_err(|e| WriterError::FailedToSerialize {
+                    reason: e.to_string(),
+                })?;
+            if compressed {
+                metadata |= compression.flag();
+                payload = &self.compress_buf[..];
+            }
+        }
+        let payload_len = payload.len();
+
+        // If encryption is enabled, encrypt the encoded record and flag the record as such.  The
+        // checksum is calculated over the encrypted payload, so that corruption can still be
+        // detected without needing to decrypt the record first.
+        let payload = match &self.cipher {
+            Some(cipher) => {
+                metadata |= ENCRYPTED_RECORD_FLAG;
+                cipher
+                    .encrypt(id, metadata, payload, &mut self.encrypt_buf)
+                    .map_err(|e| WriterError::FailedToSerialize {
+                        reason: e.to_string(),
+                    })?;
+                &self.encrypt_buf[..]
+            }
+            None => payload,
+        };
+        let wrapped_record = Record::with_checksum(id, metadata, payload, &self.checksummer);
 
         // Push 8 dummy bytes where our length delimiter will sit.  We'll fix this up after
         // serialization.  Notably, `AlignedSerializer` will report the serializer position as
@@ -559,6 +619,8 @@ where
 
         Ok(WriteToken {
             event_count,
+            encoded_len,
+            payload_len,
             serialized_len,
         })
     }
@@ -666,14 +728,7 @@ where
         })?;
 
         // Now we can actually decode it as `T`.
-        let record_metadata = T::Metadata::from_u32(wrapped_record.metadata()).ok_or(
-            WriterError::InconsistentState {
-                reason: "failed to decode record metadata immediately after encoding it"
-                    .to_string(),
-            },
-        )?;
-
-        T::decode(record_metadata, wrapped_record.payload()).map_err(|_| {
+        decode_record_payload(wrapped_record, self.cipher.as_ref()).map_err(|_| {
             WriterError::InconsistentState {
                 reason: "failed to decode record immediately after encoding it".to_string(),
             }
@@ -882,11 +937,28 @@ where
                 // next writer record ID should be.
                 let record = try_as_record_archive(data_file_mmap.as_ref())
                     .expect("record was already validated");
-                let item = decode_record_payload::<T>(record).map_err(|e| {
-                    WriterError::FailedToValidate {
-                        reason: e.to_string(),
+                let cipher = self.config.encryption.as_ref();
+                let item = match decode_record_payload::<T>(record, cipher) {
+                    Ok(item) => item,
+                    // The key that the last record was encrypted with is no longer configured.  We
+                    // already checked that no unread records need it when loading the ledger, so
+                    // the record has been read, and we can simply start over in the next data file.
+                    Err(ReaderError::MissingEncryptionKey { version }) => {
+                        debug!(
+                            key_version = version,
+                            "Last written record was encrypted with a key that is no longer configured."
+                        );
+                        self.reset();
+                        self.mark_for_skip();
+                        self.ready_to_write = true;
+                        return Ok(());
                     }
-                })?;
+                    Err(e) => {
+                        return Err(WriterError::FailedToValidate {
+                            reason: e.to_string(),
+                        })
+                    }
+                };
 
                 // Since we have a valid record, checksum and all, see if the writer record ID
                 // in the ledger lines up with the record ID we have here.  Specifically, the record
@@ -1126,6 +1198,8 @@ where
                     self.config.write_buffer_size,
                     self.config.max_data_file_size,
                     self.config.max_record_size,
+                    self.config.encryption.clone(),
+                    self.config.compression,
                 ));
                 self.data_file_size = data_file_size;
 
@@ -1233,6 +1307,7 @@ where
         //
         // Otherwise, we proceed with flushing like we normally would.
         let can_write_record = self.can_write_record(token.serialized_len());
+        let (encoded_len, payload_len) = (token.encoded_len(), token.payload_len());
         let writer = self
             .writer
             .as_mut()
@@ -1257,6 +1332,10 @@ where
         // setting the ledger state to a record ID that we may never have actually written, which
         // could lead to record ID gaps.
         self.track_write(record_events.get(), bytes_written as u64);
+        if self.config.compression.is_some() {
+            self.ledger
+                .track_compression(encoded_len as u64, payload_len as u64);
+        }
 
         // If we did flush some buffered writes during this write, however, we now compensate for
         // that after updating our internal state.  We'll also notify the reader, too, since the
@@ -1300,6 +1379,159 @@ where
         }
     }
 
+    /// Writes a record, evicting the oldest data files in the buffer to make space if necessary.
+    ///
+    /// Only data files that the reader has not yet started reading can be evicted.  If the buffer
+    /// is full, and no data files can be evicted, the record is dropped and returned instead.
+    /// Otherwise, `None` is returned.
+    ///
+    /// Events in evicted data files, as well as dropped records, are tracked as intentionally
+    /// dropped.
+    ///
+    /// # Errors
+    ///
+    /// If an error occurred while writing the record, or evicting a data file, an error variant
+    /// will be returned describing the error.
+    #[instrument(skip_all, level = "debug")]
+    pub async fn write_record_evicting_oldest(
+        &mut self,
+        mut record: T,
+    ) -> Result<Option<T>, WriterError<T>> {
+        loop {
+            match self.try_write_record_inner(record).await? {
+                Ok(_) => return Ok(None),
+                Err(old_record) => {
+                    record = old_record;
+                    if !self.evict_oldest_data_file().await.context(IoSnafu)? {
+                        let record_events = u64::try_from(record.event_count())
+                            .expect("event count should never exceed u64");
+                        let record_size = u64::try_from(record.size_of())
+                            .expect("Vector only supports 64-bit architectures.");
+                        self.ledger.track_dropped_record(record_events, record_size);
+                        return Ok(Some(record));
+                    }
+                }
+            }
+        }
+    }
+
+    /// Evicts the oldest data file that the reader has not yet started reading.
+    ///
+    /// Returns `true` if a data file was evicted, or `false` if there were no data files eligible
+    /// for eviction: the reader and writer are on the same data file, or the data file after the
+    /// reader's is the one being written to.
+    #[instrument(skip(self), level = "debug")]
+    async fn evict_oldest_data_file(&mut self) -> io::Result<bool> {
+        // Make sure all of our buffered writes are on disk, as we may need to figure out where the
+        // records in the current writer data file start.
+        self.flush_inner(false).await?;
+        self.flush_write_state();
+
+        // Hold the data file lock while evicting so that the reader can't open the data file we're
+        // evicting in the meantime.
+        let _data_file_lock = self.ledger.lock_data_files().await;
+
+        // Find the oldest data file after the one the reader is on, skipping over any data files
+        // that we've already evicted but that the reader hasn't yet moved past.
+        let (reader_file_id, writer_file_id) = self.ledger.get_current_reader_writer_file_id();
+        if reader_file_id == writer_file_id {
+            return Ok(false);
+        }
+
+        let mut evicted_file_id = reader_file_id;
+        let (data_file_path, data_file_size) = loop {
+            evicted_file_id = (evicted_file_id + 1) % MAX_FILE_ID;
+            if evicted_file_id == writer_file_id {
+                return Ok(false);
+            }
+
+            let data_file_path = self.ledger.get_data_file_path(evicted_file_id);
+            match self
+                .ledger
+                .filesystem()
+                .open_file_readable(&data_file_path)
+                .await
+            {
+                Ok(data_file) => break (data_file_path, data_file.metadata().await?.len()),
+                Err(e) if e.kind() == ErrorKind::NotFound => continue,
+                Err(e) => return Err(e),
+            }
+        };
+
+        // Figure out how many events we're evicting based on the ID of the first record in the data
+        // file, and the ID of the first record after it.  If either can't be determined, we still
+        // evict the data file, but the reader will end up tracking its events as lost instead.
+        let next_file_id = (evicted_file_id + 1) % MAX_FILE_ID;
+        let evicted_first_record_id = self.read_first_record_id(evicted_file_id).await?;
+        let next_first_record_id = if next_file_id == writer_file_id && self.data_file_size == 0 {
+            Some(self.get_next_record_id())
+        } else {
+            self.read_first_record_id(next_file_id).await?
+        };
+        let event_count = match (evicted_first_record_id, next_first_record_id) {
+            (Some(first), Some(next)) => next.wrapping_sub(first),
+            _ => 0,
+        };
+
+        self.ledger
+            .filesystem()
+            .delete_file(&data_file_path)
+            .await?;
+        self.ledger
+            .track_evicted_data_file(event_count, data_file_size);
+
+        debug!(
+            data_file_path = data_file_path.to_string_lossy().as_ref(),
+            data_file_size, event_count, "Evicted oldest data file to make space for new records."
+        );
+
+        Ok(true)
+    }
+
+    /// Reads the ID of the first record in the given data file.
+    ///
+    /// Returns `None` if the data file does not exist, or if its first record is not valid.
+    async fn read_first_record_id(&self, file_id: u16) -> io::Result<Option<u64>> {
+        let data_file_path = self.ledger.get_data_file_path(file_id);
+        let mut data_file = match self
+            .ledger
+            .filesystem()
+            .open_file_readable(&data_file_path)
+            .await
+        {
+            Ok(data_file) => data_file,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e),
+        };
+
+        let mut length_buf = [0; 8];
+        match data_file.read_exact(&mut length_buf).await {
+            Ok(_) => {}
+            Err(e) if e.kind() == ErrorKind::UnexpectedEof => return Ok(None),
+            Err(e) => return Err(e),
+        }
+
+        let Ok(record_len) = usize::try_from(u64::from_be_bytes(length_buf)) else {
+            return Ok(None);
+        };
+        if record_len == 0 || record_len > self.config.max_record_size {
+            return Ok(None);
+        }
+
+        let mut record_buf = AlignedVec::with_capacity(record_len);
+        record_buf.resize(record_len, 0);
+        match data_file.read_exact(record_buf.as_mut_slice()).await {
+            Ok(_) => {}
+            Err(e) if e.kind() == ErrorKind::UnexpectedEof => return Ok(None),
+            Err(e) => return Err(e),
+        }
+
+        match validate_record_archive(record_buf.as_slice(), &Hasher::new()) {
+            RecordStatus::Valid { id, .. } => Ok(Some(id)),
+            _ => Ok(None),
+        }
+    }
+
     #[instrument(skip(self), level = "debug")]
     async fn flush_inner(&mut self, force_full_flush: bool) -> io::Result<()> {
         // We always flush the `BufWriter` when this is called, but we don't always flush to disk or
@@ -3,7 +3,15 @@
 //! as it flows through transforms, being duplicated and merged, and
 //! then report its status when the last copy is delivered or dropped.
 
-use std::{cmp, future::Future, mem, pin::Pin, sync::Arc, task::Poll};
+use std::{
+    cmp,
+    future::Future,
+    hash::{Hash, Hasher},
+    mem,
+    pin::Pin,
+    sync::{Arc, Weak},
+    task::Poll,
+};
 
 use crossbeam_utils::atomic::AtomicCell;
 use futures::future::FutureExt;
@@ -93,6 +101,11 @@ impl EventFinalizers {
             finalizer.update_batch();
         }
     }
+
+    /// Returns the identifiers of the batches the event finalizers in the collection belong to.
+    pub fn batch_ids(&self) -> impl Iterator<Item = BatchId> + '_ {
+        self.0.iter().map(|finalizer| finalizer.batch.id())
+    }
 }
 
 impl Finalizable for EventFinalizers {
@@ -239,6 +252,12 @@ impl BatchNotifier {
         enabled.then(|| Self::apply_to(items))
     }
 
+    /// Returns an identifier for this batch, shared by all clones of the notifier.
+    #[must_use]
+    pub fn id(&self) -> BatchId {
+        BatchId(Arc::downgrade(&self.0))
+    }
+
     /// Updates the status of the notifier.
     fn update_status(&self, status: EventStatus) {
         // The status starts as Delivered and can only change if the new
@@ -252,6 +271,28 @@ impl BatchNotifier {
     }
 }
 
+/// An identifier for a batch, as created by [`BatchNotifier::id`].
+///
+/// Two identifiers are equal if they were created from clones of the same batch notifier. The
+/// identifier does not keep the batch alive, and so it can be held after the batch was finalized
+/// without affecting its status.
+#[derive(Clone, Debug)]
+pub struct BatchId(Weak<OwnedBatchNotifier>);
+
+impl PartialEq for BatchId {
+    fn eq(&self, other: &Self) -> bool {
+        Weak::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for BatchId {}
+
+impl Hash for BatchId {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.as_ptr().hash(state);
+    }
+}
+
 /// The non-shared data underlying the shared `BatchNotifier`
 #[derive(Debug)]
 pub struct OwnedBatchNotifier {
@@ -507,6 +548,26 @@ mod tests {
         assert_eq!(receiver.try_recv(), Ok(BatchStatus::Delivered));
     }
 
+    #[test]
+    fn batch_ids() {
+        let (batch1, _receiver1) = BatchNotifier::new_with_receiver();
+        let (batch2, _receiver2) = BatchNotifier::new_with_receiver();
+        let mut event1 = EventFinalizers::new(EventFinalizer::new(batch1.clone()));
+        let event2 = EventFinalizers::new(EventFinalizer::new(batch1.clone()));
+        let event3 = EventFinalizers::new(EventFinalizer::new(batch2.clone()));
+
+        assert_eq!(batch1.id(), batch1.clone().id());
+        assert_ne!(batch1.id(), batch2.id());
+        assert_eq!(event1.batch_ids().collect::<Vec<_>>(), vec![batch1.id()]);
+        assert_eq!(event2.batch_ids().collect::<Vec<_>>(), vec![batch1.id()]);
+
+        event1.merge(event3);
+        assert_eq!(
+            event1.batch_ids().collect::<Vec<_>>(),
+            vec![batch1.id(), batch2.id()]
+        );
+    }
+
     fn make_finalizer() -> (EventFinalizers, BatchStatusReceiver) {
         let (batch, receiver) = BatchNotifier::new_with_receiver();
         let finalizer = EventFinalizers::new(EventFinalizer::new(batch));
@@ -34,6 +34,17 @@ impl Discriminant {
             .collect();
         Self { values }
     }
+
+    /// Create a new Discriminant from the values of its fields, as returned by
+    /// [`Discriminant::values`].
+    pub fn from_values(values: Vec<Option<Value>>) -> Self {
+        Self { values }
+    }
+
+    /// The values of the fields included into the discriminant, in order.
+    pub fn values(&self) -> &[Option<Value>] {
+        &self.values
+    }
 }
 
 impl PartialEq for Discriminant {
@@ -35,6 +35,7 @@ pub mod partition;
 pub mod schema;
 pub mod serde;
 pub mod sink;
+pub mod sink_errors;
 pub mod source;
 pub mod tcp;
 #[cfg(test)]
@@ -0,0 +1,586 @@
+//! Support for the `errors` output of sinks.
+//!
+//! Sinks normally drop events that they fail to deliver, after recording the failure in their
+//! internal metrics. When the `errors` output of a sink is consumed by another component, the
+//! topology wraps the sink's input with an [`ErrorsOutput`], which keeps a copy of each event
+//! until the sink has finalized it. Events that end up being rejected, or that errored, are
+//! annotated with the error that caused them to be dropped and sent to the `errors` output
+//! instead, along with the original event finalizers, so that acknowledgements are only sent
+//! to the source once the events have been delivered there.
+//!
+//! The copies share their data with the events handed to the sink until either is modified, and
+//! the number of copies kept at once is bounded. Once the bound is reached, events are handed to
+//! the sink without being captured, and are dropped as usual if the sink fails to deliver them.
+//!
+//! The error details are recorded by the generic sink machinery, such as the request driver and
+//! retry policy, which access the handle through a task-local set while the sink is running.
+
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    future::Future,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+};
+
+use futures::{stream::FuturesUnordered, StreamExt};
+use lookup::{metadata_path, path, PathPrefix};
+use parking_lot::Mutex;
+use tokio::sync::mpsc;
+use vector_common::{
+    finalization::{
+        BatchId, BatchNotifier, BatchStatus, BatchStatusReceiver, EventFinalizer, EventFinalizers,
+        EventStatus,
+    },
+    internal_event::{emit, ComponentEventsDropped, InternalEvent, UNINTENTIONAL},
+};
+use vrl::value::Value;
+
+use crate::{
+    config::{log_schema, ComponentKey, LogNamespace, OutputId},
+    event::{Event, EventArray, EventMutRef},
+    fanout::Fanout,
+};
+
+/// The name of the output port that sinks send rejected events to.
+pub const ERRORS_OUTPUT: &str = "errors";
+
+/// The maximum number of captured events that can be waiting to be finalized by a sink at once.
+const MAX_CAPTURED_EVENTS: usize = 100_000;
+
+/// The maximum number of captures, and of events dropped by a sink before being sent, that can
+/// be queued for the pump.
+const QUEUE_SIZE: usize = 1_024;
+
+tokio::task_local! {
+    static CURRENT_ERRORS_OUTPUT: ErrorsOutput;
+
+    static REQUEST_ERROR: RefCell<Option<SinkError>>;
+}
+
+/// The error that caused a sink to drop an event.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SinkError {
+    message: String,
+    status_code: Option<u16>,
+}
+
+impl SinkError {
+    /// Creates a new `SinkError` with the given message.
+    pub fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            status_code: None,
+        }
+    }
+
+    /// Sets the status code returned by the downstream service, such as an HTTP status code.
+    #[must_use]
+    pub fn with_status_code(mut self, status_code: Option<u16>) -> Self {
+        self.status_code = status_code;
+        self
+    }
+
+    /// Gets the message describing the error.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Gets the status code returned by the downstream service, if any.
+    pub fn status_code(&self) -> Option<u16> {
+        self.status_code
+    }
+}
+
+/// Records the error that caused the current request to fail.
+///
+/// This is called by the components processing a request on behalf of a sink, such as its retry
+/// policy, and is picked up by the request driver if the request ends up failing. If the request
+/// isn't being run by a driver that captures the error, this does nothing.
+pub fn record_request_error(error: SinkError) {
+    _ = REQUEST_ERROR.try_with(|slot| *slot.borrow_mut() = Some(error));
+}
+
+/// Runs the given request future, returning its output along with the last error recorded with
+/// [`record_request_error`] while it was running.
+pub async fn capture_request_error<F: Future>(fut: F) -> (F::Output, Option<SinkError>) {
+    REQUEST_ERROR
+        .scope(RefCell::new(None), async move {
+            let output = fut.await;
+            let error = REQUEST_ERROR.with(|slot| slot.borrow_mut().take());
+            (output, error)
+        })
+        .await
+}
+
+/// An event that is being processed by the sink, and which is sent to the `errors` output if the
+/// sink fails to deliver it.
+struct Pending {
+    id: BatchId,
+    receiver: BatchStatusReceiver,
+    event: Event,
+    finalizers: EventFinalizers,
+}
+
+enum Captured {
+    /// The events of an array handed to the sink.
+    Pending(Vec<Pending>),
+
+    /// Events that were dropped by the sink before being sent.
+    Rejected {
+        events: Vec<Event>,
+        error: SinkError,
+    },
+}
+
+struct Shared {
+    component_key: ComponentKey,
+    component_type: &'static str,
+    pending: Mutex<HashMap<BatchId, Option<SinkError>>>,
+
+    /// The number of captured events that haven't been finalized yet.
+    captured: AtomicUsize,
+}
+
+/// A handle for sending the events rejected by a sink to its `errors` output.
+#[derive(Clone)]
+pub struct ErrorsOutput {
+    shared: Arc<Shared>,
+    tx: mpsc::Sender<Captured>,
+}
+
+impl ErrorsOutput {
+    /// Creates a new `ErrorsOutput` for the given sink, along with the pump that forwards the
+    /// rejected events to the output.
+    pub fn new(component_key: ComponentKey, component_type: &'static str) -> (Self, ErrorsPump) {
+        let (tx, rx) = mpsc::channel(QUEUE_SIZE);
+        let shared = Arc::new(Shared {
+            component_key: component_key.clone(),
+            component_type,
+            pending: Mutex::new(HashMap::new()),
+            captured: AtomicUsize::new(0),
+        });
+        let pump = ErrorsPump {
+            output_id: Arc::new(OutputId {
+                component: component_key,
+                port: Some(ERRORS_OUTPUT.to_string()),
+            }),
+            shared: Arc::clone(&shared),
+            rx,
+        };
+        (Self { shared, tx }, pump)
+    }
+
+    /// Gets the `ErrorsOutput` of the sink running in the current task, if its `errors` output is
+    /// being used.
+    pub fn current() -> Option<Self> {
+        CURRENT_ERRORS_OUTPUT.try_with(Clone::clone).ok()
+    }
+
+    /// Runs the given sink future with this handle set as the current `ErrorsOutput`.
+    pub async fn scope<F: Future>(self, fut: F) -> F::Output {
+        CURRENT_ERRORS_OUTPUT.scope(self, fut).await
+    }
+
+    /// Runs the given future with the given handle, if any, set as the current `ErrorsOutput`.
+    ///
+    /// This is used to carry the handle over to futures spawned by the sink.
+    pub async fn maybe_scope<F: Future>(errors: Option<Self>, fut: F) -> F::Output {
+        match errors {
+            Some(errors) => errors.scope(fut).await,
+            None => fut.await,
+        }
+    }
+
+    /// Captures the given events before they are handed to the sink.
+    ///
+    /// A copy of each event is kept, and the finalizers of the event are replaced by one that
+    /// reports back to this handle, so that the copy can be sent to the `errors` output if the sink
+    /// fails to deliver the event.
+    ///
+    /// If too many events are already captured, the events are returned as is instead.
+    pub fn capture(&self, mut events: EventArray) -> EventArray {
+        let count = events.len();
+        let captured = self.shared.captured.fetch_add(count, Ordering::AcqRel);
+        let permit = if captured + count <= MAX_CAPTURED_EVENTS {
+            self.tx.try_reserve().ok()
+        } else {
+            None
+        };
+        let Some(permit) = permit else {
+            self.shared.captured.fetch_sub(count, Ordering::AcqRel);
+            emit(ErrorsOutputFull { count });
+            return events;
+        };
+
+        let mut pending = Vec::with_capacity(count);
+        for mut event in events.iter_events_mut() {
+            let (batch, receiver) = BatchNotifier::new_with_receiver();
+            let id = batch.id();
+
+            let finalizers = event.metadata_mut().take_finalizers();
+            let copy = match &event {
+                EventMutRef::Log(log) => Event::Log((**log).clone()),
+                EventMutRef::Metric(metric) => Event::Metric((**metric).clone()),
+                EventMutRef::Trace(trace) => Event::Trace((**trace).clone()),
+            };
+            event
+                .metadata_mut()
+                .add_finalizer(EventFinalizer::new(batch));
+
+            pending.push(Pending {
+                id,
+                receiver,
+                event: copy,
+                finalizers,
+            });
+        }
+
+        self.shared
+            .pending
+            .lock()
+            .extend(pending.iter().map(|pending| (pending.id.clone(), None)));
+        permit.send(Captured::Pending(pending));
+        events
+    }
+
+    /// Records the error that caused the sink to fail to deliver the events with the given
+    /// finalizers.
+    ///
+    /// This must be called before the finalizers are updated with the failed status.
+    pub fn reject(&self, finalizers: &EventFinalizers, error: &SinkError) {
+        let mut pending = self.shared.pending.lock();
+        for id in finalizers.batch_ids() {
+            if let Some(slot) = pending.get_mut(&id) {
+                *slot = Some(error.clone());
+            }
+        }
+    }
+
+    /// Sends events that the sink dropped before sending them, such as events that failed to be
+    /// encoded, directly to the `errors` output.
+    ///
+    /// The events should not hold any finalizers, as the finalizers taken from the events by the
+    /// sink are still expected to be updated by it.
+    pub fn send_rejected(&self, events: impl IntoIterator<Item = Event>, error: &SinkError) {
+        let events = events.into_iter().collect::<Vec<_>>();
+        if events.is_empty() {
+            return;
+        }
+
+        let count = events.len();
+        let rejected = Captured::Rejected {
+            events,
+            error: error.clone(),
+        };
+        if self.tx.try_send(rejected).is_err() {
+            emit(ComponentEventsDropped::<UNINTENTIONAL> {
+                count,
+                reason: "The errors output is full.",
+            });
+        }
+    }
+}
+
+impl Shared {
+    fn annotate(&self, event: &mut Event, reason: &str, error: &SinkError) {
+        let data = || -> Value {
+            serde_json::json!({
+                "reason": reason,
+                "message": error.message,
+                "status_code": error.status_code,
+                "component_id": self.component_key,
+                "component_type": self.component_type,
+                "component_kind": "sink",
+            })
+            .into()
+        };
+
+        match event {
+            Event::Log(log) => match log.namespace() {
+                LogNamespace::Legacy => {
+                    if let Some(metadata_key) = log_schema().metadata_key() {
+                        log.insert(
+                            (PathPrefix::Event, metadata_key.concat(path!("error"))),
+                            data(),
+                        );
+                    }
+                }
+                LogNamespace::Vector => {
+                    log.insert(metadata_path!("vector", "error"), data());
+                }
+            },
+            Event::Metric(metric) => {
+                if let Some(metadata_key) = log_schema().metadata_key() {
+                    let mut tags = vec![
+                        ("reason", reason.to_string()),
+                        ("message", error.message.clone()),
+                        ("component_id", self.component_key.to_string()),
+                        ("component_type", self.component_type.to_string()),
+                        ("component_kind", "sink".to_string()),
+                    ];
+                    if let Some(status_code) = error.status_code {
+                        tags.push(("status_code", status_code.to_string()));
+                    }
+                    for (name, value) in tags {
+                        metric.replace_tag(format!("{metadata_key}.error.{name}"), value);
+                    }
+                }
+            }
+            Event::Trace(trace) => {
+                trace.maybe_insert(log_schema().metadata_key_target_path(), data);
+            }
+        }
+    }
+}
+
+/// Emitted when events are handed to a sink without being captured, because too many events are
+/// already captured.
+struct ErrorsOutputFull {
+    count: usize,
+}
+
+impl InternalEvent for ErrorsOutputFull {
+    fn emit(self) {
+        warn!(
+            message = "Too many events are awaiting delivery, skipping the `errors` output.",
+            count = self.count,
+            internal_log_rate_limit = true,
+        );
+        metrics::counter!(
+            "component_errors_output_uncaptured_events_total",
+            self.count as u64
+        );
+    }
+
+    fn name(&self) -> Option<&'static str> {
+        Some("ErrorsOutputFull")
+    }
+}
+
+/// Forwards the events rejected by a sink to its `errors` output.
+pub struct ErrorsPump {
+    output_id: Arc<OutputId>,
+    shared: Arc<Shared>,
+    rx: mpsc::Receiver<Captured>,
+}
+
+impl ErrorsPump {
+    /// Runs the pump, sending rejected events to the given fanout.
+    ///
+    /// The pump runs until every [`ErrorsOutput`] handle has been dropped and all of the captured
+    /// events have been finalized.
+    ///
+    /// # Errors
+    ///
+    /// If sending to the fanout fails, an error is returned.
+    pub async fn run(mut self, mut fanout: Fanout) -> crate::Result<()> {
+        let mut in_flight = FuturesUnordered::new();
+
+        loop {
+            tokio::select! {
+                captured = self.rx.recv() => match captured {
+                    Some(Captured::Pending(pending)) => {
+                        in_flight.extend(pending.into_iter().map(|pending| async move {
+                            let status = pending.receiver.await;
+                            (pending.id, status, pending.event, pending.finalizers)
+                        }));
+                    }
+                    Some(Captured::Rejected { events, error }) => {
+                        for event in events {
+                            self.send(&mut fanout, event, "rejected", &error).await?;
+                        }
+                    }
+                    None if in_flight.is_empty() => break,
+                    None => {
+                        while let Some((id, status, event, finalizers)) = in_flight.next().await {
+                            self.finalize(&mut fanout, id, status, event, finalizers).await?;
+                        }
+                        break;
+                    }
+                },
+                Some((id, status, event, finalizers)) = in_flight.next(), if !in_flight.is_empty() => {
+                    self.finalize(&mut fanout, id, status, event, finalizers).await?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn finalize(
+        &self,
+        fanout: &mut Fanout,
+        id: BatchId,
+        status: BatchStatus,
+        mut event: Event,
+        finalizers: EventFinalizers,
+    ) -> crate::Result<()> {
+        let error = self.shared.pending.lock().remove(&id).flatten();
+        self.shared.captured.fetch_sub(1, Ordering::AcqRel);
+
+        let reason = match status {
+            BatchStatus::Delivered => {
+                finalizers.update_status(EventStatus::Delivered);
+                return Ok(());
+            }
+            BatchStatus::Errored => "errored",
+            BatchStatus::Rejected => "rejected",
+        };
+        let error = error.unwrap_or_else(|| SinkError::new("Event was not delivered by the sink."));
+
+        event.metadata_mut().merge_finalizers(finalizers);
+        self.send(fanout, event, reason, &error).await
+    }
+
+    async fn send(
+        &self,
+        fanout: &mut Fanout,
+        mut event: Event,
+        reason: &str,
+        error: &SinkError,
+    ) -> crate::Result<()> {
+        self.shared.annotate(&mut event, reason, error);
+        event.set_upstream_id(Arc::clone(&self.output_id));
+        fanout.send(event.into(), None).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroUsize;
+
+    use tokio::sync::oneshot::error::TryRecvError;
+    use tracing::Span;
+    use vector_buffers::{
+        topology::{builder::TopologyBuilder, channel::BufferReceiver},
+        WhenFull,
+    };
+
+    use super::*;
+    use crate::event::{EventContainer, Finalizable, LogEvent};
+
+    async fn run_pump(pump: ErrorsPump) -> BufferReceiver<EventArray> {
+        let (tx, rx) = TopologyBuilder::standalone_memory(
+            NonZeroUsize::new(100).unwrap(),
+            WhenFull::Block,
+            &Span::current(),
+        )
+        .await;
+        let (mut fanout, _control) = Fanout::new();
+        fanout.add(ComponentKey::from("dead_letters"), tx);
+        pump.run(fanout).await.expect("pump should not fail");
+        rx
+    }
+
+    #[tokio::test]
+    async fn rejected_events_are_sent_with_original_finalizers() {
+        let (batch, mut source_rx) = BatchNotifier::new_with_receiver();
+        let event = Event::from(LogEvent::from("hello")).with_batch_notifier(&batch);
+        drop(batch);
+
+        let (errors, pump) = ErrorsOutput::new(ComponentKey::from("out"), "http");
+        let mut captured = errors.capture(event.into());
+        let finalizers = captured.take_finalizers();
+        drop(captured);
+
+        errors.reject(
+            &finalizers,
+            &SinkError::new("Bad request.").with_status_code(Some(400)),
+        );
+        finalizers.update_status(EventStatus::Rejected);
+        drop(finalizers);
+        drop(errors);
+
+        let mut rx = run_pump(pump).await;
+        let events = rx.next().await.expect("should receive rejected event");
+        assert_eq!(events.len(), 1);
+
+        let event = events.into_events().next().unwrap().into_log();
+        assert_eq!(event.get("message"), Some(&Value::from("hello")));
+        assert_eq!(
+            event.get("metadata.error.reason"),
+            Some(&Value::from("rejected"))
+        );
+        assert_eq!(
+            event.get("metadata.error.message"),
+            Some(&Value::from("Bad request."))
+        );
+        assert_eq!(
+            event.get("metadata.error.status_code"),
+            Some(&Value::from(400))
+        );
+        assert_eq!(
+            event.get("metadata.error.component_id"),
+            Some(&Value::from("out"))
+        );
+        assert_eq!(
+            event.get("metadata.error.component_kind"),
+            Some(&Value::from("sink"))
+        );
+
+        // The source is only notified once the rejected event is finalized downstream.
+        assert_eq!(source_rx.try_recv(), Err(TryRecvError::Empty));
+        drop(event);
+        assert_eq!(source_rx.try_recv(), Ok(BatchStatus::Delivered));
+    }
+
+    #[tokio::test]
+    async fn delivered_events_are_not_sent() {
+        let (batch, mut source_rx) = BatchNotifier::new_with_receiver();
+        let event = Event::from(LogEvent::from("hello")).with_batch_notifier(&batch);
+        drop(batch);
+
+        let (errors, pump) = ErrorsOutput::new(ComponentKey::from("out"), "http");
+        let mut captured = errors.capture(event.into());
+        let finalizers = captured.take_finalizers();
+        drop(captured);
+        finalizers.update_status(EventStatus::Delivered);
+        drop(finalizers);
+        drop(errors);
+
+        let mut rx = run_pump(pump).await;
+        assert!(rx.next().await.is_none());
+        assert_eq!(source_rx.try_recv(), Ok(BatchStatus::Delivered));
+    }
+
+    #[tokio::test]
+    async fn events_are_not_captured_when_full() {
+        let (errors, _pump) = ErrorsOutput::new(ComponentKey::from("out"), "http");
+        let captured = (0..QUEUE_SIZE)
+            .map(|_| errors.capture(Event::from(LogEvent::from("hello")).into()))
+            .collect::<Vec<_>>();
+
+        let (batch, mut source_rx) = BatchNotifier::new_with_receiver();
+        let event = Event::from(LogEvent::from("hello")).with_batch_notifier(&batch);
+        drop(batch);
+
+        // The event keeps its original finalizers, so the source is notified as soon as the sink
+        // finalizes it, without waiting for the pump.
+        let mut uncaptured = errors.capture(event.into());
+        let finalizers = uncaptured.take_finalizers();
+        drop(uncaptured);
+        finalizers.update_status(EventStatus::Rejected);
+        drop(finalizers);
+        assert_eq!(source_rx.try_recv(), Ok(BatchStatus::Rejected));
+
+        drop(captured);
+    }
+
+    #[tokio::test]
+    async fn captures_request_errors() {
+        let (output, error) = capture_request_error(async {
+            record_request_error(SinkError::new("first"));
+            record_request_error(SinkError::new("second"));
+            1
+        })
+        .await;
+        assert_eq!(output, 1);
+        assert_eq!(error, Some(SinkError::new("second")));
+
+        // Recording an error outside of a request does nothing.
+        record_request_error(SinkError::new("ignored"));
+    }
+}
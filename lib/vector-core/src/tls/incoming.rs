@@ -42,7 +42,11 @@ impl TlsSettings {
 impl MaybeTlsSettings {
     pub async fn bind(&self, addr: &SocketAddr) -> crate::tls::Result<MaybeTlsListener> {
         let listener = TcpListener::bind(addr).await.context(TcpBindSnafu)?;
+        self.wrap_listener(listener)
+    }
 
+    /// Wraps an already bound listener, so that the connections accepted on it use these settings.
+    pub fn wrap_listener(&self, listener: TcpListener) -> crate::tls::Result<MaybeTlsListener> {
         let acceptor = match self {
             Self::Tls(tls) => Some(tls.acceptor()?),
             Self::Raw(()) => None,
@@ -149,6 +149,13 @@ pub trait SyncTransform: Send + dyn_clone::DynClone + Sync {
             self.transform(event, output);
         }
     }
+
+    /// Called once the input has ended and every event has been transformed.
+    ///
+    /// Transforms with state that outlives them, such as state persisted to disk, should save it
+    /// here rather than when they are dropped, as clones of the transform may be dropped at any
+    /// time.
+    fn finish(&mut self, _output: &mut TransformOutputsBuf) {}
 }
 
 dyn_clone::clone_trait_object!(SyncTransform);
@@ -19,8 +19,8 @@ pub use vector_config::impl_generate_config_from_default;
 pub use vector_core::compile_vrl;
 pub use vector_core::{
     buckets, default_data_dir, emit, event, fanout, metric_tags, metrics, partition, quantiles,
-    register, samples, schema, serde, sink, source, tcp, tls, transform, update_counter,
-    EstimatedJsonEncodedSizeOf,
+    register, samples, schema, serde, sink, sink_errors, source, tcp, tls, transform,
+    update_counter, EstimatedJsonEncodedSizeOf,
 };
 pub use vector_lookup as lookup;
 pub use vector_stream as stream;
@@ -37,7 +37,7 @@ pub mod config {
 
 #[cfg(feature = "opentelemetry")]
 pub mod opentelemetry {
-    pub use opentelemetry_proto::{convert, proto};
+    pub use opentelemetry_proto::{convert, export, proto};
 }
 
 #[cfg(feature = "prometheus")]
@@ -10,7 +10,10 @@ use vector_common::internal_event::{
     RegisteredEventCache, SharedString, TaggedEventsSent,
 };
 use vector_common::request_metadata::{GroupedCountByteSize, MetaDescriptive};
-use vector_core::event::{EventFinalizers, EventStatus, Finalizable};
+use vector_core::{
+    event::{EventFinalizers, EventStatus, Finalizable},
+    sink_errors::{capture_request_error, ErrorsOutput, SinkError},
+};
 
 use super::FuturesUnorderedCount;
 
@@ -34,7 +37,8 @@ pub trait DriverResponse {
 /// managing waiting for the service to be ready before processing more items, and so on.
 ///
 /// Additionally, `Driver` handles event finalization, which triggers acknowledgements
-/// to the source or disk buffer.
+/// to the source or disk buffer. If the sink's `errors` output is in use, the error that caused a
+/// request to fail is recorded against its events, so they can be sent to that output.
 ///
 /// This capability is parameterized so any implementation which can define how to interpret the
 /// response for each request, as well as define how many events a request is compromised of, can be
@@ -98,6 +102,7 @@ where
 
         let bytes_sent = protocol.map(|protocol| register(BytesSent { protocol }));
         let events_sent = RegisteredEventCache::new(());
+        let errors = ErrorsOutput::current();
 
         loop {
             // Core behavior of the loop:
@@ -166,16 +171,17 @@ where
                         let bytes_sent = bytes_sent.clone();
                         let events_sent = events_sent.clone();
                         let event_count = req.get_metadata().event_count();
+                        let errors = errors.clone();
 
-                        let fut = svc.call(req)
-                            .err_into()
-                            .map(move |result| Self::handle_response(
+                        let fut = capture_request_error(svc.call(req).err_into())
+                            .map(move |(result, request_error)| Self::handle_response(
                                 result,
                                 request_id,
                                 finalizers,
                                 event_count,
                                 &bytes_sent,
                                 &events_sent,
+                                errors.as_ref().map(|errors| (errors, request_error)),
                             ))
                             .instrument(info_span!("request", request_id).or_current());
 
@@ -202,14 +208,30 @@ where
         event_count: usize,
         bytes_sent: &Option<Registered<BytesSent>>,
         events_sent: &RegisteredEventCache<(), TaggedEventsSent>,
+        errors: Option<(&ErrorsOutput, Option<SinkError>)>,
     ) {
         match result {
             Err(error) => {
+                if let Some((errors, request_error)) = errors {
+                    let error =
+                        request_error.unwrap_or_else(|| SinkError::new(format!("{error:?}")));
+                    errors.reject(&finalizers, &error);
+                }
                 Self::emit_call_error(Some(error), request_id, event_count);
                 finalizers.update_status(EventStatus::Rejected);
             }
             Ok(response) => {
                 trace!(message = "Service call succeeded.", request_id);
+                if let Some((errors, request_error)) = errors {
+                    if matches!(
+                        response.event_status(),
+                        EventStatus::Errored | EventStatus::Rejected
+                    ) {
+                        let error = request_error
+                            .unwrap_or_else(|| SinkError::new("Request was not delivered."));
+                        errors.reject(&finalizers, &error);
+                    }
+                }
                 finalizers.update_status(response.event_status());
                 if response.event_status() == EventStatus::Delivered {
                     if let Some(bytes_sent) = bytes_sent {
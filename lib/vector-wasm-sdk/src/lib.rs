@@ -0,0 +1,363 @@
+//! Guest-side SDK for writing Vector `wasm` transforms in Rust.
+//!
+//! A transform is a type implementing [`Transform`], exported from a `cdylib` crate with
+//! [`export_transform!`] and compiled for the `wasm32-wasi` target:
+//!
+//! ```ignore
+//! use vector_wasm_sdk::{event::EventWrapper, export_transform, Output, Transform};
+//!
+//! #[derive(Default)]
+//! struct Passthrough {
+//!     seen: u64,
+//! }
+//!
+//! impl Transform for Passthrough {
+//!     fn process(&mut self, event: EventWrapper, output: &mut Output) -> Result<(), String> {
+//!         self.seen += 1;
+//!         output.emit(event);
+//!         Ok(())
+//!     }
+//! }
+//!
+//! export_transform!(Passthrough);
+//! ```
+//!
+//! # ABI
+//!
+//! Events cross the module boundary as protobuf-encoded `event.EventWrapper` messages, as defined
+//! in `lib/vector-core/proto/event.proto`. The module exports:
+//!
+//! - `memory`
+//! - `vector_abi_version() -> u32`, returning [`ABI_VERSION`]
+//! - `vector_alloc(len: u32) -> u32`, allocating a buffer the host writes its input into
+//! - `vector_init(ptr: u32, len: u32) -> u32`, receiving the transform's `options` as JSON
+//! - `vector_process(ptr: u32, len: u32) -> u32`, receiving a single event
+//! - `vector_shutdown() -> u32`
+//!
+//! The module takes ownership of the buffers passed to `vector_init` and `vector_process`. Each of
+//! them returns zero on success. Events are sent back by calling the `emit(ptr: u32, len: u32)`
+//! function imported from the `vector` module, and messages are logged through its
+//! `log(level: u32, ptr: u32, len: u32)` function.
+
+#![deny(missing_docs)]
+
+/// Generated protobuf types for Vector events.
+#[allow(warnings, clippy::all, clippy::pedantic)]
+pub mod event {
+    include!(concat!(env!("OUT_DIR"), "/event.rs"));
+}
+
+use event::EventWrapper;
+
+/// The version of the ABI implemented by this SDK.
+///
+/// The host refuses to load modules built against a different version.
+pub const ABI_VERSION: u32 = 1;
+
+/// Status returned by exported functions on success.
+pub const STATUS_OK: u32 = 0;
+
+/// Status returned when the host's input could not be decoded.
+pub const STATUS_DECODE_ERROR: u32 = 1;
+
+/// Status returned when the transform itself returned an error.
+pub const STATUS_TRANSFORM_ERROR: u32 = 2;
+
+/// A transform running inside the `wasm` transform.
+///
+/// A single instance is created when the module is loaded, and lives until the module is reloaded
+/// or Vector shuts down, so any state kept in it is preserved between events.
+pub trait Transform: Default {
+    /// Called once, before the first event, with the transform's `options` encoded as JSON.
+    ///
+    /// `options` is `null` if the transform has no options configured.
+    fn init(&mut self, _options: &str) -> Result<(), String> {
+        Ok(())
+    }
+
+    /// Called for every event.
+    ///
+    /// Events are only forwarded if they are emitted to `output`, so an event can be dropped by
+    /// not emitting anything, or split by emitting several events.
+    fn process(&mut self, event: EventWrapper, output: &mut Output) -> Result<(), String>;
+
+    /// Called once when Vector shuts down, or before the module is reloaded.
+    fn shutdown(&mut self, _output: &mut Output) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+/// The events emitted by a single call to a [`Transform`].
+#[derive(Debug, Default)]
+pub struct Output {
+    events: Vec<EventWrapper>,
+}
+
+impl Output {
+    /// Creates an empty output.
+    pub const fn new() -> Self {
+        Self { events: Vec::new() }
+    }
+
+    /// Emits an event to the transform's output.
+    pub fn emit(&mut self, event: EventWrapper) {
+        self.events.push(event);
+    }
+
+    /// Returns the events emitted so far.
+    pub fn events(&self) -> &[EventWrapper] {
+        &self.events
+    }
+
+    /// Consumes the output, returning the events emitted to it.
+    pub fn into_events(self) -> Vec<EventWrapper> {
+        self.events
+    }
+}
+
+/// Log levels understood by the host.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u32)]
+pub enum Level {
+    /// The `error` level.
+    Error = 0,
+    /// The `warn` level.
+    Warn = 1,
+    /// The `info` level.
+    Info = 2,
+    /// The `debug` level.
+    Debug = 3,
+    /// The `trace` level.
+    Trace = 4,
+}
+
+/// Logs a message through Vector's internal logs.
+///
+/// Outside of WebAssembly, such as in the transform's own tests, the message is written to
+/// standard error instead.
+pub fn log(level: Level, message: &str) {
+    #[cfg(target_arch = "wasm32")]
+    // SAFETY: the host only reads `message.len()` bytes starting at `message.as_ptr()`.
+    unsafe {
+        host::log(level as u32, message.as_ptr(), message.len() as u32);
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    eprintln!("[{:?}] {}", level, message);
+}
+
+#[cfg(target_arch = "wasm32")]
+mod host {
+    #[link(wasm_import_module = "vector")]
+    extern "C" {
+        pub fn emit(ptr: *const u8, len: u32);
+        pub fn log(level: u32, ptr: *const u8, len: u32);
+    }
+}
+
+/// Implementation details of [`export_transform!`].
+#[doc(hidden)]
+pub mod __private {
+    use prost::Message;
+
+    use super::*;
+
+    pub fn alloc(len: u32) -> *mut u8 {
+        Box::into_raw(vec![0u8; len as usize].into_boxed_slice()).cast()
+    }
+
+    /// # Safety
+    ///
+    /// `ptr` and `len` must describe a buffer returned by [`alloc`] that has not been freed yet.
+    unsafe fn take(ptr: *mut u8, len: u32) -> Box<[u8]> {
+        Box::from_raw(std::ptr::slice_from_raw_parts_mut(ptr, len as usize))
+    }
+
+    fn status(result: Result<(), String>, output: Output) -> u32 {
+        #[cfg(target_arch = "wasm32")]
+        for event in output.into_events() {
+            let bytes = event.encode_to_vec();
+            // SAFETY: the host only reads `bytes.len()` bytes starting at `bytes.as_ptr()`.
+            unsafe { host::emit(bytes.as_ptr(), bytes.len() as u32) };
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        drop(output);
+
+        match result {
+            Ok(()) => STATUS_OK,
+            Err(error) => {
+                log(Level::Error, &error);
+                STATUS_TRANSFORM_ERROR
+            }
+        }
+    }
+
+    /// # Safety
+    ///
+    /// See [`take`].
+    pub unsafe fn init<T: Transform>(transform: &mut T, ptr: *mut u8, len: u32) -> u32 {
+        let buffer = take(ptr, len);
+        match std::str::from_utf8(&buffer) {
+            Ok(options) => status(transform.init(options), Output::new()),
+            Err(error) => {
+                log(
+                    Level::Error,
+                    &format!("Options are not valid UTF-8: {}", error),
+                );
+                STATUS_DECODE_ERROR
+            }
+        }
+    }
+
+    /// # Safety
+    ///
+    /// See [`take`].
+    pub unsafe fn process<T: Transform>(transform: &mut T, ptr: *mut u8, len: u32) -> u32 {
+        let buffer = take(ptr, len);
+        match EventWrapper::decode(&buffer[..]) {
+            Ok(event) => {
+                let mut output = Output::new();
+                let result = transform.process(event, &mut output);
+                status(result, output)
+            }
+            Err(error) => {
+                log(Level::Error, &format!("Failed to decode event: {}", error));
+                STATUS_DECODE_ERROR
+            }
+        }
+    }
+
+    pub fn shutdown<T: Transform>(transform: &mut T) -> u32 {
+        let mut output = Output::new();
+        let result = transform.shutdown(&mut output);
+        status(result, output)
+    }
+}
+
+/// Exports a [`Transform`] from the module, implementing the ABI expected by the host.
+///
+/// This must be used exactly once, in a crate compiled as a `cdylib`.
+#[macro_export]
+macro_rules! export_transform {
+    ($transform:ty) => {
+        #[cfg(target_arch = "wasm32")]
+        const _: () = {
+            ::std::thread_local! {
+                static TRANSFORM: ::std::cell::RefCell<$transform> =
+                    ::std::cell::RefCell::new(<$transform as ::std::default::Default>::default());
+            }
+
+            #[no_mangle]
+            pub extern "C" fn vector_abi_version() -> u32 {
+                $crate::ABI_VERSION
+            }
+
+            #[no_mangle]
+            pub extern "C" fn vector_alloc(len: u32) -> *mut u8 {
+                $crate::__private::alloc(len)
+            }
+
+            #[no_mangle]
+            pub extern "C" fn vector_init(ptr: *mut u8, len: u32) -> u32 {
+                // SAFETY: the host only passes buffers returned by `vector_alloc`.
+                TRANSFORM.with(|transform| unsafe {
+                    $crate::__private::init(&mut *transform.borrow_mut(), ptr, len)
+                })
+            }
+
+            #[no_mangle]
+            pub extern "C" fn vector_process(ptr: *mut u8, len: u32) -> u32 {
+                // SAFETY: the host only passes buffers returned by `vector_alloc`.
+                TRANSFORM.with(|transform| unsafe {
+                    $crate::__private::process(&mut *transform.borrow_mut(), ptr, len)
+                })
+            }
+
+            #[no_mangle]
+            pub extern "C" fn vector_shutdown() -> u32 {
+                TRANSFORM
+                    .with(|transform| $crate::__private::shutdown(&mut *transform.borrow_mut()))
+            }
+        };
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use prost::Message;
+
+    use super::event::{event_wrapper, Log, Value, ValueMap};
+    use super::*;
+
+    #[derive(Default)]
+    struct Duplicate {
+        seen: usize,
+    }
+
+    impl Transform for Duplicate {
+        fn process(&mut self, event: EventWrapper, output: &mut Output) -> Result<(), String> {
+            self.seen += 1;
+            output.emit(event.clone());
+            output.emit(event);
+            Ok(())
+        }
+    }
+
+    fn log_event() -> EventWrapper {
+        EventWrapper {
+            event: Some(event_wrapper::Event::Log(Log {
+                value: Some(Value {
+                    kind: Some(event::value::Kind::Map(ValueMap {
+                        fields: [(
+                            "message".to_string(),
+                            Value {
+                                kind: Some(event::value::Kind::RawBytes("hello".into())),
+                            },
+                        )]
+                        .into(),
+                    })),
+                }),
+                ..Default::default()
+            })),
+        }
+    }
+
+    #[test]
+    fn process_round_trips_through_buffers() {
+        let mut transform = Duplicate::default();
+        let bytes = log_event().encode_to_vec();
+        let ptr = __private::alloc(bytes.len() as u32);
+        // SAFETY: `ptr` was just allocated with room for `bytes.len()` bytes.
+        let status = unsafe {
+            std::ptr::copy_nonoverlapping(bytes.as_ptr(), ptr, bytes.len());
+            __private::process(&mut transform, ptr, bytes.len() as u32)
+        };
+
+        assert_eq!(status, STATUS_OK);
+        assert_eq!(transform.seen, 1);
+    }
+
+    #[test]
+    fn process_rejects_invalid_input() {
+        let mut transform = Duplicate::default();
+        let bytes = [0xff, 0xff, 0xff];
+        let ptr = __private::alloc(bytes.len() as u32);
+        // SAFETY: `ptr` was just allocated with room for `bytes.len()` bytes.
+        let status = unsafe {
+            std::ptr::copy_nonoverlapping(bytes.as_ptr(), ptr, bytes.len());
+            __private::process(&mut transform, ptr, bytes.len() as u32)
+        };
+
+        assert_eq!(status, STATUS_DECODE_ERROR);
+        assert_eq!(transform.seen, 0);
+    }
+
+    #[test]
+    fn output_collects_events() {
+        let mut transform = Duplicate::default();
+        let mut output = Output::new();
+        transform.process(log_event(), &mut output).unwrap();
+
+        assert_eq!(output.into_events(), vec![log_event(), log_event()]);
+    }
+}
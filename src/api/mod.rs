@@ -13,3 +13,10 @@ use tokio::sync::oneshot;
 // Shutdown channel types used by the server and tap.
 type ShutdownTx = oneshot::Sender<()>;
 type ShutdownRx = oneshot::Receiver<()>;
+
+/// Compares credentials in constant time, so that they can't be guessed from how long the
+/// comparison takes. Only their length is leaked.
+fn credentials_eq(provided: &str, expected: &str) -> bool {
+    provided.len() == expected.len()
+        && openssl::memcmp::eq(provided.as_bytes(), expected.as_bytes())
+}
@@ -0,0 +1,120 @@
+use async_graphql::{Context, Object};
+
+use crate::{
+    api::credentials_eq,
+    topology::{TopologyCommand, TopologyControl},
+};
+
+/// Whether a request is allowed to run mutations, based on the `api.mutation_token` or `api.auth`
+/// option and the `Authorization` header of the request.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum MutationAccess {
+    /// No mutation token is configured.
+    Disabled,
+    /// The request didn't carry the configured mutation token.
+    Denied,
+    /// The request carried the configured mutation token.
+    Granted,
+}
+
+impl MutationAccess {
+    /// Checks the token provided as the bearer token of the `Authorization` header.
+    pub fn new(token: Option<&str>, authorization: Option<&str>) -> Self {
+        match token {
+            None => Self::Disabled,
+            Some(token) => {
+                let provided = authorization.and_then(|header| header.strip_prefix("Bearer "));
+                if provided.is_some_and(|provided| credentials_eq(provided, token)) {
+                    Self::Granted
+                } else {
+                    Self::Denied
+                }
+            }
+        }
+    }
+}
+
+/// Sends a command to the topology, once the request has been checked for mutation access.
+async fn send(ctx: &Context<'_>, command: TopologyCommand) -> async_graphql::Result<bool> {
+    match ctx.data_opt::<MutationAccess>() {
+        Some(MutationAccess::Granted) => {}
+        Some(MutationAccess::Denied) => return Err("Missing or invalid mutation token.".into()),
+        Some(MutationAccess::Disabled) | None => {
+            return Err("Mutations are disabled. Set `api.mutation_token` to enable them.".into())
+        }
+    }
+
+    ctx.data::<TopologyControl>()?
+        .send(command)
+        .await
+        .map_err(|error| error.to_string().into())
+}
+
+#[derive(Default)]
+pub struct ControlMutation;
+
+#[Object]
+impl ControlMutation {
+    /// Reloads the configuration from disk, as on SIGHUP. Returns `true` if the new configuration
+    /// was applied, or `false` if it was rolled back
+    async fn reload_config(&self, ctx: &Context<'_>) -> async_graphql::Result<bool> {
+        send(ctx, TopologyCommand::ReloadConfig).await
+    }
+
+    /// Pauses a component, holding back the events flowing into it, or out of it for sources,
+    /// until it is resumed or the configuration is reloaded
+    async fn pause_component(
+        &self,
+        ctx: &Context<'_>,
+        component_id: String,
+    ) -> async_graphql::Result<bool> {
+        send(ctx, TopologyCommand::PauseComponent(component_id.into())).await
+    }
+
+    /// Resumes a paused component
+    async fn resume_component(
+        &self,
+        ctx: &Context<'_>,
+        component_id: String,
+    ) -> async_graphql::Result<bool> {
+        send(ctx, TopologyCommand::ResumeComponent(component_id.into())).await
+    }
+
+    /// Restarts a sink, forcing it to send any partially filled batches. Buffered events are kept
+    async fn flush_sink(
+        &self,
+        ctx: &Context<'_>,
+        component_id: String,
+    ) -> async_graphql::Result<bool> {
+        send(ctx, TopologyCommand::FlushSink(component_id.into())).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mutation_access() {
+        assert_eq!(
+            MutationAccess::new(None, Some("Bearer secret")),
+            MutationAccess::Disabled
+        );
+        assert_eq!(
+            MutationAccess::new(Some("secret"), None),
+            MutationAccess::Denied
+        );
+        assert_eq!(
+            MutationAccess::new(Some("secret"), Some("Bearer other")),
+            MutationAccess::Denied
+        );
+        assert_eq!(
+            MutationAccess::new(Some("secret"), Some("secret")),
+            MutationAccess::Denied
+        );
+        assert_eq!(
+            MutationAccess::new(Some("secret"), Some("Bearer secret")),
+            MutationAccess::Granted
+        );
+    }
+}
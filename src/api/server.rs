@@ -9,23 +9,38 @@ use async_graphql::{
     Data, Request, Schema,
 };
 use async_graphql_warp::{graphql_protocol, GraphQLResponse, GraphQLWebSocket};
-use hyper::{server::conn::AddrIncoming, service::make_service_fn, Server as HyperServer};
+use futures::StreamExt;
+use hyper::{service::make_service_fn, Server as HyperServer};
 use tokio::runtime::Handle;
 use tokio::sync::oneshot;
 use tower::ServiceBuilder;
 use tracing::Span;
-use warp::{filters::BoxedFilter, http::Response, ws::Ws, Filter, Reply};
+use warp::{
+    filters::BoxedFilter,
+    http::{header, HeaderMap, Response, StatusCode},
+    reject::Reject,
+    ws::Ws,
+    Filter, Rejection, Reply,
+};
 
-use super::{handler, schema, ShutdownTx};
+use super::{credentials_eq, handler, schema, schema::control::MutationAccess, ShutdownTx};
 use crate::{
     config::{self, api},
-    http::build_http_trace_layer,
+    http::{build_http_trace_layer, Auth},
     internal_events::{SocketBindError, SocketMode},
-    topology,
+    tls::MaybeTlsSettings,
+    topology::{self, TopologyControl},
 };
 
+/// Rejection for a request that didn't carry the credentials configured in `api.auth`.
+#[derive(Debug)]
+struct Unauthorized;
+
+impl Reject for Unauthorized {}
+
 pub struct Server {
     _shutdown: ShutdownTx,
+    listener_closed: oneshot::Receiver<()>,
     addr: SocketAddr,
 }
 
@@ -36,22 +51,38 @@ impl Server {
         config: &config::Config,
         watch_rx: topology::WatchRx,
         running: Arc<AtomicBool>,
+        topology_control: TopologyControl,
         handle: &Handle,
     ) -> crate::Result<Self> {
-        let routes = make_routes(config.api, watch_rx, running);
+        let routes = make_routes(&config.api, watch_rx, running, topology_control);
 
         let (_shutdown, rx) = oneshot::channel();
         // warp uses `tokio::spawn` and so needs us to enter the runtime context.
         let _guard = handle.enter();
 
         let addr = config.api.address.expect("No socket address");
-        let incoming = AddrIncoming::bind(&addr).map_err(|error| {
-            emit!(SocketBindError {
-                mode: SocketMode::Tcp,
-                error: &error,
-            });
-            error
-        })?;
+        let tls = MaybeTlsSettings::from_config(&config.api.tls, true)?;
+        let listener = std::net::TcpListener::bind(addr)
+            .and_then(|listener| {
+                listener.set_nonblocking(true)?;
+                tokio::net::TcpListener::from_std(listener)
+            })
+            .map_err(|error| {
+                emit!(SocketBindError {
+                    mode: SocketMode::Tcp,
+                    error: &error,
+                });
+                error
+            })?;
+        let listener = tls.wrap_listener(listener)?;
+
+        // Completes once the server has stopped accepting connections and closed the listener,
+        // which is when the accept stream below is dropped.
+        let (listening, listener_closed) = oneshot::channel::<()>();
+        let incoming = listener.accept_stream().map(move |conn| {
+            let _listening = &listening;
+            conn
+        });
 
         let span = Span::current();
         let make_svc = make_service_fn(move |_conn| {
@@ -62,7 +93,7 @@ impl Server {
         });
 
         let server = async move {
-            HyperServer::builder(incoming)
+            HyperServer::builder(hyper::server::accept::from_stream(incoming))
                 .serve(make_svc)
                 .with_graceful_shutdown(async {
                     rx.await.ok();
@@ -79,7 +110,23 @@ impl Server {
         // Spawn the server in the background.
         handle.spawn(server);
 
-        Ok(Self { _shutdown, addr })
+        Ok(Self {
+            _shutdown,
+            listener_closed,
+            addr,
+        })
+    }
+
+    /// Shuts down the server, and waits for it to close its listener, so that another server can
+    /// be started on the same address.
+    pub async fn stop(self) {
+        let Self {
+            _shutdown,
+            listener_closed,
+            ..
+        } = self;
+        drop(_shutdown);
+        _ = listener_closed.await;
     }
 
     /// Returns a copy of the SocketAddr that the server was started on.
@@ -96,9 +143,10 @@ impl Server {
 }
 
 fn make_routes(
-    api: api::Options,
+    api: &api::Options,
     watch_tx: topology::WatchRx,
     running: Arc<AtomicBool>,
+    topology_control: TopologyControl,
 ) -> BoxedFilter<(impl Reply,)> {
     // Routes...
 
@@ -111,20 +159,65 @@ fn make_routes(
     let not_found_graphql = warp::any().and_then(|| async { Err(warp::reject::not_found()) });
     let not_found = warp::any().and_then(|| async { Err(warp::reject::not_found()) });
 
+    // Authentication, required by the GraphQL and playground endpoints when `api.auth` is set.
+    let expected_authorization = api.auth.as_ref().map(expected_authorization);
+    let authorized = warp::header::optional::<String>("authorization")
+        .and_then(move |authorization: Option<String>| {
+            let authorized = match &expected_authorization {
+                None => true,
+                Some(expected) => match (authorization, expected) {
+                    (Some(provided), Some(expected)) => credentials_eq(&provided, expected),
+                    _ => false,
+                },
+            };
+            async move {
+                if authorized {
+                    Ok(())
+                } else {
+                    Err(warp::reject::custom(Unauthorized))
+                }
+            }
+        })
+        .untuple_one();
+
+    // Mutation access, determined by the bearer token of the `Authorization` header of each
+    // request. When `api.auth` is set, the header is used for authentication instead, and every
+    // authenticated request is allowed to run mutations.
+    let mutation_token = api
+        .mutation_token
+        .as_ref()
+        .map(|token| token.inner().to_owned());
+    let authenticated = api.auth.is_some();
+    let mutation_access = warp::header::optional::<String>("authorization").map(
+        move |authorization: Option<String>| {
+            if authenticated {
+                // Requests only get this far once they've been checked against `api.auth`.
+                MutationAccess::Granted
+            } else {
+                MutationAccess::new(mutation_token.as_deref(), authorization.as_deref())
+            }
+        },
+    );
+
     // GraphQL subscription handler. Creates a Warp WebSocket handler and for each connection,
     // parses the required headers for GraphQL and builds per-connection context based on the
     // provided `WatchTx` channel sender. This allows GraphQL resolvers to subscribe to
     // topology changes.
-    let graphql_subscription_handler =
-        warp::ws()
-            .and(graphql_protocol())
-            .map(move |ws: Ws, protocol: WebSocketProtocols| {
-                let schema = schema::build_schema().finish();
+    let subscription_control = topology_control.clone();
+    let graphql_subscription_handler = warp::ws()
+        .and(graphql_protocol())
+        .and(mutation_access.clone())
+        .map(
+            move |ws: Ws, protocol: WebSocketProtocols, mutation_access: MutationAccess| {
+                let schema = schema::build_schema()
+                    .data(subscription_control.clone())
+                    .finish();
                 let watch_tx = watch_tx.clone();
 
                 let reply = ws.on_upgrade(move |socket| {
                     let mut data = Data::default();
                     data.insert(watch_tx);
+                    data.insert(mutation_access);
 
                     GraphQLWebSocket::new(socket, schema, protocol)
                         .with_data(data)
@@ -136,20 +229,28 @@ fn make_routes(
                     "Sec-WebSocket-Protocol",
                     protocol.sec_websocket_protocol(),
                 )
-            });
+            },
+        );
 
     // Handle GraphQL queries. Headers will first be parsed to determine whether the query is
     // a subscription and if so, an attempt will be made to upgrade the connection to WebSockets.
     // All other queries will fall back to the default HTTP handler.
     let graphql_handler = if api.graphql {
         warp::path("graphql")
-            .and(graphql_subscription_handler.or(
-                async_graphql_warp::graphql(schema::build_schema().finish()).and_then(
-                    |(schema, request): (Schema<_, _, _>, Request)| async move {
+            .and(authorized.clone())
+            .and(
+                graphql_subscription_handler.or(async_graphql_warp::graphql(
+                    schema::build_schema().data(topology_control).finish(),
+                )
+                .and(mutation_access)
+                .and_then(
+                    |(schema, request): (Schema<_, _, _>, Request),
+                     mutation_access: MutationAccess| async move {
+                        let request = request.data(mutation_access);
                         Ok::<_, Infallible>(GraphQLResponse::from(schema.execute(request).await))
                     },
-                ),
-            ))
+                )),
+            )
             .boxed()
     } else {
         not_found_graphql.boxed()
@@ -158,6 +259,7 @@ fn make_routes(
     // Provide a playground for executing GraphQL queries/mutations/subscriptions.
     let graphql_playground = if api.playground && api.graphql {
         warp::path("playground")
+            .and(authorized)
             .map(move || {
                 Response::builder()
                     .header("content-type", "text/html")
@@ -170,12 +272,29 @@ fn make_routes(
         not_found.boxed()
     };
 
+    // Challenge unauthenticated requests with the scheme that `api.auth` expects.
+    let challenge = match &api.auth {
+        Some(Auth::Basic { .. }) => r#"Basic realm="vector""#,
+        _ => r#"Bearer realm="vector""#,
+    };
+
     // Wire up the health + GraphQL endpoints. Provides a permissive CORS policy to allow for
     // cross-origin interaction with the Vector API.
     health
         .or(graphql_handler)
         .or(graphql_playground)
         .or(not_found)
+        .recover(move |rejection: Rejection| async move {
+            if rejection.find::<Unauthorized>().is_some() {
+                Ok(warp::reply::with_header(
+                    warp::reply::with_status("Unauthorized", StatusCode::UNAUTHORIZED),
+                    header::WWW_AUTHENTICATE,
+                    challenge,
+                ))
+            } else {
+                Err(rejection)
+            }
+        })
         .with(
             warp::cors()
                 .allow_any_origin()
@@ -188,6 +307,7 @@ fn make_routes(
                     "Access-Control-Allow-Origin",
                     "Access-Control-Request-Headers",
                     "Content-Type",
+                    "Authorization",
                     "X-Apollo-Tracing", // for Apollo GraphQL clients
                     "Pragma",
                     "Host",
@@ -199,8 +319,108 @@ fn make_routes(
         .boxed()
 }
 
+/// The `Authorization` header that requests must carry to satisfy `auth`, or `None` if no valid
+/// header can be built from it, in which case every request is rejected.
+fn expected_authorization(auth: &Auth) -> Option<String> {
+    let mut headers = HeaderMap::new();
+    auth.apply_headers_map(&mut headers);
+    headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .map(ToOwned::to_owned)
+}
+
 fn with_shared(
     shared: Arc<AtomicBool>,
 ) -> impl Filter<Extract = (Arc<AtomicBool>,), Error = Infallible> + Clone {
     warp::any().map(move || Arc::<AtomicBool>::clone(&shared))
 }
+
+#[cfg(test)]
+mod tests {
+    use tokio::sync::watch;
+    use vector_lib::sensitive_string::SensitiveString;
+
+    use super::*;
+
+    fn routes() -> BoxedFilter<(impl Reply,)> {
+        let (topology_control, _) = TopologyControl::new();
+        routes_with_control(topology_control)
+    }
+
+    fn routes_with_control(topology_control: TopologyControl) -> BoxedFilter<(impl Reply,)> {
+        let api = api::Options {
+            enabled: true,
+            auth: Some(Auth::Basic {
+                user: "user".to_owned(),
+                password: SensitiveString::from("pass".to_owned()),
+            }),
+            ..Default::default()
+        };
+        let (_, watch_rx) = watch::channel(topology::TapResource::default());
+
+        make_routes(
+            &api,
+            watch_rx,
+            Arc::new(AtomicBool::new(true)),
+            topology_control,
+        )
+    }
+
+    #[tokio::test]
+    async fn auth_protects_graphql_but_not_health() {
+        let routes = routes();
+        let query = r#"{"query":"{ health }"}"#;
+
+        let response = warp::test::request().path("/health").reply(&routes).await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let response = warp::test::request()
+            .method("POST")
+            .path("/graphql")
+            .header("content-type", "application/json")
+            .body(query)
+            .reply(&routes)
+            .await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(
+            response.headers()[header::WWW_AUTHENTICATE],
+            r#"Basic realm="vector""#
+        );
+
+        let response = warp::test::request()
+            .method("POST")
+            .path("/graphql")
+            .header("authorization", "Basic dXNlcjpwYXNz")
+            .header("content-type", "application/json")
+            .body(query)
+            .reply(&routes)
+            .await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn auth_grants_mutations() {
+        let (topology_control, mut control_rx) = TopologyControl::new();
+        let routes = routes_with_control(topology_control);
+        tokio::spawn(async move {
+            while let Some(request) = control_rx.recv().await {
+                _ = request.response.send(Ok(true));
+            }
+        });
+
+        let response = warp::test::request()
+            .method("POST")
+            .path("/graphql")
+            .header("authorization", "Basic dXNlcjpwYXNz")
+            .header("content-type", "application/json")
+            .body(r#"{"query":"mutation { flushSink(componentId: \"out\") }"}"#)
+            .reply(&routes)
+            .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            String::from_utf8_lossy(response.body()),
+            r#"{"data":{"flushSink":true}}"#
+        );
+    }
+}
@@ -25,7 +25,8 @@ use crate::{
     internal_events::{VectorQuit, VectorStarted, VectorStopped},
     signal::{SignalHandler, SignalPair, SignalRx, SignalTo},
     topology::{
-        ReloadOutcome, RunningTopology, SharedTopologyController, ShutdownErrorReceiver,
+        ControlError, ControlReceiver, ControlRequest, ReloadOutcome, RunningTopology,
+        SharedTopologyController, ShutdownErrorReceiver, TopologyCommand, TopologyControl,
         TopologyController,
     },
     trace,
@@ -95,7 +96,7 @@ impl ApplicationConfig {
         let enterprise = build_enterprise(&mut config, config_paths.clone())?;
 
         #[cfg(feature = "api")]
-        let api = config.api;
+        let api = config.api.clone();
 
         let (topology, graceful_crash_receiver) =
             RunningTopology::start_init_validated(config, extra_context.clone())
@@ -131,12 +132,17 @@ impl ApplicationConfig {
 
     /// Configure the API server, if applicable
     #[cfg(feature = "api")]
-    pub fn setup_api(&self, handle: &Handle) -> Option<api::Server> {
+    pub fn setup_api(
+        &self,
+        topology_control: TopologyControl,
+        handle: &Handle,
+    ) -> Option<api::Server> {
         if self.api.enabled {
             match api::Server::start(
                 self.topology.config(),
                 self.topology.watch(),
                 std::sync::Arc::clone(&self.topology.running),
+                topology_control,
                 handle,
             ) {
                 Ok(api_server) => {
@@ -249,9 +255,15 @@ impl Application {
             signals,
         } = self;
 
+        // Runtime control commands are only sent by the API.
+        #[cfg_attr(not(feature = "api"), allow(unused_variables))]
+        let (topology_control, control_receiver) = TopologyControl::new();
+
         let topology_controller = SharedTopologyController::new(TopologyController {
             #[cfg(feature = "api")]
-            api_server: config.setup_api(handle),
+            api_server: config.setup_api(topology_control.clone(), handle),
+            #[cfg(feature = "api")]
+            topology_control,
             topology: config.topology,
             config_paths: config.config_paths.clone(),
             require_healthy: root_opts.require_healthy,
@@ -265,6 +277,7 @@ impl Application {
             internal_topologies: config.internal_topologies,
             graceful_crash_receiver: config.graceful_crash_receiver,
             signals,
+            control_receiver,
             topology_controller,
             allow_empty_config: root_opts.allow_empty_config,
         })
@@ -276,6 +289,7 @@ pub struct StartedApplication {
     pub internal_topologies: Vec<RunningTopology>,
     pub graceful_crash_receiver: ShutdownErrorReceiver,
     pub signals: SignalPair,
+    pub control_receiver: ControlReceiver,
     pub topology_controller: SharedTopologyController,
     pub allow_empty_config: bool,
 }
@@ -290,6 +304,7 @@ impl StartedApplication {
             config_paths,
             graceful_crash_receiver,
             signals,
+            mut control_receiver,
             topology_controller,
             internal_topologies,
             allow_empty_config,
@@ -312,6 +327,15 @@ impl StartedApplication {
                 ).await {
                     break signal;
                 },
+                Some(request) = control_receiver.recv() => if let Some(signal) = handle_control(
+                    request,
+                    &topology_controller,
+                    &config_paths,
+                    &mut signal_handler,
+                    allow_empty_config,
+                ).await {
+                    break signal;
+                },
                 // Trigger graceful shutdown if a component crashed, or all sources have ended.
                 error = graceful_crash.next() => break SignalTo::Shutdown(error),
                 _ = TopologyController::sources_finished(topology_controller.clone()), if has_sources => {
@@ -348,24 +372,14 @@ async fn handle_signal(
             }
         }
         Ok(SignalTo::ReloadFromDisk) => {
-            let mut topology_controller = topology_controller.lock().await;
-
-            // Reload paths
-            if let Some(paths) = config::process_paths(config_paths) {
-                topology_controller.config_paths = paths;
-            }
-
-            // Reload config
-            let new_config = config::load_from_paths_with_provider_and_secrets(
-                &topology_controller.config_paths,
+            match reload_from_disk(
+                topology_controller,
+                config_paths,
                 signal_handler,
                 allow_empty_config,
             )
             .await
-            .map_err(handle_config_errors)
-            .ok();
-
-            match topology_controller.reload(new_config).await {
+            {
                 ReloadOutcome::FatalError(error) => Some(SignalTo::Shutdown(Some(error))),
                 _ => None,
             }
@@ -379,6 +393,72 @@ async fn handle_signal(
     }
 }
 
+async fn reload_from_disk(
+    topology_controller: &SharedTopologyController,
+    config_paths: &[ConfigPath],
+    signal_handler: &mut SignalHandler,
+    allow_empty_config: bool,
+) -> ReloadOutcome {
+    let mut topology_controller = topology_controller.lock().await;
+
+    // Reload paths
+    if let Some(paths) = config::process_paths(config_paths) {
+        topology_controller.config_paths = paths;
+    }
+
+    // Reload config
+    let new_config = config::load_from_paths_with_provider_and_secrets(
+        &topology_controller.config_paths,
+        signal_handler,
+        allow_empty_config,
+    )
+    .await
+    .map_err(handle_config_errors)
+    .ok();
+
+    topology_controller.reload(new_config).await
+}
+
+async fn handle_control(
+    request: ControlRequest,
+    topology_controller: &SharedTopologyController,
+    config_paths: &[ConfigPath],
+    signal_handler: &mut SignalHandler,
+    allow_empty_config: bool,
+) -> Option<SignalTo> {
+    let ControlRequest { command, response } = request;
+    let (outcome, signal) = match command {
+        TopologyCommand::ReloadConfig => {
+            match reload_from_disk(
+                topology_controller,
+                config_paths,
+                signal_handler,
+                allow_empty_config,
+            )
+            .await
+            {
+                ReloadOutcome::Success => (Ok(true), None),
+                ReloadOutcome::RolledBack => (Ok(false), None),
+                ReloadOutcome::NoConfig | ReloadOutcome::MissingApiKey => {
+                    (Err(ControlError::ReloadFailed), None)
+                }
+                ReloadOutcome::FatalError(error) => (
+                    Err(ControlError::ReloadFailed),
+                    Some(SignalTo::Shutdown(Some(error))),
+                ),
+            }
+        }
+        command => (
+            topology_controller.lock().await.control(command).await,
+            None,
+        ),
+    };
+
+    // The requester may have gone away in the meantime, which is fine.
+    _ = response.send(outcome);
+    signal
+}
+
 pub struct FinishedApplication {
     pub signal: SignalTo,
     pub signal_rx: SignalRx,
@@ -0,0 +1,454 @@
+use std::{
+    io::{self, Write},
+    path::PathBuf,
+};
+
+use chrono::{DateTime, Utc};
+use clap::Parser;
+use futures::{future, stream::BoxStream, StreamExt};
+use vector_lib::{
+    buffers::{BufferConfig, BufferInspector, BufferType},
+    event::{Event, EventArray, EventContainer, EventRef, Value},
+    finalization::{EventStatus, Finalizable},
+    lookup::event_path,
+};
+
+use crate::config::{self, ComponentKey, ConfigBuilder, DataType, ProxyConfig, SinkContext};
+
+#[derive(Parser, Debug)]
+#[command(rename_all = "kebab-case")]
+pub struct Opts {
+    /// Read configuration from one or more files. Wildcard paths are supported.
+    /// File format is detected from the file name.
+    /// If zero files are specified the default config path
+    /// `/etc/vector/vector.yaml` will be targeted.
+    #[arg(
+        id = "config",
+        short,
+        long,
+        env = "VECTOR_CONFIG",
+        value_delimiter(',')
+    )]
+    paths: Vec<PathBuf>,
+
+    /// Vector config files in TOML format.
+    #[arg(id = "config-toml", long, value_delimiter(','))]
+    paths_toml: Vec<PathBuf>,
+
+    /// Vector config files in JSON format.
+    #[arg(id = "config-json", long, value_delimiter(','))]
+    paths_json: Vec<PathBuf>,
+
+    /// Vector config files in YAML format.
+    #[arg(id = "config-yaml", long, value_delimiter(','))]
+    paths_yaml: Vec<PathBuf>,
+
+    /// Read configuration from files in one or more directories.
+    /// File format is detected from the file name.
+    ///
+    /// Files not ending in .toml, .json, .yaml, or .yml will be ignored.
+    #[arg(
+        id = "config-dir",
+        short = 'C',
+        long,
+        env = "VECTOR_CONFIG_DIR",
+        value_delimiter(',')
+    )]
+    pub config_dirs: Vec<PathBuf>,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+impl Opts {
+    fn paths_with_formats(&self) -> Vec<config::ConfigPath> {
+        config::merge_path_lists(vec![
+            (&self.paths, None),
+            (&self.paths_toml, Some(config::Format::Toml)),
+            (&self.paths_json, Some(config::Format::Json)),
+            (&self.paths_yaml, Some(config::Format::Yaml)),
+        ])
+        .map(|(path, hint)| config::ConfigPath::File(path, hint))
+        .chain(
+            self.config_dirs
+                .iter()
+                .map(|dir| config::ConfigPath::Dir(dir.to_path_buf())),
+        )
+        .collect()
+    }
+}
+
+#[derive(Parser, Debug)]
+#[command(rename_all = "kebab-case")]
+enum Command {
+    /// Show the number of records, events, and bytes in the disk buffer of a sink, as well as the
+    /// timestamps of the oldest and newest events.
+    Info(InfoOpts),
+
+    /// Write the events in the disk buffer of a sink to stdout, as native JSON, one event per line.
+    Dump(DumpOpts),
+
+    /// Send the events in the disk buffer of a sink to another sink.
+    Replay(ReplayOpts),
+}
+
+#[derive(Parser, Debug)]
+#[command(rename_all = "kebab-case")]
+struct InfoOpts {
+    /// The ID of the sink whose buffer should be inspected.
+    sink: ComponentKey,
+}
+
+#[derive(Parser, Debug)]
+#[command(rename_all = "kebab-case")]
+struct DumpOpts {
+    /// The ID of the sink whose buffer should be dumped.
+    sink: ComponentKey,
+
+    /// Stop after writing this many events.
+    #[arg(long)]
+    limit: Option<usize>,
+
+    /// Remove the dumped events from the buffer.
+    ///
+    /// By default, the buffer is opened read-only and is left as-is, so that Vector delivers the
+    /// events to the sink the next time it starts.
+    #[arg(long, conflicts_with = "limit")]
+    consume: bool,
+}
+
+#[derive(Parser, Debug)]
+#[command(rename_all = "kebab-case")]
+struct ReplayOpts {
+    /// The ID of the sink whose buffer should be replayed.
+    sink: ComponentKey,
+
+    /// The ID of the sink to send the events to.
+    #[arg(long)]
+    to: ComponentKey,
+
+    /// Read the sink to send the events to from these config files, instead of from the
+    /// configuration of the buffered sink. Only the sink itself is used, so its inputs do not need to
+    /// exist.
+    #[arg(long, value_delimiter(','))]
+    to_config: Vec<PathBuf>,
+
+    /// Remove the replayed events from the buffer.
+    ///
+    /// By default, the buffer is opened read-only and is left as-is, so that Vector delivers the
+    /// events to the buffered sink the next time it starts. Events are removed once the target sink
+    /// has finished with them, even if it failed to deliver them.
+    #[arg(long)]
+    consume: bool,
+}
+
+/// Events read from a buffer, along with any errors encountered while reading them.
+type BufferStream = BoxStream<'static, Result<EventArray, String>>;
+
+pub async fn cmd(opts: &Opts) -> exitcode::ExitCode {
+    let paths = opts.paths_with_formats();
+    let paths = match config::process_paths(&paths) {
+        Some(paths) => paths,
+        None => return exitcode::CONFIG,
+    };
+
+    let builder = match load_builder(&paths) {
+        Some(builder) => builder,
+        None => return exitcode::CONFIG,
+    };
+
+    match &opts.command {
+        Command::Info(info_opts) => info(&builder, info_opts).await,
+        Command::Dump(dump_opts) => dump(&builder, dump_opts).await,
+        Command::Replay(replay_opts) => replay(&builder, replay_opts).await,
+    }
+}
+
+fn load_builder(paths: &[config::ConfigPath]) -> Option<ConfigBuilder> {
+    match config::load_builder_from_paths(paths) {
+        Ok((builder, _)) => Some(builder),
+        Err(errs) => {
+            #[allow(clippy::print_stderr)]
+            for err in errs {
+                eprintln!("{}", err);
+            }
+            None
+        }
+    }
+}
+
+/// Gets the disk buffer stage configured for the given sink.
+fn disk_buffer<'a>(
+    builder: &'a ConfigBuilder,
+    key: &ComponentKey,
+) -> Result<&'a BufferType, String> {
+    let sink = builder
+        .sinks
+        .get(key)
+        .ok_or_else(|| format!("Sink \"{}\" does not exist.", key))?;
+
+    sink.buffer
+        .stages()
+        .iter()
+        .find(|stage| matches!(stage, BufferType::DiskV2 { .. }))
+        .ok_or_else(|| format!("Sink \"{}\" does not have a disk buffer.", key))
+}
+
+/// Opens the disk buffer of the given sink, read-only unless `consume` is set.
+///
+/// When consuming, the buffer is opened just as Vector would open it, so events are removed from
+/// the buffer once their finalizers are updated.
+async fn open_buffer(
+    builder: &ConfigBuilder,
+    key: &ComponentKey,
+    consume: bool,
+) -> Result<BufferStream, String> {
+    let buffer = disk_buffer(builder, key)?;
+    let data_dir = builder.global.data_dir.clone();
+
+    if consume {
+        let span = error_span!("buffer", component_id = %key.id());
+        let (tx, rx) = BufferConfig::Single(buffer.clone())
+            .build(data_dir, key.to_string(), span)
+            .await
+            .map_err(|error| format!("Sink \"{}\": {}", key, error))?;
+
+        // Nothing will ever be written to the buffer, which lets the stream end once every event
+        // in it has been read and acknowledged.
+        drop(tx);
+
+        return Ok(rx.into_stream().map(Ok).boxed());
+    }
+
+    let inspector = buffer
+        .inspect::<EventArray>(data_dir, key)
+        .await
+        .map_err(|error| format!("Sink \"{}\": {}", key, error))?
+        .expect("disk buffers can always be inspected");
+
+    Ok(futures::stream::unfold(inspector, inspect_next).boxed())
+}
+
+async fn inspect_next(
+    mut inspector: BufferInspector<EventArray>,
+) -> Option<(Result<EventArray, String>, BufferInspector<EventArray>)> {
+    match inspector.next().await {
+        Ok(Some(record)) => Some((Ok(record.into_item()), inspector)),
+        Ok(None) => None,
+        Err(error) => Some((Err(error.to_string()), inspector)),
+    }
+}
+
+fn event_timestamp(event: EventRef<'_>) -> Option<DateTime<Utc>> {
+    match event {
+        EventRef::Log(log) => log.get_timestamp().and_then(Value::as_timestamp).copied(),
+        EventRef::Metric(metric) => metric.timestamp(),
+        EventRef::Trace(trace) => trace
+            .get(event_path!("timestamp"))
+            .and_then(Value::as_timestamp)
+            .copied(),
+    }
+}
+
+#[allow(clippy::print_stderr)]
+fn report_read_error(key: &ComponentKey, error: &str) {
+    eprintln!("Error reading buffer of sink \"{}\": {}", key, error);
+}
+
+#[allow(clippy::print_stderr, clippy::print_stdout)]
+async fn info(builder: &ConfigBuilder, opts: &InfoOpts) -> exitcode::ExitCode {
+    let buffer = match disk_buffer(builder, &opts.sink) {
+        Ok(buffer) => buffer,
+        Err(error) => {
+            eprintln!("{}", error);
+            return exitcode::CONFIG;
+        }
+    };
+
+    let mut inspector = match buffer
+        .inspect::<EventArray>(builder.global.data_dir.clone(), &opts.sink)
+        .await
+    {
+        Ok(inspector) => inspector.expect("disk buffers can always be inspected"),
+        Err(error) => {
+            eprintln!("Sink \"{}\": {}", opts.sink, error);
+            return exitcode::UNAVAILABLE;
+        }
+    };
+
+    let unread_events = inspector.unread_events();
+    let (reader_file_id, writer_file_id) = inspector.data_file_ids();
+
+    let mut records = 0u64;
+    let mut events = 0u64;
+    let mut bytes = 0u64;
+    let mut errors = 0u64;
+    let mut oldest: Option<DateTime<Utc>> = None;
+    let mut newest: Option<DateTime<Utc>> = None;
+    loop {
+        match inspector.next().await {
+            Ok(Some(record)) => {
+                records += 1;
+                bytes += record.size();
+                for event in record.item().iter_events() {
+                    events += 1;
+                    if let Some(timestamp) = event_timestamp(event) {
+                        oldest = Some(oldest.map_or(timestamp, |ts| ts.min(timestamp)));
+                        newest = Some(newest.map_or(timestamp, |ts| ts.max(timestamp)));
+                    }
+                }
+            }
+            Ok(None) => break,
+            Err(error) => {
+                errors += 1;
+                report_read_error(&opts.sink, &error.to_string());
+            }
+        }
+    }
+
+    let format_timestamp =
+        |ts: Option<DateTime<Utc>>| ts.map_or_else(|| "-".to_string(), |ts| ts.to_rfc3339());
+
+    println!("Sink:          {}", opts.sink);
+    println!("Data files:    {} to {}", reader_file_id, writer_file_id);
+    println!("Records:       {}", records);
+    println!(
+        "Events:        {} ({} according to ledger)",
+        events, unread_events
+    );
+    println!("Bytes:         {}", bytes);
+    println!("Oldest event:  {}", format_timestamp(oldest));
+    println!("Newest event:  {}", format_timestamp(newest));
+    println!("Read errors:   {}", errors);
+
+    exitcode::OK
+}
+
+#[allow(clippy::print_stderr)]
+async fn dump(builder: &ConfigBuilder, opts: &DumpOpts) -> exitcode::ExitCode {
+    let mut buffer = match open_buffer(builder, &opts.sink, opts.consume).await {
+        Ok(buffer) => buffer,
+        Err(error) => {
+            eprintln!("{}", error);
+            return exitcode::UNAVAILABLE;
+        }
+    };
+
+    let limit = opts.limit.unwrap_or(usize::MAX);
+    let mut written = 0;
+    let mut stdout = io::stdout().lock();
+    while written < limit {
+        let Some(events) = buffer.next().await else {
+            break;
+        };
+        let mut events = match events {
+            Ok(events) => events,
+            Err(error) => {
+                report_read_error(&opts.sink, &error);
+                continue;
+            }
+        };
+
+        let finalizers = events.take_finalizers();
+        for event in events.into_events().take(limit - written) {
+            if let Err(error) = write_event(&mut stdout, &event) {
+                eprintln!("Failed to write event: {}", error);
+                return exitcode::IOERR;
+            }
+            written += 1;
+        }
+        finalizers.update_status(EventStatus::Delivered);
+    }
+
+    exitcode::OK
+}
+
+fn write_event(writer: &mut impl Write, event: &Event) -> io::Result<()> {
+    serde_json::to_writer(&mut *writer, event)?;
+    writer.write_all(b"\n")
+}
+
+#[allow(clippy::print_stderr)]
+async fn replay(builder: &ConfigBuilder, opts: &ReplayOpts) -> exitcode::ExitCode {
+    let loaded_builder;
+    let target_builder = if opts.to_config.is_empty() {
+        builder
+    } else {
+        let paths = opts
+            .to_config
+            .iter()
+            .map(|path| config::ConfigPath::File(path.clone(), None))
+            .collect::<Vec<_>>();
+        loaded_builder = match load_builder(&paths) {
+            Some(builder) => builder,
+            None => return exitcode::CONFIG,
+        };
+        &loaded_builder
+    };
+
+    let Some(target) = target_builder.sinks.get(&opts.to) else {
+        eprintln!("Sink \"{}\" does not exist.", opts.to);
+        return exitcode::CONFIG;
+    };
+    let input_type = target.inner.input().data_type();
+
+    let cx = SinkContext {
+        globals: target_builder.global.clone(),
+        proxy: ProxyConfig::merge_with_env(&target_builder.global.proxy, target.proxy()),
+        ..Default::default()
+    };
+    let sink = match target.inner.build(cx).await {
+        Ok((sink, _healthcheck)) => sink,
+        Err(error) => {
+            eprintln!("Sink \"{}\": {}", opts.to, error);
+            return exitcode::CONFIG;
+        }
+    };
+
+    let buffer = match open_buffer(builder, &opts.sink, opts.consume).await {
+        Ok(buffer) => buffer,
+        Err(error) => {
+            eprintln!("{}", error);
+            return exitcode::UNAVAILABLE;
+        }
+    };
+
+    let key = opts.sink.clone();
+    let events = buffer.filter_map(move |events| {
+        let events = match events {
+            Ok(events) => Some(events),
+            Err(error) => {
+                report_read_error(&key, &error);
+                None
+            }
+        };
+        future::ready(events.and_then(|mut events| {
+            if accepts_events(&events, input_type) {
+                Some(events)
+            } else {
+                // Events the target sink can't accept are skipped, but are still considered
+                // handled so that they're removed from the buffer when consuming it.
+                events
+                    .take_finalizers()
+                    .update_status(EventStatus::Delivered);
+                None
+            }
+        }))
+    });
+
+    match sink.run(events).await {
+        Ok(()) => exitcode::OK,
+        Err(()) => {
+            eprintln!("Sink \"{}\" failed.", opts.to);
+            exitcode::SOFTWARE
+        }
+    }
+}
+
+const fn accepts_events(events: &EventArray, data_type: DataType) -> bool {
+    match events {
+        EventArray::Logs(_) => data_type.contains(DataType::Log),
+        EventArray::Metrics(_) => data_type.contains(DataType::Metric),
+        EventArray::Traces(_) => data_type.contains(DataType::Trace),
+    }
+}
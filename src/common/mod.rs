@@ -1,4 +1,7 @@
 //! Modules that are common between sources and sinks.
+#[cfg(any(feature = "sources-azure_blob", feature = "sinks-azure_blob"))]
+pub(crate) mod azure_blob;
+
 #[cfg(any(
     feature = "sources-datadog_agent",
     feature = "sinks-datadog_events",
@@ -9,6 +12,12 @@
 ))]
 pub mod datadog;
 
+#[cfg(any(feature = "sources-mqtt", feature = "sinks-mqtt"))]
+pub(crate) mod mqtt;
+
+#[cfg(any(feature = "sources-pulsar", feature = "sinks-pulsar"))]
+pub(crate) mod pulsar;
+
 #[cfg(any(
     feature = "sources-aws_sqs",
     feature = "sinks-aws_sqs",
@@ -0,0 +1,408 @@
+//! Connection settings shared by the `mqtt` source and sink.
+
+use std::time::Duration;
+
+use rand::Rng;
+use rand_distr::Alphanumeric;
+use rumqttc::{MqttOptions, QoS, TlsConfiguration, Transport};
+use snafu::{ResultExt, Snafu};
+use vector_lib::configurable::configurable_component;
+use vector_lib::sensitive_string::SensitiveString;
+
+use crate::tls::{MaybeTlsSettings, TlsEnableableConfig, TlsError};
+
+#[derive(Debug, Snafu)]
+#[snafu(visibility(pub(crate)))]
+pub(crate) enum MqttError {
+    #[snafu(display("invalid client ID: client IDs can't be empty"))]
+    EmptyClientId,
+    #[snafu(display("invalid credentials: a password requires a user"))]
+    MissingUser,
+    #[snafu(display("invalid TLS configuration: {}", source))]
+    Tls { source: TlsError },
+    #[snafu(display("MQTT client error: {}", source))]
+    Client { source: rumqttc::ClientError },
+    #[snafu(display("MQTT connection error: {}", source))]
+    Connection { source: rumqttc::ConnectionError },
+}
+
+/// Quality of service levels of MQTT messages.
+#[configurable_component]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum MqttQoS {
+    /// QoS 0: messages are delivered at most once, and may be lost.
+    AtMostOnce,
+
+    /// QoS 1: messages are delivered at least once, and may be duplicated.
+    #[default]
+    AtLeastOnce,
+
+    /// QoS 2: messages are delivered exactly once.
+    ExactlyOnce,
+}
+
+impl From<MqttQoS> for QoS {
+    fn from(qos: MqttQoS) -> Self {
+        match qos {
+            MqttQoS::AtMostOnce => QoS::AtMostOnce,
+            MqttQoS::AtLeastOnce => QoS::AtLeastOnce,
+            MqttQoS::ExactlyOnce => QoS::ExactlyOnce,
+        }
+    }
+}
+
+/// Settings for connecting to an MQTT broker.
+#[configurable_component]
+#[derive(Clone, Debug, Derivative)]
+#[derivative(Default)]
+pub struct MqttCommonConfig {
+    /// The host name or IP address of the MQTT broker.
+    #[configurable(metadata(docs::examples = "mqtt.example.com"))]
+    #[configurable(metadata(docs::examples = "127.0.0.1"))]
+    pub host: String,
+
+    /// The TCP port of the MQTT broker.
+    #[serde(default = "default_port")]
+    #[derivative(Default(value = "default_port()"))]
+    pub port: u16,
+
+    /// The user name used to authenticate with the broker.
+    #[configurable(metadata(docs::examples = "vector"))]
+    pub user: Option<String>,
+
+    /// The password used to authenticate with the broker.
+    #[configurable(metadata(docs::examples = "${MQTT_PASSWORD}"))]
+    pub password: Option<SensitiveString>,
+
+    /// The client ID to connect with.
+    ///
+    /// Client IDs must be unique for each client connected to a broker. With a persistent session,
+    /// the broker uses the client ID to find the session when the client reconnects, so it must
+    /// also be stable across restarts.
+    ///
+    /// By default, the source uses a client ID derived from its component ID, and the sink uses a
+    /// random client ID.
+    #[configurable(metadata(docs::examples = "vector-edge-1"))]
+    pub client_id: Option<String>,
+
+    /// The interval at which the client pings the broker when no other packets are exchanged.
+    #[serde(default = "default_keep_alive_secs")]
+    #[derivative(Default(value = "default_keep_alive_secs()"))]
+    #[configurable(metadata(docs::type_unit = "seconds"))]
+    #[configurable(metadata(docs::human_name = "Keep Alive Interval"))]
+    pub keep_alive_secs: u16,
+
+    /// The maximum size of the packets sent to and received from the broker.
+    #[serde(default = "default_max_packet_size")]
+    #[derivative(Default(value = "default_max_packet_size()"))]
+    #[configurable(metadata(docs::type_unit = "bytes"))]
+    pub max_packet_size: usize,
+
+    /// TLS configuration.
+    ///
+    /// The certificate authorities of the broker must be set with `ca_file`, as the system
+    /// certificate store isn't used.
+    #[configurable(derived)]
+    pub tls: Option<TlsEnableableConfig>,
+}
+
+const fn default_port() -> u16 {
+    1883
+}
+
+const fn default_keep_alive_secs() -> u16 {
+    60
+}
+
+const fn default_max_packet_size() -> usize {
+    10 * 1024 * 1024
+}
+
+impl MqttCommonConfig {
+    /// Builds the options of an MQTT client.
+    ///
+    /// `default_client_id` is used when no `client_id` is configured.
+    pub(crate) fn mqtt_options(&self, default_client_id: &str) -> Result<MqttOptions, MqttError> {
+        let client_id = self.client_id.as_deref().unwrap_or(default_client_id);
+        if client_id.is_empty() {
+            return Err(MqttError::EmptyClientId);
+        }
+
+        let mut options = MqttOptions::new(client_id, &self.host, self.port);
+        options.set_keep_alive(Duration::from_secs(self.keep_alive_secs.into()));
+        options.set_max_packet_size(self.max_packet_size, self.max_packet_size);
+
+        match (&self.user, &self.password) {
+            (Some(user), password) => {
+                let password = password.as_ref().map_or("", |password| password.inner());
+                options.set_credentials(user, password);
+            }
+            (None, Some(_)) => return Err(MqttError::MissingUser),
+            (None, None) => {}
+        }
+
+        if let MaybeTlsSettings::Tls(tls) =
+            MaybeTlsSettings::from_config(&self.tls, false).context(TlsSnafu)?
+        {
+            let ca = tls.authorities_pem().flatten().collect();
+            let client_auth = tls.identity_pem();
+            options.set_transport(Transport::Tls(TlsConfiguration::Simple {
+                ca,
+                alpn: None,
+                client_auth,
+            }));
+        }
+
+        Ok(options)
+    }
+}
+
+/// Derives a client ID from a component ID, as MQTT client IDs are limited to alphanumeric
+/// characters by some brokers.
+pub(crate) fn default_client_id(component_id: &str) -> String {
+    let id = component_id
+        .chars()
+        .filter(char::is_ascii_alphanumeric)
+        .collect::<String>();
+    format!("vector{id}")
+}
+
+/// Generates a random client ID, for clients that don't need a persistent session.
+pub(crate) fn random_client_id() -> String {
+    let id = rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(16)
+        .map(char::from)
+        .collect::<String>();
+    format!("vector{id}")
+}
+
+/// A minimal MQTT 3.1.1 broker, for testing the source and sink without a real broker.
+#[cfg(test)]
+pub(crate) mod test_util {
+    use std::net::SocketAddr;
+
+    use bytes::{Buf, BufMut, Bytes, BytesMut};
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::{TcpListener, TcpStream},
+    };
+
+    const CONNECT: u8 = 1;
+    const PUBLISH: u8 = 3;
+    const PUBACK: u8 = 4;
+    const SUBSCRIBE: u8 = 8;
+    const PINGREQ: u8 = 12;
+
+    /// A message published by a client.
+    #[derive(Debug)]
+    pub(crate) struct Published {
+        pub(crate) topic: String,
+        pub(crate) payload: Bytes,
+        pub(crate) qos: u8,
+        pub(crate) retain: bool,
+    }
+
+    /// Listens for the connection of a single client.
+    pub(crate) struct MockBroker {
+        listener: TcpListener,
+    }
+
+    impl MockBroker {
+        pub(crate) async fn bind() -> Self {
+            let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+            Self { listener }
+        }
+
+        pub(crate) fn addr(&self) -> SocketAddr {
+            self.listener.local_addr().unwrap()
+        }
+
+        /// Accepts the next connection, and acknowledges it.
+        pub(crate) async fn accept(&self) -> MockConnection {
+            let (stream, _) = self.listener.accept().await.unwrap();
+            let mut connection = MockConnection { stream };
+            let (packet_type, _, _) = connection.read().await.expect("client disconnected");
+            assert_eq!(packet_type, CONNECT);
+            connection.write(&[0x20, 0x02, 0x00, 0x00]).await;
+            connection
+        }
+    }
+
+    pub(crate) struct MockConnection {
+        stream: TcpStream,
+    }
+
+    impl MockConnection {
+        /// Reads the next packet from the client, returning its type, flags and body.
+        ///
+        /// Pings are answered without being returned, and `None` is returned once the client has
+        /// disconnected.
+        pub(crate) async fn read(&mut self) -> Option<(u8, u8, Bytes)> {
+            loop {
+                let header = self.stream.read_u8().await.ok()?;
+                let mut len = 0;
+                for shift in (0..28).step_by(7) {
+                    let byte = self.stream.read_u8().await.ok()?;
+                    len |= usize::from(byte & 0x7f) << shift;
+                    if byte & 0x80 == 0 {
+                        break;
+                    }
+                }
+                let mut body = vec![0; len];
+                self.stream.read_exact(&mut body).await.ok()?;
+
+                if header >> 4 == PINGREQ {
+                    self.write(&[0xd0, 0x00]).await;
+                } else {
+                    return Some((header >> 4, header & 0x0f, body.into()));
+                }
+            }
+        }
+
+        pub(crate) async fn write(&mut self, packet: &[u8]) {
+            self.stream.write_all(packet).await.unwrap();
+        }
+
+        /// Waits for a subscription, and grants it with the requested quality of service.
+        pub(crate) async fn expect_subscribe(&mut self) -> Vec<String> {
+            let (packet_type, _, mut body) = self.read().await.expect("client disconnected");
+            assert_eq!(packet_type, SUBSCRIBE);
+
+            let pkid = body.get_u16();
+            let mut topics = Vec::new();
+            let mut ack = vec![0x90, 0x00, (pkid >> 8) as u8, pkid as u8];
+            while body.has_remaining() {
+                let len = body.get_u16().into();
+                topics.push(String::from_utf8(body.split_to(len).to_vec()).unwrap());
+                ack.push(body.get_u8());
+            }
+            ack[1] = (ack.len() - 2) as u8;
+            self.write(&ack).await;
+            topics
+        }
+
+        /// Publishes a message to the client with a quality of service of `at_least_once`.
+        pub(crate) async fn publish(&mut self, pkid: u16, topic: &str, payload: &str) {
+            let mut body = BytesMut::new();
+            body.put_u16(topic.len() as u16);
+            body.put_slice(topic.as_bytes());
+            body.put_u16(pkid);
+            body.put_slice(payload.as_bytes());
+
+            let mut packet = BytesMut::new();
+            packet.put_u8(0x32);
+            let mut len = body.len();
+            loop {
+                let byte = (len & 0x7f) as u8;
+                len >>= 7;
+                if len == 0 {
+                    packet.put_u8(byte);
+                    break;
+                }
+                packet.put_u8(byte | 0x80);
+            }
+            packet.put_slice(&body);
+            self.write(&packet).await;
+        }
+
+        /// Reads the next acknowledgement of a message published to the client.
+        pub(crate) async fn read_puback(&mut self) -> Option<u16> {
+            let (packet_type, _, mut body) = self.read().await?;
+            assert_eq!(packet_type, PUBACK);
+            Some(body.get_u16())
+        }
+
+        /// Reads the next message published by the client, acknowledging it.
+        pub(crate) async fn read_publish(&mut self) -> Option<Published> {
+            let (pkid, published) = self.read_unacknowledged_publish().await?;
+            if let Some(pkid) = pkid {
+                self.puback(pkid).await;
+            }
+            Some(published)
+        }
+
+        /// Reads the next message published by the client, returning its packet identifier if it
+        /// must be acknowledged.
+        pub(crate) async fn read_unacknowledged_publish(
+            &mut self,
+        ) -> Option<(Option<u16>, Published)> {
+            let (packet_type, flags, mut body) = self.read().await?;
+            if packet_type != PUBLISH {
+                return None;
+            }
+
+            let len = body.get_u16().into();
+            let topic = String::from_utf8(body.split_to(len).to_vec()).unwrap();
+            let qos = (flags >> 1) & 0x03;
+            let pkid = (qos > 0).then(|| body.get_u16());
+            let published = Published {
+                topic,
+                payload: body,
+                qos,
+                retain: flags & 0x01 != 0,
+            };
+            Some((pkid, published))
+        }
+
+        /// Acknowledges a message published by the client with a quality of service of
+        /// `at_least_once`.
+        pub(crate) async fn puback(&mut self, pkid: u16) {
+            self.write(&[0x40, 0x02, (pkid >> 8) as u8, pkid as u8])
+                .await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds_options() {
+        let config = MqttCommonConfig {
+            host: "localhost".to_string(),
+            user: Some("user".to_string()),
+            password: Some("secret".to_string().into()),
+            ..Default::default()
+        };
+
+        let options = config.mqtt_options("vectormqtt").unwrap();
+        assert_eq!(options.client_id(), "vectormqtt");
+        assert_eq!(options.broker_address(), ("localhost".to_string(), 1883));
+        assert_eq!(
+            options.credentials(),
+            Some(("user".to_string(), "secret".to_string()))
+        );
+        assert_eq!(options.keep_alive(), Duration::from_secs(60));
+    }
+
+    #[test]
+    fn rejects_invalid_options() {
+        let config = MqttCommonConfig {
+            host: "localhost".to_string(),
+            client_id: Some(String::new()),
+            ..Default::default()
+        };
+        assert!(matches!(
+            config.mqtt_options("vectormqtt"),
+            Err(MqttError::EmptyClientId)
+        ));
+
+        let config = MqttCommonConfig {
+            host: "localhost".to_string(),
+            password: Some("secret".to_string().into()),
+            ..Default::default()
+        };
+        assert!(matches!(
+            config.mqtt_options("vectormqtt"),
+            Err(MqttError::MissingUser)
+        ));
+    }
+
+    #[test]
+    fn derives_client_id() {
+        assert_eq!(default_client_id("edge.mqtt-in_1"), "vectoredgemqttin1");
+    }
+}
@@ -5,7 +5,7 @@ use hyper::Body;
 use tokio::{pin, select, sync::mpsc};
 use tonic::{
     body::BoxBody,
-    transport::{Channel, Endpoint, NamedService},
+    transport::{server::Routes, Channel, Endpoint, NamedService},
     Status,
 };
 use tower::Service;
@@ -165,7 +165,7 @@ pub fn spawn_grpc_server<S>(
         let server = run_grpc_server(
             listen_addr.as_socket_addr(),
             tls_settings,
-            service,
+            Routes::new(service),
             shutdown_signal,
         );
         pin!(server);
@@ -2,10 +2,13 @@ use std::net::{Ipv4Addr, SocketAddr};
 
 use url::Url;
 use vector_lib::configurable::configurable_component;
+use vector_lib::sensitive_string::SensitiveString;
+
+use crate::{http::Auth, tls::TlsEnableableConfig};
 
 /// API options.
 #[configurable_component]
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq)]
 #[serde(default, deny_unknown_fields)]
 pub struct Options {
     /// Whether or not the API endpoint is available.
@@ -23,6 +26,28 @@ pub struct Options {
     /// Whether or not the GraphQL endpoint is enabled
     #[serde(default = "default_graphql", skip_serializing_if = "is_true")]
     pub graphql: bool,
+
+    /// The token that GraphQL mutations must be authorized with.
+    ///
+    /// Mutations control the running instance, for example by pausing components or reloading the
+    /// configuration, so they are disabled unless this is set. Requests must then send the token
+    /// in an `Authorization: Bearer <token>` header.
+    ///
+    /// This can't be set along with `auth`, in which case every authenticated request is allowed
+    /// to run mutations.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mutation_token: Option<SensitiveString>,
+
+    /// The credentials that requests to the GraphQL and playground endpoints must carry.
+    ///
+    /// The health endpoint is left open, so that it can still be used as a liveness probe.
+    #[configurable(derived)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auth: Option<Auth>,
+
+    #[configurable(derived)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tls: Option<TlsEnableableConfig>,
 }
 
 impl Default for Options {
@@ -32,6 +57,9 @@ impl Default for Options {
             playground: default_playground(),
             address: default_address(),
             graphql: default_graphql(),
+            mutation_token: None,
+            auth: None,
+            tls: None,
         }
     }
 }
@@ -94,6 +122,13 @@ impl Options {
             enabled: self.enabled | other.enabled,
             playground: self.playground & other.playground,
             graphql: self.graphql & other.graphql,
+            mutation_token: merge_option(
+                &self.mutation_token,
+                other.mutation_token,
+                "mutation token",
+            )?,
+            auth: merge_option(&self.auth, other.auth, "auth")?,
+            tls: merge_option(&self.tls, other.tls, "tls")?,
         };
 
         *self = options;
@@ -101,6 +136,20 @@ impl Options {
     }
 }
 
+/// Merges an option that can only be set once, or to the same value everywhere.
+fn merge_option<T: Clone + PartialEq>(
+    a: &Option<T>,
+    b: Option<T>,
+    name: &str,
+) -> Result<Option<T>, String> {
+    match (a, b) {
+        (None, b) => Ok(b),
+        (Some(a), None) => Ok(Some(a.clone())),
+        (Some(a), Some(b)) if *a == b => Ok(Some(b)),
+        (Some(_), Some(_)) => Err(format!("Conflicting `api` {}.", name)),
+    }
+}
+
 #[test]
 fn bool_merge() {
     let mut a = Options {
@@ -108,6 +157,9 @@ fn bool_merge() {
         address: None,
         playground: false,
         graphql: false,
+        mutation_token: None,
+        auth: None,
+        tls: None,
     };
 
     a.merge(Options::default()).unwrap();
@@ -118,7 +170,10 @@ fn bool_merge() {
             enabled: true,
             address: default_address(),
             playground: false,
-            graphql: false
+            graphql: false,
+            mutation_token: None,
+            auth: None,
+            tls: None,
         }
     );
 }
@@ -131,6 +186,9 @@ fn bind_merge() {
         address: Some(address),
         playground: true,
         graphql: true,
+        mutation_token: None,
+        auth: None,
+        tls: None,
     };
 
     a.merge(Options::default()).unwrap();
@@ -142,6 +200,9 @@ fn bind_merge() {
             address: Some(address),
             playground: true,
             graphql: true,
+            mutation_token: None,
+            auth: None,
+            tls: None,
         }
     );
 }
@@ -160,3 +221,21 @@ fn bind_conflict() {
 
     assert!(a.merge(b).is_err());
 }
+
+#[test]
+fn mutation_token_conflict() {
+    let mut a = Options {
+        mutation_token: Some("one".to_owned().into()),
+        ..Options::default()
+    };
+
+    a.merge(Options::default()).unwrap();
+    assert_eq!(a.mutation_token, Some("one".to_owned().into()));
+
+    let b = Options {
+        mutation_token: Some("two".to_owned().into()),
+        ..Options::default()
+    };
+
+    assert!(a.merge(b).is_err());
+}
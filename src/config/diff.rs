@@ -18,10 +18,23 @@ impl ConfigDiff {
     }
 
     pub fn new(old: &Config, new: &Config) -> Self {
+        let mut sinks = Difference::new(&old.sinks, &new.sinks);
+
+        // Sinks only capture the events they fail to deliver when their `errors` output is used,
+        // so a sink has to be rebuilt whenever a component starts or stops consuming that output.
+        let errors_output_changed = new
+            .sinks
+            .keys()
+            .filter(|key| old.sinks.contains_key(*key) && !sinks.to_change.contains(*key))
+            .filter(|key| old.sink_errors_output_used(key) != new.sink_errors_output_used(key))
+            .cloned()
+            .collect::<Vec<_>>();
+        sinks.to_change.extend(errors_output_changed);
+
         ConfigDiff {
             sources: Difference::new(&old.sources, &new.sources),
             transforms: Difference::new(&old.transforms, &new.transforms),
-            sinks: Difference::new(&old.sinks, &new.sinks),
+            sinks,
             enrichment_tables: Difference::new(&old.enrichment_tables, &new.enrichment_tables),
         }
     }
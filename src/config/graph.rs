@@ -1,5 +1,6 @@
 use indexmap::{set::IndexSet, IndexMap};
 use std::collections::{HashMap, HashSet, VecDeque};
+use vector_lib::sink_errors::ERRORS_OUTPUT;
 
 use super::{
     schema, ComponentKey, DataType, OutputId, SinkOuter, SourceOuter, SourceOutput, TransformOuter,
@@ -162,10 +163,13 @@ impl Graph {
 
     /// Return the output type associated with a given `OutputId`.
     ///
+    /// Sinks only have an `errors` output, which carries the events the sink failed to deliver, and
+    /// so has the same type as the input of the sink.
+    ///
     /// # Panics
     ///
-    /// Will panic if the given id is not present in the graph or identifies a sink, which can't
-    /// have inputs.
+    /// Will panic if the given id is not present in the graph, or identifies an output that the
+    /// component doesn't have.
     fn get_output_type(&self, id: &OutputId) -> DataType {
         match &self.nodes[&id.component] {
             Node::Source { outputs } => outputs
@@ -178,7 +182,14 @@ impl Graph {
                 .find(|output| output.port == id.port)
                 .map(|output| output.ty)
                 .expect("output didn't exist"),
-            Node::Sink { .. } => panic!("no outputs on sinks"),
+            Node::Sink { ty } => {
+                assert_eq!(
+                    id.port.as_deref(),
+                    Some(ERRORS_OUTPUT),
+                    "sinks only have an errors output"
+                );
+                *ty
+            }
         }
     }
 
@@ -263,7 +274,10 @@ impl Graph {
         self.nodes
             .iter()
             .flat_map(|(key, node)| match node {
-                Node::Sink { .. } => vec![],
+                Node::Sink { .. } => vec![OutputId {
+                    component: key.clone(),
+                    port: Some(ERRORS_OUTPUT.to_string()),
+                }],
                 Node::Source { outputs } => outputs
                     .iter()
                     .map(|output| OutputId {
@@ -291,6 +305,7 @@ impl Graph {
     ///
     ///   1. A component that's part of an expanded macro (e.g. `route.branch`)
     ///   2. A named output of a branching transform (e.g. `name.errors`)
+    ///   3. The `errors` output of a sink (e.g. `sink_name.errors`)
     ///
     /// A naive way to do that is to compare the string representation of all valid inputs to the
     /// provided string and pick the one that matches. This works better if you can assume that there
@@ -632,6 +647,58 @@ mod test {
         );
     }
 
+    #[test]
+    fn allows_sink_errors_output() {
+        let mut graph = Graph::default();
+        graph.add_source("log_source", DataType::Log);
+        graph.add_sink("http_sink", DataType::Log, vec!["log_source"]);
+        graph.add_transform("parse", DataType::Log, DataType::Log, vec![]);
+        graph.add_sink("dead_letter_sink", DataType::Log, vec![]);
+        graph.add_sink("bad_sink", DataType::Log, vec![]);
+
+        assert_eq!(Ok(()), graph.test_add_input("parse", "http_sink.errors"));
+        assert_eq!(
+            Ok(()),
+            graph.test_add_input("dead_letter_sink", "http_sink.errors")
+        );
+        assert_eq!(Ok(()), graph.typecheck());
+        assert_eq!(Ok(()), graph.check_for_cycles());
+
+        let expected =
+            "Input \"http_sink\" for sink \"bad_sink\" doesn't match any components.".to_string();
+        assert_eq!(Err(expected), graph.test_add_input("bad_sink", "http_sink"));
+    }
+
+    #[test]
+    fn detects_type_mismatches_for_sink_errors_output() {
+        let mut graph = Graph::default();
+        graph.add_source("metric_source", DataType::Metric);
+        graph.add_sink("metric_sink", DataType::Metric, vec!["metric_source"]);
+        graph.add_sink("log_sink", DataType::Log, vec![]);
+
+        assert_eq!(
+            Ok(()),
+            graph.test_add_input("log_sink", "metric_sink.errors")
+        );
+        assert_eq!(
+            Err(vec![
+                "Data type mismatch between metric_sink.errors (Metric) and log_sink (Log)".into()
+            ]),
+            graph.typecheck()
+        );
+    }
+
+    #[test]
+    fn detects_cycles_through_sink_errors_output() {
+        let mut graph = Graph::default();
+        graph.add_source("in", DataType::Log);
+        graph.add_transform("one", DataType::Log, DataType::Log, vec!["in"]);
+        graph.add_sink("out", DataType::Log, vec!["one"]);
+
+        assert_eq!(Ok(()), graph.test_add_input("one", "out.errors"));
+        assert!(graph.check_for_cycles().is_err());
+    }
+
     #[test]
     fn disallows_ambiguous_inputs() {
         let mut graph = Graph::default();
@@ -155,6 +155,20 @@ impl Config {
         self.sinks.get(id)
     }
 
+    /// Returns `true` if the `errors` output of the sink with the given key is used as an input by
+    /// any component.
+    pub fn sink_errors_output_used(&self, key: &ComponentKey) -> bool {
+        let errors_output = OutputId {
+            component: key.clone(),
+            port: Some(vector_lib::sink_errors::ERRORS_OUTPUT.to_string()),
+        };
+        self.transforms
+            .values()
+            .flat_map(|transform| transform.inputs.iter())
+            .chain(self.sinks.values().flat_map(|sink| sink.inputs.iter()))
+            .any(|input| input == &errors_output)
+    }
+
     pub fn inputs_for_node(&self, id: &ComponentKey) -> Option<&[OutputId]> {
         self.transforms
             .get(id)
@@ -202,6 +216,15 @@ impl Config {
                     .map(|input| (sink.clone(), input.clone()))
                     .collect();
                 self.propagate_acks_rec(inputs);
+            } else if let Some(upstream_sink) = self.sinks.get(component) {
+                // Events sent to the `errors` output of a sink keep the finalizers of the events
+                // received by it, so acknowledgements go all the way back to its sources.
+                let inputs = upstream_sink
+                    .inputs
+                    .iter()
+                    .map(|input| (sink.clone(), input.clone()))
+                    .collect();
+                self.propagate_acks_rec(inputs);
             }
         }
     }
@@ -54,6 +54,16 @@ pub fn check_shape(config: &ConfigBuilder) -> Result<(), Vec<String>> {
         }
     }
 
+    // Both use the `Authorization` header, which can only carry one of them.
+    #[cfg(feature = "api")]
+    if config.api.auth.is_some() && config.api.mutation_token.is_some() {
+        errors.push(
+            "`api.mutation_token` can't be set along with `api.auth`, authenticated requests are \
+             allowed to run mutations instead."
+                .to_owned(),
+        );
+    }
+
     // Helper for below
     fn tagged<'a>(
         tag: &'static str,
@@ -31,13 +31,35 @@ const RETRY_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
 pub fn spawn_thread<'a>(
     config_paths: impl IntoIterator<Item = &'a PathBuf> + 'a,
     delay: impl Into<Option<Duration>>,
+) -> Result<(), Error> {
+    spawn_thread_with_callback(config_paths, delay, false, || {
+        raise_sighup();
+        true
+    })
+}
+
+/// Calls `on_change` when any file or directory on config_paths changes, watching directories
+/// recursively if `recursive` is set.
+/// Accumulates file changes until no change for given duration has occurred.
+/// The thread stops watching once `on_change` returns `false`.
+#[cfg(unix)]
+pub fn spawn_thread_with_callback<'a>(
+    config_paths: impl IntoIterator<Item = &'a PathBuf> + 'a,
+    delay: impl Into<Option<Duration>>,
+    recursive: bool,
+    mut on_change: impl FnMut() -> bool + Send + 'static,
 ) -> Result<(), Error> {
     let config_paths: Vec<_> = config_paths.into_iter().cloned().collect();
     let delay = delay.into().unwrap_or(CONFIG_WATCH_DELAY);
+    let mode = if recursive {
+        RecursiveMode::Recursive
+    } else {
+        RecursiveMode::NonRecursive
+    };
 
     // Create watcher now so not to miss any changes happening between
     // returning from this function and the thread starting.
-    let mut watcher = Some(create_watcher(&config_paths)?);
+    let mut watcher = Some(create_watcher(&config_paths, mode)?);
 
     info!("Watching configuration files.");
 
@@ -56,8 +78,8 @@ pub fn spawn_thread<'a>(
                     debug!(message = "Consumed file change events for delay.", delay = ?delay);
 
                     // We need to read paths to resolve any inode changes that may have happened.
-                    // And we need to do it before raising sighup to avoid missing any change.
-                    if let Err(error) = add_paths(&mut watcher, &config_paths) {
+                    // And we need to do it before notifying to avoid missing any change.
+                    if let Err(error) = add_paths(&mut watcher, &config_paths, mode) {
                         error!(message = "Failed to read files to watch.", %error);
                         break;
                     }
@@ -65,7 +87,9 @@ pub fn spawn_thread<'a>(
                     debug!(message = "Reloaded paths.");
 
                     info!("Configuration file changed.");
-                    raise_sighup();
+                    if !on_change() {
+                        return;
+                    }
                 } else {
                     debug!(message = "Ignoring event.", event = ?event)
                 }
@@ -74,16 +98,18 @@ pub fn spawn_thread<'a>(
 
         thread::sleep(RETRY_TIMEOUT);
 
-        watcher = create_watcher(&config_paths)
+        watcher = create_watcher(&config_paths, mode)
             .map_err(|error| error!(message = "Failed to create file watcher.", %error))
             .ok();
 
         if watcher.is_some() {
             // Config files could have changed while we weren't watching,
-            // so for a good measure raise SIGHUP and let reload logic
+            // so for a good measure notify and let reload logic
             // determine if anything changed.
             info!("Speculating that configuration files have changed.");
-            raise_sighup();
+            if !on_change() {
+                return;
+            }
         }
     });
 
@@ -99,6 +125,17 @@ pub fn spawn_thread<'a>(
     Err("Reloading config on Windows isn't currently supported. Related issue https://github.com/vectordotdev/vector/issues/938 .".into())
 }
 
+#[cfg(windows)]
+/// Errors on Windows.
+pub fn spawn_thread_with_callback<'a>(
+    _config_paths: impl IntoIterator<Item = &'a PathBuf> + 'a,
+    _delay: impl Into<Option<Duration>>,
+    _recursive: bool,
+    _on_change: impl FnMut() -> bool + Send + 'static,
+) -> Result<(), Error> {
+    Err("Watching configuration files on Windows isn't currently supported. Related issue https://github.com/vectordotdev/vector/issues/938 .".into())
+}
+
 #[cfg(unix)]
 fn raise_sighup() {
     use nix::sys::signal;
@@ -110,6 +147,7 @@ fn raise_sighup() {
 #[cfg(unix)]
 fn create_watcher(
     config_paths: &[PathBuf],
+    mode: RecursiveMode,
 ) -> Result<
     (
         RecommendedWatcher,
@@ -120,14 +158,18 @@ fn create_watcher(
     info!("Creating configuration file watcher.");
     let (sender, receiver) = channel();
     let mut watcher = recommended_watcher(sender)?;
-    add_paths(&mut watcher, config_paths)?;
+    add_paths(&mut watcher, config_paths, mode)?;
     Ok((watcher, receiver))
 }
 
 #[cfg(unix)]
-fn add_paths(watcher: &mut RecommendedWatcher, config_paths: &[PathBuf]) -> Result<(), Error> {
+fn add_paths(
+    watcher: &mut RecommendedWatcher,
+    config_paths: &[PathBuf],
+    mode: RecursiveMode,
+) -> Result<(), Error> {
     for path in config_paths {
-        watcher.watch(path, RecursiveMode::NonRecursive)?;
+        watcher.watch(path, mode)?;
     }
     Ok(())
 }
@@ -199,4 +241,30 @@ mod tests {
             panic!("Test timed out");
         }
     }
+
+    #[tokio::test]
+    async fn recursive_directory_update_with_callback() {
+        trace_init();
+
+        let delay = Duration::from_secs(3);
+        let dir = temp_dir().to_path_buf();
+        let sub_dir = dir.join("sinks");
+        let file_path = sub_dir.join("out.toml");
+
+        std::fs::create_dir_all(&sub_dir).unwrap();
+        let mut file = File::create(&file_path).unwrap();
+
+        let (tx, rx) = std::sync::mpsc::channel();
+        spawn_thread_with_callback(&[dir], delay, true, move || tx.send(()).is_ok()).unwrap();
+
+        file.write_all(&[0]).unwrap();
+        file.sync_all().unwrap();
+
+        let changed = tokio::task::spawn_blocking(move || rx.recv_timeout(delay * 5))
+            .await
+            .unwrap();
+        if changed.is_err() {
+            panic!("Test timed out");
+        }
+    }
 }
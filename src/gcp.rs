@@ -173,7 +173,10 @@ impl GcpAuthenticator {
                     .path_and_query
                     .as_ref()
                     .map_or("/", PathAndQuery::path);
-                let paq = format!("{path}?key={api_key}");
+                let paq = match parts.path_and_query.as_ref().and_then(PathAndQuery::query) {
+                    Some(query) => format!("{path}?{query}&key={api_key}"),
+                    None => format!("{path}?key={api_key}"),
+                };
                 // The API key is verified above to only contain
                 // URL-safe characters. That key is added to a path
                 // that came from a successfully parsed URI. As such,
@@ -333,6 +336,10 @@ mod tests {
             apply_uri(&auth, "http://example.com/path1/"),
             format!("http://example.com/path1/?key={key}")
         );
+        assert_eq!(
+            apply_uri(&auth, "http://example.com/path?prefix=logs"),
+            format!("http://example.com/path?prefix=logs&key={key}")
+        );
     }
 
     #[tokio::test]
@@ -1,5 +1,7 @@
 use metrics::counter;
+use vector_lib::event::MetricKind;
 use vector_lib::internal_event::InternalEvent;
+use vector_lib::internal_event::{error_stage, error_type, ComponentEventsDropped, UNINTENTIONAL};
 
 #[derive(Debug)]
 pub struct AggregateEventRecorded;
@@ -27,3 +29,33 @@ impl InternalEvent for AggregateUpdateFailed {
         counter!("aggregate_failed_updates", 1);
     }
 }
+
+#[derive(Debug)]
+pub struct AggregateModeMismatch {
+    pub mode: &'static str,
+    pub kind: MetricKind,
+    pub value_type: &'static str,
+}
+
+impl InternalEvent for AggregateModeMismatch {
+    fn emit(self) {
+        let reason = "Aggregation mode does not apply to metric.";
+        error!(
+            message = reason,
+            mode = self.mode,
+            metric_kind = ?self.kind,
+            value_type = self.value_type,
+            error_code = "mode_mismatch",
+            error_type = error_type::CONDITION_FAILED,
+            stage = error_stage::PROCESSING,
+            internal_log_rate_limit = true,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "mode_mismatch",
+            "error_type" => error_type::CONDITION_FAILED,
+            "stage" => error_stage::PROCESSING,
+        );
+        emit!(ComponentEventsDropped::<UNINTENTIONAL> { count: 1, reason });
+    }
+}
@@ -1,4 +1,9 @@
-use vector_lib::internal_event::{ComponentEventsDropped, InternalEvent, INTENTIONAL};
+use std::{io::Error, path::Path};
+
+use metrics::counter;
+use vector_lib::internal_event::{
+    error_stage, error_type, ComponentEventsDropped, InternalEvent, INTENTIONAL,
+};
 
 #[derive(Debug)]
 pub struct DedupeEventsDropped {
@@ -13,3 +18,55 @@ impl InternalEvent for DedupeEventsDropped {
         });
     }
 }
+
+#[derive(Debug)]
+pub struct DedupeCacheLoadError<'a> {
+    pub error: Error,
+    pub path: &'a Path,
+}
+
+impl<'a> InternalEvent for DedupeCacheLoadError<'a> {
+    fn emit(self) {
+        error!(
+            message = "Failed to load persisted deduplication cache, starting with an empty cache.",
+            path = %self.path.display(),
+            error = %self.error,
+            error_code = "cache_load_failed",
+            error_type = error_type::READER_FAILED,
+            stage = error_stage::PROCESSING,
+            internal_log_rate_limit = true,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "cache_load_failed",
+            "error_type" => error_type::READER_FAILED,
+            "stage" => error_stage::PROCESSING,
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct DedupeCachePersistError<'a> {
+    pub error: Error,
+    pub path: &'a Path,
+}
+
+impl<'a> InternalEvent for DedupeCachePersistError<'a> {
+    fn emit(self) {
+        error!(
+            message = "Failed to persist deduplication cache.",
+            path = %self.path.display(),
+            error = %self.error,
+            error_code = "cache_persist_failed",
+            error_type = error_type::WRITER_FAILED,
+            stage = error_stage::PROCESSING,
+            internal_log_rate_limit = true,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "cache_persist_failed",
+            "error_type" => error_type::WRITER_FAILED,
+            "stage" => error_stage::PROCESSING,
+        );
+    }
+}
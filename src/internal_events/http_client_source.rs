@@ -1,3 +1,5 @@
+use std::{io::Error, path::Path};
+
 use metrics::counter;
 use vector_lib::internal_event::InternalEvent;
 use vector_lib::{
@@ -83,3 +85,55 @@ impl InternalEvent for HttpClientHttpError {
         );
     }
 }
+
+#[derive(Debug)]
+pub struct HttpClientCheckpointLoadError<'a> {
+    pub error: Error,
+    pub path: &'a Path,
+}
+
+impl<'a> InternalEvent for HttpClientCheckpointLoadError<'a> {
+    fn emit(self) {
+        error!(
+            message = "Failed to load checkpoint, starting from the first page.",
+            path = %self.path.display(),
+            error = %self.error,
+            error_code = "checkpoint_load_failed",
+            error_type = error_type::READER_FAILED,
+            stage = error_stage::RECEIVING,
+            internal_log_rate_limit = true,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "checkpoint_load_failed",
+            "error_type" => error_type::READER_FAILED,
+            "stage" => error_stage::RECEIVING,
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct HttpClientCheckpointPersistError<'a> {
+    pub error: Error,
+    pub path: &'a Path,
+}
+
+impl<'a> InternalEvent for HttpClientCheckpointPersistError<'a> {
+    fn emit(self) {
+        error!(
+            message = "Failed to persist checkpoint.",
+            path = %self.path.display(),
+            error = %self.error,
+            error_code = "checkpoint_persist_failed",
+            error_type = error_type::WRITER_FAILED,
+            stage = error_stage::RECEIVING,
+            internal_log_rate_limit = true,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "checkpoint_persist_failed",
+            "error_type" => error_type::WRITER_FAILED,
+            "stage" => error_stage::RECEIVING,
+        );
+    }
+}
@@ -0,0 +1,57 @@
+use metrics::counter;
+use rumqttc::ConnectionError;
+#[cfg(feature = "sources-mqtt")]
+use vector_lib::internal_event::error_stage;
+use vector_lib::internal_event::error_type;
+use vector_lib::internal_event::InternalEvent;
+
+#[derive(Debug)]
+pub struct MqttConnectionError {
+    pub error: ConnectionError,
+    pub stage: &'static str,
+}
+
+impl InternalEvent for MqttConnectionError {
+    fn emit(self) {
+        error!(
+            message = "MQTT connection error, reconnecting.",
+            error = %self.error,
+            error_code = "mqtt_connection_error",
+            error_type = error_type::CONNECTION_FAILED,
+            stage = self.stage,
+            internal_log_rate_limit = true,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "mqtt_connection_error",
+            "error_type" => error_type::CONNECTION_FAILED,
+            "stage" => self.stage,
+        );
+    }
+}
+
+#[cfg(feature = "sources-mqtt")]
+#[derive(Debug)]
+pub struct MqttSubscriptionRejected<'a> {
+    pub topic: &'a str,
+}
+
+#[cfg(feature = "sources-mqtt")]
+impl<'a> InternalEvent for MqttSubscriptionRejected<'a> {
+    fn emit(self) {
+        error!(
+            message = "Subscription rejected by the broker.",
+            topic = %self.topic,
+            error_code = "mqtt_subscription_rejected",
+            error_type = error_type::REQUEST_FAILED,
+            stage = error_stage::RECEIVING,
+            internal_log_rate_limit = true,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "mqtt_subscription_rejected",
+            "error_type" => error_type::REQUEST_FAILED,
+            "stage" => error_stage::RECEIVING,
+        );
+    }
+}
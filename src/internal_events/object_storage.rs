@@ -0,0 +1,135 @@
+use std::{io::Error, path::Path};
+
+use metrics::counter;
+use vector_lib::internal_event::InternalEvent;
+use vector_lib::internal_event::{error_stage, error_type};
+
+use crate::sources::util::object_storage::listing::IngestError;
+
+#[derive(Debug)]
+pub struct ObjectStorageListError<'a> {
+    pub error: crate::Error,
+    pub container: &'a str,
+    pub prefix: &'a str,
+}
+
+impl<'a> InternalEvent for ObjectStorageListError<'a> {
+    fn emit(self) {
+        error!(
+            message = "Failed to list objects.",
+            container = %self.container,
+            prefix = %self.prefix,
+            error = %self.error,
+            error_code = "failed_listing_objects",
+            error_type = error_type::REQUEST_FAILED,
+            stage = error_stage::RECEIVING,
+            internal_log_rate_limit = true,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "failed_listing_objects",
+            "error_type" => error_type::REQUEST_FAILED,
+            "stage" => error_stage::RECEIVING,
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct ObjectStorageIngestError<'a> {
+    pub error: IngestError,
+    pub container: &'a str,
+    pub key: &'a str,
+}
+
+impl<'a> InternalEvent for ObjectStorageIngestError<'a> {
+    fn emit(self) {
+        error!(
+            message = "Failed to ingest object.",
+            container = %self.container,
+            key = %self.key,
+            error = %self.error,
+            error_code = "failed_ingesting_object",
+            error_type = error_type::READER_FAILED,
+            stage = error_stage::RECEIVING,
+            internal_log_rate_limit = true,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "failed_ingesting_object",
+            "error_type" => error_type::READER_FAILED,
+            "stage" => error_stage::RECEIVING,
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct ObjectStorageObjectSkipped<'a> {
+    pub container: &'a str,
+    pub key: &'a str,
+    pub attempts: usize,
+}
+
+impl<'a> InternalEvent for ObjectStorageObjectSkipped<'a> {
+    fn emit(self) {
+        // The failures are already counted by `ObjectStorageIngestError`.
+        error!(
+            message = "Skipping object after failing to ingest it repeatedly.",
+            container = %self.container,
+            key = %self.key,
+            attempts = %self.attempts,
+            internal_log_rate_limit = true,
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct ObjectStorageCheckpointLoadError<'a> {
+    pub error: Error,
+    pub path: &'a Path,
+}
+
+impl<'a> InternalEvent for ObjectStorageCheckpointLoadError<'a> {
+    fn emit(self) {
+        error!(
+            message = "Failed to load checkpoint, ingesting all objects.",
+            path = %self.path.display(),
+            error = %self.error,
+            error_code = "checkpoint_load_failed",
+            error_type = error_type::READER_FAILED,
+            stage = error_stage::RECEIVING,
+            internal_log_rate_limit = true,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "checkpoint_load_failed",
+            "error_type" => error_type::READER_FAILED,
+            "stage" => error_stage::RECEIVING,
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct ObjectStorageCheckpointPersistError<'a> {
+    pub error: Error,
+    pub path: &'a Path,
+}
+
+impl<'a> InternalEvent for ObjectStorageCheckpointPersistError<'a> {
+    fn emit(self) {
+        error!(
+            message = "Failed to persist checkpoint.",
+            path = %self.path.display(),
+            error = %self.error,
+            error_code = "checkpoint_persist_failed",
+            error_type = error_type::WRITER_FAILED,
+            stage = error_stage::RECEIVING,
+            internal_log_rate_limit = true,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "checkpoint_persist_failed",
+            "error_type" => error_type::WRITER_FAILED,
+            "stage" => error_stage::RECEIVING,
+        );
+    }
+}
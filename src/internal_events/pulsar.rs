@@ -52,3 +52,60 @@ impl<F: std::fmt::Display> InternalEvent for PulsarPropertyExtractionError<F> {
         );
     }
 }
+
+#[derive(Debug)]
+pub struct PulsarReadError {
+    pub error: pulsar::Error,
+}
+
+impl InternalEvent for PulsarReadError {
+    fn emit(self) {
+        error!(
+            message = "Failed to read message.",
+            error = %self.error,
+            error_code = "reading_message",
+            error_type = error_type::READER_FAILED,
+            stage = error_stage::RECEIVING,
+            internal_log_rate_limit = true,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "reading_message",
+            "error_type" => error_type::READER_FAILED,
+            "stage" => error_stage::RECEIVING,
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct PulsarAcknowledgmentError {
+    pub error: pulsar::error::ConsumerError,
+    pub negative: bool,
+}
+
+impl InternalEvent for PulsarAcknowledgmentError {
+    fn emit(self) {
+        let (message, error_code) = if self.negative {
+            (
+                "Unable to negatively acknowledge message.",
+                "negative_acknowledgment_failed",
+            )
+        } else {
+            ("Unable to acknowledge message.", "acknowledgment_failed")
+        };
+        error!(
+            message = message,
+            error = %self.error,
+            error_code = error_code,
+            error_type = error_type::ACKNOWLEDGMENT_FAILED,
+            stage = error_stage::RECEIVING,
+            internal_log_rate_limit = true,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => error_code,
+            "error_type" => error_type::ACKNOWLEDGMENT_FAILED,
+            "stage" => error_stage::RECEIVING,
+        );
+    }
+}
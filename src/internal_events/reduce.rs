@@ -1,5 +1,7 @@
-use metrics::counter;
-use vector_lib::internal_event::InternalEvent;
+use std::{io::Error, path::Path};
+
+use metrics::{counter, gauge};
+use vector_lib::internal_event::{error_stage, error_type, InternalEvent};
 
 #[derive(Debug)]
 pub struct ReduceStaleEventFlushed;
@@ -9,3 +11,82 @@ impl InternalEvent for ReduceStaleEventFlushed {
         counter!("stale_events_flushed_total", 1);
     }
 }
+
+#[derive(Debug)]
+pub struct ReduceActiveGroups {
+    pub count: usize,
+}
+
+impl InternalEvent for ReduceActiveGroups {
+    fn emit(self) {
+        gauge!("reduce_active_groups", self.count as f64);
+    }
+}
+
+#[derive(Debug)]
+pub struct ReduceGroupEvicted {
+    pub reason: &'static str,
+}
+
+impl InternalEvent for ReduceGroupEvicted {
+    fn emit(self) {
+        debug!(
+            message = "Flushing group early to stay within limits.",
+            reason = self.reason,
+            internal_log_rate_limit = true,
+        );
+        counter!("reduce_evictions_total", 1, "reason" => self.reason);
+    }
+}
+
+#[derive(Debug)]
+pub struct ReduceStateLoadError<'a> {
+    pub error: Error,
+    pub path: &'a Path,
+}
+
+impl<'a> InternalEvent for ReduceStateLoadError<'a> {
+    fn emit(self) {
+        error!(
+            message = "Failed to restore persisted groups.",
+            path = %self.path.display(),
+            error = %self.error,
+            error_code = "state_load_failed",
+            error_type = error_type::READER_FAILED,
+            stage = error_stage::PROCESSING,
+            internal_log_rate_limit = true,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "state_load_failed",
+            "error_type" => error_type::READER_FAILED,
+            "stage" => error_stage::PROCESSING,
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct ReduceStatePersistError<'a> {
+    pub error: Error,
+    pub path: &'a Path,
+}
+
+impl<'a> InternalEvent for ReduceStatePersistError<'a> {
+    fn emit(self) {
+        error!(
+            message = "Failed to persist groups, flushing them instead.",
+            path = %self.path.display(),
+            error = %self.error,
+            error_code = "state_persist_failed",
+            error_type = error_type::WRITER_FAILED,
+            stage = error_stage::PROCESSING,
+            internal_log_rate_limit = true,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "state_persist_failed",
+            "error_type" => error_type::WRITER_FAILED,
+            "stage" => error_stage::PROCESSING,
+        );
+    }
+}
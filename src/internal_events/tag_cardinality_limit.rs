@@ -5,6 +5,7 @@ pub struct TagCardinalityLimitRejectingEvent<'a> {
     pub metric_name: &'a str,
     pub tag_key: &'a str,
     pub tag_value: &'a str,
+    pub include_extended_tags: bool,
 }
 
 impl<'a> InternalEvent for TagCardinalityLimitRejectingEvent<'a> {
@@ -16,7 +17,15 @@ impl<'a> InternalEvent for TagCardinalityLimitRejectingEvent<'a> {
             tag_value = self.tag_value,
             internal_log_rate_limit = true,
         );
-        counter!("tag_value_limit_exceeded_total", 1);
+        if self.include_extended_tags {
+            counter!(
+                "tag_value_limit_exceeded_total", 1,
+                "metric_name" => self.metric_name.to_string(),
+                "tag_key" => self.tag_key.to_string(),
+            );
+        } else {
+            counter!("tag_value_limit_exceeded_total", 1);
+        }
 
         emit!(ComponentEventsDropped::<INTENTIONAL> {
             count: 1,
@@ -29,6 +38,7 @@ pub struct TagCardinalityLimitRejectingTag<'a> {
     pub metric_name: &'a str,
     pub tag_key: &'a str,
     pub tag_value: &'a str,
+    pub include_extended_tags: bool,
 }
 
 impl<'a> InternalEvent for TagCardinalityLimitRejectingTag<'a> {
@@ -40,20 +50,39 @@ impl<'a> InternalEvent for TagCardinalityLimitRejectingTag<'a> {
             tag_value = self.tag_value,
             internal_log_rate_limit = true,
         );
-        counter!("tag_value_limit_exceeded_total", 1);
+        if self.include_extended_tags {
+            counter!(
+                "tag_value_limit_exceeded_total", 1,
+                "metric_name" => self.metric_name.to_string(),
+                "tag_key" => self.tag_key.to_string(),
+            );
+        } else {
+            counter!("tag_value_limit_exceeded_total", 1);
+        }
     }
 }
 
 pub struct TagCardinalityValueLimitReached<'a> {
+    pub metric_name: &'a str,
     pub key: &'a str,
+    pub include_extended_tags: bool,
 }
 
 impl<'a> InternalEvent for TagCardinalityValueLimitReached<'a> {
     fn emit(self) {
         debug!(
             message = "Value_limit reached for key. New values for this key will be rejected.",
+            metric_name = self.metric_name,
             key = %self.key,
         );
-        counter!("value_limit_reached_total", 1);
+        if self.include_extended_tags {
+            counter!(
+                "value_limit_reached_total", 1,
+                "metric_name" => self.metric_name.to_string(),
+                "tag_key" => self.key.to_string(),
+            );
+        } else {
+            counter!("value_limit_reached_total", 1);
+        }
     }
 }
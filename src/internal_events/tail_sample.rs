@@ -0,0 +1,50 @@
+use metrics::counter;
+use vector_lib::internal_event::{ComponentEventsDropped, InternalEvent, INTENTIONAL};
+
+#[derive(Debug)]
+pub struct TailSampleEventsDropped {
+    pub count: usize,
+}
+
+impl InternalEvent for TailSampleEventsDropped {
+    fn emit(self) {
+        emit!(ComponentEventsDropped::<INTENTIONAL> {
+            count: self.count,
+            reason: "Trace was not sampled.",
+        });
+    }
+}
+
+#[derive(Debug)]
+pub struct TailSamplePolicyDecision<'a> {
+    pub policy: &'a str,
+    pub sampled: bool,
+}
+
+impl<'a> InternalEvent for TailSamplePolicyDecision<'a> {
+    fn emit(self) {
+        let decision = if self.sampled {
+            "sampled"
+        } else {
+            "not_sampled"
+        };
+        counter!(
+            "tail_sample_policy_decisions_total", 1,
+            "policy" => self.policy.to_owned(),
+            "decision" => decision,
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct TailSampleTraceEvicted;
+
+impl InternalEvent for TailSampleTraceEvicted {
+    fn emit(self) {
+        debug!(
+            message = "Buffer is full, deciding on trace early.",
+            internal_log_rate_limit = true
+        );
+        counter!("tail_sample_traces_evicted_total", 1);
+    }
+}
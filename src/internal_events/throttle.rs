@@ -1,5 +1,7 @@
 use metrics::counter;
-use vector_lib::internal_event::{ComponentEventsDropped, InternalEvent, INTENTIONAL};
+use vector_lib::internal_event::{
+    error_stage, error_type, ComponentEventsDropped, InternalEvent, INTENTIONAL,
+};
 
 #[derive(Debug)]
 pub(crate) struct ThrottleEventDiscarded {
@@ -31,3 +33,29 @@ impl InternalEvent for ThrottleEventDiscarded {
         })
     }
 }
+
+#[derive(Debug)]
+pub(crate) struct ThrottleThresholdError {
+    pub field: &'static str,
+    pub threshold: String,
+}
+
+impl InternalEvent for ThrottleThresholdError {
+    fn emit(self) {
+        error!(
+            message = "Threshold is not a positive integer.",
+            field = self.field,
+            threshold = self.threshold,
+            error_code = "invalid_threshold",
+            error_type = error_type::CONVERSION_FAILED,
+            stage = error_stage::PROCESSING,
+            internal_log_rate_limit = true,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "invalid_threshold",
+            "error_type" => error_type::CONVERSION_FAILED,
+            "stage" => error_stage::PROCESSING,
+        );
+    }
+}
@@ -0,0 +1,133 @@
+use std::path::Path;
+
+use metrics::counter;
+use vector_lib::internal_event::InternalEvent;
+use vector_lib::internal_event::{error_stage, error_type, ComponentEventsDropped, UNINTENTIONAL};
+
+use crate::transforms::wasm::WasmError;
+
+#[derive(Debug)]
+pub struct WasmProcessError {
+    pub error: WasmError,
+}
+
+impl InternalEvent for WasmProcessError {
+    fn emit(self) {
+        let reason = "Error in WebAssembly module.";
+        error!(
+            message = reason,
+            error = %self.error,
+            error_code = wasm_error_code(&self.error),
+            error_type = error_type::SCRIPT_FAILED,
+            stage = error_stage::PROCESSING,
+            internal_log_rate_limit = true,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => wasm_error_code(&self.error),
+            "error_type" => error_type::SCRIPT_FAILED,
+            "stage" => error_stage::PROCESSING,
+        );
+
+        emit!(ComponentEventsDropped::<UNINTENTIONAL> { count: 1, reason })
+    }
+}
+
+#[derive(Debug)]
+pub struct WasmHookError {
+    pub hook: &'static str,
+    pub error: WasmError,
+}
+
+impl InternalEvent for WasmHookError {
+    fn emit(self) {
+        error!(
+            message = "Error in WebAssembly module.",
+            hook = self.hook,
+            error = %self.error,
+            error_code = wasm_error_code(&self.error),
+            error_type = error_type::SCRIPT_FAILED,
+            stage = error_stage::PROCESSING,
+            internal_log_rate_limit = true,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => wasm_error_code(&self.error),
+            "error_type" => error_type::SCRIPT_FAILED,
+            "stage" => error_stage::PROCESSING,
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct WasmModuleReloaded<'a> {
+    pub path: &'a Path,
+}
+
+impl<'a> InternalEvent for WasmModuleReloaded<'a> {
+    fn emit(self) {
+        info!(
+            message = "Reloaded WebAssembly module.",
+            path = ?self.path,
+        );
+        counter!("wasm_module_reloads_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub struct WasmModuleReloadError {
+    pub error: WasmError,
+}
+
+impl InternalEvent for WasmModuleReloadError {
+    fn emit(self) {
+        error!(
+            message = "Failed to reload WebAssembly module; keeping the running instance.",
+            error = %self.error,
+            error_code = "reload_failed",
+            error_type = error_type::SCRIPT_FAILED,
+            stage = error_stage::PROCESSING,
+            internal_log_rate_limit = true,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "reload_failed",
+            "error_type" => error_type::SCRIPT_FAILED,
+            "stage" => error_stage::PROCESSING,
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct WasmGuestLog<'a> {
+    pub level: u32,
+    pub message: &'a str,
+}
+
+impl<'a> InternalEvent for WasmGuestLog<'a> {
+    fn emit(self) {
+        let message = self.message;
+        match self.level {
+            0 => error!(message, internal_log_rate_limit = true),
+            1 => warn!(message, internal_log_rate_limit = true),
+            2 => info!(message, internal_log_rate_limit = true),
+            3 => debug!(message, internal_log_rate_limit = true),
+            _ => trace!(message, internal_log_rate_limit = true),
+        }
+    }
+}
+
+const fn wasm_error_code(error: &WasmError) -> &'static str {
+    use WasmError::*;
+
+    match error {
+        LoadModule { .. } => "load_module_failed",
+        Instantiate { .. } => "instantiate_failed",
+        MissingExport { .. } => "missing_export",
+        AbiVersion { .. } => "unsupported_abi_version",
+        EncodeOptions { .. } => "encode_options_failed",
+        FuelExhausted { .. } => "fuel_exhausted",
+        Trapped { .. } => "trapped",
+        Status { .. } => "error_status",
+    }
+}
@@ -0,0 +1,14 @@
+use vector_lib::internal_event::{ComponentEventsDropped, Count, Registered, INTENTIONAL};
+
+vector_lib::registered_event! (
+    WindowEventsDropped => {
+        events_dropped: Registered<ComponentEventsDropped<'static, INTENTIONAL>>
+            = register!(ComponentEventsDropped::<INTENTIONAL>::from(
+                "Events were not flushed from the window."
+            )),
+    }
+
+    fn emit(&self, data: Count) {
+        self.events_dropped.emit(data);
+    }
+);
@@ -66,6 +66,7 @@ pub mod app;
 pub mod async_read;
 #[cfg(feature = "aws-config")]
 pub mod aws;
+pub(crate) mod buffer;
 #[allow(unreachable_pub)]
 pub mod codecs;
 pub mod common;
@@ -102,6 +103,7 @@ pub mod sinks;
 pub mod source_sender;
 #[allow(unreachable_pub)]
 pub mod sources;
+pub mod state_file;
 pub mod stats;
 #[cfg(feature = "api-client")]
 #[allow(unreachable_pub)]
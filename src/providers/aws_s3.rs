@@ -0,0 +1,379 @@
+use async_stream::stream;
+use aws_sdk_s3::Client as S3Client;
+use bytes::Buf;
+use futures::Stream;
+use tokio::time;
+use vector_lib::configurable::configurable_component;
+
+use crate::{
+    aws::{create_client, AwsAuthentication, RegionOrEndpoint},
+    common::s3::S3ClientBuilder,
+    config::{self, format::Format, provider::ProviderConfig, ConfigBuilder, ProxyConfig},
+    signal,
+    tls::TlsConfig,
+};
+
+use super::BuildResult;
+
+/// Configuration for the `aws_s3` provider.
+///
+/// Works with any S3-compatible object store, such as MinIO, by setting `endpoint`.
+#[configurable_component(provider("aws_s3"))]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct AwsS3Config {
+    /// The name of the bucket to load the configuration from.
+    #[configurable(metadata(docs::examples = "my-bucket"))]
+    bucket: String,
+
+    /// The prefix of the objects to load the configuration from.
+    ///
+    /// Every object under this prefix with a `.toml`, `.yaml`, `.yml`, or `.json` extension is
+    /// loaded, in lexicographical order of its key, and merged into a single configuration.
+    #[serde(default)]
+    #[configurable(metadata(docs::examples = "pipelines/"))]
+    prefix: String,
+
+    #[serde(flatten)]
+    #[configurable(derived)]
+    region: RegionOrEndpoint,
+
+    #[configurable(derived)]
+    #[serde(default)]
+    auth: AwsAuthentication,
+
+    /// How often to poll the provider, in seconds.
+    #[serde(default = "default_poll_interval_secs")]
+    poll_interval_secs: u64,
+
+    #[configurable(derived)]
+    tls: Option<TlsConfig>,
+
+    #[configurable(derived)]
+    #[serde(default, skip_serializing_if = "crate::serde::is_default")]
+    proxy: ProxyConfig,
+}
+
+const fn default_poll_interval_secs() -> u64 {
+    30
+}
+
+impl config::GenerateConfig for AwsS3Config {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"bucket = "my-bucket"
+            prefix = "pipelines/"
+            region = "us-east-1""#,
+        )
+        .unwrap()
+    }
+}
+
+/// The key and entity tag of every object the configuration was loaded from, used to skip
+/// reloading when nothing has changed.
+type ObjectVersions = Vec<(String, Option<String>)>;
+
+/// Lists the configuration objects under `prefix`, sorted by key.
+async fn list_config_objects(
+    client: &S3Client,
+    bucket: &str,
+    prefix: &str,
+) -> Result<ObjectVersions, String> {
+    let mut objects = Vec::new();
+    let mut continuation_token = None;
+
+    loop {
+        let response = client
+            .list_objects_v2()
+            .bucket(bucket)
+            .prefix(prefix)
+            .set_continuation_token(continuation_token)
+            .send()
+            .await
+            .map_err(|error| format!("Failed to list objects: {}", error))?;
+
+        objects.extend(response.contents().iter().filter_map(|object| {
+            let key = object.key()?;
+            Format::from_path(key)
+                .is_ok()
+                .then(|| (key.to_owned(), object.e_tag().map(ToOwned::to_owned)))
+        }));
+
+        continuation_token = response.next_continuation_token().map(ToOwned::to_owned);
+        if continuation_token.is_none() {
+            break;
+        }
+    }
+
+    objects.sort();
+    Ok(objects)
+}
+
+/// Downloads the given objects, merging them into a single `ConfigBuilder`.
+async fn load_config_objects(
+    client: &S3Client,
+    bucket: &str,
+    objects: &ObjectVersions,
+) -> BuildResult {
+    let mut merged: Option<ConfigBuilder> = None;
+
+    for (key, _) in objects {
+        info!(
+            message = "Attempting to retrieve configuration.",
+            bucket = %bucket,
+            key = %key
+        );
+
+        let object = client
+            .get_object()
+            .bucket(bucket)
+            .key(key)
+            .send()
+            .await
+            .map_err(|error| vec![format!("Failed to get object `{}`: {}", key, error)])?;
+        let body = object
+            .body
+            .collect()
+            .await
+            .map_err(|error| vec![format!("Failed to read object `{}`: {}", key, error)])?;
+
+        let format = Format::from_path(key).unwrap_or_default();
+        let (config_builder, warnings) = config::load(body.reader(), format).map_err(|errors| {
+            errors
+                .into_iter()
+                .map(|error| format!("In object `{}`: {}", key, error))
+                .collect::<Vec<_>>()
+        })?;
+
+        for warning in warnings.into_iter() {
+            warn!("{}", warning);
+        }
+
+        match merged.as_mut() {
+            Some(merged) => merged.append(config_builder)?,
+            None => merged = Some(config_builder),
+        }
+    }
+
+    merged.ok_or_else(|| vec!["No configuration objects were found.".to_owned()])
+}
+
+/// Lists and loads the configuration, returning `None` if the objects haven't changed since
+/// `previous` was loaded.
+/// Loads the configuration from the objects under `prefix`, along with their versions.
+async fn s3_request_to_config_builder(
+    client: &S3Client,
+    bucket: &str,
+    prefix: &str,
+) -> Result<(ConfigBuilder, ObjectVersions), Vec<String>> {
+    let objects = list_config_objects(client, bucket, prefix)
+        .await
+        .map_err(|error| vec![error])?;
+
+    let config_builder = load_config_objects(client, bucket, &objects).await?;
+    Ok((config_builder, objects))
+}
+
+/// Loads the configuration again if any of the objects under `prefix` changed since `previous`.
+async fn s3_request_changed_config_builder(
+    client: &S3Client,
+    bucket: &str,
+    prefix: &str,
+    previous: &ObjectVersions,
+) -> Result<Option<(ConfigBuilder, ObjectVersions)>, Vec<String>> {
+    let objects = list_config_objects(client, bucket, prefix)
+        .await
+        .map_err(|error| vec![error])?;
+
+    if *previous == objects {
+        debug!(message = "Configuration objects are unchanged.", bucket = %bucket, prefix = %prefix);
+        return Ok(None);
+    }
+
+    let config_builder = load_config_objects(client, bucket, &objects).await?;
+    Ok(Some((config_builder, objects)))
+}
+
+/// Polls the bucket after/every `poll_interval_secs`, returning a stream of `ConfigBuilder`.
+///
+/// If the configuration fails to load, nothing is emitted, so the last good configuration stays
+/// in place until the next successful poll.
+fn poll_s3(
+    poll_interval_secs: u64,
+    client: S3Client,
+    bucket: String,
+    prefix: String,
+    mut versions: ObjectVersions,
+) -> impl Stream<Item = signal::SignalTo> {
+    let duration = time::Duration::from_secs(poll_interval_secs);
+    let mut interval = time::interval_at(time::Instant::now() + duration, duration);
+
+    stream! {
+        loop {
+            interval.tick().await;
+
+            match s3_request_changed_config_builder(&client, &bucket, &prefix, &versions).await {
+                Ok(Some((config_builder, new_versions))) => {
+                    versions = new_versions;
+                    yield signal::SignalTo::ReloadFromConfigBuilder(config_builder);
+                }
+                Ok(None) => {}
+                Err(errors) => {
+                    for error in errors {
+                        error!(
+                            message = "Failed to load configuration, keeping the last good configuration.",
+                            %error,
+                            bucket = %bucket,
+                            prefix = %prefix);
+                    }
+                }
+            };
+
+            info!(
+                message = "S3 provider is waiting.",
+                poll_interval_secs = ?poll_interval_secs,
+                bucket = %bucket,
+                prefix = %prefix);
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl ProviderConfig for AwsS3Config {
+    async fn build(&mut self, signal_handler: &mut signal::SignalHandler) -> BuildResult {
+        let proxy = ProxyConfig::from_env().merge(&self.proxy);
+        let client = create_client::<S3ClientBuilder>(
+            &self.auth,
+            self.region.region(),
+            self.region.endpoint(),
+            &proxy,
+            &self.tls,
+        )
+        .await
+        .map_err(|error| vec![format!("Failed to create S3 client: {}", error)])?;
+
+        let (config_builder, versions) =
+            s3_request_to_config_builder(&client, &self.bucket, &self.prefix).await?;
+
+        // Poll for changes to remote configuration.
+        signal_handler.add(poll_s3(
+            self.poll_interval_secs,
+            client,
+            self.bucket.clone(),
+            self.prefix.clone(),
+            versions,
+        ));
+
+        Ok(config_builder)
+    }
+}
+
+#[cfg(feature = "aws-s3-integration-tests")]
+#[cfg(test)]
+mod integration_tests {
+    use aws_sdk_s3::primitives::ByteStream;
+
+    use super::*;
+    use crate::{config::ComponentKey, test_util::trace_init};
+
+    fn s3_address() -> String {
+        std::env::var("S3_ADDRESS").unwrap_or_else(|_| "http://localhost:4566".into())
+    }
+
+    fn config(bucket: &str) -> AwsS3Config {
+        AwsS3Config {
+            bucket: bucket.to_owned(),
+            prefix: "pipelines/".to_owned(),
+            region: RegionOrEndpoint::with_both("us-east-1", s3_address()),
+            auth: AwsAuthentication::test_auth(),
+            poll_interval_secs: 1,
+            tls: None,
+            proxy: ProxyConfig::default(),
+        }
+    }
+
+    async fn client() -> S3Client {
+        let config = config("");
+        create_client::<S3ClientBuilder>(
+            &config.auth,
+            config.region.region(),
+            config.region.endpoint(),
+            &config.proxy,
+            &None,
+        )
+        .await
+        .unwrap()
+    }
+
+    async fn create_bucket(client: &S3Client) -> String {
+        let bucket = uuid::Uuid::new_v4().to_string();
+        client.create_bucket().bucket(&bucket).send().await.unwrap();
+        bucket
+    }
+
+    async fn put_object(client: &S3Client, bucket: &str, key: &str, body: &str) {
+        client
+            .put_object()
+            .bucket(bucket)
+            .key(key)
+            .body(ByteStream::from(body.as_bytes().to_vec()))
+            .send()
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn aws_s3_provider_merges_objects_and_keeps_last_good_config() {
+        trace_init();
+
+        let client = client().await;
+        let bucket = create_bucket(&client).await;
+        put_object(
+            &client,
+            &bucket,
+            "pipelines/sources.toml",
+            r#"
+            [sources.in]
+            type = "demo_logs"
+            format = "json"
+            "#,
+        )
+        .await;
+        put_object(
+            &client,
+            &bucket,
+            "pipelines/sinks.yaml",
+            "sinks:\n  out:\n    type: blackhole\n    inputs: [in]\n",
+        )
+        .await;
+        put_object(&client, &bucket, "pipelines/README.md", "not a config").await;
+
+        let (config_builder, versions) =
+            s3_request_to_config_builder(&client, &bucket, "pipelines/")
+                .await
+                .unwrap();
+        assert_eq!(versions.len(), 2);
+        assert!(config_builder
+            .sources
+            .contains_key(&ComponentKey::from("in")));
+        assert!(config_builder
+            .sinks
+            .contains_key(&ComponentKey::from("out")));
+
+        // Nothing is reloaded while the objects are unchanged.
+        assert!(
+            s3_request_changed_config_builder(&client, &bucket, "pipelines/", &versions)
+                .await
+                .unwrap()
+                .is_none()
+        );
+
+        // A broken object fails the reload, which leaves the current configuration in place.
+        put_object(&client, &bucket, "pipelines/sinks.yaml", "sinks: [").await;
+        assert!(
+            s3_request_changed_config_builder(&client, &bucket, "pipelines/", &versions)
+                .await
+                .is_err()
+        );
+    }
+}
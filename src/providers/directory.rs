@@ -0,0 +1,169 @@
+use std::path::{Path, PathBuf};
+
+use async_stream::stream;
+use futures::{channel::mpsc, Stream, StreamExt};
+use vector_lib::configurable::configurable_component;
+
+use crate::{
+    config::{self, provider::ProviderConfig, ConfigPath},
+    signal,
+};
+
+use super::BuildResult;
+
+/// Configuration for the `directory` provider.
+#[configurable_component(provider("directory"))]
+#[derive(Clone, Debug, Default)]
+#[serde(deny_unknown_fields, default)]
+pub struct DirectoryConfig {
+    /// Path of the configuration to load.
+    ///
+    /// Either a single configuration file, or a directory that is loaded the same way as one
+    /// passed with `--config-dir`.
+    #[configurable(metadata(docs::examples = "/etc/vector/pipelines"))]
+    path: Option<PathBuf>,
+
+    /// How long to wait after a change before reloading, in seconds.
+    ///
+    /// Changes are accumulated until none have occurred for this long, so that a configuration
+    /// that is synced file by file is only reloaded once.
+    #[configurable(metadata(docs::examples = 5))]
+    watch_delay_secs: Option<u64>,
+}
+
+fn config_path(path: &Path) -> ConfigPath {
+    if path.is_dir() {
+        ConfigPath::Dir(path.to_path_buf())
+    } else {
+        ConfigPath::File(path.to_path_buf(), None)
+    }
+}
+
+/// Loads the configuration from `path`, logging any warnings.
+///
+/// Loading reads the whole configuration tree from disk, so it's done on a blocking thread.
+async fn load_config_builder(path: PathBuf) -> BuildResult {
+    tokio::task::spawn_blocking(move || {
+        info!(
+            message = "Attempting to load configuration.",
+            path = ?path
+        );
+
+        let (config_builder, warnings) = config::load_builder_from_paths(&[config_path(&path)])?;
+
+        for warning in warnings.into_iter() {
+            warn!("{}", warning);
+        }
+
+        Ok(config_builder)
+    })
+    .await
+    .map_err(|error| vec![format!("Failed to load configuration: {}", error)])?
+}
+
+/// Reloads the configuration every time a change is detected by the watcher, returning a stream
+/// of `ConfigBuilder`.
+///
+/// If the configuration fails to load, nothing is emitted, so the last good configuration stays
+/// in place until the next change.
+fn watch_directory(
+    path: PathBuf,
+    mut changes: mpsc::Receiver<()>,
+) -> impl Stream<Item = signal::SignalTo> {
+    stream! {
+        while changes.next().await.is_some() {
+            match load_config_builder(path.clone()).await {
+                Ok(config_builder) => yield signal::SignalTo::ReloadFromConfigBuilder(config_builder),
+                Err(errors) => {
+                    for error in errors {
+                        error!(
+                            message = "Failed to load configuration, keeping the last good configuration.",
+                            %error,
+                            path = ?path);
+                    }
+                }
+            };
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl ProviderConfig for DirectoryConfig {
+    async fn build(&mut self, signal_handler: &mut signal::SignalHandler) -> BuildResult {
+        let path = self
+            .path
+            .take()
+            .ok_or_else(|| vec!["Path is required for the `directory` provider.".to_owned()])?;
+
+        let config_builder = load_config_builder(path.clone()).await?;
+
+        // A single pending notification is enough, as every reload reads the whole tree again.
+        let (mut tx, rx) = mpsc::channel(0);
+        let delay = self.watch_delay_secs.map(std::time::Duration::from_secs);
+        config::watcher::spawn_thread_with_callback([&path], delay, true, move || {
+            match tx.try_send(()) {
+                Ok(()) => true,
+                // The stream is dropped when the provider is rebuilt, at which point this watcher
+                // is no longer needed.
+                Err(error) => !error.is_disconnected(),
+            }
+        })
+        .map_err(|error| vec![format!("Failed to watch `{}`: {}", path.display(), error)])?;
+
+        // Watch for changes to the configuration.
+        signal_handler.add(watch_directory(path, rx));
+
+        Ok(config_builder)
+    }
+}
+
+impl_generate_config_from_default!(DirectoryConfig);
+
+#[cfg(test)]
+mod tests {
+    use futures::SinkExt;
+
+    use super::*;
+    use crate::{config::ComponentKey, test_util::temp_dir};
+
+    #[tokio::test]
+    async fn reloads_on_change_and_keeps_last_good_config() {
+        let dir = temp_dir();
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("vector.toml"),
+            r#"
+            [sources.in]
+            type = "demo_logs"
+            format = "json"
+
+            [sinks.out]
+            type = "blackhole"
+            inputs = ["in"]
+            "#,
+        )
+        .unwrap();
+
+        let (mut tx, rx) = mpsc::channel(0);
+        let mut reloads = Box::pin(watch_directory(dir.clone(), rx));
+
+        tx.send(()).await.unwrap();
+        match reloads.next().await {
+            Some(signal::SignalTo::ReloadFromConfigBuilder(config_builder)) => {
+                assert!(config_builder
+                    .sources
+                    .contains_key(&ComponentKey::from("in")));
+                assert!(config_builder
+                    .sinks
+                    .contains_key(&ComponentKey::from("out")));
+            }
+            _ => panic!("expected the configuration to be reloaded"),
+        }
+
+        // A broken configuration isn't reloaded, so nothing is emitted before the stream ends.
+        std::fs::write(dir.join("vector.toml"), "[sources.in").unwrap();
+        tx.send(()).await.unwrap();
+        drop(tx);
+        assert!(reloads.next().await.is_none());
+    }
+}
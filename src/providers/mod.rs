@@ -7,6 +7,9 @@ use crate::{
     signal,
 };
 
+#[cfg(any(feature = "sources-aws_s3", feature = "sinks-aws_s3"))]
+pub mod aws_s3;
+pub mod directory;
 pub mod http;
 
 pub type BuildResult = std::result::Result<ConfigBuilder, Vec<String>>;
@@ -17,6 +20,13 @@ pub type BuildResult = std::result::Result<ConfigBuilder, Vec<String>>;
 #[serde(tag = "type", rename_all = "snake_case")]
 #[enum_dispatch(ProviderConfig)]
 pub enum Providers {
+    /// AWS S3.
+    #[cfg(any(feature = "sources-aws_s3", feature = "sinks-aws_s3"))]
+    AwsS3(aws_s3::AwsS3Config),
+
+    /// Directory.
+    Directory(directory::DirectoryConfig),
+
     /// HTTP.
     Http(http::HttpConfig),
 }
@@ -25,6 +35,9 @@ pub enum Providers {
 impl NamedComponent for Providers {
     fn get_component_name(&self) -> &'static str {
         match self {
+            #[cfg(any(feature = "sources-aws_s3", feature = "sinks-aws_s3"))]
+            Self::AwsS3(config) => config.get_component_name(),
+            Self::Directory(config) => config.get_component_name(),
             Self::Http(config) => config.get_component_name(),
         }
     }
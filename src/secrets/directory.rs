@@ -0,0 +1,74 @@
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Component, Path, PathBuf},
+};
+
+use vector_lib::configurable::{component::GenerateConfig, configurable_component};
+
+use crate::{config::SecretBackend, signal};
+
+/// Configuration for the `directory` secrets backend.
+#[configurable_component(secrets("directory"))]
+#[derive(Clone, Debug)]
+pub struct DirectoryBackend {
+    /// Directory path to read secrets from.
+    ///
+    /// Each secret is stored in its own file, named after the secret key and containing the secret
+    /// value, such as the layout used by Kubernetes when mounting a `Secret` as a volume.
+    pub path: PathBuf,
+
+    /// Whether or not to remove trailing newlines from the secret values.
+    #[serde(default)]
+    pub remove_trailing_newline: bool,
+}
+
+impl GenerateConfig for DirectoryBackend {
+    fn generate_config() -> toml::Value {
+        toml::Value::try_from(DirectoryBackend {
+            path: PathBuf::from("/path/to/secrets"),
+            remove_trailing_newline: false,
+        })
+        .unwrap()
+    }
+}
+
+impl SecretBackend for DirectoryBackend {
+    fn retrieve(
+        &mut self,
+        secret_keys: HashSet<String>,
+        _: &mut signal::SignalRx,
+    ) -> crate::Result<HashMap<String, String>> {
+        // The files are read on every retrieval, rather than once on creation, so that rotated
+        // secrets are picked up when the configuration is reloaded.
+        let mut secrets = HashMap::new();
+        for k in secret_keys.into_iter() {
+            // Keys must name a file directly within the directory, so that a configuration can't
+            // be used to read arbitrary files.
+            let is_file_name = matches!(
+                Path::new(&k).components().collect::<Vec<_>>().as_slice(),
+                [Component::Normal(_)]
+            );
+            if !is_file_name {
+                return Err(format!("secret key '{}' is not a valid file name", k).into());
+            }
+
+            let file_path = self.path.join(&k);
+            let mut secret = std::fs::read_to_string(&file_path).map_err(|e| {
+                format!(
+                    "secret for key '{}' was not retrieved from '{}': {}",
+                    k,
+                    file_path.display(),
+                    e
+                )
+            })?;
+            if self.remove_trailing_newline {
+                super::remove_trailing_newline(&mut secret);
+            }
+            if secret.is_empty() {
+                return Err(format!("secret for key '{}' was empty", k).into());
+            }
+            secrets.insert(k, secret);
+        }
+        Ok(secrets)
+    }
+}
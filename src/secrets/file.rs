@@ -0,0 +1,67 @@
+use std::{
+    collections::{HashMap, HashSet},
+    path::PathBuf,
+};
+
+use vector_lib::configurable::{component::GenerateConfig, configurable_component};
+
+use crate::{config::SecretBackend, signal};
+
+/// Configuration for the `file` secrets backend.
+#[configurable_component(secrets("file"))]
+#[derive(Clone, Debug)]
+pub struct FileBackend {
+    /// File path to read secrets from.
+    ///
+    /// The file must contain a JSON object mapping each secret key to its value.
+    pub path: PathBuf,
+
+    /// Whether or not to remove trailing newlines from the secret values.
+    #[serde(default)]
+    pub remove_trailing_newline: bool,
+}
+
+impl GenerateConfig for FileBackend {
+    fn generate_config() -> toml::Value {
+        toml::Value::try_from(FileBackend {
+            path: PathBuf::from("/path/to/secrets.json"),
+            remove_trailing_newline: false,
+        })
+        .unwrap()
+    }
+}
+
+impl SecretBackend for FileBackend {
+    fn retrieve(
+        &mut self,
+        secret_keys: HashSet<String>,
+        _: &mut signal::SignalRx,
+    ) -> crate::Result<HashMap<String, String>> {
+        // The file is read on every retrieval, rather than once on creation, so that rotated
+        // secrets are picked up when the configuration is reloaded.
+        let contents = std::fs::read_to_string(&self.path).map_err(|e| {
+            format!(
+                "unable to read secrets file '{}': {}",
+                self.path.display(),
+                e
+            )
+        })?;
+        let mut output = serde_json::from_str::<HashMap<String, String>>(&contents)?;
+
+        let mut secrets = HashMap::new();
+        for k in secret_keys.into_iter() {
+            if let Some(mut secret) = output.remove(&k) {
+                if self.remove_trailing_newline {
+                    super::remove_trailing_newline(&mut secret);
+                }
+                if secret.is_empty() {
+                    return Err(format!("secret for key '{}' was empty", k).into());
+                }
+                secrets.insert(k, secret);
+            } else {
+                return Err(format!("secret for key '{}' was not retrieved", k).into());
+            }
+        }
+        Ok(secrets)
+    }
+}
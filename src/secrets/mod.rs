@@ -6,7 +6,9 @@ use vector_lib::configurable::{configurable_component, NamedComponent};
 
 use crate::{config::SecretBackend, signal};
 
+mod directory;
 mod exec;
+mod file;
 mod test;
 
 /// Configurable secret backends in Vector.
@@ -18,6 +20,12 @@ pub enum SecretBackends {
     /// Exec.
     Exec(exec::ExecBackend),
 
+    /// File.
+    File(file::FileBackend),
+
+    /// Directory.
+    Directory(directory::DirectoryBackend),
+
     /// Test.
     #[configurable(metadata(docs::hidden))]
     Test(test::TestBackend),
@@ -28,7 +36,16 @@ impl NamedComponent for SecretBackends {
     fn get_component_name(&self) -> &'static str {
         match self {
             Self::Exec(config) => config.get_component_name(),
+            Self::File(config) => config.get_component_name(),
+            Self::Directory(config) => config.get_component_name(),
             Self::Test(config) => config.get_component_name(),
         }
     }
 }
+
+/// Removes any trailing newline characters, as commonly left by editors or `echo` when a secret
+/// is written to a file.
+fn remove_trailing_newline(secret: &mut String) {
+    let len = secret.trim_end_matches(['\r', '\n']).len();
+    secret.truncate(len);
+}
@@ -0,0 +1,132 @@
+use std::time::Duration;
+
+use rumqttc::{AsyncClient, MqttOptions, Packet};
+use snafu::ResultExt;
+use vector_lib::codecs::JsonSerializerConfig;
+
+use crate::{
+    common::mqtt::{random_client_id, MqttCommonConfig, MqttError, MqttQoS},
+    sinks::prelude::*,
+};
+
+use super::{sink::MqttSink, ConfigSnafu, MqttSinkError};
+
+/// Configuration for the `mqtt` sink.
+#[configurable_component(sink("mqtt", "Publish observability data to topics on an MQTT broker."))]
+#[derive(Clone, Debug)]
+pub struct MqttSinkConfig {
+    #[serde(flatten)]
+    #[configurable(derived)]
+    pub(super) common: MqttCommonConfig,
+
+    /// The MQTT topic to publish events to.
+    #[configurable(metadata(docs::examples = "vector"))]
+    #[configurable(metadata(docs::examples = "devices/{{ device_id }}/logs"))]
+    pub(super) topic: Template,
+
+    /// The quality of service level to publish events with.
+    #[configurable(derived)]
+    #[serde(default)]
+    pub(super) qos: MqttQoS,
+
+    /// Whether the broker retains the last event published to each topic.
+    ///
+    /// Retained events are delivered to new subscribers of the topic as soon as they subscribe.
+    #[serde(default)]
+    pub(super) retain: bool,
+
+    /// Whether to start a new session when connecting to the broker.
+    ///
+    /// When disabled, the broker keeps the session of the client across reconnections. This
+    /// requires a stable `client_id`.
+    #[serde(default = "crate::serde::default_true")]
+    pub(super) clean_session: bool,
+
+    #[configurable(derived)]
+    pub(super) encoding: EncodingConfig,
+
+    #[configurable(derived)]
+    #[serde(default)]
+    pub(super) request: TowerRequestConfig,
+
+    #[configurable(derived)]
+    #[serde(
+        default,
+        deserialize_with = "crate::serde::bool_or_struct",
+        skip_serializing_if = "crate::serde::is_default"
+    )]
+    pub acknowledgements: AcknowledgementsConfig,
+}
+
+impl GenerateConfig for MqttSinkConfig {
+    fn generate_config() -> toml::Value {
+        toml::Value::try_from(Self {
+            common: MqttCommonConfig {
+                host: "localhost".to_string(),
+                ..Default::default()
+            },
+            topic: Template::try_from("vector").unwrap(),
+            qos: MqttQoS::default(),
+            retain: false,
+            clean_session: true,
+            encoding: JsonSerializerConfig::default().into(),
+            request: Default::default(),
+            acknowledgements: Default::default(),
+        })
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "mqtt")]
+impl SinkConfig for MqttSinkConfig {
+    async fn build(&self, _cx: SinkContext) -> crate::Result<(VectorSink, Healthcheck)> {
+        let sink = MqttSink::new(self.clone())?;
+        let healthcheck = healthcheck(self.clone()).boxed();
+        Ok((VectorSink::from_event_streamsink(sink), healthcheck))
+    }
+
+    fn input(&self) -> Input {
+        Input::new(self.encoding.config().input_type() & DataType::Log)
+    }
+
+    fn acknowledgements(&self) -> &AcknowledgementsConfig {
+        &self.acknowledgements
+    }
+}
+
+impl MqttSinkConfig {
+    /// Creates the options of the client, with a random client ID unless one is configured.
+    pub(super) fn options(&self) -> Result<MqttOptions, MqttSinkError> {
+        let mut options = self
+            .common
+            .mqtt_options(&random_client_id())
+            .context(ConfigSnafu)?;
+        options.set_clean_session(self.clean_session);
+        Ok(options)
+    }
+}
+
+async fn healthcheck(mut config: MqttSinkConfig) -> crate::Result<()> {
+    // A distinct client ID is used, so that the connection of the sink isn't taken over.
+    if let Some(client_id) = config.common.client_id.as_mut() {
+        client_id.push_str("healthcheck");
+    }
+    config.clean_session = true;
+    let (client, mut event_loop) = AsyncClient::new(config.options()?, 1);
+
+    let result = tokio::time::timeout(Duration::from_secs(10), async {
+        loop {
+            match event_loop.poll().await {
+                Ok(rumqttc::Event::Incoming(Packet::ConnAck(_))) => break Ok(()),
+                Ok(_) => {}
+                Err(source) => break Err(MqttError::Connection { source }),
+            }
+        }
+    })
+    .await
+    .map_err(|_| "Timed out connecting to the MQTT broker.")?;
+
+    _ = client.try_disconnect();
+    result.map_err(Into::into)
+}
@@ -0,0 +1,165 @@
+//! The connection of the sink to the broker.
+//!
+//! Publishes are queued on the client, and sent to the broker by its event loop, which only reports
+//! the packet identifier of each publish it sends. As publishes are sent in the order they are
+//! queued in, each sent publish is matched with the oldest queued one, and completed once the
+//! broker has acknowledged it.
+
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use bytes::Bytes;
+use rumqttc::{
+    AsyncClient, ClientError, ConnectionError, Event, EventLoop, MqttOptions, Outgoing, Packet, QoS,
+};
+use tokio::sync::{oneshot, Mutex as AsyncMutex};
+use vector_lib::internal_event::error_stage;
+
+use crate::internal_events::MqttConnectionError;
+
+use super::MqttSinkError;
+
+/// The number of publishes that can be queued on the client before it is polled.
+const REQUEST_CHANNEL_CAPACITY: usize = 1024;
+
+/// How long to wait before reconnecting after a connection error.
+const RECONNECT_DELAY: Duration = Duration::from_secs(1);
+
+pub(super) struct Connection {
+    options: MqttOptions,
+    state: Mutex<State>,
+
+    /// Held while a publish is queued, so that publishes are queued on the client in the order they
+    /// are tracked in.
+    queue_lock: AsyncMutex<()>,
+}
+
+struct State {
+    client: AsyncClient,
+
+    /// Incremented for each publish, to find it again if it can't be queued.
+    next_id: u64,
+
+    /// The publishes queued on the client that haven't been sent yet, oldest first.
+    queued: VecDeque<(u64, oneshot::Sender<()>)>,
+
+    /// The publishes sent with a `qos` of `at_least_once` or `exactly_once`, by packet identifier.
+    inflight: HashMap<u16, oneshot::Sender<()>>,
+}
+
+impl Connection {
+    /// Creates a connection, and the event loop that must be passed to [`Connection::run`].
+    pub(super) fn new(options: MqttOptions) -> (Arc<Self>, EventLoop) {
+        let (client, event_loop) = AsyncClient::new(options.clone(), REQUEST_CHANNEL_CAPACITY);
+        let connection = Self {
+            options,
+            state: Mutex::new(State {
+                client,
+                next_id: 0,
+                queued: VecDeque::new(),
+                inflight: HashMap::new(),
+            }),
+            queue_lock: AsyncMutex::new(()),
+        };
+        (Arc::new(connection), event_loop)
+    }
+
+    /// Publishes a message, and waits for the broker to acknowledge it.
+    ///
+    /// Messages published with a `qos` of `at_most_once` aren't acknowledged by the broker, so
+    /// they are completed as soon as they have been sent.
+    pub(super) async fn publish(
+        &self,
+        topic: String,
+        qos: QoS,
+        retain: bool,
+        payload: Bytes,
+    ) -> Result<(), MqttSinkError> {
+        let (tx, rx) = oneshot::channel();
+        {
+            let _guard = self.queue_lock.lock().await;
+            let (id, client) = {
+                let mut state = self.state.lock().expect("lock poisoned");
+                let id = state.next_id;
+                state.next_id += 1;
+                state.queued.push_back((id, tx));
+                (id, state.client.clone())
+            };
+
+            if let Err(source) = client.publish_bytes(topic, qos, retain, payload).await {
+                let mut state = self.state.lock().expect("lock poisoned");
+                state.queued.retain(|(queued, _)| *queued != id);
+                return Err(MqttSinkError::Publish { source });
+            }
+        }
+
+        rx.await.map_err(|_| MqttSinkError::ConnectionLost)
+    }
+
+    /// Queues the disconnection of the client, after the publishes queued so far.
+    pub(super) async fn disconnect(&self) -> Result<(), ClientError> {
+        let client = self.state.lock().expect("lock poisoned").client.clone();
+        client.disconnect().await
+    }
+
+    /// Polls the event loop until the client disconnects, completing publishes as they are
+    /// acknowledged.
+    ///
+    /// After a connection error, a new client is created rather than letting the event loop
+    /// reconnect, as the event loop would then either send the publishes that weren't acknowledged
+    /// again or drop them, depending on the session, without reporting which. These publishes fail
+    /// instead, so that they are retried on the new client.
+    pub(super) async fn run(&self, mut event_loop: EventLoop) {
+        loop {
+            match event_loop.poll().await {
+                Ok(Event::Outgoing(Outgoing::Publish(pkid))) => self.sent(pkid),
+                Ok(Event::Incoming(Packet::PubAck(ack))) => self.acknowledged(ack.pkid),
+                Ok(Event::Incoming(Packet::PubComp(comp))) => self.acknowledged(comp.pkid),
+                Ok(Event::Outgoing(Outgoing::Disconnect)) | Err(ConnectionError::RequestsDone) => {
+                    break
+                }
+                Ok(_) => {}
+                Err(error) => {
+                    emit!(MqttConnectionError {
+                        error,
+                        stage: error_stage::SENDING,
+                    });
+                    event_loop = self.reset();
+                    tokio::time::sleep(RECONNECT_DELAY).await;
+                }
+            }
+        }
+    }
+
+    fn sent(&self, pkid: u16) {
+        let mut state = self.state.lock().expect("lock poisoned");
+        if let Some((_, tx)) = state.queued.pop_front() {
+            // Publishes with a `qos` of `at_most_once` have no packet identifier.
+            if pkid == 0 {
+                _ = tx.send(());
+            } else {
+                state.inflight.insert(pkid, tx);
+            }
+        }
+    }
+
+    fn acknowledged(&self, pkid: u16) {
+        let mut state = self.state.lock().expect("lock poisoned");
+        if let Some(tx) = state.inflight.remove(&pkid) {
+            _ = tx.send(());
+        }
+    }
+
+    /// Replaces the client, failing the publishes that haven't been acknowledged yet.
+    fn reset(&self) -> EventLoop {
+        let (client, event_loop) = AsyncClient::new(self.options.clone(), REQUEST_CHANNEL_CAPACITY);
+        let mut state = self.state.lock().expect("lock poisoned");
+        state.client = client;
+        state.queued.clear();
+        state.inflight.clear();
+        event_loop
+    }
+}
@@ -0,0 +1,146 @@
+use std::time::Duration;
+
+use rumqttc::{AsyncClient, Event as MqttEvent, MqttOptions, Packet, Publish, QoS};
+use tokio::sync::mpsc;
+use vector_lib::codecs::TextSerializerConfig;
+
+use super::{config::MqttSinkConfig, sink::MqttSink};
+use crate::{
+    common::mqtt::{MqttCommonConfig, MqttQoS},
+    sinks::prelude::*,
+    test_util::{
+        components::{run_and_assert_sink_compliance, SINK_TAGS},
+        random_lines_with_stream, random_string, trace_init,
+    },
+};
+
+fn mqtt_host() -> String {
+    std::env::var("MQTT_HOST").unwrap_or_else(|_| "localhost".into())
+}
+
+fn make_config(topic: &str) -> MqttSinkConfig {
+    MqttSinkConfig {
+        common: MqttCommonConfig {
+            host: mqtt_host(),
+            ..Default::default()
+        },
+        topic: Template::try_from(topic).unwrap(),
+        qos: MqttQoS::AtLeastOnce,
+        retain: false,
+        clean_session: true,
+        encoding: TextSerializerConfig::default().into(),
+        request: Default::default(),
+        acknowledgements: Default::default(),
+    }
+}
+
+/// Subscribes to a topic filter, forwarding the messages received.
+async fn subscribe(topic: &str) -> (AsyncClient, mpsc::UnboundedReceiver<Publish>) {
+    let options = MqttOptions::new(format!("test{}", random_string(10)), mqtt_host(), 1883);
+    let (client, mut event_loop) = AsyncClient::new(options, 16);
+    client.subscribe(topic, QoS::AtLeastOnce).await.unwrap();
+
+    let (tx, rx) = mpsc::unbounded_channel();
+    let (subscribed_tx, subscribed_rx) = tokio::sync::oneshot::channel();
+    tokio::spawn(async move {
+        let mut subscribed_tx = Some(subscribed_tx);
+        while let Ok(event) = event_loop.poll().await {
+            match event {
+                MqttEvent::Incoming(Packet::SubAck(_)) => {
+                    if let Some(subscribed_tx) = subscribed_tx.take() {
+                        _ = subscribed_tx.send(());
+                    }
+                }
+                MqttEvent::Incoming(Packet::Publish(publish)) => {
+                    if tx.send(publish).is_err() {
+                        break;
+                    }
+                }
+                _ => {}
+            }
+        }
+    });
+    subscribed_rx.await.expect("failed to subscribe");
+
+    (client, rx)
+}
+
+async fn receive(rx: &mut mpsc::UnboundedReceiver<Publish>, count: usize) -> Vec<Publish> {
+    let mut messages = Vec::new();
+    while messages.len() < count {
+        match tokio::time::timeout(Duration::from_secs(5), rx.recv()).await {
+            Ok(Some(message)) => messages.push(message),
+            _ => break,
+        }
+    }
+    messages
+}
+
+#[tokio::test]
+async fn mqtt_happy() {
+    trace_init();
+
+    let topic = format!("test-{}", random_string(10));
+    let (_client, mut rx) = subscribe(&topic).await;
+
+    let sink = MqttSink::new(make_config(&topic)).unwrap();
+    let (input, events) = random_lines_with_stream(100, 10, None);
+    run_and_assert_sink_compliance(VectorSink::from_event_streamsink(sink), events, &SINK_TAGS)
+        .await;
+
+    let output = receive(&mut rx, input.len())
+        .await
+        .into_iter()
+        .map(|message| String::from_utf8_lossy(&message.payload).into_owned())
+        .collect::<Vec<_>>();
+    assert_eq!(output, input);
+}
+
+#[tokio::test]
+async fn mqtt_templated_topic() {
+    trace_init();
+
+    let prefix = format!("test-{}", random_string(10));
+    let (_client, mut rx) = subscribe(&format!("{prefix}/#")).await;
+
+    let sink = MqttSink::new(make_config(&format!("{prefix}/{{{{ device }}}}"))).unwrap();
+    let events = ["a", "b"].map(|device| {
+        let mut log = LogEvent::from(format!("from {device}"));
+        log.insert("device", device);
+        Event::from(log)
+    });
+    run_and_assert_sink_compliance(
+        VectorSink::from_event_streamsink(sink),
+        futures::stream::iter(events),
+        &SINK_TAGS,
+    )
+    .await;
+
+    let topics = receive(&mut rx, 2)
+        .await
+        .into_iter()
+        .map(|message| message.topic)
+        .collect::<Vec<_>>();
+    assert_eq!(topics, [format!("{prefix}/a"), format!("{prefix}/b")]);
+}
+
+#[tokio::test]
+async fn mqtt_retain() {
+    trace_init();
+
+    let topic = format!("test-{}", random_string(10));
+    let mut config = make_config(&topic);
+    config.retain = true;
+
+    let sink = MqttSink::new(config).unwrap();
+    let (input, events) = random_lines_with_stream(100, 1, None);
+    run_and_assert_sink_compliance(VectorSink::from_event_streamsink(sink), events, &SINK_TAGS)
+        .await;
+
+    // The retained event is delivered to subscribers that subscribe after it was published.
+    let (_client, mut rx) = subscribe(&topic).await;
+    let messages = receive(&mut rx, 1).await;
+    assert_eq!(messages.len(), 1);
+    assert!(messages[0].retain);
+    assert_eq!(String::from_utf8_lossy(&messages[0].payload), input[0]);
+}
@@ -0,0 +1,33 @@
+//! `MQTT` sink
+//! Publishes data to topics on an [MQTT](https://mqtt.org/) broker.
+
+use snafu::Snafu;
+
+use crate::common::mqtt::MqttError;
+
+mod config;
+mod connection;
+#[cfg(feature = "mqtt-integration-tests")]
+#[cfg(test)]
+mod integration_tests;
+mod request_builder;
+mod service;
+mod sink;
+#[cfg(test)]
+mod tests;
+
+pub use config::MqttSinkConfig;
+
+#[derive(Debug, Snafu)]
+enum MqttSinkError {
+    #[snafu(display("invalid encoding: {}", source))]
+    Encoding {
+        source: vector_lib::codecs::encoding::BuildError,
+    },
+    #[snafu(display("MQTT Config Error: {}", source))]
+    Config { source: MqttError },
+    #[snafu(display("MQTT Publish Error: {}", source))]
+    Publish { source: rumqttc::ClientError },
+    #[snafu(display("MQTT Publish Error: connection lost before the broker acknowledged it"))]
+    ConnectionLost,
+}
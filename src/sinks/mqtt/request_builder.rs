@@ -0,0 +1,117 @@
+use std::io;
+
+use bytes::{Bytes, BytesMut};
+use tokio_util::codec::Encoder as _;
+use vector_lib::config::telemetry;
+
+use crate::sinks::prelude::*;
+
+use super::sink::MqttEvent;
+
+pub(super) struct MqttEncoder {
+    pub(super) transformer: Transformer,
+    pub(super) encoder: Encoder<()>,
+}
+
+impl encoding::Encoder<Event> for MqttEncoder {
+    fn encode_input(
+        &self,
+        mut input: Event,
+        writer: &mut dyn io::Write,
+    ) -> io::Result<(usize, GroupedCountByteSize)> {
+        let mut body = BytesMut::new();
+        self.transformer.transform(&mut input);
+
+        let mut byte_size = telemetry().create_request_count_byte_size();
+        byte_size.add_event(&input, input.estimated_json_encoded_size_of());
+
+        let mut encoder = self.encoder.clone();
+        encoder
+            .encode(input, &mut body)
+            .map_err(|_| io::Error::new(io::ErrorKind::Other, "unable to encode"))?;
+
+        let body = body.freeze();
+        write_all(writer, 1, body.as_ref())?;
+
+        Ok((body.len(), byte_size))
+    }
+}
+
+pub(super) struct MqttMetadata {
+    topic: String,
+    finalizers: EventFinalizers,
+}
+
+pub(super) struct MqttRequestBuilder {
+    pub(super) encoder: MqttEncoder,
+}
+
+#[derive(Clone)]
+pub(super) struct MqttRequest {
+    pub(super) bytes: Bytes,
+    pub(super) topic: String,
+    finalizers: EventFinalizers,
+    pub(super) metadata: RequestMetadata,
+}
+
+impl Finalizable for MqttRequest {
+    fn take_finalizers(&mut self) -> EventFinalizers {
+        std::mem::take(&mut self.finalizers)
+    }
+}
+
+impl MetaDescriptive for MqttRequest {
+    fn get_metadata(&self) -> &RequestMetadata {
+        &self.metadata
+    }
+
+    fn metadata_mut(&mut self) -> &mut RequestMetadata {
+        &mut self.metadata
+    }
+}
+
+impl RequestBuilder<MqttEvent> for MqttRequestBuilder {
+    type Metadata = MqttMetadata;
+    type Events = Event;
+    type Encoder = MqttEncoder;
+    type Payload = Bytes;
+    type Request = MqttRequest;
+    type Error = io::Error;
+
+    fn compression(&self) -> Compression {
+        Compression::None
+    }
+
+    fn encoder(&self) -> &Self::Encoder {
+        &self.encoder
+    }
+
+    fn split_input(
+        &self,
+        mut input: MqttEvent,
+    ) -> (Self::Metadata, RequestMetadataBuilder, Self::Events) {
+        let builder = RequestMetadataBuilder::from_event(&input.event);
+
+        let metadata = MqttMetadata {
+            topic: input.topic,
+            finalizers: input.event.take_finalizers(),
+        };
+
+        (metadata, builder, input.event)
+    }
+
+    fn build_request(
+        &self,
+        mqtt_metadata: Self::Metadata,
+        metadata: RequestMetadata,
+        payload: EncodeResult<Self::Payload>,
+    ) -> Self::Request {
+        let body = payload.into_payload();
+        MqttRequest {
+            bytes: body,
+            topic: mqtt_metadata.topic,
+            finalizers: mqtt_metadata.finalizers,
+            metadata,
+        }
+    }
+}
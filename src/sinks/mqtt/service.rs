@@ -0,0 +1,65 @@
+use std::{
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use rumqttc::QoS;
+
+use crate::sinks::prelude::*;
+
+use super::{connection::Connection, request_builder::MqttRequest, MqttSinkError};
+
+#[derive(Clone)]
+pub(super) struct MqttService {
+    pub(super) connection: Arc<Connection>,
+    pub(super) qos: QoS,
+    pub(super) retain: bool,
+}
+
+pub(super) struct MqttResponse {
+    metadata: RequestMetadata,
+}
+
+impl DriverResponse for MqttResponse {
+    fn event_status(&self) -> EventStatus {
+        EventStatus::Delivered
+    }
+
+    fn events_sent(&self) -> &GroupedCountByteSize {
+        self.metadata.events_estimated_json_encoded_byte_size()
+    }
+
+    fn bytes_sent(&self) -> Option<usize> {
+        Some(self.metadata.request_encoded_size())
+    }
+}
+
+impl Service<MqttRequest> for MqttService {
+    type Response = MqttResponse;
+
+    type Error = MqttSinkError;
+
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: MqttRequest) -> Self::Future {
+        let connection = Arc::clone(&self.connection);
+        let qos = self.qos;
+        let retain = self.retain;
+
+        Box::pin(async move {
+            // Only completes once the broker has acknowledged the publish, so that the events are
+            // only marked as delivered then.
+            connection
+                .publish(req.topic, qos, retain, req.bytes)
+                .await?;
+
+            Ok(MqttResponse {
+                metadata: req.metadata,
+            })
+        })
+    }
+}
@@ -0,0 +1,150 @@
+use std::{sync::Arc, time::Duration};
+
+use rumqttc::EventLoop;
+use snafu::ResultExt;
+
+use crate::sinks::prelude::*;
+
+use super::{
+    config::MqttSinkConfig,
+    connection::Connection,
+    request_builder::{MqttEncoder, MqttRequestBuilder},
+    service::{MqttResponse, MqttService},
+    EncodingSnafu, MqttSinkError,
+};
+
+/// How long to wait for the client to disconnect from the broker when the sink shuts down.
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(10);
+
+pub(super) struct MqttEvent {
+    pub(super) event: Event,
+    pub(super) topic: String,
+}
+
+pub(super) struct MqttSink {
+    request: TowerRequestConfig,
+    transformer: Transformer,
+    encoder: Encoder<()>,
+    connection: Arc<Connection>,
+    event_loop: EventLoop,
+    topic: Template,
+    qos: rumqttc::QoS,
+    retain: bool,
+}
+
+impl MqttSink {
+    pub(super) fn new(config: MqttSinkConfig) -> Result<Self, MqttSinkError> {
+        let (connection, event_loop) = Connection::new(config.options()?);
+        let transformer = config.encoding.transformer();
+        let serializer = config.encoding.build().context(EncodingSnafu)?;
+        let encoder = Encoder::<()>::new(serializer);
+
+        Ok(MqttSink {
+            request: config.request,
+            transformer,
+            encoder,
+            connection,
+            event_loop,
+            topic: config.topic,
+            qos: config.qos.into(),
+            retain: config.retain,
+        })
+    }
+
+    async fn run_inner(self: Box<Self>, input: BoxStream<'_, Event>) -> Result<(), ()> {
+        let MqttSink {
+            request,
+            transformer,
+            encoder,
+            connection,
+            event_loop,
+            topic,
+            qos,
+            retain,
+        } = *self;
+        let request = request.into_settings();
+
+        let request_builder = MqttRequestBuilder {
+            encoder: MqttEncoder {
+                encoder,
+                transformer,
+            },
+        };
+
+        let service = ServiceBuilder::new()
+            .settings(request, MqttRetryLogic)
+            .service(MqttService {
+                connection: Arc::clone(&connection),
+                qos,
+                retain,
+            });
+
+        // Publishes are only queued on the client, the event loop is what sends them to the
+        // broker, and reports their acknowledgements.
+        let event_loop = tokio::spawn({
+            let connection = Arc::clone(&connection);
+            async move { connection.run(event_loop).await }
+        });
+
+        let result = input
+            .filter_map(|event| std::future::ready(make_mqtt_event(&topic, event)))
+            .request_builder(default_request_builder_concurrency_limit(), request_builder)
+            .filter_map(|request| async move {
+                match request {
+                    Err(e) => {
+                        error!("Failed to build MQTT request: {:?}.", e);
+                        None
+                    }
+                    Ok(req) => Some(req),
+                }
+            })
+            .into_driver(service)
+            .protocol("mqtt")
+            .run()
+            .await;
+
+        if connection.disconnect().await.is_ok()
+            && tokio::time::timeout(SHUTDOWN_TIMEOUT, event_loop)
+                .await
+                .is_err()
+        {
+            warn!(message = "Timed out disconnecting from the MQTT broker.");
+        }
+
+        result
+    }
+}
+
+fn make_mqtt_event(topic: &Template, event: Event) -> Option<MqttEvent> {
+    let topic = topic
+        .render_string(&event)
+        .map_err(|missing_keys| {
+            emit!(TemplateRenderingError {
+                error: missing_keys,
+                field: Some("topic"),
+                drop_event: true,
+            });
+        })
+        .ok()?;
+
+    Some(MqttEvent { event, topic })
+}
+
+#[async_trait]
+impl StreamSink<Event> for MqttSink {
+    async fn run(mut self: Box<Self>, input: BoxStream<'_, Event>) -> Result<(), ()> {
+        self.run_inner(input).await
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct MqttRetryLogic;
+
+impl RetryLogic for MqttRetryLogic {
+    type Error = MqttSinkError;
+    type Response = MqttResponse;
+
+    fn is_retriable_error(&self, _error: &Self::Error) -> bool {
+        true
+    }
+}
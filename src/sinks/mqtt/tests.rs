@@ -0,0 +1,172 @@
+use std::time::Duration;
+
+use vector_lib::codecs::TextSerializerConfig;
+use vector_lib::event::{BatchNotifier, BatchStatus, BatchStatusReceiver};
+
+use super::{config::MqttSinkConfig, sink::MqttSink};
+use crate::{
+    common::mqtt::{
+        test_util::{MockBroker, Published},
+        MqttCommonConfig, MqttQoS,
+    },
+    sinks::prelude::*,
+    test_util::components::{run_and_assert_sink_compliance, SINK_TAGS},
+};
+
+#[test]
+fn generate_config() {
+    crate::test_util::test_generate_config::<MqttSinkConfig>();
+}
+
+fn make_config(broker: &MockBroker, topic: &str) -> MqttSinkConfig {
+    MqttSinkConfig {
+        common: MqttCommonConfig {
+            host: "127.0.0.1".to_string(),
+            port: broker.addr().port(),
+            ..Default::default()
+        },
+        topic: Template::try_from(topic).unwrap(),
+        qos: MqttQoS::AtLeastOnce,
+        retain: false,
+        clean_session: true,
+        encoding: TextSerializerConfig::default().into(),
+        request: Default::default(),
+        acknowledgements: Default::default(),
+    }
+}
+
+/// Collects the messages published to a mock broker, until the client disconnects.
+fn receive(broker: MockBroker) -> tokio::task::JoinHandle<Vec<Published>> {
+    tokio::spawn(async move {
+        let mut connection = broker.accept().await;
+        let mut messages = Vec::new();
+        while let Some(message) = connection.read_publish().await {
+            messages.push(message);
+        }
+        messages
+    })
+}
+
+fn log_event(message: &str, device: Option<&str>) -> Event {
+    let mut log = LogEvent::from(message);
+    if let Some(device) = device {
+        log.insert("device", device);
+    }
+    log.into()
+}
+
+#[tokio::test]
+async fn publishes_events() {
+    let broker = MockBroker::bind().await;
+    let sink = MqttSink::new(make_config(&broker, "vector")).unwrap();
+    let messages = receive(broker);
+
+    let events = ["first", "second"].map(|message| log_event(message, None));
+    run_and_assert_sink_compliance(
+        VectorSink::from_event_streamsink(sink),
+        futures::stream::iter(events),
+        &SINK_TAGS,
+    )
+    .await;
+
+    let messages = messages.await.unwrap();
+    assert_eq!(messages.len(), 2);
+    for (message, payload) in messages.iter().zip(["first", "second"]) {
+        assert_eq!(message.topic, "vector");
+        assert_eq!(message.payload, payload);
+        assert_eq!(message.qos, 1);
+        assert!(!message.retain);
+    }
+}
+
+#[tokio::test]
+async fn publishes_to_templated_topic() {
+    let broker = MockBroker::bind().await;
+    let mut config = make_config(&broker, "devices/{{ device }}");
+    config.retain = true;
+    let sink = MqttSink::new(config).unwrap();
+    let messages = receive(broker);
+
+    // Events missing the fields of the topic template are dropped.
+    let events = [
+        log_event("from a", Some("a")),
+        log_event("from nowhere", None),
+        log_event("from b", Some("b")),
+    ];
+    VectorSink::from_event_streamsink(sink)
+        .run(futures::stream::iter(events).map(Into::into))
+        .await
+        .unwrap();
+
+    let messages = messages.await.unwrap();
+    let topics = messages
+        .iter()
+        .map(|message| (message.topic.as_str(), message.payload.as_ref()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        topics,
+        [
+            ("devices/a", b"from a".as_ref()),
+            ("devices/b", b"from b".as_ref())
+        ]
+    );
+    assert!(messages.iter().all(|message| message.retain));
+}
+
+/// Runs the sink with a single event, returning the receiver of its delivery status.
+fn send_acknowledged(
+    sink: MqttSink,
+    message: &str,
+) -> (tokio::task::JoinHandle<Result<(), ()>>, BatchStatusReceiver) {
+    let (batch, receiver) = BatchNotifier::new_with_receiver();
+    let event = log_event(message, None).with_batch_notifier(&batch);
+    let sink = tokio::spawn(async move {
+        VectorSink::from_event_streamsink(sink)
+            .run(futures::stream::iter([event]).map(Into::into))
+            .await
+    });
+    (sink, receiver)
+}
+
+#[tokio::test]
+async fn delivers_events_once_acknowledged() {
+    let broker = MockBroker::bind().await;
+    let sink = MqttSink::new(make_config(&broker, "vector")).unwrap();
+    let (sink, mut receiver) = send_acknowledged(sink, "first");
+
+    let mut connection = broker.accept().await;
+    let (pkid, message) = connection.read_unacknowledged_publish().await.unwrap();
+    assert_eq!(message.payload, "first");
+
+    // The event isn't delivered until the broker has acknowledged it.
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert!(receiver.try_recv().is_err());
+
+    connection.puback(pkid.unwrap()).await;
+    assert_eq!(receiver.await, BatchStatus::Delivered);
+
+    // Wait for the sink to disconnect.
+    assert!(connection.read_publish().await.is_none());
+    sink.await.unwrap().unwrap();
+}
+
+#[tokio::test]
+async fn retries_events_unacknowledged_when_disconnected() {
+    let broker = MockBroker::bind().await;
+    let sink = MqttSink::new(make_config(&broker, "vector")).unwrap();
+    let (sink, receiver) = send_acknowledged(sink, "first");
+
+    // The connection is lost before the broker acknowledges the event.
+    let mut connection = broker.accept().await;
+    let (_, message) = connection.read_unacknowledged_publish().await.unwrap();
+    assert_eq!(message.payload, "first");
+    drop(connection);
+
+    let messages = receive(broker);
+    assert_eq!(receiver.await, BatchStatus::Delivered);
+    sink.await.unwrap().unwrap();
+
+    let messages = messages.await.unwrap();
+    assert_eq!(messages.len(), 1);
+    assert_eq!(messages[0].payload, "first");
+}
@@ -0,0 +1,241 @@
+use futures::FutureExt;
+use http::StatusCode;
+use tower::ServiceBuilder;
+use vector_lib::configurable::configurable_component;
+use vector_lib::opentelemetry::proto::collector::logs::v1::ExportLogsServiceRequest;
+
+use super::{
+    service::{
+        ExportRequest, GrpcTransport, HttpTransport, OpentelemetryResponse, OpentelemetryService,
+        Transport,
+    },
+    sink::OpentelemetrySink,
+    OpentelemetrySinkError,
+};
+use crate::{
+    config::{AcknowledgementsConfig, GenerateConfig, Input, SinkConfig, SinkContext},
+    http::{build_proxy_connector, HttpClient},
+    sinks::{
+        util::{
+            http::{validate_headers, RequestConfig},
+            retries::RetryLogic,
+            uri::with_default_scheme,
+            BatchConfig, Compression, RealtimeSizeBasedDefaultBatchSettings, ServiceBuilderExt,
+        },
+        Healthcheck, VectorSink,
+    },
+    tls::{MaybeTlsSettings, TlsEnableableConfig},
+};
+
+/// The transport protocol used to send data to the OTLP receiver.
+#[configurable_component]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum OpentelemetryProtocol {
+    /// Send data using [OTLP/gRPC][otlp_grpc].
+    ///
+    /// [otlp_grpc]: https://opentelemetry.io/docs/specs/otlp/#otlpgrpc
+    #[default]
+    Grpc,
+
+    /// Send data as binary protobuf payloads using [OTLP/HTTP][otlp_http].
+    ///
+    /// [otlp_http]: https://opentelemetry.io/docs/specs/otlp/#otlphttp
+    Http,
+}
+
+/// Configuration for the `opentelemetry` sink.
+#[configurable_component(sink(
+    "opentelemetry",
+    "Export logs, metrics, and traces to an OTLP receiver through gRPC or HTTP."
+))]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct OpentelemetryConfig {
+    /// The OTLP receiver endpoint to which to connect.
+    ///
+    /// For the `http` protocol, data is sent to the `/v1/logs`, `/v1/metrics`, and `/v1/traces`
+    /// paths relative to this endpoint.
+    ///
+    /// If no scheme is given, `http` or `https` is used depending on whether TLS is enabled.
+    #[configurable(validation(format = "uri"))]
+    #[configurable(metadata(docs::examples = "http://localhost:4317"))]
+    #[configurable(metadata(docs::examples = "https://otlp.example.com:4318"))]
+    endpoint: String,
+
+    #[configurable(derived)]
+    #[serde(default)]
+    protocol: OpentelemetryProtocol,
+
+    /// Compression configuration.
+    ///
+    /// The `grpc` protocol only supports `gzip` compression.
+    #[configurable(derived)]
+    #[serde(default)]
+    compression: Compression,
+
+    #[configurable(derived)]
+    #[serde(default)]
+    pub batch: BatchConfig<RealtimeSizeBasedDefaultBatchSettings>,
+
+    /// Outbound request settings.
+    ///
+    /// For the `grpc` protocol, the configured headers are sent as request metadata.
+    #[configurable(derived)]
+    #[serde(default)]
+    pub request: RequestConfig,
+
+    #[configurable(derived)]
+    #[serde(default)]
+    tls: Option<TlsEnableableConfig>,
+
+    #[configurable(derived)]
+    #[serde(
+        default,
+        deserialize_with = "crate::serde::bool_or_struct",
+        skip_serializing_if = "crate::serde::is_default"
+    )]
+    acknowledgements: AcknowledgementsConfig,
+}
+
+impl GenerateConfig for OpentelemetryConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"endpoint = "http://localhost:4317"
+            protocol = "grpc""#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "opentelemetry")]
+impl SinkConfig for OpentelemetryConfig {
+    async fn build(&self, cx: SinkContext) -> crate::Result<(VectorSink, Healthcheck)> {
+        let tls = MaybeTlsSettings::from_config(&self.tls, false)?;
+        let uri = with_default_scheme(&self.endpoint, tls.is_tls())?;
+        let headers = validate_headers(&self.request.headers)?;
+
+        let transport = match self.protocol {
+            OpentelemetryProtocol::Grpc => {
+                let compression = match self.compression {
+                    Compression::None => false,
+                    Compression::Gzip(_) => true,
+                    compression => {
+                        return Err(Box::new(
+                            OpentelemetrySinkError::UnsupportedGrpcCompression {
+                                compression: compression.to_string(),
+                            },
+                        ))
+                    }
+                };
+                let proxy = build_proxy_connector(tls, cx.proxy())?;
+                let client = hyper::Client::builder().http2_only(true).build(proxy);
+                Transport::Grpc(GrpcTransport::new(
+                    client,
+                    uri.clone(),
+                    headers,
+                    compression,
+                ))
+            }
+            OpentelemetryProtocol::Http => {
+                let client = HttpClient::new(tls, cx.proxy())?;
+                Transport::Http(HttpTransport::new(
+                    client,
+                    uri.clone(),
+                    headers,
+                    self.compression,
+                ))
+            }
+        };
+
+        let healthcheck = healthcheck(transport.clone()).boxed();
+        let service = OpentelemetryService::new(transport, uri);
+        let request_settings = self.request.tower.into_settings();
+        let batch_settings = self.batch.into_batcher_settings()?;
+
+        let service = ServiceBuilder::new()
+            .settings(request_settings, OpentelemetryRetryLogic)
+            .service(service);
+
+        let sink = OpentelemetrySink {
+            batch_settings,
+            service,
+        };
+
+        Ok((VectorSink::from_event_streamsink(sink), healthcheck))
+    }
+
+    fn input(&self) -> Input {
+        Input::all()
+    }
+
+    fn acknowledgements(&self) -> &AcknowledgementsConfig {
+        &self.acknowledgements
+    }
+}
+
+/// Checks that the receiver accepts export requests.
+///
+/// OTLP has no health checking mechanism of its own, but receivers are expected to accept export
+/// requests that carry no data, so an empty logs export request is sent instead.
+async fn healthcheck(transport: Transport) -> crate::Result<()> {
+    let request = ExportRequest::Logs(ExportLogsServiceRequest::default());
+    transport.export(request).await.map_err(Into::into)
+}
+
+#[derive(Debug, Clone)]
+struct OpentelemetryRetryLogic;
+
+impl RetryLogic for OpentelemetryRetryLogic {
+    type Error = OpentelemetrySinkError;
+    type Response = OpentelemetryResponse;
+
+    fn is_retriable_error(&self, err: &Self::Error) -> bool {
+        use tonic::Code::*;
+
+        match err {
+            OpentelemetrySinkError::Grpc { source } => !matches!(
+                source.code(),
+                // List taken from
+                //
+                // <https://github.com/grpc/grpc/blob/ed1b20777c69bd47e730a63271eafc1b299f6ca0/doc/statuscodes.md>
+                NotFound
+                    | InvalidArgument
+                    | AlreadyExists
+                    | PermissionDenied
+                    | OutOfRange
+                    | Unimplemented
+                    | Unauthenticated
+                    | DataLoss
+            ),
+            // <https://opentelemetry.io/docs/specs/otlp/#retryable-response-codes>
+            OpentelemetrySinkError::HttpStatus { status, .. } => matches!(
+                *status,
+                StatusCode::TOO_MANY_REQUESTS
+                    | StatusCode::BAD_GATEWAY
+                    | StatusCode::SERVICE_UNAVAILABLE
+                    | StatusCode::GATEWAY_TIMEOUT
+            ),
+            OpentelemetrySinkError::Compression { .. }
+            | OpentelemetrySinkError::UnsupportedGrpcCompression { .. } => false,
+            OpentelemetrySinkError::Http { .. } | OpentelemetrySinkError::HttpBody { .. } => true,
+        }
+    }
+}
+
+impl OpentelemetryConfig {
+    /// Creates an `OpentelemetryConfig` sending to the given endpoint with the given protocol.
+    #[cfg(test)]
+    pub fn new(endpoint: http::Uri, protocol: OpentelemetryProtocol) -> Self {
+        Self {
+            endpoint: endpoint.to_string(),
+            protocol,
+            compression: Compression::None,
+            batch: BatchConfig::default(),
+            request: RequestConfig::default(),
+            tls: None,
+            acknowledgements: Default::default(),
+        }
+    }
+}
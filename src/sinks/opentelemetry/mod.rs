@@ -0,0 +1,41 @@
+//! The `opentelemetry` sink.
+//!
+//! Exports logs, metrics, and traces to any receiver that implements the OpenTelemetry
+//! Protocol (OTLP), over either gRPC or HTTP.
+
+use http::StatusCode;
+use snafu::Snafu;
+
+mod config;
+mod service;
+mod sink;
+
+#[cfg(test)]
+mod tests;
+
+pub use config::OpentelemetryConfig;
+
+#[derive(Debug, Snafu)]
+#[snafu(visibility(pub))]
+pub enum OpentelemetrySinkError {
+    #[snafu(display("gRPC request failed: {}", source))]
+    Grpc { source: tonic::Status },
+
+    #[snafu(display("HTTP request failed: {}", source))]
+    Http { source: crate::http::HttpError },
+
+    #[snafu(display("Failed to read HTTP response: {}", source))]
+    HttpBody { source: hyper::Error },
+
+    #[snafu(display("Receiver responded with {}: {}", status, body))]
+    HttpStatus { status: StatusCode, body: String },
+
+    #[snafu(display("Failed to compress payload: {}", source))]
+    Compression { source: std::io::Error },
+
+    #[snafu(display(
+        "Compression `{}` is not supported by the gRPC protocol, only `gzip` is.",
+        compression
+    ))]
+    UnsupportedGrpcCompression { compression: String },
+}
@@ -0,0 +1,343 @@
+use std::{
+    io::Write,
+    task::{Context, Poll},
+};
+
+use bytes::Bytes;
+use futures::future::{self, BoxFuture};
+use http::{
+    header::{CONTENT_ENCODING, CONTENT_TYPE},
+    HeaderName, HeaderValue, Request, Uri,
+};
+use hyper::{client::HttpConnector, Body};
+use hyper_openssl::HttpsConnector;
+use hyper_proxy::ProxyConnector;
+use indexmap::IndexMap;
+use prost::Message;
+use snafu::ResultExt;
+use tonic::{body::BoxBody, codec::CompressionEncoding, IntoRequest};
+use tower::Service;
+use vector_lib::opentelemetry::proto::collector::{
+    logs::v1::{logs_service_client::LogsServiceClient, ExportLogsServiceRequest},
+    metrics::v1::{metrics_service_client::MetricsServiceClient, ExportMetricsServiceRequest},
+    trace::v1::{trace_service_client::TraceServiceClient, ExportTraceServiceRequest},
+};
+use vector_lib::request_metadata::{GroupedCountByteSize, MetaDescriptive, RequestMetadata};
+use vector_lib::stream::DriverResponse;
+
+use super::{
+    CompressionSnafu, GrpcSnafu, HttpBodySnafu, HttpSnafu, HttpStatusSnafu, OpentelemetrySinkError,
+};
+use crate::{
+    event::{EventFinalizers, EventStatus, Finalizable},
+    http::HttpClient,
+    internal_events::EndpointBytesSent,
+    sinks::util::{uri, Compression, Compressor},
+    Error,
+};
+
+/// The kind of telemetry carried by a request, which determines the OTLP service it is sent to.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Signal {
+    Logs,
+    Metrics,
+    Traces,
+}
+
+impl Signal {
+    /// The path of the OTLP/HTTP endpoint for this signal, relative to the configured endpoint.
+    const fn http_path(self) -> &'static str {
+        match self {
+            Signal::Logs => "v1/logs",
+            Signal::Metrics => "v1/metrics",
+            Signal::Traces => "v1/traces",
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum ExportRequest {
+    Logs(ExportLogsServiceRequest),
+    Metrics(ExportMetricsServiceRequest),
+    Traces(ExportTraceServiceRequest),
+}
+
+impl ExportRequest {
+    const fn signal(&self) -> Signal {
+        match self {
+            ExportRequest::Logs(_) => Signal::Logs,
+            ExportRequest::Metrics(_) => Signal::Metrics,
+            ExportRequest::Traces(_) => Signal::Traces,
+        }
+    }
+
+    pub fn encoded_len(&self) -> usize {
+        match self {
+            ExportRequest::Logs(request) => request.encoded_len(),
+            ExportRequest::Metrics(request) => request.encoded_len(),
+            ExportRequest::Traces(request) => request.encoded_len(),
+        }
+    }
+
+    fn encode_to_vec(&self) -> Vec<u8> {
+        match self {
+            ExportRequest::Logs(request) => request.encode_to_vec(),
+            ExportRequest::Metrics(request) => request.encode_to_vec(),
+            ExportRequest::Traces(request) => request.encode_to_vec(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct OpentelemetryRequest {
+    pub finalizers: EventFinalizers,
+    pub metadata: RequestMetadata,
+    pub payload: ExportRequest,
+}
+
+impl Finalizable for OpentelemetryRequest {
+    fn take_finalizers(&mut self) -> EventFinalizers {
+        self.finalizers.take_finalizers()
+    }
+}
+
+impl MetaDescriptive for OpentelemetryRequest {
+    fn get_metadata(&self) -> &RequestMetadata {
+        &self.metadata
+    }
+
+    fn metadata_mut(&mut self) -> &mut RequestMetadata {
+        &mut self.metadata
+    }
+}
+
+pub struct OpentelemetryResponse {
+    events_byte_size: GroupedCountByteSize,
+}
+
+impl DriverResponse for OpentelemetryResponse {
+    fn event_status(&self) -> EventStatus {
+        EventStatus::Delivered
+    }
+
+    fn events_sent(&self) -> &GroupedCountByteSize {
+        &self.events_byte_size
+    }
+}
+
+/// Sends export requests to the OTLP/gRPC services of the receiver.
+#[derive(Clone, Debug)]
+pub struct GrpcTransport {
+    logs: LogsServiceClient<HyperSvc>,
+    metrics: MetricsServiceClient<HyperSvc>,
+    traces: TraceServiceClient<HyperSvc>,
+}
+
+impl GrpcTransport {
+    pub fn new(
+        hyper_client: hyper::Client<ProxyConnector<HttpsConnector<HttpConnector>>, BoxBody>,
+        uri: Uri,
+        headers: IndexMap<HeaderName, HeaderValue>,
+        compression: bool,
+    ) -> Self {
+        let svc = HyperSvc {
+            uri,
+            client: hyper_client,
+            headers,
+        };
+        let mut logs = LogsServiceClient::new(svc.clone());
+        let mut metrics = MetricsServiceClient::new(svc.clone());
+        let mut traces = TraceServiceClient::new(svc);
+
+        if compression {
+            logs = logs.send_compressed(CompressionEncoding::Gzip);
+            metrics = metrics.send_compressed(CompressionEncoding::Gzip);
+            traces = traces.send_compressed(CompressionEncoding::Gzip);
+        }
+
+        Self {
+            logs,
+            metrics,
+            traces,
+        }
+    }
+
+    async fn export(mut self, payload: ExportRequest) -> Result<(), OpentelemetrySinkError> {
+        match payload {
+            ExportRequest::Logs(request) => {
+                self.logs.export(request.into_request()).await.map(drop)
+            }
+            ExportRequest::Metrics(request) => {
+                self.metrics.export(request.into_request()).await.map(drop)
+            }
+            ExportRequest::Traces(request) => {
+                self.traces.export(request.into_request()).await.map(drop)
+            }
+        }
+        .context(GrpcSnafu)
+    }
+}
+
+/// Sends export requests as binary protobuf payloads to the OTLP/HTTP endpoints of the receiver.
+#[derive(Clone, Debug)]
+pub struct HttpTransport {
+    client: HttpClient,
+    endpoint: String,
+    headers: IndexMap<HeaderName, HeaderValue>,
+    compression: Compression,
+}
+
+impl HttpTransport {
+    pub fn new(
+        client: HttpClient,
+        endpoint: Uri,
+        headers: IndexMap<HeaderName, HeaderValue>,
+        compression: Compression,
+    ) -> Self {
+        Self {
+            client,
+            endpoint: endpoint.to_string().trim_end_matches('/').to_owned(),
+            headers,
+            compression,
+        }
+    }
+
+    async fn export(self, payload: ExportRequest) -> Result<(), OpentelemetrySinkError> {
+        let uri = format!("{}/{}", self.endpoint, payload.signal().http_path());
+
+        let mut compressor = Compressor::from(self.compression);
+        compressor
+            .write_all(&payload.encode_to_vec())
+            .context(CompressionSnafu)?;
+        let body = compressor.finish().context(CompressionSnafu)?.freeze();
+
+        let mut builder = Request::post(uri).header(CONTENT_TYPE, "application/x-protobuf");
+        if let Some(content_encoding) = self.compression.content_encoding() {
+            builder = builder.header(CONTENT_ENCODING, content_encoding);
+        }
+        for (name, value) in &self.headers {
+            builder = builder.header(name, value);
+        }
+        let request = builder
+            .body(Body::from(body))
+            .expect("request with validated headers should be valid");
+
+        let response = self.client.send(request).await.context(HttpSnafu)?;
+        let status = response.status();
+        if status.is_success() {
+            Ok(())
+        } else {
+            let body: Bytes = hyper::body::to_bytes(response.into_body())
+                .await
+                .context(HttpBodySnafu)?;
+            HttpStatusSnafu {
+                status,
+                body: String::from_utf8_lossy(&body),
+            }
+            .fail()
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum Transport {
+    Grpc(GrpcTransport),
+    Http(HttpTransport),
+}
+
+impl Transport {
+    pub async fn export(self, payload: ExportRequest) -> Result<(), OpentelemetrySinkError> {
+        match self {
+            Transport::Grpc(transport) => transport.export(payload).await,
+            Transport::Http(transport) => transport.export(payload).await,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct OpentelemetryService {
+    transport: Transport,
+    protocol: String,
+    endpoint: String,
+}
+
+impl OpentelemetryService {
+    pub fn new(transport: Transport, uri: Uri) -> Self {
+        let (protocol, endpoint) = uri::protocol_endpoint(uri);
+        Self {
+            transport,
+            protocol,
+            endpoint,
+        }
+    }
+}
+
+impl Service<OpentelemetryRequest> for OpentelemetryService {
+    type Response = OpentelemetryResponse;
+    type Error = Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    // Emission of an internal event in case of errors is handled upstream by the caller.
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        // For gRPC, the readiness of the client is checked through the `export()` call happening
+        // inside `call()`, which blocks until the client is ready to perform another request.
+        Poll::Ready(Ok(()))
+    }
+
+    // Emission of internal events for errors and dropped events is handled upstream by the caller.
+    fn call(&mut self, mut request: OpentelemetryRequest) -> Self::Future {
+        let service = self.clone();
+        let byte_size = request.payload.encoded_len();
+        let metadata = std::mem::take(request.metadata_mut());
+        let events_byte_size = metadata.into_events_estimated_json_encoded_byte_size();
+
+        Box::pin(async move {
+            service.transport.export(request.payload).await?;
+
+            emit!(EndpointBytesSent {
+                byte_size,
+                protocol: &service.protocol,
+                endpoint: &service.endpoint,
+            });
+
+            Ok(OpentelemetryResponse { events_byte_size })
+        })
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct HyperSvc {
+    uri: Uri,
+    client: hyper::Client<ProxyConnector<HttpsConnector<HttpConnector>>, BoxBody>,
+    headers: IndexMap<HeaderName, HeaderValue>,
+}
+
+impl Service<hyper::Request<BoxBody>> for HyperSvc {
+    type Response = hyper::Response<hyper::Body>;
+    type Error = Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    // Emission of an internal event in case of errors is handled upstream by the caller.
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    // Emission of internal events for errors and dropped events is handled upstream by the caller.
+    fn call(&mut self, mut req: hyper::Request<BoxBody>) -> Self::Future {
+        // The gRPC client only knows the path of the method it calls, so the request is pointed
+        // at the configured endpoint here.
+        let mut parts = self.uri.clone().into_parts();
+        parts.path_and_query = req.uri().path_and_query().cloned();
+        match Uri::from_parts(parts) {
+            Ok(uri) => *req.uri_mut() = uri,
+            Err(error) => return Box::pin(future::err(error.into())),
+        }
+
+        for (name, value) in &self.headers {
+            req.headers_mut().insert(name, value.clone());
+        }
+
+        let client = self.client.clone();
+        Box::pin(async move { client.request(req).await.map_err(Into::into) })
+    }
+}
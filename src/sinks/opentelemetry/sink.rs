@@ -0,0 +1,93 @@
+use std::{fmt, num::NonZeroUsize};
+
+use async_trait::async_trait;
+use futures::{stream::BoxStream, StreamExt};
+use tower::Service;
+use vector_lib::opentelemetry::export;
+use vector_lib::partition::Partitioner;
+use vector_lib::stream::{BatcherSettings, DriverResponse};
+
+use super::service::{ExportRequest, OpentelemetryRequest, Signal};
+use crate::{
+    event::{Event, Finalizable},
+    sinks::util::{metadata::RequestMetadataBuilder, SinkBuilderExt, StreamSink},
+};
+
+/// Partitions events by signal, as each signal is exported to its own OTLP service.
+struct SignalPartitioner;
+
+impl Partitioner for SignalPartitioner {
+    type Item = Event;
+    type Key = Signal;
+
+    fn partition(&self, item: &Self::Item) -> Self::Key {
+        match item {
+            Event::Log(_) => Signal::Logs,
+            Event::Metric(_) => Signal::Metrics,
+            Event::Trace(_) => Signal::Traces,
+        }
+    }
+}
+
+pub struct OpentelemetrySink<S> {
+    pub batch_settings: BatcherSettings,
+    pub service: S,
+}
+
+impl<S> OpentelemetrySink<S>
+where
+    S: Service<OpentelemetryRequest> + Send + 'static,
+    S::Future: Send + 'static,
+    S::Response: DriverResponse + Send + 'static,
+    S::Error: fmt::Debug + Into<crate::Error> + Send,
+{
+    async fn run_inner(self: Box<Self>, input: BoxStream<'_, Event>) -> Result<(), ()> {
+        let batch_settings = self.batch_settings;
+
+        input
+            .batched_partitioned(SignalPartitioner, || batch_settings.as_byte_size_config())
+            .map(|(signal, mut events)| {
+                let finalizers = events.take_finalizers();
+                let builder = RequestMetadataBuilder::from_events(&events);
+
+                let payload = match signal {
+                    Signal::Logs => ExportRequest::Logs(export::logs_into_request(
+                        events.into_iter().map(Event::into_log),
+                    )),
+                    Signal::Metrics => ExportRequest::Metrics(export::metrics_into_request(
+                        events.into_iter().map(Event::into_metric),
+                    )),
+                    Signal::Traces => ExportRequest::Traces(export::traces_into_request(
+                        events.into_iter().map(Event::into_trace),
+                    )),
+                };
+
+                // A batch can encode to an empty payload when none of its metrics had any value
+                // to report, which is still a valid export request.
+                let bytes_len =
+                    NonZeroUsize::new(payload.encoded_len()).unwrap_or(NonZeroUsize::MIN);
+
+                OpentelemetryRequest {
+                    finalizers,
+                    metadata: builder.with_request_size(bytes_len),
+                    payload,
+                }
+            })
+            .into_driver(self.service)
+            .run()
+            .await
+    }
+}
+
+#[async_trait]
+impl<S> StreamSink<Event> for OpentelemetrySink<S>
+where
+    S: Service<OpentelemetryRequest> + Send + 'static,
+    S::Future: Send + 'static,
+    S::Response: DriverResponse + Send + 'static,
+    S::Error: fmt::Debug + Into<crate::Error> + Send,
+{
+    async fn run(self: Box<Self>, input: BoxStream<'_, Event>) -> Result<(), ()> {
+        self.run_inner(input).await
+    }
+}
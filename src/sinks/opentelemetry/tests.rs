@@ -0,0 +1,383 @@
+use bytes::{BufMut, Bytes, BytesMut};
+use futures::{channel::mpsc, stream, Stream, StreamExt};
+use http::{request::Parts, StatusCode};
+use hyper::Method;
+use prost::Message;
+use vector_lib::{
+    event::{BatchNotifier, BatchStatus, Event, EventArray, TraceEvent},
+    lookup::event_path,
+    opentelemetry::proto::{
+        collector::{
+            logs::v1::ExportLogsServiceRequest,
+            metrics::v1::{ExportMetricsServiceRequest, ExportMetricsServiceResponse},
+            trace::v1::{ExportTraceServiceRequest, ExportTraceServiceResponse},
+        },
+        common::v1::any_value::Value as PBValue,
+        metrics::v1::metric::Data,
+    },
+};
+
+use super::{config::OpentelemetryProtocol, *};
+use crate::{
+    config::{SinkConfig as _, SinkContext},
+    sinks::util::test::{build_test_server, build_test_server_generic, build_test_server_status},
+    test_util::{
+        components::{run_and_assert_sink_compliance, HTTP_SINK_TAGS},
+        map_event_batch_stream, next_addr, random_lines_with_stream, random_metrics_with_stream,
+        random_string,
+    },
+};
+
+// one byte for the compression flag plus four bytes for the length
+const GRPC_HEADER_SIZE: usize = 5;
+
+#[test]
+fn generate_config() {
+    crate::test_util::test_generate_config::<OpentelemetryConfig>();
+}
+
+#[tokio::test]
+async fn http_delivers_logs() {
+    let num_lines = 10;
+    let in_addr = next_addr();
+
+    let config = OpentelemetryConfig::new(
+        format!("http://{}", in_addr).parse().unwrap(),
+        OpentelemetryProtocol::Http,
+    );
+    let (sink, _) = config.build(SinkContext::default()).await.unwrap();
+    let (rx, trigger, server) = build_test_server(in_addr);
+    tokio::spawn(server);
+
+    let (batch, mut receiver) = BatchNotifier::new_with_receiver();
+    let (input_lines, events) = random_lines_with_stream(8, num_lines, Some(batch));
+
+    run_and_assert_sink_compliance(sink, events, &HTTP_SINK_TAGS).await;
+    drop(trigger);
+    assert_eq!(receiver.try_recv(), Ok(BatchStatus::Delivered));
+
+    let output_lines = get_received(rx, |parts, body| {
+        assert_eq!(Method::POST, parts.method);
+        assert_eq!("/v1/logs", parts.uri.path());
+        assert_eq!(
+            "application/x-protobuf",
+            parts.headers.get("content-type").unwrap().to_str().unwrap()
+        );
+
+        ExportLogsServiceRequest::decode(body)
+            .unwrap()
+            .resource_logs
+            .into_iter()
+            .flat_map(|resource_logs| resource_logs.scope_logs)
+            .flat_map(|scope_logs| scope_logs.log_records)
+            .map(|record| match record.body.unwrap().value.unwrap() {
+                PBValue::StringValue(line) => line,
+                value => panic!("unexpected body {:?}", value),
+            })
+            .collect()
+    })
+    .await;
+
+    assert_eq!(input_lines, output_lines);
+}
+
+#[tokio::test]
+async fn grpc_delivers_metrics() {
+    let num_metrics = 10;
+    let in_addr = next_addr();
+
+    let config = OpentelemetryConfig::new(
+        format!("http://{}", in_addr).parse().unwrap(),
+        OpentelemetryProtocol::Grpc,
+    );
+    let (sink, _) = config.build(SinkContext::default()).await.unwrap();
+    let (rx, trigger, server) = build_test_server_generic(in_addr, move || {
+        hyper::Response::builder()
+            .header("grpc-status", "0") // OK
+            .header("content-type", "application/grpc")
+            .body(hyper::Body::from(encode_body(
+                ExportMetricsServiceResponse {
+                    partial_success: None,
+                },
+            )))
+            .unwrap()
+    });
+    tokio::spawn(server);
+
+    let (batch, mut receiver) = BatchNotifier::new_with_receiver();
+    let (input_metrics, events) = random_metrics_with_stream(num_metrics, Some(batch), None);
+
+    run_and_assert_sink_compliance(sink, events, &HTTP_SINK_TAGS).await;
+    drop(trigger);
+    assert_eq!(receiver.try_recv(), Ok(BatchStatus::Delivered));
+
+    let output_names = get_received(rx, |parts, body| {
+        assert_eq!(Method::POST, parts.method);
+        assert_eq!(
+            "/opentelemetry.proto.collector.metrics.v1.MetricsService/Export",
+            parts.uri.path()
+        );
+
+        ExportMetricsServiceRequest::decode(body.slice(GRPC_HEADER_SIZE..))
+            .unwrap()
+            .resource_metrics
+            .into_iter()
+            .flat_map(|resource_metrics| resource_metrics.scope_metrics)
+            .flat_map(|scope_metrics| scope_metrics.metrics)
+            .map(|metric| {
+                assert!(matches!(metric.data, Some(Data::Sum(ref sum)) if sum.is_monotonic));
+                metric.name
+            })
+            .collect()
+    })
+    .await;
+
+    let input_names = input_metrics
+        .iter()
+        .map(|event| event.as_metric().name().to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(input_names, output_names);
+}
+
+#[tokio::test]
+async fn http_delivers_metrics() {
+    let num_metrics = 10;
+    let in_addr = next_addr();
+
+    let config = OpentelemetryConfig::new(
+        format!("http://{}", in_addr).parse().unwrap(),
+        OpentelemetryProtocol::Http,
+    );
+    let (sink, _) = config.build(SinkContext::default()).await.unwrap();
+    let (rx, trigger, server) = build_test_server(in_addr);
+    tokio::spawn(server);
+
+    let (batch, mut receiver) = BatchNotifier::new_with_receiver();
+    let (input_metrics, events) = random_metrics_with_stream(num_metrics, Some(batch), None);
+
+    run_and_assert_sink_compliance(sink, events, &HTTP_SINK_TAGS).await;
+    drop(trigger);
+    assert_eq!(receiver.try_recv(), Ok(BatchStatus::Delivered));
+
+    let output_names = get_received(rx, |parts, body| {
+        assert_eq!(Method::POST, parts.method);
+        assert_eq!("/v1/metrics", parts.uri.path());
+
+        ExportMetricsServiceRequest::decode(body)
+            .unwrap()
+            .resource_metrics
+            .into_iter()
+            .flat_map(|resource_metrics| resource_metrics.scope_metrics)
+            .flat_map(|scope_metrics| scope_metrics.metrics)
+            .map(|metric| metric.name)
+            .collect()
+    })
+    .await;
+
+    let input_names = input_metrics
+        .iter()
+        .map(|event| event.as_metric().name().to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(input_names, output_names);
+}
+
+#[tokio::test]
+async fn http_delivers_traces() {
+    let num_traces = 10;
+    let in_addr = next_addr();
+
+    let config = OpentelemetryConfig::new(
+        format!("http://{}", in_addr).parse().unwrap(),
+        OpentelemetryProtocol::Http,
+    );
+    let (sink, _) = config.build(SinkContext::default()).await.unwrap();
+    let (rx, trigger, server) = build_test_server(in_addr);
+    tokio::spawn(server);
+
+    let (batch, mut receiver) = BatchNotifier::new_with_receiver();
+    let (input_names, events) = random_traces_with_stream(num_traces, Some(batch));
+
+    run_and_assert_sink_compliance(sink, events, &HTTP_SINK_TAGS).await;
+    drop(trigger);
+    assert_eq!(receiver.try_recv(), Ok(BatchStatus::Delivered));
+
+    let output_names = get_received(rx, |parts, body| {
+        assert_eq!(Method::POST, parts.method);
+        assert_eq!("/v1/traces", parts.uri.path());
+
+        span_names(ExportTraceServiceRequest::decode(body).unwrap())
+    })
+    .await;
+
+    assert_eq!(input_names, output_names);
+}
+
+#[tokio::test]
+async fn grpc_delivers_traces() {
+    let num_traces = 10;
+    let in_addr = next_addr();
+
+    let config = OpentelemetryConfig::new(
+        format!("http://{}", in_addr).parse().unwrap(),
+        OpentelemetryProtocol::Grpc,
+    );
+    let (sink, _) = config.build(SinkContext::default()).await.unwrap();
+    let (rx, trigger, server) = build_test_server_generic(in_addr, move || {
+        hyper::Response::builder()
+            .header("grpc-status", "0") // OK
+            .header("content-type", "application/grpc")
+            .body(hyper::Body::from(encode_body(ExportTraceServiceResponse {
+                partial_success: None,
+            })))
+            .unwrap()
+    });
+    tokio::spawn(server);
+
+    let (batch, mut receiver) = BatchNotifier::new_with_receiver();
+    let (input_names, events) = random_traces_with_stream(num_traces, Some(batch));
+
+    run_and_assert_sink_compliance(sink, events, &HTTP_SINK_TAGS).await;
+    drop(trigger);
+    assert_eq!(receiver.try_recv(), Ok(BatchStatus::Delivered));
+
+    let output_names = get_received(rx, |parts, body| {
+        assert_eq!(Method::POST, parts.method);
+        assert_eq!(
+            "/opentelemetry.proto.collector.trace.v1.TraceService/Export",
+            parts.uri.path()
+        );
+
+        span_names(ExportTraceServiceRequest::decode(body.slice(GRPC_HEADER_SIZE..)).unwrap())
+    })
+    .await;
+
+    assert_eq!(input_names, output_names);
+}
+
+#[tokio::test]
+async fn healthcheck_sends_empty_export_request() {
+    let in_addr = next_addr();
+
+    let config = OpentelemetryConfig::new(
+        format!("http://{}", in_addr).parse().unwrap(),
+        OpentelemetryProtocol::Http,
+    );
+    let (_, healthcheck) = config.build(SinkContext::default()).await.unwrap();
+    let (mut rx, trigger, server) = build_test_server(in_addr);
+    tokio::spawn(server);
+
+    healthcheck.await.unwrap();
+    drop(trigger);
+
+    let (parts, body) = rx.next().await.unwrap();
+    assert_eq!("/v1/logs", parts.uri.path());
+    assert_eq!(
+        ExportLogsServiceRequest::decode(body).unwrap(),
+        ExportLogsServiceRequest::default()
+    );
+}
+
+#[tokio::test]
+async fn healthcheck_fails_on_error_status() {
+    let in_addr = next_addr();
+
+    let config = OpentelemetryConfig::new(
+        format!("http://{}", in_addr).parse().unwrap(),
+        OpentelemetryProtocol::Http,
+    );
+    let (_, healthcheck) = config.build(SinkContext::default()).await.unwrap();
+    let (_rx, _trigger, server) =
+        build_test_server_status(in_addr, StatusCode::SERVICE_UNAVAILABLE);
+    tokio::spawn(server);
+
+    let error = healthcheck.await.unwrap_err();
+    assert!(error.to_string().contains("503"));
+}
+
+#[tokio::test]
+async fn grpc_rejects_unsupported_compression() {
+    let config: OpentelemetryConfig = toml::from_str(
+        r#"
+        endpoint = "http://localhost:4317"
+        protocol = "grpc"
+        compression = "zstd"
+        "#,
+    )
+    .unwrap();
+
+    let error = config.build(SinkContext::default()).await.err().unwrap();
+    assert!(error
+        .to_string()
+        .contains("not supported by the gRPC protocol"));
+}
+
+fn random_traces_with_stream(
+    count: usize,
+    batch: Option<BatchNotifier>,
+) -> (Vec<String>, impl Stream<Item = EventArray>) {
+    let names = (0..count).map(|_| random_string(16)).collect::<Vec<_>>();
+    let events = names
+        .iter()
+        .map(|name| {
+            let mut trace = TraceEvent::default();
+            trace.insert(event_path!("name"), name.clone());
+            Event::from(trace)
+        })
+        .collect::<Vec<_>>();
+    (names, map_event_batch_stream(stream::iter(events), batch))
+}
+
+fn span_names(request: ExportTraceServiceRequest) -> Vec<String> {
+    request
+        .resource_spans
+        .into_iter()
+        .flat_map(|resource_spans| resource_spans.scope_spans)
+        .flat_map(|scope_spans| scope_spans.spans)
+        .map(|span| span.name)
+        .collect()
+}
+
+async fn get_received(
+    rx: mpsc::Receiver<(Parts, Bytes)>,
+    decode: impl Fn(Parts, Bytes) -> Vec<String>,
+) -> Vec<String> {
+    rx.map(|(parts, body)| decode(parts, body))
+        .collect::<Vec<_>>()
+        .await
+        .into_iter()
+        .flatten()
+        .collect()
+}
+
+// taken from <https://github.com/hyperium/tonic/blob/5aa8ae1fec27377cd4c2a41d309945d7e38087d0/examples/src/grpc-web/client.rs#L45-L75>
+fn encode_body<T>(msg: T) -> Bytes
+where
+    T: prost::Message,
+{
+    let mut buf = BytesMut::with_capacity(1024);
+
+    // first skip past the header
+    // cannot write it yet since we don't know the size of the
+    // encoded message
+    buf.reserve(GRPC_HEADER_SIZE);
+    unsafe {
+        buf.advance_mut(GRPC_HEADER_SIZE);
+    }
+
+    // write the message
+    msg.encode(&mut buf).unwrap();
+
+    // now we know the size of encoded message and can write the
+    // header
+    let len = buf.len() - GRPC_HEADER_SIZE;
+    {
+        let mut buf = &mut buf[..GRPC_HEADER_SIZE];
+
+        // compression flag, 0 means "no compression"
+        buf.put_u8(0);
+
+        buf.put_u32(len as u32);
+    }
+
+    buf.split_to(len + GRPC_HEADER_SIZE).freeze()
+}
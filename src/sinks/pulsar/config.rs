@@ -1,4 +1,5 @@
 use crate::{
+    common::pulsar::{create_pulsar_client, PulsarAuthConfig},
     schema,
     sinks::{
         prelude::*,
@@ -7,18 +8,12 @@ use crate::{
 };
 use futures_util::FutureExt;
 use pulsar::{
-    authentication::oauth2::{OAuth2Authentication, OAuth2Params},
-    compression,
-    message::proto,
-    Authentication, ConnectionRetryOptions, Error as PulsarError, ProducerOptions, Pulsar,
-    TokioExecutor,
+    compression, message::proto, Error as PulsarError, ProducerOptions, Pulsar, TokioExecutor,
 };
-use pulsar::{error::AuthenticationError, OperationRetryOptions};
 use snafu::ResultExt;
 use vector_lib::codecs::{encoding::SerializerConfig, TextSerializerConfig};
 use vector_lib::config::DataType;
 use vector_lib::lookup::lookup_v2::OptionalTargetPath;
-use vector_lib::sensitive_string::SensitiveString;
 use vrl::value::Kind;
 
 /// Configuration for the `pulsar` sink.
@@ -96,58 +91,6 @@ pub(crate) struct PulsarBatchConfig {
     pub max_bytes: Option<usize>,
 }
 
-/// Authentication configuration.
-#[configurable_component]
-#[derive(Clone, Debug)]
-pub(crate) struct PulsarAuthConfig {
-    /// Basic authentication name/username.
-    ///
-    /// This can be used either for basic authentication (username/password) or JWT authentication.
-    /// When used for JWT, the value should be `token`.
-    #[configurable(metadata(docs::examples = "${PULSAR_NAME}"))]
-    #[configurable(metadata(docs::examples = "name123"))]
-    name: Option<String>,
-
-    /// Basic authentication password/token.
-    ///
-    /// This can be used either for basic authentication (username/password) or JWT authentication.
-    /// When used for JWT, the value should be the signed JWT, in the compact representation.
-    #[configurable(metadata(docs::examples = "${PULSAR_TOKEN}"))]
-    #[configurable(metadata(docs::examples = "123456789"))]
-    token: Option<SensitiveString>,
-
-    #[configurable(derived)]
-    oauth2: Option<OAuth2Config>,
-}
-
-/// OAuth2-specific authentication configuration.
-#[configurable_component]
-#[derive(Clone, Debug)]
-pub struct OAuth2Config {
-    /// The issuer URL.
-    #[configurable(metadata(docs::examples = "${OAUTH2_ISSUER_URL}"))]
-    #[configurable(metadata(docs::examples = "https://oauth2.issuer"))]
-    issuer_url: String,
-
-    /// The credentials URL.
-    ///
-    /// A data URL is also supported.
-    #[configurable(metadata(docs::examples = "{OAUTH2_CREDENTIALS_URL}"))]
-    #[configurable(metadata(docs::examples = "file:///oauth2_credentials"))]
-    #[configurable(metadata(docs::examples = "data:application/json;base64,cHVsc2FyCg=="))]
-    credentials_url: String,
-
-    /// The OAuth2 audience.
-    #[configurable(metadata(docs::examples = "${OAUTH2_AUDIENCE}"))]
-    #[configurable(metadata(docs::examples = "pulsar"))]
-    audience: Option<String>,
-
-    /// The OAuth2 scope.
-    #[configurable(metadata(docs::examples = "${OAUTH2_SCOPE}"))]
-    #[configurable(metadata(docs::examples = "admin"))]
-    scope: Option<String>,
-}
-
 /// Supported compression types for Pulsar.
 #[configurable_component]
 #[derive(Clone, Copy, Debug, Derivative)]
@@ -191,41 +134,7 @@ impl Default for PulsarSinkConfig {
 
 impl PulsarSinkConfig {
     pub(crate) async fn create_pulsar_client(&self) -> Result<Pulsar<TokioExecutor>, PulsarError> {
-        let mut builder = Pulsar::builder(&self.endpoint, TokioExecutor);
-        if let Some(auth) = &self.auth {
-            builder = match (
-                auth.name.as_ref(),
-                auth.token.as_ref(),
-                auth.oauth2.as_ref(),
-            ) {
-                (Some(name), Some(token), None) => builder.with_auth(Authentication {
-                    name: name.clone(),
-                    data: token.inner().as_bytes().to_vec(),
-                }),
-                (None, None, Some(oauth2)) => builder.with_auth_provider(
-                    OAuth2Authentication::client_credentials(OAuth2Params {
-                        issuer_url: oauth2.issuer_url.clone(),
-                        credentials_url: oauth2.credentials_url.clone(),
-                        audience: oauth2.audience.clone(),
-                        scope: oauth2.scope.clone(),
-                    }),
-                ),
-                _ => return Err(PulsarError::Authentication(AuthenticationError::Custom(
-                    "Invalid auth config: can only specify name and token or oauth2 configuration"
-                        .to_string(),
-                ))),
-            };
-        }
-
-        // Apply configuration for reconnection exponential backoff.
-        let retry_opts = ConnectionRetryOptions::default();
-        builder = builder.with_connection_retry_options(retry_opts);
-
-        // Apply configuration for retrying Pulsar operations.
-        let operation_retry_opts = OperationRetryOptions::default();
-        builder = builder.with_operation_retry_options(operation_retry_opts);
-
-        builder.build().await
+        create_pulsar_client(&self.endpoint, self.auth.as_ref()).await
     }
 
     pub(crate) fn build_producer_options(&self) -> ProducerOptions {
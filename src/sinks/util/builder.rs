@@ -20,6 +20,7 @@ use vector_lib::stream::{
 use vector_lib::{
     event::{Finalizable, Metric},
     partition::Partitioner,
+    sink_errors::ErrorsOutput,
     ByteSizeOf,
 };
 
@@ -120,11 +121,16 @@ pub trait SinkBuilderExt: Stream {
         // the span context in order to propagate the sink's automatic tags.
         let span = Arc::new(Span::current());
 
+        // Likewise, the sink's `errors` output has to be carried over to the spawned future so that
+        // events which fail to be encoded can be sent to it.
+        let errors = ErrorsOutput::current();
+
         self.concurrent_map(limit, move |input| {
             let builder = Arc::clone(&builder);
             let span = Arc::clone(&span);
+            let errors = errors.clone();
 
-            Box::pin(async move {
+            Box::pin(ErrorsOutput::maybe_scope(errors, async move {
                 let _entered = span.enter();
 
                 // Split the input into metadata and events.
@@ -143,7 +149,7 @@ pub trait SinkBuilderExt: Stream {
 
                 // Now build the actual request.
                 Ok(builder.build_request(metadata, request_metadata, payload))
-            })
+            }))
         })
     }
 
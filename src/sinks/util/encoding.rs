@@ -5,9 +5,14 @@ use itertools::{Itertools, Position};
 use tokio_util::codec::Encoder as _;
 use vector_lib::codecs::encoding::Framer;
 use vector_lib::request_metadata::GroupedCountByteSize;
+use vector_lib::sink_errors::{ErrorsOutput, SinkError};
 use vector_lib::{config::telemetry, EstimatedJsonEncodedSizeOf};
 
-use crate::{codecs::Transformer, event::Event, internal_events::EncoderWriteError};
+use crate::{
+    codecs::Transformer,
+    event::{Event, Finalizable},
+    internal_events::EncoderWriteError,
+};
 
 pub trait Encoder<T> {
     /// Encodes the input into the provided writer.
@@ -28,46 +33,48 @@ impl Encoder<Vec<Event>> for (Transformer, crate::codecs::Encoder<Framer>) {
         events: Vec<Event>,
         writer: &mut dyn io::Write,
     ) -> io::Result<(usize, GroupedCountByteSize)> {
-        let mut encoder = self.1.clone();
-        let mut bytes_written = 0;
-        let mut n_events_pending = events.len();
-        let batch_prefix = encoder.batch_prefix();
-        write_all(writer, n_events_pending, batch_prefix)?;
-        bytes_written += batch_prefix.len();
-
-        let mut byte_size = telemetry().create_request_count_byte_size();
-
-        for (position, mut event) in events.into_iter().with_position() {
-            self.0.transform(&mut event);
-
-            // Ensure the json size is calculated after any fields have been removed
-            // by the transformer.
-            byte_size.add_event(&event, event.estimated_json_encoded_size_of());
-
-            let mut bytes = BytesMut::new();
-            match position {
-                Position::Last | Position::Only => {
-                    encoder
-                        .serialize(event, &mut bytes)
-                        .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
-                }
-                _ => {
-                    encoder
-                        .encode(event, &mut bytes)
-                        .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+        with_errors_output(events, |events| {
+            let mut encoder = self.1.clone();
+            let mut bytes_written = 0;
+            let mut n_events_pending = events.len();
+            let batch_prefix = encoder.batch_prefix();
+            write_all(writer, n_events_pending, batch_prefix)?;
+            bytes_written += batch_prefix.len();
+
+            let mut byte_size = telemetry().create_request_count_byte_size();
+
+            for (position, mut event) in events.with_position() {
+                self.0.transform(&mut event);
+
+                // Ensure the json size is calculated after any fields have been removed
+                // by the transformer.
+                byte_size.add_event(&event, event.estimated_json_encoded_size_of());
+
+                let mut bytes = BytesMut::new();
+                match position {
+                    Position::Last | Position::Only => {
+                        encoder
+                            .serialize(event, &mut bytes)
+                            .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+                    }
+                    _ => {
+                        encoder
+                            .encode(event, &mut bytes)
+                            .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+                    }
                 }
+                write_all(writer, n_events_pending, &bytes)?;
+                bytes_written += bytes.len();
+                n_events_pending -= 1;
             }
-            write_all(writer, n_events_pending, &bytes)?;
-            bytes_written += bytes.len();
-            n_events_pending -= 1;
-        }
 
-        let batch_suffix = encoder.batch_suffix();
-        assert!(n_events_pending == 0);
-        write_all(writer, 0, batch_suffix)?;
-        bytes_written += batch_suffix.len();
+            let batch_suffix = encoder.batch_suffix();
+            assert!(n_events_pending == 0);
+            write_all(writer, 0, batch_suffix)?;
+            bytes_written += batch_suffix.len();
 
-        Ok((bytes_written, byte_size))
+            Ok((bytes_written, byte_size))
+        })
     }
 }
 
@@ -77,19 +84,47 @@ impl Encoder<Event> for (Transformer, crate::codecs::Encoder<()>) {
         mut event: Event,
         writer: &mut dyn io::Write,
     ) -> io::Result<(usize, GroupedCountByteSize)> {
-        let mut encoder = self.1.clone();
-        self.0.transform(&mut event);
-
-        let mut byte_size = telemetry().create_request_count_byte_size();
-        byte_size.add_event(&event, event.estimated_json_encoded_size_of());
-
-        let mut bytes = BytesMut::new();
-        encoder
-            .serialize(event, &mut bytes)
-            .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
-        write_all(writer, 1, &bytes)?;
-        Ok((bytes.len(), byte_size))
+        with_errors_output(vec![event], |events| {
+            let mut event = events.next().expect("there should be one event");
+            let mut encoder = self.1.clone();
+            self.0.transform(&mut event);
+
+            let mut byte_size = telemetry().create_request_count_byte_size();
+            byte_size.add_event(&event, event.estimated_json_encoded_size_of());
+
+            let mut bytes = BytesMut::new();
+            encoder
+                .serialize(event, &mut bytes)
+                .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+            write_all(writer, 1, &bytes)?;
+            Ok((bytes.len(), byte_size))
+        })
+    }
+}
+
+/// Runs the given encoding function, sending the events to the sink's `errors` output if encoding
+/// them fails and the output is in use.
+fn with_errors_output<F>(mut events: Vec<Event>, f: F) -> io::Result<(usize, GroupedCountByteSize)>
+where
+    F: FnOnce(
+        &mut dyn ExactSizeIterator<Item = Event>,
+    ) -> io::Result<(usize, GroupedCountByteSize)>,
+{
+    let Some(errors) = ErrorsOutput::current() else {
+        return f(&mut events.into_iter());
+    };
+
+    // Each event is only copied when it is encoded, rather than copying the whole batch upfront,
+    // and the events are kept as they were before being transformed, to be sent to the `errors`
+    // output if encoding fails.
+    let result = f(&mut events.iter().cloned());
+    if let Err(error) = &result {
+        // The finalizers of the events are held by the request being built, so the events sent to
+        // the `errors` output don't carry any.
+        drop(events.take_finalizers());
+        errors.send_rejected(events, &SinkError::new(error.to_string()));
     }
+    result
 }
 
 /// Write the buffer to the writer. If the operation fails, emit an internal event which complies with the
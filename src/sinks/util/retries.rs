@@ -11,6 +11,7 @@ use futures::FutureExt;
 use tokio::time::{sleep, Sleep};
 use tower::{retry::Policy, timeout::error::Elapsed};
 use vector_lib::configurable::configurable_component;
+use vector_lib::sink_errors::{record_request_error, SinkError};
 
 use crate::Error;
 
@@ -41,6 +42,15 @@ pub trait RetryLogic: Clone + Send + Sync + 'static {
         // Treat the default as the request is successful
         RetryAction::Successful
     }
+
+    /// Returns the status code of the given response, if the downstream service has one, such as
+    /// an HTTP status code.
+    ///
+    /// This is included in the error metadata of events sent to the `errors` output of the sink
+    /// when a response is not retried.
+    fn response_status_code(&self, _response: &Self::Response) -> Option<u16> {
+        None
+    }
 }
 
 /// The jitter mode to use for retry backoff behavior.
@@ -143,7 +153,7 @@ where
     type Future = RetryPolicyFuture<L>;
 
     // NOTE: in the error cases- `Error` and `EventsDropped` internal events are emitted by the
-    // driver, so only need to log here.
+    // driver, so only need to log here, and record the error for the sink's `errors` output.
     fn retry(&self, _: &Req, result: Result<&Res, &Error>) -> Option<Self::Future> {
         match result {
             Ok(response) => match self.logic.should_retry_response(response) {
@@ -154,6 +164,10 @@ where
                             reason = ?reason,
                             internal_log_rate_limit = true,
                         );
+                        record_request_error(
+                            SinkError::new(reason)
+                                .with_status_code(self.logic.response_status_code(response)),
+                        );
                         return None;
                     }
 
@@ -163,6 +177,10 @@ where
 
                 RetryAction::DontRetry(reason) => {
                     error!(message = "Not retriable; dropping the request.", reason = ?reason, internal_log_rate_limit = true);
+                    record_request_error(
+                        SinkError::new(reason)
+                            .with_status_code(self.logic.response_status_code(response)),
+                    );
                     None
                 }
 
@@ -171,6 +189,7 @@ where
             Err(error) => {
                 if self.remaining_attempts == 0 {
                     error!(message = "Retries exhausted; dropping the request.", %error, internal_log_rate_limit = true);
+                    record_request_error(SinkError::new(error.to_string()));
                     return None;
                 }
 
@@ -184,6 +203,7 @@ where
                             %error,
                             internal_log_rate_limit = true,
                         );
+                        record_request_error(SinkError::new(error.to_string()));
                         None
                     }
                 } else if error.downcast_ref::<Elapsed>().is_some() {
@@ -198,6 +218,7 @@ where
                         %error,
                         internal_log_rate_limit = true
                     );
+                    record_request_error(SinkError::new(error.to_string()));
                     None
                 }
             }
@@ -53,6 +53,7 @@ use vector_lib::internal_event::{
 };
 // === StreamSink<Event> ===
 pub use vector_lib::sink::StreamSink;
+use vector_lib::sink_errors::{capture_request_error, ErrorsOutput, SinkError};
 
 use super::{
     batch::{Batch, EncodedBatch, FinalizersBatch, PushResult, StatefulBatch},
@@ -442,11 +443,19 @@ where
             in_flight_requests = self.in_flight.len()
         );
         let events_sent = register!(EventsSent::from(Output(None)));
-        self.service
-            .call(items)
-            .err_into()
-            .map(move |result| {
+        let errors = ErrorsOutput::current();
+        capture_request_error(self.service.call(items).err_into())
+            .map(move |(result, request_error)| {
                 let status = result_status(&result);
+                if let Some(errors) = errors {
+                    if status != EventStatus::Delivered {
+                        let error = request_error.unwrap_or_else(|| match &result {
+                            Err(error) => SinkError::new(error.to_string()),
+                            Ok(_) => SinkError::new("Response failed."),
+                        });
+                        errors.reject(&finalizers, &error);
+                    }
+                }
                 finalizers.update_status(status);
                 match status {
                     EventStatus::Delivered => {
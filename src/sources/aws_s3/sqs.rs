@@ -1,5 +1,5 @@
 use std::collections::HashMap;
-use std::{future::ready, num::NonZeroUsize, panic, sync::Arc};
+use std::{num::NonZeroUsize, panic, sync::Arc};
 
 use aws_sdk_s3::operation::get_object::GetObjectError;
 use aws_sdk_s3::Client as S3Client;
@@ -14,19 +14,18 @@ use aws_smithy_runtime_api::client::result::SdkError;
 use aws_types::region::Region;
 use bytes::Bytes;
 use chrono::{DateTime, TimeZone, Utc};
-use futures::{FutureExt, Stream, StreamExt, TryFutureExt};
+use futures::{FutureExt, StreamExt, TryFutureExt};
 use once_cell::sync::Lazy;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use serde_with::serde_as;
 use smallvec::SmallVec;
 use snafu::{ResultExt, Snafu};
 use tokio::{pin, select};
-use tokio_util::codec::FramedRead;
 use tracing::Instrument;
 use vector_lib::codecs::decoding::FramingError;
 use vector_lib::configurable::configurable_component;
 use vector_lib::internal_event::{
-    ByteSize, BytesReceived, CountByteSize, InternalEventHandle as _, Protocol, Registered,
+    BytesReceived, CountByteSize, InternalEventHandle as _, Protocol, Registered,
 };
 
 use crate::codecs::Decoder;
@@ -40,9 +39,9 @@ use crate::{
         SqsMessageReceiveError, SqsMessageReceiveSucceeded, SqsS3EventRecordInvalidEventIgnored,
         StreamClosedError,
     },
-    line_agg::{self, LineAgg},
+    line_agg,
     shutdown::ShutdownSignal,
-    sources::aws_s3::AwsS3Config,
+    sources::{aws_s3::AwsS3Config, util::object_storage::object_lines},
     tls::TlsConfig,
     SourceSender,
 };
@@ -511,45 +510,20 @@ impl IngestorProcess {
         // Record the read error seen to propagate up later so we avoid ack'ing the SQS
         // message
         //
-        // String is used as we cannot clone std::io::Error to take ownership in closure
-        //
-        // FramedRead likely stops when it gets an i/o error but I found it more clear to
-        // show that we `take_while` there hasn't been an error
-        //
         // This can result in objects being partially processed before an error, but we
         // prefer duplicate lines over message loss. Future work could include recording
         // the offset of the object that has been read, but this would only be relevant in
         // the case that the same vector instance processes the same message.
         let mut read_error = None;
-        let bytes_received = self.bytes_received.clone();
         let events_received = self.events_received.clone();
-        let lines: Box<dyn Stream<Item = Bytes> + Send + Unpin> = Box::new(
-            FramedRead::new(object_reader, self.state.decoder.framer.clone())
-                .map(|res| {
-                    res.map(|bytes| {
-                        bytes_received.emit(ByteSize(bytes.len()));
-                        bytes
-                    })
-                    .map_err(|err| {
-                        read_error = Some(err);
-                    })
-                    .ok()
-                })
-                .take_while(|res| ready(res.is_some()))
-                .map(|r| r.expect("validated by take_while")),
+        let lines = object_lines(
+            object_reader,
+            self.state.decoder.framer.clone(),
+            self.state.multiline.as_ref(),
+            self.bytes_received.clone(),
+            &mut read_error,
         );
 
-        let lines: Box<dyn Stream<Item = Bytes> + Send + Unpin> = match &self.state.multiline {
-            Some(config) => Box::new(
-                LineAgg::new(
-                    lines.map(|line| ((), line, ())),
-                    line_agg::Logic::new(config.clone()),
-                )
-                .map(|(_src, line, _context, _lastline_context)| line),
-            ),
-            None => lines,
-        };
-
         let mut stream = lines.flat_map(|line| {
             let events = match self.state.decoder.deserializer_parse(line) {
                 Ok((events, _events_size)) => events,
@@ -0,0 +1,195 @@
+use std::{io::Write, time::Duration};
+
+use azure_core::error::HttpError;
+use flate2::{write::GzEncoder, Compression as GzCompression};
+use http::StatusCode;
+use vector_lib::config::log_schema;
+
+use super::*;
+use crate::{
+    config::SourceContext,
+    event::Event,
+    line_agg::Mode,
+    test_util::{
+        collect_n, collect_ready,
+        components::{run_and_assert_source_compliance_n, SOURCE_TAGS},
+        random_lines, random_string, temp_dir, trace_init,
+    },
+    SourceSender,
+};
+
+const CONTAINER_NAME: &str = "logs";
+
+fn connection_string() -> String {
+    let address = std::env::var("AZURE_ADDRESS").unwrap_or_else(|_| "localhost".into());
+    format!("UseDevelopmentStorage=true;DefaultEndpointsProtocol=http;AccountName=devstoreaccount1;AccountKey=Eby8vdM02xNOcqFlqUwJPLlmEtlCDXJ1OUzFT50uSRZ6IFsuFq2UVErCz4I6tq/K1SZFPTOtr/KBHBeksoGMGw==;BlobEndpoint=http://{}:10000/devstoreaccount1;", address)
+}
+
+fn client() -> Arc<ContainerClient> {
+    build_client(
+        Some(connection_string()),
+        None,
+        CONTAINER_NAME.to_string(),
+        None,
+    )
+    .expect("Failed to create client")
+}
+
+async fn make_config(blob_prefix: &str) -> AzureBlobConfig {
+    let response = match client()
+        .create()
+        .public_access(PublicAccess::None)
+        .into_future()
+        .await
+    {
+        Ok(_) => Ok(()),
+        Err(reason) => match reason.downcast_ref::<HttpError>() {
+            Some(err) if StatusCode::from_u16(err.status().into()) == Ok(StatusCode::CONFLICT) => {
+                Ok(())
+            }
+            _ => Err(reason),
+        },
+    };
+    response.expect("Failed to create container");
+
+    let data_dir = temp_dir();
+    std::fs::create_dir_all(&data_dir).unwrap();
+
+    AzureBlobConfig {
+        connection_string: Some(connection_string().into()),
+        container_name: CONTAINER_NAME.to_string(),
+        blob_prefix: blob_prefix.to_string(),
+        poll_interval_secs: Duration::from_secs(1),
+        data_dir: Some(data_dir),
+        ..Default::default()
+    }
+}
+
+async fn put_blob(name: &str, data: Vec<u8>, content_encoding: Option<&'static str>) {
+    let blob = client()
+        .blob_client(name)
+        .put_block_blob(Bytes::from(data))
+        .content_type("text/plain");
+    let blob = match content_encoding {
+        Some(encoding) => blob.content_encoding(encoding),
+        None => blob,
+    };
+    blob.into_future().await.expect("Failed to put blob");
+}
+
+fn gzip(data: &[u8]) -> Vec<u8> {
+    let mut encoder = GzEncoder::new(Vec::new(), GzCompression::fast());
+    encoder.write_all(data).unwrap();
+    encoder.finish().unwrap()
+}
+
+fn messages(events: &[Event]) -> Vec<String> {
+    events
+        .iter()
+        .map(|event| {
+            event.as_log()[log_schema().message_key().unwrap().to_string()]
+                .to_string_lossy()
+                .into_owned()
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn azure_blob_ingests_blobs() {
+    trace_init();
+
+    let prefix = format!("{}/", random_string(10));
+    let plain = random_lines(100).take(5).collect::<Vec<_>>();
+    let compressed = random_lines(100).take(5).collect::<Vec<_>>();
+    put_blob(
+        &format!("{prefix}1.log"),
+        plain.join("\n").into_bytes(),
+        None,
+    )
+    .await;
+    put_blob(
+        &format!("{prefix}2.log"),
+        gzip(compressed.join("\n").as_bytes()),
+        Some("gzip"),
+    )
+    .await;
+    put_blob("other.log", b"ignored".to_vec(), None).await;
+
+    let config = make_config(&prefix).await;
+    let events = run_and_assert_source_compliance_n(config, 10, &SOURCE_TAGS).await;
+
+    let mut expected = plain;
+    expected.extend(compressed);
+    assert_eq!(messages(&events), expected);
+    let log = events[0].as_log();
+    assert_eq!(log["container"], CONTAINER_NAME.into());
+    assert_eq!(log["object"], format!("{prefix}1.log").into());
+}
+
+#[tokio::test]
+async fn azure_blob_aggregates_multiline() {
+    trace_init();
+
+    let prefix = format!("{}/", random_string(10));
+    put_blob(
+        &format!("{prefix}trace.log"),
+        b"first\n  at one\n  at two\nsecond\n  at three\n".to_vec(),
+        None,
+    )
+    .await;
+
+    let mut config = make_config(&prefix).await;
+    config.multiline = Some(MultilineConfig {
+        start_pattern: "^[^\\s]".to_owned(),
+        condition_pattern: "^[\\s]+".to_owned(),
+        mode: Mode::ContinueThrough,
+        timeout_ms: Duration::from_millis(1000),
+    });
+    let events = run_and_assert_source_compliance_n(config, 2, &SOURCE_TAGS).await;
+
+    assert_eq!(
+        messages(&events),
+        vec!["first\n  at one\n  at two", "second\n  at three"]
+    );
+}
+
+#[tokio::test]
+async fn azure_blob_resumes_from_checkpoint() {
+    trace_init();
+
+    let prefix = format!("{}/", random_string(10));
+    put_blob(&format!("{prefix}1.log"), b"one\ntwo".to_vec(), None).await;
+    let config = make_config(&prefix).await;
+
+    let (tx, rx) = SourceSender::new_test();
+    let source = tokio::spawn(
+        config
+            .build(SourceContext::new_test(tx, None))
+            .await
+            .unwrap(),
+    );
+    let events = tokio::time::timeout(Duration::from_secs(10), collect_n(rx, 2))
+        .await
+        .expect("blob wasn't ingested");
+    assert_eq!(messages(&events), vec!["one", "two"]);
+    // Give the source time to persist the checkpoint.
+    tokio::time::sleep(Duration::from_secs(1)).await;
+    source.abort();
+
+    put_blob(&format!("{prefix}2.log"), b"three".to_vec(), None).await;
+
+    let (tx, mut rx) = SourceSender::new_test();
+    tokio::spawn(
+        config
+            .build(SourceContext::new_test(tx, None))
+            .await
+            .unwrap(),
+    );
+    let events = tokio::time::timeout(Duration::from_secs(10), collect_n(&mut rx, 1))
+        .await
+        .expect("new blob wasn't ingested");
+    assert_eq!(messages(&events), vec!["three"]);
+
+    tokio::time::sleep(Duration::from_secs(2)).await;
+    assert!(collect_ready(&mut rx).await.is_empty());
+}
@@ -0,0 +1,340 @@
+use std::{convert::TryInto, io, path::PathBuf, sync::Arc, time::Duration};
+
+use azure_storage_blobs::prelude::*;
+use bytes::Bytes;
+use chrono::{TimeZone, Utc};
+use futures::{stream::BoxStream, StreamExt, TryStreamExt};
+use serde_with::serde_as;
+use vector_lib::codecs::decoding::{DeserializerConfig, FramingConfig};
+use vector_lib::codecs::NewlineDelimitedDecoderConfig;
+use vector_lib::config::LogNamespace;
+use vector_lib::configurable::configurable_component;
+use vector_lib::sensitive_string::SensitiveString;
+
+use super::util::{
+    object_storage::{
+        listing::{self, ListingSource, ObjectInfo, ObjectStorageClient, CHECKPOINT_FILE_NAME},
+        Compression,
+    },
+    MultilineConfig,
+};
+use crate::{
+    codecs::DecodingConfig,
+    common::azure_blob::build_client,
+    config::{
+        GenerateConfig, SourceAcknowledgementsConfig, SourceConfig, SourceContext, SourceOutput,
+    },
+    line_agg,
+    serde::{bool_or_struct, default_decoding},
+};
+
+#[cfg(all(test, feature = "azure-blob-integration-tests"))]
+mod integration_tests;
+
+/// Configuration for the `azure_blob` source.
+#[serde_as]
+#[configurable_component(source("azure_blob", "Collect logs from Azure Blob Storage."))]
+#[derive(Clone, Debug, Derivative)]
+#[derivative(Default)]
+#[serde(deny_unknown_fields)]
+pub struct AzureBlobConfig {
+    /// The Azure Blob Storage Account connection string.
+    ///
+    /// Authentication with access key is the only supported authentication method.
+    ///
+    /// Either `storage_account`, or this field, must be specified.
+    #[configurable(metadata(
+        docs::examples = "DefaultEndpointsProtocol=https;AccountName=mylogstorage;AccountKey=storageaccountkeybase64encoded;EndpointSuffix=core.windows.net"
+    ))]
+    connection_string: Option<SensitiveString>,
+
+    /// The Azure Blob Storage Account name.
+    ///
+    /// Attempts to load credentials for the account in the following ways, in order:
+    ///
+    /// - read from environment variables ([more information][env_cred_docs])
+    /// - looks for a [Managed Identity][managed_ident_docs]
+    /// - uses the `az` CLI tool to get an access token ([more information][az_cli_docs])
+    ///
+    /// Either `connection_string`, or this field, must be specified.
+    ///
+    /// [env_cred_docs]: https://docs.rs/azure_identity/latest/azure_identity/struct.EnvironmentCredential.html
+    /// [managed_ident_docs]: https://docs.microsoft.com/en-us/azure/active-directory/managed-identities-azure-resources/overview
+    /// [az_cli_docs]: https://docs.microsoft.com/en-us/cli/azure/account?view=azure-cli-latest#az-account-get-access-token
+    #[configurable(metadata(docs::examples = "mylogstorage"))]
+    storage_account: Option<String>,
+
+    /// The Azure Blob Storage Endpoint URL.
+    ///
+    /// This is used to override the default blob storage endpoint URL in cases where you are using
+    /// credentials read from the environment/managed identities or access tokens without using an
+    /// explicit connection_string (which already explicitly supports overriding the blob endpoint
+    /// URL).
+    ///
+    /// This may only be used with `storage_account` and is ignored when used with
+    /// `connection_string`.
+    #[configurable(metadata(docs::examples = "https://test.blob.core.usgovcloudapi.net/"))]
+    #[configurable(metadata(docs::examples = "https://test.blob.core.windows.net/"))]
+    endpoint: Option<String>,
+
+    /// The Azure Blob Storage Account container name.
+    #[configurable(metadata(docs::examples = "my-logs"))]
+    container_name: String,
+
+    /// The prefix of the names of the blobs to ingest.
+    ///
+    /// A trailing `/` is **not** automatically added. By default, all blobs of the container are
+    /// ingested.
+    #[configurable(metadata(docs::examples = "logs/"))]
+    #[configurable(metadata(docs::examples = "date=2024-01-01/"))]
+    #[serde(default)]
+    blob_prefix: String,
+
+    /// The interval between listings of the blobs, in seconds.
+    ///
+    /// Each listing goes through all the blobs matching `blob_prefix`, and ingests the ones that
+    /// were created or modified since the last ingested blob, in the order of their modification
+    /// time. Blobs that are overwritten are ingested again.
+    #[serde(default = "default_poll_interval_secs")]
+    #[serde_as(as = "serde_with::DurationSeconds<u64>")]
+    #[derivative(Default(value = "default_poll_interval_secs()"))]
+    #[configurable(metadata(docs::human_name = "Poll Interval"))]
+    poll_interval_secs: Duration,
+
+    /// The compression scheme used for decompressing blobs.
+    #[serde(default)]
+    compression: Compression,
+
+    /// Multiline aggregation configuration.
+    ///
+    /// If not specified, multiline aggregation is disabled.
+    #[configurable(derived)]
+    multiline: Option<MultilineConfig>,
+
+    /// The directory used to persist the checkpoint of the ingested blobs.
+    ///
+    /// By default, the [global `data_dir` option][global_data_dir] is used.
+    /// Make sure the running user has write permissions to this directory.
+    ///
+    /// If this directory is specified, then Vector will attempt to create it.
+    ///
+    /// [global_data_dir]: https://vector.dev/docs/reference/configuration/global-options/#data_dir
+    #[serde(default)]
+    #[configurable(metadata(docs::examples = "/var/local/lib/vector/"))]
+    #[configurable(metadata(docs::human_name = "Data Directory"))]
+    data_dir: Option<PathBuf>,
+
+    #[configurable(derived)]
+    #[serde(default = "default_framing")]
+    #[derivative(Default(value = "default_framing()"))]
+    framing: FramingConfig,
+
+    #[configurable(derived)]
+    #[serde(default = "default_decoding")]
+    #[derivative(Default(value = "default_decoding()"))]
+    decoding: DeserializerConfig,
+
+    #[configurable(derived)]
+    #[serde(default, deserialize_with = "bool_or_struct")]
+    acknowledgements: SourceAcknowledgementsConfig,
+
+    /// The namespace to use for logs. This overrides the global setting.
+    #[configurable(metadata(docs::hidden))]
+    #[serde(default)]
+    log_namespace: Option<bool>,
+}
+
+const fn default_poll_interval_secs() -> Duration {
+    Duration::from_secs(60)
+}
+
+fn default_framing() -> FramingConfig {
+    NewlineDelimitedDecoderConfig::new().into()
+}
+
+impl GenerateConfig for AzureBlobConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"
+            connection_string = "DefaultEndpointsProtocol=https;AccountName=mylogstorage;AccountKey=storageaccountkeybase64encoded;EndpointSuffix=core.windows.net"
+            container_name = "logs""#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "azure_blob")]
+impl SourceConfig for AzureBlobConfig {
+    async fn build(&self, cx: SourceContext) -> crate::Result<super::Source> {
+        let log_namespace = cx.log_namespace(self.log_namespace);
+
+        let multiline: Option<line_agg::Config> = self
+            .multiline
+            .as_ref()
+            .map(|config| config.try_into())
+            .transpose()?;
+
+        let client = build_client(
+            self.connection_string
+                .as_ref()
+                .map(|v| v.inner().to_string()),
+            self.storage_account.clone(),
+            self.container_name.clone(),
+            self.endpoint.clone(),
+        )?;
+
+        let decoder =
+            DecodingConfig::new(self.framing.clone(), self.decoding.clone(), log_namespace)
+                .build()?;
+
+        let data_dir = cx
+            .globals
+            .resolve_and_make_data_subdir(self.data_dir.as_ref(), cx.key.id())?;
+
+        let source = ListingSource {
+            client: AzureBlobClient { client },
+            source_name: Self::NAME,
+            container_key: "container",
+            container: self.container_name.clone(),
+            prefix: self.blob_prefix.clone(),
+            poll_interval: self.poll_interval_secs,
+            compression: self.compression,
+            multiline,
+            decoder,
+            checkpoint_file: data_dir.join(CHECKPOINT_FILE_NAME),
+            acknowledgements: cx.do_acknowledgements(self.acknowledgements),
+            log_namespace,
+        };
+        Ok(Box::pin(source.run(cx.out, cx.shutdown)))
+    }
+
+    fn outputs(&self, global_log_namespace: LogNamespace) -> Vec<SourceOutput> {
+        let log_namespace = global_log_namespace.merge(self.log_namespace);
+        let schema_definition = listing::schema_definition(
+            self.decoding.schema_definition(log_namespace),
+            Self::NAME,
+            "container",
+            log_namespace,
+        );
+
+        vec![SourceOutput::new_logs(
+            self.decoding.output_type(),
+            schema_definition,
+        )]
+    }
+
+    fn can_acknowledge(&self) -> bool {
+        true
+    }
+}
+
+struct AzureBlobClient {
+    client: Arc<ContainerClient>,
+}
+
+#[async_trait::async_trait]
+impl ObjectStorageClient for AzureBlobClient {
+    async fn list_objects(&self, prefix: &str) -> crate::Result<Vec<ObjectInfo>> {
+        let mut builder = self.client.list_blobs().include_metadata(true);
+        if !prefix.is_empty() {
+            builder = builder.prefix(prefix.to_string());
+        }
+
+        let mut pages = builder.into_stream();
+        let mut objects = Vec::new();
+        while let Some(page) = pages.next().await {
+            objects.extend(
+                page?.blobs.blobs().map(|blob| ObjectInfo {
+                    key: blob.name.clone(),
+                    last_modified: Utc.timestamp_nanos(
+                        blob.properties.last_modified.unix_timestamp_nanos() as i64,
+                    ),
+                    content_encoding: blob.properties.content_encoding.clone(),
+                    content_type: Some(blob.properties.content_type.clone()),
+                    metadata: blob.metadata.clone().unwrap_or_default(),
+                }),
+            );
+        }
+        Ok(objects)
+    }
+
+    async fn get_object(
+        &self,
+        object: &ObjectInfo,
+    ) -> crate::Result<BoxStream<'static, io::Result<Bytes>>> {
+        // Large blobs are read in several chunks, each with its own response.
+        let body = self
+            .client
+            .blob_client(object.key.clone())
+            .get()
+            .into_stream()
+            .map_ok(|response| response.data)
+            .try_flatten()
+            .map_err(|error| io::Error::new(io::ErrorKind::Other, error));
+        Ok(body.boxed())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use vector_lib::lookup::{owned_value_path, OwnedTargetPath};
+    use vector_lib::schema::Definition;
+    use vrl::value::{kind::Collection, Kind};
+
+    use super::*;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<AzureBlobConfig>();
+    }
+
+    #[test]
+    fn output_schema_definition_vector_namespace() {
+        let config = AzureBlobConfig {
+            log_namespace: Some(true),
+            ..Default::default()
+        };
+
+        let definitions = config
+            .outputs(LogNamespace::Vector)
+            .remove(0)
+            .schema_definition(true);
+
+        let expected_definition =
+            Definition::new_with_default_metadata(Kind::bytes(), [LogNamespace::Vector])
+                .with_meaning(OwnedTargetPath::event_root(), "message")
+                .with_metadata_field(
+                    &owned_value_path!("azure_blob", "container"),
+                    Kind::bytes(),
+                    None,
+                )
+                .with_metadata_field(
+                    &owned_value_path!("azure_blob", "object"),
+                    Kind::bytes(),
+                    None,
+                )
+                .with_metadata_field(
+                    &owned_value_path!("azure_blob", "timestamp"),
+                    Kind::timestamp(),
+                    Some("timestamp"),
+                )
+                .with_metadata_field(
+                    &owned_value_path!("vector", "source_type"),
+                    Kind::bytes(),
+                    None,
+                )
+                .with_metadata_field(
+                    &owned_value_path!("vector", "ingest_timestamp"),
+                    Kind::timestamp(),
+                    None,
+                )
+                .with_metadata_field(
+                    &owned_value_path!("azure_blob", "metadata"),
+                    Kind::object(Collection::empty().with_unknown(Kind::bytes())).or_undefined(),
+                    None,
+                );
+
+        assert_eq!(definitions, Some(expected_definition));
+    }
+}
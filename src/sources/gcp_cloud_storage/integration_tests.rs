@@ -0,0 +1,204 @@
+use std::io::Write;
+
+use flate2::{write::GzEncoder, Compression as GzCompression};
+use http::Method;
+use serde_json::json;
+use vector_lib::config::log_schema;
+
+use super::*;
+use crate::{
+    config::ProxyConfig,
+    event::Event,
+    line_agg::Mode,
+    test_util::{
+        collect_n, collect_ready,
+        components::{run_and_assert_source_compliance_n, SOURCE_TAGS},
+        random_lines, random_string, temp_dir, trace_init,
+    },
+    SourceSender,
+};
+
+fn address() -> String {
+    std::env::var("GCS_ADDRESS").unwrap_or_else(|_| "http://localhost:4443".into())
+}
+
+async fn request(method: Method, uri: String, headers: &[(&str, &str)], body: Vec<u8>) {
+    let tls = TlsSettings::from_options(&None).unwrap();
+    let client = HttpClient::new(tls, &ProxyConfig::default()).unwrap();
+    let mut request = Request::builder().method(method).uri(uri);
+    for (name, value) in headers {
+        request = request.header(*name, *value);
+    }
+    let response = client
+        .send(request.body(Body::from(body)).unwrap())
+        .await
+        .unwrap();
+    let status = response.status();
+    assert!(
+        status.is_success() || status == StatusCode::CONFLICT,
+        "Unexpected status code: {status}"
+    );
+}
+
+async fn make_config(key_prefix: &str) -> GcsSourceConfig {
+    let bucket = format!("logs-{}", random_string(10).to_lowercase());
+    request(
+        Method::POST,
+        format!("{}/storage/v1/b", address()),
+        &[("Content-Type", "application/json")],
+        serde_json::to_vec(&json!({ "name": bucket })).unwrap(),
+    )
+    .await;
+
+    let data_dir = temp_dir();
+    std::fs::create_dir_all(&data_dir).unwrap();
+
+    GcsSourceConfig {
+        bucket,
+        key_prefix: key_prefix.to_string(),
+        poll_interval_secs: Duration::from_secs(1),
+        endpoint: address(),
+        data_dir: Some(data_dir),
+        auth: GcpAuthConfig {
+            skip_authentication: true,
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+}
+
+async fn put_object(
+    config: &GcsSourceConfig,
+    name: &str,
+    data: Vec<u8>,
+    content_encoding: Option<&str>,
+) {
+    let mut headers = vec![("Content-Type", "text/plain")];
+    if let Some(content_encoding) = content_encoding {
+        headers.push(("Content-Encoding", content_encoding));
+    }
+    request(
+        Method::POST,
+        format!(
+            "{}/upload/storage/v1/b/{}/o?uploadType=media&name={}",
+            address(),
+            config.bucket,
+            encode(name)
+        ),
+        &headers,
+        data,
+    )
+    .await;
+}
+
+fn gzip(data: &[u8]) -> Vec<u8> {
+    let mut encoder = GzEncoder::new(Vec::new(), GzCompression::fast());
+    encoder.write_all(data).unwrap();
+    encoder.finish().unwrap()
+}
+
+fn messages(events: &[Event]) -> Vec<String> {
+    events
+        .iter()
+        .map(|event| {
+            event.as_log()[log_schema().message_key().unwrap().to_string()]
+                .to_string_lossy()
+                .into_owned()
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn gcp_cloud_storage_ingests_objects() {
+    trace_init();
+
+    let config = make_config("logs/").await;
+    let plain = random_lines(100).take(5).collect::<Vec<_>>();
+    let compressed = random_lines(100).take(5).collect::<Vec<_>>();
+    put_object(&config, "logs/1.log", plain.join("\n").into_bytes(), None).await;
+    put_object(
+        &config,
+        "logs/2.log",
+        gzip(compressed.join("\n").as_bytes()),
+        Some("gzip"),
+    )
+    .await;
+    put_object(&config, "other.log", b"ignored".to_vec(), None).await;
+
+    let bucket = config.bucket.clone();
+    let events = run_and_assert_source_compliance_n(config, 10, &SOURCE_TAGS).await;
+
+    let mut expected = plain;
+    expected.extend(compressed);
+    assert_eq!(messages(&events), expected);
+    let log = events[0].as_log();
+    assert_eq!(log["bucket"], bucket.into());
+    assert_eq!(log["object"], "logs/1.log".into());
+}
+
+#[tokio::test]
+async fn gcp_cloud_storage_aggregates_multiline() {
+    trace_init();
+
+    let mut config = make_config("").await;
+    put_object(
+        &config,
+        "trace.log",
+        b"first\n  at one\n  at two\nsecond\n  at three\n".to_vec(),
+        None,
+    )
+    .await;
+
+    config.multiline = Some(MultilineConfig {
+        start_pattern: "^[^\\s]".to_owned(),
+        condition_pattern: "^[\\s]+".to_owned(),
+        mode: Mode::ContinueThrough,
+        timeout_ms: Duration::from_millis(1000),
+    });
+    let events = run_and_assert_source_compliance_n(config, 2, &SOURCE_TAGS).await;
+
+    assert_eq!(
+        messages(&events),
+        vec!["first\n  at one\n  at two", "second\n  at three"]
+    );
+}
+
+#[tokio::test]
+async fn gcp_cloud_storage_resumes_from_checkpoint() {
+    trace_init();
+
+    let config = make_config("").await;
+    put_object(&config, "1.log", b"one\ntwo".to_vec(), None).await;
+
+    let (tx, rx) = SourceSender::new_test();
+    let source = tokio::spawn(
+        config
+            .build(SourceContext::new_test(tx, None))
+            .await
+            .unwrap(),
+    );
+    let events = tokio::time::timeout(Duration::from_secs(10), collect_n(rx, 2))
+        .await
+        .expect("object wasn't ingested");
+    assert_eq!(messages(&events), vec!["one", "two"]);
+    // Give the source time to persist the checkpoint.
+    tokio::time::sleep(Duration::from_secs(1)).await;
+    source.abort();
+
+    put_object(&config, "2.log", b"three".to_vec(), None).await;
+
+    let (tx, mut rx) = SourceSender::new_test();
+    tokio::spawn(
+        config
+            .build(SourceContext::new_test(tx, None))
+            .await
+            .unwrap(),
+    );
+    let events = tokio::time::timeout(Duration::from_secs(10), collect_n(&mut rx, 1))
+        .await
+        .expect("new object wasn't ingested");
+    assert_eq!(messages(&events), vec!["three"]);
+
+    tokio::time::sleep(Duration::from_secs(2)).await;
+    assert!(collect_ready(&mut rx).await.is_empty());
+}
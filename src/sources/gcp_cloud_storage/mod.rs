@@ -0,0 +1,429 @@
+use std::{borrow::Cow, collections::HashMap, convert::TryInto, io, path::PathBuf, time::Duration};
+
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use futures::{stream::BoxStream, StreamExt, TryStreamExt};
+use http::{header::ACCEPT_ENCODING, Request, StatusCode};
+use hyper::Body;
+use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
+use serde::Deserialize;
+use serde_with::serde_as;
+use snafu::{ResultExt, Snafu};
+use vector_lib::codecs::decoding::{DeserializerConfig, FramingConfig};
+use vector_lib::codecs::NewlineDelimitedDecoderConfig;
+use vector_lib::config::LogNamespace;
+use vector_lib::configurable::configurable_component;
+
+use super::util::{
+    object_storage::{
+        listing::{self, ListingSource, ObjectInfo, ObjectStorageClient, CHECKPOINT_FILE_NAME},
+        Compression,
+    },
+    MultilineConfig,
+};
+use crate::{
+    codecs::DecodingConfig,
+    config::{
+        GenerateConfig, SourceAcknowledgementsConfig, SourceConfig, SourceContext, SourceOutput,
+    },
+    gcp::{GcpAuthConfig, GcpAuthenticator, Scope},
+    http::HttpClient,
+    line_agg,
+    serde::{bool_or_struct, default_decoding},
+    tls::{TlsConfig, TlsSettings},
+};
+
+#[cfg(all(test, feature = "gcp-cloud-storage-integration-tests"))]
+mod integration_tests;
+
+#[derive(Debug, Snafu)]
+enum GcsError {
+    #[snafu(display("Invalid request: {}", source))]
+    BuildRequest { source: http::Error },
+    #[snafu(display("Unexpected status code: {}", status))]
+    UnexpectedStatus { status: StatusCode },
+    #[snafu(display("Failed to read response: {}", source))]
+    ReadResponse { source: hyper::Error },
+    #[snafu(display("Invalid response: {}", source))]
+    ParseResponse { source: serde_json::Error },
+}
+
+/// Configuration for the `gcp_cloud_storage` source.
+#[serde_as]
+#[configurable_component(source("gcp_cloud_storage", "Collect logs from GCP Cloud Storage."))]
+#[derive(Clone, Debug, Derivative)]
+#[derivative(Default)]
+#[serde(deny_unknown_fields)]
+pub struct GcsSourceConfig {
+    /// The GCS bucket name.
+    #[configurable(metadata(docs::examples = "my-bucket"))]
+    bucket: String,
+
+    /// The prefix of the names of the objects to ingest.
+    ///
+    /// A trailing `/` is **not** automatically added. By default, all objects of the bucket are
+    /// ingested.
+    #[configurable(metadata(docs::examples = "logs/"))]
+    #[configurable(metadata(docs::examples = "date=2024-01-01/"))]
+    #[serde(default)]
+    key_prefix: String,
+
+    /// The interval between listings of the objects, in seconds.
+    ///
+    /// Each listing goes through all the objects matching `key_prefix`, and ingests the ones that
+    /// were created or modified since the last ingested object, in the order of their modification
+    /// time. Objects that are overwritten are ingested again.
+    #[serde(default = "default_poll_interval_secs")]
+    #[serde_as(as = "serde_with::DurationSeconds<u64>")]
+    #[derivative(Default(value = "default_poll_interval_secs()"))]
+    #[configurable(metadata(docs::human_name = "Poll Interval"))]
+    poll_interval_secs: Duration,
+
+    /// The endpoint of the Cloud Storage JSON API.
+    ///
+    /// This is used to point to an emulator, such as `fake-gcs-server`.
+    #[configurable(metadata(docs::examples = "http://localhost:4443"))]
+    #[serde(default = "default_endpoint")]
+    #[derivative(Default(value = "default_endpoint()"))]
+    endpoint: String,
+
+    /// The compression scheme used for decompressing objects.
+    #[serde(default)]
+    compression: Compression,
+
+    /// Multiline aggregation configuration.
+    ///
+    /// If not specified, multiline aggregation is disabled.
+    #[configurable(derived)]
+    multiline: Option<MultilineConfig>,
+
+    /// The directory used to persist the checkpoint of the ingested objects.
+    ///
+    /// By default, the [global `data_dir` option][global_data_dir] is used.
+    /// Make sure the running user has write permissions to this directory.
+    ///
+    /// If this directory is specified, then Vector will attempt to create it.
+    ///
+    /// [global_data_dir]: https://vector.dev/docs/reference/configuration/global-options/#data_dir
+    #[serde(default)]
+    #[configurable(metadata(docs::examples = "/var/local/lib/vector/"))]
+    #[configurable(metadata(docs::human_name = "Data Directory"))]
+    data_dir: Option<PathBuf>,
+
+    #[serde(flatten)]
+    auth: GcpAuthConfig,
+
+    #[configurable(derived)]
+    tls: Option<TlsConfig>,
+
+    #[configurable(derived)]
+    #[serde(default = "default_framing")]
+    #[derivative(Default(value = "default_framing()"))]
+    framing: FramingConfig,
+
+    #[configurable(derived)]
+    #[serde(default = "default_decoding")]
+    #[derivative(Default(value = "default_decoding()"))]
+    decoding: DeserializerConfig,
+
+    #[configurable(derived)]
+    #[serde(default, deserialize_with = "bool_or_struct")]
+    acknowledgements: SourceAcknowledgementsConfig,
+
+    /// The namespace to use for logs. This overrides the global setting.
+    #[configurable(metadata(docs::hidden))]
+    #[serde(default)]
+    log_namespace: Option<bool>,
+}
+
+const fn default_poll_interval_secs() -> Duration {
+    Duration::from_secs(60)
+}
+
+fn default_endpoint() -> String {
+    "https://storage.googleapis.com".to_string()
+}
+
+fn default_framing() -> FramingConfig {
+    NewlineDelimitedDecoderConfig::new().into()
+}
+
+impl GenerateConfig for GcsSourceConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"
+            bucket = "my-bucket"
+            credentials_path = "/path/to/credentials.json""#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "gcp_cloud_storage")]
+impl SourceConfig for GcsSourceConfig {
+    async fn build(&self, cx: SourceContext) -> crate::Result<super::Source> {
+        let log_namespace = cx.log_namespace(self.log_namespace);
+
+        let multiline: Option<line_agg::Config> = self
+            .multiline
+            .as_ref()
+            .map(|config| config.try_into())
+            .transpose()?;
+
+        let auth = self.auth.build(Scope::DevStorageReadOnly).await?;
+        auth.spawn_regenerate_token();
+        let tls = TlsSettings::from_options(&self.tls)?;
+        let client = GcsClient {
+            client: HttpClient::new(tls, &cx.proxy)?,
+            endpoint: self.endpoint.trim_end_matches('/').to_string(),
+            bucket: self.bucket.clone(),
+            auth,
+        };
+
+        let decoder =
+            DecodingConfig::new(self.framing.clone(), self.decoding.clone(), log_namespace)
+                .build()?;
+
+        let data_dir = cx
+            .globals
+            .resolve_and_make_data_subdir(self.data_dir.as_ref(), cx.key.id())?;
+
+        let source = ListingSource {
+            client,
+            source_name: Self::NAME,
+            container_key: "bucket",
+            container: self.bucket.clone(),
+            prefix: self.key_prefix.clone(),
+            poll_interval: self.poll_interval_secs,
+            compression: self.compression,
+            multiline,
+            decoder,
+            checkpoint_file: data_dir.join(CHECKPOINT_FILE_NAME),
+            acknowledgements: cx.do_acknowledgements(self.acknowledgements),
+            log_namespace,
+        };
+        Ok(Box::pin(source.run(cx.out, cx.shutdown)))
+    }
+
+    fn outputs(&self, global_log_namespace: LogNamespace) -> Vec<SourceOutput> {
+        let log_namespace = global_log_namespace.merge(self.log_namespace);
+        let schema_definition = listing::schema_definition(
+            self.decoding.schema_definition(log_namespace),
+            Self::NAME,
+            "bucket",
+            log_namespace,
+        );
+
+        vec![SourceOutput::new_logs(
+            self.decoding.output_type(),
+            schema_definition,
+        )]
+    }
+
+    fn can_acknowledge(&self) -> bool {
+        true
+    }
+}
+
+/// A page of the objects of a bucket.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ObjectList {
+    #[serde(default)]
+    items: Vec<Object>,
+    next_page_token: Option<String>,
+}
+
+/// The metadata of an object.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Object {
+    name: String,
+    updated: DateTime<Utc>,
+    content_encoding: Option<String>,
+    content_type: Option<String>,
+    #[serde(default)]
+    metadata: HashMap<String, String>,
+}
+
+impl From<Object> for ObjectInfo {
+    fn from(object: Object) -> Self {
+        Self {
+            key: object.name,
+            last_modified: object.updated,
+            content_encoding: object.content_encoding,
+            content_type: object.content_type,
+            metadata: object.metadata,
+        }
+    }
+}
+
+struct GcsClient {
+    client: HttpClient,
+    endpoint: String,
+    bucket: String,
+    auth: GcpAuthenticator,
+}
+
+impl GcsClient {
+    fn objects_url(&self) -> String {
+        format!("{}/storage/v1/b/{}/o", self.endpoint, encode(&self.bucket))
+    }
+
+    async fn get(&self, url: &str, accept_encoding: &str) -> crate::Result<http::Response<Body>> {
+        let mut request = Request::get(url)
+            .header(ACCEPT_ENCODING, accept_encoding)
+            .body(Body::empty())
+            .context(BuildRequestSnafu)?;
+        self.auth.apply(&mut request);
+
+        let response = self.client.send(request).await?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(GcsError::UnexpectedStatus { status }.into());
+        }
+        Ok(response)
+    }
+}
+
+#[async_trait::async_trait]
+impl ObjectStorageClient for GcsClient {
+    async fn list_objects(&self, prefix: &str) -> crate::Result<Vec<ObjectInfo>> {
+        let mut objects = Vec::new();
+        let mut page_token = None;
+        loop {
+            let mut url = format!("{}?prefix={}", self.objects_url(), encode(prefix));
+            if let Some(page_token) = page_token {
+                url.push_str("&pageToken=");
+                url.push_str(&encode(&page_token));
+            }
+
+            let response = self.get(&url, "identity").await?;
+            let body = hyper::body::to_bytes(response.into_body())
+                .await
+                .context(ReadResponseSnafu)?;
+            let list: ObjectList = serde_json::from_slice(&body).context(ParseResponseSnafu)?;
+
+            objects.extend(list.items.into_iter().map(Into::into));
+            match list.next_page_token {
+                Some(next_page_token) => page_token = Some(next_page_token),
+                None => break,
+            }
+        }
+        Ok(objects)
+    }
+
+    async fn get_object(
+        &self,
+        object: &ObjectInfo,
+    ) -> crate::Result<BoxStream<'static, io::Result<Bytes>>> {
+        let url = format!("{}/{}?alt=media", self.objects_url(), encode(&object.key));
+        // Objects stored with a `Content-Encoding` of `gzip` are decompressed by the service
+        // unless it is accepted, which would make them look compressed but not be.
+        let response = self.get(&url, "gzip").await?;
+        Ok(response
+            .into_body()
+            .map_err(|error| io::Error::new(io::ErrorKind::Other, error))
+            .boxed())
+    }
+}
+
+fn encode(value: &str) -> Cow<'_, str> {
+    utf8_percent_encode(value, NON_ALPHANUMERIC).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use vector_lib::lookup::{owned_value_path, OwnedTargetPath};
+    use vector_lib::schema::Definition;
+    use vrl::value::{kind::Collection, Kind};
+
+    use super::*;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<GcsSourceConfig>();
+    }
+
+    #[test]
+    fn parses_object_list() {
+        let list: ObjectList = serde_json::from_str(
+            r#"{
+                "kind": "storage#objects",
+                "nextPageToken": "CgVsb2dzLw==",
+                "items": [{
+                    "kind": "storage#object",
+                    "name": "logs/1.log.gz",
+                    "bucket": "my-bucket",
+                    "size": "1024",
+                    "updated": "2024-01-02T03:04:05.678Z",
+                    "contentType": "text/plain",
+                    "contentEncoding": "gzip",
+                    "metadata": {"origin": "vector"}
+                }]
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(list.next_page_token.as_deref(), Some("CgVsb2dzLw=="));
+        let object = ObjectInfo::from(list.items.into_iter().next().unwrap());
+        assert_eq!(object.key, "logs/1.log.gz");
+        assert_eq!(
+            object.last_modified,
+            DateTime::parse_from_rfc3339("2024-01-02T03:04:05.678Z").unwrap()
+        );
+        assert_eq!(object.content_encoding.as_deref(), Some("gzip"));
+        assert_eq!(object.content_type.as_deref(), Some("text/plain"));
+        assert_eq!(object.metadata["origin"], "vector");
+    }
+
+    #[test]
+    fn output_schema_definition_vector_namespace() {
+        let config = GcsSourceConfig {
+            log_namespace: Some(true),
+            ..Default::default()
+        };
+
+        let definitions = config
+            .outputs(LogNamespace::Vector)
+            .remove(0)
+            .schema_definition(true);
+
+        let expected_definition =
+            Definition::new_with_default_metadata(Kind::bytes(), [LogNamespace::Vector])
+                .with_meaning(OwnedTargetPath::event_root(), "message")
+                .with_metadata_field(
+                    &owned_value_path!("gcp_cloud_storage", "bucket"),
+                    Kind::bytes(),
+                    None,
+                )
+                .with_metadata_field(
+                    &owned_value_path!("gcp_cloud_storage", "object"),
+                    Kind::bytes(),
+                    None,
+                )
+                .with_metadata_field(
+                    &owned_value_path!("gcp_cloud_storage", "timestamp"),
+                    Kind::timestamp(),
+                    Some("timestamp"),
+                )
+                .with_metadata_field(
+                    &owned_value_path!("vector", "source_type"),
+                    Kind::bytes(),
+                    None,
+                )
+                .with_metadata_field(
+                    &owned_value_path!("vector", "ingest_timestamp"),
+                    Kind::timestamp(),
+                    None,
+                )
+                .with_metadata_field(
+                    &owned_value_path!("gcp_cloud_storage", "metadata"),
+                    Kind::object(Collection::empty().with_unknown(Kind::bytes())).or_undefined(),
+                    None,
+                );
+
+        assert_eq!(definitions, Some(expected_definition));
+    }
+}
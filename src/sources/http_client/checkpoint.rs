@@ -0,0 +1,53 @@
+//! Checkpointing of the pagination state of the `http_client` source.
+
+use std::{
+    io::{self, ErrorKind},
+    path::Path,
+};
+
+use serde::{Deserialize, Serialize};
+use vrl::value::Value;
+
+use crate::state_file;
+
+/// The file name of the checkpoint, in the data directory of the source.
+pub(super) const CHECKPOINT_FILE_NAME: &str = "checkpoint.json";
+
+/// The position of the source within the pages of the HTTP endpoint.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub(super) struct Checkpoint {
+    /// The last cursor read from a response, when using the `cursor` strategy.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cursor: Option<String>,
+
+    /// The number of events read so far, when using the `offset` strategy.
+    #[serde(default)]
+    pub offset: u64,
+
+    /// The `next` link of the last response, when using the `link_header` strategy.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_url: Option<String>,
+
+    /// The number of events already read from the last page, when it is requested again by the
+    /// next scrape because it had no `next` link or new cursor.
+    #[serde(default)]
+    pub last_page_events: u64,
+
+    /// The most recent value of the `timestamp_field` seen in any event.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_timestamp: Option<Value>,
+}
+
+impl Checkpoint {
+    pub(super) fn read(path: &Path) -> io::Result<Option<Self>> {
+        state_file::read(path)?
+            .map(|data| serde_json::from_slice(&data))
+            .transpose()
+            .map_err(|error| io::Error::new(ErrorKind::InvalidData, error))
+    }
+
+    pub(super) fn write(&self, path: &Path) -> io::Result<()> {
+        let data = serde_json::to_vec(self)?;
+        state_file::write_atomic(path, &data)
+    }
+}
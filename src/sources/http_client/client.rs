@@ -7,13 +7,18 @@ use futures_util::FutureExt;
 use http::{response::Parts, Uri};
 use serde_with::serde_as;
 use snafu::ResultExt;
-use std::{collections::HashMap, time::Duration};
+use std::{collections::HashMap, path::PathBuf, time::Duration};
 use tokio_util::codec::Decoder as _;
 
+use super::{
+    checkpoint::CHECKPOINT_FILE_NAME,
+    pagination::PaginationConfig,
+    poller::{parse_request_values, Poller, RequestValue},
+};
 use crate::{
     codecs::{Decoder, DecodingConfig},
     config::{SourceConfig, SourceContext},
-    http::Auth,
+    http::{Auth, HttpClient},
     register_validatable_component,
     serde::{default_decoding, default_framing_message_based},
     sources,
@@ -33,8 +38,9 @@ use vector_lib::codecs::{
     StreamDecodingError,
 };
 use vector_lib::configurable::configurable_component;
+use vector_lib::lookup::lookup_v2::ConfigTargetPath;
 use vector_lib::{
-    config::{log_schema, LogNamespace, SourceOutput},
+    config::{log_schema, LogNamespace, SourceAcknowledgementsConfig, SourceOutput},
     event::Event,
 };
 
@@ -55,6 +61,10 @@ pub struct HttpClientConfig {
     /// The interval between scrapes. Requests are run concurrently so if a scrape takes longer
     /// than the interval a new scrape will be started. This can take extra resources, set the timeout
     /// to a value lower than the scrape interval to prevent this from happening.
+    ///
+    /// When `pagination` or `timestamp_field` is set, or a query parameter or header value is
+    /// templated, requests are run sequentially instead, and a new scrape is only started once the
+    /// previous one is complete.
     #[serde(default = "default_interval")]
     #[serde_as(as = "serde_with::DurationSeconds<u64>")]
     #[serde(rename = "scrape_interval_secs")]
@@ -74,6 +84,13 @@ pub struct HttpClientConfig {
     ///
     /// The parameters provided in this option are appended to any parameters
     /// manually provided in the `endpoint` option.
+    ///
+    /// Values can be [templated][template] with the state of the previous request: `cursor`,
+    /// `offset`, `last_timestamp`, and `response` (the JSON body of the previous response). A
+    /// value is omitted when a field it references isn't available, such as during the first
+    /// request.
+    ///
+    /// [template]: https://vector.dev/docs/reference/configuration/template-syntax/
     #[serde(default)]
     #[configurable(metadata(
         docs::additional_props_description = "A query string parameter and it's value(s)."
@@ -94,6 +111,8 @@ pub struct HttpClientConfig {
     /// Headers to apply to the HTTP requests.
     ///
     /// One or more values for the same header can be provided.
+    ///
+    /// Values can be templated in the same way as the values of `query`.
     #[serde(default)]
     #[configurable(metadata(
         docs::additional_props_description = "An HTTP request header and it's value(s)."
@@ -113,6 +132,36 @@ pub struct HttpClientConfig {
     #[configurable(derived)]
     pub auth: Option<Auth>,
 
+    #[configurable(derived)]
+    pub pagination: Option<PaginationConfig>,
+
+    /// The field of the decoded events holding their timestamp.
+    ///
+    /// The most recent value seen is available to templated query parameters and headers as
+    /// `last_timestamp`, so that each scrape only requests newer events, and is kept in the
+    /// checkpoint. Timestamps and RFC 3339 strings are compared as points in time, and numbers
+    /// numerically.
+    #[configurable(metadata(docs::examples = "timestamp"))]
+    #[configurable(metadata(docs::examples = "metadata.updated_at"))]
+    pub timestamp_field: Option<ConfigTargetPath>,
+
+    /// The directory used to persist the checkpoint when `pagination` or `timestamp_field` is set.
+    ///
+    /// The checkpoint records the pagination position and the last-seen timestamp once the events
+    /// of each page have been delivered, so that the source resumes where it left off after a
+    /// restart. Without end-to-end acknowledgements, it's recorded as soon as the events are sent.
+    ///
+    /// By default, the [global `data_dir` option][global_data_dir] is used.
+    /// Make sure the running user has write permissions to this directory.
+    ///
+    /// If this directory is specified, then Vector will attempt to create it.
+    ///
+    /// [global_data_dir]: https://vector.dev/docs/reference/configuration/global-options/#data_dir
+    #[serde(default)]
+    #[configurable(metadata(docs::examples = "/var/local/lib/vector/"))]
+    #[configurable(metadata(docs::human_name = "Data Directory"))]
+    pub data_dir: Option<PathBuf>,
+
     /// The namespace to use for logs. This overrides the global setting.
     #[configurable(metadata(docs::hidden))]
     #[serde(default)]
@@ -164,6 +213,9 @@ impl Default for HttpClientConfig {
             method: default_http_method(),
             tls: None,
             auth: None,
+            pagination: None,
+            timestamp_field: None,
+            data_dir: None,
             log_namespace: None,
         }
     }
@@ -198,6 +250,48 @@ impl SourceConfig for HttpClientConfig {
             log_namespace,
         };
 
+        let query = parse_request_values(&self.query)?;
+        let headers = parse_request_values(&self.headers)?;
+        let is_templated = query
+            .values()
+            .chain(headers.values())
+            .flatten()
+            .any(RequestValue::is_templated);
+
+        if self.is_checkpointed() || is_templated {
+            let checkpoint_file = if self.is_checkpointed() {
+                let data_dir = cx
+                    .globals
+                    .resolve_and_make_data_subdir(self.data_dir.as_ref(), cx.key.id())?;
+                Some(data_dir.join(CHECKPOINT_FILE_NAME))
+            } else {
+                None
+            };
+
+            let poller = Poller {
+                client: HttpClient::new(tls, &cx.proxy)?,
+                endpoint: self
+                    .endpoint
+                    .parse::<Uri>()
+                    .context(sources::UriParseSnafu)?,
+                method: self.method,
+                query,
+                headers,
+                content_type,
+                auth: self.auth.clone(),
+                interval: self.interval,
+                timeout: self.timeout,
+                pagination: self.pagination.clone(),
+                timestamp_field: self.timestamp_field.clone(),
+                acknowledgements: checkpoint_file.is_some()
+                    && cx.do_acknowledgements(SourceAcknowledgementsConfig::DEFAULT),
+                checkpoint_file,
+                context,
+            };
+
+            return Ok(poller.run(cx.out, cx.shutdown).boxed());
+        }
+
         warn_if_interval_too_low(self.timeout, self.interval);
 
         let inputs = GenericHttpClientInputs {
@@ -232,7 +326,8 @@ impl SourceConfig for HttpClientConfig {
     }
 
     fn can_acknowledge(&self) -> bool {
-        false
+        // Only the checkpoint depends on the delivery of the events.
+        self.is_checkpointed()
     }
 }
 
@@ -268,6 +363,11 @@ impl HttpClientConfig {
 
         DecodingConfig::new(framing, decoding, log_namespace)
     }
+
+    /// Whether the position of the source is checkpointed, so that it resumes after a restart.
+    const fn is_checkpointed(&self) -> bool {
+        self.pagination.is_some() || self.timestamp_field.is_some()
+    }
 }
 
 /// Captures the configuration options required to decode the incoming requests into events.
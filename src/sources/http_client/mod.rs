@@ -1,5 +1,11 @@
 #[cfg(feature = "sources-http_client")]
+mod checkpoint;
+#[cfg(feature = "sources-http_client")]
 pub mod client;
+#[cfg(feature = "sources-http_client")]
+mod pagination;
+#[cfg(feature = "sources-http_client")]
+mod poller;
 
 #[cfg(test)]
 mod tests;
@@ -0,0 +1,211 @@
+//! Pagination strategies for the `http_client` source.
+
+use std::num::NonZeroUsize;
+
+use http::{header::LINK, HeaderMap, Uri};
+use vector_lib::configurable::configurable_component;
+use vector_lib::lookup::lookup_v2::ConfigValuePath;
+use vrl::value::Value;
+
+/// Configuration for paginating through the responses of the HTTP endpoint.
+#[configurable_component]
+#[derive(Clone, Debug)]
+pub struct PaginationConfig {
+    #[configurable(derived)]
+    #[serde(flatten)]
+    pub strategy: PaginationStrategy,
+
+    /// The maximum number of pages to request during a single scrape.
+    ///
+    /// When this limit is reached, the remaining pages are requested during the next scrape.
+    #[serde(default = "default_max_pages")]
+    pub max_pages: NonZeroUsize,
+}
+
+fn default_max_pages() -> NonZeroUsize {
+    NonZeroUsize::new(100).expect("static non-zero number")
+}
+
+/// How to request the next page of results.
+#[configurable_component]
+#[derive(Clone, Debug)]
+#[serde(tag = "strategy", rename_all = "snake_case")]
+#[configurable(metadata(docs::enum_tag_description = "The pagination strategy to use."))]
+pub enum PaginationStrategy {
+    /// Follow the `next` link of the [`Link` response header][link_header].
+    ///
+    /// Pages are requested until a response has no `next` link, or contains no events. The next
+    /// scrape resumes from the `next` link of the last response, or requests the last page again
+    /// if it had none, in which case only the events added to it since are sent.
+    ///
+    /// [link_header]: https://datatracker.ietf.org/doc/html/rfc8288
+    LinkHeader,
+
+    /// Pass a cursor, read from the JSON body of the previous response, as a query parameter.
+    ///
+    /// Pages are requested until a response has no cursor, or the cursor is unchanged. The last
+    /// cursor received is kept and used during the next scrape, which requests the last page again
+    /// and only sends the events added to it since.
+    Cursor {
+        /// The path of the cursor in the JSON body of the response.
+        #[configurable(metadata(docs::examples = "next_cursor"))]
+        #[configurable(metadata(docs::examples = "meta.cursor"))]
+        cursor_field: ConfigValuePath,
+
+        /// The name of the query parameter used to pass the cursor.
+        #[configurable(metadata(docs::examples = "cursor"))]
+        cursor_param: String,
+    },
+
+    /// Pass an offset and a limit as query parameters.
+    ///
+    /// The offset is advanced by the number of events decoded from each page, and pages are
+    /// requested until a page contains fewer events than the limit. The offset is never reset, so
+    /// the next scrape only requests events added since the last one.
+    Offset {
+        /// The name of the query parameter used to pass the offset.
+        #[serde(default = "default_offset_param")]
+        offset_param: String,
+
+        /// The name of the query parameter used to pass the limit.
+        #[serde(default = "default_limit_param")]
+        limit_param: String,
+
+        /// The number of events to request per page.
+        #[serde(default = "default_limit")]
+        #[configurable(metadata(docs::type_unit = "events"))]
+        limit: NonZeroUsize,
+    },
+}
+
+fn default_offset_param() -> String {
+    "offset".to_string()
+}
+
+fn default_limit_param() -> String {
+    "limit".to_string()
+}
+
+fn default_limit() -> NonZeroUsize {
+    NonZeroUsize::new(100).expect("static non-zero number")
+}
+
+/// Finds the `next` link in the `Link` headers of a response, resolved against the request URL.
+pub(super) fn next_link(headers: &HeaderMap, url: &Uri) -> Option<Uri> {
+    let target = headers
+        .get_all(LINK)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .find_map(parse_next_link)?;
+
+    let base = url::Url::parse(&url.to_string()).ok()?;
+    base.join(target).ok()?.as_str().parse().ok()
+}
+
+/// Returns the target of a single link value if it has the `next` relation type.
+fn parse_next_link(link: &str) -> Option<&str> {
+    let (target, params) = link.trim().strip_prefix('<')?.split_once('>')?;
+    params
+        .split(';')
+        .filter_map(|param| param.split_once('='))
+        .any(|(name, value)| {
+            name.trim().eq_ignore_ascii_case("rel")
+                && value
+                    .trim()
+                    .trim_matches('"')
+                    .split_ascii_whitespace()
+                    .any(|rel| rel.eq_ignore_ascii_case("next"))
+        })
+        .then_some(target)
+}
+
+/// Reads the cursor from the JSON body of a response.
+///
+/// Missing, null and empty cursors are all treated as the end of the pages.
+pub(super) fn read_cursor(
+    response: Option<&Value>,
+    cursor_field: &ConfigValuePath,
+) -> Option<String> {
+    match response?.get(&cursor_field.0)? {
+        Value::Null => None,
+        value => Some(value.to_string_lossy().into_owned()).filter(|cursor| !cursor.is_empty()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use http::HeaderValue;
+    use vrl::value::ObjectMap;
+
+    use super::*;
+
+    fn headers(links: &[&'static str]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for link in links {
+            headers.append(LINK, HeaderValue::from_static(link));
+        }
+        headers
+    }
+
+    #[test]
+    fn finds_next_link() {
+        let url = Uri::from_static("http://example.com/items?page=1");
+
+        let cases = [
+            (
+                vec![r#"<http://example.com/items?page=2>; rel="next""#],
+                Some("http://example.com/items?page=2"),
+            ),
+            (
+                vec![r#"</items?page=1>; rel="prev", </items?page=2>; rel="next""#],
+                Some("http://example.com/items?page=2"),
+            ),
+            (
+                vec![
+                    r#"</items?page=1>; rel=first"#,
+                    r#"<?page=2>; rel="next last""#,
+                ],
+                Some("http://example.com/items?page=2"),
+            ),
+            (vec![r#"</items?page=1>; rel="prev""#], None),
+            (vec![r#"/items?page=2; rel="next""#], None),
+            (vec![], None),
+        ];
+
+        for (links, expected) in cases {
+            assert_eq!(
+                next_link(&headers(&links), &url),
+                expected.map(Uri::from_static),
+                "{links:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn reads_cursor() {
+        let cursor_field = ConfigValuePath::try_from("meta.next".to_string()).unwrap();
+        let response = |next: Value| {
+            Value::from(ObjectMap::from([(
+                "meta".into(),
+                Value::from(ObjectMap::from([("next".into(), next)])),
+            )]))
+        };
+
+        assert_eq!(
+            read_cursor(Some(&response("abc".into())), &cursor_field),
+            Some("abc".to_string())
+        );
+        assert_eq!(
+            read_cursor(Some(&response(Value::Integer(42))), &cursor_field),
+            Some("42".to_string())
+        );
+        assert_eq!(read_cursor(Some(&response("".into())), &cursor_field), None);
+        assert_eq!(
+            read_cursor(Some(&response(Value::Null)), &cursor_field),
+            None
+        );
+        assert_eq!(read_cursor(Some(&Value::from("abc")), &cursor_field), None);
+        assert_eq!(read_cursor(None, &cursor_field), None);
+    }
+}
@@ -0,0 +1,545 @@
+//! Sequential scraping of the HTTP endpoint, used when requests depend on earlier responses.
+//!
+//! Unlike the generic HTTP client scraping, pages are requested one at a time, so that each
+//! request can be built from the state left by the previous one: the pagination position, the
+//! last-seen timestamp and the body of the previous response.
+
+use std::{cmp::Ordering, collections::HashMap, path::PathBuf, time::Duration};
+
+use chrono::{DateTime, Utc};
+use http::Uri;
+use vector_lib::{
+    event::{BatchNotifier, BatchStatus, Event, LogEvent},
+    json_size::JsonSize,
+    lookup::{event_path, lookup_v2::ConfigTargetPath},
+    shutdown::ShutdownSignal,
+    EstimatedJsonEncodedSizeOf,
+};
+use vrl::value::Value;
+
+use super::{
+    checkpoint::Checkpoint,
+    client::HttpClientContext,
+    pagination::{next_link, read_cursor, PaginationConfig, PaginationStrategy},
+};
+use crate::{
+    http::{Auth, HttpClient},
+    internal_events::{
+        HttpClientCheckpointLoadError, HttpClientCheckpointPersistError, HttpClientEventsReceived,
+        HttpClientHttpError, HttpClientHttpResponseError, StreamClosedError,
+    },
+    sources::util::{
+        http::HttpMethod,
+        http_client::{build_request, build_url, send_request, HttpClientContext as _},
+    },
+    template::Template,
+    SourceSender,
+};
+
+/// A query parameter or header value, which may be templated.
+#[derive(Clone, Debug)]
+pub(super) enum RequestValue {
+    Static(String),
+    Templated(Template),
+}
+
+impl RequestValue {
+    /// Parses a configured value, only treating it as a template if it references a field.
+    ///
+    /// Other values are kept as is, so that existing values containing a `%` aren't mistaken for
+    /// `strftime` specifiers.
+    pub(super) fn parse(value: &str) -> crate::Result<Self> {
+        if value.contains("{{") {
+            Ok(Self::Templated(Template::try_from(value)?))
+        } else {
+            Ok(Self::Static(value.to_string()))
+        }
+    }
+
+    pub(super) const fn is_templated(&self) -> bool {
+        matches!(self, Self::Templated(_))
+    }
+
+    /// Renders the value, returning `None` if a referenced field isn't available yet.
+    fn render(&self, state: &LogEvent) -> Option<String> {
+        match self {
+            Self::Static(value) => Some(value.clone()),
+            Self::Templated(template) => template.render_string(state).ok(),
+        }
+    }
+}
+
+pub(super) type RequestValues = HashMap<String, Vec<RequestValue>>;
+
+pub(super) fn parse_request_values(
+    values: &HashMap<String, Vec<String>>,
+) -> crate::Result<RequestValues> {
+    values
+        .iter()
+        .map(|(key, values)| {
+            values
+                .iter()
+                .map(|value| RequestValue::parse(value))
+                .collect::<crate::Result<Vec<_>>>()
+                .map(|values| (key.clone(), values))
+        })
+        .collect()
+}
+
+fn render_request_values(values: &RequestValues, state: &LogEvent) -> HashMap<String, Vec<String>> {
+    values
+        .iter()
+        .map(|(key, values)| {
+            let values = values
+                .iter()
+                .filter_map(|value| value.render(state))
+                .collect::<Vec<_>>();
+            (key.clone(), values)
+        })
+        .filter(|(_, values)| !values.is_empty())
+        .collect()
+}
+
+/// Where to continue after a page has been processed.
+enum NextPage {
+    Continue,
+    Stop,
+}
+
+pub(super) struct Poller {
+    pub client: HttpClient,
+    pub endpoint: Uri,
+    pub method: HttpMethod,
+    pub query: RequestValues,
+    pub headers: RequestValues,
+    pub content_type: String,
+    pub auth: Option<Auth>,
+    pub interval: Duration,
+    pub timeout: Duration,
+    pub pagination: Option<PaginationConfig>,
+    pub timestamp_field: Option<ConfigTargetPath>,
+    pub checkpoint_file: Option<PathBuf>,
+    pub acknowledgements: bool,
+    pub context: HttpClientContext,
+}
+
+impl Poller {
+    pub(super) async fn run(
+        self,
+        mut out: SourceSender,
+        mut shutdown: ShutdownSignal,
+    ) -> Result<(), ()> {
+        let checkpoint = self.load_checkpoint();
+        let mut state = PollerState {
+            committed: checkpoint.clone(),
+            checkpoint,
+            response: None,
+        };
+        let mut interval = tokio::time::interval(self.interval);
+
+        loop {
+            tokio::select! {
+                _ = &mut shutdown => break,
+                _ = interval.tick() => {}
+            }
+
+            // Interrupting a scrape drops the checkpoints that haven't been committed yet, so the
+            // pages they cover are requested again after a restart.
+            tokio::select! {
+                result = self.scrape(&mut state, &mut out) => result?,
+                _ = &mut shutdown => break,
+            }
+        }
+
+        debug!("Finished sending.");
+        Ok(())
+    }
+
+    /// Requests pages until the pagination strategy stops or `max_pages` is reached.
+    ///
+    /// The checkpoint reached after each page is committed once the events of the page have been
+    /// delivered, or as soon as they have been sent when acknowledgements are disabled. If they
+    /// can't be delivered, the next scrape starts over from the last committed checkpoint.
+    async fn scrape(&self, state: &mut PollerState, out: &mut SourceSender) -> Result<(), ()> {
+        let max_pages = self
+            .pagination
+            .as_ref()
+            .map_or(1, |pagination| pagination.max_pages.get());
+        let mut pending = Vec::new();
+
+        for _ in 0..max_pages {
+            let template_state = state.template_state();
+            let url = self.page_url(state, &template_state);
+            let headers = render_request_values(&self.headers, &template_state);
+            let request = build_request(
+                self.method,
+                &url,
+                &headers,
+                &self.content_type,
+                self.auth.as_ref(),
+            );
+
+            let mut context = self.context.clone();
+            let (header, body) = match send_request(&self.client, request, self.timeout).await {
+                Ok((header, body)) if header.status == hyper::StatusCode::OK => (header, body),
+                Ok((header, _)) => {
+                    context.on_http_response_error(&url, &header);
+                    emit!(HttpClientHttpResponseError {
+                        code: header.status,
+                        url: url.to_string(),
+                    });
+                    break;
+                }
+                Err(error) => {
+                    emit!(HttpClientHttpError {
+                        error,
+                        url: url.to_string()
+                    });
+                    break;
+                }
+            };
+
+            let mut events = context
+                .on_response(&url, &header, &body)
+                .unwrap_or_default();
+            let count = events.len();
+
+            // Events already sent from this page, when it is the last page of the previous scrape
+            // requested again, are skipped so that they aren't sent twice.
+            let seen = usize::try_from(state.checkpoint.last_page_events).unwrap_or(usize::MAX);
+            events.drain(..seen.min(count));
+
+            let byte_size = if events.is_empty() {
+                JsonSize::zero()
+            } else {
+                events.estimated_json_encoded_size_of()
+            };
+            emit!(HttpClientEventsReceived {
+                byte_size,
+                count: events.len(),
+                url: url.to_string()
+            });
+
+            if let Some(timestamp_field) = &self.timestamp_field {
+                state.track_timestamp(&events, timestamp_field);
+            }
+            state.response = serde_json::from_slice::<Value>(&body).ok();
+            let next_page = match &self.pagination {
+                Some(pagination) => {
+                    state.advance(&pagination.strategy, &url, &header.headers, count)
+                }
+                None => NextPage::Stop,
+            };
+
+            // We'll enrich after receiving the events so that the byte sizes are accurate.
+            context.enrich_events(&mut events);
+            let (batch, receiver) = BatchNotifier::maybe_new_with_receiver(self.acknowledgements);
+            if !events.is_empty() {
+                let count = events.len();
+                let events = events
+                    .into_iter()
+                    .map(|event| event.with_batch_notifier_option(&batch));
+                if out.send_batch(events).await.is_err() {
+                    emit!(StreamClosedError { count });
+                    return Err(());
+                }
+            }
+            drop(batch);
+
+            match receiver {
+                Some(receiver) => pending.push((receiver, state.checkpoint.clone())),
+                None => {
+                    let checkpoint = state.checkpoint.clone();
+                    self.commit_checkpoint(state, checkpoint);
+                }
+            }
+
+            if matches!(next_page, NextPage::Stop) {
+                break;
+            }
+        }
+
+        for (receiver, checkpoint) in pending {
+            if receiver.await == BatchStatus::Delivered {
+                self.commit_checkpoint(state, checkpoint);
+            } else {
+                state.checkpoint = state.committed.clone();
+                break;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Builds the URL of the next page to request.
+    fn page_url(&self, state: &PollerState, template_state: &LogEvent) -> Uri {
+        let mut query = render_request_values(&self.query, template_state);
+
+        match self
+            .pagination
+            .as_ref()
+            .map(|pagination| &pagination.strategy)
+        {
+            Some(PaginationStrategy::LinkHeader) => {
+                if let Some(next_url) = state
+                    .checkpoint
+                    .next_url
+                    .as_ref()
+                    .and_then(|next_url| next_url.parse().ok())
+                {
+                    return next_url;
+                }
+            }
+            Some(PaginationStrategy::Cursor { cursor_param, .. }) => {
+                if let Some(cursor) = &state.checkpoint.cursor {
+                    query.insert(cursor_param.clone(), vec![cursor.clone()]);
+                }
+            }
+            Some(PaginationStrategy::Offset {
+                offset_param,
+                limit_param,
+                limit,
+            }) => {
+                query.insert(
+                    offset_param.clone(),
+                    vec![state.checkpoint.offset.to_string()],
+                );
+                query.insert(limit_param.clone(), vec![limit.to_string()]);
+            }
+            None => {}
+        }
+
+        build_url(&self.endpoint, &query)
+    }
+
+    fn load_checkpoint(&self) -> Checkpoint {
+        let Some(path) = self.checkpoint_file.as_deref() else {
+            return Checkpoint::default();
+        };
+        match Checkpoint::read(path) {
+            Ok(checkpoint) => checkpoint.unwrap_or_default(),
+            Err(error) => {
+                emit!(HttpClientCheckpointLoadError { error, path });
+                Checkpoint::default()
+            }
+        }
+    }
+
+    /// Commits the checkpoint, persisting it so that the source resumes from it after a restart.
+    fn commit_checkpoint(&self, state: &mut PollerState, checkpoint: Checkpoint) {
+        if let Some(path) = self.checkpoint_file.as_deref() {
+            if let Err(error) = checkpoint.write(path) {
+                emit!(HttpClientCheckpointPersistError { error, path });
+            }
+        }
+        state.committed = checkpoint;
+    }
+}
+
+/// The state carried from one request to the next.
+struct PollerState {
+    /// The checkpoint reached by the pages requested so far.
+    checkpoint: Checkpoint,
+
+    /// The checkpoint reached by the pages whose events have been delivered.
+    committed: Checkpoint,
+
+    /// The JSON body of the previous response, if it was valid JSON.
+    response: Option<Value>,
+}
+
+impl PollerState {
+    /// Builds the event that query parameter and header templates are rendered against.
+    fn template_state(&self) -> LogEvent {
+        let mut state = LogEvent::default();
+        if let Some(cursor) = &self.checkpoint.cursor {
+            state.insert(event_path!("cursor"), cursor.clone());
+        }
+        state.insert(
+            event_path!("offset"),
+            Value::Integer(self.checkpoint.offset as i64),
+        );
+        if let Some(last_timestamp) = &self.checkpoint.last_timestamp {
+            state.insert(event_path!("last_timestamp"), last_timestamp.clone());
+        }
+        if let Some(response) = &self.response {
+            state.insert(event_path!("response"), response.clone());
+        }
+        state
+    }
+
+    /// Updates the pagination position after a page has been received.
+    fn advance(
+        &mut self,
+        strategy: &PaginationStrategy,
+        url: &Uri,
+        headers: &http::HeaderMap,
+        count: usize,
+    ) -> NextPage {
+        self.checkpoint.last_page_events = 0;
+
+        match strategy {
+            PaginationStrategy::LinkHeader => match next_link(headers, url) {
+                Some(next_url) if next_url != *url => {
+                    self.checkpoint.next_url = Some(next_url.to_string());
+                    if count == 0 {
+                        NextPage::Stop
+                    } else {
+                        NextPage::Continue
+                    }
+                }
+                _ => {
+                    // The last page is requested again during the next scrape, rather than
+                    // starting over from the first one, and only the events added to it since
+                    // are sent.
+                    self.checkpoint.next_url = Some(url.to_string());
+                    self.checkpoint.last_page_events = count as u64;
+                    NextPage::Stop
+                }
+            },
+            PaginationStrategy::Cursor { cursor_field, .. } => {
+                match read_cursor(self.response.as_ref(), cursor_field) {
+                    Some(cursor) if self.checkpoint.cursor.as_ref() != Some(&cursor) => {
+                        self.checkpoint.cursor = Some(cursor);
+                        if count == 0 {
+                            NextPage::Stop
+                        } else {
+                            NextPage::Continue
+                        }
+                    }
+                    // The cursor of the last page is kept, so that it is requested again during the
+                    // next scrape, rather than starting over from the first one, and only the
+                    // events added to it since are sent.
+                    _ => {
+                        self.checkpoint.last_page_events = count as u64;
+                        NextPage::Stop
+                    }
+                }
+            }
+            PaginationStrategy::Offset { limit, .. } => {
+                self.checkpoint.offset += count as u64;
+                if count < limit.get() {
+                    NextPage::Stop
+                } else {
+                    NextPage::Continue
+                }
+            }
+        }
+    }
+
+    /// Keeps track of the most recent value of the timestamp field.
+    fn track_timestamp(&mut self, events: &[Event], timestamp_field: &ConfigTargetPath) {
+        for event in events {
+            let Some(timestamp) = event
+                .maybe_as_log()
+                .and_then(|log| log.get(timestamp_field))
+            else {
+                continue;
+            };
+            let is_newer = self
+                .checkpoint
+                .last_timestamp
+                .as_ref()
+                .map_or(true, |last| compare_timestamps(timestamp, last).is_gt());
+            if is_newer {
+                self.checkpoint.last_timestamp = Some(timestamp.clone());
+            }
+        }
+    }
+}
+
+/// Compares two timestamp values.
+///
+/// Timestamps and RFC 3339 strings are compared as points in time, and numbers numerically. Any
+/// other values are compared as strings.
+fn compare_timestamps(a: &Value, b: &Value) -> Ordering {
+    fn as_time(value: &Value) -> Option<DateTime<Utc>> {
+        match value {
+            Value::Timestamp(timestamp) => Some(*timestamp),
+            Value::Bytes(bytes) => std::str::from_utf8(bytes)
+                .ok()
+                .and_then(|s| DateTime::parse_from_rfc3339(s).ok())
+                .map(|timestamp| timestamp.with_timezone(&Utc)),
+            _ => None,
+        }
+    }
+
+    fn as_number(value: &Value) -> Option<f64> {
+        match value {
+            Value::Integer(number) => Some(*number as f64),
+            Value::Float(number) => Some(number.into_inner()),
+            _ => None,
+        }
+    }
+
+    if let (Some(a), Some(b)) = (as_time(a), as_time(b)) {
+        a.cmp(&b)
+    } else if let (Some(a), Some(b)) = (as_number(a), as_number(b)) {
+        a.total_cmp(&b)
+    } else {
+        a.to_string_lossy().cmp(&b.to_string_lossy())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    #[test]
+    fn compares_timestamps() {
+        let time = |seconds| Value::from(Utc.timestamp_opt(seconds, 0).unwrap());
+
+        assert!(compare_timestamps(&time(2), &time(1)).is_gt());
+        assert!(compare_timestamps(&Value::from("2024-01-01T00:00:01Z"), &time(1)).is_gt());
+        assert!(compare_timestamps(
+            &Value::from("2024-01-01T01:00:00+02:00"),
+            &Value::from("2024-01-01T00:00:00Z")
+        )
+        .is_lt());
+        assert!(compare_timestamps(&Value::Integer(10), &Value::Integer(9)).is_gt());
+        assert!(compare_timestamps(&Value::from("b"), &Value::from("a")).is_gt());
+    }
+
+    #[test]
+    fn renders_request_values() {
+        let values = parse_request_values(&HashMap::from([
+            (
+                "since".to_string(),
+                vec!["{{ last_timestamp }}".to_string()],
+            ),
+            (
+                "page".to_string(),
+                vec!["{{ response.page }}".to_string(), "%20".to_string()],
+            ),
+        ]))
+        .unwrap();
+
+        let mut state = PollerState {
+            checkpoint: Checkpoint::default(),
+            committed: Checkpoint::default(),
+            response: None,
+        };
+        assert_eq!(
+            render_request_values(&values, &state.template_state()),
+            HashMap::from([("page".to_string(), vec!["%20".to_string()])])
+        );
+
+        state.checkpoint.last_timestamp = Some(Value::from("2024-01-01T00:00:00Z"));
+        state.response = Some(Value::from(vrl::value::ObjectMap::from([(
+            "page".into(),
+            Value::Integer(2),
+        )])));
+        assert_eq!(
+            render_request_values(&values, &state.template_state()),
+            HashMap::from([
+                (
+                    "since".to_string(),
+                    vec!["2024-01-01T00:00:00Z".to_string()]
+                ),
+                ("page".to_string(), vec!["2".to_string(), "%20".to_string()]),
+            ])
+        );
+    }
+}
@@ -1,19 +1,35 @@
-use std::collections::HashMap;
-use tokio::time::Duration;
+use std::{
+    collections::HashMap,
+    num::NonZeroUsize,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+};
+use tokio::time::{Duration, Instant};
 use vector_lib::codecs::CharacterDelimitedDecoderConfig;
+use vector_lib::event::EventStatus;
 use warp::{http::HeaderMap, Filter};
 
 use crate::sources::util::http::HttpMethod;
-use crate::{serde::default_decoding, serde::default_framing_message_based};
+use crate::{
+    config::{ComponentKey, SourceConfig, SourceContext},
+    serde::default_decoding,
+    serde::default_framing_message_based,
+    SourceSender,
+};
 use vector_lib::codecs::decoding::{
     CharacterDelimitedDecoderOptions, DeserializerConfig, FramingConfig,
 };
 use vector_lib::event::Event;
 
-use super::HttpClientConfig;
+use super::{
+    checkpoint::{Checkpoint, CHECKPOINT_FILE_NAME},
+    pagination::{PaginationConfig, PaginationStrategy},
+    HttpClientConfig,
+};
 use crate::test_util::{
+    collect_n,
     components::{run_and_assert_source_compliance, HTTP_PULL_SOURCE_TAGS},
-    next_addr, test_generate_config, wait_for_tcp,
+    next_addr, temp_dir, test_generate_config, wait_for_tcp,
 };
 
 pub(crate) const INTERVAL: Duration = Duration::from_secs(1);
@@ -59,6 +75,9 @@ async fn bytes_decoding() {
         method: HttpMethod::Get,
         tls: None,
         auth: None,
+        pagination: None,
+        timestamp_field: None,
+        data_dir: None,
         log_namespace: None,
     })
     .await;
@@ -88,6 +107,9 @@ async fn json_decoding_newline_delimited() {
         method: HttpMethod::Get,
         tls: None,
         auth: None,
+        pagination: None,
+        timestamp_field: None,
+        data_dir: None,
         log_namespace: None,
     })
     .await;
@@ -122,6 +144,9 @@ async fn json_decoding_character_delimited() {
         method: HttpMethod::Get,
         tls: None,
         auth: None,
+        pagination: None,
+        timestamp_field: None,
+        data_dir: None,
         log_namespace: None,
     })
     .await;
@@ -156,6 +181,9 @@ async fn request_query_applied() {
         method: HttpMethod::Get,
         tls: None,
         auth: None,
+        pagination: None,
+        timestamp_field: None,
+        data_dir: None,
         log_namespace: None,
     })
     .await;
@@ -220,6 +248,9 @@ async fn headers_applied() {
         method: HttpMethod::Get,
         auth: None,
         tls: None,
+        pagination: None,
+        timestamp_field: None,
+        data_dir: None,
         log_namespace: None,
     })
     .await;
@@ -249,7 +280,331 @@ async fn accept_header_override() {
         method: HttpMethod::Get,
         auth: None,
         tls: None,
+        pagination: None,
+        timestamp_field: None,
+        data_dir: None,
         log_namespace: None,
     })
     .await;
 }
+
+fn data_dir() -> PathBuf {
+    let data_dir = temp_dir();
+    std::fs::create_dir_all(&data_dir).unwrap();
+    data_dir
+}
+
+fn field_values(events: &[Event], field: &str) -> Vec<String> {
+    events
+        .iter()
+        .filter_map(|event| event.as_log().get(field))
+        .map(|value| value.to_string_lossy().into_owned())
+        .collect()
+}
+
+/// Pages should be followed through the `next` link of the `Link` response header.
+#[tokio::test]
+async fn link_header_pagination() {
+    let in_addr = next_addr();
+
+    let dummy_endpoint = warp::path!("endpoint")
+        .and(warp::query::<HashMap<String, String>>())
+        .map(|query: HashMap<String, String>| {
+            let page: usize = query.get("page").map_or(1, |page| page.parse().unwrap());
+            let link = if page < 3 {
+                format!(r#"</endpoint?page={}>; rel="next""#, page + 1)
+            } else {
+                String::new()
+            };
+            warp::reply::with_header(format!(r#"{{"page": {page}}}"#), "Link", link)
+        });
+
+    tokio::spawn(warp::serve(dummy_endpoint).run(in_addr));
+    wait_for_tcp(in_addr).await;
+
+    let events = run_compliance(HttpClientConfig {
+        endpoint: format!("http://{}/endpoint", in_addr),
+        interval: INTERVAL,
+        timeout: TIMEOUT,
+        decoding: DeserializerConfig::Json(Default::default()),
+        pagination: Some(PaginationConfig {
+            strategy: PaginationStrategy::LinkHeader,
+            max_pages: NonZeroUsize::new(10).unwrap(),
+        }),
+        data_dir: Some(data_dir()),
+        ..Default::default()
+    })
+    .await;
+
+    // Once the last page is reached, the next scrapes request it again rather than starting over,
+    // without sending its events again.
+    assert_eq!(field_values(&events, "page"), ["1", "2", "3"]);
+}
+
+/// Only the events added to the last page since it was last requested should be sent.
+#[tokio::test]
+async fn link_header_pagination_sends_events_added_to_last_page() {
+    let in_addr = next_addr();
+    let items = Arc::new(Mutex::new((0..3).collect::<Vec<usize>>()));
+
+    let dummy_endpoint = warp::path!("endpoint")
+        .and(warp::query::<HashMap<String, usize>>())
+        .map({
+            let items = Arc::clone(&items);
+            move |query: HashMap<String, usize>| {
+                let items = items.lock().unwrap();
+                let (page, link) = match query.get("page") {
+                    None => (&items[..1], r#"</endpoint?page=2>; rel="next""#),
+                    Some(_) => (&items[1..], ""),
+                };
+                let body = page
+                    .iter()
+                    .map(|item| format!(r#"{{"item": {item}}}"#))
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                warp::reply::with_header(body, "Link", link)
+            }
+        });
+
+    tokio::spawn(warp::serve(dummy_endpoint).run(in_addr));
+    wait_for_tcp(in_addr).await;
+
+    let config = HttpClientConfig {
+        endpoint: format!("http://{}/endpoint", in_addr),
+        interval: INTERVAL,
+        timeout: TIMEOUT,
+        decoding: DeserializerConfig::Json(Default::default()),
+        framing: FramingConfig::NewlineDelimited(Default::default()),
+        pagination: Some(PaginationConfig {
+            strategy: PaginationStrategy::LinkHeader,
+            max_pages: NonZeroUsize::new(10).unwrap(),
+        }),
+        data_dir: Some(data_dir()),
+        ..Default::default()
+    };
+
+    let events = run_compliance(config.clone()).await;
+    assert_eq!(field_values(&events, "item"), ["0", "1", "2"]);
+
+    items.lock().unwrap().push(3);
+
+    let events = run_compliance(config).await;
+    assert_eq!(field_values(&events, "item"), ["3"]);
+}
+
+/// The cursor read from each response should be passed to the next request.
+#[tokio::test]
+async fn cursor_pagination() {
+    let in_addr = next_addr();
+
+    let dummy_endpoint = warp::path!("endpoint")
+        .and(warp::query::<HashMap<String, String>>())
+        .map(|query: HashMap<String, String>| {
+            let page: usize = query
+                .get("cursor")
+                .map_or(1, |cursor| cursor.trim_start_matches('c').parse().unwrap());
+            if page < 3 {
+                format!(r#"{{"page": {page}, "meta": {{"next": "c{}"}}}}"#, page + 1)
+            } else {
+                format!(r#"{{"page": {page}, "meta": {{"next": null}}}}"#)
+            }
+        });
+
+    tokio::spawn(warp::serve(dummy_endpoint).run(in_addr));
+    wait_for_tcp(in_addr).await;
+
+    let events = run_compliance(HttpClientConfig {
+        endpoint: format!("http://{}/endpoint", in_addr),
+        interval: INTERVAL,
+        timeout: TIMEOUT,
+        decoding: DeserializerConfig::Json(Default::default()),
+        pagination: Some(PaginationConfig {
+            strategy: PaginationStrategy::Cursor {
+                cursor_field: "meta.next".to_string().try_into().unwrap(),
+                cursor_param: "cursor".to_string(),
+            },
+            max_pages: NonZeroUsize::new(10).unwrap(),
+        }),
+        data_dir: Some(data_dir()),
+        ..Default::default()
+    })
+    .await;
+
+    // The cursor of the last page is kept, so the next scrapes request it again, without sending
+    // its events again.
+    assert_eq!(field_values(&events, "page"), ["1", "2", "3"]);
+}
+
+/// The offset should be checkpointed, so that a restarted source only requests new events.
+#[tokio::test]
+async fn offset_pagination_resumes_from_checkpoint() {
+    let in_addr = next_addr();
+    let items = Arc::new(Mutex::new((0..5).collect::<Vec<usize>>()));
+
+    let dummy_endpoint = warp::path!("endpoint")
+        .and(warp::query::<HashMap<String, usize>>())
+        .map({
+            let items = Arc::clone(&items);
+            move |query: HashMap<String, usize>| {
+                let items = items.lock().unwrap();
+                let start = usize::min(query["offset"], items.len());
+                let end = (start + query["limit"]).min(items.len());
+                items[start..end]
+                    .iter()
+                    .map(|item| format!(r#"{{"item": {item}}}"#))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            }
+        });
+
+    tokio::spawn(warp::serve(dummy_endpoint).run(in_addr));
+    wait_for_tcp(in_addr).await;
+
+    let config = HttpClientConfig {
+        endpoint: format!("http://{}/endpoint", in_addr),
+        interval: INTERVAL,
+        timeout: TIMEOUT,
+        decoding: DeserializerConfig::Json(Default::default()),
+        framing: FramingConfig::NewlineDelimited(Default::default()),
+        pagination: Some(PaginationConfig {
+            strategy: PaginationStrategy::Offset {
+                offset_param: "offset".to_string(),
+                limit_param: "limit".to_string(),
+                limit: NonZeroUsize::new(2).unwrap(),
+            },
+            max_pages: NonZeroUsize::new(10).unwrap(),
+        }),
+        data_dir: Some(data_dir()),
+        ..Default::default()
+    };
+
+    let events = run_compliance(config.clone()).await;
+    assert_eq!(field_values(&events, "item"), ["0", "1", "2", "3", "4"]);
+
+    items.lock().unwrap().extend([5, 6]);
+
+    let events = run_compliance(config).await;
+    assert_eq!(field_values(&events, "item"), ["5", "6"]);
+}
+
+/// Runs the source with end-to-end acknowledgements until it has sent `count` events, which are
+/// finalized with the given status.
+async fn run_acknowledged(
+    config: &HttpClientConfig,
+    status: EventStatus,
+    count: usize,
+) -> Vec<Event> {
+    let key = ComponentKey::from("http_client");
+    let (tx, rx) = SourceSender::new_test_finalize(status);
+    let (mut cx, mut shutdown) = SourceContext::new_shutdown(&key, tx);
+    cx.acknowledgements = true;
+    let source = tokio::spawn(config.build(cx).await.unwrap());
+
+    let events = collect_n(rx, count).await;
+    // Leave the source some time to commit the checkpoint.
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let deadline = Instant::now() + Duration::from_secs(1);
+    assert!(shutdown.shutdown_source(&key, deadline).await);
+    _ = source.await.unwrap();
+    events
+}
+
+/// The checkpoint should only be committed once the events of each page have been delivered.
+#[tokio::test]
+async fn offset_pagination_checkpoints_delivered_pages() {
+    let in_addr = next_addr();
+
+    let dummy_endpoint = warp::path!("endpoint")
+        .and(warp::query::<HashMap<String, usize>>())
+        .map(|query: HashMap<String, usize>| {
+            let start = usize::min(query["offset"], 3);
+            let end = (start + query["limit"]).min(3);
+            (start..end)
+                .map(|item| format!(r#"{{"item": {item}}}"#))
+                .collect::<Vec<_>>()
+                .join("\n")
+        });
+
+    tokio::spawn(warp::serve(dummy_endpoint).run(in_addr));
+    wait_for_tcp(in_addr).await;
+
+    let data_dir = data_dir();
+    let config = HttpClientConfig {
+        endpoint: format!("http://{}/endpoint", in_addr),
+        interval: Duration::from_secs(60),
+        timeout: TIMEOUT,
+        decoding: DeserializerConfig::Json(Default::default()),
+        framing: FramingConfig::NewlineDelimited(Default::default()),
+        pagination: Some(PaginationConfig {
+            strategy: PaginationStrategy::Offset {
+                offset_param: "offset".to_string(),
+                limit_param: "limit".to_string(),
+                limit: NonZeroUsize::new(2).unwrap(),
+            },
+            max_pages: NonZeroUsize::new(10).unwrap(),
+        }),
+        data_dir: Some(data_dir.clone()),
+        ..Default::default()
+    };
+    let checkpoint_file = data_dir.join("http_client").join(CHECKPOINT_FILE_NAME);
+
+    // Events that are rejected downstream are requested again after a restart.
+    let events = run_acknowledged(&config, EventStatus::Rejected, 3).await;
+    assert_eq!(field_values(&events, "item"), ["0", "1", "2"]);
+    assert_eq!(Checkpoint::read(&checkpoint_file).unwrap(), None);
+
+    let events = run_acknowledged(&config, EventStatus::Delivered, 3).await;
+    assert_eq!(field_values(&events, "item"), ["0", "1", "2"]);
+    assert_eq!(
+        Checkpoint::read(&checkpoint_file).unwrap().unwrap().offset,
+        3
+    );
+}
+
+/// Templated query parameters should be rendered with the last-seen timestamp, and omitted until
+/// one has been seen.
+#[tokio::test]
+async fn templated_query_last_timestamp() {
+    let in_addr = next_addr();
+    let requests = Arc::new(Mutex::new(0));
+
+    let dummy_endpoint = warp::path!("endpoint")
+        .and(warp::query::<HashMap<String, String>>())
+        .map({
+            let requests = Arc::clone(&requests);
+            move |query: HashMap<String, String>| {
+                let mut requests = requests.lock().unwrap();
+                *requests += 1;
+                let since = query
+                    .get("since")
+                    .map_or_else(|| "none".to_string(), Clone::clone);
+                format!(
+                    r#"{{"timestamp": "2024-01-0{}T00:00:00Z", "since": "{since}"}}"#,
+                    *requests
+                )
+            }
+        });
+
+    tokio::spawn(warp::serve(dummy_endpoint).run(in_addr));
+    wait_for_tcp(in_addr).await;
+
+    let events = run_compliance(HttpClientConfig {
+        endpoint: format!("http://{}/endpoint", in_addr),
+        interval: INTERVAL,
+        timeout: TIMEOUT,
+        query: HashMap::from([(
+            "since".to_string(),
+            vec!["{{ last_timestamp }}".to_string()],
+        )]),
+        decoding: DeserializerConfig::Json(Default::default()),
+        timestamp_field: Some("timestamp".to_string().try_into().unwrap()),
+        data_dir: Some(data_dir()),
+        ..Default::default()
+    })
+    .await;
+
+    let since = field_values(&events, "since");
+    assert!(since.len() >= 2);
+    assert_eq!(since[..2], ["none", "2024-01-01T00:00:00Z"]);
+}
@@ -0,0 +1,118 @@
+use std::time::Duration;
+
+use bytes::Bytes;
+use rumqttc::{AsyncClient, Event as MqttEvent, MqttOptions, Outgoing, Packet, QoS};
+use tokio::time::Instant;
+use vector_lib::config::{log_schema, ComponentKey};
+
+use super::*;
+use crate::{
+    test_util::{
+        collect_n,
+        components::{run_and_assert_source_compliance_n, SOURCE_TAGS},
+        random_lines, random_string, trace_init,
+    },
+    SourceSender,
+};
+
+fn mqtt_host() -> String {
+    std::env::var("MQTT_HOST").unwrap_or_else(|_| "localhost".into())
+}
+
+fn make_config(topics: Vec<String>) -> MqttSourceConfig {
+    MqttSourceConfig {
+        common: MqttCommonConfig {
+            host: mqtt_host(),
+            ..Default::default()
+        },
+        topics,
+        ..Default::default()
+    }
+}
+
+/// Publishes messages to a topic, waiting until they are acknowledged by the broker.
+async fn publish(topic: &str, messages: Vec<String>) {
+    let options = MqttOptions::new(format!("test{}", random_string(10)), mqtt_host(), 1883);
+    let (client, mut event_loop) = AsyncClient::new(options, messages.len() + 1);
+    for message in messages {
+        client
+            .publish_bytes(topic, QoS::AtLeastOnce, false, Bytes::from(message))
+            .await
+            .unwrap();
+    }
+    client.disconnect().await.unwrap();
+
+    while !matches!(
+        event_loop.poll().await.expect("failed to publish"),
+        MqttEvent::Outgoing(Outgoing::Disconnect)
+    ) {}
+}
+
+#[tokio::test]
+async fn mqtt_happy() {
+    trace_init();
+
+    let prefix = format!("test-{}", random_string(10));
+    let config = make_config(vec![format!("{prefix}/+")]);
+    let topic = format!("{prefix}/logs");
+    let input = random_lines(100).take(10).collect::<Vec<_>>();
+
+    let messages = input.clone();
+    tokio::spawn(async move {
+        // Give the source time to subscribe.
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        publish(&topic, messages).await;
+    });
+
+    let events = run_and_assert_source_compliance_n(config, input.len(), &SOURCE_TAGS).await;
+
+    assert_eq!(events.len(), input.len());
+    for (event, message) in events.iter().zip(input) {
+        let log = event.as_log();
+        assert_eq!(
+            log[log_schema().message_key().unwrap().to_string()],
+            message.into()
+        );
+        assert_eq!(log["topic"], format!("{prefix}/logs").into());
+    }
+}
+
+#[tokio::test]
+async fn mqtt_persistent_session() {
+    trace_init();
+
+    let topic = format!("test-{}", random_string(10));
+    let mut config = make_config(vec![topic.clone()]);
+    config.common.client_id = Some(format!("vector{}", random_string(10)));
+    config.clean_session = false;
+    let source_id = ComponentKey::from("mqtt");
+
+    // Subscribe, then shut down the source.
+    let (tx, _rx) = SourceSender::new_test();
+    let (context, mut shutdown) = SourceContext::new_shutdown(&source_id, tx);
+    let source = tokio::spawn(config.build(context).await.unwrap());
+    tokio::time::sleep(Duration::from_secs(1)).await;
+    let deadline = Instant::now() + Duration::from_secs(5);
+    assert!(shutdown.shutdown_source(&source_id, deadline).await);
+    source.await.unwrap().unwrap();
+
+    // The broker keeps the events published while the source is stopped in its session.
+    let input = random_lines(100).take(3).collect::<Vec<_>>();
+    publish(&topic, input.clone()).await;
+
+    let (tx, rx) = SourceSender::new_test();
+    let (context, _shutdown) = SourceContext::new_shutdown(&source_id, tx);
+    tokio::spawn(config.build(context).await.unwrap());
+
+    let events = tokio::time::timeout(Duration::from_secs(5), collect_n(rx, input.len()))
+        .await
+        .expect("events published while disconnected weren't received");
+    let output = events
+        .iter()
+        .map(|event| event.as_log()[log_schema().message_key().unwrap().to_string()].clone())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        output,
+        input.into_iter().map(Into::into).collect::<Vec<_>>()
+    );
+}
@@ -0,0 +1,324 @@
+use rumqttc::{AsyncClient, EventLoop};
+use snafu::{ResultExt, Snafu};
+use vector_lib::codecs::decoding::{DeserializerConfig, FramingConfig};
+use vector_lib::config::{LegacyKey, LogNamespace, SourceAcknowledgementsConfig};
+use vector_lib::configurable::configurable_component;
+use vector_lib::lookup::{lookup_v2::OptionalValuePath, owned_value_path};
+use vrl::value::Kind;
+
+use crate::{
+    codecs::DecodingConfig,
+    common::mqtt::{default_client_id, MqttCommonConfig, MqttError, MqttQoS},
+    config::{GenerateConfig, SourceConfig, SourceContext, SourceOutput},
+    serde::{default_decoding, default_framing_message_based},
+};
+
+#[cfg(feature = "mqtt-integration-tests")]
+#[cfg(test)]
+mod integration_tests;
+mod source;
+
+use self::source::MqttSource;
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("MQTT config error: {}", source))]
+    Config { source: MqttError },
+    #[snafu(display("invalid topics: at least one topic filter is required"))]
+    NoTopics,
+}
+
+/// Configuration for the `mqtt` source.
+#[configurable_component(source(
+    "mqtt",
+    "Collect observability data from topics on an MQTT broker."
+))]
+#[derive(Clone, Debug, Derivative)]
+#[derivative(Default)]
+pub struct MqttSourceConfig {
+    #[serde(flatten)]
+    #[configurable(derived)]
+    common: MqttCommonConfig,
+
+    /// The MQTT topic filters to subscribe to.
+    ///
+    /// Topic filters can contain the `+` single-level and `#` multi-level wildcards.
+    #[configurable(metadata(docs::examples = "vector"))]
+    #[configurable(metadata(docs::examples = "devices/+/logs"))]
+    #[configurable(metadata(docs::examples = "devices/#"))]
+    topics: Vec<String>,
+
+    /// The maximum quality of service level of the events delivered by the broker.
+    #[configurable(derived)]
+    #[serde(default)]
+    qos: MqttQoS,
+
+    /// Whether to start a new session when connecting to the broker.
+    ///
+    /// When disabled, the broker keeps the session of the client while it is disconnected, and
+    /// delivers the events published with a `qos` of `at_least_once` or `exactly_once` in the
+    /// meantime after reconnecting. This requires a stable `client_id`.
+    #[serde(default = "crate::serde::default_true")]
+    #[derivative(Default(value = "true"))]
+    clean_session: bool,
+
+    #[configurable(derived)]
+    #[serde(default = "default_framing_message_based")]
+    #[derivative(Default(value = "default_framing_message_based()"))]
+    framing: FramingConfig,
+
+    #[configurable(derived)]
+    #[serde(default = "default_decoding")]
+    #[derivative(Default(value = "default_decoding()"))]
+    decoding: DeserializerConfig,
+
+    /// Overrides the name of the log field used to add the topic to each event.
+    ///
+    /// The value is the topic the event was published to.
+    ///
+    /// By default, `"topic"` is used.
+    #[serde(default = "default_topic_key")]
+    #[derivative(Default(value = "default_topic_key()"))]
+    #[configurable(metadata(docs::examples = "topic"))]
+    topic_key: OptionalValuePath,
+
+    /// The namespace to use for logs. This overrides the global setting.
+    #[configurable(metadata(docs::hidden))]
+    #[serde(default)]
+    log_namespace: Option<bool>,
+}
+
+fn default_topic_key() -> OptionalValuePath {
+    OptionalValuePath::from(owned_value_path!("topic"))
+}
+
+impl GenerateConfig for MqttSourceConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"
+            host = "localhost"
+            topics = ["vector"]"#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "mqtt")]
+impl SourceConfig for MqttSourceConfig {
+    async fn build(&self, cx: SourceContext) -> crate::Result<super::Source> {
+        let log_namespace = cx.log_namespace(self.log_namespace);
+        let (client, event_loop) = self.client(&default_client_id(cx.key.id()))?;
+        let decoder =
+            DecodingConfig::new(self.framing.clone(), self.decoding.clone(), log_namespace)
+                .build()?;
+
+        let source = MqttSource {
+            client,
+            event_loop,
+            topics: self.topics.clone(),
+            qos: self.qos.into(),
+            topic_key: self.topic_key.clone(),
+            decoder,
+            log_namespace,
+            acknowledgements: cx.do_acknowledgements(SourceAcknowledgementsConfig::DEFAULT),
+        };
+        Ok(Box::pin(source.run(cx.out, cx.shutdown)))
+    }
+
+    fn outputs(&self, global_log_namespace: LogNamespace) -> Vec<SourceOutput> {
+        let log_namespace = global_log_namespace.merge(self.log_namespace);
+        let legacy_topic_key = self.topic_key.clone().path.map(LegacyKey::InsertIfEmpty);
+        let schema_definition = self
+            .decoding
+            .schema_definition(log_namespace)
+            .with_standard_vector_source_metadata()
+            .with_source_metadata(
+                Self::NAME,
+                legacy_topic_key,
+                &owned_value_path!("topic"),
+                Kind::bytes(),
+                None,
+            );
+
+        vec![SourceOutput::new_logs(
+            self.decoding.output_type(),
+            schema_definition,
+        )]
+    }
+
+    fn can_acknowledge(&self) -> bool {
+        true
+    }
+}
+
+impl MqttSourceConfig {
+    /// Creates a client, and the event loop that must be polled to receive events.
+    fn client(&self, default_client_id: &str) -> Result<(AsyncClient, EventLoop), BuildError> {
+        if self.topics.is_empty() {
+            return Err(BuildError::NoTopics);
+        }
+
+        let mut options = self
+            .common
+            .mqtt_options(default_client_id)
+            .context(ConfigSnafu)?;
+        options.set_clean_session(self.clean_session);
+        // Events are only acknowledged to the broker once they have been sent, or delivered when
+        // acknowledgements are enabled.
+        options.set_manual_acks(true);
+        Ok(AsyncClient::new(options, 16))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use futures::{Stream, StreamExt};
+    use vector_lib::event::{Event, EventStatus, Finalizable};
+    use vector_lib::lookup::OwnedTargetPath;
+    use vector_lib::schema::Definition;
+
+    use super::*;
+    use crate::{
+        common::mqtt::test_util::{MockBroker, MockConnection},
+        SourceSender,
+    };
+
+    /// Runs the source against a mock broker, and publishes a message to it.
+    async fn publish_message(
+        acknowledgements: bool,
+    ) -> (MockConnection, impl Stream<Item = Event> + Unpin) {
+        let broker = MockBroker::bind().await;
+        let config = MqttSourceConfig {
+            common: MqttCommonConfig {
+                host: "127.0.0.1".to_string(),
+                port: broker.addr().port(),
+                ..Default::default()
+            },
+            topics: vec!["vector".to_string()],
+            ..Default::default()
+        };
+
+        let (tx, rx) = SourceSender::new_test();
+        let mut cx = SourceContext::new_test(tx, None);
+        cx.acknowledgements = acknowledgements;
+        tokio::spawn(config.build(cx).await.unwrap());
+
+        let mut connection = broker.accept().await;
+        assert_eq!(connection.expect_subscribe().await, ["vector"]);
+        connection.publish(1, "vector", "hello").await;
+        (connection, rx)
+    }
+
+    async fn receive_event(rx: &mut (impl Stream<Item = Event> + Unpin)) -> Event {
+        let event = tokio::time::timeout(Duration::from_secs(5), rx.next())
+            .await
+            .expect("timed out receiving event")
+            .expect("source stopped");
+        let log = event.as_log();
+        assert_eq!(log["message"], "hello".into());
+        assert_eq!(log["topic"], "vector".into());
+        event
+    }
+
+    async fn assert_not_acknowledged(connection: &mut MockConnection) {
+        let ack = tokio::time::timeout(Duration::from_millis(200), connection.read_puback()).await;
+        assert!(ack.is_err(), "message was acknowledged: {ack:?}");
+    }
+
+    async fn assert_acknowledged(connection: &mut MockConnection) {
+        let ack = tokio::time::timeout(Duration::from_secs(5), connection.read_puback()).await;
+        assert_eq!(ack, Ok(Some(1)));
+    }
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<MqttSourceConfig>();
+    }
+
+    #[test]
+    fn requires_topics() {
+        let config: MqttSourceConfig = toml::from_str(
+            r#"
+            host = "localhost"
+            topics = []"#,
+        )
+        .unwrap();
+        assert!(matches!(
+            config.client("vectormqtt"),
+            Err(BuildError::NoTopics)
+        ));
+    }
+
+    #[test]
+    fn output_schema_definition_vector_namespace() {
+        let config = MqttSourceConfig {
+            log_namespace: Some(true),
+            ..Default::default()
+        };
+
+        let definitions = config
+            .outputs(LogNamespace::Vector)
+            .remove(0)
+            .schema_definition(true);
+
+        let expected_definition =
+            Definition::new_with_default_metadata(Kind::bytes(), [LogNamespace::Vector])
+                .with_meaning(OwnedTargetPath::event_root(), "message")
+                .with_metadata_field(
+                    &owned_value_path!("vector", "source_type"),
+                    Kind::bytes(),
+                    None,
+                )
+                .with_metadata_field(
+                    &owned_value_path!("vector", "ingest_timestamp"),
+                    Kind::timestamp(),
+                    None,
+                )
+                .with_metadata_field(&owned_value_path!("mqtt", "topic"), Kind::bytes(), None);
+
+        assert_eq!(definitions, Some(expected_definition));
+    }
+
+    #[tokio::test]
+    async fn acknowledges_delivered_events() {
+        let (mut connection, mut rx) = publish_message(true).await;
+        let mut event = receive_event(&mut rx).await;
+        assert_not_acknowledged(&mut connection).await;
+
+        event
+            .take_finalizers()
+            .update_status(EventStatus::Delivered);
+        drop(event);
+        assert_acknowledged(&mut connection).await;
+    }
+
+    #[tokio::test]
+    async fn acknowledges_rejected_events() {
+        let (mut connection, mut rx) = publish_message(true).await;
+        let mut event = receive_event(&mut rx).await;
+
+        event.take_finalizers().update_status(EventStatus::Rejected);
+        drop(event);
+        assert_acknowledged(&mut connection).await;
+    }
+
+    #[tokio::test]
+    async fn doesnt_acknowledge_errored_events() {
+        let (mut connection, mut rx) = publish_message(true).await;
+        let mut event = receive_event(&mut rx).await;
+
+        event.take_finalizers().update_status(EventStatus::Errored);
+        drop(event);
+        assert_not_acknowledged(&mut connection).await;
+    }
+
+    #[tokio::test]
+    async fn acknowledges_sent_events_without_acknowledgements() {
+        let (mut connection, mut rx) = publish_message(false).await;
+        let _event = receive_event(&mut rx).await;
+        assert_acknowledged(&mut connection).await;
+    }
+}
@@ -0,0 +1,212 @@
+use std::{collections::VecDeque, time::Duration};
+
+use chrono::Utc;
+use futures::StreamExt;
+use rumqttc::{
+    AsyncClient, Event as MqttEvent, EventLoop, Outgoing, Packet, Publish, QoS, SubscribeFilter,
+    SubscribeReasonCode,
+};
+use tokio_util::codec::FramedRead;
+use vector_lib::codecs::StreamDecodingError;
+use vector_lib::config::{LegacyKey, LogNamespace};
+use vector_lib::finalizer::UnorderedFinalizer;
+use vector_lib::internal_event::{
+    error_stage, ByteSize, BytesReceived, CountByteSize, EventsReceived, InternalEventHandle as _,
+    Protocol, Registered,
+};
+use vector_lib::lookup::{lookup_v2::OptionalValuePath, owned_value_path};
+use vector_lib::EstimatedJsonEncodedSizeOf;
+
+use super::MqttSourceConfig;
+use crate::{
+    codecs::Decoder,
+    config::SourceConfig,
+    event::{BatchNotifier, BatchStatus, BatchStatusReceiver, Event},
+    internal_events::{MqttConnectionError, MqttSubscriptionRejected, StreamClosedError},
+    shutdown::ShutdownSignal,
+    SourceSender,
+};
+
+/// How long to wait before reconnecting after a connection error.
+const RECONNECT_DELAY: Duration = Duration::from_secs(1);
+
+/// How long to wait for the disconnection to be sent to the broker when the source shuts down.
+const DISCONNECT_TIMEOUT: Duration = Duration::from_secs(1);
+
+pub(super) struct MqttSource {
+    pub(super) client: AsyncClient,
+    pub(super) event_loop: EventLoop,
+    pub(super) topics: Vec<String>,
+    pub(super) qos: QoS,
+    pub(super) topic_key: OptionalValuePath,
+    pub(super) decoder: Decoder,
+    pub(super) log_namespace: LogNamespace,
+    pub(super) acknowledgements: bool,
+}
+
+type Finalizer = UnorderedFinalizer<Publish>;
+
+impl MqttSource {
+    pub(super) async fn run(
+        mut self,
+        mut out: SourceSender,
+        mut shutdown: ShutdownSignal,
+    ) -> Result<(), ()> {
+        let events_received = register!(EventsReceived);
+        let bytes_received = register!(BytesReceived::from(Protocol::from("mqtt")));
+        let (finalizer, mut ack_stream) =
+            Finalizer::maybe_new(self.acknowledgements, Some(shutdown.clone()));
+        // The events that can be acknowledged to the broker. The acknowledgements are queued on the
+        // client without waiting, as the requests are only sent while the event loop is polled.
+        let mut acks = VecDeque::new();
+
+        loop {
+            tokio::select! {
+                _ = &mut shutdown => break,
+                entry = ack_stream.next() => if let Some((status, publish)) = entry {
+                    handle_ack(status, publish, &mut acks);
+                },
+                event = self.event_loop.poll() => match event {
+                    // Subscriptions are made again on each connection, as they are lost with the
+                    // session when it is clean, and may have changed since it was created when it
+                    // is persistent.
+                    Ok(MqttEvent::Incoming(Packet::ConnAck(_))) => self.subscribe(),
+                    Ok(MqttEvent::Incoming(Packet::SubAck(ack))) => {
+                        for (topic, code) in self.topics.iter().zip(ack.return_codes) {
+                            if matches!(code, SubscribeReasonCode::Failure) {
+                                emit!(MqttSubscriptionRejected { topic });
+                            }
+                        }
+                    }
+                    Ok(MqttEvent::Incoming(Packet::Publish(publish))) => {
+                        let receiver = self
+                            .handle_publish(&publish, &bytes_received, &events_received, &mut out)
+                            .await?;
+                        match (&finalizer, receiver) {
+                            (Some(finalizer), Some(receiver)) => finalizer.add(publish, receiver),
+                            _ => acks.push_back(publish),
+                        }
+                    }
+                    Ok(_) => {}
+                    Err(error) => {
+                        emit!(MqttConnectionError {
+                            error,
+                            stage: error_stage::RECEIVING,
+                        });
+                        tokio::time::sleep(RECONNECT_DELAY).await;
+                    }
+                }
+            }
+
+            self.send_acks(&mut acks);
+        }
+
+        // The event loop is polled until the disconnection is sent, so that the broker doesn't
+        // consider the connection lost.
+        if self.client.try_disconnect().is_ok() {
+            _ = tokio::time::timeout(DISCONNECT_TIMEOUT, async {
+                while !matches!(
+                    self.event_loop.poll().await,
+                    Ok(MqttEvent::Outgoing(Outgoing::Disconnect)) | Err(_)
+                ) {}
+            })
+            .await;
+        }
+
+        Ok(())
+    }
+
+    fn subscribe(&self) {
+        let filters = self
+            .topics
+            .iter()
+            .map(|topic| SubscribeFilter::new(topic.clone(), self.qos));
+        // The request can't be awaited while the event loop isn't polled, but the request
+        // channel is only full if the connection is lost before the previous one was sent, in
+        // which case it is sent after reconnecting.
+        if let Err(error) = self.client.try_subscribe_many(filters) {
+            debug!(message = "Failed to queue subscription request.", %error);
+        }
+    }
+
+    /// Queues the acknowledgements of events on the client, until its request channel is full.
+    fn send_acks(&self, acks: &mut VecDeque<Publish>) {
+        while let Some(publish) = acks.front() {
+            if self.client.try_ack(publish).is_err() {
+                break;
+            }
+            acks.pop_front();
+        }
+    }
+
+    /// Sends the events decoded from a message, returning the receiver of their status when
+    /// acknowledgements are enabled.
+    async fn handle_publish(
+        &self,
+        publish: &Publish,
+        bytes_received: &Registered<BytesReceived>,
+        events_received: &Registered<EventsReceived>,
+        out: &mut SourceSender,
+    ) -> Result<Option<BatchStatusReceiver>, ()> {
+        bytes_received.emit(ByteSize(publish.payload.len()));
+
+        let (batch, receiver) = BatchNotifier::maybe_new_with_receiver(self.acknowledgements);
+        let mut stream = FramedRead::new(publish.payload.as_ref(), self.decoder.clone());
+        while let Some(next) = stream.next().await {
+            match next {
+                Ok((events, _byte_size)) => {
+                    let count = events.len();
+                    let byte_size = events.estimated_json_encoded_size_of();
+                    events_received.emit(CountByteSize(count, byte_size));
+
+                    let now = Utc::now();
+
+                    let events = events.into_iter().map(|mut event| {
+                        if let Event::Log(ref mut log) = event {
+                            self.log_namespace.insert_standard_vector_source_metadata(
+                                log,
+                                MqttSourceConfig::NAME,
+                                now,
+                            );
+
+                            let legacy_topic_key =
+                                self.topic_key.path.as_ref().map(LegacyKey::InsertIfEmpty);
+                            self.log_namespace.insert_source_metadata(
+                                MqttSourceConfig::NAME,
+                                log,
+                                legacy_topic_key,
+                                &owned_value_path!("topic"),
+                                publish.topic.as_str(),
+                            );
+                        }
+                        event.with_batch_notifier_option(&batch)
+                    });
+
+                    out.send_batch(events).await.map_err(|_| {
+                        emit!(StreamClosedError { count });
+                    })?;
+                }
+                Err(error) => {
+                    // Error is logged by `crate::codecs`, no further
+                    // handling is needed here.
+                    if !error.can_continue() {
+                        break;
+                    }
+                }
+            }
+        }
+
+        Ok(receiver)
+    }
+}
+
+/// Acknowledges the events that were delivered or rejected to the broker.
+///
+/// MQTT has no way to reject an event, so events that errored aren't acknowledged, and are
+/// delivered again by the broker after reconnecting if the session is persistent.
+fn handle_ack(status: BatchStatus, publish: Publish, acks: &mut VecDeque<Publish>) {
+    match status {
+        BatchStatus::Delivered | BatchStatus::Rejected => acks.push_back(publish),
+        BatchStatus::Errored => {}
+    }
+}
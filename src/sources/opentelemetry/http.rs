@@ -13,8 +13,10 @@ use tracing::Span;
 use vector_lib::internal_event::{
     ByteSize, BytesReceived, CountByteSize, InternalEventHandle as _, Registered,
 };
-use vector_lib::opentelemetry::proto::collector::logs::v1::{
-    ExportLogsServiceRequest, ExportLogsServiceResponse,
+use vector_lib::opentelemetry::proto::collector::{
+    logs::v1::{ExportLogsServiceRequest, ExportLogsServiceResponse},
+    metrics::v1::{ExportMetricsServiceRequest, ExportMetricsServiceResponse},
+    trace::v1::{ExportTraceServiceRequest, ExportTraceServiceResponse},
 };
 use vector_lib::tls::MaybeTlsIncomingStream;
 use vector_lib::{
@@ -87,8 +89,59 @@ pub(crate) fn build_warp_filter(
     bytes_received: Registered<BytesReceived>,
     events_received: Registered<EventsReceived>,
 ) -> BoxedFilter<(Response,)> {
+    let log_filters = build_ingest_filter::<ExportLogsServiceResponse, _>(
+        super::LOGS,
+        acknowledgements,
+        out.clone(),
+        bytes_received.clone(),
+        events_received.clone(),
+        move |body| decode_log_body(body, log_namespace),
+    );
+    let metrics_filters = build_ingest_filter::<ExportMetricsServiceResponse, _>(
+        super::METRICS,
+        acknowledgements,
+        out.clone(),
+        bytes_received.clone(),
+        events_received.clone(),
+        decode_metrics_body,
+    );
+    let trace_filters = build_ingest_filter::<ExportTraceServiceResponse, _>(
+        super::TRACES,
+        acknowledgements,
+        out,
+        bytes_received,
+        events_received,
+        decode_trace_body,
+    );
+
+    log_filters
+        .or(metrics_filters)
+        .unify()
+        .or(trace_filters)
+        .unify()
+        .boxed()
+}
+
+/// Builds the filter handling `POST /v1/<telemetry_type>` requests.
+///
+/// Decoded events are sent to the output named after the telemetry type, and `Resp` is the
+/// (empty) OTLP response message returned on success.
+fn build_ingest_filter<Resp, F>(
+    telemetry_type: &'static str,
+    acknowledgements: bool,
+    out: SourceSender,
+    bytes_received: Registered<BytesReceived>,
+    events_received: Registered<EventsReceived>,
+    decode_body: F,
+) -> BoxedFilter<(Response,)>
+where
+    Resp: Message + Default + 'static,
+    F: Fn(Bytes) -> Result<Vec<Event>, ErrorMessage> + Clone + Send + Sync + 'static,
+{
     warp::post()
-        .and(warp::path!("v1" / "logs"))
+        .and(warp::path("v1"))
+        .and(warp::path(telemetry_type))
+        .and(warp::path::end())
         .and(warp::header::exact_ignore_case(
             "content-type",
             "application/x-protobuf",
@@ -96,48 +149,70 @@ pub(crate) fn build_warp_filter(
         .and(warp::header::optional::<String>("content-encoding"))
         .and(warp::body::bytes())
         .and_then(move |encoding_header: Option<String>, body: Bytes| {
-            let events = decode(encoding_header.as_deref(), body).and_then(|body| {
-                bytes_received.emit(ByteSize(body.len()));
-                decode_body(body, log_namespace, &events_received)
-            });
+            let events = decode(encoding_header.as_deref(), body)
+                .and_then(|body| {
+                    bytes_received.emit(ByteSize(body.len()));
+                    decode_body(body)
+                })
+                .map(|events| {
+                    events_received.emit(CountByteSize(
+                        events.len(),
+                        events.estimated_json_encoded_size_of(),
+                    ));
+                    events
+                });
 
-            handle_request(events, acknowledgements, out.clone(), super::LOGS)
+            handle_request::<Resp>(events, acknowledgements, out.clone(), telemetry_type)
         })
         .boxed()
 }
 
-fn decode_body(
-    body: Bytes,
-    log_namespace: LogNamespace,
-    events_received: &Registered<EventsReceived>,
-) -> Result<Vec<Event>, ErrorMessage> {
-    let request = ExportLogsServiceRequest::decode(body).map_err(|error| {
-        ErrorMessage::new(
-            StatusCode::BAD_REQUEST,
-            format!("Could not decode request: {}", error),
-        )
-    })?;
-
-    let events: Vec<Event> = request
+fn decode_error(error: prost::DecodeError) -> ErrorMessage {
+    ErrorMessage::new(
+        StatusCode::BAD_REQUEST,
+        format!("Could not decode request: {}", error),
+    )
+}
+
+fn decode_log_body(body: Bytes, log_namespace: LogNamespace) -> Result<Vec<Event>, ErrorMessage> {
+    let request = ExportLogsServiceRequest::decode(body).map_err(decode_error)?;
+
+    Ok(request
         .resource_logs
         .into_iter()
         .flat_map(|v| v.into_event_iter(log_namespace))
-        .collect();
+        .collect())
+}
+
+fn decode_metrics_body(body: Bytes) -> Result<Vec<Event>, ErrorMessage> {
+    let request = ExportMetricsServiceRequest::decode(body).map_err(decode_error)?;
 
-    events_received.emit(CountByteSize(
-        events.len(),
-        events.estimated_json_encoded_size_of(),
-    ));
+    Ok(request
+        .resource_metrics
+        .into_iter()
+        .flat_map(|v| v.into_event_iter())
+        .collect())
+}
+
+fn decode_trace_body(body: Bytes) -> Result<Vec<Event>, ErrorMessage> {
+    let request = ExportTraceServiceRequest::decode(body).map_err(decode_error)?;
 
-    Ok(events)
+    Ok(request
+        .resource_spans
+        .into_iter()
+        .flat_map(|v| v.into_event_iter())
+        .collect())
 }
 
-async fn handle_request(
+async fn handle_request<Resp>(
     events: Result<Vec<Event>, ErrorMessage>,
     acknowledgements: bool,
     mut out: SourceSender,
     output: &str,
-) -> Result<Response, Rejection> {
+) -> Result<Response, Rejection>
+where
+    Resp: Message + Default,
+{
     match events {
         Ok(mut events) => {
             let receiver = BatchNotifier::maybe_apply_to(acknowledgements, &mut events);
@@ -149,15 +224,9 @@ async fn handle_request(
             })?;
 
             match receiver {
-                None => Ok(protobuf(ExportLogsServiceResponse {
-                    partial_success: None,
-                })
-                .into_response()),
+                None => Ok(protobuf(Resp::default()).into_response()),
                 Some(receiver) => match receiver.await {
-                    BatchStatus::Delivered => Ok(protobuf(ExportLogsServiceResponse {
-                        partial_success: None,
-                    })
-                    .into_response()),
+                    BatchStatus::Delivered => Ok(protobuf(Resp::default()).into_response()),
                     BatchStatus::Errored => Err(warp::reject::custom(Status {
                         code: 2, // UNKNOWN - OTLP doesn't require use of status.code, but we can't encode a None here
                         message: "Error delivering contents to sink".into(),
@@ -1,17 +1,33 @@
-use std::sync::Arc;
+use std::{net::SocketAddr, sync::Arc};
 
 use chrono::{TimeZone, Utc};
 use futures::Stream;
 use futures_util::StreamExt;
+use prost::Message;
 use similar_asserts::assert_eq;
 use tonic::Request;
 use vector_lib::config::LogNamespace;
-use vector_lib::lookup::path;
+use vector_lib::event::{
+    metric::{Bucket, MetricSketch, Quantile},
+    MetricKind, MetricValue,
+};
+use vector_lib::lookup::{event_path, path};
 use vector_lib::opentelemetry::proto::{
-    collector::logs::v1::{logs_service_client::LogsServiceClient, ExportLogsServiceRequest},
-    common::v1::{any_value, AnyValue, KeyValue},
+    collector::{
+        logs::v1::{logs_service_client::LogsServiceClient, ExportLogsServiceRequest},
+        metrics::v1::{metrics_service_client::MetricsServiceClient, ExportMetricsServiceRequest},
+        trace::v1::{trace_service_client::TraceServiceClient, ExportTraceServiceRequest},
+    },
+    common::v1::{any_value, AnyValue, InstrumentationScope, KeyValue},
     logs::v1::{LogRecord, ResourceLogs, ScopeLogs},
+    metrics::v1::{
+        exponential_histogram_data_point::Buckets, metric::Data, number_data_point,
+        summary_data_point::ValueAtQuantile, AggregationTemporality, ExponentialHistogram,
+        ExponentialHistogramDataPoint, Gauge, Histogram, HistogramDataPoint, Metric as OtelMetric,
+        NumberDataPoint, ResourceMetrics, ScopeMetrics, Sum, Summary, SummaryDataPoint,
+    },
     resource::v1::Resource as OtelResource,
+    trace::v1::{span::Event as SpanEvent, ResourceSpans, ScopeSpans, Span, Status as SpanStatus},
 };
 use vrl::value;
 
@@ -19,7 +35,7 @@ use crate::config::OutputId;
 use crate::{
     config::{SourceConfig, SourceContext},
     event::{into_event_stream, Event, EventStatus, LogEvent, ObjectMap, Value},
-    sources::opentelemetry::{GrpcConfig, HttpConfig, OpentelemetryConfig, LOGS},
+    sources::opentelemetry::{GrpcConfig, HttpConfig, OpentelemetryConfig, LOGS, METRICS, TRACES},
     test_util::{
         self,
         components::{assert_source_compliance, SOURCE_TAGS},
@@ -283,18 +299,568 @@ async fn receive_grpc_logs_legacy_namespace() {
     .await;
 }
 
+fn number_point(time_unix_nano: u64, value: number_data_point::Value) -> NumberDataPoint {
+    NumberDataPoint {
+        attributes: vec![string_attribute("point_key", "point_val")],
+        start_time_unix_nano: 0,
+        time_unix_nano,
+        exemplars: vec![],
+        flags: 0,
+        value: Some(value),
+    }
+}
+
+fn otel_metric(name: &str, data: Data) -> OtelMetric {
+    OtelMetric {
+        name: name.into(),
+        description: String::new(),
+        unit: String::new(),
+        data: Some(data),
+    }
+}
+
+fn export_metrics_request() -> ExportMetricsServiceRequest {
+    ExportMetricsServiceRequest {
+        resource_metrics: vec![ResourceMetrics {
+            resource: Some(OtelResource {
+                attributes: vec![string_attribute("res_key", "res_val")],
+                dropped_attributes_count: 0,
+            }),
+            scope_metrics: vec![ScopeMetrics {
+                scope: Some(InstrumentationScope {
+                    name: "test_scope".into(),
+                    version: "1.0".into(),
+                    attributes: vec![],
+                    dropped_attributes_count: 0,
+                }),
+                metrics: vec![
+                    otel_metric(
+                        "requests",
+                        Data::Sum(Sum {
+                            data_points: vec![number_point(1, number_data_point::Value::AsInt(42))],
+                            aggregation_temporality: AggregationTemporality::Delta as i32,
+                            is_monotonic: true,
+                        }),
+                    ),
+                    otel_metric(
+                        "queue_depth",
+                        Data::Sum(Sum {
+                            data_points: vec![number_point(
+                                2,
+                                number_data_point::Value::AsDouble(-3.5),
+                            )],
+                            aggregation_temporality: AggregationTemporality::Cumulative as i32,
+                            is_monotonic: false,
+                        }),
+                    ),
+                    otel_metric(
+                        "temperature",
+                        Data::Gauge(Gauge {
+                            data_points: vec![number_point(
+                                3,
+                                number_data_point::Value::AsDouble(21.5),
+                            )],
+                        }),
+                    ),
+                    otel_metric(
+                        "latency",
+                        Data::Histogram(Histogram {
+                            data_points: vec![HistogramDataPoint {
+                                attributes: vec![],
+                                start_time_unix_nano: 0,
+                                time_unix_nano: 4,
+                                count: 6,
+                                sum: Some(12.0),
+                                bucket_counts: vec![1, 2, 3],
+                                explicit_bounds: vec![1.0, 5.0],
+                                exemplars: vec![],
+                                flags: 0,
+                                min: None,
+                                max: None,
+                            }],
+                            aggregation_temporality: AggregationTemporality::Cumulative as i32,
+                        }),
+                    ),
+                    otel_metric(
+                        "response_size",
+                        Data::Summary(Summary {
+                            data_points: vec![SummaryDataPoint {
+                                attributes: vec![],
+                                start_time_unix_nano: 0,
+                                time_unix_nano: 5,
+                                count: 10,
+                                sum: 100.0,
+                                quantile_values: vec![ValueAtQuantile {
+                                    quantile: 0.5,
+                                    value: 9.0,
+                                }],
+                                flags: 0,
+                            }],
+                        }),
+                    ),
+                ],
+                schema_url: "v1".into(),
+            }],
+            schema_url: "v1".into(),
+        }],
+    }
+}
+
+fn assert_metrics(output: Vec<Event>) {
+    assert_eq!(output.len(), 5);
+
+    let requests = output[0].as_metric();
+    assert_eq!(requests.name(), "requests");
+    assert_eq!(requests.kind(), MetricKind::Incremental);
+    assert_eq!(requests.value(), &MetricValue::Counter { value: 42.0 });
+    assert_eq!(requests.timestamp(), Some(Utc.timestamp_nanos(1)));
+    assert_eq!(
+        requests.tag_value("resource.res_key"),
+        Some("res_val".into())
+    );
+    assert_eq!(requests.tag_value("scope.name"), Some("test_scope".into()));
+    assert_eq!(requests.tag_value("scope.version"), Some("1.0".into()));
+    assert_eq!(requests.tag_value("point_key"), Some("point_val".into()));
+
+    let queue_depth = output[1].as_metric();
+    assert_eq!(queue_depth.name(), "queue_depth");
+    assert_eq!(queue_depth.kind(), MetricKind::Absolute);
+    assert_eq!(queue_depth.value(), &MetricValue::Gauge { value: -3.5 });
+
+    let temperature = output[2].as_metric();
+    assert_eq!(temperature.name(), "temperature");
+    assert_eq!(temperature.kind(), MetricKind::Absolute);
+    assert_eq!(temperature.value(), &MetricValue::Gauge { value: 21.5 });
+
+    let latency = output[3].as_metric();
+    assert_eq!(latency.name(), "latency");
+    assert_eq!(latency.kind(), MetricKind::Absolute);
+    assert_eq!(
+        latency.value(),
+        &MetricValue::AggregatedHistogram {
+            buckets: vec![
+                Bucket {
+                    upper_limit: 1.0,
+                    count: 1
+                },
+                Bucket {
+                    upper_limit: 5.0,
+                    count: 2
+                },
+                Bucket {
+                    upper_limit: f64::INFINITY,
+                    count: 3
+                },
+            ],
+            count: 6,
+            sum: 12.0,
+        }
+    );
+    assert_eq!(
+        latency.tag_value("resource.res_key"),
+        Some("res_val".into())
+    );
+    assert_eq!(latency.tag_value("point_key"), None);
+
+    let response_size = output[4].as_metric();
+    assert_eq!(response_size.name(), "response_size");
+    assert_eq!(response_size.kind(), MetricKind::Absolute);
+    assert_eq!(
+        response_size.value(),
+        &MetricValue::AggregatedSummary {
+            quantiles: vec![Quantile {
+                quantile: 0.5,
+                value: 9.0,
+            }],
+            count: 10,
+            sum: 100.0,
+        }
+    );
+}
+
+fn exponential_histogram_metrics(scale: i32, offset: i32) -> ResourceMetrics {
+    ResourceMetrics {
+        resource: None,
+        scope_metrics: vec![ScopeMetrics {
+            scope: None,
+            metrics: vec![otel_metric(
+                "payload_size",
+                Data::ExponentialHistogram(ExponentialHistogram {
+                    data_points: vec![ExponentialHistogramDataPoint {
+                        attributes: vec![],
+                        start_time_unix_nano: 0,
+                        time_unix_nano: 6,
+                        count: 6,
+                        sum: Some(21.0),
+                        scale,
+                        zero_count: 1,
+                        positive: Some(Buckets {
+                            offset,
+                            bucket_counts: vec![2, 3],
+                        }),
+                        negative: None,
+                        flags: 0,
+                        exemplars: vec![],
+                        min: Some(0.0),
+                        max: Some(4.0),
+                        zero_threshold: 0.0,
+                    }],
+                    aggregation_temporality: AggregationTemporality::Delta as i32,
+                }),
+            )],
+            schema_url: String::new(),
+        }],
+        schema_url: String::new(),
+    }
+}
+
+#[test]
+fn exponential_histogram_uses_point_summary() {
+    let output = exponential_histogram_metrics(0, 0)
+        .into_event_iter()
+        .collect::<Vec<_>>();
+    assert_eq!(output.len(), 1);
+
+    let metric = output[0].as_metric();
+    assert_eq!(metric.name(), "payload_size");
+    assert_eq!(metric.kind(), MetricKind::Incremental);
+    let MetricValue::Sketch {
+        sketch: MetricSketch::AgentDDSketch(sketch),
+    } = metric.value()
+    else {
+        panic!("unexpected metric value: {:?}", metric.value());
+    };
+    assert_eq!(sketch.count(), 6);
+    assert_eq!(sketch.sum(), Some(21.0));
+    assert_eq!(sketch.min(), Some(0.0));
+    assert_eq!(sketch.max(), Some(4.0));
+}
+
+#[test]
+fn exponential_histogram_rejects_invalid_scale() {
+    for scale in [-11, -10, 21, i32::MIN, i32::MAX] {
+        assert_eq!(
+            exponential_histogram_metrics(scale, 0)
+                .into_event_iter()
+                .count(),
+            0
+        );
+    }
+}
+
+#[test]
+fn exponential_histogram_rejects_overflowing_buckets() {
+    // The upper bound of the last bucket is `2^1024` or more, which doesn't fit in an `f64`.
+    for (scale, offset) in [(-9, 0), (20, (1024 << 20) - 1), (20, i32::MAX - 1)] {
+        assert_eq!(
+            exponential_histogram_metrics(scale, offset)
+                .into_event_iter()
+                .count(),
+            0,
+            "scale {scale}, offset {offset}"
+        );
+    }
+}
+
+#[test]
+fn exponential_histogram_accepts_extreme_buckets() {
+    // The bounds of the buckets fit in an `f64`, right below `2^1024` for the highest indices, or
+    // rounded down to zero for the lowest ones.
+    for (scale, offset) in [
+        (-9, -1),
+        (20, (1024 << 20) - 3),
+        (-9, i32::MIN),
+        (20, i32::MIN),
+    ] {
+        let output = exponential_histogram_metrics(scale, offset)
+            .into_event_iter()
+            .collect::<Vec<_>>();
+        assert_eq!(output.len(), 1, "scale {scale}, offset {offset}");
+
+        let MetricValue::Sketch {
+            sketch: MetricSketch::AgentDDSketch(sketch),
+        } = output[0].as_metric().value()
+        else {
+            panic!(
+                "unexpected metric value: {:?}",
+                output[0].as_metric().value()
+            );
+        };
+        assert_eq!(sketch.count(), 6);
+    }
+}
+
+#[tokio::test]
+async fn receive_grpc_metrics() {
+    assert_source_compliance(&SOURCE_TAGS, async {
+        let grpc_addr = next_addr();
+        let http_addr = next_addr();
+        let source = new_config(grpc_addr, http_addr);
+
+        let (sender, metrics_output, _) = new_source_with_output(EventStatus::Delivered, METRICS);
+        let server = source
+            .build(SourceContext::new_test(sender, None))
+            .await
+            .unwrap();
+        tokio::spawn(server);
+        test_util::wait_for_tcp(grpc_addr).await;
+
+        let mut client = MetricsServiceClient::connect(format!("http://{}", grpc_addr))
+            .await
+            .unwrap();
+        _ = client
+            .export(Request::new(export_metrics_request()))
+            .await
+            .unwrap();
+
+        assert_metrics(test_util::collect_ready(metrics_output).await);
+    })
+    .await;
+}
+
+#[tokio::test]
+async fn receive_http_metrics() {
+    assert_source_compliance(&SOURCE_TAGS, async {
+        let grpc_addr = next_addr();
+        let http_addr = next_addr();
+        let source = new_config(grpc_addr, http_addr);
+
+        let (sender, metrics_output, _) = new_source_with_output(EventStatus::Delivered, METRICS);
+        let server = source
+            .build(SourceContext::new_test(sender, None))
+            .await
+            .unwrap();
+        tokio::spawn(server);
+        test_util::wait_for_tcp(http_addr).await;
+
+        let response = reqwest::Client::new()
+            .post(format!("http://{}/v1/metrics", http_addr))
+            .header("Content-Type", "application/x-protobuf")
+            .body(export_metrics_request().encode_to_vec())
+            .send()
+            .await
+            .unwrap();
+        assert!(response.status().is_success());
+
+        assert_metrics(test_util::collect_ready(metrics_output).await);
+    })
+    .await;
+}
+
+fn export_trace_request() -> ExportTraceServiceRequest {
+    ExportTraceServiceRequest {
+        resource_spans: vec![ResourceSpans {
+            resource: Some(OtelResource {
+                attributes: vec![string_attribute("res_key", "res_val")],
+                dropped_attributes_count: 0,
+            }),
+            scope_spans: vec![ScopeSpans {
+                scope: None,
+                spans: vec![Span {
+                    trace_id: str_into_hex_bytes("4ac52aadf321c2e531db005df08792f5"),
+                    span_id: str_into_hex_bytes("0b9e4bda2a55530d"),
+                    trace_state: "foo=bar".into(),
+                    parent_span_id: str_into_hex_bytes("b6a2b9f3eb4f43c5"),
+                    name: "GET /users".into(),
+                    kind: 2,
+                    start_time_unix_nano: 1,
+                    end_time_unix_nano: 2,
+                    attributes: vec![string_attribute("attr_key", "attr_val")],
+                    dropped_attributes_count: 3,
+                    events: vec![SpanEvent {
+                        time_unix_nano: 1,
+                        name: "cache miss".into(),
+                        attributes: vec![],
+                        dropped_attributes_count: 0,
+                    }],
+                    dropped_events_count: 4,
+                    links: vec![],
+                    dropped_links_count: 5,
+                    status: Some(SpanStatus {
+                        message: "something went wrong".into(),
+                        code: 2,
+                    }),
+                }],
+                schema_url: "v1".into(),
+            }],
+            schema_url: "v1".into(),
+        }],
+    }
+}
+
+fn assert_traces(mut output: Vec<Event>) {
+    assert_eq!(output.len(), 1);
+    let trace = output.pop().unwrap().into_trace();
+
+    assert_eq!(
+        trace.get(event_path!("trace_id")),
+        Some(&value!("4ac52aadf321c2e531db005df08792f5"))
+    );
+    assert_eq!(
+        trace.get(event_path!("span_id")),
+        Some(&value!("0b9e4bda2a55530d"))
+    );
+    assert_eq!(
+        trace.get(event_path!("parent_span_id")),
+        Some(&value!("b6a2b9f3eb4f43c5"))
+    );
+    assert_eq!(
+        trace.get(event_path!("trace_state")),
+        Some(&value!("foo=bar"))
+    );
+    assert_eq!(trace.get(event_path!("name")), Some(&value!("GET /users")));
+    assert_eq!(trace.get(event_path!("kind")), Some(&value!(2)));
+    assert_eq!(
+        trace.get(event_path!("start_time_unix_nano")),
+        Some(&value!(Utc.timestamp_nanos(1)))
+    );
+    assert_eq!(
+        trace.get(event_path!("end_time_unix_nano")),
+        Some(&value!(Utc.timestamp_nanos(2)))
+    );
+    assert_eq!(
+        trace.get(event_path!("attributes")),
+        Some(&value!({attr_key: "attr_val"}))
+    );
+    assert_eq!(
+        trace.get(event_path!("resources")),
+        Some(&value!({res_key: "res_val"}))
+    );
+    assert_eq!(
+        trace.get(event_path!("dropped_attributes_count")),
+        Some(&value!(3))
+    );
+    assert_eq!(
+        trace.get(event_path!("dropped_events_count")),
+        Some(&value!(4))
+    );
+    assert_eq!(
+        trace.get(event_path!("dropped_links_count")),
+        Some(&value!(5))
+    );
+    assert_eq!(
+        trace.get(event_path!("events", 0, "name")),
+        Some(&value!("cache miss"))
+    );
+    assert_eq!(
+        trace.get(event_path!("status")),
+        Some(&value!({message: "something went wrong", code: 2}))
+    );
+    assert_eq!(
+        trace.get(event_path!("source_type")),
+        Some(&value!(OpentelemetryConfig::NAME))
+    );
+    assert!(trace
+        .get(event_path!("ingest_timestamp"))
+        .unwrap()
+        .is_timestamp());
+}
+
+#[tokio::test]
+async fn receive_grpc_traces() {
+    assert_source_compliance(&SOURCE_TAGS, async {
+        let grpc_addr = next_addr();
+        let http_addr = next_addr();
+        let source = new_config(grpc_addr, http_addr);
+
+        let (sender, traces_output, _) = new_source_with_output(EventStatus::Delivered, TRACES);
+        let server = source
+            .build(SourceContext::new_test(sender, None))
+            .await
+            .unwrap();
+        tokio::spawn(server);
+        test_util::wait_for_tcp(grpc_addr).await;
+
+        let mut client = TraceServiceClient::connect(format!("http://{}", grpc_addr))
+            .await
+            .unwrap();
+        _ = client
+            .export(Request::new(export_trace_request()))
+            .await
+            .unwrap();
+
+        assert_traces(test_util::collect_ready(traces_output).await);
+    })
+    .await;
+}
+
+#[tokio::test]
+async fn receive_http_traces() {
+    assert_source_compliance(&SOURCE_TAGS, async {
+        let grpc_addr = next_addr();
+        let http_addr = next_addr();
+        let source = new_config(grpc_addr, http_addr);
+
+        let (sender, traces_output, _) = new_source_with_output(EventStatus::Delivered, TRACES);
+        let server = source
+            .build(SourceContext::new_test(sender, None))
+            .await
+            .unwrap();
+        tokio::spawn(server);
+        test_util::wait_for_tcp(http_addr).await;
+
+        let response = reqwest::Client::new()
+            .post(format!("http://{}/v1/traces", http_addr))
+            .header("Content-Type", "application/x-protobuf")
+            .body(export_trace_request().encode_to_vec())
+            .send()
+            .await
+            .unwrap();
+        assert!(response.status().is_success());
+
+        assert_traces(test_util::collect_ready(traces_output).await);
+    })
+    .await;
+}
+
 pub(super) fn new_source(
     status: EventStatus,
 ) -> (
     SourceSender,
     impl Stream<Item = Event>,
     impl Stream<Item = Event>,
+) {
+    new_source_with_output(status, LOGS)
+}
+
+fn new_source_with_output(
+    status: EventStatus,
+    output: &str,
+) -> (
+    SourceSender,
+    impl Stream<Item = Event>,
+    impl Stream<Item = Event>,
 ) {
     let (mut sender, recv) = SourceSender::new_test_finalize(status);
-    let logs_output = sender
-        .add_outputs(status, LOGS.to_string())
+    let output = sender
+        .add_outputs(status, output.to_string())
         .flat_map(into_event_stream);
-    (sender, logs_output, recv)
+    (sender, output, recv)
+}
+
+fn new_config(grpc_addr: SocketAddr, http_addr: SocketAddr) -> OpentelemetryConfig {
+    OpentelemetryConfig {
+        grpc: GrpcConfig {
+            address: grpc_addr,
+            tls: Default::default(),
+        },
+        http: HttpConfig {
+            address: http_addr,
+            tls: Default::default(),
+            keepalive: Default::default(),
+        },
+        acknowledgements: Default::default(),
+        log_namespace: Default::default(),
+    }
+}
+
+fn string_attribute(key: &str, value: &str) -> KeyValue {
+    KeyValue {
+        key: key.into(),
+        value: Some(AnyValue {
+            value: Some(any_value::Value::StringValue(value.into())),
+        }),
+    }
 }
 
 fn str_into_hex_bytes(s: &str) -> Vec<u8> {
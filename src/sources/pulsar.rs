@@ -0,0 +1,943 @@
+//! `pulsar` source.
+//! Consumes messages from Apache Pulsar topics.
+use std::time::Duration;
+
+use bytes::Bytes;
+use chrono::{DateTime, TimeZone, Utc};
+use futures::StreamExt;
+use pulsar::{
+    consumer::{DeadLetterPolicy, InitialPosition, Message},
+    message::proto::MessageIdData,
+    Consumer, ConsumerOptions, SubType, TokioExecutor,
+};
+use regex::Regex;
+use snafu::{ResultExt, Snafu};
+use tokio_util::codec::FramedRead;
+use vector_lib::codecs::{
+    decoding::{DeserializerConfig, FramingConfig},
+    StreamDecodingError,
+};
+use vector_lib::configurable::configurable_component;
+use vector_lib::finalizer::UnorderedFinalizer;
+use vector_lib::internal_event::{
+    ByteSize, BytesReceived, CountByteSize, EventsReceived, InternalEventHandle as _, Protocol,
+    Registered,
+};
+use vector_lib::lookup::{lookup_v2::OptionalValuePath, owned_value_path, path, OwnedValuePath};
+use vector_lib::{
+    config::{log_schema, LegacyKey, LogNamespace, SourceAcknowledgementsConfig},
+    EstimatedJsonEncodedSizeOf,
+};
+use vrl::value::{kind::Collection, Kind, ObjectMap, Value};
+
+use crate::{
+    codecs::{Decoder, DecodingConfig},
+    common::pulsar::{create_pulsar_client, PulsarAuthConfig},
+    config::{LogSchema, SourceConfig, SourceContext, SourceOutput},
+    event::{BatchNotifier, BatchStatus, Event},
+    internal_events::{PulsarAcknowledgmentError, PulsarReadError, StreamClosedError},
+    serde::{bool_or_struct, default_decoding, default_framing_message_based},
+    shutdown::ShutdownSignal,
+    SourceSender,
+};
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("At least one of `topics` or `topics_pattern` must be set"))]
+    NoTopics,
+    #[snafu(display("Invalid topics pattern: {}", source))]
+    InvalidTopicsPattern { source: regex::Error },
+    #[snafu(display("Could not create Pulsar client: {}", source))]
+    CreateClient { source: pulsar::Error },
+    #[snafu(display("Could not create Pulsar consumer: {}", source))]
+    CreateConsumer { source: pulsar::Error },
+}
+
+/// Configuration for the `pulsar` source.
+#[configurable_component(source(
+    "pulsar",
+    "Collect observability events from Apache Pulsar topics."
+))]
+#[derive(Clone, Debug, Derivative)]
+#[derivative(Default)]
+#[serde(deny_unknown_fields)]
+pub struct PulsarSourceConfig {
+    /// The endpoint to which the Pulsar client should connect to.
+    ///
+    /// The endpoint should specify the pulsar protocol and port.
+    #[serde(alias = "address")]
+    #[configurable(metadata(docs::examples = "pulsar://127.0.0.1:6650"))]
+    endpoint: String,
+
+    /// The Pulsar topic names to read events from.
+    #[serde(default)]
+    #[configurable(metadata(docs::examples = "persistent://public/default/logs"))]
+    topics: Vec<String>,
+
+    #[configurable(derived)]
+    topics_pattern: Option<PulsarTopicsPattern>,
+
+    /// The name of the Pulsar subscription.
+    ///
+    /// Consumers sharing a subscription name share the messages of the topics according to the
+    /// `subscription_type`.
+    #[serde(default = "default_subscription_name")]
+    #[derivative(Default(value = "default_subscription_name()"))]
+    #[configurable(metadata(docs::examples = "vector"))]
+    subscription_name: String,
+
+    #[configurable(derived)]
+    #[serde(default)]
+    subscription_type: PulsarSubscriptionType,
+
+    /// The name of the consumer. If not specified, the default name assigned by Pulsar is used.
+    #[configurable(metadata(docs::examples = "consumer-name"))]
+    consumer_name: Option<String>,
+
+    #[configurable(derived)]
+    #[serde(default)]
+    initial_position: PulsarInitialPosition,
+
+    /// The maximum number of messages the broker sends to the consumer at once.
+    #[configurable(metadata(docs::type_unit = "messages"))]
+    #[configurable(metadata(docs::examples = 1000))]
+    batch_size: Option<u32>,
+
+    #[configurable(derived)]
+    dead_letter_queue_policy: Option<PulsarDeadLetterQueuePolicy>,
+
+    #[configurable(derived)]
+    auth: Option<PulsarAuthConfig>,
+
+    /// Overrides the name of the log field used to add the message key to each event.
+    ///
+    /// The value is the partition key of the Pulsar message itself.
+    ///
+    /// By default, `"message_key"` is used.
+    #[serde(default = "default_key_field")]
+    #[derivative(Default(value = "default_key_field()"))]
+    #[configurable(metadata(docs::examples = "message_key"))]
+    key_field: OptionalValuePath,
+
+    /// Overrides the name of the log field used to add the topic to each event.
+    ///
+    /// The value is the topic from which the Pulsar message was consumed from.
+    ///
+    /// By default, `"topic"` is used.
+    #[serde(default = "default_topic_key")]
+    #[derivative(Default(value = "default_topic_key()"))]
+    #[configurable(metadata(docs::examples = "topic"))]
+    topic_key: OptionalValuePath,
+
+    /// Overrides the name of the log field used to add the properties to each event.
+    ///
+    /// The value is the properties of the Pulsar message itself.
+    ///
+    /// By default, `"properties"` is used.
+    #[serde(default = "default_properties_key")]
+    #[derivative(Default(value = "default_properties_key()"))]
+    #[configurable(metadata(docs::examples = "properties"))]
+    properties_key: OptionalValuePath,
+
+    #[configurable(derived)]
+    #[serde(default = "default_framing_message_based")]
+    #[derivative(Default(value = "default_framing_message_based()"))]
+    framing: FramingConfig,
+
+    #[configurable(derived)]
+    #[serde(default = "default_decoding")]
+    #[derivative(Default(value = "default_decoding()"))]
+    decoding: DeserializerConfig,
+
+    #[configurable(derived)]
+    #[serde(default, deserialize_with = "bool_or_struct")]
+    acknowledgements: SourceAcknowledgementsConfig,
+
+    /// The namespace to use for logs. This overrides the global setting.
+    #[configurable(metadata(docs::hidden))]
+    #[serde(default)]
+    log_namespace: Option<bool>,
+}
+
+/// Subscription to the topics of a namespace whose names match a pattern.
+///
+/// The topics are looked up periodically, so that new topics are subscribed to as they are
+/// created.
+#[configurable_component]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct PulsarTopicsPattern {
+    /// The regular expression that the names of the topics must match.
+    ///
+    /// Topic names are fully qualified, such as `persistent://public/default/logs`.
+    #[configurable(metadata(docs::examples = "persistent://public/default/logs-.*"))]
+    pattern: String,
+
+    /// The namespace in which topics are looked up.
+    #[serde(default = "default_namespace")]
+    #[configurable(metadata(docs::examples = "public/default"))]
+    namespace: String,
+
+    /// The interval at which topics matching the pattern are looked up.
+    #[serde(default = "default_refresh_interval_secs")]
+    #[configurable(metadata(docs::type_unit = "seconds"))]
+    #[configurable(metadata(docs::human_name = "Refresh Interval"))]
+    refresh_interval_secs: u64,
+}
+
+/// The type of the Pulsar subscription.
+#[configurable_component]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum PulsarSubscriptionType {
+    /// Only a single consumer can be attached to the subscription.
+    Exclusive,
+
+    /// The messages are distributed across the consumers attached to the subscription.
+    #[default]
+    Shared,
+
+    /// A single consumer attached to the subscription receives the messages, and another one takes
+    /// over when it disconnects.
+    Failover,
+
+    /// The messages are distributed across the consumers attached to the subscription, with
+    /// messages of the same key always delivered to the same consumer.
+    KeyShared,
+}
+
+impl From<PulsarSubscriptionType> for SubType {
+    fn from(subscription_type: PulsarSubscriptionType) -> Self {
+        match subscription_type {
+            PulsarSubscriptionType::Exclusive => SubType::Exclusive,
+            PulsarSubscriptionType::Shared => SubType::Shared,
+            PulsarSubscriptionType::Failover => SubType::Failover,
+            PulsarSubscriptionType::KeyShared => SubType::KeyShared,
+        }
+    }
+}
+
+/// The position from which a new subscription starts reading messages.
+#[configurable_component]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum PulsarInitialPosition {
+    /// Read the messages published after the subscription is created.
+    #[default]
+    Latest,
+
+    /// Read all the messages retained in the topics.
+    Earliest,
+}
+
+impl From<PulsarInitialPosition> for InitialPosition {
+    fn from(position: PulsarInitialPosition) -> Self {
+        match position {
+            PulsarInitialPosition::Latest => InitialPosition::Latest,
+            PulsarInitialPosition::Earliest => InitialPosition::Earliest,
+        }
+    }
+}
+
+/// Dead letter queue policy.
+///
+/// Messages that are negatively acknowledged more than `max_redeliver_count` times are published
+/// to the dead letter topic, instead of being redelivered.
+#[configurable_component]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct PulsarDeadLetterQueuePolicy {
+    /// The maximum number of times a message is redelivered.
+    max_redeliver_count: usize,
+
+    /// The topic dead letters are published to.
+    #[configurable(metadata(docs::examples = "persistent://public/default/logs-dlq"))]
+    dead_letter_topic: String,
+}
+
+fn default_subscription_name() -> String {
+    "vector".into()
+}
+
+fn default_namespace() -> String {
+    "public/default".into()
+}
+
+const fn default_refresh_interval_secs() -> u64 {
+    60
+}
+
+fn default_key_field() -> OptionalValuePath {
+    OptionalValuePath::from(owned_value_path!("message_key"))
+}
+
+fn default_topic_key() -> OptionalValuePath {
+    OptionalValuePath::from(owned_value_path!("topic"))
+}
+
+fn default_properties_key() -> OptionalValuePath {
+    OptionalValuePath::from(owned_value_path!("properties"))
+}
+
+impl_generate_config_from_default!(PulsarSourceConfig);
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "pulsar")]
+impl SourceConfig for PulsarSourceConfig {
+    async fn build(&self, cx: SourceContext) -> crate::Result<super::Source> {
+        let log_namespace = cx.log_namespace(self.log_namespace);
+        let acknowledgements = cx.do_acknowledgements(self.acknowledgements);
+
+        let consumer = self.create_consumer().await?;
+        let decoder =
+            DecodingConfig::new(self.framing.clone(), self.decoding.clone(), log_namespace)
+                .build()?;
+
+        Ok(Box::pin(pulsar_source(
+            Keys::from(log_schema(), self),
+            consumer,
+            decoder,
+            cx.shutdown,
+            cx.out,
+            acknowledgements,
+            log_namespace,
+        )))
+    }
+
+    fn outputs(&self, global_log_namespace: LogNamespace) -> Vec<SourceOutput> {
+        let log_namespace = global_log_namespace.merge(self.log_namespace);
+        let keys = Keys::from(log_schema(), self);
+
+        let schema_definition = self
+            .decoding
+            .schema_definition(log_namespace)
+            .with_standard_vector_source_metadata()
+            .with_source_metadata(
+                Self::NAME,
+                keys.timestamp.map(LegacyKey::Overwrite),
+                &owned_value_path!("timestamp"),
+                Kind::timestamp(),
+                Some("timestamp"),
+            )
+            .with_source_metadata(
+                Self::NAME,
+                keys.topic.map(LegacyKey::Overwrite),
+                &owned_value_path!("topic"),
+                Kind::bytes(),
+                None,
+            )
+            .with_source_metadata(
+                Self::NAME,
+                keys.key_field.map(LegacyKey::Overwrite),
+                &owned_value_path!("message_key"),
+                Kind::bytes().or_null(),
+                None,
+            )
+            .with_source_metadata(
+                Self::NAME,
+                keys.properties.map(LegacyKey::Overwrite),
+                &owned_value_path!("properties"),
+                Kind::object(Collection::empty().with_unknown(Kind::bytes())),
+                None,
+            );
+
+        vec![SourceOutput::new_logs(
+            self.decoding.output_type(),
+            schema_definition,
+        )]
+    }
+
+    fn can_acknowledge(&self) -> bool {
+        true
+    }
+}
+
+impl PulsarSourceConfig {
+    async fn create_consumer(&self) -> Result<Consumer<String, TokioExecutor>, BuildError> {
+        if self.topics.is_empty() && self.topics_pattern.is_none() {
+            return Err(BuildError::NoTopics);
+        }
+        let topics_regex = self
+            .topics_pattern
+            .as_ref()
+            .map(|topics_pattern| Regex::new(&topics_pattern.pattern))
+            .transpose()
+            .context(InvalidTopicsPatternSnafu)?;
+
+        let pulsar = create_pulsar_client(&self.endpoint, self.auth.as_ref())
+            .await
+            .context(CreateClientSnafu)?;
+
+        // The payloads are decoded by the configured codecs, rather than deserialized by the
+        // consumer.
+        let mut builder = pulsar
+            .consumer()
+            .with_subscription(&self.subscription_name)
+            .with_subscription_type(self.subscription_type.into())
+            .with_options(ConsumerOptions {
+                initial_position: self.initial_position.into(),
+                ..Default::default()
+            });
+        if !self.topics.is_empty() {
+            builder = builder.with_topics(&self.topics);
+        }
+        if let (Some(topics_pattern), Some(topics_regex)) = (&self.topics_pattern, topics_regex) {
+            builder = builder
+                .with_topic_regex(topics_regex)
+                .with_lookup_namespace(&topics_pattern.namespace)
+                .with_topic_refresh(Duration::from_secs(topics_pattern.refresh_interval_secs));
+        }
+        if let Some(consumer_name) = &self.consumer_name {
+            builder = builder.with_consumer_name(consumer_name);
+        }
+        if let Some(batch_size) = self.batch_size {
+            builder = builder.with_batch_size(batch_size);
+        }
+        if let Some(policy) = &self.dead_letter_queue_policy {
+            builder = builder.with_dead_letter_policy(DeadLetterPolicy {
+                max_redeliver_count: policy.max_redeliver_count,
+                dead_letter_topic: policy.dead_letter_topic.clone(),
+            });
+        }
+
+        builder.build().await.context(CreateConsumerSnafu)
+    }
+}
+
+#[derive(Debug)]
+struct FinalizerEntry {
+    topic: String,
+    message_id: MessageIdData,
+}
+
+impl<T> From<&Message<T>> for FinalizerEntry {
+    fn from(message: &Message<T>) -> Self {
+        Self {
+            topic: message.topic.clone(),
+            message_id: message.message_id().clone(),
+        }
+    }
+}
+
+async fn pulsar_source(
+    keys: Keys,
+    mut consumer: Consumer<String, TokioExecutor>,
+    decoder: Decoder,
+    mut shutdown: ShutdownSignal,
+    mut out: SourceSender,
+    acknowledgements: bool,
+    log_namespace: LogNamespace,
+) -> Result<(), ()> {
+    let (finalizer, mut ack_stream) =
+        UnorderedFinalizer::<FinalizerEntry>::maybe_new(acknowledgements, Some(shutdown.clone()));
+    let bytes_received = register!(BytesReceived::from(Protocol::TCP));
+    let events_received = register!(EventsReceived);
+
+    loop {
+        tokio::select! {
+            _ = &mut shutdown => break,
+            entry = ack_stream.next() => {
+                if let Some((status, entry)) = entry {
+                    handle_ack(&mut consumer, status, entry).await;
+                }
+            },
+            message = consumer.next() => match message {
+                None => break,
+                Some(Err(error)) => emit!(PulsarReadError { error }),
+                Some(Ok(message)) => {
+                    bytes_received.emit(ByteSize(message.payload.data.len()));
+
+                    // All the events decoded from a message share a batch notifier, so that the
+                    // message is acknowledged once all of them are delivered.
+                    let (batch, receiver) = finalizer
+                        .as_ref()
+                        .map(|_| BatchNotifier::new_with_receiver())
+                        .unzip();
+                    send_events(
+                        &message,
+                        &keys,
+                        &decoder,
+                        batch,
+                        &events_received,
+                        &mut out,
+                        log_namespace,
+                    )
+                    .await?;
+
+                    match (&finalizer, receiver) {
+                        (Some(finalizer), Some(receiver)) => {
+                            finalizer.add(FinalizerEntry::from(&message), receiver);
+                        }
+                        _ => {
+                            if let Err(error) = consumer.ack(&message).await {
+                                emit!(PulsarAcknowledgmentError {
+                                    error,
+                                    negative: false,
+                                });
+                            }
+                        }
+                    }
+                }
+            },
+        }
+    }
+
+    Ok(())
+}
+
+/// Decodes the payload of a message, and sends the resulting events.
+async fn send_events(
+    message: &Message<String>,
+    keys: &Keys,
+    decoder: &Decoder,
+    batch: Option<BatchNotifier>,
+    events_received: &Registered<EventsReceived>,
+    out: &mut SourceSender,
+    log_namespace: LogNamespace,
+) -> Result<(), ()> {
+    let received = ReceivedMessage::from(message);
+
+    let mut stream = FramedRead::new(message.payload.data.as_slice(), decoder.clone());
+    while let Some(next) = stream.next().await {
+        match next {
+            Ok((events, _byte_size)) => {
+                let count = events.len();
+                events_received.emit(CountByteSize(
+                    count,
+                    events.estimated_json_encoded_size_of(),
+                ));
+
+                let events = events.into_iter().map(|mut event| {
+                    received.apply(keys, &mut event, log_namespace);
+                    match &batch {
+                        Some(batch) => event.with_batch_notifier(batch),
+                        None => event,
+                    }
+                });
+                out.send_batch(events).await.map_err(|_| {
+                    emit!(StreamClosedError { count });
+                })?;
+            }
+            Err(error) => {
+                // Error is logged by `crate::codecs`, no further
+                // handling is needed here.
+                if !error.can_continue() {
+                    break;
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Acknowledges a message once its events are delivered, and negatively acknowledges it otherwise
+/// so that it is redelivered.
+async fn handle_ack(
+    consumer: &mut Consumer<String, TokioExecutor>,
+    status: BatchStatus,
+    entry: FinalizerEntry,
+) {
+    match status {
+        BatchStatus::Delivered => {
+            if let Err(error) = consumer.ack_with_id(&entry.topic, entry.message_id).await {
+                emit!(PulsarAcknowledgmentError {
+                    error,
+                    negative: false,
+                });
+            }
+        }
+        BatchStatus::Errored | BatchStatus::Rejected => {
+            if let Err(error) = consumer.nack_with_id(&entry.topic, entry.message_id).await {
+                emit!(PulsarAcknowledgmentError {
+                    error,
+                    negative: true,
+                });
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Keys {
+    timestamp: Option<OwnedValuePath>,
+    key_field: Option<OwnedValuePath>,
+    topic: Option<OwnedValuePath>,
+    properties: Option<OwnedValuePath>,
+}
+
+impl Keys {
+    fn from(schema: &LogSchema, config: &PulsarSourceConfig) -> Self {
+        Self {
+            timestamp: schema.timestamp_key().cloned(),
+            key_field: config.key_field.path.clone(),
+            topic: config.topic_key.path.clone(),
+            properties: config.properties_key.path.clone(),
+        }
+    }
+}
+
+struct ReceivedMessage {
+    timestamp: Option<DateTime<Utc>>,
+    key: Value,
+    properties: ObjectMap,
+    topic: String,
+}
+
+impl ReceivedMessage {
+    fn from(message: &Message<String>) -> Self {
+        let metadata = &message.payload.metadata;
+
+        let timestamp = Utc
+            .timestamp_millis_opt(metadata.publish_time as i64)
+            .latest();
+
+        let key = metadata
+            .partition_key
+            .clone()
+            .map(Value::from)
+            .unwrap_or(Value::Null);
+
+        let properties = metadata
+            .properties
+            .iter()
+            .map(|property| {
+                (
+                    property.key.clone().into(),
+                    Value::from(Bytes::from(property.value.clone())),
+                )
+            })
+            .collect();
+
+        Self {
+            timestamp,
+            key,
+            properties,
+            topic: message.topic.clone(),
+        }
+    }
+
+    fn apply(&self, keys: &Keys, event: &mut Event, log_namespace: LogNamespace) {
+        if let Event::Log(ref mut log) = event {
+            match log_namespace {
+                LogNamespace::Vector => {
+                    log_namespace.insert_standard_vector_source_metadata(
+                        log,
+                        PulsarSourceConfig::NAME,
+                        Utc::now(),
+                    );
+                }
+                LogNamespace::Legacy => {
+                    // The timestamp is only inserted below in legacy namespaces, as it
+                    // corresponds to the publish time of the message, rather than the time it
+                    // was received.
+                    if let Some(source_type_key) = log_schema().source_type_key_target_path() {
+                        log.insert(source_type_key, PulsarSourceConfig::NAME);
+                    }
+                }
+            }
+
+            log_namespace.insert_source_metadata(
+                PulsarSourceConfig::NAME,
+                log,
+                keys.timestamp.as_ref().map(LegacyKey::Overwrite),
+                path!("timestamp"),
+                self.timestamp,
+            );
+
+            log_namespace.insert_source_metadata(
+                PulsarSourceConfig::NAME,
+                log,
+                keys.topic.as_ref().map(LegacyKey::Overwrite),
+                path!("topic"),
+                self.topic.clone(),
+            );
+
+            log_namespace.insert_source_metadata(
+                PulsarSourceConfig::NAME,
+                log,
+                keys.key_field.as_ref().map(LegacyKey::Overwrite),
+                path!("message_key"),
+                self.key.clone(),
+            );
+
+            log_namespace.insert_source_metadata(
+                PulsarSourceConfig::NAME,
+                log,
+                keys.properties.as_ref().map(LegacyKey::Overwrite),
+                path!("properties"),
+                self.properties.clone(),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use vector_lib::lookup::OwnedTargetPath;
+    use vector_lib::schema::Definition;
+
+    use super::*;
+    use crate::event::LogEvent;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<PulsarSourceConfig>();
+    }
+
+    #[tokio::test]
+    async fn requires_topics() {
+        let config = PulsarSourceConfig::default();
+        assert!(matches!(
+            config.create_consumer().await,
+            Err(BuildError::NoTopics)
+        ));
+
+        let config: PulsarSourceConfig = toml::from_str(
+            r#"
+            endpoint = "pulsar://127.0.0.1:6650"
+            topics_pattern.pattern = "persistent://public/default/(logs"
+            "#,
+        )
+        .unwrap();
+        assert!(matches!(
+            config.create_consumer().await,
+            Err(BuildError::InvalidTopicsPattern { .. })
+        ));
+    }
+
+    fn received_message() -> ReceivedMessage {
+        ReceivedMessage {
+            timestamp: Utc.timestamp_millis_opt(1_700_000_000_000).latest(),
+            key: Value::from("device-1"),
+            properties: ObjectMap::from([("origin".into(), Value::from("edge"))]),
+            topic: "persistent://public/default/logs".to_string(),
+        }
+    }
+
+    #[test]
+    fn applies_metadata_legacy_namespace() {
+        let config = PulsarSourceConfig::default();
+        let keys = Keys::from(log_schema(), &config);
+
+        let mut event = Event::Log(LogEvent::from("hello"));
+        received_message().apply(&keys, &mut event, LogNamespace::Legacy);
+
+        let log = event.as_log();
+        assert_eq!(log["source_type"], "pulsar".into());
+        assert_eq!(
+            log["timestamp"],
+            Utc.timestamp_millis_opt(1_700_000_000_000)
+                .latest()
+                .unwrap()
+                .into()
+        );
+        assert_eq!(log["topic"], "persistent://public/default/logs".into());
+        assert_eq!(log["message_key"], "device-1".into());
+        assert_eq!(log["properties.origin"], "edge".into());
+    }
+
+    #[test]
+    fn applies_metadata_vector_namespace() {
+        let config = PulsarSourceConfig::default();
+        let keys = Keys::from(log_schema(), &config);
+
+        let mut event = Event::Log(LogEvent::from(Value::from("hello")));
+        received_message().apply(&keys, &mut event, LogNamespace::Vector);
+
+        let log = event.as_log();
+        assert_eq!(log.value(), &Value::from("hello"));
+        let metadata = log.metadata().value();
+        assert_eq!(
+            metadata.get(path!("pulsar", "topic")),
+            Some(&Value::from("persistent://public/default/logs"))
+        );
+        assert_eq!(
+            metadata.get(path!("pulsar", "message_key")),
+            Some(&Value::from("device-1"))
+        );
+        assert_eq!(
+            metadata.get(path!("pulsar", "properties", "origin")),
+            Some(&Value::from("edge"))
+        );
+        assert!(metadata
+            .get(path!("vector", "ingest_timestamp"))
+            .unwrap()
+            .is_timestamp());
+    }
+
+    #[test]
+    fn output_schema_definition_vector_namespace() {
+        let config = PulsarSourceConfig {
+            log_namespace: Some(true),
+            ..Default::default()
+        };
+
+        let definition = config
+            .outputs(LogNamespace::Vector)
+            .remove(0)
+            .schema_definition(true);
+
+        let expected_definition =
+            Definition::new_with_default_metadata(Kind::bytes(), [LogNamespace::Vector])
+                .with_meaning(OwnedTargetPath::event_root(), "message")
+                .with_metadata_field(
+                    &owned_value_path!("vector", "source_type"),
+                    Kind::bytes(),
+                    None,
+                )
+                .with_metadata_field(
+                    &owned_value_path!("vector", "ingest_timestamp"),
+                    Kind::timestamp(),
+                    None,
+                )
+                .with_metadata_field(
+                    &owned_value_path!("pulsar", "timestamp"),
+                    Kind::timestamp(),
+                    Some("timestamp"),
+                )
+                .with_metadata_field(&owned_value_path!("pulsar", "topic"), Kind::bytes(), None)
+                .with_metadata_field(
+                    &owned_value_path!("pulsar", "message_key"),
+                    Kind::bytes().or_null(),
+                    None,
+                )
+                .with_metadata_field(
+                    &owned_value_path!("pulsar", "properties"),
+                    Kind::object(Collection::empty().with_unknown(Kind::bytes())),
+                    None,
+                );
+
+        assert_eq!(definition, Some(expected_definition));
+    }
+}
+
+#[cfg(feature = "pulsar-integration-tests")]
+#[cfg(test)]
+mod integration_tests {
+    use std::collections::HashMap;
+
+    use pulsar::producer;
+    use vector_lib::event::EventStatus;
+
+    use super::*;
+    use crate::test_util::{
+        collect_n,
+        components::{assert_source_compliance, SOURCE_TAGS},
+        random_string, trace_init,
+    };
+
+    fn pulsar_address() -> String {
+        std::env::var("PULSAR_ADDRESS").unwrap_or_else(|_| "pulsar://127.0.0.1:6650".into())
+    }
+
+    fn make_config(topic: &str) -> PulsarSourceConfig {
+        PulsarSourceConfig {
+            endpoint: pulsar_address(),
+            topics: vec![topic.to_string()],
+            initial_position: PulsarInitialPosition::Earliest,
+            acknowledgements: true.into(),
+            ..Default::default()
+        }
+    }
+
+    async fn produce(topic: &str, messages: &[&str]) {
+        let pulsar = create_pulsar_client(&pulsar_address(), None).await.unwrap();
+        let mut producer = pulsar.producer().build_multi_topic();
+        for message in messages {
+            producer
+                .send(
+                    topic,
+                    producer::Message {
+                        payload: message.as_bytes().to_vec(),
+                        properties: HashMap::from([("origin".to_string(), "test".to_string())]),
+                        partition_key: Some("key".to_string()),
+                        ..Default::default()
+                    },
+                )
+                .await
+                .unwrap()
+                .await
+                .unwrap();
+        }
+    }
+
+    async fn run_source(
+        config: PulsarSourceConfig,
+        out: SourceSender,
+    ) -> tokio::task::JoinHandle<Result<(), ()>> {
+        let source = config.build(SourceContext::new_test(out, None)).await;
+        tokio::spawn(source.unwrap())
+    }
+
+    #[tokio::test]
+    async fn consumes_messages() {
+        trace_init();
+
+        let topic = format!("test-{}", random_string(10));
+        produce(&topic, &["first", "second"]).await;
+
+        let events = assert_source_compliance(&SOURCE_TAGS, async {
+            let (tx, rx) = SourceSender::new_test_finalize(EventStatus::Delivered);
+            let _source = run_source(make_config(&topic), tx).await;
+            collect_n(rx, 2).await
+        })
+        .await;
+
+        let messages = events
+            .iter()
+            .map(|event| event.as_log()[log_schema().message_key().unwrap().to_string()].clone())
+            .collect::<Vec<_>>();
+        assert_eq!(messages, vec!["first".into(), "second".into()]);
+
+        let log = events[0].as_log();
+        assert_eq!(log["source_type"], "pulsar".into());
+        assert!(log["topic"].to_string_lossy().ends_with(&topic));
+        assert_eq!(log["message_key"], "key".into());
+        assert_eq!(log["properties.origin"], "test".into());
+    }
+
+    #[tokio::test]
+    async fn consumes_topics_pattern() {
+        trace_init();
+
+        let prefix = format!("test-{}", random_string(10));
+        let topic = format!("persistent://public/default/{prefix}-logs");
+        produce(&topic, &["matched"]).await;
+
+        let config = PulsarSourceConfig {
+            topics: Vec::new(),
+            topics_pattern: Some(PulsarTopicsPattern {
+                pattern: format!("persistent://public/default/{prefix}-.*"),
+                namespace: default_namespace(),
+                refresh_interval_secs: 1,
+            }),
+            ..make_config(&topic)
+        };
+
+        let (tx, rx) = SourceSender::new_test_finalize(EventStatus::Delivered);
+        let _source = run_source(config, tx).await;
+        let events = collect_n(rx, 1).await;
+        assert_eq!(events[0].as_log()["topic"], topic.into());
+    }
+
+    #[tokio::test]
+    async fn redelivers_negatively_acknowledged_messages() {
+        trace_init();
+
+        let topic = format!("test-{}", random_string(10));
+        produce(&topic, &["redelivered"]).await;
+
+        // The first delivery fails, so the message is negatively acknowledged and redelivered.
+        let (tx, rx) = SourceSender::new_test_errors(|index| index == 0);
+        let _source = run_source(make_config(&topic), tx).await;
+        let events = collect_n(rx, 2).await;
+
+        for event in events {
+            assert_eq!(
+                event.as_log()[log_schema().message_key().unwrap().to_string()],
+                "redelivered".into()
+            );
+        }
+    }
+}
@@ -6,12 +6,11 @@ use crate::{
 use futures::FutureExt;
 use http::{Request, Response};
 use hyper::Body;
-use std::{convert::Infallible, net::SocketAddr, time::Duration};
+use std::{net::SocketAddr, time::Duration};
 use tonic::{
     body::BoxBody,
-    transport::server::{NamedService, Server},
+    transport::server::{Routes, Server},
 };
-use tower::Service;
 use tower_http::{
     classify::{GrpcErrorsAsFailures, SharedClassifier},
     trace::TraceLayer,
@@ -21,20 +20,15 @@ use tracing::Span;
 mod decompression;
 pub use self::decompression::{DecompressionAndMetrics, DecompressionAndMetricsLayer};
 
-pub async fn run_grpc_server<S>(
+/// Runs a gRPC server serving all of the services in the given [`Routes`].
+///
+/// Sources exposing a single service can wrap it with [`Routes::new`].
+pub async fn run_grpc_server(
     address: SocketAddr,
     tls_settings: MaybeTlsSettings,
-    service: S,
+    routes: Routes,
     shutdown: ShutdownSignal,
-) -> crate::Result<()>
-where
-    S: Service<Request<Body>, Response = Response<BoxBody>, Error = Infallible>
-        + NamedService
-        + Clone
-        + Send
-        + 'static,
-    S::Future: Send + 'static,
-{
+) -> crate::Result<()> {
     let span = Span::current();
     let (tx, rx) = tokio::sync::oneshot::channel::<ShutdownSignalToken>();
     let listener = tls_settings.bind(&address).await?;
@@ -54,7 +48,7 @@ where
         // modified or wrapped.. so instead of a cleaner design, we're opting here to bake it all together until the
         // crates are sufficiently flexible for us to craft a better design.
         .layer(DecompressionAndMetricsLayer)
-        .add_service(service)
+        .add_routes(routes)
         .serve_with_incoming_shutdown(stream, shutdown.map(|token| tx.send(token).unwrap()))
         .await?;
 
@@ -9,7 +9,7 @@
 //!     context.
 
 use bytes::Bytes;
-use futures_util::{stream, FutureExt, StreamExt, TryFutureExt};
+use futures_util::{stream, FutureExt, StreamExt};
 use http::{response::Parts, Uri};
 use hyper::{Body, Request};
 use std::time::Duration;
@@ -119,6 +119,74 @@ pub(crate) fn warn_if_interval_too_low(timeout: Duration, interval: Duration) {
     }
 }
 
+/// Builds an HTTP request for the given URL.
+///
+/// The `Accept` header is set to `content_type`, unless it is part of `headers`.
+pub(crate) fn build_request(
+    http_method: HttpMethod,
+    url: &Uri,
+    headers: &HashMap<String, Vec<String>>,
+    content_type: &str,
+    auth: Option<&Auth>,
+) -> Request<Body> {
+    let mut builder = match http_method {
+        HttpMethod::Head => Request::head(url),
+        HttpMethod::Get => Request::get(url),
+        HttpMethod::Post => Request::post(url),
+        HttpMethod::Put => Request::put(url),
+        HttpMethod::Patch => Request::patch(url),
+        HttpMethod::Delete => Request::delete(url),
+    };
+
+    // add user specified headers
+    for (header, values) in headers {
+        for value in values {
+            builder = builder.header(header, value);
+        }
+    }
+
+    // set ACCEPT header if not user specified
+    if !headers.contains_key(http::header::ACCEPT.as_str()) {
+        builder = builder.header(http::header::ACCEPT, content_type);
+    }
+
+    // building an empty request should be infallible
+    let mut request = builder.body(Body::empty()).expect("error creating request");
+
+    if let Some(auth) = auth {
+        auth.apply(&mut request);
+    }
+
+    request
+}
+
+/// Sends an HTTP request, returning the response headers and the full response body.
+pub(crate) async fn send_request(
+    client: &HttpClient,
+    request: Request<Body>,
+    timeout: Duration,
+) -> crate::Result<(Parts, Bytes)> {
+    let endpoint = request.uri().to_string();
+    let response = match tokio::time::timeout(timeout, client.send(request)).await {
+        Ok(Ok(response)) => response,
+        Ok(Err(error)) => return Err(error.into()),
+        Err(_) => {
+            return Err(
+                format!("Timeout error: request exceeded {}s", timeout.as_secs_f64()).into(),
+            )
+        }
+    };
+
+    let (header, body) = response.into_parts();
+    let body = hyper::body::to_bytes(body).await?;
+    emit!(EndpointBytesReceived {
+        byte_size: body.len(),
+        protocol: "http",
+        endpoint: endpoint.as_str(),
+    });
+    Ok((header, body))
+}
+
 /// Calls one or more urls at an interval.
 ///   - The HTTP request is built per the options in provided generic inputs.
 ///   - The HTTP response is decoded/parsed into events by the specific context.
@@ -142,61 +210,20 @@ pub(crate) async fn call<
         .flatten()
         .map(move |url| {
             let client = client.clone();
-            let endpoint = url.to_string();
 
             let context_builder = context_builder.clone();
             let mut context = context_builder.build(&url);
 
-            let mut builder = match http_method {
-                HttpMethod::Head => Request::head(&url),
-                HttpMethod::Get => Request::get(&url),
-                HttpMethod::Post => Request::post(&url),
-                HttpMethod::Put => Request::put(&url),
-                HttpMethod::Patch => Request::patch(&url),
-                HttpMethod::Delete => Request::delete(&url),
-            };
+            let request = build_request(
+                http_method,
+                &url,
+                &inputs.headers,
+                &inputs.content_type,
+                inputs.auth.as_ref(),
+            );
+            let timeout = inputs.timeout;
 
-            // add user specified headers
-            for (header, values) in &inputs.headers {
-                for value in values {
-                    builder = builder.header(header, value);
-                }
-            }
-
-            // set ACCEPT header if not user specified
-            if !inputs.headers.contains_key(http::header::ACCEPT.as_str()) {
-                builder = builder.header(http::header::ACCEPT, &inputs.content_type);
-            }
-
-            // building an empty request should be infallible
-            let mut request = builder.body(Body::empty()).expect("error creating request");
-
-            if let Some(auth) = &inputs.auth {
-                auth.apply(&mut request);
-            }
-
-            tokio::time::timeout(inputs.timeout, client.send(request))
-                .then(move |result| async move {
-                    match result {
-                        Ok(Ok(response)) => Ok(response),
-                        Ok(Err(error)) => Err(error.into()),
-                        Err(_) => Err(format!(
-                            "Timeout error: request exceeded {}s",
-                            inputs.timeout.as_secs_f64()
-                        )
-                        .into()),
-                    }
-                })
-                .and_then(|response| async move {
-                    let (header, body) = response.into_parts();
-                    let body = hyper::body::to_bytes(body).await?;
-                    emit!(EndpointBytesReceived {
-                        byte_size: body.len(),
-                        protocol: "http",
-                        endpoint: endpoint.as_str(),
-                    });
-                    Ok((header, body))
-                })
+            async move { send_request(&client, request, timeout).await }
                 .into_stream()
                 .filter_map(move |response| {
                     ready(match response {
@@ -0,0 +1,638 @@
+//! Ingestion of the objects found by periodically listing a prefix of a bucket or container.
+//!
+//! The objects are ingested in the order of their modification time, which is checkpointed after
+//! each object so that the source resumes where it left off after a restart. An object that appears
+//! after newer objects have been ingested, with a modification time before theirs, is therefore
+//! never ingested.
+
+use std::{
+    collections::{BTreeSet, HashMap},
+    io::{self, ErrorKind},
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use futures::{stream::BoxStream, FutureExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use smallvec::SmallVec;
+use snafu::{ResultExt, Snafu};
+use tokio_stream::wrappers::IntervalStream;
+use vector_lib::codecs::decoding::BoxedFramingError;
+use vector_lib::config::{log_schema, LegacyKey, LogNamespace};
+use vector_lib::event::MaybeAsLogMut;
+use vector_lib::internal_event::{
+    BytesReceived, CountByteSize, EventsReceived, InternalEventHandle as _, Protocol, Registered,
+};
+use vector_lib::lookup::{metadata_path, owned_value_path, path, PathPrefix};
+use vector_lib::schema::Definition;
+use vector_lib::EstimatedJsonEncodedSizeOf;
+use vrl::value::{kind::Collection, Kind};
+
+use super::{object_lines, object_reader, Compression};
+use crate::{
+    codecs::Decoder,
+    event::{BatchNotifier, BatchStatus, Event, LogEvent},
+    internal_events::{
+        ObjectStorageCheckpointLoadError, ObjectStorageCheckpointPersistError,
+        ObjectStorageIngestError, ObjectStorageListError, ObjectStorageObjectSkipped,
+        StreamClosedError,
+    },
+    line_agg,
+    shutdown::ShutdownSignal,
+    state_file, SourceSender,
+};
+
+/// The file name of the checkpoint, in the data directory of the source.
+pub const CHECKPOINT_FILE_NAME: &str = "checkpoint.json";
+
+/// The number of times an object that can't be read is ingested before it is skipped.
+const MAX_INGEST_ATTEMPTS: usize = 3;
+
+/// An object found by listing a prefix.
+#[derive(Clone, Debug)]
+pub struct ObjectInfo {
+    /// The key of the object.
+    pub key: String,
+
+    /// The time the object was last modified at.
+    pub last_modified: DateTime<Utc>,
+
+    /// The `Content-Encoding` metadata of the object.
+    pub content_encoding: Option<String>,
+
+    /// The `Content-Type` metadata of the object.
+    pub content_type: Option<String>,
+
+    /// The user-defined metadata of the object.
+    pub metadata: HashMap<String, String>,
+}
+
+/// A client of the bucket or container of an object storage service.
+#[async_trait::async_trait]
+pub trait ObjectStorageClient: Send + Sync {
+    /// Lists all the objects whose key starts with `prefix`.
+    async fn list_objects(&self, prefix: &str) -> crate::Result<Vec<ObjectInfo>>;
+
+    /// Reads the content of an object, as stored.
+    async fn get_object(
+        &self,
+        object: &ObjectInfo,
+    ) -> crate::Result<BoxStream<'static, io::Result<Bytes>>>;
+}
+
+/// The most recently modified objects that have been ingested.
+///
+/// As objects are ingested in the order of their modification time, all of the objects modified
+/// before `last_modified` have been ingested, and only the keys of the objects modified at that
+/// time need to be kept.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct Checkpoint {
+    /// The modification time of the last ingested object.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    last_modified: Option<DateTime<Utc>>,
+
+    /// The keys of the ingested objects modified at `last_modified`.
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    keys: BTreeSet<String>,
+}
+
+impl Checkpoint {
+    fn contains(&self, object: &ObjectInfo) -> bool {
+        match self.last_modified {
+            Some(last_modified) => {
+                object.last_modified < last_modified
+                    || (object.last_modified == last_modified && self.keys.contains(&object.key))
+            }
+            None => false,
+        }
+    }
+
+    fn add(&mut self, object: &ObjectInfo) {
+        if self.last_modified != Some(object.last_modified) {
+            self.last_modified = Some(object.last_modified);
+            self.keys.clear();
+        }
+        self.keys.insert(object.key.clone());
+    }
+
+    fn read(path: &Path) -> io::Result<Option<Self>> {
+        state_file::read(path)?
+            .map(|data| serde_json::from_slice(&data))
+            .transpose()
+            .map_err(|error| io::Error::new(ErrorKind::InvalidData, error))
+    }
+
+    fn write(&self, path: &Path) -> io::Result<()> {
+        let data = serde_json::to_vec(self)?;
+        state_file::write_atomic(path, &data)
+    }
+}
+
+/// Adds the metadata inserted by [`ListingSource`] to the schema definition of the decoded events.
+pub fn schema_definition(
+    definition: Definition,
+    source_name: &'static str,
+    container_key: &'static str,
+    log_namespace: LogNamespace,
+) -> Definition {
+    let mut definition = definition
+        .with_source_metadata(
+            source_name,
+            Some(LegacyKey::Overwrite(owned_value_path!(container_key))),
+            &owned_value_path!(container_key),
+            Kind::bytes(),
+            None,
+        )
+        .with_source_metadata(
+            source_name,
+            Some(LegacyKey::Overwrite(owned_value_path!("object"))),
+            &owned_value_path!("object"),
+            Kind::bytes(),
+            None,
+        )
+        .with_source_metadata(
+            source_name,
+            None,
+            &owned_value_path!("timestamp"),
+            Kind::timestamp(),
+            Some("timestamp"),
+        )
+        .with_standard_vector_source_metadata()
+        // for metadata that is added to the events dynamically from the metadata
+        .with_source_metadata(
+            source_name,
+            None,
+            &owned_value_path!("metadata"),
+            Kind::object(Collection::empty().with_unknown(Kind::bytes())).or_undefined(),
+            None,
+        );
+
+    // for metadata that is added to the events dynamically from the metadata
+    if log_namespace == LogNamespace::Legacy {
+        definition = definition.unknown_fields(Kind::bytes());
+    }
+
+    definition
+}
+
+#[derive(Debug, Snafu)]
+pub enum IngestError {
+    #[snafu(display("Failed to get object: {}", source))]
+    GetObject { source: crate::Error },
+    #[snafu(display("Failed to read all of the object: {}", source))]
+    ReadObject { source: BoxedFramingError },
+    #[snafu(display("Failed to send events to the pipeline"))]
+    PipelineSend,
+    #[snafu(display("Events were not delivered"))]
+    ErrorAcknowledgement,
+}
+
+/// A source ingesting the objects found by listing a prefix with an [`ObjectStorageClient`].
+pub struct ListingSource<C> {
+    pub client: C,
+    /// The name of the source, used as the metadata namespace.
+    pub source_name: &'static str,
+    /// The name of the field holding the name of the bucket or container.
+    pub container_key: &'static str,
+    pub container: String,
+    pub prefix: String,
+    pub poll_interval: Duration,
+    pub compression: Compression,
+    pub multiline: Option<line_agg::Config>,
+    pub decoder: Decoder,
+    pub checkpoint_file: PathBuf,
+    pub acknowledgements: bool,
+    pub log_namespace: LogNamespace,
+}
+
+impl<C: ObjectStorageClient> ListingSource<C> {
+    pub async fn run(self, mut out: SourceSender, shutdown: ShutdownSignal) -> Result<(), ()> {
+        let bytes_received = register!(BytesReceived::from(Protocol::HTTP));
+        let events_received = register!(EventsReceived);
+        let mut checkpoint = self.load_checkpoint();
+        let mut attempts = HashMap::<String, usize>::new();
+
+        let mut ticks = IntervalStream::new(tokio::time::interval(self.poll_interval))
+            .take_until(shutdown.clone());
+        while ticks.next().await.is_some() {
+            let mut objects = match self.client.list_objects(&self.prefix).await {
+                Ok(objects) => objects,
+                Err(error) => {
+                    emit!(ObjectStorageListError {
+                        error,
+                        container: &self.container,
+                        prefix: &self.prefix,
+                    });
+                    continue;
+                }
+            };
+            objects.retain(|object| !checkpoint.contains(object));
+            objects.sort_by(|a, b| {
+                a.last_modified
+                    .cmp(&b.last_modified)
+                    .then_with(|| a.key.cmp(&b.key))
+            });
+
+            for object in objects {
+                // The remaining objects are ingested after a restart.
+                if shutdown.clone().now_or_never().is_some() {
+                    break;
+                }
+
+                match self
+                    .ingest_object(&object, &mut out, &bytes_received, &events_received)
+                    .await
+                {
+                    Ok(()) => {
+                        attempts.remove(&object.key);
+                        checkpoint.add(&object);
+                        self.persist_checkpoint(&checkpoint);
+                    }
+                    Err(IngestError::PipelineSend) => return Err(()),
+                    Err(error) => {
+                        // Objects that can't be read are skipped after a few attempts, so that
+                        // they don't block the newer objects. Events that weren't delivered
+                        // don't count, as the object itself isn't at fault.
+                        let skip = !matches!(error, IngestError::ErrorAcknowledgement) && {
+                            let count = attempts.entry(object.key.clone()).or_default();
+                            *count += 1;
+                            *count >= MAX_INGEST_ATTEMPTS
+                        };
+                        emit!(ObjectStorageIngestError {
+                            error,
+                            container: &self.container,
+                            key: &object.key,
+                        });
+
+                        if skip {
+                            emit!(ObjectStorageObjectSkipped {
+                                container: &self.container,
+                                key: &object.key,
+                                attempts: MAX_INGEST_ATTEMPTS,
+                            });
+                            attempts.remove(&object.key);
+                            checkpoint.add(&object);
+                            self.persist_checkpoint(&checkpoint);
+                        } else {
+                            // Newer objects are not ingested before this one is, as they would
+                            // move the checkpoint past it.
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn ingest_object(
+        &self,
+        object: &ObjectInfo,
+        out: &mut SourceSender,
+        bytes_received: &Registered<BytesReceived>,
+        events_received: &Registered<EventsReceived>,
+    ) -> Result<(), IngestError> {
+        let body = self
+            .client
+            .get_object(object)
+            .await
+            .context(GetObjectSnafu)?;
+        let reader = object_reader(
+            self.compression,
+            &object.key,
+            object.content_encoding.as_deref(),
+            object.content_type.as_deref(),
+            body,
+        )
+        .await;
+
+        let (batch, receiver) = BatchNotifier::maybe_new_with_receiver(self.acknowledgements);
+
+        // This can result in objects being partially processed before an error, in which case
+        // the object is ingested again from the start, as we prefer duplicate lines over
+        // message loss.
+        let mut read_error = None;
+        let lines = object_lines(
+            reader,
+            self.decoder.framer.clone(),
+            self.multiline.as_ref(),
+            bytes_received.clone(),
+            &mut read_error,
+        );
+
+        let mut stream = lines.flat_map(|line| {
+            let events = match self.decoder.deserializer_parse(line) {
+                Ok((events, _events_size)) => events,
+                Err(_error) => {
+                    // Error is handled by `codecs::Decoder`, no further handling
+                    // is needed here.
+                    SmallVec::new()
+                }
+            };
+
+            let events = events
+                .into_iter()
+                .map(|mut event: Event| {
+                    event = event.with_batch_notifier_option(&batch);
+                    if let Some(log) = event.maybe_as_log_mut() {
+                        self.enrich_log(log, object);
+                    }
+                    events_received.emit(CountByteSize(1, event.estimated_json_encoded_size_of()));
+                    event
+                })
+                .collect::<Vec<Event>>();
+            futures::stream::iter(events)
+        });
+
+        let send_result = out.send_event_stream(&mut stream).await;
+        if send_result.is_err() {
+            let (count, _) = stream.size_hint();
+            emit!(StreamClosedError { count });
+        }
+
+        // `stream` captures `read_error`, so it is dropped before using `read_error` below.
+        drop(stream);
+
+        // The BatchNotifier is cloned for each LogEvent in the batch stream, but the last
+        // reference must be dropped before the status of the batch is sent to the channel.
+        drop(batch);
+
+        if send_result.is_err() {
+            return Err(IngestError::PipelineSend);
+        }
+        if let Some(error) = read_error {
+            return Err(IngestError::ReadObject { source: error });
+        }
+
+        match receiver {
+            None => Ok(()),
+            Some(receiver) => match receiver.await {
+                // Rejected events would be rejected again, so the object isn't ingested again.
+                BatchStatus::Delivered | BatchStatus::Rejected => Ok(()),
+                BatchStatus::Errored => Err(IngestError::ErrorAcknowledgement),
+            },
+        }
+    }
+
+    fn enrich_log(&self, log: &mut LogEvent, object: &ObjectInfo) {
+        self.log_namespace.insert_source_metadata(
+            self.source_name,
+            log,
+            Some(LegacyKey::Overwrite(path!(self.container_key))),
+            path!(self.container_key),
+            self.container.clone(),
+        );
+        self.log_namespace.insert_source_metadata(
+            self.source_name,
+            log,
+            Some(LegacyKey::Overwrite(path!("object"))),
+            path!("object"),
+            object.key.clone(),
+        );
+
+        for (key, value) in &object.metadata {
+            self.log_namespace.insert_source_metadata(
+                self.source_name,
+                log,
+                Some(LegacyKey::Overwrite(path!(key))),
+                path!("metadata", key.as_str()),
+                value.clone(),
+            );
+        }
+
+        self.log_namespace.insert_vector_metadata(
+            log,
+            log_schema().source_type_key(),
+            path!("source_type"),
+            Bytes::from_static(self.source_name.as_bytes()),
+        );
+
+        match self.log_namespace {
+            LogNamespace::Vector => {
+                log.insert(
+                    metadata_path!(self.source_name, "timestamp"),
+                    object.last_modified,
+                );
+                log.insert(metadata_path!("vector", "ingest_timestamp"), Utc::now());
+            }
+            LogNamespace::Legacy => {
+                if let Some(timestamp_key) = log_schema().timestamp_key() {
+                    log.try_insert((PathPrefix::Event, timestamp_key), object.last_modified);
+                }
+            }
+        }
+    }
+
+    fn load_checkpoint(&self) -> Checkpoint {
+        match Checkpoint::read(&self.checkpoint_file) {
+            Ok(checkpoint) => checkpoint.unwrap_or_default(),
+            Err(error) => {
+                emit!(ObjectStorageCheckpointLoadError {
+                    error,
+                    path: &self.checkpoint_file,
+                });
+                Checkpoint::default()
+            }
+        }
+    }
+
+    fn persist_checkpoint(&self, checkpoint: &Checkpoint) {
+        if let Err(error) = checkpoint.write(&self.checkpoint_file) {
+            emit!(ObjectStorageCheckpointPersistError {
+                error,
+                path: &self.checkpoint_file,
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use chrono::TimeZone;
+    use futures::stream;
+
+    use super::*;
+    use crate::{
+        event::EventStatus,
+        test_util::{collect_ready, temp_dir},
+    };
+
+    #[derive(Default)]
+    struct MockClient {
+        objects: Mutex<HashMap<String, (ObjectInfo, Option<&'static str>)>>,
+    }
+
+    impl MockClient {
+        fn put(&self, key: &str, last_modified: i64, content: &'static str) {
+            self.put_content(key, last_modified, Some(content));
+        }
+
+        /// Adds an object that fails to be read.
+        fn put_unreadable(&self, key: &str, last_modified: i64) {
+            self.put_content(key, last_modified, None);
+        }
+
+        fn put_content(&self, key: &str, last_modified: i64, content: Option<&'static str>) {
+            let object = ObjectInfo {
+                key: key.into(),
+                last_modified: Utc.timestamp_opt(last_modified, 0).unwrap(),
+                content_encoding: None,
+                content_type: None,
+                metadata: HashMap::from([("origin".into(), "test".into())]),
+            };
+            self.objects
+                .lock()
+                .unwrap()
+                .insert(key.into(), (object, content));
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl ObjectStorageClient for &MockClient {
+        async fn list_objects(&self, prefix: &str) -> crate::Result<Vec<ObjectInfo>> {
+            Ok(self
+                .objects
+                .lock()
+                .unwrap()
+                .values()
+                .filter(|(object, _)| object.key.starts_with(prefix))
+                .map(|(object, _)| object.clone())
+                .collect())
+        }
+
+        async fn get_object(
+            &self,
+            object: &ObjectInfo,
+        ) -> crate::Result<BoxStream<'static, io::Result<Bytes>>> {
+            let content = self.objects.lock().unwrap()[&object.key]
+                .1
+                .ok_or("object is unreadable")?;
+            Ok(stream::iter([Ok(Bytes::from_static(content.as_bytes()))]).boxed())
+        }
+    }
+
+    fn source<'a>(
+        client: &'a MockClient,
+        checkpoint_file: PathBuf,
+    ) -> ListingSource<&'a MockClient> {
+        ListingSource {
+            client,
+            source_name: "test",
+            container_key: "bucket",
+            container: "logs".into(),
+            prefix: "app/".into(),
+            poll_interval: Duration::from_secs(3600),
+            compression: Compression::Auto,
+            multiline: None,
+            decoder: Decoder::default(),
+            checkpoint_file,
+            acknowledgements: true,
+            log_namespace: LogNamespace::Legacy,
+        }
+    }
+
+    /// Runs the source for a single listing, returning the messages of the ingested events.
+    async fn ingest(
+        client: &MockClient,
+        checkpoint_file: PathBuf,
+        status: EventStatus,
+    ) -> Vec<String> {
+        ingest_with(source(client, checkpoint_file), status).await
+    }
+
+    async fn ingest_with(source: ListingSource<&MockClient>, status: EventStatus) -> Vec<String> {
+        let (tx, rx) = SourceSender::new_test_finalize(status);
+        let run = source.run(tx, ShutdownSignal::noop());
+        // The source waits for the next listing after the first one.
+        tokio::time::timeout(Duration::from_millis(200), run)
+            .await
+            .expect_err("source stopped");
+
+        collect_ready(rx)
+            .await
+            .into_iter()
+            .map(|event| event.as_log()["message"].to_string_lossy().into_owned())
+            .collect()
+    }
+
+    #[test]
+    fn checkpoint_contains_objects_modified_before() {
+        let object = |key: &str, last_modified| ObjectInfo {
+            key: key.into(),
+            last_modified: Utc.timestamp_opt(last_modified, 0).unwrap(),
+            content_encoding: None,
+            content_type: None,
+            metadata: HashMap::new(),
+        };
+
+        let mut checkpoint = Checkpoint::default();
+        assert!(!checkpoint.contains(&object("a", 1)));
+
+        checkpoint.add(&object("a", 1));
+        checkpoint.add(&object("b", 2));
+        assert!(checkpoint.contains(&object("a", 1)));
+        assert!(checkpoint.contains(&object("b", 2)));
+        assert!(!checkpoint.contains(&object("c", 2)));
+        assert!(!checkpoint.contains(&object("b", 3)));
+        assert_eq!(checkpoint.keys, BTreeSet::from(["b".to_string()]));
+    }
+
+    #[tokio::test]
+    async fn ingests_new_objects_once() {
+        let dir = temp_dir();
+        std::fs::create_dir_all(&dir).unwrap();
+        let checkpoint_file = dir.join(CHECKPOINT_FILE_NAME);
+        let client = MockClient::default();
+        client.put("app/2", 2, "three\nfour\n");
+        client.put("app/1", 1, "one\ntwo\n");
+        client.put("other/1", 1, "ignored\n");
+
+        let messages = ingest(&client, checkpoint_file.clone(), EventStatus::Delivered).await;
+        assert_eq!(messages, vec!["one", "two", "three", "four"]);
+
+        client.put("app/3", 2, "five\n");
+        let messages = ingest(&client, checkpoint_file, EventStatus::Delivered).await;
+        assert_eq!(messages, vec!["five"]);
+    }
+
+    #[tokio::test]
+    async fn ingests_errored_objects_again() {
+        let dir = temp_dir();
+        std::fs::create_dir_all(&dir).unwrap();
+        let checkpoint_file = dir.join(CHECKPOINT_FILE_NAME);
+        let client = MockClient::default();
+        client.put("app/1", 1, "one\n");
+        client.put("app/2", 2, "two\n");
+
+        let messages = ingest(&client, checkpoint_file.clone(), EventStatus::Errored).await;
+        assert_eq!(messages, vec!["one"]);
+
+        let messages = ingest(&client, checkpoint_file, EventStatus::Delivered).await;
+        assert_eq!(messages, vec!["one", "two"]);
+    }
+
+    #[tokio::test]
+    async fn skips_unreadable_objects() {
+        let dir = temp_dir();
+        std::fs::create_dir_all(&dir).unwrap();
+        let checkpoint_file = dir.join(CHECKPOINT_FILE_NAME);
+        let client = MockClient::default();
+        client.put_unreadable("app/1", 1);
+        client.put("app/2", 2, "two\n");
+
+        // The unreadable object is skipped after it fails on a few listings in a row.
+        let mut source = source(&client, checkpoint_file.clone());
+        source.poll_interval = Duration::from_millis(10);
+        let messages = ingest_with(source, EventStatus::Delivered).await;
+        assert_eq!(messages, vec!["two"]);
+
+        // It is checkpointed, like the objects that were ingested.
+        let messages = ingest(&client, checkpoint_file, EventStatus::Delivered).await;
+        assert!(messages.is_empty());
+    }
+}
@@ -0,0 +1,265 @@
+//! Decoding of the objects read by the sources ingesting from object storage services.
+
+use std::{future::ready, io};
+
+use async_compression::tokio::bufread;
+use bytes::Bytes;
+use futures::{stream, Stream, StreamExt};
+use tokio::io::AsyncRead;
+use tokio_util::{codec::FramedRead, io::StreamReader};
+use vector_lib::codecs::decoding::{BoxedFramingError, Framer};
+use vector_lib::configurable::configurable_component;
+use vector_lib::internal_event::{ByteSize, BytesReceived, InternalEventHandle as _, Registered};
+
+use crate::line_agg::{self, LineAgg};
+
+#[cfg(any(feature = "sources-azure_blob", feature = "sources-gcp_cloud_storage"))]
+pub mod listing;
+
+/// Compression scheme for objects retrieved from object storage.
+#[configurable_component]
+#[configurable(metadata(docs::advanced))]
+#[derive(Clone, Copy, Debug, Derivative, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+#[derivative(Default)]
+pub enum Compression {
+    /// Automatically attempt to determine the compression scheme.
+    ///
+    /// The compression scheme of the object is determined from its `Content-Encoding` and
+    /// `Content-Type` metadata, as well as the key suffix (for example, `.gz`).
+    ///
+    /// It is set to `none` if the compression scheme cannot be determined.
+    #[derivative(Default)]
+    Auto,
+
+    /// Uncompressed.
+    None,
+
+    /// GZIP.
+    Gzip,
+
+    /// ZSTD.
+    Zstd,
+}
+
+/// Returns a reader of the decompressed content of an object.
+///
+/// An empty reader is returned if the body is empty.
+pub async fn object_reader<S>(
+    compression: Compression,
+    key: &str,
+    content_encoding: Option<&str>,
+    content_type: Option<&str>,
+    mut body: S,
+) -> Box<dyn AsyncRead + Send + Unpin>
+where
+    S: Stream<Item = io::Result<Bytes>> + Send + Unpin + 'static,
+{
+    let first = if let Some(first) = body.next().await {
+        first
+    } else {
+        return Box::new(tokio::io::empty());
+    };
+
+    let r = tokio::io::BufReader::new(StreamReader::new(stream::iter(Some(first)).chain(body)));
+
+    let compression = match compression {
+        Auto => determine_compression(content_encoding, content_type, key).unwrap_or(None),
+        _ => compression,
+    };
+
+    use Compression::*;
+    match compression {
+        Auto => unreachable!(), // is mapped above
+        None => Box::new(r),
+        Gzip => Box::new({
+            let mut decoder = bufread::GzipDecoder::new(r);
+            decoder.multiple_members(true);
+            decoder
+        }),
+        Zstd => Box::new({
+            let mut decoder = bufread::ZstdDecoder::new(r);
+            decoder.multiple_members(true);
+            decoder
+        }),
+    }
+}
+
+/// Splits the content of an object into lines with `framer`, aggregating them when `multiline`
+/// is set.
+///
+/// The stream ends at the first read error, which is recorded in `read_error`.
+pub fn object_lines<'a>(
+    reader: Box<dyn AsyncRead + Send + Unpin>,
+    framer: Framer,
+    multiline: Option<&line_agg::Config>,
+    bytes_received: Registered<BytesReceived>,
+    read_error: &'a mut Option<BoxedFramingError>,
+) -> Box<dyn Stream<Item = Bytes> + Send + Unpin + 'a> {
+    // FramedRead likely stops when it gets an i/o error but I found it more clear to
+    // show that we `take_while` there hasn't been an error
+    let lines: Box<dyn Stream<Item = Bytes> + Send + Unpin + 'a> = Box::new(
+        FramedRead::new(reader, framer)
+            .map(move |res| {
+                res.map(|bytes| {
+                    bytes_received.emit(ByteSize(bytes.len()));
+                    bytes
+                })
+                .map_err(|err| {
+                    *read_error = Some(err);
+                })
+                .ok()
+            })
+            .take_while(|res| ready(res.is_some()))
+            .map(|r| r.expect("validated by take_while")),
+    );
+
+    match multiline {
+        Some(config) => Box::new(
+            LineAgg::new(
+                lines.map(|line| ((), line, ())),
+                line_agg::Logic::new(config.clone()),
+            )
+            .map(|(_src, line, _context, _lastline_context)| line),
+        ),
+        None => lines,
+    }
+}
+
+// try to determine the compression given the:
+// * content-encoding
+// * content-type
+// * key name (for file extension)
+//
+// It will use this information in this order
+fn determine_compression(
+    content_encoding: Option<&str>,
+    content_type: Option<&str>,
+    key: &str,
+) -> Option<Compression> {
+    content_encoding
+        .and_then(content_encoding_to_compression)
+        .or_else(|| content_type.and_then(content_type_to_compression))
+        .or_else(|| object_key_to_compression(key))
+}
+
+fn content_encoding_to_compression(content_encoding: &str) -> Option<Compression> {
+    match content_encoding {
+        "gzip" => Some(Compression::Gzip),
+        "zstd" => Some(Compression::Zstd),
+        _ => None,
+    }
+}
+
+fn content_type_to_compression(content_type: &str) -> Option<Compression> {
+    match content_type {
+        "application/gzip" | "application/x-gzip" => Some(Compression::Gzip),
+        "application/zstd" => Some(Compression::Zstd),
+        _ => None,
+    }
+}
+
+fn object_key_to_compression(key: &str) -> Option<Compression> {
+    let extension = std::path::Path::new(key)
+        .extension()
+        .and_then(std::ffi::OsStr::to_str);
+
+    use Compression::*;
+    extension.and_then(|extension| match extension {
+        "gz" => Some(Gzip),
+        "zst" => Some(Zstd),
+        _ => Option::None,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::Write;
+
+    use flate2::{write::GzEncoder, Compression as GzCompression};
+    use tokio::io::AsyncReadExt;
+    use vector_lib::codecs::NewlineDelimitedDecoder;
+
+    use super::*;
+    use crate::line_agg::Mode;
+
+    #[test]
+    fn determine_compression() {
+        use super::Compression;
+
+        let cases = vec![
+            ("out.log", Some("gzip"), None, Some(Compression::Gzip)),
+            (
+                "out.log",
+                None,
+                Some("application/gzip"),
+                Some(Compression::Gzip),
+            ),
+            ("out.log.gz", None, None, Some(Compression::Gzip)),
+            ("out.txt", None, None, None),
+        ];
+        for case in cases {
+            let (key, content_encoding, content_type, expected) = case;
+            assert_eq!(
+                super::determine_compression(content_encoding, content_type, key),
+                expected,
+                "key={:?} content_encoding={:?} content_type={:?}",
+                key,
+                content_encoding,
+                content_type,
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn decompresses_by_key_suffix() {
+        let mut encoder = GzEncoder::new(Vec::new(), GzCompression::fast());
+        encoder.write_all(b"hello\nworld\n").unwrap();
+        let body = stream::iter([Ok(Bytes::from(encoder.finish().unwrap()))]);
+
+        let mut data = Vec::new();
+        object_reader(Compression::Auto, "logs/out.log.gz", None, None, body)
+            .await
+            .read_to_end(&mut data)
+            .await
+            .unwrap();
+
+        assert_eq!(data, b"hello\nworld\n");
+    }
+
+    #[tokio::test]
+    async fn aggregates_multiline() {
+        let body = stream::iter([Ok(Bytes::from_static(
+            b"first\n  continued\nsecond\n  continued\n",
+        ))]);
+        let reader = object_reader(Compression::None, "out.log", None, None, body).await;
+        let multiline = line_agg::Config {
+            start_pattern: regex::bytes::Regex::new("^[^\\s]").unwrap(),
+            condition_pattern: regex::bytes::Regex::new("^[\\s]+").unwrap(),
+            mode: Mode::ContinueThrough,
+            timeout: std::time::Duration::from_millis(1000),
+        };
+
+        let mut read_error = None;
+        let lines = object_lines(
+            reader,
+            Framer::NewlineDelimited(NewlineDelimitedDecoder::new()),
+            Some(&multiline),
+            register!(BytesReceived::from(
+                vector_lib::internal_event::Protocol::HTTP
+            )),
+            &mut read_error,
+        )
+        .collect::<Vec<_>>()
+        .await;
+
+        assert_eq!(
+            lines,
+            vec![
+                Bytes::from_static(b"first\n  continued"),
+                Bytes::from_static(b"second\n  continued"),
+            ]
+        );
+        assert!(read_error.is_none());
+    }
+}
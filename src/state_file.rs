@@ -0,0 +1,103 @@
+//! Persistence of the state files of stateful components.
+//!
+//! State files are replaced atomically, so that a crash while writing one never leaves a partially
+//! written file behind, and they are flushed to disk before being renamed into place, so that the
+//! previous state isn't lost if the system crashes right after the rename.
+//!
+//! When a transform is reloaded, the topology builds and starts the new instance before the old
+//! instance has finished shutting down, so both can be running at once. A transform that restores
+//! its state on start and persists it on shutdown would otherwise load the state file before the
+//! old instance has written it, and then have its own state overwritten by it.
+
+use std::{
+    collections::HashMap,
+    fs,
+    io::{self, ErrorKind, Write},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, Weak},
+};
+
+use once_cell::sync::Lazy;
+use tokio::sync::{Mutex as AsyncMutex, OwnedMutexGuard};
+
+static LOCKS: Lazy<Mutex<HashMap<PathBuf, Weak<AsyncMutex<()>>>>> = Lazy::new(Default::default);
+
+/// Serializes the instances of a transform that share a state file.
+///
+/// Each instance acquires the lock when it starts running, before loading its state, and releases
+/// it once it has persisted its state on shutdown. The instance started by a reload therefore only
+/// loads the state once the instance it replaces has written it.
+#[derive(Clone, Debug)]
+pub struct StateFileLock(Arc<AsyncMutex<()>>);
+
+impl StateFileLock {
+    /// Gets the lock for the given state file, shared with every other instance using it.
+    pub fn new(path: &Path) -> Self {
+        let mut locks = LOCKS.lock().expect("lock poisoned");
+        locks.retain(|_, lock| lock.strong_count() > 0);
+
+        let lock = locks.get(path).and_then(Weak::upgrade).unwrap_or_else(|| {
+            let lock = Arc::new(AsyncMutex::new(()));
+            locks.insert(path.to_path_buf(), Arc::downgrade(&lock));
+            lock
+        });
+        Self(lock)
+    }
+
+    /// Acquires the state file, unless another instance is still using it.
+    pub fn try_acquire(&self) -> Option<OwnedMutexGuard<()>> {
+        Arc::clone(&self.0).try_lock_owned().ok()
+    }
+
+    /// Waits for any other instance using the state file to release it, and acquires it.
+    pub async fn acquire(&self) -> OwnedMutexGuard<()> {
+        Arc::clone(&self.0).lock_owned().await
+    }
+}
+
+/// Reads a state file, returning `None` if it doesn't exist yet.
+pub fn read(path: &Path) -> io::Result<Option<Vec<u8>>> {
+    match fs::read(path) {
+        Ok(data) => Ok(Some(data)),
+        Err(error) if error.kind() == ErrorKind::NotFound => Ok(None),
+        Err(error) => Err(error),
+    }
+}
+
+/// Atomically replaces the content of a state file.
+///
+/// The data is written to a temporary file next to the state file and flushed to disk before the
+/// temporary file is renamed over the state file.
+pub fn write_atomic(path: &Path, data: &[u8]) -> io::Result<()> {
+    let tmp_path = path.with_extension("tmp");
+    let mut file = fs::File::create(&tmp_path)?;
+    file.write_all(data)?;
+    file.sync_all()?;
+    fs::rename(&tmp_path, path)?;
+
+    // Flush the directory as well, so that the rename itself is persisted.
+    #[cfg(unix)]
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        fs::File::open(dir)?.sync_all()?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn writes_and_reads_state() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("state");
+
+        assert_eq!(read(&path).unwrap(), None);
+
+        write_atomic(&path, b"first").unwrap();
+        write_atomic(&path, b"second").unwrap();
+        assert_eq!(read(&path).unwrap().as_deref(), Some(b"second".as_ref()));
+        assert!(!path.with_extension("tmp").exists());
+    }
+}
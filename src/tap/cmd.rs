@@ -4,7 +4,7 @@ use colored::{ColoredString, Colorize};
 use tokio_stream::StreamExt;
 use url::Url;
 use vector_lib::api_client::{
-    connect_subscription_client,
+    connect_subscription_client_with_options,
     gql::{
         output_events_by_component_id_patterns_subscription::OutputEventsByComponentIdPatternsSubscriptionOutputEventsByComponentIdPatterns,
         TapEncodingFormat, TapSubscriptionExt,
@@ -23,7 +23,16 @@ pub(crate) async fn cmd(opts: &super::Opts, signal_rx: SignalRx) -> exitcode::Ex
     let url = opts.url();
     // Return early with instructions for enabling the API if the endpoint isn't reachable
     // via a healthcheck.
-    let client = Client::new(url.clone());
+    let client = match Client::with_options(url.clone(), &opts.connect) {
+        Ok(client) => client,
+        Err(error) => {
+            #[allow(clippy::print_stderr)]
+            {
+                eprintln!("[tap] Couldn't set up the API client: {:#}", error);
+            }
+            return exitcode::CONFIG;
+        }
+    };
     #[allow(clippy::print_stderr)]
     if client.healthcheck().await.is_err() {
         eprintln!(
@@ -77,16 +86,17 @@ async fn run(
     outputs_patterns: Vec<String>,
     formatter: EventFormatter,
 ) -> exitcode::ExitCode {
-    let subscription_client = match connect_subscription_client(url).await {
-        Ok(c) => c,
-        Err(e) => {
-            #[allow(clippy::print_stderr)]
-            {
-                eprintln!("[tap] Couldn't connect to API via WebSockets: {}", e);
+    let subscription_client =
+        match connect_subscription_client_with_options(url, &opts.connect).await {
+            Ok(c) => c,
+            Err(e) => {
+                #[allow(clippy::print_stderr)]
+                {
+                    eprintln!("[tap] Couldn't connect to API via WebSockets: {}", e);
+                }
+                return exitcode::UNAVAILABLE;
             }
-            return exitcode::UNAVAILABLE;
-        }
-    };
+        };
 
     tokio::pin! {
         let stream = subscription_client.output_events_by_component_id_patterns_subscription(
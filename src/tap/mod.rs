@@ -5,7 +5,7 @@ use clap::Parser;
 pub(crate) use cmd::cmd;
 pub use cmd::tap;
 use url::Url;
-use vector_lib::api_client::gql::TapEncodingFormat;
+use vector_lib::api_client::{gql::TapEncodingFormat, ConnectOptions};
 
 use crate::config::api::default_graphql_url;
 
@@ -21,6 +21,9 @@ pub struct Opts {
     #[arg(short, long)]
     url: Option<Url>,
 
+    #[command(flatten)]
+    connect: ConnectOptions,
+
     /// Maximum number of events to sample each interval
     #[arg(default_value = "100", short = 'l', long)]
     limit: u32,
@@ -3,7 +3,7 @@ use std::time::Duration;
 use chrono::Local;
 use futures_util::future::join_all;
 use tokio::sync::{mpsc, oneshot};
-use vector_lib::api_client::{connect_subscription_client, Client};
+use vector_lib::api_client::{connect_subscription_client_with_options, Client};
 
 use super::{
     dashboard::{init_dashboard, is_tty},
@@ -28,7 +28,16 @@ pub async fn cmd(opts: &super::Opts) -> exitcode::ExitCode {
 
     let url = opts.url();
     // Create a new API client for connecting to the local/remote Vector instance.
-    let client = Client::new(url.clone());
+    let client = match Client::with_options(url.clone(), &opts.connect) {
+        Ok(client) => client,
+        Err(error) => {
+            #[allow(clippy::print_stderr)]
+            {
+                eprintln!("[top] Couldn't set up the API client: {:#}", error);
+            }
+            return exitcode::CONFIG;
+        }
+    };
     #[allow(clippy::print_stderr)]
     if client.healthcheck().await.is_err() {
         eprintln!(
@@ -107,13 +116,14 @@ async fn subscription(
         };
         _ = tx.send(EventType::InitializeState(state)).await;
 
-        let subscription_client = match connect_subscription_client(ws_url.clone()).await {
-            Ok(c) => c,
-            Err(_) => {
-                tokio::time::sleep(Duration::from_millis(RECONNECT_DELAY)).await;
-                continue;
-            }
-        };
+        let subscription_client =
+            match connect_subscription_client_with_options(ws_url.clone(), &opts.connect).await {
+                Ok(c) => c,
+                Err(_) => {
+                    tokio::time::sleep(Duration::from_millis(RECONNECT_DELAY)).await;
+                    continue;
+                }
+            };
 
         // Subscribe to updated metrics
         let finished = metrics::subscribe(subscription_client, tx.clone(), opts.interval as i64);
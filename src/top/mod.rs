@@ -10,6 +10,7 @@ pub use cmd::cmd;
 pub use cmd::top;
 pub use dashboard::is_tty;
 use url::Url;
+use vector_lib::api_client::ConnectOptions;
 
 use crate::config::api::default_graphql_url;
 
@@ -25,6 +26,9 @@ pub struct Opts {
     #[arg(short, long)]
     url: Option<Url>,
 
+    #[command(flatten)]
+    connect: ConnectOptions,
+
     /// Humanize metrics, using numeric suffixes - e.g. 1,100 = 1.10 k, 1,000,000 = 1.00 M
     #[arg(short = 'H', long, default_value_t = true)]
     human_metrics: bool,
@@ -20,6 +20,7 @@ use vector_lib::config::LogNamespace;
 use vector_lib::internal_event::{
     self, CountByteSize, EventsSent, InternalEventHandle as _, Registered,
 };
+use vector_lib::sink_errors::{ErrorsOutput, ERRORS_OUTPUT};
 use vector_lib::transform::update_runtime_schema_definition;
 use vector_lib::{
     buffers::{
@@ -583,6 +584,16 @@ impl<'a> Builder<'a> {
                 Ok(built) => built,
             };
 
+            // The `errors` output of the sink always exists, so that components can be connected
+            // to it, but the events going into the sink are only captured if it's actually used.
+            let (errors_fanout, errors_control) = Fanout::new();
+            let (errors, errors_pump) = self
+                .config
+                .sink_errors_output_used(key)
+                .then(|| ErrorsOutput::new(key.clone(), typetag))
+                .unzip();
+            let capture = errors.clone();
+
             let (trigger, tripwire) = Tripwire::new();
 
             let sink = async move {
@@ -603,26 +614,48 @@ impl<'a> Builder<'a> {
                 let mut rx = wrap(rx);
 
                 let events_received = register!(EventsReceived);
-                sink.run(
-                    rx.by_ref()
-                        .filter(|events: &EventArray| ready(filter_events_type(events, input_type)))
-                        .inspect(|events| {
-                            events_received.emit(CountByteSize(
-                                events.len(),
-                                events.estimated_json_encoded_size_of(),
-                            ))
-                        })
-                        .take_until_if(tripwire),
-                )
-                .await
-                .map(|_| {
-                    debug!("Sink finished normally.");
-                    TaskOutput::Sink(rx)
-                })
-                .map_err(|_| {
-                    debug!("Sink finished with an error.");
-                    TaskError::Opaque
-                })
+                let run = ErrorsOutput::maybe_scope(
+                    errors,
+                    sink.run(
+                        rx.by_ref()
+                            .filter(|events: &EventArray| {
+                                ready(filter_events_type(events, input_type))
+                            })
+                            .inspect(|events| {
+                                events_received.emit(CountByteSize(
+                                    events.len(),
+                                    events.estimated_json_encoded_size_of(),
+                                ))
+                            })
+                            .map(move |events| match &capture {
+                                Some(errors) => errors.capture(events),
+                                None => events,
+                            })
+                            .take_until_if(tripwire),
+                    ),
+                );
+                let pump = async move {
+                    match errors_pump {
+                        Some(pump) => pump.run(errors_fanout).await,
+                        None => Ok(()),
+                    }
+                };
+
+                let (result, pump_result) = futures::join!(run, pump);
+                if let Err(error) = pump_result {
+                    debug!("Sink errors pump finished with an error.");
+                    return Err(TaskError::wrapped(error));
+                }
+
+                result
+                    .map(|_| {
+                        debug!("Sink finished normally.");
+                        TaskOutput::Sink(rx)
+                    })
+                    .map_err(|_| {
+                        debug!("Sink finished with an error.");
+                        TaskError::Opaque
+                    })
             };
 
             let task = Task::new(key.clone(), typetag, sink);
@@ -666,6 +699,13 @@ impl<'a> Builder<'a> {
 
             let healthcheck_task = Task::new(key.clone(), typetag, healthcheck_task);
 
+            self.outputs.insert(
+                OutputId {
+                    component: key.clone(),
+                    port: Some(ERRORS_OUTPUT.to_string()),
+                },
+                errors_control,
+            );
             self.inputs.insert(key.clone(), (tx, sink_inputs.clone()));
             self.healthchecks.insert(key.clone(), healthcheck_task);
             self.tasks.insert(key.clone(), task);
@@ -886,6 +926,11 @@ impl Runner {
                 .map_err(TaskError::wrapped)?;
         }
 
+        self.transform.finish(&mut outputs_buf);
+        self.send_outputs(&mut outputs_buf)
+            .await
+            .map_err(TaskError::wrapped)?;
+
         Ok(TaskOutput::Transform)
     }
 
@@ -953,6 +998,12 @@ impl Runner {
             }
         }
 
+        let mut outputs_buf = self.outputs.new_buf_with_capacity(0);
+        self.transform.finish(&mut outputs_buf);
+        self.send_outputs(&mut outputs_buf)
+            .await
+            .map_err(TaskError::wrapped)?;
+
         Ok(TaskOutput::Transform)
     }
 }
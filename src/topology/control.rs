@@ -0,0 +1,70 @@
+use snafu::Snafu;
+use tokio::sync::{mpsc, oneshot};
+
+use crate::config::ComponentKey;
+
+/// An error from a runtime control command.
+#[derive(Debug, Snafu)]
+pub enum ControlError {
+    #[snafu(display(r#"Component "{key}" does not exist"#))]
+    UnknownComponent { key: ComponentKey },
+    #[snafu(display(r#"Component "{key}" is not a sink"#))]
+    NotASink { key: ComponentKey },
+    #[snafu(display(r#"Component "{key}" is already paused"#))]
+    AlreadyPaused { key: ComponentKey },
+    #[snafu(display(r#"Component "{key}" is not paused"#))]
+    NotPaused { key: ComponentKey },
+    #[snafu(display(r#"Sink "{key}" failed to restart after being flushed"#))]
+    FlushFailed { key: ComponentKey },
+    #[snafu(display("The configuration failed to reload, see the logs for details"))]
+    ReloadFailed,
+    #[snafu(display("The configuration can only be reloaded by the application"))]
+    ReloadUnsupported,
+    #[snafu(display("The topology is no longer accepting commands"))]
+    Unavailable,
+}
+
+/// A runtime control command for the running topology.
+#[derive(Clone, Debug)]
+pub enum TopologyCommand {
+    /// Reload the configuration from disk, as on `SIGHUP`.
+    ReloadConfig,
+    /// Hold back the events flowing into, or out of for sources, the given component.
+    PauseComponent(ComponentKey),
+    /// Release the events held back by a previous pause.
+    ResumeComponent(ComponentKey),
+    /// Restart the given sink, forcing it to flush any partially filled batches.
+    FlushSink(ComponentKey),
+}
+
+/// A command, along with the channel on which to send its outcome.
+#[derive(Debug)]
+pub struct ControlRequest {
+    pub command: TopologyCommand,
+    pub response: oneshot::Sender<Result<bool, ControlError>>,
+}
+
+pub type ControlReceiver = mpsc::UnboundedReceiver<ControlRequest>;
+
+/// A handle for sending commands to the application driving the running topology.
+#[derive(Clone, Debug)]
+pub struct TopologyControl(mpsc::UnboundedSender<ControlRequest>);
+
+impl TopologyControl {
+    pub fn new() -> (Self, ControlReceiver) {
+        let (tx, rx) = mpsc::unbounded_channel();
+        (Self(tx), rx)
+    }
+
+    /// Sends a command and waits for its outcome.
+    ///
+    /// For `ReloadConfig`, the outcome is whether the new configuration was applied, rather than
+    /// rolled back. Every other command returns `true` once it has been carried out.
+    pub async fn send(&self, command: TopologyCommand) -> Result<bool, ControlError> {
+        let (response, outcome) = oneshot::channel();
+        self.0
+            .send(ControlRequest { command, response })
+            .map_err(|_| ControlError::Unavailable)?;
+        outcome.await.map_err(|_| ControlError::Unavailable)?
+    }
+}
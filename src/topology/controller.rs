@@ -17,7 +17,11 @@ use crate::internal_events::{
     VectorConfigLoadError, VectorRecoveryError, VectorReloadError, VectorReloaded,
 };
 
-use crate::{config, signal::ShutdownError, topology::RunningTopology};
+use crate::{
+    config,
+    signal::ShutdownError,
+    topology::{ControlError, RunningTopology, TopologyCommand},
+};
 
 #[derive(Clone, Debug)]
 pub struct SharedTopologyController(Arc<Mutex<TopologyController>>);
@@ -44,6 +48,8 @@ pub struct TopologyController {
     pub enterprise_reporter: Option<EnterpriseReporter<BoxFuture<'static, ()>>>,
     #[cfg(feature = "api")]
     pub api_server: Option<api::Server>,
+    #[cfg(feature = "api")]
+    pub topology_control: super::TopologyControl,
     pub extra_context: ExtraContext,
 }
 
@@ -98,71 +104,110 @@ impl TopologyController {
             }
         }
 
-        // Start the api server or disable it, if necessary
         #[cfg(feature = "api")]
-        if !new_config.api.enabled {
+        let previous_api = self.topology.config().api.clone();
+
+        match self
+            .topology
+            .reload_config_and_respawn(new_config, self.extra_context.clone())
+            .await
+        {
+            Ok(true) => {
+                #[cfg(feature = "api")]
+                if let Err(outcome) = self.update_api_server(&previous_api).await {
+                    return outcome;
+                }
+
+                emit!(VectorReloaded {
+                    config_paths: &self.config_paths
+                });
+                ReloadOutcome::Success
+            }
+            Ok(false) => {
+                emit!(VectorReloadError);
+                ReloadOutcome::RolledBack
+            }
+            // Trigger graceful shutdown for what remains of the topology
+            Err(()) => {
+                emit!(VectorReloadError);
+                emit!(VectorRecoveryError);
+                ReloadOutcome::FatalError(ShutdownError::ReloadFailedToRestore)
+            }
+        }
+    }
+
+    /// Starts, stops or restarts the api server to match the reloaded config, if necessary.
+    ///
+    /// The server is restarted whenever the `api` options change, so that changes to its address,
+    /// authentication or TLS settings take effect.
+    #[cfg(feature = "api")]
+    async fn update_api_server(
+        &mut self,
+        previous_api: &config::api::Options,
+    ) -> Result<(), ReloadOutcome> {
+        let config = self.topology.config();
+        if !config.api.enabled || config.api != *previous_api {
             if let Some(server) = self.api_server.take() {
-                debug!("Dropping api server.");
-                drop(server)
+                debug!("Stopping api server.");
+                server.stop().await;
             }
-        } else if self.api_server.is_none() {
+        }
+
+        if let Some(ref api_server) = self.api_server {
+            // Pass the new config to the API server.
+            api_server.update_config(config);
+        } else if config.api.enabled {
             use crate::internal_events::ApiStarted;
             use std::sync::atomic::AtomicBool;
             use tokio::runtime::Handle;
 
             debug!("Starting api server.");
 
-            self.api_server = match api::Server::start(
-                self.topology.config(),
+            match api::Server::start(
+                config,
                 self.topology.watch(),
                 Arc::<AtomicBool>::clone(&self.topology.running),
+                self.topology_control.clone(),
                 &Handle::current(),
             ) {
                 Ok(api_server) => {
                     emit!(ApiStarted {
-                        addr: new_config.api.address.unwrap(),
-                        playground: new_config.api.playground,
-                        graphql: new_config.api.graphql,
+                        addr: config.api.address.unwrap(),
+                        playground: config.api.playground,
+                        graphql: config.api.graphql,
                     });
 
-                    Some(api_server)
+                    self.api_server = Some(api_server);
                 }
                 Err(error) => {
                     let error = error.to_string();
                     error!("An error occurred that Vector couldn't handle: {}.", error);
-                    return ReloadOutcome::FatalError(ShutdownError::ApiFailed { error });
+                    return Err(ReloadOutcome::FatalError(ShutdownError::ApiFailed {
+                        error,
+                    }));
                 }
             }
         }
 
-        match self
-            .topology
-            .reload_config_and_respawn(new_config, self.extra_context.clone())
-            .await
-        {
-            Ok(true) => {
-                #[cfg(feature = "api")]
-                // Pass the new config to the API server.
-                if let Some(ref api_server) = self.api_server {
-                    api_server.update_config(self.topology.config());
-                }
+        Ok(())
+    }
 
-                emit!(VectorReloaded {
-                    config_paths: &self.config_paths
-                });
-                ReloadOutcome::Success
-            }
-            Ok(false) => {
-                emit!(VectorReloadError);
-                ReloadOutcome::RolledBack
-            }
-            // Trigger graceful shutdown for what remains of the topology
-            Err(()) => {
-                emit!(VectorReloadError);
-                emit!(VectorRecoveryError);
-                ReloadOutcome::FatalError(ShutdownError::ReloadFailedToRestore)
+    /// Applies a runtime control command to the running topology.
+    ///
+    /// Reloading is left to the caller, as it needs to load the configuration first, so it's
+    /// rejected here.
+    pub async fn control(&mut self, command: TopologyCommand) -> Result<bool, ControlError> {
+        match command {
+            TopologyCommand::ReloadConfig => Err(ControlError::ReloadUnsupported),
+            TopologyCommand::PauseComponent(key) => self.topology.pause_component(&key),
+            TopologyCommand::ResumeComponent(key) => self.topology.resume_component(&key),
+            TopologyCommand::FlushSink(key) => {
+                self.topology
+                    .flush_sink(&key, self.extra_context.clone())
+                    .await
             }
         }
+        .map(|()| true)
     }
 
     pub async fn stop(self) {
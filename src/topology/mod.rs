@@ -11,6 +11,7 @@ pub(super) use vector_lib::fanout;
 pub mod schema;
 
 pub mod builder;
+mod control;
 mod controller;
 mod ready_arrays;
 mod running;
@@ -30,6 +31,9 @@ use tokio::sync::{mpsc, watch};
 use vector_lib::buffers::topology::channel::{BufferReceiverStream, BufferSender};
 
 pub use self::builder::TopologyPieces;
+pub use self::control::{
+    ControlError, ControlReceiver, ControlRequest, TopologyCommand, TopologyControl,
+};
 pub use self::controller::{ReloadOutcome, SharedTopologyController, TopologyController};
 pub use self::running::{RunningTopology, ShutdownErrorReceiver};
 
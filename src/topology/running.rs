@@ -18,6 +18,7 @@ use vector_lib::trigger::DisabledTrigger;
 use super::{
     builder,
     builder::TopologyPieces,
+    control::ControlError,
     fanout::{ControlChannel, ControlMessage},
     handle_errors, retain, take_healthchecks,
     task::TaskOutput,
@@ -44,6 +45,7 @@ pub struct RunningTopology {
     tasks: HashMap<ComponentKey, TaskHandle>,
     shutdown_coordinator: SourceShutdownCoordinator,
     detach_triggers: HashMap<ComponentKey, DisabledTrigger>,
+    paused: HashMap<ComponentKey, Vec<(OutputId, ComponentKey)>>,
     pub(crate) config: Config,
     pub(crate) abort_tx: mpsc::UnboundedSender<ShutdownError>,
     watch: (WatchTx, WatchRx),
@@ -60,6 +62,7 @@ impl RunningTopology {
             outputs_tap_metadata: HashMap::new(),
             shutdown_coordinator: SourceShutdownCoordinator::default(),
             detach_triggers: HashMap::new(),
+            paused: HashMap::new(),
             source_tasks: HashMap::new(),
             tasks: HashMap::new(),
             abort_tx,
@@ -106,7 +109,10 @@ impl RunningTopology {
     /// poll for when the tasks have completed. Once the returned future is
     /// dropped then everything from this RunningTopology instance is fully
     /// dropped.
-    pub fn stop(self) -> impl Future<Output = ()> {
+    pub fn stop(mut self) -> impl Future<Output = ()> {
+        // Paused fanouts would otherwise hold back events forever, keeping upstream components from
+        // ever finishing.
+        self.resume_all_components();
         // Update the API's health endpoint to signal shutdown
         self.running.store(false, Ordering::Relaxed);
         // Create handy handles collections of all tasks for the subsequent
@@ -235,6 +241,10 @@ impl RunningTopology {
             return Ok(false);
         }
 
+        // Pausing is done through the fanouts of the current topology, which the reload is about to
+        // rewire, so we start from a clean slate.
+        self.resume_all_components();
+
         // Calculate the change between the current configuration and the new configuration, and
         // shutdown any components that are changing so that we can reclaim their buffers before
         // spawning the new version of the component.
@@ -307,6 +317,219 @@ impl RunningTopology {
         Err(())
     }
 
+    /// Pauses a component, holding back the events flowing into it.
+    ///
+    /// As sources have no inputs, pausing a source instead holds back the events flowing out of it,
+    /// which in turn applies backpressure to the source itself. Since a fanout can only send to all
+    /// of its consumers at once, pausing a component also holds back any other component consuming
+    /// the same outputs.
+    ///
+    /// Paused components are resumed when the configuration is reloaded.
+    ///
+    /// # Errors
+    ///
+    /// If the component doesn't exist, or is already paused, an error is returned.
+    pub fn pause_component(&mut self, key: &ComponentKey) -> Result<(), ControlError> {
+        if self.paused.contains_key(key) {
+            return Err(ControlError::AlreadyPaused { key: key.clone() });
+        }
+
+        let connections = self.connections_of(key)?;
+        for (output_id, consumer) in &connections {
+            // A connection can only be paused once, so skip any that are already held back by
+            // another paused component.
+            if self.is_connection_paused(output_id, consumer) {
+                continue;
+            }
+
+            if let Some(output) = self.outputs.get(output_id) {
+                debug!(component = %consumer, fanout_id = %output_id, "Pausing component input in fanout.");
+
+                _ = output.send(ControlMessage::Pause(consumer.clone()));
+            }
+        }
+
+        info!(component = %key, "Paused component.");
+        self.paused.insert(key.clone(), connections);
+        Ok(())
+    }
+
+    /// Resumes a component paused by [`RunningTopology::pause_component`].
+    ///
+    /// # Errors
+    ///
+    /// If the component isn't paused, an error is returned.
+    pub fn resume_component(&mut self, key: &ComponentKey) -> Result<(), ControlError> {
+        let connections = self
+            .paused
+            .remove(key)
+            .ok_or_else(|| ControlError::NotPaused { key: key.clone() })?;
+
+        for (output_id, consumer) in connections {
+            // Connections still held back by another paused component stay paused.
+            if !self.is_connection_paused(&output_id, &consumer) {
+                self.replace_connection(&output_id, &consumer);
+            }
+        }
+
+        info!(component = %key, "Resumed component.");
+        Ok(())
+    }
+
+    fn resume_all_components(&mut self) {
+        let connections = self
+            .paused
+            .drain()
+            .flat_map(|(_, connections)| connections)
+            .collect::<HashSet<_>>();
+
+        for (output_id, consumer) in connections {
+            self.replace_connection(&output_id, &consumer);
+        }
+    }
+
+    /// Flushes a sink by restarting it.
+    ///
+    /// The inputs of the sink are paused while the sink is shut down, which makes it send any
+    /// partially filled batches, and is then started again on top of its existing buffer, so no
+    /// buffered events are lost.
+    ///
+    /// # Errors
+    ///
+    /// If the component isn't a sink, or the sink fails to shut down or to be started again, an
+    /// error is returned. In the latter cases, the topology is shut down, as it would be if the
+    /// sink had crashed.
+    pub async fn flush_sink(
+        &mut self,
+        key: &ComponentKey,
+        extra_context: ExtraContext,
+    ) -> Result<(), ControlError> {
+        if self.config.sink(key).is_none() {
+            let exists = self.config.source(key).is_some() || self.config.transform(key).is_some();
+            return Err(if exists {
+                ControlError::NotASink { key: key.clone() }
+            } else {
+                ControlError::UnknownComponent { key: key.clone() }
+            });
+        }
+
+        info!(component = %key, "Flushing sink.");
+
+        // Pause the inputs of the sink so they can be replaced once the sink is restarted. Any
+        // input already held back by a paused component is re-paused below.
+        let connections = self.connections_of(key)?;
+        for (output_id, consumer) in &connections {
+            if !self.is_connection_paused(output_id, consumer) {
+                if let Some(output) = self.outputs.get(output_id) {
+                    _ = output.send(ControlMessage::Pause(consumer.clone()));
+                }
+            }
+        }
+
+        // Detach the sink from its buffer, and wait for it to shut down so we can reclaim the
+        // buffer for the restarted sink.
+        self.detach_triggers
+            .remove(key)
+            .expect("sink has a detach trigger")
+            .into_inner()
+            .cancel();
+        let tx = self.inputs.get(key).expect("sink has an input").clone();
+        let previous = self.tasks.remove(key).expect("sink has a task");
+        debug!(message = "Waiting for sink to shutdown.", %key);
+        let rx = match previous.await {
+            Ok(Ok(TaskOutput::Sink(rx))) => rx.into_inner(),
+            // The buffer is lost along with the sink, so it can't be started again and its
+            // inputs would stay paused forever. Shut down, as if the sink had crashed.
+            _ => {
+                _ = self.abort_tx.send(ShutdownError::SinkAborted {
+                    key: key.clone(),
+                    error: "Failed to shut down while being flushed.".to_string(),
+                });
+                return Err(ControlError::FlushFailed { key: key.clone() });
+            }
+        };
+
+        let mut diff = ConfigDiff::new(&self.config, &self.config);
+        diff.sinks.to_change.insert(key.clone());
+        let buffers = HashMap::from([(key.clone(), (tx, Arc::new(Mutex::new(Some(rx)))))]);
+
+        let Some(mut new_pieces) =
+            TopologyPieces::build_or_log_errors(&self.config, &diff, buffers, extra_context).await
+        else {
+            _ = self.abort_tx.send(ShutdownError::SinkAborted {
+                key: key.clone(),
+                error: "Failed to restart after being flushed.".to_string(),
+            });
+            return Err(ControlError::FlushFailed { key: key.clone() });
+        };
+
+        self.connect_diff(&diff, &mut new_pieces).await;
+        self.spawn_diff(&diff, new_pieces);
+
+        for (output_id, consumer) in &connections {
+            if self.is_connection_paused(output_id, consumer) {
+                if let Some(output) = self.outputs.get(output_id) {
+                    _ = output.send(ControlMessage::Pause(consumer.clone()));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the fanout connections, as `(output, consumer)` pairs, that pausing the given
+    /// component holds back.
+    fn connections_of(
+        &self,
+        key: &ComponentKey,
+    ) -> Result<Vec<(OutputId, ComponentKey)>, ControlError> {
+        if let Some(inputs) = self.config.inputs_for_node(key) {
+            Ok(inputs
+                .iter()
+                .map(|output_id| (output_id.clone(), key.clone()))
+                .collect())
+        } else if self.config.source(key).is_some() {
+            let consumers = self
+                .config
+                .transforms()
+                .map(|(consumer, transform)| (consumer, &transform.inputs[..]))
+                .chain(
+                    self.config
+                        .sinks()
+                        .map(|(consumer, sink)| (consumer, &sink.inputs[..])),
+                );
+
+            Ok(consumers
+                .flat_map(|(consumer, inputs)| {
+                    inputs
+                        .iter()
+                        .filter(|output_id| &output_id.component == key)
+                        .map(move |output_id| (output_id.clone(), consumer.clone()))
+                })
+                .collect())
+        } else {
+            Err(ControlError::UnknownComponent { key: key.clone() })
+        }
+    }
+
+    fn is_connection_paused(&self, output_id: &OutputId, consumer: &ComponentKey) -> bool {
+        self.paused.values().any(|connections| {
+            connections.iter().any(|(paused_output, paused_consumer)| {
+                paused_output == output_id && paused_consumer == consumer
+            })
+        })
+    }
+
+    fn replace_connection(&self, output_id: &OutputId, consumer: &ComponentKey) {
+        if let (Some(output), Some(input)) =
+            (self.outputs.get(output_id), self.inputs.get(consumer))
+        {
+            debug!(component = %consumer, fanout_id = %output_id, "Replacing component input in fanout.");
+
+            _ = output.send(ControlMessage::Replace(consumer.clone(), input.clone()));
+        }
+    }
+
     pub(crate) async fn run_healthchecks(
         &mut self,
         diff: &ConfigDiff,
@@ -466,6 +689,7 @@ impl RunningTopology {
         for key in &diff.sinks.to_remove {
             debug!(component = %key, "Removing sink.");
             self.remove_inputs(key, diff, new_config).await;
+            self.remove_outputs(key);
         }
 
         // After that, for any changed sinks, we temporarily detach their inputs (not remove) so
@@ -494,6 +718,7 @@ impl RunningTopology {
                 buffer_tx.insert(key.clone(), self.inputs.get(key).unwrap().clone());
             }
             self.remove_inputs(key, diff, new_config).await;
+            self.remove_outputs(key);
         }
 
         // Now that we've disconnected or temporarily detached the inputs to all changed/removed
@@ -601,6 +826,13 @@ impl RunningTopology {
             self.setup_outputs(key, new_pieces).await;
         }
 
+        // Sinks only have an `errors` output, but it has to be available to any transforms and
+        // sinks using it as well.
+        for key in diff.sinks.changed_and_added() {
+            debug!(component = %key, "Configuring outputs for sink.");
+            self.setup_outputs(key, new_pieces).await;
+        }
+
         // Now that all possible outputs are configured, we can start wiring up inputs, starting
         // with transforms.
         for key in diff.transforms.changed_and_added() {
@@ -1067,5 +1299,14 @@ fn get_changed_outputs(diff: &ConfigDiff, output_ids: Inputs<OutputId>) -> Vec<O
         );
     }
 
+    for sink_key in &diff.sinks.to_change {
+        changed_outputs.extend(
+            output_ids
+                .iter()
+                .filter(|id| &id.component == sink_key)
+                .cloned(),
+        );
+    }
+
     changed_outputs
 }
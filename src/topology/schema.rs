@@ -90,6 +90,17 @@ pub fn possible_definitions(
 
             definitions.append(&mut transform_definition);
         }
+
+        // If the input is the `errors` output of a sink, it carries the events received by the sink.
+        if let Some(inputs) = config.sink_inputs(key) {
+            let mut sink_definitions = input.with_definitions(
+                possible_definitions(inputs, config, enrichment_tables.clone(), cache)?
+                    .into_iter()
+                    .map(|(_, definition)| definition),
+            );
+
+            definitions.append(&mut sink_definitions);
+        }
     }
 
     Ok(definitions)
@@ -193,6 +204,17 @@ pub(super) fn expanded_definitions(
             // Append whatever number of additional pipelines we created to the existing
             // pipeline definitions.
             definitions.append(&mut transform_definition);
+
+        // The `errors` output of a sink carries the events received by the sink, so each of the
+        // sink's inputs is expanded to a new pipeline.
+        } else if let Some(inputs) = config.sink_inputs(key) {
+            let mut sink_definitions = input.with_definitions(
+                expanded_definitions(enrichment_tables.clone(), inputs, config, &mut merged_cache)?
+                    .into_iter()
+                    .map(|(_, definition)| definition),
+            );
+
+            definitions.append(&mut sink_definitions);
         }
     }
 
@@ -283,6 +305,18 @@ pub(crate) fn input_definitions(
 
             definitions.append(&mut transform_definitions);
         }
+
+        // If the input is the `errors` output of a sink, we recurse to the upstream components of
+        // the sink, as it sends the events it received unchanged.
+        if let Some(inputs) = config.sink_inputs(key) {
+            let mut sink_definitions = input.with_definitions(
+                input_definitions(inputs, config, enrichment_tables.clone(), cache)?
+                    .into_iter()
+                    .map(|(_, definition)| definition),
+            );
+
+            definitions.append(&mut sink_definitions);
+        }
     }
 
     Ok(definitions)
@@ -349,6 +383,12 @@ pub trait ComponentContainer {
 
     fn transform_inputs(&self, key: &ComponentKey) -> Option<&[OutputId]>;
 
+    /// Gets the inputs of the sink with the given key, which determine the definitions of the
+    /// sink's `errors` output.
+    fn sink_inputs(&self, _key: &ComponentKey) -> Option<&[OutputId]> {
+        None
+    }
+
     fn transform_outputs(
         &self,
         key: &ComponentKey,
@@ -421,6 +461,10 @@ impl ComponentContainer for Config {
         self.transform(key).map(|transform| &transform.inputs[..])
     }
 
+    fn sink_inputs(&self, key: &ComponentKey) -> Option<&[OutputId]> {
+        self.sink(key).map(|sink| &sink.inputs[..])
+    }
+
     fn transform_outputs(
         &self,
         key: &ComponentKey,
@@ -0,0 +1,82 @@
+use futures::StreamExt;
+use tokio::time::{timeout, Duration};
+
+use super::into_message;
+use crate::{
+    config::{ComponentKey, Config},
+    event::{Event, EventArray, EventContainer, LogEvent},
+    test_util::{
+        mock::{basic_sink, basic_source},
+        start_topology, trace_init,
+    },
+    topology::ControlError,
+};
+
+#[tokio::test]
+async fn topology_pause_and_resume_sink() {
+    trace_init();
+
+    let (mut in1, source1) = basic_source();
+    let (mut out1, sink1) = basic_sink(10);
+
+    let mut config = Config::builder();
+    config.add_source("in1", source1);
+    config.add_sink("out1", &["in1"], sink1);
+
+    let (mut topology, _) = start_topology(config.build().unwrap(), false).await;
+
+    let key = ComponentKey::from("out1");
+    topology.pause_component(&key).unwrap();
+    assert!(matches!(
+        topology.pause_component(&key),
+        Err(ControlError::AlreadyPaused { .. })
+    ));
+
+    in1.send_event(Event::Log(LogEvent::from("this")))
+        .await
+        .unwrap();
+
+    // Nothing reaches the sink while it's paused.
+    assert!(timeout(Duration::from_millis(100), out1.next())
+        .await
+        .is_err());
+
+    topology.resume_component(&key).unwrap();
+    assert!(matches!(
+        topology.resume_component(&key),
+        Err(ControlError::NotPaused { .. })
+    ));
+
+    let events: EventArray = out1.next().await.unwrap().into();
+    let messages = events.into_events().map(into_message).collect::<Vec<_>>();
+    assert_eq!(messages, vec!["this".to_owned()]);
+
+    topology.stop().await;
+}
+
+#[tokio::test]
+async fn topology_pause_unknown_component() {
+    trace_init();
+
+    let (_in1, source1) = basic_source();
+    let (_out1, sink1) = basic_sink(10);
+
+    let mut config = Config::builder();
+    config.add_source("in1", source1);
+    config.add_sink("out1", &["in1"], sink1);
+
+    let (mut topology, _) = start_topology(config.build().unwrap(), false).await;
+
+    assert!(matches!(
+        topology.pause_component(&ComponentKey::from("nope")),
+        Err(ControlError::UnknownComponent { .. })
+    ));
+    assert!(matches!(
+        topology
+            .flush_sink(&ComponentKey::from("in1"), Default::default())
+            .await,
+        Err(ControlError::NotASink { .. })
+    ));
+
+    topology.stop().await;
+}
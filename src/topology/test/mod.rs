@@ -32,6 +32,7 @@ use vector_lib::config::OutputId;
 
 mod backpressure;
 mod compliance;
+mod control;
 #[cfg(all(feature = "sinks-socket", feature = "sources-socket"))]
 mod crash;
 mod doesnt_reload;
@@ -874,6 +875,8 @@ async fn topology_disk_buffer_flushes_on_idle() {
     sink1_outer.buffer = BufferConfig::Single(BufferType::DiskV2 {
         max_size: std::num::NonZeroU64::new(268435488).unwrap(),
         when_full: WhenFull::DropNewest,
+        encryption: None,
+        compression: None,
     });
     config.add_sink_outer("out1", sink1_outer);
 
@@ -12,7 +12,9 @@ use vector_lib::configurable::configurable_component;
 use crate::{
     config::{DataType, Input, OutputId, TransformConfig, TransformContext, TransformOutput},
     event::{metric, Event, EventMetadata},
-    internal_events::{AggregateEventRecorded, AggregateFlushed, AggregateUpdateFailed},
+    internal_events::{
+        AggregateEventRecorded, AggregateFlushed, AggregateModeMismatch, AggregateUpdateFailed,
+    },
     schema,
     transforms::{TaskTransform, Transform},
 };
@@ -28,6 +30,119 @@ pub struct AggregateConfig {
     #[serde(default = "default_interval_ms")]
     #[configurable(metadata(docs::human_name = "Flush Interval"))]
     pub interval_ms: u64,
+
+    /// How metrics with the same series data are combined during a flush interval.
+    ///
+    /// Not every mode applies to every metric. Metrics that the configured mode does not apply to
+    /// are dropped, and an error is reported.
+    #[serde(default)]
+    #[configurable(derived)]
+    pub mode: AggregationMode,
+}
+
+/// Aggregation modes.
+#[configurable_component]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum AggregationMode {
+    /// Sums incremental metrics and keeps the latest value of absolute metrics.
+    ///
+    /// Applies to all metrics.
+    #[default]
+    Auto,
+
+    /// Sums incremental metrics.
+    ///
+    /// Applies to incremental metrics of any type other than aggregated summaries.
+    Sum,
+
+    /// Keeps the latest value of absolute metrics.
+    ///
+    /// Applies to absolute metrics of any type.
+    Latest,
+
+    /// Counts the metrics received, emitting an incremental counter.
+    ///
+    /// Applies to all metrics.
+    Count,
+
+    /// Emits the change between the latest value of an absolute metric and its value at the
+    /// previous flush, as an incremental metric.
+    ///
+    /// The first time a series is seen, or when it wasn't seen in the previous interval, the
+    /// change is computed from its first value. If the change can't be computed, such as when a
+    /// counter is reset, the latest value is emitted instead.
+    ///
+    /// Applies to absolute counters, gauges, sets, and aggregated histograms.
+    Diff,
+
+    /// Keeps the maximum value of absolute metrics.
+    ///
+    /// Applies to absolute counters and gauges.
+    Max,
+
+    /// Keeps the minimum value of absolute metrics.
+    ///
+    /// Applies to absolute counters and gauges.
+    Min,
+
+    /// Computes the mean of the values of absolute metrics, emitting a gauge.
+    ///
+    /// Applies to absolute counters and gauges.
+    Mean,
+
+    /// Computes the population standard deviation of the values of absolute metrics, emitting a
+    /// gauge.
+    ///
+    /// Applies to absolute counters and gauges.
+    Stdev,
+}
+
+impl AggregationMode {
+    const fn as_str(self) -> &'static str {
+        match self {
+            Self::Auto => "auto",
+            Self::Sum => "sum",
+            Self::Latest => "latest",
+            Self::Count => "count",
+            Self::Diff => "diff",
+            Self::Max => "max",
+            Self::Min => "min",
+            Self::Mean => "mean",
+            Self::Stdev => "stdev",
+        }
+    }
+
+    /// Whether or not this mode can aggregate the given metric.
+    fn applies_to(self, data: &metric::MetricData) -> bool {
+        use metric::{MetricKind, MetricValue};
+
+        match self {
+            Self::Auto | Self::Count => true,
+            Self::Sum => {
+                data.kind == MetricKind::Incremental
+                    && !matches!(data.value(), MetricValue::AggregatedSummary { .. })
+            }
+            Self::Latest => data.kind == MetricKind::Absolute,
+            Self::Diff => {
+                data.kind == MetricKind::Absolute
+                    && matches!(
+                        data.value(),
+                        MetricValue::Counter { .. }
+                            | MetricValue::Gauge { .. }
+                            | MetricValue::Set { .. }
+                            | MetricValue::AggregatedHistogram { .. }
+                    )
+            }
+            Self::Max | Self::Min | Self::Mean | Self::Stdev => {
+                data.kind == MetricKind::Absolute
+                    && matches!(
+                        data.value(),
+                        MetricValue::Counter { .. } | MetricValue::Gauge { .. }
+                    )
+            }
+        }
+    }
 }
 
 const fn default_interval_ms() -> u64 {
@@ -59,52 +174,231 @@ impl TransformConfig for AggregateConfig {
 
 type MetricEntry = (metric::MetricData, EventMetadata);
 
+/// Running statistics over the values of a series, used by the `mean` and `stdev` modes.
+#[derive(Debug, Default)]
+struct Statistics {
+    count: u64,
+    mean: f64,
+    m2: f64,
+}
+
+impl Statistics {
+    fn add(&mut self, value: f64) {
+        // Welford's online algorithm, which avoids the loss of precision that comes with summing
+        // the squares of the values.
+        self.count += 1;
+        let delta = value - self.mean;
+        self.mean += delta / self.count as f64;
+        self.m2 += delta * (value - self.mean);
+    }
+
+    fn stdev(&self) -> f64 {
+        if self.count == 0 {
+            0.0
+        } else {
+            (self.m2 / self.count as f64).sqrt()
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct Aggregate {
     interval: Duration,
+    mode: AggregationMode,
     map: HashMap<metric::MetricSeries, MetricEntry>,
+    statistics: HashMap<metric::MetricSeries, Statistics>,
+    previous: HashMap<metric::MetricSeries, metric::MetricData>,
 }
 
 impl Aggregate {
     pub fn new(config: &AggregateConfig) -> crate::Result<Self> {
         Ok(Self {
             interval: Duration::from_millis(config.interval_ms),
+            mode: config.mode,
             map: Default::default(),
+            statistics: Default::default(),
+            previous: Default::default(),
         })
     }
 
     fn record(&mut self, event: Event) {
         let (series, data, metadata) = event.into_metric().into_parts();
 
-        match data.kind {
-            metric::MetricKind::Incremental => match self.map.entry(series) {
-                Entry::Occupied(mut entry) => {
-                    let existing = entry.get_mut();
-                    // In order to update (add) the new and old kind's must match
-                    if existing.0.kind == data.kind && existing.0.update(&data) {
+        if !self.mode.applies_to(&data) {
+            emit!(AggregateModeMismatch {
+                mode: self.mode.as_str(),
+                kind: data.kind,
+                value_type: data.value().as_name(),
+            });
+            return;
+        }
+
+        match self.mode {
+            AggregationMode::Auto => match data.kind {
+                metric::MetricKind::Incremental => self.record_sum(series, data, metadata),
+                metric::MetricKind::Absolute => self.record_latest(series, data, metadata),
+            },
+            AggregationMode::Sum => self.record_sum(series, data, metadata),
+            AggregationMode::Latest => self.record_latest(series, data, metadata),
+            AggregationMode::Count => self.record_count(series, data, metadata),
+            AggregationMode::Diff => {
+                // The first value of a series is the baseline of its first change.
+                self.previous
+                    .entry(series.clone())
+                    .or_insert_with(|| data.clone());
+                self.record_latest(series, data, metadata);
+            }
+            AggregationMode::Max => self.record_extreme(series, data, metadata, f64::max),
+            AggregationMode::Min => self.record_extreme(series, data, metadata, f64::min),
+            AggregationMode::Mean | AggregationMode::Stdev => {
+                self.record_statistics(series, data, metadata)
+            }
+        }
+
+        emit!(AggregateEventRecorded);
+    }
+
+    fn record_sum(
+        &mut self,
+        series: metric::MetricSeries,
+        data: metric::MetricData,
+        metadata: EventMetadata,
+    ) {
+        match self.map.entry(series) {
+            Entry::Occupied(mut entry) => {
+                let existing = entry.get_mut();
+                // In order to update (add) the new and old kind's must match
+                if existing.0.kind == data.kind && existing.0.update(&data) {
+                    existing.1.merge(metadata);
+                } else {
+                    emit!(AggregateUpdateFailed);
+                    *existing = (data, metadata);
+                }
+            }
+            Entry::Vacant(entry) => {
+                entry.insert((data, metadata));
+            }
+        }
+    }
+
+    fn record_latest(
+        &mut self,
+        series: metric::MetricSeries,
+        data: metric::MetricData,
+        metadata: EventMetadata,
+    ) {
+        // Always replace/store
+        self.map.insert(series, (data, metadata));
+    }
+
+    fn record_count(
+        &mut self,
+        series: metric::MetricSeries,
+        data: metric::MetricData,
+        metadata: EventMetadata,
+    ) {
+        let (time, _, _) = data.into_parts();
+        let data = metric::MetricData::from_parts(
+            time,
+            metric::MetricKind::Incremental,
+            metric::MetricValue::Counter { value: 1.0 },
+        );
+        self.record_sum(series, data, metadata);
+    }
+
+    fn record_extreme(
+        &mut self,
+        series: metric::MetricSeries,
+        data: metric::MetricData,
+        metadata: EventMetadata,
+        pick: fn(f64, f64) -> f64,
+    ) {
+        match self.map.entry(series) {
+            Entry::Occupied(mut entry) => {
+                let existing = entry.get_mut();
+                match (existing.0.value_mut(), data.value()) {
+                    (
+                        metric::MetricValue::Counter { value },
+                        metric::MetricValue::Counter { value: new },
+                    )
+                    | (
+                        metric::MetricValue::Gauge { value },
+                        metric::MetricValue::Gauge { value: new },
+                    ) => {
+                        *value = pick(*value, *new);
                         existing.1.merge(metadata);
-                    } else {
+                    }
+                    _ => {
                         emit!(AggregateUpdateFailed);
                         *existing = (data, metadata);
                     }
                 }
-                Entry::Vacant(entry) => {
-                    entry.insert((data, metadata));
-                }
-            },
-            metric::MetricKind::Absolute => {
-                // Always replace/store
-                self.map.insert(series, (data, metadata));
             }
+            Entry::Vacant(entry) => {
+                entry.insert((data, metadata));
+            }
+        }
+    }
+
+    fn record_statistics(
+        &mut self,
+        series: metric::MetricSeries,
+        data: metric::MetricData,
+        metadata: EventMetadata,
+    ) {
+        let value = match data.value() {
+            metric::MetricValue::Counter { value } | metric::MetricValue::Gauge { value } => *value,
+            _ => unreachable!("mode should only apply to counters and gauges"),
         };
+        let (time, kind, _) = data.into_parts();
+        let data = metric::MetricData::from_parts(time, kind, metric::MetricValue::Gauge { value });
 
-        emit!(AggregateEventRecorded);
+        match self.map.entry(series.clone()) {
+            Entry::Occupied(mut entry) => {
+                let existing = entry.get_mut();
+                existing.0 = data;
+                existing.1.merge(metadata);
+            }
+            Entry::Vacant(entry) => {
+                entry.insert((data, metadata));
+            }
+        }
+        self.statistics.entry(series).or_default().add(value);
     }
 
     fn flush_into(&mut self, output: &mut Vec<Event>) {
         let map = std::mem::take(&mut self.map);
-        for (series, entry) in map.into_iter() {
-            let metric = metric::Metric::from_parts(series, entry.0, entry.1);
+        let mut statistics = std::mem::take(&mut self.statistics);
+        // Only the series seen in this interval are kept as the baseline of the next one, so
+        // that series that stopped being received don't accumulate.
+        let mut previous = std::mem::take(&mut self.previous);
+        for (series, (mut data, metadata)) in map.into_iter() {
+            match self.mode {
+                AggregationMode::Diff => {
+                    let latest = data.clone();
+                    let previous = previous.remove(&series);
+                    self.previous.insert(series.clone(), latest);
+                    if let Some(previous) = previous {
+                        // If the change can't be computed, the latest value is the change since
+                        // the metric was reset.
+                        let _ = data.subtract(&previous);
+                    }
+                    data = data.into_incremental();
+                }
+                AggregationMode::Mean | AggregationMode::Stdev => {
+                    if let Some(stats) = statistics.remove(&series) {
+                        let value = if self.mode == AggregationMode::Mean {
+                            stats.mean
+                        } else {
+                            stats.stdev()
+                        };
+                        *data.value_mut() = metric::MetricValue::Gauge { value };
+                    }
+                }
+                _ => {}
+            }
+
+            let metric = metric::Metric::from_parts(series, data, metadata);
             output.push(Event::Metric(metric));
         }
 
@@ -192,6 +486,7 @@ mod tests {
     fn incremental() {
         let mut agg = Aggregate::new(&AggregateConfig {
             interval_ms: 1000_u64,
+            ..Default::default()
         })
         .unwrap();
 
@@ -262,6 +557,7 @@ mod tests {
     fn absolute() {
         let mut agg = Aggregate::new(&AggregateConfig {
             interval_ms: 1000_u64,
+            ..Default::default()
         })
         .unwrap();
 
@@ -327,6 +623,7 @@ mod tests {
     fn conflicting_value_type() {
         let mut agg = Aggregate::new(&AggregateConfig {
             interval_ms: 1000_u64,
+            ..Default::default()
         })
         .unwrap();
 
@@ -384,6 +681,7 @@ mod tests {
     fn conflicting_kinds() {
         let mut agg = Aggregate::new(&AggregateConfig {
             interval_ms: 1000_u64,
+            ..Default::default()
         })
         .unwrap();
 
@@ -434,6 +732,223 @@ mod tests {
         assert_eq!(&summed, &out[0]);
     }
 
+    fn aggregate_with_mode(mode: AggregationMode) -> Aggregate {
+        Aggregate::new(&AggregateConfig {
+            interval_ms: 1000_u64,
+            mode,
+        })
+        .unwrap()
+    }
+
+    fn counter(kind: metric::MetricKind, value: f64) -> Event {
+        make_metric("counter", kind, metric::MetricValue::Counter { value })
+    }
+
+    fn gauge(kind: metric::MetricKind, value: f64) -> Event {
+        make_metric("gauge", kind, metric::MetricValue::Gauge { value })
+    }
+
+    fn flush(agg: &mut Aggregate) -> Vec<Event> {
+        let mut out = vec![];
+        agg.flush_into(&mut out);
+        out
+    }
+
+    #[test]
+    fn mode_parses_from_config() {
+        let config = toml::from_str::<AggregateConfig>(r#"mode = "stdev""#).unwrap();
+        assert_eq!(config.mode, AggregationMode::Stdev);
+
+        let config = toml::from_str::<AggregateConfig>("").unwrap();
+        assert_eq!(config.mode, AggregationMode::Auto);
+    }
+
+    #[test]
+    fn mode_applicability() {
+        use metric::MetricKind::{Absolute, Incremental};
+
+        let summary = metric::MetricData::from_parts(
+            metric::MetricTime {
+                timestamp: None,
+                interval_ms: None,
+            },
+            Incremental,
+            metric::MetricValue::AggregatedSummary {
+                quantiles: vec![],
+                count: 0,
+                sum: 0.0,
+            },
+        );
+        let data = |event: Event| event.into_metric().into_parts().1;
+
+        assert!(AggregationMode::Sum.applies_to(&data(counter(Incremental, 1.0))));
+        assert!(!AggregationMode::Sum.applies_to(&data(counter(Absolute, 1.0))));
+        assert!(!AggregationMode::Sum.applies_to(&summary));
+        assert!(AggregationMode::Latest.applies_to(&data(gauge(Absolute, 1.0))));
+        assert!(!AggregationMode::Latest.applies_to(&data(gauge(Incremental, 1.0))));
+        assert!(AggregationMode::Count.applies_to(&summary));
+        assert!(AggregationMode::Auto.applies_to(&summary));
+        assert!(!AggregationMode::Diff.applies_to(&summary.clone().into_absolute()));
+        for mode in [
+            AggregationMode::Max,
+            AggregationMode::Min,
+            AggregationMode::Mean,
+            AggregationMode::Stdev,
+        ] {
+            assert!(mode.applies_to(&data(gauge(Absolute, 1.0))));
+            assert!(mode.applies_to(&data(counter(Absolute, 1.0))));
+            assert!(!mode.applies_to(&data(gauge(Incremental, 1.0))));
+            assert!(!mode.applies_to(&summary.clone().into_absolute()));
+        }
+    }
+
+    #[test]
+    fn mode_mismatch_drops_metric() {
+        let mut agg = aggregate_with_mode(AggregationMode::Sum);
+
+        agg.record(counter(metric::MetricKind::Incremental, 1.0));
+        agg.record(gauge(metric::MetricKind::Absolute, 2.0));
+
+        let out = flush(&mut agg);
+        assert_eq!(1, out.len());
+        assert_eq!(counter(metric::MetricKind::Incremental, 1.0), out[0]);
+    }
+
+    #[test]
+    fn mode_sum() {
+        let mut agg = aggregate_with_mode(AggregationMode::Sum);
+
+        agg.record(counter(metric::MetricKind::Incremental, 1.0));
+        agg.record(counter(metric::MetricKind::Incremental, 2.0));
+
+        let out = flush(&mut agg);
+        assert_eq!(1, out.len());
+        assert_eq!(counter(metric::MetricKind::Incremental, 3.0), out[0]);
+    }
+
+    #[test]
+    fn mode_latest() {
+        let mut agg = aggregate_with_mode(AggregationMode::Latest);
+
+        agg.record(gauge(metric::MetricKind::Absolute, 3.0));
+        agg.record(gauge(metric::MetricKind::Absolute, 1.0));
+        agg.record(gauge(metric::MetricKind::Incremental, 5.0));
+
+        let out = flush(&mut agg);
+        assert_eq!(1, out.len());
+        assert_eq!(gauge(metric::MetricKind::Absolute, 1.0), out[0]);
+    }
+
+    #[test]
+    fn mode_count() {
+        let mut agg = aggregate_with_mode(AggregationMode::Count);
+
+        agg.record(gauge(metric::MetricKind::Absolute, 3.0));
+        agg.record(gauge(metric::MetricKind::Absolute, 1.0));
+        agg.record(gauge(metric::MetricKind::Incremental, 5.0));
+
+        let out = flush(&mut agg);
+        assert_eq!(1, out.len());
+        assert_eq!(
+            make_metric(
+                "gauge",
+                metric::MetricKind::Incremental,
+                metric::MetricValue::Counter { value: 3.0 },
+            ),
+            out[0]
+        );
+    }
+
+    #[test]
+    fn mode_diff() {
+        let mut agg = aggregate_with_mode(AggregationMode::Diff);
+
+        // The first change is computed from the first value seen.
+        agg.record(gauge(metric::MetricKind::Absolute, 3.0));
+        agg.record(gauge(metric::MetricKind::Absolute, 5.0));
+        let out = flush(&mut agg);
+        assert_eq!(1, out.len());
+        assert_eq!(gauge(metric::MetricKind::Incremental, 2.0), out[0]);
+
+        // Later changes are computed from the value at the previous flush.
+        agg.record(gauge(metric::MetricKind::Absolute, 4.0));
+        agg.record(gauge(metric::MetricKind::Absolute, 1.0));
+        let out = flush(&mut agg);
+        assert_eq!(1, out.len());
+        assert_eq!(gauge(metric::MetricKind::Incremental, -4.0), out[0]);
+
+        // Nothing is emitted for a series that wasn't seen, and its value is forgotten.
+        assert!(flush(&mut agg).is_empty());
+        assert!(agg.previous.is_empty());
+
+        // So its next change is computed from its first value again.
+        agg.record(gauge(metric::MetricKind::Absolute, 6.0));
+        agg.record(gauge(metric::MetricKind::Absolute, 8.0));
+        let out = flush(&mut agg);
+        assert_eq!(1, out.len());
+        assert_eq!(gauge(metric::MetricKind::Incremental, 2.0), out[0]);
+
+        // A counter that went backwards was reset, so its latest value is the change.
+        agg.record(counter(metric::MetricKind::Absolute, 10.0));
+        let out = flush(&mut agg);
+        assert_eq!(counter(metric::MetricKind::Incremental, 0.0), out[0]);
+        agg.record(counter(metric::MetricKind::Absolute, 4.0));
+        let out = flush(&mut agg);
+        assert_eq!(counter(metric::MetricKind::Incremental, 4.0), out[0]);
+    }
+
+    #[test]
+    fn mode_max_and_min() {
+        let mut max = aggregate_with_mode(AggregationMode::Max);
+        let mut min = aggregate_with_mode(AggregationMode::Min);
+
+        for value in [3.0, 7.0, -2.0, 5.0] {
+            max.record(gauge(metric::MetricKind::Absolute, value));
+            min.record(gauge(metric::MetricKind::Absolute, value));
+        }
+
+        let out = flush(&mut max);
+        assert_eq!(1, out.len());
+        assert_eq!(gauge(metric::MetricKind::Absolute, 7.0), out[0]);
+
+        let out = flush(&mut min);
+        assert_eq!(1, out.len());
+        assert_eq!(gauge(metric::MetricKind::Absolute, -2.0), out[0]);
+    }
+
+    #[test]
+    fn mode_mean_and_stdev() {
+        let mut mean = aggregate_with_mode(AggregationMode::Mean);
+        let mut stdev = aggregate_with_mode(AggregationMode::Stdev);
+
+        for value in [2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0] {
+            mean.record(counter(metric::MetricKind::Absolute, value));
+            stdev.record(counter(metric::MetricKind::Absolute, value));
+        }
+
+        // Both emit gauges, regardless of the type of the metrics they aggregate.
+        let expected = |value| {
+            make_metric(
+                "counter",
+                metric::MetricKind::Absolute,
+                metric::MetricValue::Gauge { value },
+            )
+        };
+
+        let out = flush(&mut mean);
+        assert_eq!(1, out.len());
+        assert_eq!(expected(5.0), out[0]);
+
+        let out = flush(&mut stdev);
+        assert_eq!(1, out.len());
+        assert_eq!(expected(2.0), out[0]);
+
+        // Statistics don't carry over between flushes.
+        mean.record(counter(metric::MetricKind::Absolute, 1.0));
+        let out = flush(&mut mean);
+        assert_eq!(expected(1.0), out[0]);
+    }
+
     #[tokio::test]
     async fn transform_shutdown() {
         let agg = toml::from_str::<AggregateConfig>(
@@ -1,12 +1,20 @@
-use std::{future::ready, num::NonZeroUsize, pin::Pin};
+use std::{
+    io::{self, ErrorKind},
+    num::NonZeroUsize,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
 
 use bytes::Bytes;
-use futures::{Stream, StreamExt};
 use lru::LruCache;
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+use tokio::sync::OwnedMutexGuard;
 use vector_lib::config::{clone_input_definitions, LogNamespace};
 use vector_lib::configurable::configurable_component;
 use vector_lib::lookup::lookup_v2::ConfigTargetPath;
-use vrl::path::OwnedTargetPath;
+use vector_lib::transform::{SyncTransform, TransformOutputsBuf};
 
 use crate::{
     config::{
@@ -14,11 +22,17 @@ use crate::{
         TransformOutput,
     },
     event::{Event, Value},
-    internal_events::DedupeEventsDropped,
+    internal_events::{DedupeCacheLoadError, DedupeCachePersistError, DedupeEventsDropped},
     schema,
-    transforms::{TaskTransform, Transform},
+    state_file::{self, StateFileLock},
+    transforms::Transform,
 };
 
+/// The name of the output duplicate events are sent to when `reroute_duplicates` is enabled.
+pub(crate) const DUPLICATES_OUTPUT: &str = "duplicates";
+
+const CACHE_FILE_NAME: &str = "cache.mp";
+
 /// Options to control what fields to match against.
 ///
 /// When no field matching configuration is specified, events are matched using the `timestamp`,
@@ -70,6 +84,25 @@ pub struct CacheConfig {
     pub num_events: NonZeroUsize,
 }
 
+/// Time-based expiration configuration for deduplication.
+#[serde_as]
+#[configurable_component]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct TimedCacheConfig {
+    /// The maximum amount of time an event is kept in the cache, in seconds.
+    ///
+    /// Once an event is older than this, matching events are no longer considered duplicates of
+    /// it, and the next matching event takes its place in the cache.
+    #[serde_as(as = "serde_with::DurationSecondsWithFrac<f64>")]
+    #[configurable(metadata(docs::human_name = "Maximum Age"))]
+    pub max_age: Duration,
+
+    /// Whether or not a duplicate event resets the age of the cached event it matched.
+    #[serde(default)]
+    pub refresh_on_drop: bool,
+}
+
 /// Configuration for the `dedupe` transform.
 #[configurable_component(transform("dedupe", "Deduplicate logs passing through a topology."))]
 #[derive(Clone, Debug)]
@@ -82,6 +115,39 @@ pub struct DedupeConfig {
     #[configurable(derived)]
     #[serde(default = "default_cache_config")]
     pub cache: CacheConfig,
+
+    #[configurable(derived)]
+    #[serde(default)]
+    pub time_settings: Option<TimedCacheConfig>,
+
+    /// Whether or not to persist the cache to disk.
+    ///
+    /// When enabled, the cache is written to disk once the input of the transform ends, and loaded
+    /// back when it starts, so that events seen before a restart or reload are still deduplicated
+    /// afterwards. On reload, the cache is loaded as soon as the previous instance of the transform
+    /// has written it, and merged with the events seen in the meantime.
+    #[serde(default)]
+    pub flush_to_disk: bool,
+
+    /// The directory used to persist the cache when `flush_to_disk` is enabled.
+    ///
+    /// By default, the [global `data_dir` option][global_data_dir] is used.
+    /// Make sure the running user has write permissions to this directory.
+    ///
+    /// If this directory is specified, then Vector will attempt to create it.
+    ///
+    /// [global_data_dir]: https://vector.dev/docs/reference/configuration/global-options/#data_dir
+    #[serde(default)]
+    #[configurable(metadata(docs::examples = "/var/local/lib/vector/"))]
+    #[configurable(metadata(docs::human_name = "Data Directory"))]
+    pub data_dir: Option<PathBuf>,
+
+    /// Reroutes duplicate events to a named output instead of dropping them.
+    ///
+    /// When enabled, duplicate events are sent to the `<transform_name>.duplicates` output.
+    #[serde(default)]
+    #[configurable(metadata(docs::human_name = "Reroute Duplicate Events"))]
+    pub reroute_duplicates: bool,
 }
 
 fn default_cache_config() -> CacheConfig {
@@ -132,9 +198,25 @@ impl DedupeConfig {
     }
 }
 
-pub struct Dedupe {
+/// The `dedupe` transform.
+///
+/// Clones of the transform share the same cache.
+#[derive(Clone)]
+pub struct Dedupe(Arc<Mutex<DedupeState>>);
+
+struct DedupeState {
     fields: FieldMatchConfig,
-    cache: LruCache<CacheEntry, bool>,
+    cache: LruCache<CacheEntry, Instant>,
+    time_settings: Option<TimedCacheConfig>,
+    reroute_duplicates: bool,
+    cache_file: Option<CacheFile>,
+}
+
+/// The file the cache is persisted to, and the lock serializing its use across reloads.
+struct CacheFile {
+    path: PathBuf,
+    lock: StateFileLock,
+    guard: Option<OwnedMutexGuard<()>>,
 }
 
 impl GenerateConfig for DedupeConfig {
@@ -142,6 +224,10 @@ impl GenerateConfig for DedupeConfig {
         toml::Value::try_from(Self {
             fields: None,
             cache: default_cache_config(),
+            time_settings: None,
+            flush_to_disk: false,
+            data_dir: None,
+            reroute_duplicates: false,
         })
         .unwrap()
     }
@@ -150,8 +236,21 @@ impl GenerateConfig for DedupeConfig {
 #[async_trait::async_trait]
 #[typetag::serde(name = "dedupe")]
 impl TransformConfig for DedupeConfig {
-    async fn build(&self, _context: &TransformContext) -> crate::Result<Transform> {
-        Ok(Transform::event_task(Dedupe::new(self.clone())))
+    async fn build(&self, context: &TransformContext) -> crate::Result<Transform> {
+        let cache_file = if self.flush_to_disk {
+            let subdir = context.key.as_ref().map_or("dedupe", |key| key.id());
+            let data_dir = context
+                .globals
+                .resolve_and_make_data_subdir(self.data_dir.as_ref(), subdir)?;
+            Some(data_dir.join(CACHE_FILE_NAME))
+        } else {
+            None
+        };
+
+        Ok(Transform::synchronous(Dedupe::new(
+            self.clone(),
+            cache_file,
+        )))
     }
 
     fn input(&self) -> Input {
@@ -164,10 +263,17 @@ impl TransformConfig for DedupeConfig {
         input_definitions: &[(OutputId, schema::Definition)],
         _: LogNamespace,
     ) -> Vec<TransformOutput> {
-        vec![TransformOutput::new(
+        let mut outputs = vec![TransformOutput::new(
             DataType::Log,
             clone_input_definitions(input_definitions),
-        )]
+        )];
+        if self.reroute_duplicates {
+            outputs.push(
+                TransformOutput::new(DataType::Log, clone_input_definitions(input_definitions))
+                    .with_port(DUPLICATES_OUTPUT),
+            );
+        }
+        outputs
     }
 }
 
@@ -188,7 +294,7 @@ type TypeId = u8;
 ///
 /// When ignoring fields, a CacheEntry contains a vector of 3-tuples. Each
 /// element in the vector represents one field in the corresponding LogEvent.
-/// The tuples will each contain the field name, TypeId, and data as Bytes for
+/// The tuples will each contain the field path, TypeId, and data as Bytes for
 /// the corresponding field (in that order). Since the set of fields that might
 /// go into CacheEntries is not known at startup, we must store the field names
 /// as part of CacheEntries. Since Event objects store their field in alphabetic
@@ -196,10 +302,20 @@ type TypeId = u8;
 /// iterating over the fields of the incoming Events, we know that the
 /// CacheEntries for 2 equivalent events will always contain the fields in the
 /// same order.
-#[derive(PartialEq, Eq, Hash)]
+#[derive(Clone, Deserialize, PartialEq, Eq, Hash, Serialize)]
 enum CacheEntry {
     Match(Vec<Option<(TypeId, Bytes)>>),
-    Ignore(Vec<(OwnedTargetPath, TypeId, Bytes)>),
+    Ignore(Vec<(ConfigTargetPath, TypeId, Bytes)>),
+}
+
+/// The cache as persisted to disk.
+///
+/// Entries are ordered from least to most recently used, and their ages are relative to the time
+/// the cache was persisted at.
+#[derive(Deserialize, Serialize)]
+struct PersistedCache {
+    persisted_at_ms: u64,
+    entries: Vec<(CacheEntry, u64)>,
 }
 
 /// Assigns a unique number to each of the types supported by Event::Value.
@@ -218,26 +334,187 @@ const fn type_id_for_value(val: &Value) -> TypeId {
 }
 
 impl Dedupe {
-    pub fn new(config: DedupeConfig) -> Self {
+    pub fn new(config: DedupeConfig, cache_file: Option<PathBuf>) -> Self {
         let num_entries = config.cache.num_events;
         let fields = config.fill_default_fields_match();
-        Self {
+        let mut state = DedupeState {
             fields,
             cache: LruCache::new(num_entries),
+            time_settings: config.time_settings,
+            reroute_duplicates: config.reroute_duplicates,
+            cache_file: cache_file.map(|path| CacheFile {
+                lock: StateFileLock::new(&path),
+                path,
+                guard: None,
+            }),
+        };
+        state.try_load_cache();
+        Self(Arc::new(Mutex::new(state)))
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, DedupeState> {
+        self.0.lock().expect("dedupe state lock poisoned")
+    }
+}
+
+impl DedupeState {
+    fn is_duplicate(&mut self, event: &Event) -> bool {
+        let cache_entry = build_cache_entry(event, &self.fields);
+        let now = Instant::now();
+        match self.cache.get_mut(&cache_entry) {
+            Some(inserted_at) => match &self.time_settings {
+                Some(time_settings)
+                    if now.duration_since(*inserted_at) >= time_settings.max_age =>
+                {
+                    *inserted_at = now;
+                    false
+                }
+                Some(time_settings) => {
+                    if time_settings.refresh_on_drop {
+                        *inserted_at = now;
+                    }
+                    true
+                }
+                None => true,
+            },
+            None => {
+                self.cache.put(cache_entry, now);
+                false
+            }
         }
     }
 
-    fn transform_one(&mut self, event: Event) -> Option<Event> {
-        let cache_entry = build_cache_entry(&event, &self.fields);
-        if self.cache.put(cache_entry, true).is_some() {
-            emit!(DedupeEventsDropped { count: 1 });
-            None
+    fn is_expired(&self, age: Duration) -> bool {
+        self.time_settings
+            .as_ref()
+            .is_some_and(|time_settings| age >= time_settings.max_age)
+    }
+
+    /// Loads the cache persisted by a previous instance of the transform, unless that instance is
+    /// still running, in which case this is retried until it has persisted its cache.
+    fn try_load_cache(&mut self) {
+        let Some(cache_file) = self.cache_file.as_mut() else {
+            return;
+        };
+        if cache_file.guard.is_some() {
+            return;
+        }
+        let Some(guard) = cache_file.lock.try_acquire() else {
+            return;
+        };
+        cache_file.guard = Some(guard);
+        self.load_cache();
+    }
+
+    /// Loads the cache persisted by a previous instance of the transform, if any.
+    ///
+    /// Events seen since the transform started are kept, as the most recently used entries.
+    fn load_cache(&mut self) {
+        let Some(path) = self
+            .cache_file
+            .as_ref()
+            .map(|cache_file| cache_file.path.as_path())
+        else {
+            return;
+        };
+        let persisted = match read_persisted_cache(path) {
+            Ok(Some(persisted)) => persisted,
+            Ok(None) => return,
+            Err(error) => {
+                emit!(DedupeCacheLoadError { error, path });
+                return;
+            }
+        };
+
+        let seen: Vec<_> = self
+            .cache
+            .iter()
+            .rev()
+            .map(|(entry, inserted_at)| (entry.clone(), *inserted_at))
+            .collect();
+        self.cache.clear();
+
+        let now = Instant::now();
+        let since_persisted =
+            Duration::from_millis(unix_time_ms().saturating_sub(persisted.persisted_at_ms));
+        for (entry, age_ms) in persisted.entries {
+            let age = Duration::from_millis(age_ms) + since_persisted;
+            if !self.is_expired(age) {
+                self.cache.put(entry, now.checked_sub(age).unwrap_or(now));
+            }
+        }
+        for (entry, inserted_at) in seen {
+            self.cache.put(entry, inserted_at);
+        }
+    }
+
+    /// Persists the cache, so that it can be loaded by the next instance of the transform, and
+    /// releases the cache file to it.
+    fn persist_cache(&mut self) {
+        let Some(cache_file) = self.cache_file.as_ref() else {
+            return;
+        };
+        // The previous instance of the transform is still running, so it is the one that gets to
+        // persist the cache.
+        if cache_file.guard.is_none() {
+            return;
+        }
+        let path = cache_file.path.as_path();
+
+        let now = Instant::now();
+        let entries = self
+            .cache
+            .iter()
+            .rev()
+            .map(|(entry, inserted_at)| (entry, now.duration_since(*inserted_at)))
+            .filter(|(_, age)| !self.is_expired(*age))
+            .map(|(entry, age)| (entry.clone(), age.as_millis() as u64))
+            .collect();
+        let persisted = PersistedCache {
+            persisted_at_ms: unix_time_ms(),
+            entries,
+        };
+
+        if let Err(error) = write_persisted_cache(path, &persisted) {
+            emit!(DedupeCachePersistError { error, path });
+        }
+
+        if let Some(cache_file) = self.cache_file.as_mut() {
+            cache_file.guard = None;
+        }
+    }
+
+    fn transform(&mut self, event: Event, output: &mut TransformOutputsBuf) {
+        self.try_load_cache();
+        if !self.is_duplicate(&event) {
+            output.push(None, event);
+        } else if self.reroute_duplicates {
+            output.push(Some(DUPLICATES_OUTPUT), event);
         } else {
-            Some(event)
+            emit!(DedupeEventsDropped { count: 1 });
         }
     }
 }
 
+fn unix_time_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_millis() as u64)
+}
+
+fn read_persisted_cache(path: &Path) -> io::Result<Option<PersistedCache>> {
+    state_file::read(path)?
+        .map(|data| rmp_serde::from_slice(&data))
+        .transpose()
+        .map_err(|error| io::Error::new(ErrorKind::InvalidData, error))
+}
+
+fn write_persisted_cache(path: &Path, persisted: &PersistedCache) -> io::Result<()> {
+    let data = rmp_serde::to_vec(persisted)
+        .map_err(|error| io::Error::new(ErrorKind::InvalidData, error))?;
+    state_file::write_atomic(path, &data)
+}
+
 /// Takes in an Event and returns a CacheEntry to place into the LRU cache
 /// containing all relevant information for the fields that need matching
 /// against according to the specified FieldMatchConfig.
@@ -263,7 +540,7 @@ fn build_cache_entry(event: &Event, fields: &FieldMatchConfig) -> CacheEntry {
                         if let Ok(path) = ConfigTargetPath::try_from(field_name) {
                             if !fields.contains(&path) {
                                 entry.push((
-                                    path.0,
+                                    path,
                                     type_id_for_value(value),
                                     value.coerce_to_bytes(),
                                 ));
@@ -278,35 +555,37 @@ fn build_cache_entry(event: &Event, fields: &FieldMatchConfig) -> CacheEntry {
     }
 }
 
-impl TaskTransform<Event> for Dedupe {
-    fn transform(
-        self: Box<Self>,
-        task: Pin<Box<dyn Stream<Item = Event> + Send>>,
-    ) -> Pin<Box<dyn Stream<Item = Event> + Send>>
-    where
-        Self: 'static,
-    {
-        let mut inner = self;
-        Box::pin(task.filter_map(move |v| ready(inner.transform_one(v))))
+impl SyncTransform for Dedupe {
+    fn transform(&mut self, event: Event, output: &mut TransformOutputsBuf) {
+        self.state().transform(event, output);
+    }
+
+    fn finish(&mut self, _output: &mut TransformOutputsBuf) {
+        self.state().persist_cache();
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use std::sync::Arc;
+    use std::{collections::HashMap, path::PathBuf, sync::Arc, time::Duration};
 
     use tokio::sync::mpsc;
     use tokio_stream::wrappers::ReceiverStream;
     use vector_lib::config::ComponentKey;
     use vector_lib::config::OutputId;
     use vector_lib::lookup::lookup_v2::ConfigTargetPath;
+    use vector_lib::transform::{SyncTransform, TransformOutputsBuf};
 
     use crate::config::schema::Definition;
     use crate::{
+        config::{DataType, LogNamespace, TransformConfig, TransformOutput},
         event::{Event, LogEvent, ObjectMap, Value},
         test_util::components::assert_transform_compliance,
         transforms::{
-            dedupe::{CacheConfig, DedupeConfig, FieldMatchConfig},
+            dedupe::{
+                CacheConfig, Dedupe, DedupeConfig, FieldMatchConfig, TimedCacheConfig,
+                CACHE_FILE_NAME, DUPLICATES_OUTPUT,
+            },
             test::create_topology,
         },
     };
@@ -325,6 +604,10 @@ mod tests {
                 num_events: std::num::NonZeroUsize::new(num_events).expect("non-zero num_events"),
             },
             fields: Some(FieldMatchConfig::MatchFields(fields)),
+            time_settings: None,
+            flush_to_disk: false,
+            data_dir: None,
+            reroute_duplicates: false,
         }
     }
 
@@ -341,6 +624,10 @@ mod tests {
                 num_events: std::num::NonZeroUsize::new(num_events).expect("non-zero num_events"),
             },
             fields: Some(FieldMatchConfig::IgnoreFields(fields)),
+            time_settings: None,
+            flush_to_disk: false,
+            data_dir: None,
+            reroute_duplicates: false,
         }
     }
 
@@ -774,4 +1061,174 @@ mod tests {
         })
         .await;
     }
+
+    fn outputs() -> TransformOutputsBuf {
+        TransformOutputsBuf::new_with_capacity(
+            vec![
+                TransformOutput::new(DataType::Log, HashMap::new()),
+                TransformOutput::new(DataType::Log, HashMap::new()).with_port(DUPLICATES_OUTPUT),
+            ],
+            1,
+        )
+    }
+
+    fn run(dedupe: &mut Dedupe, event: Event) -> (Vec<Event>, Vec<Event>) {
+        let mut outputs = outputs();
+        dedupe.transform(event, &mut outputs);
+        (
+            outputs.drain().collect(),
+            outputs.drain_named(DUPLICATES_OUTPUT).collect(),
+        )
+    }
+
+    fn make_event(value: &str) -> Event {
+        let mut event = Event::Log(LogEvent::from("message"));
+        event.as_mut_log().insert("matched", value);
+        event
+    }
+
+    #[test]
+    fn dedupe_max_age() {
+        let mut config = make_match_transform_config(5, vec!["matched".into()]);
+        config.time_settings = Some(TimedCacheConfig {
+            max_age: Duration::from_millis(100),
+            refresh_on_drop: false,
+        });
+        let mut dedupe = Dedupe::new(config, None);
+
+        assert_eq!(run(&mut dedupe, make_event("a")).0.len(), 1);
+        assert_eq!(run(&mut dedupe, make_event("a")).0.len(), 0);
+
+        // Once the cached event is old enough, a matching event is no longer a duplicate.
+        std::thread::sleep(Duration::from_millis(150));
+        assert_eq!(run(&mut dedupe, make_event("a")).0.len(), 1);
+        assert_eq!(run(&mut dedupe, make_event("a")).0.len(), 0);
+    }
+
+    #[test]
+    fn dedupe_max_age_refresh_on_drop() {
+        let mut config = make_match_transform_config(5, vec!["matched".into()]);
+        config.time_settings = Some(TimedCacheConfig {
+            max_age: Duration::from_millis(200),
+            refresh_on_drop: true,
+        });
+        let mut dedupe = Dedupe::new(config, None);
+
+        assert_eq!(run(&mut dedupe, make_event("a")).0.len(), 1);
+
+        // Every duplicate resets the age of the cached event, so it never gets old enough.
+        for _ in 0..3 {
+            std::thread::sleep(Duration::from_millis(100));
+            assert_eq!(run(&mut dedupe, make_event("a")).0.len(), 0);
+        }
+    }
+
+    #[test]
+    fn dedupe_reroute_duplicates() {
+        let mut config = make_match_transform_config(5, vec!["matched".into()]);
+        config.reroute_duplicates = true;
+        let mut dedupe = Dedupe::new(config, None);
+
+        let event = make_event("a");
+        let (output, duplicates) = run(&mut dedupe, event.clone());
+        assert_eq!(output, vec![event.clone()]);
+        assert!(duplicates.is_empty());
+
+        let (output, duplicates) = run(&mut dedupe, event.clone());
+        assert!(output.is_empty());
+        assert_eq!(duplicates, vec![event]);
+    }
+
+    #[test]
+    fn dedupe_reroute_duplicates_output() {
+        let mut config = make_match_transform_config(5, vec!["matched".into()]);
+        let outputs = config.outputs(
+            vector_lib::enrichment::TableRegistry::default(),
+            &[],
+            LogNamespace::Legacy,
+        );
+        assert_eq!(outputs.len(), 1);
+
+        config.reroute_duplicates = true;
+        let outputs = config.outputs(
+            vector_lib::enrichment::TableRegistry::default(),
+            &[],
+            LogNamespace::Legacy,
+        );
+        assert_eq!(outputs.len(), 2);
+        assert_eq!(outputs[1].port.as_deref(), Some(DUPLICATES_OUTPUT));
+    }
+
+    fn cache_file() -> PathBuf {
+        let data_dir = crate::test_util::temp_dir();
+        std::fs::create_dir_all(&data_dir).unwrap();
+        data_dir.join(CACHE_FILE_NAME)
+    }
+
+    #[test]
+    fn dedupe_persists_cache() {
+        let cache_file = cache_file();
+        let config = make_ignore_transform_config(5, vec![]);
+
+        let mut dedupe = Dedupe::new(config.clone(), Some(cache_file.clone()));
+        assert_eq!(run(&mut dedupe, make_event("a")).0.len(), 1);
+        assert_eq!(run(&mut dedupe, make_event("b")).0.len(), 1);
+        dedupe.finish(&mut outputs());
+        assert!(cache_file.exists());
+
+        // Events seen by the previous instance are still duplicates.
+        let mut dedupe = Dedupe::new(config, Some(cache_file));
+        assert_eq!(run(&mut dedupe, make_event("a")).0.len(), 0);
+        assert_eq!(run(&mut dedupe, make_event("b")).0.len(), 0);
+        assert_eq!(run(&mut dedupe, make_event("c")).0.len(), 1);
+    }
+
+    #[test]
+    fn dedupe_persisted_cache_expires() {
+        let cache_file = cache_file();
+        let mut config = make_match_transform_config(5, vec!["matched".into()]);
+        config.time_settings = Some(TimedCacheConfig {
+            max_age: Duration::from_millis(100),
+            refresh_on_drop: false,
+        });
+
+        let mut dedupe = Dedupe::new(config.clone(), Some(cache_file.clone()));
+        assert_eq!(run(&mut dedupe, make_event("a")).0.len(), 1);
+        dedupe.finish(&mut outputs());
+
+        // Time spent while the transform isn't running counts towards the age of cached events.
+        std::thread::sleep(Duration::from_millis(150));
+        let mut dedupe = Dedupe::new(config, Some(cache_file));
+        assert_eq!(run(&mut dedupe, make_event("a")).0.len(), 1);
+    }
+
+    #[test]
+    fn dedupe_ignores_corrupted_cache() {
+        let cache_file = cache_file();
+        std::fs::write(&cache_file, b"not a cache").unwrap();
+
+        let config = make_match_transform_config(5, vec!["matched".into()]);
+        let mut dedupe = Dedupe::new(config, Some(cache_file));
+        assert_eq!(run(&mut dedupe, make_event("a")).0.len(), 1);
+        assert_eq!(run(&mut dedupe, make_event("a")).0.len(), 0);
+    }
+
+    #[test]
+    fn dedupe_reload_merges_previous_cache() {
+        let cache_file = cache_file();
+        let config = make_match_transform_config(5, vec!["matched".into()]);
+
+        let mut old = Dedupe::new(config.clone(), Some(cache_file.clone()));
+        assert_eq!(run(&mut old, make_event("a")).0.len(), 1);
+
+        // As on reload, the new instance starts before the old one has persisted its cache.
+        let mut new = Dedupe::new(config, Some(cache_file));
+        assert_eq!(run(&mut new, make_event("b")).0.len(), 1);
+
+        // Once it has, the new instance picks the cache up, and keeps the events it saw meanwhile.
+        old.finish(&mut outputs());
+        assert_eq!(run(&mut new, make_event("a")).0.len(), 0);
+        assert_eq!(run(&mut new, make_event("b")).0.len(), 0);
+        assert_eq!(run(&mut new, make_event("c")).0.len(), 1);
+    }
 }
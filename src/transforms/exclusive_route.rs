@@ -0,0 +1,319 @@
+use std::collections::HashSet;
+
+use vector_lib::config::{clone_input_definitions, LogNamespace};
+use vector_lib::configurable::configurable_component;
+use vector_lib::transform::SyncTransform;
+
+use crate::{
+    conditions::{AnyCondition, Condition},
+    config::{
+        DataType, GenerateConfig, Input, OutputId, TransformConfig, TransformContext,
+        TransformOutput,
+    },
+    event::Event,
+    schema,
+    transforms::Transform,
+};
+
+pub(crate) const UNMATCHED_ROUTE: &str = "_unmatched";
+
+const RESERVED_ROUTES: [&str; 2] = [UNMATCHED_ROUTE, "_default"];
+
+#[derive(Clone)]
+pub struct ExclusiveRoute {
+    conditions: Vec<(String, Condition)>,
+}
+
+impl ExclusiveRoute {
+    pub fn new(config: &ExclusiveRouteConfig, context: &TransformContext) -> crate::Result<Self> {
+        let mut conditions = Vec::with_capacity(config.routes.len());
+        for route in &config.routes {
+            let condition = route.condition.build(&context.enrichment_tables)?;
+            conditions.push((route.name.clone(), condition));
+        }
+        Ok(Self { conditions })
+    }
+}
+
+impl SyncTransform for ExclusiveRoute {
+    fn transform(
+        &mut self,
+        mut event: Event,
+        output: &mut vector_lib::transform::TransformOutputsBuf,
+    ) {
+        for (output_name, condition) in &self.conditions {
+            let (result, checked) = condition.check(event);
+            if result {
+                output.push(Some(output_name), checked);
+                return;
+            }
+            event = checked;
+        }
+        output.push(Some(UNMATCHED_ROUTE), event);
+    }
+}
+
+/// A named route.
+#[configurable_component]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct NamedRoute {
+    /// The name of the route.
+    ///
+    /// The route can be referenced as an input by other components with the name
+    /// `<transform_name>.<name>`.
+    #[configurable(metadata(docs::examples = "errors"))]
+    pub name: String,
+
+    /// The condition an event must match to be sent to the route.
+    pub condition: AnyCondition,
+}
+
+/// Configuration for the `exclusive_route` transform.
+#[configurable_component(transform(
+    "exclusive_route",
+    "Split a stream of events into mutually exclusive sub-streams based on user-supplied conditions."
+))]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct ExclusiveRouteConfig {
+    /// An ordered list of routes.
+    ///
+    /// Each event is checked against the routes in order, and is only sent to the first route whose
+    /// condition it matches. Events that don't match any route are sent to the
+    /// `<transform_name>._unmatched` output.
+    ///
+    /// Route names must be unique, and both `_unmatched`, as well as `_default`, are reserved
+    /// output names and thus cannot be used as a route name.
+    pub routes: Vec<NamedRoute>,
+}
+
+impl GenerateConfig for ExclusiveRouteConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"
+            [[routes]]
+            name = "errors"
+            condition = '.level == "error"'
+            "#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "exclusive_route")]
+impl TransformConfig for ExclusiveRouteConfig {
+    async fn build(&self, context: &TransformContext) -> crate::Result<Transform> {
+        let route = ExclusiveRoute::new(self, context)?;
+        Ok(Transform::synchronous(route))
+    }
+
+    fn input(&self) -> Input {
+        Input::all()
+    }
+
+    fn validate(&self, _: &schema::Definition) -> Result<(), Vec<String>> {
+        let mut errors = Vec::new();
+        let mut names = HashSet::new();
+        for route in &self.routes {
+            if RESERVED_ROUTES.contains(&route.name.as_str()) {
+                errors.push(format!(
+                    "cannot have a named output with reserved name: `{}`",
+                    route.name
+                ));
+            } else if !names.insert(route.name.as_str()) {
+                errors.push(format!(
+                    "cannot have more than one route named `{}`",
+                    route.name
+                ));
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    fn outputs(
+        &self,
+        _: vector_lib::enrichment::TableRegistry,
+        input_definitions: &[(OutputId, schema::Definition)],
+        _: LogNamespace,
+    ) -> Vec<TransformOutput> {
+        self.routes
+            .iter()
+            .map(|route| route.name.as_str())
+            .chain(std::iter::once(UNMATCHED_ROUTE))
+            .map(|output_name| {
+                TransformOutput::new(DataType::all(), clone_input_definitions(input_definitions))
+                    .with_port(output_name)
+            })
+            .collect()
+    }
+
+    fn enable_concurrency(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use indoc::indoc;
+    use vector_lib::transform::TransformOutputsBuf;
+
+    use super::*;
+    use crate::{
+        config::{build_unit_tests, ConfigBuilder},
+        test_util::components::{init_test, COMPONENT_MULTIPLE_OUTPUTS_TESTS},
+    };
+
+    const CONFIG: &str = r#"
+        [[routes]]
+        name = "first"
+        condition.type = "vrl"
+        condition.source = '.message == "hello world"'
+
+        [[routes]]
+        name = "second"
+        condition.type = "vrl"
+        condition.source = '.second == "second"'
+
+        [[routes]]
+        name = "third"
+        condition.type = "vrl"
+        condition.source = '.third == "third"'
+    "#;
+
+    fn route(event: &Event) -> HashMap<&'static str, Vec<Event>> {
+        let output_names = ["first", "second", "third", UNMATCHED_ROUTE];
+        let config = toml::from_str::<ExclusiveRouteConfig>(CONFIG).unwrap();
+
+        let mut transform = ExclusiveRoute::new(&config, &Default::default()).unwrap();
+        let mut outputs = TransformOutputsBuf::new_with_capacity(
+            output_names
+                .iter()
+                .map(|output_name| {
+                    TransformOutput::new(DataType::all(), HashMap::new())
+                        .with_port(output_name.to_owned())
+                })
+                .collect(),
+            1,
+        );
+
+        transform.transform(event.clone(), &mut outputs);
+        output_names
+            .into_iter()
+            .map(|output_name| (output_name, outputs.drain_named(output_name).collect()))
+            .collect()
+    }
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<super::ExclusiveRouteConfig>();
+    }
+
+    #[test]
+    fn routes_to_first_match_only() {
+        let event = Event::from_json_value(
+            serde_json::json!({"message": "NOPE", "second": "second", "third": "third"}),
+            LogNamespace::Legacy,
+        )
+        .unwrap();
+
+        let outputs = route(&event);
+        assert_eq!(outputs["first"], vec![]);
+        assert_eq!(outputs["second"], vec![event]);
+        assert_eq!(outputs["third"], vec![]);
+        assert_eq!(outputs[UNMATCHED_ROUTE], vec![]);
+    }
+
+    #[test]
+    fn routes_unmatched_events() {
+        let event =
+            Event::from_json_value(serde_json::json!({"message": "NOPE"}), LogNamespace::Legacy)
+                .unwrap();
+
+        let outputs = route(&event);
+        assert_eq!(outputs["first"], vec![]);
+        assert_eq!(outputs["second"], vec![]);
+        assert_eq!(outputs["third"], vec![]);
+        assert_eq!(outputs[UNMATCHED_ROUTE], vec![event]);
+    }
+
+    #[test]
+    fn rejects_invalid_route_names() {
+        let config = toml::from_str::<ExclusiveRouteConfig>(
+            r#"
+            [[routes]]
+            name = "first"
+            condition = "true"
+
+            [[routes]]
+            name = "first"
+            condition = "true"
+
+            [[routes]]
+            name = "_unmatched"
+            condition = "true"
+
+            [[routes]]
+            name = "_default"
+            condition = "true"
+        "#,
+        )
+        .unwrap();
+
+        let errors = config
+            .validate(&schema::Definition::any())
+            .expect_err("config should be invalid");
+        assert_eq!(
+            errors,
+            vec![
+                "cannot have more than one route named `first`",
+                "cannot have a named output with reserved name: `_unmatched`",
+                "cannot have a named output with reserved name: `_default`",
+            ]
+        );
+
+        let config = toml::from_str::<ExclusiveRouteConfig>(CONFIG).unwrap();
+        assert!(config.validate(&schema::Definition::any()).is_ok());
+    }
+
+    #[tokio::test]
+    async fn exclusive_route_metrics_with_output_tag() {
+        init_test();
+
+        let config: ConfigBuilder = toml::from_str(indoc! {r#"
+            [transforms.foo]
+            inputs = []
+            type = "exclusive_route"
+            [[transforms.foo.routes]]
+                name = "first"
+                condition.type = "is_log"
+
+            [[tests]]
+            name = "metric output"
+
+            [tests.input]
+                insert_at = "foo"
+                value = "none"
+
+            [[tests.outputs]]
+                extract_from = "foo.first"
+                [[tests.outputs.conditions]]
+                type = "vrl"
+                source = "true"
+        "#})
+        .unwrap();
+
+        let mut tests = build_unit_tests(config).await.unwrap();
+        assert!(tests.remove(0).run().await.errors.is_empty());
+        // Check that metrics were emitted with output tag
+        COMPONENT_MULTIPLE_OUTPUTS_TESTS.assert(&["output"]);
+    }
+}
@@ -88,6 +88,20 @@ pub struct CounterConfig {
     pub kind: MetricKind,
 }
 
+/// Specification of a histogram derived from a log event.
+#[configurable_component]
+#[derive(Clone, Debug, Default)]
+pub struct HistogramConfig {
+    /// The upper bounds of the buckets to count the value of `field` into.
+    ///
+    /// When set, an aggregated histogram with these buckets is created. Otherwise, the value is
+    /// passed on as a distribution, leaving it to the sink to bucket it.
+    ///
+    /// The bounds must be finite and sorted in ascending order.
+    #[serde(default)]
+    pub buckets: Option<Vec<f64>>,
+}
+
 /// Specification of a metric derived from a log event.
 // TODO: While we're resolving the schema for this enum somewhat reasonably (in
 // `generate-components-docs.rb`), we have a problem where an overlapping field (overlap between two
@@ -141,7 +155,7 @@ pub enum MetricTypeConfig {
     Counter(CounterConfig),
 
     /// A histogram.
-    Histogram,
+    Histogram(HistogramConfig),
 
     /// A gauge.
     Gauge,
@@ -150,7 +164,39 @@ pub enum MetricTypeConfig {
     Set,
 
     /// A summary.
+    ///
+    /// The value is inserted into a distribution, whose quantiles are computed once it has been
+    /// merged across events, for example according to the `quantiles` option of the
+    /// `prometheus_exporter` and `prometheus_remote_write` sinks.
     Summary,
+
+    /// A sketch.
+    ///
+    /// The value is inserted into a DDSketch, which can be merged with other sketches to compute
+    /// quantiles across events.
+    Sketch,
+}
+
+impl MetricTypeConfig {
+    fn validate(&self) -> crate::Result<()> {
+        match self {
+            Self::Histogram(HistogramConfig {
+                buckets: Some(buckets),
+            }) => {
+                if buckets.is_empty() {
+                    return Err("histogram `buckets` must not be empty".into());
+                }
+                if buckets.iter().any(|bound| !bound.is_finite()) {
+                    return Err("histogram `buckets` must be finite".into());
+                }
+                if buckets.windows(2).any(|pair| pair[0] >= pair[1]) {
+                    return Err("histogram `buckets` must be sorted in ascending order".into());
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
 }
 
 impl MetricConfig {
@@ -195,6 +241,10 @@ impl GenerateConfig for LogToMetricConfig {
 #[typetag::serde(name = "log_to_metric")]
 impl TransformConfig for LogToMetricConfig {
     async fn build(&self, _context: &TransformContext) -> crate::Result<Transform> {
+        for metric in &self.metrics {
+            metric.metric.validate()?;
+        }
+
         Ok(Transform::function(LogToMetric::new(self.clone())))
     }
 
@@ -379,7 +429,28 @@ fn to_metric_with_config(config: &MetricConfig, event: &Event) -> Result<Metric,
 
             (counter.kind, MetricValue::Counter { value })
         }
-        MetricTypeConfig::Histogram => {
+        MetricTypeConfig::Histogram(histogram) => {
+            let value = value.to_string_lossy().parse().map_err(|error| {
+                TransformError::ParseFloatError {
+                    path: field.to_string(),
+                    error,
+                }
+            })?;
+
+            let distribution = MetricValue::Distribution {
+                samples: vector_lib::samples![value => 1],
+                statistic: StatisticKind::Histogram,
+            };
+            let value = match &histogram.buckets {
+                Some(buckets) => distribution
+                    .distribution_to_agg_histogram(buckets)
+                    .expect("value is a distribution"),
+                None => distribution,
+            };
+
+            (MetricKind::Incremental, value)
+        }
+        MetricTypeConfig::Summary => {
             let value = value.to_string_lossy().parse().map_err(|error| {
                 TransformError::ParseFloatError {
                     path: field.to_string(),
@@ -391,11 +462,11 @@ fn to_metric_with_config(config: &MetricConfig, event: &Event) -> Result<Metric,
                 MetricKind::Incremental,
                 MetricValue::Distribution {
                     samples: vector_lib::samples![value => 1],
-                    statistic: StatisticKind::Histogram,
+                    statistic: StatisticKind::Summary,
                 },
             )
         }
-        MetricTypeConfig::Summary => {
+        MetricTypeConfig::Sketch => {
             let value = value.to_string_lossy().parse().map_err(|error| {
                 TransformError::ParseFloatError {
                     path: field.to_string(),
@@ -403,12 +474,16 @@ fn to_metric_with_config(config: &MetricConfig, event: &Event) -> Result<Metric,
                 }
             })?;
 
+            let distribution = MetricValue::Distribution {
+                samples: vector_lib::samples![value => 1],
+                statistic: StatisticKind::Summary,
+            };
+
             (
                 MetricKind::Incremental,
-                MetricValue::Distribution {
-                    samples: vector_lib::samples![value => 1],
-                    statistic: StatisticKind::Summary,
-                },
+                distribution
+                    .distribution_to_sketch()
+                    .expect("value is a distribution"),
             )
         }
         MetricTypeConfig::Gauge => {
@@ -868,7 +943,7 @@ mod tests {
     use crate::{
         config::log_schema,
         event::{
-            metric::{Metric, MetricKind, MetricValue, StatisticKind},
+            metric::{Metric, MetricKind, MetricSketch, MetricValue, StatisticKind},
             Event, LogEvent,
         },
     };
@@ -1592,6 +1667,77 @@ mod tests {
         Event::Log(LogEvent::from_parts(log_value, metadata.clone()))
     }
 
+    #[tokio::test]
+    async fn response_time_histogram_buckets() {
+        let config = parse_config(
+            r#"
+            [[metrics]]
+            type = "histogram"
+            field = "response_time"
+            buckets = [1.0, 2.5, 5.0]
+            "#,
+        );
+
+        let event = create_event("response_time", "2.5");
+        let metric = do_transform(config, event).await.unwrap().into_metric();
+
+        assert_eq!(metric.kind(), MetricKind::Incremental);
+        assert_eq!(
+            metric.value(),
+            &MetricValue::AggregatedHistogram {
+                buckets: vector_lib::buckets![1.0 => 0, 2.5 => 1, 5.0 => 0],
+                count: 1,
+                sum: 2.5,
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn response_time_sketch() {
+        let config = parse_config(
+            r#"
+            [[metrics]]
+            type = "sketch"
+            field = "response_time"
+            "#,
+        );
+
+        let event = create_event("response_time", "2.5");
+        let metric = do_transform(config, event).await.unwrap().into_metric();
+
+        assert_eq!(metric.kind(), MetricKind::Incremental);
+        match metric.value() {
+            MetricValue::Sketch {
+                sketch: MetricSketch::AgentDDSketch(sketch),
+            } => {
+                assert_eq!(sketch.count(), 1);
+                assert_eq!(sketch.sum(), Some(2.5));
+            }
+            value => panic!("expected a sketch, got {:?}", value),
+        }
+    }
+
+    #[tokio::test]
+    async fn invalid_buckets() {
+        for config in [
+            r#"
+            [[metrics]]
+            type = "histogram"
+            field = "response_time"
+            buckets = [5.0, 1.0]
+            "#,
+            r#"
+            [[metrics]]
+            type = "histogram"
+            field = "response_time"
+            buckets = []
+            "#,
+        ] {
+            let config = parse_config(config);
+            assert!(config.build(&TransformContext::default()).await.is_err());
+        }
+    }
+
     #[tokio::test]
     async fn transform_gauge() {
         let config = parse_yaml_config(
@@ -6,7 +6,7 @@ use ordered_float::NotNan;
 use vector_lib::configurable::configurable_component;
 use vrl::event_path;
 
-use crate::event::{KeyString, LogEvent, Value};
+use crate::event::{KeyString, LogEvent, ObjectMap, Value};
 
 /// Strategies for merging events.
 #[configurable_component]
@@ -72,6 +72,10 @@ impl ReduceValueMerger for DiscardMerger {
         v.insert(event_path!(k.as_str()), self.v);
         Ok(())
     }
+
+    fn snapshot(&self) -> Value {
+        merger_snapshot("discard", self.v.clone())
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -98,6 +102,10 @@ impl ReduceValueMerger for RetainMerger {
         v.insert(event_path!(k.as_str()), self.v);
         Ok(())
     }
+
+    fn snapshot(&self) -> Value {
+        merger_snapshot("retain", self.v.clone())
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -138,6 +146,14 @@ impl ReduceValueMerger for ConcatMerger {
         v.insert(event_path!(k.as_str()), Value::Bytes(self.v.into()));
         Ok(())
     }
+
+    fn snapshot(&self) -> Value {
+        let mut snapshot = merger_snapshot("concat", Value::Bytes(self.v.clone().freeze()));
+        if let (Value::Object(fields), Some(join_by)) = (&mut snapshot, &self.join_by) {
+            fields.insert("join_by".into(), Value::Bytes(join_by.clone().into()));
+        }
+        snapshot
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -165,6 +181,10 @@ impl ReduceValueMerger for ConcatArrayMerger {
         v.insert(event_path!(k.as_str()), Value::Array(self.v));
         Ok(())
     }
+
+    fn snapshot(&self) -> Value {
+        merger_snapshot("concat_array", Value::Array(self.v.clone()))
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -188,6 +208,10 @@ impl ReduceValueMerger for ArrayMerger {
         v.insert(event_path!(k.as_str()), Value::Array(self.v));
         Ok(())
     }
+
+    fn snapshot(&self) -> Value {
+        merger_snapshot("array", Value::Array(self.v.clone()))
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -220,6 +244,10 @@ impl ReduceValueMerger for LongestArrayMerger {
         v.insert(event_path!(k.as_str()), Value::Array(self.v));
         Ok(())
     }
+
+    fn snapshot(&self) -> Value {
+        merger_snapshot("longest_array", Value::Array(self.v.clone()))
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -252,6 +280,10 @@ impl ReduceValueMerger for ShortestArrayMerger {
         v.insert(event_path!(k.as_str()), Value::Array(self.v));
         Ok(())
     }
+
+    fn snapshot(&self) -> Value {
+        merger_snapshot("shortest_array", Value::Array(self.v.clone()))
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -300,6 +332,13 @@ impl ReduceValueMerger for FlatUniqueMerger {
         );
         Ok(())
     }
+
+    fn snapshot(&self) -> Value {
+        merger_snapshot(
+            "flat_unique",
+            Value::Array(self.v.iter().cloned().collect()),
+        )
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -338,6 +377,16 @@ impl ReduceValueMerger for TimestampWindowMerger {
         v.insert(event_path!(k.as_str()), Value::Timestamp(self.started));
         Ok(())
     }
+
+    fn snapshot(&self) -> Value {
+        merger_snapshot(
+            "timestamp_window",
+            Value::Array(vec![
+                Value::Timestamp(self.started),
+                Value::Timestamp(self.latest),
+            ]),
+        )
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -358,6 +407,30 @@ impl From<NotNan<f64>> for NumberMergerValue {
     }
 }
 
+impl From<NumberMergerValue> for Value {
+    fn from(v: NumberMergerValue) -> Self {
+        match v {
+            NumberMergerValue::Int(i) => Value::Integer(i),
+            NumberMergerValue::Float(f) => Value::Float(f),
+        }
+    }
+}
+
+impl TryFrom<Value> for NumberMergerValue {
+    type Error = String;
+
+    fn try_from(v: Value) -> Result<Self, Self::Error> {
+        match v {
+            Value::Integer(i) => Ok(i.into()),
+            Value::Float(f) => Ok(f.into()),
+            _ => Err(format!(
+                "expected number value, found: '{}'",
+                v.to_string_lossy()
+            )),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 struct AddNumbersMerger {
     v: NumberMergerValue,
@@ -401,6 +474,10 @@ impl ReduceValueMerger for AddNumbersMerger {
         };
         Ok(())
     }
+
+    fn snapshot(&self) -> Value {
+        merger_snapshot("sum", self.v.clone().into())
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -460,6 +537,10 @@ impl ReduceValueMerger for MaxNumberMerger {
         };
         Ok(())
     }
+
+    fn snapshot(&self) -> Value {
+        merger_snapshot("max", self.v.clone().into())
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -519,11 +600,81 @@ impl ReduceValueMerger for MinNumberMerger {
         };
         Ok(())
     }
+
+    fn snapshot(&self) -> Value {
+        merger_snapshot("min", self.v.clone().into())
+    }
 }
 
 pub trait ReduceValueMerger: std::fmt::Debug + Send + Sync {
     fn add(&mut self, v: Value) -> Result<(), String>;
     fn insert_into(self: Box<Self>, k: KeyString, v: &mut LogEvent) -> Result<(), String>;
+
+    /// Returns the state of the merger, from which it can be recreated with
+    /// [`restore_value_merger`].
+    fn snapshot(&self) -> Value;
+}
+
+fn merger_snapshot(merger: &str, value: Value) -> Value {
+    Value::Object(ObjectMap::from([
+        ("merger".into(), merger.into()),
+        ("value".into(), value),
+    ]))
+}
+
+/// Recreates a merger from the state returned by [`ReduceValueMerger::snapshot`].
+#[allow(clippy::mutable_key_type)] // false positive due to bytes::Bytes
+pub(crate) fn restore_value_merger(snapshot: Value) -> Result<Box<dyn ReduceValueMerger>, String> {
+    let Value::Object(mut snapshot) = snapshot else {
+        return Err(format!(
+            "expected merger snapshot, found: '{}'",
+            snapshot.to_string_lossy()
+        ));
+    };
+    let merger = match snapshot.remove("merger") {
+        Some(Value::Bytes(merger)) => merger,
+        _ => return Err("merger snapshot has no merger kind".to_string()),
+    };
+    let value = snapshot.remove("value").unwrap_or(Value::Null);
+
+    match (merger.as_ref(), value) {
+        (b"discard", v) => Ok(Box::new(DiscardMerger::new(v))),
+        (b"retain", v) => Ok(Box::new(RetainMerger::new(v))),
+        (b"concat", Value::Bytes(b)) => {
+            let join_by = match snapshot.remove("join_by") {
+                Some(Value::Bytes(join_by)) => Some(join_by.to_vec()),
+                _ => None,
+            };
+            Ok(Box::new(ConcatMerger {
+                v: BytesMut::from(&b[..]),
+                join_by,
+            }))
+        }
+        (b"concat_array", Value::Array(a)) => Ok(Box::new(ConcatArrayMerger::new(a))),
+        (b"array", Value::Array(a)) => Ok(Box::new(ArrayMerger { v: a })),
+        (b"longest_array", Value::Array(a)) => Ok(Box::new(LongestArrayMerger::new(a))),
+        (b"shortest_array", Value::Array(a)) => Ok(Box::new(ShortestArrayMerger::new(a))),
+        (b"flat_unique", Value::Array(a)) => Ok(Box::new(FlatUniqueMerger {
+            v: a.into_iter().collect(),
+        })),
+        (b"timestamp_window", Value::Array(a)) => match a.as_slice() {
+            [Value::Timestamp(started), Value::Timestamp(latest)] => {
+                Ok(Box::new(TimestampWindowMerger {
+                    started: *started,
+                    latest: *latest,
+                }))
+            }
+            _ => Err("expected start and end timestamps in merger snapshot".to_string()),
+        },
+        (b"sum", v) => Ok(Box::new(AddNumbersMerger::new(v.try_into()?))),
+        (b"max", v) => Ok(Box::new(MaxNumberMerger::new(v.try_into()?))),
+        (b"min", v) => Ok(Box::new(MinNumberMerger::new(v.try_into()?))),
+        (merger, v) => Err(format!(
+            "invalid snapshot for merger '{}': '{}'",
+            String::from_utf8_lossy(merger),
+            v.to_string_lossy()
+        )),
+    }
 }
 
 impl From<Value> for Box<dyn ReduceValueMerger> {
@@ -876,6 +1027,91 @@ mod test {
         }
     }
 
+    #[test]
+    fn restore_from_snapshot() {
+        let ts = chrono::Utc::now();
+        let cases: Vec<(Box<dyn ReduceValueMerger>, Vec<Value>)> = vec![
+            (
+                get_value_merger("foo".into(), &MergeStrategy::Discard).unwrap(),
+                vec!["bar".into(), "baz".into()],
+            ),
+            (
+                get_value_merger("foo".into(), &MergeStrategy::Retain).unwrap(),
+                vec!["bar".into(), Value::Null],
+            ),
+            (
+                get_value_merger(1.into(), &MergeStrategy::Sum).unwrap(),
+                vec![2.into(), 3.5.into()],
+            ),
+            (
+                get_value_merger(1.into(), &MergeStrategy::Max).unwrap(),
+                vec![3.into(), 2.into()],
+            ),
+            (
+                get_value_merger(1.into(), &MergeStrategy::Min).unwrap(),
+                vec![0.5.into(), 2.into()],
+            ),
+            (
+                get_value_merger("foo".into(), &MergeStrategy::Array).unwrap(),
+                vec![1.into(), json!([2]).into()],
+            ),
+            (
+                get_value_merger("foo".into(), &MergeStrategy::Concat).unwrap(),
+                vec!["bar".into(), "baz".into()],
+            ),
+            (
+                get_value_merger("foo".into(), &MergeStrategy::ConcatRaw).unwrap(),
+                vec!["bar".into(), "baz".into()],
+            ),
+            (
+                get_value_merger(json!([1]).into(), &MergeStrategy::Concat).unwrap(),
+                vec![json!([2, 3]).into(), 4.into()],
+            ),
+            (
+                get_value_merger(json!([1, 2]).into(), &MergeStrategy::ShortestArray).unwrap(),
+                vec![json!([3]).into(), json!([4, 5, 6]).into()],
+            ),
+            (
+                get_value_merger(json!([1]).into(), &MergeStrategy::LongestArray).unwrap(),
+                vec![json!([2, 3]).into(), json!([4]).into()],
+            ),
+            (
+                get_value_merger(1.into(), &MergeStrategy::FlatUnique).unwrap(),
+                vec![json!([1]).into(), 1.into()],
+            ),
+            (
+                Value::Timestamp(ts).into(),
+                vec![
+                    Value::Timestamp(ts + chrono::Duration::seconds(1)),
+                    Value::Timestamp(ts + chrono::Duration::seconds(2)),
+                ],
+            ),
+        ];
+
+        for (mut merger, additional) in cases {
+            let mut additional = additional.into_iter();
+            merger.add(additional.next().unwrap()).unwrap();
+
+            let mut restored = restore_value_merger(merger.snapshot()).unwrap();
+            let last = additional.next().unwrap();
+            merger.add(last.clone()).unwrap();
+            restored.add(last).unwrap();
+
+            let mut expected = LogEvent::default();
+            merger.insert_into("out".into(), &mut expected).unwrap();
+            let mut actual = LogEvent::default();
+            restored.insert_into("out".into(), &mut actual).unwrap();
+            assert_eq!(actual, expected);
+        }
+    }
+
+    #[test]
+    fn restore_from_invalid_snapshot() {
+        assert!(restore_value_merger("foo".into()).is_err());
+        assert!(restore_value_merger(json!({ "merger": "sum", "value": "foo" }).into()).is_err());
+        assert!(restore_value_merger(json!({ "merger": "unknown", "value": 1 }).into()).is_err());
+    }
+
     fn merge(initial: Value, additional: Value, strategy: &MergeStrategy) -> Result<Value, String> {
         let mut merger = get_value_merger(initial, strategy)?;
         merger.add(additional)?;
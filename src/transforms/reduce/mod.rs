@@ -1,24 +1,35 @@
-use futures::Stream;
+use chrono::{DateTime, Utc};
+use futures::{stream, Stream, StreamExt};
 use indexmap::IndexMap;
+use prost::Message;
 use serde_with::serde_as;
 use std::collections::BTreeMap;
 use std::{
     collections::{hash_map, HashMap},
+    fs,
+    io::{self, ErrorKind},
     num::NonZeroUsize,
+    path::{Path, PathBuf},
     pin::Pin,
     time::{Duration, Instant},
 };
 use vector_lib::configurable::configurable_component;
+use vector_lib::event::proto;
 use vector_lib::lookup::lookup_v2::parse_target_path;
 use vector_lib::lookup::PathPrefix;
+use vector_lib::ByteSizeOf;
 
 use crate::config::OutputId;
 use crate::{
     conditions::{AnyCondition, Condition},
     config::{DataType, Input, TransformConfig, TransformContext, TransformOutput},
-    event::{discriminant::Discriminant, Event, EventMetadata, LogEvent},
-    internal_events::ReduceStaleEventFlushed,
+    event::{discriminant::Discriminant, Event, EventArray, EventMetadata, LogEvent, ObjectMap},
+    internal_events::{
+        ReduceActiveGroups, ReduceGroupEvicted, ReduceStaleEventFlushed, ReduceStateLoadError,
+        ReduceStatePersistError,
+    },
     schema,
+    state_file::{self, StateFileLock},
     transforms::{TaskTransform, Transform},
 };
 
@@ -27,6 +38,7 @@ mod merge_strategy;
 use crate::config::schema::Definition;
 use crate::event::Value;
 pub use merge_strategy::*;
+use tokio::sync::OwnedMutexGuard;
 use vector_lib::config::LogNamespace;
 use vector_lib::stream::expiration_map::{map_with_expiration, Emitter};
 use vrl::value::kind::Collection;
@@ -104,8 +116,61 @@ pub struct ReduceConfig {
     /// If this condition resolves to `true` for an event, the previous transaction is flushed
     /// (without this event) and a new transaction is started.
     pub starts_when: Option<AnyCondition>,
+
+    /// The maximum number of groups to hold in memory at once.
+    ///
+    /// When this limit is exceeded, groups are flushed early, as chosen by `eviction_policy`.
+    pub max_groups: Option<NonZeroUsize>,
+
+    /// The maximum number of bytes to hold in memory across all groups at once.
+    ///
+    /// The size of a group is estimated from the in-memory size of the events added to it. When
+    /// this limit is exceeded, groups are flushed early, as chosen by `eviction_policy`, until
+    /// the total size is back under it.
+    #[configurable(metadata(docs::type_unit = "bytes"))]
+    pub max_bytes: Option<NonZeroUsize>,
+
+    #[configurable(derived)]
+    #[serde(default)]
+    pub eviction_policy: EvictionPolicy,
+
+    /// Whether or not to persist the groups in progress to disk when the transform shuts down.
+    ///
+    /// When enabled, groups that are still in progress when Vector stops, or when the transform is
+    /// reloaded, are written to disk instead of being flushed. They are restored when the
+    /// transform starts again, so that the events received afterwards are still reduced with them.
+    #[serde(default)]
+    pub persist_state: bool,
+
+    /// The directory used to persist groups when `persist_state` is enabled.
+    ///
+    /// By default, the [global `data_dir` option][global_data_dir] is used.
+    /// Make sure the running user has write permissions to this directory.
+    ///
+    /// If this directory is specified, then Vector will attempt to create it.
+    ///
+    /// [global_data_dir]: https://vector.dev/docs/reference/configuration/global-options/#data_dir
+    #[serde(default)]
+    #[configurable(metadata(docs::examples = "/var/local/lib/vector/"))]
+    #[configurable(metadata(docs::human_name = "Data Directory"))]
+    pub data_dir: Option<PathBuf>,
+}
+
+/// The policy used to choose which group to flush early when a limit is exceeded.
+#[configurable_component]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum EvictionPolicy {
+    /// Flushes the group that received its first event the earliest.
+    #[default]
+    Oldest,
+
+    /// Flushes the group holding the most bytes.
+    Largest,
 }
 
+const SNAPSHOT_FILE_NAME: &str = "groups.pb";
+
 const fn default_expire_after_ms() -> Duration {
     Duration::from_millis(30000)
 }
@@ -120,7 +185,17 @@ impl_generate_config_from_default!(ReduceConfig);
 #[typetag::serde(name = "reduce")]
 impl TransformConfig for ReduceConfig {
     async fn build(&self, context: &TransformContext) -> crate::Result<Transform> {
-        Reduce::new(self, &context.enrichment_tables).map(Transform::event_task)
+        let snapshot_file = if self.persist_state {
+            let subdir = context.key.as_ref().map_or("reduce", |key| key.id());
+            let data_dir = context
+                .globals
+                .resolve_and_make_data_subdir(self.data_dir.as_ref(), subdir)?;
+            Some(data_dir.join(SNAPSHOT_FILE_NAME))
+        } else {
+            None
+        };
+
+        Reduce::new(self, &context.enrichment_tables, snapshot_file).map(Transform::event_task)
     }
 
     fn input(&self) -> Input {
@@ -237,7 +312,9 @@ impl TransformConfig for ReduceConfig {
 #[derive(Debug)]
 struct ReduceState {
     events: usize,
+    bytes: usize,
     fields: HashMap<KeyString, Box<dyn ReduceValueMerger>>,
+    started_at: Instant,
     stale_since: Instant,
     metadata: EventMetadata,
 }
@@ -246,16 +323,20 @@ impl ReduceState {
     fn new() -> Self {
         let fields = HashMap::new();
         let metadata = EventMetadata::default();
+        let now = Instant::now();
 
         Self {
             events: 0,
-            stale_since: Instant::now(),
+            bytes: 0,
+            started_at: now,
+            stale_since: now,
             fields,
             metadata,
         }
     }
 
     fn add_event(&mut self, e: LogEvent, strategies: &IndexMap<KeyString, MergeStrategy>) {
+        self.bytes += e.size_of();
         let (value, metadata) = e.into_parts();
         self.metadata.merge(metadata);
 
@@ -303,6 +384,118 @@ impl ReduceState {
         self.events = 0;
         event
     }
+
+    /// Encodes the group as a log event, from which it can be recreated with
+    /// [`ReduceState::restore`].
+    fn snapshot(&self, discriminant: &Discriminant, clock: &Clock) -> LogEvent {
+        // Fields missing from the event are distinguished from `null` ones by wrapping each
+        // value in an object.
+        let discriminant = discriminant
+            .values()
+            .iter()
+            .map(|value| {
+                Value::Object(
+                    value
+                        .iter()
+                        .map(|value| ("value".into(), value.clone()))
+                        .collect(),
+                )
+            })
+            .collect();
+        let fields = self
+            .fields
+            .iter()
+            .map(|(k, merger)| (k.clone(), merger.snapshot()))
+            .collect();
+
+        let snapshot = ObjectMap::from([
+            ("discriminant".into(), Value::Array(discriminant)),
+            ("events".into(), Value::Integer(self.events as i64)),
+            ("bytes".into(), Value::Integer(self.bytes as i64)),
+            (
+                "started_at".into(),
+                Value::Timestamp(clock.to_timestamp(self.started_at)),
+            ),
+            (
+                "stale_since".into(),
+                Value::Timestamp(clock.to_timestamp(self.stale_since)),
+            ),
+            ("fields".into(), Value::Object(fields)),
+        ]);
+        LogEvent::from_map(snapshot, self.metadata.clone())
+    }
+
+    /// Recreates a group from the event returned by [`ReduceState::snapshot`].
+    fn restore(event: LogEvent, clock: &Clock) -> Result<(Discriminant, Self), String> {
+        let (value, metadata) = event.into_parts();
+        let Value::Object(mut snapshot) = value else {
+            return Err("expected group snapshot to be an object".to_string());
+        };
+
+        let discriminant = match snapshot.remove("discriminant") {
+            Some(Value::Array(values)) => values
+                .into_iter()
+                .map(|value| match value {
+                    Value::Object(mut value) => Ok(value.remove("value")),
+                    _ => Err("invalid discriminant in group snapshot".to_string()),
+                })
+                .collect::<Result<Vec<_>, String>>()?,
+            _ => return Err("group snapshot has no discriminant".to_string()),
+        };
+        let fields = match snapshot.remove("fields") {
+            Some(Value::Object(fields)) => fields
+                .into_iter()
+                .map(|(k, v)| restore_value_merger(v).map(|merger| (k, merger)))
+                .collect::<Result<HashMap<_, _>, String>>()?,
+            _ => return Err("group snapshot has no fields".to_string()),
+        };
+
+        let count = |key: &str| match snapshot.get(key) {
+            Some(Value::Integer(count)) => Ok(*count as usize),
+            _ => Err(format!("group snapshot has no `{}` count", key)),
+        };
+        let instant = |key: &str| match snapshot.get(key) {
+            Some(Value::Timestamp(timestamp)) => Ok(clock.to_instant(*timestamp)),
+            _ => Err(format!("group snapshot has no `{}` timestamp", key)),
+        };
+
+        let state = Self {
+            events: count("events")?,
+            bytes: count("bytes")?,
+            fields,
+            started_at: instant("started_at")?,
+            stale_since: instant("stale_since")?,
+            metadata,
+        };
+        Ok((Discriminant::from_values(discriminant), state))
+    }
+}
+
+/// Converts between monotonic and wall clock times, so that the age of groups is preserved across
+/// restarts.
+struct Clock {
+    instant: Instant,
+    timestamp: DateTime<Utc>,
+}
+
+impl Clock {
+    fn now() -> Self {
+        Self {
+            instant: Instant::now(),
+            timestamp: Utc::now(),
+        }
+    }
+
+    fn to_timestamp(&self, instant: Instant) -> DateTime<Utc> {
+        let elapsed = self.instant.saturating_duration_since(instant);
+        self.timestamp
+            - chrono::Duration::from_std(elapsed).unwrap_or_else(|_| chrono::Duration::zero())
+    }
+
+    fn to_instant(&self, timestamp: DateTime<Utc>) -> Instant {
+        let elapsed = (self.timestamp - timestamp).to_std().unwrap_or_default();
+        self.instant.checked_sub(elapsed).unwrap_or(self.instant)
+    }
 }
 
 pub struct Reduce {
@@ -314,12 +507,19 @@ pub struct Reduce {
     ends_when: Option<Condition>,
     starts_when: Option<Condition>,
     max_events: Option<usize>,
+    max_groups: Option<usize>,
+    max_bytes: Option<usize>,
+    eviction_policy: EvictionPolicy,
+    bytes: usize,
+    snapshot_file: Option<(PathBuf, StateFileLock)>,
+    snapshot_guard: Option<OwnedMutexGuard<()>>,
 }
 
 impl Reduce {
     pub fn new(
         config: &ReduceConfig,
         enrichment_tables: &vector_lib::enrichment::TableRegistry,
+        snapshot_file: Option<PathBuf>,
     ) -> crate::Result<Self> {
         if config.ends_when.is_some() && config.starts_when.is_some() {
             return Err("only one of `ends_when` and `starts_when` can be provided".into());
@@ -338,7 +538,7 @@ impl Reduce {
         let group_by = config.group_by.clone().into_iter().collect();
         let max_events = config.max_events.map(|max| max.into());
 
-        Ok(Reduce {
+        let reduce = Reduce {
             expire_after: config.expire_after_ms,
             flush_period: config.flush_period_ms,
             group_by,
@@ -347,7 +547,30 @@ impl Reduce {
             ends_when,
             starts_when,
             max_events,
-        })
+            max_groups: config.max_groups.map(Into::into),
+            max_bytes: config.max_bytes.map(Into::into),
+            eviction_policy: config.eviction_policy,
+            bytes: 0,
+            snapshot_file: snapshot_file.map(|path| {
+                let lock = StateFileLock::new(&path);
+                (path, lock)
+            }),
+            snapshot_guard: None,
+        };
+        Ok(reduce)
+    }
+
+    fn insert_state(&mut self, discriminant: Discriminant, state: ReduceState) {
+        self.bytes += state.bytes;
+        if let Some(previous) = self.reduce_merge_states.insert(discriminant, state) {
+            self.bytes -= previous.bytes;
+        }
+    }
+
+    fn remove_state(&mut self, discriminant: &Discriminant) -> Option<ReduceState> {
+        let state = self.reduce_merge_states.remove(discriminant)?;
+        self.bytes -= state.bytes;
+        Some(state)
     }
 
     fn flush_into(&mut self, emitter: &mut Emitter<Event>) {
@@ -359,20 +582,135 @@ impl Reduce {
             }
         }
         for k in &flush_discriminants {
-            if let Some(t) = self.reduce_merge_states.remove(k) {
+            if let Some(t) = self.remove_state(k) {
                 emit!(ReduceStaleEventFlushed);
                 emitter.emit(Event::from(t.flush()));
             }
         }
+        emit!(ReduceActiveGroups {
+            count: self.reduce_merge_states.len()
+        });
     }
 
     fn flush_all_into(&mut self, emitter: &mut Emitter<Event>) {
-        self.reduce_merge_states
-            .drain()
-            .for_each(|(_, s)| emitter.emit(Event::from(s.flush())));
+        if !self.persist_state() {
+            self.reduce_merge_states
+                .drain()
+                .for_each(|(_, s)| emitter.emit(Event::from(s.flush())));
+        }
+        self.bytes = 0;
+        emit!(ReduceActiveGroups { count: 0 });
+
+        // Let the instance replacing this one, if any, restore the groups now that they're
+        // persisted.
+        self.snapshot_guard = None;
+    }
+
+    /// Waits for the previous instance of the transform, if it is still shutting down, to persist
+    /// its groups, and then restores them.
+    async fn acquire_state(&mut self) {
+        if let Some((_, lock)) = self.snapshot_file.as_ref() {
+            self.snapshot_guard = Some(lock.acquire().await);
+            self.load_state();
+        }
+    }
+
+    /// Restores the groups persisted by a previous instance of the transform, if any.
+    fn load_state(&mut self) {
+        let Some(path) = self.snapshot_file.as_ref().map(|(path, _)| path.clone()) else {
+            return;
+        };
+        let groups = match read_snapshot(&path) {
+            Ok(Some(groups)) => groups,
+            Ok(None) => return,
+            Err(error) => {
+                emit!(ReduceStateLoadError { error, path: &path });
+                return;
+            }
+        };
+
+        let clock = Clock::now();
+        for group in groups {
+            match ReduceState::restore(group, &clock) {
+                Ok((discriminant, state)) => self.insert_state(discriminant, state),
+                Err(error) => emit!(ReduceStateLoadError {
+                    error: io::Error::new(ErrorKind::InvalidData, error),
+                    path: &path,
+                }),
+            }
+        }
+
+        // The groups are persisted again when the transform shuts down. Until then, remove the
+        // snapshot so they are not restored a second time after they have been flushed.
+        if let Err(error) = fs::remove_file(&path) {
+            emit!(ReduceStateLoadError { error, path: &path });
+        }
+    }
+
+    /// Persists the groups in progress, so that they can be restored by the next instance of the
+    /// transform. Returns `false` if they have not been persisted, and still need to be flushed.
+    fn persist_state(&mut self) -> bool {
+        let Some((path, _)) = self.snapshot_file.as_ref() else {
+            return false;
+        };
+
+        let clock = Clock::now();
+        let groups = self
+            .reduce_merge_states
+            .iter()
+            .map(|(discriminant, state)| state.snapshot(discriminant, &clock))
+            .collect();
+
+        match write_snapshot(path, groups) {
+            Ok(()) => {
+                self.reduce_merge_states.clear();
+                true
+            }
+            Err(error) => {
+                emit!(ReduceStatePersistError { error, path });
+                false
+            }
+        }
+    }
+
+    /// Flushes groups early, as chosen by the eviction policy, until the number of groups and the
+    /// bytes they hold are within the configured limits.
+    fn enforce_limits(&mut self, emitter: &mut Emitter<Event>) {
+        while let Some(reason) = self.exceeded_limit() {
+            let Some(discriminant) = self.eviction_candidate() else {
+                break;
+            };
+            if let Some(state) = self.remove_state(&discriminant) {
+                emit!(ReduceGroupEvicted { reason });
+                emitter.emit(state.flush().into());
+            }
+        }
+    }
+
+    fn exceeded_limit(&self) -> Option<&'static str> {
+        if self
+            .max_groups
+            .is_some_and(|max| self.reduce_merge_states.len() > max)
+        {
+            Some("max_groups")
+        } else if self.max_bytes.is_some_and(|max| self.bytes > max) {
+            Some("max_bytes")
+        } else {
+            None
+        }
+    }
+
+    fn eviction_candidate(&self) -> Option<Discriminant> {
+        let states = self.reduce_merge_states.iter();
+        match self.eviction_policy {
+            EvictionPolicy::Oldest => states.min_by_key(|(_, state)| state.started_at),
+            EvictionPolicy::Largest => states.max_by_key(|(_, state)| state.bytes),
+        }
+        .map(|(discriminant, _)| discriminant.clone())
     }
 
     fn push_or_new_reduce_state(&mut self, event: LogEvent, discriminant: Discriminant) {
+        let bytes = event.size_of();
         match self.reduce_merge_states.entry(discriminant) {
             hash_map::Entry::Vacant(entry) => {
                 let mut state = ReduceState::new();
@@ -383,6 +721,7 @@ impl Reduce {
                 entry.get_mut().add_event(event, &self.merge_strategies);
             }
         }
+        self.bytes += bytes;
     }
 
     pub(crate) fn transform_one(&mut self, emitter: &mut Emitter<Event>, event: Event) {
@@ -411,13 +750,13 @@ impl Reduce {
         }
 
         if starts_here {
-            if let Some(state) = self.reduce_merge_states.remove(&discriminant) {
+            if let Some(state) = self.remove_state(&discriminant) {
                 emitter.emit(state.flush().into());
             }
 
             self.push_or_new_reduce_state(event, discriminant)
         } else if ends_here {
-            emitter.emit(match self.reduce_merge_states.remove(&discriminant) {
+            emitter.emit(match self.remove_state(&discriminant) {
                 Some(mut state) => {
                     state.add_event(event, &self.merge_strategies);
                     state.flush().into()
@@ -431,9 +770,35 @@ impl Reduce {
         } else {
             self.push_or_new_reduce_state(event, discriminant)
         }
+
+        self.enforce_limits(emitter);
+        emit!(ReduceActiveGroups {
+            count: self.reduce_merge_states.len()
+        });
     }
 }
 
+fn read_snapshot(path: &Path) -> io::Result<Option<Vec<LogEvent>>> {
+    let Some(data) = state_file::read(path)? else {
+        return Ok(None);
+    };
+
+    let events = proto::EventArray::decode(&data[..])
+        .map_err(|error| io::Error::new(ErrorKind::InvalidData, error))?;
+    match events.events.is_some().then(|| EventArray::from(events)) {
+        Some(EventArray::Logs(groups)) => Ok(Some(groups)),
+        _ => Err(io::Error::new(
+            ErrorKind::InvalidData,
+            "snapshot does not contain groups",
+        )),
+    }
+}
+
+fn write_snapshot(path: &Path, groups: Vec<LogEvent>) -> io::Result<()> {
+    let data = proto::EventArray::from(EventArray::Logs(groups)).encode_to_vec();
+    state_file::write_atomic(path, &data)
+}
+
 impl TaskTransform<Event> for Reduce {
     fn transform(
         self: Box<Self>,
@@ -443,24 +808,31 @@ impl TaskTransform<Event> for Reduce {
         Self: 'static,
     {
         let flush_period = self.flush_period;
-
-        Box::pin(map_with_expiration(
-            self,
-            input_rx,
-            flush_period,
-            |me: &mut Box<Reduce>, event, emitter: &mut Emitter<Event>| {
-                // called for each event
-                me.transform_one(emitter, event);
-            },
-            |me: &mut Box<Reduce>, emitter: &mut Emitter<Event>| {
-                // called periodically to check for expired events
-                me.flush_into(emitter);
-            },
-            |me: &mut Box<Reduce>, emitter: &mut Emitter<Event>| {
-                // called when the input stream ends
-                me.flush_all_into(emitter);
-            },
-        ))
+        let mut me = self;
+
+        Box::pin(
+            stream::once(async move {
+                me.acquire_state().await;
+                map_with_expiration(
+                    me,
+                    input_rx,
+                    flush_period,
+                    |me: &mut Box<Reduce>, event, emitter: &mut Emitter<Event>| {
+                        // called for each event
+                        me.transform_one(emitter, event);
+                    },
+                    |me: &mut Box<Reduce>, emitter: &mut Emitter<Event>| {
+                        // called periodically to check for expired events
+                        me.flush_into(emitter);
+                    },
+                    |me: &mut Box<Reduce>, emitter: &mut Emitter<Event>| {
+                        // called when the input stream ends
+                        me.flush_all_into(emitter);
+                    },
+                )
+            })
+            .flatten(),
+        )
     }
 }
 
@@ -473,6 +845,8 @@ mod test {
     use vector_lib::enrichment::TableRegistry;
     use vrl::value::Kind;
 
+    use futures::StreamExt;
+
     use super::*;
     use crate::config::schema::Definition;
     use crate::event::{LogEvent, Value};
@@ -966,4 +1340,216 @@ merge_strategies.bar = "concat"
         })
         .await;
     }
+
+    async fn run(reduce: Reduce, events: Vec<LogEvent>) -> Vec<LogEvent> {
+        let input = futures::stream::iter(events.into_iter().map(Event::from));
+        Box::new(reduce)
+            .transform(Box::pin(input))
+            .map(Event::into_log)
+            .collect()
+            .await
+    }
+
+    fn make_event(id: &str, message: &str) -> LogEvent {
+        let mut event = LogEvent::from(message);
+        event.insert("id", id);
+        event
+    }
+
+    fn limits_config(limits: &str) -> ReduceConfig {
+        toml::from_str::<ReduceConfig>(&format!(
+            r#"
+group_by = [ "id" ]
+merge_strategies.message = "array"
+{}
+"#,
+            limits
+        ))
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn max_groups_evicts_oldest() {
+        let config = limits_config("max_groups = 2");
+        let reduce = Reduce::new(&config, &TableRegistry::default(), None).unwrap();
+
+        let output = run(
+            reduce,
+            vec![
+                make_event("1", "test 1"),
+                make_event("2", "test 2"),
+                make_event("1", "test 3"),
+                make_event("3", "test 4"),
+            ],
+        )
+        .await;
+
+        assert_eq!(output.len(), 3);
+        assert_eq!(output[0]["message"], vec!["test 1", "test 3"].into());
+    }
+
+    #[tokio::test]
+    async fn max_groups_evicts_largest() {
+        let config = limits_config(
+            r#"
+max_groups = 2
+eviction_policy = "largest"
+"#,
+        );
+        let reduce = Reduce::new(&config, &TableRegistry::default(), None).unwrap();
+
+        let large = "x".repeat(1024);
+        let output = run(
+            reduce,
+            vec![
+                make_event("1", "test 1"),
+                make_event("2", &large),
+                make_event("3", "test 3"),
+            ],
+        )
+        .await;
+
+        assert_eq!(output.len(), 3);
+        assert_eq!(output[0]["message"], vec![large.as_str()].into());
+    }
+
+    #[tokio::test]
+    async fn max_bytes_evicts_groups() {
+        let events = vec![
+            make_event("1", "test 1"),
+            make_event("2", "test 2"),
+            make_event("1", "test 3"),
+        ];
+        let max_bytes = events[0].size_of() + events[1].size_of();
+        let config = limits_config(&format!("max_bytes = {}", max_bytes));
+        let reduce = Reduce::new(&config, &TableRegistry::default(), None).unwrap();
+
+        let output = run(reduce, events).await;
+
+        assert_eq!(output.len(), 2);
+        assert_eq!(output[0]["message"], vec!["test 1", "test 3"].into());
+        assert_eq!(output[1]["message"], vec!["test 2"].into());
+    }
+
+    fn snapshot_file() -> PathBuf {
+        let data_dir = crate::test_util::temp_dir();
+        std::fs::create_dir_all(&data_dir).unwrap();
+        data_dir.join(SNAPSHOT_FILE_NAME)
+    }
+
+    #[tokio::test]
+    async fn persists_state() {
+        let snapshot_file = snapshot_file();
+        let config = toml::from_str::<ReduceConfig>(
+            r#"
+group_by = [ "id" ]
+merge_strategies.message = "array"
+persist_state = true
+
+[ends_when]
+  type = "vrl"
+  source = "exists(.test_end)"
+"#,
+        )
+        .unwrap();
+
+        let reduce = Reduce::new(
+            &config,
+            &TableRegistry::default(),
+            Some(snapshot_file.clone()),
+        )
+        .unwrap();
+        let mut first = make_event("1", "test 1");
+        first.insert("counter", 1);
+        let output = run(reduce, vec![first, make_event("2", "test 2")]).await;
+        assert!(output.is_empty());
+        assert!(snapshot_file.exists());
+
+        // Groups started by the previous instance keep being reduced.
+        let reduce = Reduce::new(
+            &config,
+            &TableRegistry::default(),
+            Some(snapshot_file.clone()),
+        )
+        .unwrap();
+        let mut last = make_event("1", "test 3");
+        last.insert("counter", 2);
+        last.insert("test_end", "yep");
+        let output = run(reduce, vec![last]).await;
+        assert_eq!(output.len(), 1);
+        assert_eq!(output[0]["message"], vec!["test 1", "test 3"].into());
+        assert_eq!(output[0]["counter"], Value::from(3));
+
+        let reduce = Reduce::new(
+            &config,
+            &TableRegistry::default(),
+            Some(snapshot_file.clone()),
+        )
+        .unwrap();
+        let output = run(reduce, vec![]).await;
+        assert!(output.is_empty());
+        assert!(snapshot_file.exists());
+    }
+
+    #[tokio::test]
+    async fn ignores_corrupted_state() {
+        let snapshot_file = snapshot_file();
+        std::fs::write(&snapshot_file, b"not a snapshot").unwrap();
+
+        let config = limits_config("persist_state = true");
+        let mut reduce =
+            Reduce::new(&config, &TableRegistry::default(), Some(snapshot_file)).unwrap();
+        reduce.load_state();
+        assert!(reduce.reduce_merge_states.is_empty());
+    }
+
+    #[tokio::test]
+    async fn reload_restores_open_groups() {
+        let snapshot_file = snapshot_file();
+        let config = toml::from_str::<ReduceConfig>(
+            r#"
+group_by = [ "id" ]
+merge_strategies.message = "array"
+persist_state = true
+
+[ends_when]
+  type = "vrl"
+  source = "exists(.test_end)"
+"#,
+        )
+        .unwrap();
+
+        // The old instance is running with an open group.
+        let (tx, rx) = mpsc::channel(1);
+        let old = Reduce::new(
+            &config,
+            &TableRegistry::default(),
+            Some(snapshot_file.clone()),
+        )
+        .unwrap();
+        let mut old_out = Box::new(old).transform(Box::pin(ReceiverStream::new(rx)));
+        tx.send(make_event("1", "test 1").into()).await.unwrap();
+        assert!(futures::poll!(old_out.next()).is_pending());
+
+        // As on reload, the new instance is started before the old one has shut down.
+        let new = Reduce::new(
+            &config,
+            &TableRegistry::default(),
+            Some(snapshot_file.clone()),
+        )
+        .unwrap();
+        let mut last = make_event("1", "test 2");
+        last.insert("test_end", "yep");
+        let new_out = tokio::spawn(run(new, vec![last]));
+        tokio::task::yield_now().await;
+        assert!(!new_out.is_finished());
+
+        drop(tx);
+        assert_eq!(old_out.collect::<Vec<_>>().await.len(), 0);
+
+        let output = new_out.await.unwrap();
+        assert_eq!(output.len(), 1);
+        assert_eq!(output[0]["message"], vec!["test 1", "test 2"].into());
+        assert!(snapshot_file.exists());
+    }
 }
@@ -26,6 +26,68 @@ pub struct TagCardinalityLimitConfig {
 
     #[serde(flatten)]
     pub mode: Mode,
+
+    /// Overrides of `value_limit` and `limit_exceeded_action` for specific metrics, keyed by
+    /// metric name.
+    ///
+    /// The tags of a metric listed here are tracked separately from those of every other metric,
+    /// so its values neither count towards nor are limited by the values seen on other metrics.
+    #[serde(default)]
+    #[configurable(metadata(
+        docs::additional_props_description = "An override for a specific metric."
+    ))]
+    pub per_metric_limits: HashMap<String, PerMetricConfig>,
+
+    /// Overrides of `value_limit` for specific tags, keyed by tag key.
+    ///
+    /// These take precedence over `value_limit`, whether it is set globally or in
+    /// `per_metric_limits`. A limit of `0` rejects every value of the tag.
+    #[serde(default)]
+    #[configurable(metadata(docs::additional_props_description = "The value limit for the tag."))]
+    #[configurable(metadata(docs::examples = "example_per_tag_limits()"))]
+    pub per_tag_limits: HashMap<String, usize>,
+
+    /// Tag keys that are never limited.
+    ///
+    /// Values of these tags are not tracked, and are always accepted.
+    #[serde(default)]
+    #[configurable(metadata(docs::examples = "host"))]
+    pub excluded_tags: Vec<String>,
+
+    #[configurable(derived)]
+    #[serde(default)]
+    pub internal_metrics: TagCardinalityLimitInternalMetricsConfig,
+}
+
+/// An override of the limits applied to a specific metric.
+#[configurable_component]
+#[derive(Clone, Debug, Default)]
+#[serde(deny_unknown_fields)]
+pub struct PerMetricConfig {
+    /// How many distinct values to accept for any given key of this metric.
+    ///
+    /// If left unspecified, the global `value_limit` is used.
+    pub value_limit: Option<usize>,
+
+    /// The action to take when an event of this metric would exceed the cardinality limit.
+    ///
+    /// If left unspecified, the global `limit_exceeded_action` is used.
+    pub limit_exceeded_action: Option<LimitExceededAction>,
+}
+
+/// Configuration of internal metrics for the `tag_cardinality_limit` transform.
+#[configurable_component]
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+#[serde(deny_unknown_fields)]
+pub struct TagCardinalityLimitInternalMetricsConfig {
+    /// Whether or not to include the `metric_name` and `tag_key` tags on the
+    /// `tag_value_limit_exceeded_total` and `value_limit_reached_total` internal metrics.
+    ///
+    /// This reports which metric and tag hit its limit. Note that this defaults to false because
+    /// these tags have potentially unbounded cardinality. Only set this to true if you know that
+    /// the number of unique metric names and tag keys is bounded.
+    #[serde(default)]
+    pub include_extended_tags: bool,
 }
 
 /// Controls the approach taken for tracking tag cardinality.
@@ -67,7 +129,7 @@ pub struct BloomFilterConfig {
 /// Possible actions to take when an event arrives that would exceed the cardinality limit for one
 /// or more of its tags.
 #[configurable_component]
-#[derive(Clone, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
 pub enum LimitExceededAction {
     /// Drop the tag(s) that would exceed the configured limit.
@@ -89,12 +151,20 @@ pub(crate) const fn default_cache_size() -> usize {
     5 * 1024 // 5KB
 }
 
+fn example_per_tag_limits() -> HashMap<String, usize> {
+    HashMap::from([("user_id".to_string(), 10), ("endpoint".to_string(), 1000)])
+}
+
 impl GenerateConfig for TagCardinalityLimitConfig {
     fn generate_config() -> toml::Value {
         toml::Value::try_from(Self {
             mode: Mode::Exact,
             value_limit: default_value_limit(),
             limit_exceeded_action: default_limit_exceeded_action(),
+            per_metric_limits: HashMap::new(),
+            per_tag_limits: HashMap::new(),
+            excluded_tags: Vec::new(),
+            internal_metrics: TagCardinalityLimitInternalMetricsConfig::default(),
         })
         .unwrap()
     }
@@ -1,5 +1,5 @@
 use futures::{Stream, StreamExt};
-use hashbrown::HashMap;
+use hashbrown::{HashMap, HashSet};
 use std::{future::ready, pin::Pin};
 
 use crate::transforms::tag_cardinality_limit::config::LimitExceededAction;
@@ -25,14 +25,76 @@ use tag_value_set::AcceptedTagValueSet;
 #[derive(Debug)]
 pub struct TagCardinalityLimit {
     config: TagCardinalityLimitConfig,
+    excluded_tags: HashSet<String>,
+    /// Accepted values of tags on metrics without an entry in `per_metric_limits`.
     accepted_tags: HashMap<String, AcceptedTagValueSet>,
+    /// Accepted values of tags on metrics with an entry in `per_metric_limits`, keyed by metric
+    /// name.
+    accepted_tags_per_metric: HashMap<String, HashMap<String, AcceptedTagValueSet>>,
 }
 
 impl TagCardinalityLimit {
     fn new(config: TagCardinalityLimitConfig) -> Self {
         Self {
+            excluded_tags: config.excluded_tags.iter().cloned().collect(),
             config,
             accepted_tags: HashMap::new(),
+            accepted_tags_per_metric: HashMap::new(),
+        }
+    }
+
+    /// Returns the value limit for the given tag key on the given metric, with per-tag overrides
+    /// taking precedence over per-metric overrides.
+    fn value_limit(&self, metric_name: &str, key: &str) -> usize {
+        self.config
+            .per_tag_limits
+            .get(key)
+            .copied()
+            .or_else(|| {
+                self.config
+                    .per_metric_limits
+                    .get(metric_name)
+                    .and_then(|config| config.value_limit)
+            })
+            .unwrap_or(self.config.value_limit)
+    }
+
+    fn limit_exceeded_action(&self, metric_name: &str) -> LimitExceededAction {
+        self.config
+            .per_metric_limits
+            .get(metric_name)
+            .and_then(|config| config.limit_exceeded_action)
+            .unwrap_or(self.config.limit_exceeded_action)
+    }
+
+    /// Returns the set of accepted values for the given tag key on the given metric, creating it
+    /// if this is the first time the key has been seen.
+    fn tag_value_set_mut(
+        &mut self,
+        metric_name: &str,
+        key: &str,
+        value_limit: usize,
+    ) -> &mut AcceptedTagValueSet {
+        let accepted_tags = if self.config.per_metric_limits.contains_key(metric_name) {
+            self.accepted_tags_per_metric
+                .entry_ref(metric_name)
+                .or_default()
+        } else {
+            &mut self.accepted_tags
+        };
+        let mode = &self.config.mode;
+        accepted_tags
+            .entry_ref(key)
+            .or_insert_with(|| AcceptedTagValueSet::new(value_limit, mode))
+    }
+
+    fn tag_value_set(&self, metric_name: &str, key: &str) -> Option<&AcceptedTagValueSet> {
+        if self.config.per_metric_limits.contains_key(metric_name) {
+            self.accepted_tags_per_metric
+                .get(metric_name)
+                .and_then(|accepted_tags| accepted_tags.get(key))
+        } else {
+            self.accepted_tags.get(key)
         }
     }
 
@@ -44,10 +106,10 @@ impl TagCardinalityLimit {
     /// for the key and returns true, otherwise returns false.  A false return
     /// value indicates to the caller that the value is not accepted for this
     /// key, and the configured limit_exceeded_action should be taken.
-    fn try_accept_tag(&mut self, key: &str, value: &TagValueSet) -> bool {
-        let tag_value_set = self.accepted_tags.entry_ref(key).or_insert_with(|| {
-            AcceptedTagValueSet::new(self.config.value_limit, &self.config.mode)
-        });
+    fn try_accept_tag(&mut self, metric_name: &str, key: &str, value: &TagValueSet) -> bool {
+        let value_limit = self.value_limit(metric_name, key);
+        let include_extended_tags = self.config.internal_metrics.include_extended_tags;
+        let tag_value_set = self.tag_value_set_mut(metric_name, key, value_limit);
 
         if tag_value_set.contains(value) {
             // Tag value has already been accepted, nothing more to do.
@@ -55,12 +117,16 @@ impl TagCardinalityLimit {
         }
 
         // Tag value not yet part of the accepted set.
-        if tag_value_set.len() < self.config.value_limit {
+        if tag_value_set.len() < value_limit {
             // accept the new value
             tag_value_set.insert(value.clone());
 
-            if tag_value_set.len() == self.config.value_limit {
-                emit!(TagCardinalityValueLimitReached { key });
+            if tag_value_set.len() == value_limit {
+                emit!(TagCardinalityValueLimitReached {
+                    metric_name,
+                    key,
+                    include_extended_tags,
+                });
             }
 
             true
@@ -72,55 +138,63 @@ impl TagCardinalityLimit {
 
     /// Checks if recording a key and value corresponding to a tag on an incoming Metric would
     /// exceed the cardinality limit.
-    fn tag_limit_exceeded(&self, key: &str, value: &TagValueSet) -> bool {
-        self.accepted_tags
-            .get(key)
-            .map(|value_set| {
-                !value_set.contains(value) && value_set.len() >= self.config.value_limit
-            })
-            .unwrap_or(false)
+    fn tag_limit_exceeded(&self, metric_name: &str, key: &str, value: &TagValueSet) -> bool {
+        let value_limit = self.value_limit(metric_name, key);
+        self.tag_value_set(metric_name, key)
+            .map(|value_set| !value_set.contains(value) && value_set.len() >= value_limit)
+            // A key with no accepted values yet can only be over a limit of zero.
+            .unwrap_or(value_limit == 0)
     }
 
     /// Record a key and value corresponding to a tag on an incoming Metric.
-    fn record_tag_value(&mut self, key: &str, value: &TagValueSet) {
-        self.accepted_tags
-            .entry_ref(key)
-            .or_insert_with(|| AcceptedTagValueSet::new(self.config.value_limit, &self.config.mode))
+    fn record_tag_value(&mut self, metric_name: &str, key: &str, value: &TagValueSet) {
+        let value_limit = self.value_limit(metric_name, key);
+        self.tag_value_set_mut(metric_name, key, value_limit)
             .insert(value.clone());
     }
 
     fn transform_one(&mut self, mut event: Event) -> Option<Event> {
         let metric = event.as_mut_metric();
         let metric_name = metric.name().to_string();
+        let include_extended_tags = self.config.internal_metrics.include_extended_tags;
         if let Some(tags_map) = metric.tags_mut() {
-            match self.config.limit_exceeded_action {
+            match self.limit_exceeded_action(&metric_name) {
                 LimitExceededAction::DropEvent => {
                     // This needs to check all the tags, to ensure that the ordering of tag names
                     // doesn't change the behavior of the check.
 
                     for (key, value) in tags_map.iter_sets() {
-                        if self.tag_limit_exceeded(key, value) {
+                        if self.excluded_tags.contains(key) {
+                            continue;
+                        }
+                        if self.tag_limit_exceeded(&metric_name, key, value) {
                             emit!(TagCardinalityLimitRejectingEvent {
                                 metric_name: &metric_name,
                                 tag_key: key,
                                 tag_value: &value.to_string(),
+                                include_extended_tags,
                             });
                             return None;
                         }
                     }
                     for (key, value) in tags_map.iter_sets() {
-                        self.record_tag_value(key, value);
+                        if !self.excluded_tags.contains(key) {
+                            self.record_tag_value(&metric_name, key, value);
+                        }
                     }
                 }
                 LimitExceededAction::DropTag => {
                     tags_map.retain(|key, value| {
-                        if self.try_accept_tag(key, value) {
+                        if self.excluded_tags.contains(key)
+                            || self.try_accept_tag(&metric_name, key, value)
+                        {
                             true
                         } else {
                             emit!(TagCardinalityLimitRejectingTag {
                                 metric_name: &metric_name,
                                 tag_key: key,
                                 tag_value: &value.to_string(),
+                                include_extended_tags,
                             });
                             false
                         }
@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::sync::Arc;
 
 use vector_lib::config::ComponentKey;
@@ -12,7 +13,8 @@ use crate::event::metric::TagValue;
 use crate::event::{metric, Event, Metric, MetricTags};
 use crate::test_util::components::assert_transform_compliance;
 use crate::transforms::tag_cardinality_limit::config::{
-    default_cache_size, BloomFilterConfig, Mode,
+    default_cache_size, BloomFilterConfig, Mode, PerMetricConfig,
+    TagCardinalityLimitInternalMetricsConfig,
 };
 use crate::transforms::test::create_topology;
 use tokio::sync::mpsc;
@@ -25,11 +27,15 @@ fn generate_config() {
 }
 
 fn make_metric(tags: MetricTags) -> Event {
+    make_named_metric("event", tags)
+}
+
+fn make_named_metric(name: &str, tags: MetricTags) -> Event {
     let event_metadata = EventMetadata::default().with_source_type("unit_test_stream");
 
     Event::Metric(
         Metric::new_with_metadata(
-            "event",
+            name,
             metric::MetricKind::Incremental,
             metric::MetricValue::Counter { value: 1.0 },
             event_metadata,
@@ -38,7 +44,7 @@ fn make_metric(tags: MetricTags) -> Event {
     )
 }
 
-const fn make_transform_hashset(
+fn make_transform_hashset(
     value_limit: usize,
     limit_exceeded_action: LimitExceededAction,
 ) -> TagCardinalityLimitConfig {
@@ -46,10 +52,14 @@ const fn make_transform_hashset(
         value_limit,
         limit_exceeded_action,
         mode: Mode::Exact,
+        per_metric_limits: HashMap::new(),
+        per_tag_limits: HashMap::new(),
+        excluded_tags: Vec::new(),
+        internal_metrics: TagCardinalityLimitInternalMetricsConfig::default(),
     }
 }
 
-const fn make_transform_bloom(
+fn make_transform_bloom(
     value_limit: usize,
     limit_exceeded_action: LimitExceededAction,
 ) -> TagCardinalityLimitConfig {
@@ -59,6 +69,10 @@ const fn make_transform_bloom(
         mode: Mode::Probabilistic(BloomFilterConfig {
             cache_size_per_key: default_cache_size(),
         }),
+        per_metric_limits: HashMap::new(),
+        per_tag_limits: HashMap::new(),
+        excluded_tags: Vec::new(),
+        internal_metrics: TagCardinalityLimitInternalMetricsConfig::default(),
     }
 }
 
@@ -359,3 +373,103 @@ fn drop_event_checks_all_tags(make_tags: impl Fn(&str, &str) -> MetricTags) {
     assert_eq!(new_event3, None);
     assert_eq!(new_event4, Some(event4));
 }
+
+#[test]
+fn per_metric_limits_hashset() {
+    per_metric_limits(make_transform_hashset(1, LimitExceededAction::DropEvent));
+}
+
+#[test]
+fn per_metric_limits_bloom() {
+    per_metric_limits(make_transform_bloom(1, LimitExceededAction::DropEvent));
+}
+
+/// Test that a metric with an override gets its own limit and action, and that its values are
+/// tracked separately from other metrics.
+fn per_metric_limits(mut config: TagCardinalityLimitConfig) {
+    config.per_metric_limits.insert(
+        "important".to_string(),
+        PerMetricConfig {
+            value_limit: Some(2),
+            limit_exceeded_action: Some(LimitExceededAction::DropTag),
+        },
+    );
+    let mut transform = TagCardinalityLimit::new(config);
+
+    let event1 = make_named_metric("other", metric_tags!("tag1" => "val1"));
+    let event2 = make_named_metric("important", metric_tags!("tag1" => "val2"));
+    let event3 = make_named_metric("important", metric_tags!("tag1" => "val3"));
+    let event4 = make_named_metric("important", metric_tags!("tag1" => "val4"));
+    let event5 = make_named_metric("other", metric_tags!("tag1" => "val2"));
+
+    assert_eq!(transform.transform_one(event1.clone()), Some(event1));
+    assert_eq!(transform.transform_one(event2.clone()), Some(event2));
+    assert_eq!(transform.transform_one(event3.clone()), Some(event3));
+
+    // The override's limit of 2 is reached, and its action drops the tag.
+    let new_event4 = transform.transform_one(event4).unwrap();
+    assert!(!new_event4.as_metric().tags().unwrap().contains_key("tag1"));
+
+    // Values accepted for the overridden metric don't count for other metrics.
+    assert_eq!(transform.transform_one(event5), None);
+}
+
+#[test]
+fn per_tag_limits_hashset() {
+    per_tag_limits(make_transform_hashset(2, LimitExceededAction::DropTag));
+}
+
+#[test]
+fn per_tag_limits_bloom() {
+    per_tag_limits(make_transform_bloom(2, LimitExceededAction::DropTag));
+}
+
+/// Test that per-tag limits take precedence over the global and per-metric limits.
+fn per_tag_limits(mut config: TagCardinalityLimitConfig) {
+    config.per_tag_limits.insert("user_id".to_string(), 1);
+    config.per_tag_limits.insert("never".to_string(), 0);
+    config.per_metric_limits.insert(
+        "event".to_string(),
+        PerMetricConfig {
+            value_limit: Some(10),
+            limit_exceeded_action: None,
+        },
+    );
+    let mut transform = TagCardinalityLimit::new(config);
+
+    let event1 = make_metric(metric_tags!("user_id" => "a", "tag1" => "val1", "never" => "x"));
+    let event2 = make_metric(metric_tags!("user_id" => "b", "tag1" => "val2"));
+    let event3 = make_metric(metric_tags!("user_id" => "c", "tag1" => "val3"));
+
+    let new_event1 = transform.transform_one(event1).unwrap();
+    let tags1 = new_event1.as_metric().tags().unwrap();
+    assert!(tags1.contains_key("user_id"));
+    assert!(tags1.contains_key("tag1"));
+    assert!(!tags1.contains_key("never"));
+
+    let new_event2 = transform.transform_one(event2).unwrap();
+    let tags2 = new_event2.as_metric().tags().unwrap();
+    assert!(!tags2.contains_key("user_id"));
+    assert!(tags2.contains_key("tag1"));
+
+    // `tag1` uses the per-metric limit of 10, rather than the global limit of 2.
+    let new_event3 = transform.transform_one(event3).unwrap();
+    let tags3 = new_event3.as_metric().tags().unwrap();
+    assert!(!tags3.contains_key("user_id"));
+    assert!(tags3.contains_key("tag1"));
+}
+
+#[test]
+fn excluded_tags() {
+    let mut config = make_transform_hashset(1, LimitExceededAction::DropEvent);
+    config.excluded_tags = vec!["host".to_string()];
+    let mut transform = TagCardinalityLimit::new(config);
+
+    let event1 = make_metric(metric_tags!("host" => "a", "tag1" => "val1"));
+    let event2 = make_metric(metric_tags!("host" => "b", "tag1" => "val1"));
+    let event3 = make_metric(metric_tags!("host" => "c", "tag1" => "val2"));
+
+    assert_eq!(transform.transform_one(event1.clone()), Some(event1));
+    assert_eq!(transform.transform_one(event2.clone()), Some(event2));
+    assert_eq!(transform.transform_one(event3), None);
+}
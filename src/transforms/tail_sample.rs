@@ -0,0 +1,697 @@
+use std::{
+    num::NonZeroUsize,
+    pin::Pin,
+    time::{Duration, Instant},
+};
+
+use futures::Stream;
+use lru::LruCache;
+use serde_with::serde_as;
+use snafu::Snafu;
+use vector_lib::config::{clone_input_definitions, LogNamespace};
+use vector_lib::configurable::configurable_component;
+use vector_lib::lookup::lookup_v2::ConfigTargetPath;
+use vector_lib::stream::expiration_map::{map_with_expiration, Emitter};
+use vector_lib::ByteSizeOf;
+
+use crate::{
+    conditions::{AnyCondition, Condition},
+    config::{
+        DataType, GenerateConfig, Input, OutputId, TransformConfig, TransformContext,
+        TransformOutput,
+    },
+    event::{Event, ObjectMap, TraceEvent, Value},
+    internal_events::{TailSampleEventsDropped, TailSamplePolicyDecision, TailSampleTraceEvicted},
+    schema,
+    transforms::{TaskTransform, Transform},
+};
+
+/// A sampling policy.
+#[configurable_component]
+#[derive(Clone, Debug)]
+pub struct PolicyConfig {
+    /// The name of the policy.
+    ///
+    /// This is used as the `policy` tag of the `tail_sample_policy_decisions_total` internal
+    /// metric.
+    #[configurable(metadata(docs::examples = "errors", docs::examples = "slow_requests"))]
+    pub name: String,
+
+    #[configurable(derived)]
+    #[serde(flatten)]
+    pub policy: PolicyTypeConfig,
+}
+
+/// The criteria a trace must match to be sampled by a policy.
+#[configurable_component]
+#[derive(Clone, Debug)]
+#[serde(tag = "type", rename_all = "snake_case")]
+#[configurable(metadata(docs::enum_tag_description = "The type of policy."))]
+pub enum PolicyTypeConfig {
+    /// Samples traces with at least one span whose `error` field is set.
+    Error,
+
+    /// Samples traces with at least one span whose `duration` reaches a threshold.
+    Latency {
+        /// The minimum duration of a span, in milliseconds.
+        ///
+        /// Span durations are expected in nanoseconds.
+        #[configurable(metadata(docs::examples = 500))]
+        threshold_ms: u64,
+    },
+
+    /// Samples traces with at least one span with a matching attribute.
+    Attribute {
+        /// The name of the attribute.
+        ///
+        /// The attribute is looked up in the `meta` object of each span, and then at the root of
+        /// the span.
+        #[configurable(metadata(docs::examples = "http.status_code"))]
+        key: String,
+
+        /// The values the attribute is matched against.
+        ///
+        /// If left empty, any span that has the attribute matches.
+        #[serde(default)]
+        #[configurable(metadata(docs::examples = "500", docs::examples = "503"))]
+        values: Vec<String>,
+    },
+
+    /// Samples a fraction of traces.
+    ///
+    /// The decision is derived from a hash of the trace ID, so that every instance of Vector
+    /// makes the same decision for a given trace.
+    Probabilistic {
+        /// The fraction of traces to sample, between `0.0` and `1.0`.
+        #[configurable(metadata(docs::examples = 0.1))]
+        rate: f64,
+    },
+}
+
+/// Configuration for the `tail_sample` transform.
+#[serde_as]
+#[configurable_component(transform(
+    "tail_sample",
+    "Sample whole traces based on policies evaluated once all of their spans have been received."
+))]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct TailSampleConfig {
+    /// The sampling policies.
+    ///
+    /// A trace is sampled if it matches at least one of the policies. All policies are evaluated
+    /// for every trace, so that each of them reports its own decisions.
+    pub policies: Vec<PolicyConfig>,
+
+    /// The maximum period of time to wait after the last span of a trace is received, in seconds,
+    /// before the trace is considered complete.
+    #[serde(default = "default_decision_wait_secs")]
+    #[serde_as(as = "serde_with::DurationSecondsWithFrac<f64>")]
+    #[configurable(metadata(docs::human_name = "Decision Wait"))]
+    pub decision_wait_secs: Duration,
+
+    /// A condition used to distinguish the final event of a trace.
+    ///
+    /// If this condition resolves to `true` for an event, the trace is considered complete and a
+    /// decision is made right away, without waiting for `decision_wait_secs`.
+    pub complete_when: Option<AnyCondition>,
+
+    /// The field holding the ID of the trace an event belongs to.
+    ///
+    /// If the event doesn't have this field, the `trace_id` of its first span is used instead.
+    /// Events without a trace ID are forwarded without being sampled.
+    #[serde(default = "default_trace_id_field")]
+    #[configurable(metadata(docs::examples = "trace_id"))]
+    pub trace_id_field: ConfigTargetPath,
+
+    /// The field holding the spans of an event.
+    ///
+    /// If the event doesn't have this field, the event itself is treated as a single span.
+    #[serde(default = "default_spans_field")]
+    #[configurable(metadata(docs::examples = "spans"))]
+    pub spans_field: ConfigTargetPath,
+
+    /// The maximum number of bytes of events to buffer, across all traces.
+    ///
+    /// Once the limit is reached, a decision is made early for the traces that have been idle the
+    /// longest, until the buffered events fit within the limit again.
+    #[serde(default = "default_max_buffered_bytes")]
+    #[configurable(metadata(docs::type_unit = "bytes"))]
+    pub max_buffered_bytes: NonZeroUsize,
+
+    /// The maximum number of decisions to remember.
+    ///
+    /// Events arriving after the decision for their trace has been made follow that decision, as
+    /// long as it is remembered. Otherwise, they start a new trace.
+    #[serde(default = "default_decision_cache_size")]
+    pub decision_cache_size: NonZeroUsize,
+}
+
+const fn default_decision_wait_secs() -> Duration {
+    Duration::from_secs(30)
+}
+
+fn default_trace_id_field() -> ConfigTargetPath {
+    ConfigTargetPath::from("trace_id")
+}
+
+fn default_spans_field() -> ConfigTargetPath {
+    ConfigTargetPath::from("spans")
+}
+
+fn default_max_buffered_bytes() -> NonZeroUsize {
+    NonZeroUsize::new(100 * 1024 * 1024).expect("static non-zero number")
+}
+
+fn default_decision_cache_size() -> NonZeroUsize {
+    NonZeroUsize::new(10_000).expect("static non-zero number")
+}
+
+impl GenerateConfig for TailSampleConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"
+            [[policies]]
+            name = "errors"
+            type = "error"
+            "#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "tail_sample")]
+impl TransformConfig for TailSampleConfig {
+    async fn build(&self, context: &TransformContext) -> crate::Result<Transform> {
+        TailSample::new(self, context).map(Transform::event_task)
+    }
+
+    fn input(&self) -> Input {
+        Input::trace()
+    }
+
+    fn outputs(
+        &self,
+        _: vector_lib::enrichment::TableRegistry,
+        input_definitions: &[(OutputId, schema::Definition)],
+        _: LogNamespace,
+    ) -> Vec<TransformOutput> {
+        vec![TransformOutput::new(
+            DataType::Trace,
+            clone_input_definitions(input_definitions),
+        )]
+    }
+}
+
+#[derive(Debug, Snafu)]
+pub enum BuildError {
+    #[snafu(display("at least one policy must be configured"))]
+    NoPolicies,
+    #[snafu(display("the rate of policy `{}` must be between 0.0 and 1.0", policy))]
+    InvalidRate { policy: String },
+}
+
+impl PolicyTypeConfig {
+    /// Checks whether the trace matches the policy.
+    fn matches(&self, trace_id: &str, spans: &[&ObjectMap]) -> bool {
+        match self {
+            Self::Error => spans.iter().any(|span| match span.get("error") {
+                Some(Value::Boolean(error)) => *error,
+                Some(Value::Integer(error)) => *error != 0,
+                _ => false,
+            }),
+            Self::Latency { threshold_ms } => {
+                let threshold = i64::try_from(Duration::from_millis(*threshold_ms).as_nanos())
+                    .unwrap_or(i64::MAX);
+                spans.iter().any(|span| {
+                    matches!(span.get("duration"), Some(Value::Integer(duration)) if *duration >= threshold)
+                })
+            }
+            Self::Attribute { key, values } => spans.iter().any(|span| {
+                let attribute = span
+                    .get("meta")
+                    .and_then(Value::as_object)
+                    .and_then(|meta| meta.get(key.as_str()))
+                    .or_else(|| span.get(key.as_str()));
+                match attribute {
+                    Some(value) if values.is_empty() => !value.is_null(),
+                    Some(value) => {
+                        let value = value.to_string_lossy();
+                        values.iter().any(|expected| *expected == value)
+                    }
+                    None => false,
+                }
+            }),
+            Self::Probabilistic { rate } => {
+                (seahash::hash(trace_id.as_bytes()) as f64 / u64::MAX as f64) < *rate
+            }
+        }
+    }
+}
+
+/// The events buffered for a single trace.
+struct TraceState {
+    events: Vec<Event>,
+    size: usize,
+    last_seen: Instant,
+}
+
+pub struct TailSample {
+    policies: Vec<PolicyConfig>,
+    decision_wait: Duration,
+    complete_when: Option<Condition>,
+    trace_id_field: ConfigTargetPath,
+    spans_field: ConfigTargetPath,
+    max_buffered_bytes: usize,
+    buffered_bytes: usize,
+    /// The traces awaiting a decision, ordered from the least recently seen.
+    traces: LruCache<String, TraceState>,
+    /// Whether recently decided traces have been sampled.
+    decisions: LruCache<String, bool>,
+}
+
+impl TailSample {
+    pub fn new(config: &TailSampleConfig, context: &TransformContext) -> crate::Result<Self> {
+        if config.policies.is_empty() {
+            return Err(Box::new(BuildError::NoPolicies));
+        }
+        for policy in &config.policies {
+            if let PolicyTypeConfig::Probabilistic { rate } = policy.policy {
+                if !(0.0..=1.0).contains(&rate) {
+                    return Err(Box::new(BuildError::InvalidRate {
+                        policy: policy.name.clone(),
+                    }));
+                }
+            }
+        }
+
+        Ok(Self {
+            policies: config.policies.clone(),
+            decision_wait: config.decision_wait_secs,
+            complete_when: config
+                .complete_when
+                .as_ref()
+                .map(|condition| condition.build(&context.enrichment_tables))
+                .transpose()?,
+            trace_id_field: config.trace_id_field.clone(),
+            spans_field: config.spans_field.clone(),
+            max_buffered_bytes: config.max_buffered_bytes.get(),
+            buffered_bytes: 0,
+            traces: LruCache::unbounded(),
+            decisions: LruCache::new(config.decision_cache_size),
+        })
+    }
+
+    fn spans<'a>(&self, trace: &'a TraceEvent) -> Vec<&'a ObjectMap> {
+        match trace.get(&self.spans_field) {
+            Some(Value::Array(spans)) => spans.iter().filter_map(Value::as_object).collect(),
+            _ => vec![trace.as_map()],
+        }
+    }
+
+    fn trace_id(&self, trace: &TraceEvent) -> Option<String> {
+        trace
+            .get(&self.trace_id_field)
+            .or_else(|| {
+                self.spans(trace)
+                    .first()
+                    .and_then(|span| span.get("trace_id"))
+            })
+            .filter(|trace_id| !trace_id.is_null())
+            .map(|trace_id| trace_id.to_string_lossy().into_owned())
+    }
+
+    fn transform_one(&mut self, emitter: &mut Emitter<Event>, event: Event, now: Instant) {
+        let Some(trace_id) = self.trace_id(event.as_trace()) else {
+            emitter.emit(event);
+            return;
+        };
+
+        if let Some(sampled) = self.decisions.get(&trace_id) {
+            if *sampled {
+                emitter.emit(event);
+            } else {
+                emit!(TailSampleEventsDropped { count: 1 });
+            }
+            return;
+        }
+
+        let (complete, event) = match &self.complete_when {
+            Some(condition) => condition.check(event),
+            None => (false, event),
+        };
+
+        let size = event.size_of();
+        self.buffered_bytes += size;
+        let trace = self
+            .traces
+            .get_or_insert_mut(trace_id.clone(), || TraceState {
+                events: Vec::new(),
+                size: 0,
+                last_seen: now,
+            });
+        trace.events.push(event);
+        trace.size += size;
+        trace.last_seen = now;
+
+        if complete {
+            if let Some(trace) = self.traces.pop(&trace_id) {
+                self.decide(emitter, trace_id, trace);
+            }
+        }
+
+        while self.buffered_bytes > self.max_buffered_bytes {
+            match self.traces.pop_lru() {
+                Some((trace_id, trace)) => {
+                    emit!(TailSampleTraceEvicted);
+                    self.decide(emitter, trace_id, trace);
+                }
+                None => break,
+            }
+        }
+    }
+
+    /// Makes a decision for the traces that haven't received any span for `decision_wait`.
+    fn flush_into(&mut self, emitter: &mut Emitter<Event>, now: Instant) {
+        while let Some((_, trace)) = self.traces.peek_lru() {
+            if now.duration_since(trace.last_seen) < self.decision_wait {
+                break;
+            }
+            if let Some((trace_id, trace)) = self.traces.pop_lru() {
+                self.decide(emitter, trace_id, trace);
+            }
+        }
+    }
+
+    fn flush_all_into(&mut self, emitter: &mut Emitter<Event>) {
+        while let Some((trace_id, trace)) = self.traces.pop_lru() {
+            self.decide(emitter, trace_id, trace);
+        }
+    }
+
+    fn decide(&mut self, emitter: &mut Emitter<Event>, trace_id: String, trace: TraceState) {
+        self.buffered_bytes -= trace.size;
+
+        let spans = trace
+            .events
+            .iter()
+            .flat_map(|event| self.spans(event.as_trace()))
+            .collect::<Vec<_>>();
+        let mut sampled = false;
+        for policy in &self.policies {
+            let matched = policy.policy.matches(&trace_id, &spans);
+            emit!(TailSamplePolicyDecision {
+                policy: &policy.name,
+                sampled: matched,
+            });
+            sampled |= matched;
+        }
+
+        if sampled {
+            trace
+                .events
+                .into_iter()
+                .for_each(|event| emitter.emit(event));
+        } else {
+            emit!(TailSampleEventsDropped {
+                count: trace.events.len()
+            });
+        }
+        self.decisions.put(trace_id, sampled);
+    }
+}
+
+impl TaskTransform<Event> for TailSample {
+    fn transform(
+        self: Box<Self>,
+        input_rx: Pin<Box<dyn Stream<Item = Event> + Send>>,
+    ) -> Pin<Box<dyn Stream<Item = Event> + Send>>
+    where
+        Self: 'static,
+    {
+        let flush_period = self.decision_wait.min(Duration::from_secs(1));
+
+        Box::pin(map_with_expiration(
+            self,
+            input_rx,
+            flush_period,
+            |me: &mut Box<TailSample>, event, emitter: &mut Emitter<Event>| {
+                // called for each event
+                me.transform_one(emitter, event, Instant::now());
+            },
+            |me: &mut Box<TailSample>, emitter: &mut Emitter<Event>| {
+                // called periodically to decide on idle traces
+                me.flush_into(emitter, Instant::now());
+            },
+            |me: &mut Box<TailSample>, emitter: &mut Emitter<Event>| {
+                // called when the input stream ends
+                me.flush_all_into(emitter);
+            },
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::{stream, StreamExt};
+    use tokio::sync::mpsc;
+    use tokio_stream::wrappers::ReceiverStream;
+
+    use super::*;
+    use crate::{
+        test_util::components::assert_transform_compliance, transforms::test::create_topology,
+    };
+
+    fn span(trace_id: i64, fields: serde_json::Value) -> Event {
+        let mut span = fields;
+        span["trace_id"] = trace_id.into();
+        let span: Value = span.into();
+        Event::Trace(TraceEvent::from(ObjectMap::from([(
+            "spans".into(),
+            Value::Array(vec![span]),
+        )])))
+    }
+
+    fn trace_ids(events: &[Event]) -> Vec<String> {
+        events
+            .iter()
+            .map(|event| {
+                event.as_trace().as_map()["spans"].as_array().unwrap()[0]
+                    .as_object()
+                    .unwrap()["trace_id"]
+                    .to_string_lossy()
+                    .into_owned()
+            })
+            .collect()
+    }
+
+    async fn sample(config: &str, events: Vec<Event>) -> Vec<Event> {
+        let config = toml::from_str::<TailSampleConfig>(config).unwrap();
+        let tail_sample = TailSample::new(&config, &TransformContext::default()).unwrap();
+        Box::new(tail_sample)
+            .transform(Box::pin(stream::iter(events)))
+            .collect()
+            .await
+    }
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<TailSampleConfig>();
+    }
+
+    #[tokio::test]
+    async fn samples_whole_traces_with_errors() {
+        let output = sample(
+            r#"
+            [[policies]]
+            name = "errors"
+            type = "error"
+            "#,
+            vec![
+                span(1, serde_json::json!({"error": 0})),
+                span(2, serde_json::json!({"error": 0})),
+                span(1, serde_json::json!({"error": 1})),
+                span(2, serde_json::json!({"error": 0})),
+            ],
+        )
+        .await;
+
+        assert_eq!(trace_ids(&output), vec!["1", "1"]);
+    }
+
+    #[tokio::test]
+    async fn samples_traces_matching_any_policy() {
+        let output = sample(
+            r#"
+            [[policies]]
+            name = "slow"
+            type = "latency"
+            threshold_ms = 500
+
+            [[policies]]
+            name = "server_errors"
+            type = "attribute"
+            key = "http.status_code"
+            values = ["500", "503"]
+            "#,
+            vec![
+                span(1, serde_json::json!({"duration": 600_000_000})),
+                span(2, serde_json::json!({"duration": 100_000_000})),
+                span(3, serde_json::json!({"meta": {"http.status_code": "503"}})),
+                span(4, serde_json::json!({"meta": {"http.status_code": "200"}})),
+            ],
+        )
+        .await;
+
+        assert_eq!(trace_ids(&output), vec!["1", "3"]);
+    }
+
+    #[tokio::test]
+    async fn probabilistic_policy_is_deterministic() {
+        let config = r#"
+            [[policies]]
+            name = "sampled"
+            type = "probabilistic"
+            rate = 0.5
+        "#;
+        let events =
+            || -> Vec<Event> { (0..100).map(|id| span(id, serde_json::json!({}))).collect() };
+
+        let first = trace_ids(&sample(config, events()).await);
+        let second = trace_ids(&sample(config, events()).await);
+        assert!(!first.is_empty() && first.len() < 100);
+        assert_eq!(first, second);
+    }
+
+    #[tokio::test]
+    async fn late_spans_follow_the_decision() {
+        let output = sample(
+            r#"
+            complete_when = "exists(.spans[0].complete)"
+
+            [[policies]]
+            name = "errors"
+            type = "error"
+            "#,
+            vec![
+                span(1, serde_json::json!({"error": 1})),
+                span(2, serde_json::json!({"error": 0})),
+                span(1, serde_json::json!({"complete": true})),
+                span(2, serde_json::json!({"complete": true})),
+                span(1, serde_json::json!({"error": 0})),
+                // The trace was not sampled, even though this span has an error
+                span(2, serde_json::json!({"error": 1})),
+            ],
+        )
+        .await;
+
+        assert_eq!(trace_ids(&output), vec!["1", "1", "1"]);
+    }
+
+    #[test]
+    fn decides_on_idle_traces() {
+        let config = toml::from_str::<TailSampleConfig>(
+            r#"
+            decision_wait_secs = 10
+
+            [[policies]]
+            name = "errors"
+            type = "error"
+            "#,
+        )
+        .unwrap();
+        let mut tail_sample = TailSample::new(&config, &TransformContext::default()).unwrap();
+        let mut emitter = Emitter::new();
+        let start = Instant::now();
+
+        tail_sample.transform_one(&mut emitter, span(1, serde_json::json!({})), start);
+        tail_sample.transform_one(
+            &mut emitter,
+            span(2, serde_json::json!({})),
+            start + Duration::from_secs(5),
+        );
+
+        tail_sample.flush_into(&mut emitter, start + Duration::from_secs(10));
+        assert_eq!(tail_sample.traces.len(), 1);
+        assert!(tail_sample.decisions.contains("1"));
+
+        tail_sample.flush_into(&mut emitter, start + Duration::from_secs(15));
+        assert!(tail_sample.traces.is_empty());
+        assert_eq!(tail_sample.buffered_bytes, 0);
+    }
+
+    #[test]
+    fn bounds_buffered_bytes() {
+        let event = span(1, serde_json::json!({}));
+        let config = toml::from_str::<TailSampleConfig>(&format!(
+            r#"
+            max_buffered_bytes = {}
+
+            [[policies]]
+            name = "errors"
+            type = "error"
+            "#,
+            event.size_of() * 2
+        ))
+        .unwrap();
+        let mut tail_sample = TailSample::new(&config, &TransformContext::default()).unwrap();
+        let mut emitter = Emitter::new();
+        let now = Instant::now();
+
+        for trace_id in 1..=3 {
+            tail_sample.transform_one(&mut emitter, span(trace_id, serde_json::json!({})), now);
+        }
+
+        // The oldest trace was decided early to make room for the last one
+        assert_eq!(tail_sample.traces.len(), 2);
+        assert!(tail_sample.decisions.contains("1"));
+        assert!(tail_sample.buffered_bytes <= event.size_of() * 2);
+    }
+
+    #[test]
+    fn rejects_invalid_config() {
+        let config = toml::from_str::<TailSampleConfig>("policies = []").unwrap();
+        assert!(TailSample::new(&config, &TransformContext::default()).is_err());
+
+        let config = toml::from_str::<TailSampleConfig>(
+            r#"
+            [[policies]]
+            name = "sampled"
+            type = "probabilistic"
+            rate = 1.5
+            "#,
+        )
+        .unwrap();
+        assert!(TailSample::new(&config, &TransformContext::default()).is_err());
+    }
+
+    #[tokio::test]
+    async fn emits_internal_events() {
+        assert_transform_compliance(async move {
+            let config = toml::from_str::<TailSampleConfig>(
+                r#"
+                complete_when = "true"
+
+                [[policies]]
+                name = "errors"
+                type = "error"
+                "#,
+            )
+            .unwrap();
+            let (tx, rx) = mpsc::channel(1);
+            let (topology, mut out) = create_topology(ReceiverStream::new(rx), config).await;
+
+            tx.send(span(1, serde_json::json!({"error": 1})))
+                .await
+                .unwrap();
+            let event = out.recv().await.unwrap();
+            assert_eq!(trace_ids(&[event]), vec!["1"]);
+
+            drop(tx);
+            topology.stop().await;
+            assert_eq!(out.recv().await, None);
+        })
+        .await;
+    }
+}
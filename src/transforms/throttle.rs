@@ -1,23 +1,31 @@
-use std::{num::NonZeroU32, pin::Pin, time::Duration};
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    num::NonZeroU32,
+    time::Duration,
+};
 
-use async_stream::stream;
-use futures::{Stream, StreamExt};
-use governor::{clock, Quota, RateLimiter};
+use governor::clock::{self, Reference};
 use serde_with::serde_as;
 use snafu::Snafu;
 use vector_lib::config::{clone_input_definitions, LogNamespace};
 use vector_lib::configurable::configurable_component;
+use vector_lib::enrichment::{Case, Condition as TableCondition, IndexHandle, TableSearch};
+use vector_lib::transform::{SyncTransform, TransformOutputsBuf};
+use vector_lib::EstimatedJsonEncodedSizeOf;
 
 use crate::{
     conditions::{AnyCondition, Condition},
     config::{DataType, Input, OutputId, TransformConfig, TransformContext, TransformOutput},
-    event::Event,
-    internal_events::{TemplateRenderingError, ThrottleEventDiscarded},
+    event::{Event, Value},
+    internal_events::{TemplateRenderingError, ThrottleEventDiscarded, ThrottleThresholdError},
     schema,
     template::Template,
-    transforms::{TaskTransform, Transform},
+    transforms::Transform,
 };
 
+/// The name of the output throttled events are sent to when `reroute_dropped` is enabled.
+pub(crate) const DROPPED_OUTPUT: &str = "dropped";
+
 /// Configuration of internal metrics for the Throttle transform.
 #[configurable_component]
 #[derive(Clone, Debug, PartialEq, Eq, Default)]
@@ -36,6 +44,52 @@ pub struct ThrottleInternalMetricsConfig {
     pub emit_events_discarded_per_key: bool,
 }
 
+/// The threshold applied to a bucket.
+///
+/// This may be a fixed number, a template, or a lookup in an enrichment table.
+#[configurable_component]
+#[derive(Clone, Debug)]
+#[serde(untagged)]
+pub enum ThresholdConfig {
+    /// A fixed threshold, shared by every bucket.
+    Fixed(u32),
+
+    /// A template rendered against the first event of each bucket.
+    ///
+    /// The template must render to a positive integer. If it doesn't, an error is logged and
+    /// the bucket is not rate limited.
+    Template(Template),
+
+    /// A threshold looked up in an enrichment table.
+    EnrichmentTable(EnrichmentTableThresholdConfig),
+}
+
+/// A threshold looked up in an enrichment table, keyed on the bucket key.
+#[configurable_component]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct EnrichmentTableThresholdConfig {
+    /// The name of the enrichment table to look the threshold up in.
+    #[configurable(metadata(docs::examples = "tenant_limits"))]
+    pub table: String,
+
+    /// The column matched against the bucket key, as rendered from `key_field`.
+    #[configurable(metadata(docs::examples = "tenant"))]
+    pub key_column: String,
+
+    /// The column holding the threshold.
+    ///
+    /// The column must hold a positive integer. If it doesn't, an error is logged and `default`
+    /// is used instead.
+    #[configurable(metadata(docs::examples = "events_per_window"))]
+    pub value_column: String,
+
+    /// The threshold used for buckets that have no row in the table.
+    ///
+    /// If left unspecified, such buckets are not rate limited.
+    pub default: Option<u32>,
+}
+
 /// Configuration for the `throttle` transform.
 #[serde_as]
 #[configurable_component(transform("throttle", "Rate limit logs passing through a topology."))]
@@ -44,8 +98,22 @@ pub struct ThrottleInternalMetricsConfig {
 pub struct ThrottleConfig {
     /// The number of events allowed for a given bucket per configured `window_secs`.
     ///
-    /// Each unique key has its own `threshold`.
-    threshold: u32,
+    /// Each unique key has its own `threshold`, which is resolved when the bucket is first seen,
+    /// and kept until the bucket has been idle for twice `window_secs`.
+    ///
+    /// At least one of `threshold` and `threshold_bytes` must be set.
+    #[configurable(metadata(docs::examples = 100))]
+    threshold: Option<ThresholdConfig>,
+
+    /// The number of bytes allowed for a given bucket per configured `window_secs`.
+    ///
+    /// The size of an event is its estimated JSON-encoded size. This is resolved in the same way
+    /// as `threshold`, and both limits apply when both are set, in which case a throttled event
+    /// counts towards neither.
+    ///
+    /// An event larger than the threshold is always throttled.
+    #[configurable(metadata(docs::examples = 1000000))]
+    threshold_bytes: Option<ThresholdConfig>,
 
     /// The time window in which the configured `threshold` is applied, in seconds.
     #[serde_as(as = "serde_with::DurationSecondsWithFrac<f64>")]
@@ -62,6 +130,12 @@ pub struct ThrottleConfig {
     /// A logical condition used to exclude events from sampling.
     exclude: Option<AnyCondition>,
 
+    /// Whether or not to send throttled events to the `dropped` output.
+    ///
+    /// If false, throttled events are discarded.
+    #[serde(default)]
+    reroute_dropped: bool,
+
     #[configurable(derived)]
     #[serde(default)]
     internal_metrics: ThrottleInternalMetricsConfig,
@@ -73,7 +147,7 @@ impl_generate_config_from_default!(ThrottleConfig);
 #[typetag::serde(name = "throttle")]
 impl TransformConfig for ThrottleConfig {
     async fn build(&self, context: &TransformContext) -> crate::Result<Transform> {
-        Throttle::new(self, context, clock::MonotonicClock).map(Transform::event_task)
+        Throttle::new(self, context, clock::MonotonicClock).map(Transform::synchronous)
     }
 
     fn input(&self) -> Input {
@@ -87,21 +161,203 @@ impl TransformConfig for ThrottleConfig {
         _: LogNamespace,
     ) -> Vec<TransformOutput> {
         // The event is not modified, so the definition is passed through as-is
-        vec![TransformOutput::new(
+        let mut outputs = vec![TransformOutput::new(
             DataType::Log,
             clone_input_definitions(input_definitions),
-        )]
+        )];
+        if self.reroute_dropped {
+            outputs.push(
+                TransformOutput::new(DataType::Log, clone_input_definitions(input_definitions))
+                    .with_port(DROPPED_OUTPUT),
+            );
+        }
+        outputs
+    }
+}
+
+/// A rate limit allowing `threshold` cells per `window`, implemented as a generic cell rate
+/// algorithm.
+///
+/// Unlike governor's rate limiters, this allows checking whether cells fit within the limit
+/// without consuming them, so that an event rejected by one limit of a bucket doesn't use up the
+/// budget of the other.
+#[derive(Clone, Copy, Debug)]
+struct Limit {
+    window: Duration,
+    /// The time it takes for a single cell to be replenished.
+    interval: Duration,
+    /// The theoretical arrival time, relative to the start of the throttle, at which every
+    /// consumed cell has been replenished.
+    tat: Duration,
+}
+
+impl Limit {
+    fn new(window: Duration, threshold: NonZeroU32) -> Self {
+        // Very large thresholds would round the interval down to zero, which lifts the limit.
+        let interval = (window / threshold.get()).max(Duration::from_nanos(1));
+        Self {
+            window,
+            interval,
+            tat: Duration::ZERO,
+        }
+    }
+
+    /// Returns the theoretical arrival time after consuming `cells` at `now`, or `None` if they
+    /// don't fit within the limit.
+    fn check(&self, cells: u32, now: Duration) -> Option<Duration> {
+        let tat = self
+            .tat
+            .max(now)
+            .checked_add(self.interval.checked_mul(cells)?)?;
+        (tat <= now + self.window).then_some(tat)
+    }
+}
+
+/// A threshold, ready to be resolved for new buckets.
+#[derive(Clone)]
+enum Threshold {
+    Fixed(Limit),
+    Template(Template),
+    EnrichmentTable {
+        search: TableSearch,
+        index: IndexHandle,
+        config: EnrichmentTableThresholdConfig,
+        default: Option<Limit>,
+    },
+}
+
+impl Threshold {
+    fn new(
+        config: &ThresholdConfig,
+        window: Duration,
+        context: &TransformContext,
+    ) -> crate::Result<Self> {
+        let fixed = |threshold| {
+            NonZeroU32::new(threshold)
+                .map(|threshold| Limit::new(window, threshold))
+                .ok_or(ConfigError::NonZero)
+        };
+
+        Ok(match config {
+            ThresholdConfig::Fixed(threshold) => Self::Fixed(fixed(*threshold)?),
+            ThresholdConfig::Template(template) => Self::Template(template.clone()),
+            ThresholdConfig::EnrichmentTable(config) => {
+                let index = context
+                    .enrichment_tables
+                    .clone()
+                    .add_index(&config.table, Case::Sensitive, &[&config.key_column])
+                    .map_err(|message| ConfigError::EnrichmentTable {
+                        table: config.table.clone(),
+                        message,
+                    })?;
+                Self::EnrichmentTable {
+                    search: context.enrichment_tables.as_readonly(),
+                    index,
+                    config: config.clone(),
+                    default: config.default.map(fixed).transpose()?,
+                }
+            }
+        })
+    }
+
+    /// Resolves the limit of the bucket with the given key, or `None` if the bucket is not limited.
+    fn resolve(
+        &self,
+        key: Option<&str>,
+        event: &Event,
+        window: Duration,
+        field: &'static str,
+    ) -> Option<Limit> {
+        match self {
+            Self::Fixed(limit) => Some(*limit),
+            Self::Template(template) => {
+                let threshold = template
+                    .render_string(event)
+                    .map_err(|error| {
+                        emit!(TemplateRenderingError {
+                            error,
+                            field: Some(field),
+                            drop_event: false,
+                        })
+                    })
+                    .ok()?;
+                match threshold.parse::<NonZeroU32>() {
+                    Ok(threshold) => Some(Limit::new(window, threshold)),
+                    Err(_) => {
+                        emit!(ThrottleThresholdError { field, threshold });
+                        None
+                    }
+                }
+            }
+            Self::EnrichmentTable {
+                search,
+                index,
+                config,
+                default,
+            } => {
+                let Some(key) = key else {
+                    return *default;
+                };
+                let condition = [TableCondition::Equals {
+                    field: &config.key_column,
+                    value: Value::from(key),
+                }];
+                let select = [config.value_column.clone()];
+                let Ok(row) = search.find_table_row(
+                    &config.table,
+                    Case::Sensitive,
+                    &condition,
+                    Some(&select),
+                    Some(*index),
+                ) else {
+                    return *default;
+                };
+                let threshold = match row.get(config.value_column.as_str()) {
+                    Some(Value::Integer(threshold)) => u32::try_from(*threshold).ok(),
+                    Some(Value::Bytes(threshold)) => std::str::from_utf8(threshold)
+                        .ok()
+                        .and_then(|threshold| threshold.trim().parse().ok()),
+                    _ => None,
+                };
+                match threshold.and_then(NonZeroU32::new) {
+                    Some(threshold) => Some(Limit::new(window, threshold)),
+                    None => {
+                        emit!(ThrottleThresholdError {
+                            field,
+                            threshold: row
+                                .get(config.value_column.as_str())
+                                .map_or_else(|| "null".to_string(), ToString::to_string),
+                        });
+                        *default
+                    }
+                }
+            }
+        }
     }
 }
 
+/// The rate limits of a single bucket.
+#[derive(Clone)]
+struct Bucket<I: clock::Reference> {
+    events: Option<Limit>,
+    bytes: Option<Limit>,
+    last_seen: I,
+}
+
 #[derive(Clone)]
 pub struct Throttle<C: clock::Clock<Instant = I>, I: clock::Reference> {
-    quota: Quota,
+    window: Duration,
     flush_keys_interval: Duration,
+    threshold: Option<Threshold>,
+    threshold_bytes: Option<Threshold>,
     key_field: Option<Template>,
     exclude: Option<Condition>,
+    reroute_dropped: bool,
     clock: C,
     internal_metrics: ThrottleInternalMetricsConfig,
+    buckets: HashMap<Option<String>, Bucket<I>>,
+    start: I,
+    last_flush: I,
 }
 
 impl<C, I> Throttle<C, I>
@@ -114,111 +370,150 @@ where
         context: &TransformContext,
         clock: C,
     ) -> crate::Result<Self> {
-        let flush_keys_interval = config.window_secs;
-
-        let threshold = match NonZeroU32::new(config.threshold) {
-            Some(threshold) => threshold,
-            None => return Err(Box::new(ConfigError::NonZero)),
-        };
+        let window = config.window_secs;
+        if window.is_zero() {
+            return Err(Box::new(ConfigError::NonZero));
+        }
+        if config.threshold.is_none() && config.threshold_bytes.is_none() {
+            return Err(Box::new(ConfigError::MissingThreshold));
+        }
 
-        let quota = match Quota::with_period(Duration::from_secs_f64(
-            flush_keys_interval.as_secs_f64() / f64::from(threshold.get()),
-        )) {
-            Some(quota) => quota.allow_burst(threshold),
-            None => return Err(Box::new(ConfigError::NonZero)),
-        };
+        let threshold = config
+            .threshold
+            .as_ref()
+            .map(|threshold| Threshold::new(threshold, window, context))
+            .transpose()?;
+        let threshold_bytes = config
+            .threshold_bytes
+            .as_ref()
+            .map(|threshold| Threshold::new(threshold, window, context))
+            .transpose()?;
         let exclude = config
             .exclude
             .as_ref()
             .map(|condition| condition.build(&context.enrichment_tables))
             .transpose()?;
 
+        let start = clock.now();
         Ok(Self {
-            quota,
-            clock,
-            flush_keys_interval,
+            window,
+            flush_keys_interval: window * 2,
+            threshold,
+            threshold_bytes,
             key_field: config.key_field.clone(),
             exclude,
+            reroute_dropped: config.reroute_dropped,
+            clock,
             internal_metrics: config.internal_metrics.clone(),
+            buckets: HashMap::new(),
+            start,
+            last_flush: start,
         })
     }
+
+    /// Removes the buckets that have been idle for at least the flush interval.
+    ///
+    /// By then, their limiters have fully replenished, so they are indistinguishable from new
+    /// ones, apart from their thresholds being resolved again.
+    fn flush_keys(&mut self, now: I) {
+        if Duration::from(now.duration_since(self.last_flush)) < self.flush_keys_interval {
+            return;
+        }
+        let flush_keys_interval = self.flush_keys_interval;
+        self.buckets.retain(|_, bucket| {
+            Duration::from(now.duration_since(bucket.last_seen)) < flush_keys_interval
+        });
+        self.last_flush = now;
+    }
+
+    /// Checks whether the event fits within the limits of its bucket.
+    ///
+    /// The event only counts towards the limits of the bucket if it fits within all of them.
+    fn check(&mut self, key: Option<String>, event: &Event) -> bool {
+        let now = self.clock.now();
+        self.flush_keys(now);
+
+        let (threshold, threshold_bytes, window) =
+            (&self.threshold, &self.threshold_bytes, self.window);
+        let bucket = match self.buckets.entry(key) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => {
+                let key = entry.key().as_deref();
+                let limit = |threshold: &Option<Threshold>, field| {
+                    threshold
+                        .as_ref()
+                        .and_then(|threshold| threshold.resolve(key, event, window, field))
+                };
+                let bucket = Bucket {
+                    events: limit(threshold, "threshold"),
+                    bytes: limit(threshold_bytes, "threshold_bytes"),
+                    last_seen: now,
+                };
+                entry.insert(bucket)
+            }
+        };
+        bucket.last_seen = now;
+
+        let elapsed = Duration::from(now.duration_since(self.start));
+        let events_tat = bucket.events.map(|limit| limit.check(1, elapsed));
+        let bytes_tat = bucket.bytes.map(|limit| {
+            let size = event.estimated_json_encoded_size_of().get();
+            limit.check(u32::try_from(size).unwrap_or(u32::MAX), elapsed)
+        });
+        if matches!(events_tat, Some(None)) || matches!(bytes_tat, Some(None)) {
+            return false;
+        }
+
+        if let (Some(limit), Some(Some(tat))) = (bucket.events.as_mut(), events_tat) {
+            limit.tat = tat;
+        }
+        if let (Some(limit), Some(Some(tat))) = (bucket.bytes.as_mut(), bytes_tat) {
+            limit.tat = tat;
+        }
+        true
+    }
 }
 
-impl<C, I> TaskTransform<Event> for Throttle<C, I>
+impl<C, I> SyncTransform for Throttle<C, I>
 where
-    C: clock::Clock<Instant = I> + Send + 'static,
-    I: clock::Reference + Send + 'static,
+    C: clock::Clock<Instant = I> + Send + Sync + 'static,
+    I: clock::Reference + Send + Sync + 'static,
 {
-    fn transform(
-        self: Box<Self>,
-        mut input_rx: Pin<Box<dyn Stream<Item = Event> + Send>>,
-    ) -> Pin<Box<dyn Stream<Item = Event> + Send>>
-    where
-        Self: 'static,
-    {
-        let mut flush_keys = tokio::time::interval(self.flush_keys_interval * 2);
-
-        let limiter = RateLimiter::dashmap_with_clock(self.quota, &self.clock);
-
-        Box::pin(stream! {
-          loop {
-            let done = tokio::select! {
-                biased;
-
-                maybe_event = input_rx.next() => {
-                    match maybe_event {
-                        None => true,
-                        Some(event) => {
-                            let (throttle, event) = match self.exclude.as_ref() {
-                                Some(condition) => {
-                                    let (result, event) = condition.check(event);
-                                    (!result, event)
-                                },
-                                _ => (true, event)
-                            };
-                            let output = if throttle {
-                                let key = self.key_field.as_ref().and_then(|t| {
-                                    t.render_string(&event)
-                                        .map_err(|error| {
-                                            emit!(TemplateRenderingError {
-                                                error,
-                                                field: Some("key_field"),
-                                                drop_event: false,
-                                            })
-                                        })
-                                        .ok()
-                                });
-
-                                match limiter.check_key(&key) {
-                                    Ok(()) => {
-                                        Some(event)
-                                    }
-                                    _ => {
-                                        emit!(ThrottleEventDiscarded{
-                                            key: key.unwrap_or_else(|| "None".to_string()),
-                                            emit_events_discarded_per_key: self.internal_metrics.emit_events_discarded_per_key
-                                        });
-                                        None
-                                    }
-                                }
-                            } else {
-                                Some(event)
-                            };
-                            if let Some(event) = output {
-                                yield event;
-                            }
-                            false
-                        }
-                    }
-                }
-                _ = flush_keys.tick() => {
-                    limiter.retain_recent();
-                    false
-                }
-            };
-            if done { break }
-          }
-        })
+    fn transform(&mut self, event: Event, output: &mut TransformOutputsBuf) {
+        let (throttle, event) = match self.exclude.as_ref() {
+            Some(condition) => {
+                let (result, event) = condition.check(event);
+                (!result, event)
+            }
+            _ => (true, event),
+        };
+        if !throttle {
+            output.push(None, event);
+            return;
+        }
+
+        let key = self.key_field.as_ref().and_then(|t| {
+            t.render_string(&event)
+                .map_err(|error| {
+                    emit!(TemplateRenderingError {
+                        error,
+                        field: Some("key_field"),
+                        drop_event: false,
+                    })
+                })
+                .ok()
+        });
+
+        if self.check(key.clone(), &event) {
+            output.push(None, event);
+        } else if self.reroute_dropped {
+            output.push(Some(DROPPED_OUTPUT), event);
+        } else {
+            emit!(ThrottleEventDiscarded {
+                key: key.unwrap_or_else(|| "None".to_string()),
+                emit_events_discarded_per_key: self.internal_metrics.emit_events_discarded_per_key
+            });
+        }
     }
 }
 
@@ -226,21 +521,87 @@ where
 pub enum ConfigError {
     #[snafu(display("`threshold`, and `window_secs` must be non-zero"))]
     NonZero,
+    #[snafu(display("at least one of `threshold` and `threshold_bytes` must be set"))]
+    MissingThreshold,
+    #[snafu(display(
+        "unable to look up thresholds in enrichment table `{}`: {}",
+        table,
+        message
+    ))]
+    EnrichmentTable { table: String, message: String },
 }
 
 #[cfg(test)]
 mod tests {
-    use std::task::Poll;
+    use std::{
+        collections::{BTreeMap, HashMap},
+        task::Poll,
+    };
 
-    use futures::SinkExt;
+    use futures::{SinkExt, Stream, StreamExt};
+    use tokio::sync::mpsc;
+    use tokio_stream::wrappers::ReceiverStream;
+    use vector_lib::enrichment::{Table, TableRegistry};
+    use vrl::value::ObjectMap;
 
     use super::*;
     use crate::{
         event::LogEvent, test_util::components::assert_transform_compliance,
         transforms::test::create_topology,
     };
-    use tokio::sync::mpsc;
-    use tokio_stream::wrappers::ReceiverStream;
+
+    type FakeThrottle =
+        Throttle<clock::FakeRelativeClock, <clock::FakeRelativeClock as clock::Clock>::Instant>;
+
+    fn build(config: &str, clock: &clock::FakeRelativeClock) -> FakeThrottle {
+        let config = toml::from_str::<ThrottleConfig>(config).unwrap();
+        Throttle::new(&config, &TransformContext::default(), clock.clone()).unwrap()
+    }
+
+    fn run<C, I>(throttle: &mut Throttle<C, I>, event: Event) -> (Vec<Event>, Vec<Event>)
+    where
+        C: clock::Clock<Instant = I> + Send + Sync + 'static,
+        I: clock::Reference + Send + Sync + 'static,
+    {
+        let mut outputs = TransformOutputsBuf::new_with_capacity(
+            vec![
+                TransformOutput::new(DataType::Log, HashMap::new()),
+                TransformOutput::new(DataType::Log, HashMap::new()).with_port(DROPPED_OUTPUT),
+            ],
+            1,
+        );
+        throttle.transform(event, &mut outputs);
+        (
+            outputs.drain().collect(),
+            outputs.drain_named(DROPPED_OUTPUT).collect(),
+        )
+    }
+
+    /// Returns how many of the given events the throttle lets through.
+    fn count_passed<C, I>(throttle: &mut Throttle<C, I>, events: Vec<Event>) -> usize
+    where
+        C: clock::Clock<Instant = I> + Send + Sync + 'static,
+        I: clock::Reference + Send + Sync + 'static,
+    {
+        events
+            .into_iter()
+            .map(|event| run(throttle, event).0.len())
+            .sum()
+    }
+
+    /// Runs the throttle over a stream of events, as the topology does.
+    fn transform_events(
+        mut throttle: FakeThrottle,
+        input: impl Stream<Item = Event> + Send + 'static,
+    ) -> impl Stream<Item = Event> + Send + Unpin {
+        Box::pin(input.flat_map(move |event| futures::stream::iter(run(&mut throttle, event).0)))
+    }
+
+    fn bucket_event(bucket: &str) -> Event {
+        let mut log = LogEvent::default();
+        log.insert("bucket", bucket);
+        log.into()
+    }
 
     #[test]
     fn generate_config() {
@@ -258,17 +619,11 @@ window_secs = 5
         )
         .unwrap();
 
-        let throttle = Throttle::new(&config, &TransformContext::default(), clock.clone())
-            .map(Transform::event_task)
-            .unwrap();
-
-        let throttle = throttle.into_task();
+        let throttle = Throttle::new(&config, &TransformContext::default(), clock.clone()).unwrap();
 
         let (mut tx, rx) = futures::channel::mpsc::channel(10);
-        let mut out_stream = throttle.transform_events(Box::pin(rx));
+        let mut out_stream = transform_events(throttle, rx);
 
-        // tokio interval is always immediately ready, so we poll once to make sure
-        // we trip it/set the interval in the future
         assert_eq!(Poll::Pending, futures::poll!(out_stream.next()));
 
         tx.send(LogEvent::default().into()).await.unwrap();
@@ -324,17 +679,11 @@ exists(.special)
         )
         .unwrap();
 
-        let throttle = Throttle::new(&config, &TransformContext::default(), clock.clone())
-            .map(Transform::event_task)
-            .unwrap();
-
-        let throttle = throttle.into_task();
+        let throttle = Throttle::new(&config, &TransformContext::default(), clock.clone()).unwrap();
 
         let (mut tx, rx) = futures::channel::mpsc::channel(10);
-        let mut out_stream = throttle.transform_events(Box::pin(rx));
+        let mut out_stream = transform_events(throttle, rx);
 
-        // tokio interval is always immediately ready, so we poll once to make sure
-        // we trip it/set the interval in the future
         assert_eq!(Poll::Pending, futures::poll!(out_stream.next()));
 
         tx.send(LogEvent::default().into()).await.unwrap();
@@ -397,17 +746,11 @@ key_field = "{{ bucket }}"
         )
         .unwrap();
 
-        let throttle = Throttle::new(&config, &TransformContext::default(), clock.clone())
-            .map(Transform::event_task)
-            .unwrap();
-
-        let throttle = throttle.into_task();
+        let throttle = Throttle::new(&config, &TransformContext::default(), clock.clone()).unwrap();
 
         let (mut tx, rx) = futures::channel::mpsc::channel(10);
-        let mut out_stream = throttle.transform_events(Box::pin(rx));
+        let mut out_stream = transform_events(throttle, rx);
 
-        // tokio interval is always immediately ready, so we poll once to make sure
-        // we trip it/set the interval in the future
         assert_eq!(Poll::Pending, futures::poll!(out_stream.next()));
 
         let mut log_a = LogEvent::default();
@@ -436,15 +779,249 @@ key_field = "{{ bucket }}"
         assert_eq!(Poll::Ready(None), futures::poll!(out_stream.next()));
     }
 
+    #[test]
+    fn throttle_template_threshold() {
+        let clock = clock::FakeRelativeClock::default();
+        let mut throttle = build(
+            r#"
+threshold = "{{ limit }}"
+window_secs = 5
+key_field = "{{ bucket }}"
+"#,
+            &clock,
+        );
+
+        let event = |bucket, limit| {
+            let mut event = bucket_event(bucket);
+            event.as_mut_log().insert("limit", limit);
+            event
+        };
+
+        let events = (0..3).map(|_| event("a", "1")).collect();
+        assert_eq!(1, count_passed(&mut throttle, events));
+
+        let events = (0..3).map(|_| event("b", "2")).collect();
+        assert_eq!(2, count_passed(&mut throttle, events));
+
+        // Buckets with an invalid threshold are not rate limited
+        let events = (0..3).map(|_| event("c", "none")).collect();
+        assert_eq!(3, count_passed(&mut throttle, events));
+    }
+
+    #[derive(Clone)]
+    struct LimitsTable(BTreeMap<String, i64>);
+
+    impl Table for LimitsTable {
+        fn find_table_row<'a>(
+            &self,
+            case: Case,
+            condition: &'a [TableCondition<'a>],
+            select: Option<&[String]>,
+            index: Option<IndexHandle>,
+        ) -> Result<ObjectMap, String> {
+            let mut rows = self.find_table_rows(case, condition, select, index)?;
+            match rows.pop() {
+                Some(row) if rows.is_empty() => Ok(row),
+                Some(_) => Err("More than 1 row found".to_string()),
+                None => Err("no rows found".to_string()),
+            }
+        }
+
+        fn find_table_rows<'a>(
+            &self,
+            _: Case,
+            condition: &'a [TableCondition<'a>],
+            _: Option<&[String]>,
+            _: Option<IndexHandle>,
+        ) -> Result<Vec<ObjectMap>, String> {
+            let Some(TableCondition::Equals { value, .. }) = condition.first() else {
+                return Err("unsupported condition".to_string());
+            };
+            Ok(self
+                .0
+                .get(value.to_string_lossy().as_ref())
+                .map(|limit| ObjectMap::from([("limit".into(), Value::from(*limit))]))
+                .into_iter()
+                .collect())
+        }
+
+        fn add_index(&mut self, _: Case, _: &[&str]) -> Result<IndexHandle, String> {
+            Ok(IndexHandle(0))
+        }
+
+        fn index_fields(&self) -> Vec<(Case, Vec<String>)> {
+            Vec::new()
+        }
+
+        fn needs_reload(&self) -> bool {
+            false
+        }
+    }
+
+    #[test]
+    fn throttle_enrichment_table_threshold() {
+        let registry = TableRegistry::default();
+        registry.load(HashMap::from([(
+            "limits".to_string(),
+            Box::new(LimitsTable(BTreeMap::from([
+                ("a".to_string(), 1),
+                ("b".to_string(), 3),
+            ]))) as Box<dyn Table + Send + Sync>,
+        )]));
+        let context = TransformContext {
+            enrichment_tables: registry.clone(),
+            ..Default::default()
+        };
+
+        let config = toml::from_str::<ThrottleConfig>(
+            r#"
+threshold.table = "limits"
+threshold.key_column = "tenant"
+threshold.value_column = "limit"
+threshold.default = 2
+window_secs = 5
+key_field = "{{ bucket }}"
+"#,
+        )
+        .unwrap();
+        let clock = clock::FakeRelativeClock::default();
+        let mut throttle = Throttle::new(&config, &context, clock).unwrap();
+        registry.finish_load();
+
+        for (bucket, passed) in [("a", 1), ("b", 3), ("c", 2)] {
+            let events = (0..5).map(|_| bucket_event(bucket)).collect();
+            assert_eq!(
+                passed,
+                count_passed(&mut throttle, events),
+                "bucket {bucket}"
+            );
+        }
+    }
+
+    #[test]
+    fn throttle_bytes() {
+        let clock = clock::FakeRelativeClock::default();
+        let event = Event::from(LogEvent::from("hello world"));
+        let size = event.estimated_json_encoded_size_of().get();
+        let mut throttle = build(
+            &format!(
+                r#"
+threshold_bytes = {}
+window_secs = 5
+"#,
+                size * 2
+            ),
+            &clock,
+        );
+
+        let events = (0..3).map(|_| event.clone()).collect();
+        assert_eq!(2, count_passed(&mut throttle, events));
+
+        // Events larger than the threshold never get through
+        let large = Event::from(LogEvent::from("hello world".repeat(10).as_str()));
+        clock.advance(Duration::from_secs(5));
+        assert_eq!(0, count_passed(&mut throttle, vec![large]));
+        assert_eq!(1, count_passed(&mut throttle, vec![event]));
+    }
+
+    #[test]
+    fn throttle_bytes_rejection_keeps_event_budget() {
+        let clock = clock::FakeRelativeClock::default();
+        let small = Event::from(LogEvent::from("a"));
+        let large = Event::from(LogEvent::from("a".repeat(100).as_str()));
+        let mut throttle = build(
+            &format!(
+                r#"
+threshold = 2
+threshold_bytes = {}
+window_secs = 5
+"#,
+                small.estimated_json_encoded_size_of().get() * 2
+            ),
+            &clock,
+        );
+
+        // The large event doesn't fit within `threshold_bytes`, so it doesn't count towards
+        // `threshold` either.
+        assert_eq!(0, count_passed(&mut throttle, vec![large]));
+        let events = (0..3).map(|_| small.clone()).collect();
+        assert_eq!(2, count_passed(&mut throttle, events));
+    }
+
+    #[test]
+    fn throttle_events_rejection_keeps_byte_budget() {
+        let clock = clock::FakeRelativeClock::default();
+        let small = Event::from(LogEvent::from("a"));
+        let large = Event::from(LogEvent::from("a".repeat(100).as_str()));
+        let small_size = small.estimated_json_encoded_size_of().get();
+        let large_size = large.estimated_json_encoded_size_of().get();
+        assert!(large_size > small_size * 3);
+        // Enough for the large event once half of the window has passed, but only if the events
+        // throttled by `threshold` in the meantime haven't used up the bytes replenished since.
+        let threshold_bytes = (large_size - small_size) * 2 - 1;
+        let mut throttle = build(
+            &format!(
+                r#"
+threshold = 2
+threshold_bytes = {threshold_bytes}
+window_secs = 5
+"#
+            ),
+            &clock,
+        );
+
+        let events = (0..20).map(|_| small.clone()).collect();
+        assert_eq!(2, count_passed(&mut throttle, events));
+
+        clock.advance(Duration::from_millis(2500));
+        assert_eq!(1, count_passed(&mut throttle, vec![large]));
+    }
+
+    #[test]
+    fn throttle_reroutes_dropped_events() {
+        let clock = clock::FakeRelativeClock::default();
+        let mut throttle = build(
+            r#"
+threshold = 1
+window_secs = 5
+reroute_dropped = true
+"#,
+            &clock,
+        );
+
+        let event = Event::from(LogEvent::from("hello world"));
+        let (passed, dropped) = run(&mut throttle, event.clone());
+        assert_eq!(passed, vec![event.clone()]);
+        assert!(dropped.is_empty());
+
+        let (passed, dropped) = run(&mut throttle, event.clone());
+        assert!(passed.is_empty());
+        assert_eq!(dropped, vec![event]);
+    }
+
+    #[test]
+    fn requires_a_threshold() {
+        let config = toml::from_str::<ThrottleConfig>("window_secs = 5").unwrap();
+        let error = Throttle::new(
+            &config,
+            &TransformContext::default(),
+            clock::FakeRelativeClock::default(),
+        )
+        .err()
+        .unwrap();
+        assert_eq!(
+            error.to_string(),
+            "at least one of `threshold` and `threshold_bytes` must be set"
+        );
+    }
+
     #[tokio::test]
     async fn emits_internal_events() {
         assert_transform_compliance(async move {
             let config = ThrottleConfig {
-                threshold: 1,
+                threshold: Some(ThresholdConfig::Fixed(1)),
                 window_secs: Duration::from_secs_f64(1.0),
-                key_field: None,
-                exclude: None,
-                internal_metrics: Default::default(),
+                ..Default::default()
             };
             let (tx, rx) = mpsc::channel(1);
             let (topology, mut out) = create_topology(ReceiverStream::new(rx), config).await;
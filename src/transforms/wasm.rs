@@ -0,0 +1,876 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
+
+use prost::Message;
+use serde_with::serde_as;
+use snafu::{ResultExt, Snafu};
+use tokio::sync::oneshot::{self, error::TryRecvError};
+use vector_lib::config::LogNamespace;
+use vector_lib::configurable::configurable_component;
+use vector_lib::transform::runtime_transform::{RuntimeTransform, Timer};
+use wasmtime::{
+    Caller, Engine, Extern, Linker, Memory, Module, Store, StoreLimits, StoreLimitsBuilder, Trap,
+    TypedFunc, WasmParams, WasmResults,
+};
+use wasmtime_wasi::sync::WasiCtxBuilder;
+use wasmtime_wasi::WasiCtx;
+
+use crate::{
+    config::{
+        ComponentKey, DataType, GenerateConfig, Input, OutputId, TransformConfig, TransformContext,
+        TransformOutput,
+    },
+    event::{proto::EventWrapper, Event},
+    internal_events::{
+        WasmGuestLog, WasmHookError, WasmModuleReloadError, WasmModuleReloaded, WasmProcessError,
+    },
+    schema::{self, Definition},
+    transforms::Transform,
+};
+
+/// The version of the guest ABI implemented by this transform.
+///
+/// This must match `ABI_VERSION` in `lib/vector-wasm-sdk`.
+const ABI_VERSION: u32 = 1;
+
+#[derive(Debug, Snafu)]
+pub enum WasmError {
+    #[snafu(display("Failed to load WebAssembly module {:?}: {}", path, source))]
+    LoadModule { path: PathBuf, source: crate::Error },
+
+    #[snafu(display("Failed to instantiate WebAssembly module: {}", source))]
+    Instantiate { source: crate::Error },
+
+    #[snafu(display("WebAssembly module is missing export {:?}: {}", name, source))]
+    MissingExport {
+        name: &'static str,
+        source: crate::Error,
+    },
+
+    #[snafu(display(
+        "WebAssembly module implements ABI version {}, but version {} is required",
+        version,
+        ABI_VERSION
+    ))]
+    AbiVersion { version: u32 },
+
+    #[snafu(display("Failed to encode options: {}", source))]
+    EncodeOptions { source: serde_json::Error },
+
+    #[snafu(display("Call to {:?} ran out of fuel", function))]
+    FuelExhausted { function: &'static str },
+
+    #[snafu(display("Call to {:?} trapped: {}", function, source))]
+    Trapped {
+        function: &'static str,
+        source: crate::Error,
+    },
+
+    #[snafu(display("Call to {:?} returned status {}", function, status))]
+    Status { function: &'static str, status: u32 },
+}
+
+impl WasmError {
+    /// Whether the instance may be left in an inconsistent state by this error, and should be
+    /// replaced.
+    const fn is_trap(&self) -> bool {
+        matches!(self, Self::FuelExhausted { .. } | Self::Trapped { .. })
+    }
+}
+
+/// Configuration for the `wasm` transform.
+#[serde_as]
+#[configurable_component(transform("wasm", "Modify events using a WebAssembly module."))]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct WasmConfig {
+    /// The path to the WebAssembly module.
+    ///
+    /// The module must target WASI, and implement the ABI of the `vector-wasm-sdk` crate. Both
+    /// binary and text modules are supported.
+    #[configurable(metadata(docs::examples = "/etc/vector/transform.wasm"))]
+    pub module: PathBuf,
+
+    /// Options passed to the module when it is initialized, encoded as JSON.
+    #[configurable(metadata(docs::examples = "example_options()"))]
+    pub options: Option<toml::Value>,
+
+    /// The maximum amount of fuel that each call into the module can consume.
+    ///
+    /// Fuel roughly corresponds to the number of WebAssembly instructions executed. A call that
+    /// runs out of fuel is aborted, its event is dropped, and the instance is restarted, which
+    /// discards its state.
+    ///
+    /// If left unspecified, calls are not limited.
+    #[configurable(metadata(docs::examples = 10000000))]
+    pub max_fuel: Option<u64>,
+
+    /// The maximum size of the module's linear memory, in bytes.
+    ///
+    /// Attempts to grow the memory beyond this size fail.
+    ///
+    /// If left unspecified, memory is only limited by the 4GiB address space of the module.
+    #[configurable(metadata(docs::examples = 67108864))]
+    #[configurable(metadata(docs::type_unit = "bytes"))]
+    pub max_memory_bytes: Option<usize>,
+
+    /// Whether or not to reload the module when the file changes.
+    ///
+    /// The file's modification time is checked every `reload_interval_secs`. When it changes, the
+    /// new module is compiled and initialized in the background while the running instance keeps
+    /// processing events, and then replaces it after the shutdown function of that instance has
+    /// been called. If the new module fails to load, the running instance is kept.
+    #[serde(default)]
+    pub hot_reload: bool,
+
+    /// How often to check the module for changes when `hot_reload` is enabled, in seconds.
+    #[serde(default = "default_reload_interval_secs")]
+    #[serde_as(as = "serde_with::DurationSecondsWithFrac<f64>")]
+    #[configurable(metadata(docs::human_name = "Reload Interval"))]
+    pub reload_interval_secs: Duration,
+}
+
+const fn default_reload_interval_secs() -> Duration {
+    Duration::from_secs(5)
+}
+
+fn example_options() -> toml::Value {
+    toml::Value::Table(toml::map::Map::from_iter([(
+        "field".to_string(),
+        toml::Value::String("message".to_string()),
+    )]))
+}
+
+impl GenerateConfig for WasmConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(r#"module = "/etc/vector/transform.wasm""#).unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "wasm")]
+impl TransformConfig for WasmConfig {
+    async fn build(&self, context: &TransformContext) -> crate::Result<Transform> {
+        let key = context
+            .key
+            .as_ref()
+            .map_or_else(|| ComponentKey::from("wasm"), Clone::clone);
+        Wasm::new(self, key).map(Transform::event_task)
+    }
+
+    fn input(&self) -> Input {
+        Input::all()
+    }
+
+    fn outputs(
+        &self,
+        _: vector_lib::enrichment::TableRegistry,
+        input_definitions: &[(OutputId, schema::Definition)],
+        _: LogNamespace,
+    ) -> Vec<TransformOutput> {
+        // The module can return anything, so the type definition is reset.
+        let namespaces = input_definitions
+            .iter()
+            .flat_map(|(_output, definition)| definition.log_namespaces().clone())
+            .collect();
+
+        let definition = input_definitions
+            .iter()
+            .map(|(output, _definition)| {
+                (
+                    output.clone(),
+                    Definition::default_for_namespace(&namespaces),
+                )
+            })
+            .collect();
+
+        vec![TransformOutput::new(DataType::all(), definition)]
+    }
+}
+
+/// The state of a single instance of the module.
+struct State {
+    wasi: WasiCtx,
+    limits: StoreLimits,
+    output: Vec<Event>,
+}
+
+fn add_host_functions(linker: &mut Linker<State>) -> wasmtime::Result<()> {
+    wasmtime_wasi::sync::add_to_linker(linker, |state: &mut State| &mut state.wasi)?;
+
+    linker.func_wrap(
+        "vector",
+        "emit",
+        |mut caller: Caller<'_, State>, ptr: u32, len: u32| -> wasmtime::Result<()> {
+            let event = EventWrapper::decode(read_guest_memory(&mut caller, ptr, len)?)?;
+            if event.event.is_none() {
+                return Err(wasmtime::Error::msg("emitted event is empty"));
+            }
+            caller.data_mut().output.push(Event::from(event));
+            Ok(())
+        },
+    )?;
+
+    linker.func_wrap(
+        "vector",
+        "log",
+        |mut caller: Caller<'_, State>, level: u32, ptr: u32, len: u32| -> wasmtime::Result<()> {
+            let message = read_guest_memory(&mut caller, ptr, len)?;
+            emit!(WasmGuestLog {
+                level,
+                message: &String::from_utf8_lossy(message),
+            });
+            Ok(())
+        },
+    )?;
+
+    Ok(())
+}
+
+fn read_guest_memory<'a>(
+    caller: &'a mut Caller<'_, State>,
+    ptr: u32,
+    len: u32,
+) -> wasmtime::Result<&'a [u8]> {
+    let memory = caller
+        .get_export("memory")
+        .and_then(Extern::into_memory)
+        .ok_or_else(|| wasmtime::Error::msg("module does not export its memory"))?;
+    let start = ptr as usize;
+    memory
+        .data(caller)
+        .get(start..start + len as usize)
+        .ok_or_else(|| wasmtime::Error::msg("buffer is out of bounds"))
+}
+
+/// A running instance of the module.
+struct Instance {
+    store: Store<State>,
+    memory: Memory,
+    max_fuel: Option<u64>,
+    alloc: TypedFunc<u32, u32>,
+    init: TypedFunc<(u32, u32), u32>,
+    process: TypedFunc<(u32, u32), u32>,
+    shutdown: TypedFunc<(), u32>,
+}
+
+impl Instance {
+    fn new(
+        linker: &Linker<State>,
+        module: &Module,
+        limits: &Limits,
+        options: &[u8],
+    ) -> Result<Self, WasmError> {
+        let mut store_limits = StoreLimitsBuilder::new();
+        if let Some(max_memory_bytes) = limits.max_memory_bytes {
+            store_limits = store_limits.memory_size(max_memory_bytes);
+        }
+        let state = State {
+            wasi: WasiCtxBuilder::new().inherit_stderr().build(),
+            limits: store_limits.build(),
+            output: Vec::new(),
+        };
+        let mut store = Store::new(module.engine(), state);
+        store.limiter(|state| &mut state.limits);
+        if let Some(max_fuel) = limits.max_fuel {
+            store
+                .set_fuel(max_fuel)
+                .expect("fuel consumption is enabled");
+        }
+
+        let instance = linker
+            .instantiate(&mut store, module)
+            .map_err(Into::into)
+            .context(InstantiateSnafu)?;
+
+        let memory = instance
+            .get_memory(&mut store, "memory")
+            .ok_or_else(|| crate::Error::from("memory is not exported"))
+            .context(MissingExportSnafu { name: "memory" })?;
+
+        macro_rules! typed_func {
+            ($name:literal) => {
+                instance
+                    .get_typed_func(&mut store, $name)
+                    .map_err(Into::into)
+                    .context(MissingExportSnafu { name: $name })?
+            };
+        }
+
+        let abi_version: TypedFunc<(), u32> = typed_func!("vector_abi_version");
+        // Modules built as WASI reactors need to be initialized before anything else is called.
+        let initialize: Option<TypedFunc<(), ()>> =
+            instance.get_typed_func(&mut store, "_initialize").ok();
+        let mut instance = Self {
+            memory,
+            max_fuel: limits.max_fuel,
+            alloc: typed_func!("vector_alloc"),
+            init: typed_func!("vector_init"),
+            process: typed_func!("vector_process"),
+            shutdown: typed_func!("vector_shutdown"),
+            store,
+        };
+
+        if let Some(initialize) = initialize {
+            instance.call("_initialize", initialize, ())?;
+        }
+
+        let version = instance.call("vector_abi_version", abi_version, ())?;
+        if version != ABI_VERSION {
+            return Err(WasmError::AbiVersion { version });
+        }
+
+        let ptr = instance.write("vector_init", options)?;
+        let status = instance.call("vector_init", instance.init, (ptr, options.len() as u32))?;
+        check_status("vector_init", status)?;
+
+        Ok(instance)
+    }
+
+    /// Calls a function exported by the module, with a fresh allowance of fuel.
+    fn call<P: WasmParams, R: WasmResults>(
+        &mut self,
+        function: &'static str,
+        func: TypedFunc<P, R>,
+        params: P,
+    ) -> Result<R, WasmError> {
+        if let Some(max_fuel) = self.max_fuel {
+            self.store
+                .set_fuel(max_fuel)
+                .expect("fuel consumption is enabled");
+        }
+        func.call(&mut self.store, params).map_err(|error| {
+            if error.downcast_ref::<Trap>() == Some(&Trap::OutOfFuel) {
+                WasmError::FuelExhausted { function }
+            } else {
+                WasmError::Trapped {
+                    function,
+                    source: error.into(),
+                }
+            }
+        })
+    }
+
+    /// Copies `bytes` into a buffer allocated by the module, returning a pointer to it.
+    fn write(&mut self, function: &'static str, bytes: &[u8]) -> Result<u32, WasmError> {
+        let ptr = self.call("vector_alloc", self.alloc, bytes.len() as u32)?;
+        self.memory
+            .write(&mut self.store, ptr as usize, bytes)
+            .map_err(|error| WasmError::Trapped {
+                function,
+                source: error.into(),
+            })?;
+        Ok(ptr)
+    }
+
+    fn take_output(&mut self) -> Vec<Event> {
+        std::mem::take(&mut self.store.data_mut().output)
+    }
+
+    fn process(&mut self, event: Event) -> Result<Vec<Event>, WasmError> {
+        let bytes = EventWrapper::from(event).encode_to_vec();
+        let ptr = self.write("vector_process", &bytes)?;
+        let status = self.call("vector_process", self.process, (ptr, bytes.len() as u32));
+        // Events emitted before an error are discarded along with the input event.
+        let output = self.take_output();
+        check_status("vector_process", status?)?;
+        Ok(output)
+    }
+
+    fn shutdown(&mut self) -> Result<Vec<Event>, WasmError> {
+        let status = self.call("vector_shutdown", self.shutdown, ());
+        let output = self.take_output();
+        check_status("vector_shutdown", status?)?;
+        Ok(output)
+    }
+}
+
+fn check_status(function: &'static str, status: u32) -> Result<(), WasmError> {
+    if status == 0 {
+        Ok(())
+    } else {
+        Err(WasmError::Status { function, status })
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Limits {
+    max_fuel: Option<u64>,
+    max_memory_bytes: Option<usize>,
+}
+
+type Loaded = Result<(Module, Instance), WasmError>;
+
+fn modified_time(path: &Path) -> std::io::Result<SystemTime> {
+    std::fs::metadata(path)?.modified()
+}
+
+pub struct Wasm {
+    path: PathBuf,
+    engine: Engine,
+    linker: Linker<State>,
+    limits: Limits,
+    options: Vec<u8>,
+    module: Module,
+    instance: Instance,
+    modified: Option<SystemTime>,
+    reload_interval: Option<Duration>,
+    /// The module being loaded in the background after it changed.
+    reloading: Option<oneshot::Receiver<Loaded>>,
+    source_id: Arc<ComponentKey>,
+}
+
+impl Wasm {
+    pub fn new(config: &WasmConfig, key: ComponentKey) -> crate::Result<Self> {
+        let limits = Limits {
+            max_fuel: config.max_fuel,
+            max_memory_bytes: config.max_memory_bytes,
+        };
+
+        let mut engine_config = wasmtime::Config::new();
+        engine_config.consume_fuel(limits.max_fuel.is_some());
+        let engine = Engine::new(&engine_config)?;
+
+        let mut linker = Linker::new(&engine);
+        add_host_functions(&mut linker)?;
+
+        let options = serde_json::to_vec(&config.options).context(EncodeOptionsSnafu)?;
+        let modified = modified_time(&config.module).ok();
+        let module = load_module(&engine, &config.module)?;
+        let instance = Instance::new(&linker, &module, &limits, &options)?;
+
+        Ok(Self {
+            path: config.module.clone(),
+            engine,
+            linker,
+            limits,
+            options,
+            module,
+            instance,
+            modified,
+            reload_interval: config.hot_reload.then_some(config.reload_interval_secs),
+            reloading: None,
+            source_id: Arc::new(key),
+        })
+    }
+
+    /// Replaces the instance after it trapped, since its state may be inconsistent.
+    fn restart(&mut self) {
+        match Instance::new(&self.linker, &self.module, &self.limits, &self.options) {
+            Ok(instance) => self.instance = instance,
+            Err(error) => emit!(WasmHookError {
+                hook: "restart",
+                error
+            }),
+        }
+    }
+
+    /// Starts loading the module in the background if the file changed.
+    ///
+    /// Compiling a module can take a while, so it's done on a blocking thread, and the new
+    /// instance only replaces the running one once it's ready.
+    fn reload_if_changed<F>(&mut self, mut emit_fn: F)
+    where
+        F: FnMut(Event),
+    {
+        self.finish_reload(&mut emit_fn);
+        if self.reloading.is_some() {
+            return;
+        }
+
+        let modified = match modified_time(&self.path) {
+            Ok(modified) => modified,
+            Err(error) => {
+                emit!(WasmModuleReloadError {
+                    error: WasmError::LoadModule {
+                        path: self.path.clone(),
+                        source: error.into(),
+                    }
+                });
+                return;
+            }
+        };
+        if self.modified == Some(modified) {
+            return;
+        }
+        // A module that fails to load is only retried once the file changes again.
+        self.modified = Some(modified);
+
+        let (tx, rx) = oneshot::channel();
+        let engine = self.engine.clone();
+        let linker = self.linker.clone();
+        let limits = self.limits;
+        let options = self.options.clone();
+        let path = self.path.clone();
+        tokio::task::spawn_blocking(move || {
+            let loaded = load_module(&engine, &path).and_then(|module| {
+                Instance::new(&linker, &module, &limits, &options)
+                    .map(|instance| (module, instance))
+            });
+            _ = tx.send(loaded);
+        });
+        self.reloading = Some(rx);
+    }
+
+    /// Replaces the running instance with the reloaded module, once it has been loaded.
+    fn finish_reload<F>(&mut self, emit_fn: &mut F)
+    where
+        F: FnMut(Event),
+    {
+        let Some(reloading) = self.reloading.as_mut() else {
+            return;
+        };
+        let loaded = match reloading.try_recv() {
+            Ok(loaded) => loaded,
+            Err(TryRecvError::Empty) => return,
+            // The loading task panicked, which is already logged.
+            Err(TryRecvError::Closed) => {
+                self.reloading = None;
+                return;
+            }
+        };
+        self.reloading = None;
+
+        match loaded {
+            Ok((module, instance)) => {
+                self.shutdown_instance(emit_fn);
+                self.module = module;
+                self.instance = instance;
+                emit!(WasmModuleReloaded { path: &self.path });
+            }
+            Err(error) => emit!(WasmModuleReloadError { error }),
+        }
+    }
+
+    fn shutdown_instance<F>(&mut self, emit_fn: &mut F)
+    where
+        F: FnMut(Event),
+    {
+        match self.instance.shutdown() {
+            Ok(events) => {
+                for mut event in events {
+                    event.set_source_id(Arc::clone(&self.source_id));
+                    emit_fn(event);
+                }
+            }
+            Err(error) => emit!(WasmHookError {
+                hook: "shutdown",
+                error
+            }),
+        }
+    }
+}
+
+fn load_module(engine: &Engine, path: &Path) -> Result<Module, WasmError> {
+    Module::from_file(engine, path)
+        .map_err(Into::into)
+        .context(LoadModuleSnafu { path })
+}
+
+impl RuntimeTransform for Wasm {
+    fn hook_process<F>(&mut self, mut event: Event, mut emit_fn: F)
+    where
+        F: FnMut(Event),
+    {
+        self.finish_reload(&mut emit_fn);
+
+        let source_id = Arc::clone(event.source_id().unwrap_or(&self.source_id));
+        let finalizers = event.metadata_mut().take_finalizers();
+
+        match self.instance.process(event) {
+            Ok(events) => {
+                for mut event in events {
+                    event.set_source_id(Arc::clone(&source_id));
+                    event.metadata_mut().merge_finalizers(finalizers.clone());
+                    emit_fn(event);
+                }
+            }
+            Err(error) => {
+                let is_trap = error.is_trap();
+                emit!(WasmProcessError { error });
+                if is_trap {
+                    self.restart();
+                }
+            }
+        }
+    }
+
+    fn hook_shutdown<F>(&mut self, mut emit_fn: F)
+    where
+        F: FnMut(Event),
+    {
+        self.shutdown_instance(&mut emit_fn);
+    }
+
+    fn timer_handler<F>(&mut self, _timer: Timer, emit_fn: F)
+    where
+        F: FnMut(Event),
+    {
+        self.reload_if_changed(emit_fn);
+    }
+
+    fn timers(&self) -> Vec<Timer> {
+        self.reload_interval
+            .map(|interval| Timer { id: 0, interval })
+            .into_iter()
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use tokio::sync::mpsc;
+    use tokio_stream::wrappers::ReceiverStream;
+
+    use super::*;
+    use crate::event::{LogEvent, Value};
+    use crate::test_util::{components::assert_transform_compliance, temp_file, trace_init};
+    use crate::transforms::test::create_topology;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<WasmConfig>();
+    }
+
+    /// Builds a text module implementing the ABI, with the given body for `vector_process`.
+    ///
+    /// Every allocation returns the same buffer, which is enough for processing one small event
+    /// at a time.
+    fn module(abi_version: u32, process: &str) -> String {
+        format!(
+            r#"(module
+                (import "vector" "emit" (func $emit (param i32 i32)))
+                (memory (export "memory") 1)
+                (global $count (mut i32) (i32.const 0))
+                (func (export "vector_abi_version") (result i32) (i32.const {abi_version}))
+                (func (export "vector_alloc") (param i32) (result i32) (i32.const 1024))
+                (func (export "vector_init") (param i32 i32) (result i32) (i32.const 0))
+                (func (export "vector_process") (param $ptr i32) (param $len i32) (result i32)
+                    {process})
+                (func (export "vector_shutdown") (result i32) (i32.const 0)))"#
+        )
+    }
+
+    const PASSTHROUGH: &str = "(call $emit (local.get $ptr) (local.get $len)) (i32.const 0)";
+
+    fn write_module(path: &Path, abi_version: u32, process: &str) {
+        std::fs::write(path, module(abi_version, process)).unwrap();
+    }
+
+    fn touch(path: &Path) {
+        std::fs::File::options()
+            .write(true)
+            .open(path)
+            .unwrap()
+            .set_modified(SystemTime::now() + Duration::from_secs(1))
+            .unwrap();
+    }
+
+    fn config(path: PathBuf) -> WasmConfig {
+        WasmConfig {
+            module: path,
+            options: None,
+            max_fuel: None,
+            max_memory_bytes: None,
+            hot_reload: false,
+            reload_interval_secs: default_reload_interval_secs(),
+        }
+    }
+
+    fn build(config: &WasmConfig) -> Wasm {
+        Wasm::new(config, ComponentKey::from("transform")).unwrap()
+    }
+
+    fn process(transform: &mut Wasm, event: Event) -> Vec<Event> {
+        let mut output = Vec::new();
+        transform.hook_process(event, |event| output.push(event));
+        output
+    }
+
+    fn message(event: &Event) -> &Value {
+        event.as_log().get("message").unwrap()
+    }
+
+    #[test]
+    fn passes_events_through() {
+        let path = temp_file();
+        write_module(&path, ABI_VERSION, PASSTHROUGH);
+        let mut transform = build(&config(path));
+
+        let output = process(&mut transform, LogEvent::from("hello").into());
+
+        assert_eq!(output.len(), 1);
+        assert_eq!(message(&output[0]), &Value::from("hello"));
+    }
+
+    #[test]
+    fn drops_and_duplicates_events() {
+        let path = temp_file();
+        write_module(&path, ABI_VERSION, "(i32.const 0)");
+        let mut transform = build(&config(path.clone()));
+        assert!(process(&mut transform, LogEvent::from("hello").into()).is_empty());
+
+        write_module(
+            &path,
+            ABI_VERSION,
+            &format!(
+                "(call $emit (local.get $ptr) (local.get $len)) {}",
+                PASSTHROUGH
+            ),
+        );
+        let mut transform = build(&config(path));
+        assert_eq!(
+            process(&mut transform, LogEvent::from("hello").into()).len(),
+            2
+        );
+    }
+
+    #[test]
+    fn keeps_state_between_events() {
+        // Only every other event is emitted, based on a counter held by the instance.
+        let path = temp_file();
+        write_module(
+            &path,
+            ABI_VERSION,
+            "(global.set $count (i32.add (global.get $count) (i32.const 1)))
+             (if (i32.rem_u (global.get $count) (i32.const 2))
+                 (then (call $emit (local.get $ptr) (local.get $len))))
+             (i32.const 0)",
+        );
+        let mut transform = build(&config(path));
+
+        let emitted = (0..4)
+            .map(|_| process(&mut transform, LogEvent::from("hello").into()).len())
+            .collect::<Vec<_>>();
+        assert_eq!(emitted, vec![1, 0, 1, 0]);
+    }
+
+    #[test]
+    fn drops_events_on_error_status() {
+        let path = temp_file();
+        write_module(
+            &path,
+            ABI_VERSION,
+            "(call $emit (local.get $ptr) (local.get $len)) (i32.const 2)",
+        );
+        let mut transform = build(&config(path));
+
+        assert!(process(&mut transform, LogEvent::from("hello").into()).is_empty());
+    }
+
+    #[test]
+    fn rejects_unsupported_abi_version() {
+        let path = temp_file();
+        write_module(&path, ABI_VERSION + 1, PASSTHROUGH);
+
+        let error = Wasm::new(&config(path), ComponentKey::from("transform"))
+            .err()
+            .unwrap();
+        assert!(error.to_string().contains("ABI version"));
+    }
+
+    #[test]
+    fn restarts_instance_after_running_out_of_fuel() {
+        // Every event after the first loops forever, unless the instance is restarted with a
+        // fresh counter.
+        let path = temp_file();
+        write_module(
+            &path,
+            ABI_VERSION,
+            "(global.set $count (i32.add (global.get $count) (i32.const 1)))
+             (if (i32.ge_u (global.get $count) (i32.const 2))
+                 (then (loop $forever (br $forever))))
+             (call $emit (local.get $ptr) (local.get $len))
+             (i32.const 0)",
+        );
+        let mut config = config(path);
+        config.max_fuel = Some(100_000);
+        let mut transform = build(&config);
+
+        assert_eq!(
+            process(&mut transform, LogEvent::from("first").into()).len(),
+            1
+        );
+        assert!(process(&mut transform, LogEvent::from("second").into()).is_empty());
+        let output = process(&mut transform, LogEvent::from("third").into());
+        assert_eq!(output.len(), 1);
+        assert_eq!(message(&output[0]), &Value::from("third"));
+    }
+
+    /// Waits for the module being reloaded in the background to replace the running instance.
+    async fn finish_reload(transform: &mut Wasm) {
+        tokio::time::timeout(Duration::from_secs(10), async {
+            while transform.reloading.is_some() {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+                transform.finish_reload(&mut |_| {});
+            }
+        })
+        .await
+        .expect("timed out reloading module");
+    }
+
+    #[tokio::test]
+    async fn reloads_module_on_change() {
+        let path = temp_file();
+        write_module(&path, ABI_VERSION, PASSTHROUGH);
+        let mut config = config(path.clone());
+        config.hot_reload = true;
+        let mut transform = build(&config);
+        assert_eq!(transform.timers().len(), 1);
+
+        write_module(&path, ABI_VERSION, "(i32.const 0)");
+        // Make sure the modification time changes, even on coarse filesystems.
+        touch(&path);
+        transform.timer_handler(transform.timers()[0], |_| {});
+        assert!(transform.reloading.is_some());
+
+        finish_reload(&mut transform).await;
+        assert!(process(&mut transform, LogEvent::from("hello").into()).is_empty());
+    }
+
+    #[tokio::test]
+    async fn keeps_running_instance_when_reload_fails() {
+        let path = temp_file();
+        write_module(&path, ABI_VERSION, PASSTHROUGH);
+        let mut config = config(path.clone());
+        config.hot_reload = true;
+        let mut transform = build(&config);
+
+        std::fs::write(&path, "not a module").unwrap();
+        touch(&path);
+        transform.timer_handler(transform.timers()[0], |_| {});
+        finish_reload(&mut transform).await;
+
+        assert_eq!(
+            process(&mut transform, LogEvent::from("hello").into()).len(),
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn wasm_compliance() {
+        trace_init();
+        let path = temp_file();
+        write_module(&path, ABI_VERSION, PASSTHROUGH);
+        let config = config(path);
+
+        assert_transform_compliance(async move {
+            let (tx, rx) = mpsc::channel(1);
+            let (topology, mut out) = create_topology(ReceiverStream::new(rx), config).await;
+
+            tx.send(LogEvent::from("hello").into()).await.unwrap();
+            let event = out.recv().await.unwrap();
+            assert_eq!(message(&event), &Value::from("hello"));
+
+            drop(tx);
+            topology.stop().await;
+            assert_eq!(out.recv().await, None);
+        })
+        .await;
+    }
+}
@@ -0,0 +1,472 @@
+use std::{
+    collections::VecDeque,
+    num::{NonZeroU64, NonZeroUsize},
+};
+
+use lru::LruCache;
+use vector_lib::config::{clone_input_definitions, LogNamespace};
+use vector_lib::configurable::configurable_component;
+use vector_lib::internal_event::{Count, InternalEventHandle as _, Registered};
+
+use crate::{
+    conditions::{AnyCondition, Condition},
+    config::{
+        DataType, GenerateConfig, Input, OutputId, TransformConfig, TransformContext,
+        TransformOutput,
+    },
+    event::{discriminant::Discriminant, Event},
+    internal_events::WindowEventsDropped,
+    schema,
+    transforms::{FunctionTransform, OutputBuffer, Transform},
+};
+
+/// Configuration for the `window` transform.
+#[configurable_component(transform(
+    "window",
+    "Apply a buffered sliding window over the stream of events and flush it based on supplied criteria."
+))]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct WindowConfig {
+    /// A condition used to pass events through the transform without buffering them.
+    ///
+    /// If the condition resolves to `true` for an event, the event is immediately forwarded,
+    /// without being buffered or checked against `flush_when`.
+    pub forward_when: Option<AnyCondition>,
+
+    /// A condition used to flush the window.
+    ///
+    /// If the condition resolves to `true` for an event, the events buffered before it are
+    /// forwarded, followed by the event itself and up to `num_events_after` events after it.
+    pub flush_when: AnyCondition,
+
+    /// The maximum number of events to keep before the event that matched `flush_when`.
+    ///
+    /// Once the window is full, the oldest event in it is dropped for every new event.
+    #[serde(default = "default_num_events_before")]
+    pub num_events_before: usize,
+
+    /// The maximum number of events to forward after the event that matched `flush_when`.
+    #[serde(default = "default_num_events_after")]
+    pub num_events_after: usize,
+
+    /// An ordered list of fields by which to group events.
+    ///
+    /// Each group with matching values for the specified keys has its own window, which is only
+    /// flushed by events of the same group. When no fields are specified, all events share a single
+    /// window.
+    ///
+    /// For example, if `group_by = ["host"]`, then an error logged by one host flushes the events
+    /// buffered for that host only.
+    #[serde(default)]
+    #[configurable(metadata(docs::examples = "host", docs::examples = "request_id"))]
+    pub group_by: Vec<String>,
+
+    /// The maximum number of windows to keep, one for each group of events.
+    ///
+    /// Once the limit is reached, the window of the group that has gone the longest without
+    /// receiving an event is discarded to make room for a new one.
+    #[serde(default = "default_max_windows")]
+    pub max_windows: NonZeroUsize,
+
+    /// The rate at which events discarded from a window are forwarded anyway, expressed as `1/N`.
+    ///
+    /// For example, `sample_rate = 10` forwards 1 out of every 10 events that are pushed out of a
+    /// window without having been flushed. If left unspecified, these events are all dropped.
+    #[configurable(metadata(docs::examples = 10))]
+    pub sample_rate: Option<NonZeroU64>,
+}
+
+const fn default_num_events_before() -> usize {
+    100
+}
+
+const fn default_max_windows() -> NonZeroUsize {
+    match NonZeroUsize::new(10_000) {
+        Some(max_windows) => max_windows,
+        None => unreachable!(),
+    }
+}
+
+const fn default_num_events_after() -> usize {
+    0
+}
+
+impl GenerateConfig for WindowConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(r#"flush_when = ".level == \"error\"""#).unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "window")]
+impl TransformConfig for WindowConfig {
+    async fn build(&self, context: &TransformContext) -> crate::Result<Transform> {
+        Ok(Transform::function(Window::new(
+            self.forward_when
+                .as_ref()
+                .map(|condition| condition.build(&context.enrichment_tables))
+                .transpose()?,
+            self.flush_when.build(&context.enrichment_tables)?,
+            self.num_events_before,
+            self.num_events_after,
+            self.group_by.clone(),
+            self.max_windows,
+            self.sample_rate,
+        )))
+    }
+
+    fn input(&self) -> Input {
+        Input::log()
+    }
+
+    fn outputs(
+        &self,
+        _: vector_lib::enrichment::TableRegistry,
+        input_definitions: &[(OutputId, schema::Definition)],
+        _: LogNamespace,
+    ) -> Vec<TransformOutput> {
+        vec![TransformOutput::new(
+            DataType::Log,
+            clone_input_definitions(input_definitions),
+        )]
+    }
+}
+
+/// The window of a single group of events.
+#[derive(Clone, Debug, Default)]
+struct WindowState {
+    buffer: VecDeque<Event>,
+    /// The number of events left to forward after a flush.
+    remaining_after: usize,
+}
+
+#[derive(Clone)]
+pub struct Window {
+    forward_when: Option<Condition>,
+    flush_when: Condition,
+    num_events_before: usize,
+    num_events_after: usize,
+    group_by: Vec<String>,
+    windows: LruCache<Discriminant, WindowState>,
+    sample_rate: Option<NonZeroU64>,
+    /// The number of events discarded so far, used for sampling them.
+    discarded: u64,
+    events_dropped: Registered<WindowEventsDropped>,
+}
+
+impl Window {
+    pub fn new(
+        forward_when: Option<Condition>,
+        flush_when: Condition,
+        num_events_before: usize,
+        num_events_after: usize,
+        group_by: Vec<String>,
+        max_windows: NonZeroUsize,
+        sample_rate: Option<NonZeroU64>,
+    ) -> Self {
+        Self {
+            forward_when,
+            flush_when,
+            num_events_before,
+            num_events_after,
+            group_by,
+            windows: LruCache::new(max_windows),
+            sample_rate,
+            discarded: 0,
+            events_dropped: register!(WindowEventsDropped),
+        }
+    }
+
+    /// Gets the window of a group, discarding the least recently used window if there are too
+    /// many of them.
+    fn window(
+        &mut self,
+        output: &mut OutputBuffer,
+        discriminant: Discriminant,
+    ) -> &mut WindowState {
+        if !self.windows.contains(&discriminant) && self.windows.len() == self.windows.cap().get() {
+            if let Some((_, window)) = self.windows.pop_lru() {
+                for event in window.buffer {
+                    self.discard(output, event);
+                }
+            }
+        }
+        self.windows
+            .get_or_insert_mut(discriminant, WindowState::default)
+    }
+
+    /// Drops an event that wasn't flushed from its window, unless it's sampled.
+    fn discard(&mut self, output: &mut OutputBuffer, event: Event) {
+        let sampled = self
+            .sample_rate
+            .is_some_and(|rate| self.discarded % rate.get() == 0);
+        self.discarded = self.discarded.wrapping_add(1);
+
+        if sampled {
+            output.push(event);
+        } else {
+            self.events_dropped.emit(Count(1));
+        }
+    }
+}
+
+impl FunctionTransform for Window {
+    fn transform(&mut self, output: &mut OutputBuffer, event: Event) {
+        let event = match self.forward_when.as_ref() {
+            Some(condition) => {
+                let (forward, event) = condition.check(event);
+                if forward {
+                    output.push(event);
+                    return;
+                }
+                event
+            }
+            None => event,
+        };
+
+        let discriminant = Discriminant::from_log_event(event.as_log(), &self.group_by);
+        let (flush, event) = self.flush_when.check(event);
+
+        if flush {
+            if let Some(window) = self.windows.pop(&discriminant) {
+                output.extend(window.buffer.into_iter());
+            }
+            output.push(event);
+            // Windows are only kept while they hold events, or have events left to forward.
+            if self.num_events_after > 0 {
+                let num_events_after = self.num_events_after;
+                self.window(output, discriminant).remaining_after = num_events_after;
+            }
+            return;
+        }
+
+        if let Some(window) = self.windows.get_mut(&discriminant) {
+            if window.remaining_after > 0 {
+                window.remaining_after -= 1;
+                output.push(event);
+                if window.remaining_after == 0 {
+                    self.windows.pop(&discriminant);
+                }
+                return;
+            }
+        }
+
+        if self.num_events_before == 0 {
+            self.discard(output, event);
+            return;
+        }
+
+        let num_events_before = self.num_events_before;
+        let window = self.window(output, discriminant);
+        window.buffer.push_back(event);
+        if window.buffer.len() > num_events_before {
+            let event = window.buffer.pop_front().expect("window is not empty");
+            self.discard(output, event);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::sync::mpsc;
+    use tokio_stream::wrappers::ReceiverStream;
+
+    use super::*;
+    use crate::{
+        conditions::{ConditionConfig, VrlConfig},
+        event::LogEvent,
+        test_util::components::assert_transform_compliance,
+        transforms::test::create_topology,
+    };
+
+    fn condition(source: &str) -> Condition {
+        VrlConfig {
+            source: source.to_string(),
+            runtime: Default::default(),
+        }
+        .build(&Default::default())
+        .expect("should not fail to build VRL condition")
+    }
+
+    /// Builds a window flushed by events with an `error` message.
+    fn window(
+        forward_when: Option<Condition>,
+        num_events_before: usize,
+        num_events_after: usize,
+        group_by: Vec<String>,
+    ) -> Window {
+        Window::new(
+            forward_when,
+            condition(r#".message == "error""#),
+            num_events_before,
+            num_events_after,
+            group_by,
+            default_max_windows(),
+            None,
+        )
+    }
+
+    fn make_event(message: &str, host: &str) -> Event {
+        let mut log = LogEvent::from(message);
+        log.insert("host", host);
+        Event::Log(log)
+    }
+
+    fn run(window: &mut Window, events: Vec<Event>) -> Vec<String> {
+        let mut output = OutputBuffer::default();
+        for event in events {
+            window.transform(&mut output, event);
+        }
+        output
+            .into_events()
+            .map(|event| event.as_log()["message"].to_string_lossy().into_owned())
+            .collect()
+    }
+
+    fn messages(messages: &[&str]) -> Vec<Event> {
+        messages
+            .iter()
+            .map(|message| make_event(message, "a"))
+            .collect()
+    }
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<WindowConfig>();
+    }
+
+    #[test]
+    fn flushes_events_before_trigger() {
+        let mut window = window(None, 2, 0, Vec::new());
+
+        let output = run(
+            &mut window,
+            messages(&["debug 1", "debug 2", "debug 3", "error", "debug 4"]),
+        );
+        assert_eq!(output, vec!["debug 2", "debug 3", "error"]);
+
+        // The window starts out empty after a flush.
+        let output = run(&mut window, messages(&["error"]));
+        assert_eq!(output, vec!["debug 4", "error"]);
+    }
+
+    #[test]
+    fn forwards_events_after_trigger() {
+        let mut window = window(None, 1, 2, Vec::new());
+
+        let output = run(
+            &mut window,
+            messages(&[
+                "debug 1", "error", "debug 2", "debug 3", "debug 4", "debug 5",
+            ]),
+        );
+        assert_eq!(output, vec!["debug 1", "error", "debug 2", "debug 3"]);
+    }
+
+    #[test]
+    fn forward_when_bypasses_window() {
+        let mut window = window(Some(condition(r#".message == "info""#)), 1, 0, Vec::new());
+
+        let output = run(
+            &mut window,
+            messages(&["debug 1", "info", "debug 2", "info", "error"]),
+        );
+        assert_eq!(output, vec!["info", "info", "debug 2", "error"]);
+    }
+
+    #[test]
+    fn discards_everything_without_events_before() {
+        let mut window = window(None, 0, 0, Vec::new());
+
+        let output = run(&mut window, messages(&["debug 1", "debug 2", "error"]));
+        assert_eq!(output, vec!["error"]);
+    }
+
+    #[test]
+    fn groups_have_separate_windows() {
+        let mut window = window(None, 2, 1, vec!["host".to_string()]);
+
+        let output = run(
+            &mut window,
+            vec![
+                make_event("a 1", "a"),
+                make_event("b 1", "b"),
+                make_event("a 2", "a"),
+                make_event("error", "a"),
+                make_event("b 2", "b"),
+                make_event("a 3", "a"),
+                make_event("a 4", "a"),
+                make_event("error", "b"),
+            ],
+        );
+        assert_eq!(
+            output,
+            vec!["a 1", "a 2", "error", "a 3", "b 1", "b 2", "error"]
+        );
+    }
+
+    #[test]
+    fn discards_least_recently_used_window() {
+        let mut window = window(None, 2, 0, vec!["host".to_string()]);
+        window.windows.resize(NonZeroUsize::new(2).unwrap());
+
+        let output = run(
+            &mut window,
+            vec![
+                make_event("a 1", "a"),
+                make_event("b 1", "b"),
+                make_event("a 2", "a"),
+                make_event("c 1", "c"),
+                make_event("error", "a"),
+                make_event("error", "b"),
+                make_event("error", "c"),
+            ],
+        );
+        assert_eq!(output, vec!["a 1", "a 2", "error", "error", "c 1", "error"]);
+        assert!(window.windows.is_empty());
+    }
+
+    #[test]
+    fn samples_discarded_events() {
+        let mut window = window(None, 1, 0, Vec::new());
+        window.sample_rate = NonZeroU64::new(2);
+
+        let output = run(
+            &mut window,
+            messages(&[
+                "debug 1", "debug 2", "debug 3", "debug 4", "debug 5", "error",
+            ]),
+        );
+        assert_eq!(output, vec!["debug 1", "debug 3", "debug 5", "error"]);
+    }
+
+    #[tokio::test]
+    async fn emits_internal_events() {
+        assert_transform_compliance(async move {
+            let config = toml::from_str::<WindowConfig>(
+                r#"
+flush_when = '.message == "error"'
+num_events_before = 1
+"#,
+            )
+            .unwrap();
+
+            let (tx, rx) = mpsc::channel(1);
+            let (topology, mut out) = create_topology(ReceiverStream::new(rx), config).await;
+
+            tx.send(make_event("debug", "a")).await.unwrap();
+            tx.send(make_event("error", "a")).await.unwrap();
+
+            let event = out.recv().await.unwrap();
+            assert_eq!(event.as_log()["message"], "debug".into());
+            let event = out.recv().await.unwrap();
+            assert_eq!(event.as_log()["message"], "error".into());
+
+            drop(tx);
+            topology.stop().await;
+            assert_eq!(out.recv().await, None);
+        })
+        .await;
+    }
+}
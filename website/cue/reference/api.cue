@@ -58,6 +58,118 @@ api: {
 				endpoint of the address set using the `bind` parameter.
 				"""
 		}
+		mutation_token: {
+			common:   false
+			required: false
+			type: string: {
+				default: null
+				examples: ["${VECTOR_API_MUTATION_TOKEN}"]
+			}
+			description: """
+				The token that GraphQL mutations must be authorized with. Mutations
+				control the running instance, such as pausing and resuming components,
+				flushing sinks, or reloading the configuration, so they are disabled
+				unless this is set. Requests must then send the token in an
+				`Authorization: Bearer <token>` header. This can't be set along with
+				`auth`, in which case every authenticated request is allowed to run
+				mutations.
+				"""
+		}
+		auth: {
+			common:   false
+			required: false
+			description: """
+				The credentials that requests to the `/graphql` and `/playground`
+				endpoints must carry, including the WebSocket connections used for
+				subscriptions. Requests without them are rejected with a `401`.
+				The `/health` endpoint is left open, so that it can still be used as
+				a liveness probe. The `vector top` and `vector tap` commands
+				authenticate with the `--token`, or `--user` and `--password`, flags.
+				"""
+			type: object: options: {
+				strategy: {
+					required:    true
+					description: "The authentication strategy to use."
+					type: string: enum: {
+						basic:  "Basic authentication, with the `user` and `password` options."
+						bearer: "Bearer authentication, with the `token` option."
+					}
+				}
+				user: {
+					required:      false
+					relevant_when: "strategy = \"basic\""
+					description:   "The basic authentication username."
+					type: string: {
+						default: null
+						examples: ["${VECTOR_API_USER}"]
+					}
+				}
+				password: {
+					required:      false
+					relevant_when: "strategy = \"basic\""
+					description:   "The basic authentication password."
+					type: string: {
+						default: null
+						examples: ["${VECTOR_API_PASSWORD}"]
+					}
+				}
+				token: {
+					required:      false
+					relevant_when: "strategy = \"bearer\""
+					description:   "The bearer authentication token."
+					type: string: {
+						default: null
+						examples: ["${VECTOR_API_TOKEN}"]
+					}
+				}
+			}
+		}
+		tls: {
+			common:   false
+			required: false
+			description: """
+				Serves the API over HTTPS, and subscriptions over secure WebSockets,
+				using the given certificate. Use the `--ca-file` flag of `vector top`
+				and `vector tap` to trust a certificate that isn't signed by a
+				well-known authority.
+				"""
+			type: object: options: {
+				enabled: {
+					required:    false
+					description: "Whether TLS is enabled."
+					type: bool: default: false
+				}
+				crt_file: {
+					required:    false
+					description: "Path to the certificate file, in PEM or DER format."
+					type: string: {
+						default: null
+						examples: ["/etc/vector/api.crt"]
+					}
+				}
+				key_file: {
+					required:    false
+					description: "Path to the private key file for `crt_file`."
+					type: string: {
+						default: null
+						examples: ["/etc/vector/api.key"]
+					}
+				}
+				ca_file: {
+					required:    false
+					description: "Path to a CA certificate file, for verifying client certificates."
+					type: string: {
+						default: null
+						examples: ["/etc/vector/ca.crt"]
+					}
+				}
+				verify_certificate: {
+					required:    false
+					description: "Whether to require clients to present a certificate signed by `ca_file`."
+					type: bool: default: false
+				}
+			}
+		}
 	}
 
 	endpoints: {
@@ -196,6 +196,47 @@ cli: {
 	options: _core_options
 
 	commands: {
+		"buffer": {
+			description: """
+				Inspect the disk buffer of a sink while Vector is stopped. The `info` subcommand shows the
+				number of records, events, and bytes in the buffer, as well as the timestamps of the oldest
+				and newest events. The `dump` subcommand writes the buffered events to stdout as native JSON,
+				one event per line, and the `replay` subcommand sends them to another sink.
+
+				The buffer is opened read-only and left as-is, so that Vector delivers the events the next
+				time it starts, unless `--consume` is given.
+				"""
+
+			example: "vector buffer --config /etc/vector/vector.yaml replay my_sink --to my_other_sink"
+
+			flags: _default_flags & {
+				"consume": {
+					description: "Remove the dumped or replayed events from the buffer"
+				}
+			}
+
+			options: _core_config_options & {
+				"limit": {
+					description: "Stop after dumping this many events"
+					type:        "integer"
+				}
+				"to": {
+					description: "The ID of the sink to replay the events to"
+					type:        "string"
+				}
+				"to-config": {
+					description: "Read the sink to replay the events to from these config files"
+					type:        "string"
+				}
+			}
+
+			args: {
+				sink: {
+					description: "The ID of the sink whose buffer should be inspected"
+					type:        "string"
+				}
+			}
+		}
 		"graph": {
 			description: """
 				Generate a visual representation of topologies. The output is in the [DOT format](\(urls.dot_format)),
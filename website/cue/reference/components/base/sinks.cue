@@ -12,6 +12,86 @@ base: components: sinks: configuration: {
 			"""
 		required: false
 		type: object: options: {
+			compression: {
+				description: """
+					Compresses the buffered events before writing them to disk.
+
+					Records that would not get any smaller are written uncompressed. Records already in the
+					buffer remain readable when this is changed, regardless of how they were written.
+
+					When not set, events are written to disk uncompressed.
+					"""
+				relevant_when: "type = \"disk\""
+				required:      false
+				type: string: enum: {
+					lz4: """
+						[LZ4][lz4] compression.
+
+						Favors speed over compression ratio.
+
+						[lz4]: https://lz4.github.io/lz4/
+						"""
+					zstd: """
+						[Zstandard][zstd] compression.
+
+						Favors compression ratio over speed.
+
+						[zstd]: https://facebook.github.io/zstd/
+						"""
+				}
+			}
+			encryption: {
+				description: """
+					Encrypts the buffered events at rest.
+
+					When not set, events are written to disk in plaintext.
+					"""
+				relevant_when: "type = \"disk\""
+				required:      false
+				type: object: options: {
+					key: {
+						description: """
+							The key used to encrypt new records, as 32 bytes encoded with base64.
+
+							Use a secret reference, such as `SECRET[backend.disk_buffer_key]`, to avoid storing the key
+							in the configuration itself.
+							"""
+						required: true
+						type: string: examples: ["SECRET[backend.disk_buffer_key]"]
+					}
+					key_version: {
+						description: """
+							The version of `key`.
+
+							When rotating keys, increment the version, and move the previous key to `previous_keys`
+							until all of the records encrypted with it have been read.
+							"""
+						required: true
+						type: uint: {}
+					}
+					previous_keys: {
+						description: """
+							Previous keys, which are only used to decrypt records written before the key was rotated.
+
+							Vector refuses to start if the buffer holds unread records encrypted with a key version
+							that is neither `key_version` nor listed here.
+							"""
+						required: false
+						type: array: items: type: object: options: {
+							key: {
+								description: "The key, as 32 bytes encoded with base64."
+								required:    true
+								type: string: {}
+							}
+							version: {
+								description: "The version of the key."
+								required:    true
+								type: uint: {}
+							}
+						}
+					}
+				}
+			}
 			max_events: {
 				description:   "The maximum number of events allowed in the buffer."
 				relevant_when: "type = \"memory\""
@@ -71,6 +151,17 @@ base: components: sinks: configuration: {
 														highest priority, and it is preferable to temporarily lose events rather than cause a
 														slowdown in the acceptance/consumption of events.
 														"""
+						drop_oldest: """
+														Drops the oldest events in the buffer to make room for the event.
+
+														The evicted events will be intentionally dropped. This mode is typically used when the
+														freshest data is the most valuable, such as for live-tailing or metrics, and it is preferable
+														to lose older events that a stuck sink has not yet processed rather than newer ones.
+
+														Disk buffers evict whole data files at a time, starting with the oldest data file that has
+														not yet been read from. If the only data files left are the one being read from and the one
+														being written to, the event is dropped instead.
+														"""
 					}
 				}
 			}
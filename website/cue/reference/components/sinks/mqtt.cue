@@ -0,0 +1,62 @@
+package metadata
+
+components: sinks: mqtt: {
+	title: "MQTT"
+
+	classes: {
+		commonly_used: false
+		delivery:      "at_least_once"
+		development:   "beta"
+		egress_method: "stream"
+		service_providers: []
+		stateful: false
+	}
+
+	features: {
+		auto_generated:   true
+		acknowledgements: true
+		healthcheck: enabled: true
+		send: {
+			compression: enabled: false
+			encoding: {
+				enabled: true
+				codec: {
+					enabled: true
+					enum: ["json", "text"]
+				}
+			}
+			request: enabled: false
+			tls: {
+				enabled:                true
+				can_verify_certificate: true
+				can_verify_hostname:    true
+				enabled_default:        false
+				enabled_by_scheme:      false
+			}
+			to: components._mqtt.features.send.to
+		}
+	}
+
+	support: components._mqtt.support
+
+	configuration: base.components.sinks.mqtt.configuration
+
+	input: {
+		logs:    true
+		metrics: null
+		traces:  false
+	}
+
+	how_it_works: components._mqtt.how_it_works & {
+		delivery: {
+			title: "Delivery"
+			body:  """
+				Events published with a `qos` of `at_least_once` or `exactly_once` are only considered
+				delivered once the broker has acknowledged them. When the connection to the broker is lost,
+				the events that weren't acknowledged yet are published again after reconnecting, so they
+				can be delivered twice. Events published with a `qos` of `at_most_once` are considered
+				delivered as soon as they have been sent, and can be lost.
+				"""
+		}
+	}
+}
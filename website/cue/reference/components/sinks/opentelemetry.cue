@@ -0,0 +1,126 @@
+package metadata
+
+components: sinks: opentelemetry: {
+	title: "OpenTelemetry"
+
+	description: """
+		Exports logs, metrics, and traces to any receiver implementing the
+		[OpenTelemetry Protocol (OTLP)](\(urls.opentelemetry_protocol)), such as the OpenTelemetry
+		Collector, over either gRPC or HTTP.
+		"""
+
+	classes: {
+		commonly_used: false
+		delivery:      "at_least_once"
+		development:   "beta"
+		egress_method: "batch"
+		service_providers: []
+		stateful: false
+	}
+
+	features: {
+		acknowledgements: true
+		auto_generated:   true
+		healthcheck: enabled: true
+		send: {
+			batch: {
+				enabled:      true
+				common:       false
+				max_bytes:    10_000_000
+				timeout_secs: 1.0
+			}
+			compression: {
+				enabled: true
+				default: "none"
+				algorithms: ["none", "gzip", "snappy", "zlib", "zstd"]
+				levels: ["none", "fast", "default", "best", 0, 1, 2, 3, 4, 5, 6, 7, 8, 9]
+			}
+			encoding: enabled: false
+			request: {
+				enabled: true
+				headers: true
+			}
+			tls: {
+				enabled:                true
+				can_verify_certificate: true
+				can_verify_hostname:    true
+				enabled_default:        false
+				enabled_by_scheme:      false
+			}
+			to: {
+				service: services.opentelemetry
+
+				interface: {
+					socket: {
+						direction: "outgoing"
+						protocols: ["http"]
+						ssl: "optional"
+					}
+				}
+			}
+		}
+	}
+
+	support: {
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	input: {
+		logs: true
+		metrics: {
+			counter:      true
+			distribution: true
+			gauge:        true
+			histogram:    true
+			summary:      true
+			set:          true
+		}
+		traces: true
+	}
+
+	configuration: base.components.sinks.opentelemetry.configuration
+
+	how_it_works: {
+		logs: {
+			title: "Log conversion"
+			body:  """
+				Logs received from the `opentelemetry` source are exported with their original resource,
+				attributes, severity, and trace context. For other logs, the `message` field becomes the
+				log record body and all remaining fields become log record attributes.
+				"""
+		}
+		metrics: {
+			title: "Metric conversion"
+			body:  """
+				Vector metrics are converted into OTLP metrics as follows:
+
+				- Counters become monotonic sums.
+				- Absolute gauges become gauges, and incremental gauges become non-monotonic sums.
+				- Sets become gauges reporting the number of distinct values.
+				- Distributions and aggregated histograms become histograms.
+				- Aggregated summaries and sketches become summaries.
+
+				Incremental metrics are exported with delta aggregation temporality, and absolute
+				metrics with cumulative aggregation temporality. Tags prefixed with `resource.` become
+				resource attributes, the `scope.name` and `scope.version` tags become the
+				instrumentation scope, and all other tags become data point attributes.
+				"""
+		}
+		traces: {
+			title: "Trace conversion"
+			body:  """
+				Traces are expected to follow the layout produced by the `opentelemetry` source. Fields
+				that have no OTLP counterpart are exported as span attributes.
+				"""
+		}
+		healthcheck: {
+			title: "Health checks"
+			body:  """
+				OTLP has no health checking mechanism of its own, so the health check sends an empty
+				logs export request, which receivers are expected to accept.
+				"""
+		}
+	}
+}
@@ -0,0 +1,114 @@
+package metadata
+
+components: sources: azure_blob: {
+	title: "Azure Blob Storage"
+
+	features: {
+		auto_generated:   true
+		acknowledgements: true
+		multiline: enabled: true
+		collect: {
+			tls: enabled:        false
+			checkpoint: enabled: true
+			proxy: enabled:      false
+			from: service:       services.azure_blob
+		}
+		codecs: {
+			enabled:         true
+			default_framing: "`newline_delimited`"
+		}
+	}
+
+	classes: {
+		commonly_used: false
+		deployment_roles: ["aggregator"]
+		delivery:      "at_least_once"
+		development:   "beta"
+		egress_method: "batch"
+		stateful:      true
+	}
+
+	support: {
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	installation: {
+		platform_name: null
+	}
+
+	configuration: base.components.sources.azure_blob.configuration
+
+	output: logs: object: {
+		description: "A line from a blob."
+		fields: {
+			message: {
+				description: "A line from the blob."
+				required:    true
+				type: string: {
+					examples: ["53.126.150.246 - - [01/Oct/2020:11:25:58 -0400] \"GET /disintermediate HTTP/2.0\" 401 20308"]
+				}
+			}
+			timestamp: fields._current_timestamp & {
+				description: "The last modification time of the blob."
+			}
+			source_type: {
+				description: "The name of the source type."
+				required:    true
+				type: string: {
+					examples: ["azure_blob"]
+				}
+			}
+			container: {
+				description: "The container of the blob the line came from."
+				required:    true
+				type: string: {
+					examples: ["my-logs"]
+				}
+			}
+			object: {
+				description: "The name of the blob the line came from."
+				required:    true
+				type: string: {
+					examples: ["logs/2024/01/01/app.log.gz"]
+				}
+			}
+		}
+	}
+
+	how_it_works: {
+		listing: {
+			title: "Listing blobs"
+			body:  """
+				Every `poll_interval_secs`, the source lists the blobs of the container whose name
+				starts with `blob_prefix`, and ingests the ones that were created or modified since the
+				last ingested blob, in the order of their modification time. Each blob is read
+				in full before the next one, and results in one event per line (unless the
+				`multiline` or `framing` options are used).
+
+				Blobs are decompressed according to the `compression` option. By default, the
+				compression scheme is determined from the `Content-Encoding` and `Content-Type` of the
+				blob, as well as its name suffix (for example, `.gz`).
+				"""
+		}
+		checkpointing: {
+			title: "Checkpointing"
+			body:  """
+				The modification time of the last ingested blob is persisted in the `data_dir`
+				directory, so that the source resumes where it left off after a restart. If
+				end-to-end acknowledgements are enabled, a blob is only checkpointed once all of
+				its events are delivered, and blobs whose delivery failed are ingested again on
+				the next listing. Blobs that can't be read are retried on the following
+				listings, and skipped after three failed attempts in a row.
+
+				Only blobs modified after the last ingested blob are ingested, so a blob that
+				appears after newer blobs have been ingested, with an earlier modification time, is
+				never ingested.
+
+				Blobs that are overwritten are ingested again. Since all the matching blobs are
+				listed on each poll, scoping `blob_prefix` to the blobs to ingest keeps listings cheap.
+				"""
+		}
+	}
+}
@@ -43,6 +43,24 @@ base: components: sources: http_client: configuration: {
 			}
 		}
 	}
+	data_dir: {
+		description: """
+			The directory used to persist the checkpoint when `pagination` or `timestamp_field` is set.
+
+			The checkpoint records the pagination position and the last-seen timestamp once the events
+			of each page have been delivered, so that the source resumes where it left off after a
+			restart. Without end-to-end acknowledgements, it's recorded as soon as the events are sent.
+
+			By default, the [global `data_dir` option][global_data_dir] is used.
+			Make sure the running user has write permissions to this directory.
+
+			If this directory is specified, then Vector will attempt to create it.
+
+			[global_data_dir]: https://vector.dev/docs/reference/configuration/global-options/#data_dir
+			"""
+		required: false
+		type: string: examples: ["/var/local/lib/vector/"]
+	}
 	decoding: {
 		description: "Decoder to use on the HTTP responses."
 		required:    false
@@ -325,6 +343,8 @@ base: components: sources: http_client: configuration: {
 			Headers to apply to the HTTP requests.
 
 			One or more values for the same header can be provided.
+
+			Values can be templated in the same way as the values of `query`.
 			"""
 		required: false
 		type: object: {
@@ -354,6 +374,83 @@ base: components: sources: http_client: configuration: {
 			}
 		}
 	}
+	pagination: {
+		description: "Configuration for paginating through the responses of the HTTP endpoint."
+		required:    false
+		type: object: options: {
+			cursor_field: {
+				description:   "The path of the cursor in the JSON body of the response."
+				relevant_when: "strategy = \"cursor\""
+				required:      true
+				type: string: examples: ["next_cursor", "meta.cursor"]
+			}
+			cursor_param: {
+				description:   "The name of the query parameter used to pass the cursor."
+				relevant_when: "strategy = \"cursor\""
+				required:      true
+				type: string: examples: ["cursor"]
+			}
+			limit: {
+				description:   "The number of events to request per page."
+				relevant_when: "strategy = \"offset\""
+				required:      false
+				type: uint: {
+					default: 100
+					unit:    "events"
+				}
+			}
+			limit_param: {
+				description:   "The name of the query parameter used to pass the limit."
+				relevant_when: "strategy = \"offset\""
+				required:      false
+				type: string: default: "limit"
+			}
+			max_pages: {
+				description: """
+					The maximum number of pages to request during a single scrape.
+
+					When this limit is reached, the remaining pages are requested during the next scrape.
+					"""
+				required: false
+				type: uint: default: 100
+			}
+			offset_param: {
+				description:   "The name of the query parameter used to pass the offset."
+				relevant_when: "strategy = \"offset\""
+				required:      false
+				type: string: default: "offset"
+			}
+			strategy: {
+				description: "The pagination strategy to use."
+				required:    true
+				type: string: enum: {
+					cursor: """
+						Pass a cursor, read from the JSON body of the previous response, as a query parameter.
+
+						Pages are requested until a response has no cursor, or the cursor is unchanged. The last
+						cursor received is kept and used during the next scrape, which requests the last page again
+						and only sends the events added to it since.
+						"""
+					link_header: """
+						Follow the `next` link of the [`Link` response header][link_header].
+
+						Pages are requested until a response has no `next` link, or contains no events. The next
+						scrape resumes from the `next` link of the last response, or requests the last page again
+						if it had none, in which case only the events added to it since are sent.
+
+						[link_header]: https://datatracker.ietf.org/doc/html/rfc8288
+						"""
+					offset: """
+						Pass an offset and a limit as query parameters.
+
+						The offset is advanced by the number of events decoded from each page, and pages are
+						requested until a page contains fewer events than the limit. The offset is never reset, so
+						the next scrape only requests events added since the last one.
+						"""
+				}
+			}
+		}
+	}
 	query: {
 		description: """
 			Custom parameters for the HTTP request query string.
@@ -362,6 +459,13 @@ base: components: sources: http_client: configuration: {
 
 			The parameters provided in this option are appended to any parameters
 			manually provided in the `endpoint` option.
+
+			Values can be [templated][template] with the state of the previous request: `cursor`,
+			`offset`, `last_timestamp`, and `response` (the JSON body of the previous response). A
+			value is omitted when a field it references isn't available, such as during the first
+			request.
+
+			[template]: https://vector.dev/docs/reference/configuration/template-syntax/
 			"""
 		required: false
 		type: object: {
@@ -381,6 +485,10 @@ base: components: sources: http_client: configuration: {
 			The interval between scrapes. Requests are run concurrently so if a scrape takes longer
 			than the interval a new scrape will be started. This can take extra resources, set the timeout
 			to a value lower than the scrape interval to prevent this from happening.
+
+			When `pagination` or `timestamp_field` is set, or a query parameter or header value is
+			templated, requests are run sequentially instead, and a new scrape is only started once the
+			previous one is complete.
 			"""
 		required: false
 		type: uint: {
@@ -396,6 +504,18 @@ base: components: sources: http_client: configuration: {
 			unit:    "seconds"
 		}
 	}
+	timestamp_field: {
+		description: """
+			The field of the decoded events holding their timestamp.
+
+			The most recent value seen is available to templated query parameters and headers as
+			`last_timestamp`, so that each scrape only requests newer events, and is kept in the
+			checkpoint. Timestamps and RFC 3339 strings are compared as points in time, and numbers
+			numerically.
+			"""
+		required: false
+		type: string: examples: ["timestamp", "metadata.updated_at"]
+	}
 	tls: {
 		description: "TLS configuration."
 		required:    false
@@ -0,0 +1,527 @@
+package metadata
+
+base: components: sources: pulsar: configuration: {
+	acknowledgements: {
+		deprecated: true
+		description: """
+			Controls how acknowledgements are handled by this source.
+
+			This setting is **deprecated** in favor of enabling `acknowledgements` at the [global][global_acks] or sink level.
+
+			Enabling or disabling acknowledgements at the source level has **no effect** on acknowledgement behavior.
+
+			See [End-to-end Acknowledgements][e2e_acks] for more information on how event acknowledgement is handled.
+
+			[global_acks]: https://vector.dev/docs/reference/configuration/global-options/#acknowledgements
+			[e2e_acks]: https://vector.dev/docs/about/under-the-hood/architecture/end-to-end-acknowledgements/
+			"""
+		required: false
+		type: object: options: enabled: {
+			description: "Whether or not end-to-end acknowledgements are enabled for this source."
+			required:    false
+			type: bool: {}
+		}
+	}
+	auth: {
+		description: "Authentication configuration."
+		required:    false
+		type: object: options: {
+			name: {
+				description: """
+					Basic authentication name/username.
+
+					This can be used either for basic authentication (username/password) or JWT authentication.
+					When used for JWT, the value should be `token`.
+					"""
+				required: false
+				type: string: examples: ["${PULSAR_NAME}", "name123"]
+			}
+			oauth2: {
+				description: "OAuth2-specific authentication configuration."
+				required:    false
+				type: object: options: {
+					audience: {
+						description: "The OAuth2 audience."
+						required:    false
+						type: string: examples: ["${OAUTH2_AUDIENCE}", "pulsar"]
+					}
+					credentials_url: {
+						description: """
+																The credentials URL.
+
+																A data URL is also supported.
+																"""
+						required: true
+						type: string: examples: ["{OAUTH2_CREDENTIALS_URL}", "file:///oauth2_credentials", "data:application/json;base64,cHVsc2FyCg=="]
+					}
+					issuer_url: {
+						description: "The issuer URL."
+						required:    true
+						type: string: examples: ["${OAUTH2_ISSUER_URL}", "https://oauth2.issuer"]
+					}
+					scope: {
+						description: "The OAuth2 scope."
+						required:    false
+						type: string: examples: ["${OAUTH2_SCOPE}", "admin"]
+					}
+				}
+			}
+			token: {
+				description: """
+					Basic authentication password/token.
+
+					This can be used either for basic authentication (username/password) or JWT authentication.
+					When used for JWT, the value should be the signed JWT, in the compact representation.
+					"""
+				required: false
+				type: string: examples: ["${PULSAR_TOKEN}", "123456789"]
+			}
+		}
+	}
+	batch_size: {
+		description: "The maximum number of messages the broker sends to the consumer at once."
+		required:    false
+		type: uint: {
+			examples: [1000]
+			unit: "messages"
+		}
+	}
+	consumer_name: {
+		description: "The name of the consumer. If not specified, the default name assigned by Pulsar is used."
+		required:    false
+		type: string: examples: ["consumer-name"]
+	}
+	dead_letter_queue_policy: {
+		description: """
+			Dead letter queue policy.
+
+			Messages that are negatively acknowledged more than `max_redeliver_count` times are published
+			to the dead letter topic, instead of being redelivered.
+			"""
+		required: false
+		type: object: options: {
+			dead_letter_topic: {
+				description: "The topic dead letters are published to."
+				required:    true
+				type: string: examples: ["persistent://public/default/logs-dlq"]
+			}
+			max_redeliver_count: {
+				description: "The maximum number of times a message is redelivered."
+				required:    true
+				type: uint: {}
+			}
+		}
+	}
+	decoding: {
+		description: "Configures how events are decoded from raw bytes."
+		required:    false
+		type: object: options: {
+			avro: {
+				description:   "Apache Avro-specific encoder options."
+				relevant_when: "codec = \"avro\""
+				required:      true
+				type: object: options: {
+					schema: {
+						description: """
+																The Avro schema definition.
+																Please note that the following [`apache_avro::types::Value`] variants are currently *not* supported:
+																* `Date`
+																* `Decimal`
+																* `Duration`
+																* `Fixed`
+																* `TimeMillis`
+																"""
+						required: true
+						type: string: examples: ["{ \"type\": \"record\", \"name\": \"log\", \"fields\": [{ \"name\": \"message\", \"type\": \"string\" }] }"]
+					}
+					strip_schema_id_prefix: {
+						description: """
+																For Avro datum encoded in Kafka messages, the bytes are prefixed with the schema ID.  Set this to true to strip the schema ID prefix.
+																According to [Confluent Kafka's document](https://docs.confluent.io/platform/current/schema-registry/fundamentals/serdes-develop/index.html#wire-format).
+																"""
+						required: true
+						type: bool: {}
+					}
+				}
+			}
+			codec: {
+				description: "The codec to use for decoding events."
+				required:    false
+				type: string: {
+					default: "bytes"
+					enum: {
+						avro: """
+															Decodes the raw bytes as as an [Apache Avro][apache_avro] message.
+
+															[apache_avro]: https://avro.apache.org/
+															"""
+						bytes: "Uses the raw bytes as-is."
+						gelf: """
+															Decodes the raw bytes as a [GELF][gelf] message.
+
+															This codec is experimental for the following reason:
+
+															The GELF specification is more strict than the actual Graylog receiver.
+															Vector's decoder currently adheres more strictly to the GELF spec, with
+															the exception that some characters such as `@`  are allowed in field names.
+
+															Other GELF codecs such as Loki's, use a [Go SDK][implementation] that is maintained
+															by Graylog, and is much more relaxed than the GELF spec.
+
+															Going forward, Vector will use that [Go SDK][implementation] as the reference implementation, which means
+															the codec may continue to relax the enforcement of specification.
+
+															[gelf]: https://docs.graylog.org/docs/gelf
+															[implementation]: https://github.com/Graylog2/go-gelf/blob/v2/gelf/reader.go
+															"""
+						json: """
+															Decodes the raw bytes as [JSON][json].
+
+															[json]: https://www.json.org/
+															"""
+						native: """
+															Decodes the raw bytes as [native Protocol Buffers format][vector_native_protobuf].
+
+															This codec is **[experimental][experimental]**.
+
+															[vector_native_protobuf]: https://github.com/vectordotdev/vector/blob/master/lib/vector-core/proto/event.proto
+															[experimental]: https://vector.dev/highlights/2022-03-31-native-event-codecs
+															"""
+						native_json: """
+															Decodes the raw bytes as [native JSON format][vector_native_json].
+
+															This codec is **[experimental][experimental]**.
+
+															[vector_native_json]: https://github.com/vectordotdev/vector/blob/master/lib/codecs/tests/data/native_encoding/schema.cue
+															[experimental]: https://vector.dev/highlights/2022-03-31-native-event-codecs
+															"""
+						protobuf: """
+															Decodes the raw bytes as [protobuf][protobuf].
+
+															[protobuf]: https://protobuf.dev/
+															"""
+						syslog: """
+															Decodes the raw bytes as a Syslog message.
+
+															Decodes either as the [RFC 3164][rfc3164]-style format ("old" style) or the
+															[RFC 5424][rfc5424]-style format ("new" style, includes structured data).
+
+															[rfc3164]: https://www.ietf.org/rfc/rfc3164.txt
+															[rfc5424]: https://www.ietf.org/rfc/rfc5424.txt
+															"""
+					}
+				}
+			}
+			gelf: {
+				description:   "GELF-specific decoding options."
+				relevant_when: "codec = \"gelf\""
+				required:      false
+				type: object: options: lossy: {
+					description: """
+						Determines whether or not to replace invalid UTF-8 sequences instead of failing.
+
+						When true, invalid UTF-8 sequences are replaced with the [`U+FFFD REPLACEMENT CHARACTER`][U+FFFD].
+
+						[U+FFFD]: https://en.wikipedia.org/wiki/Specials_(Unicode_block)#Replacement_character
+						"""
+					required: false
+					type: bool: default: true
+				}
+			}
+			json: {
+				description:   "JSON-specific decoding options."
+				relevant_when: "codec = \"json\""
+				required:      false
+				type: object: options: lossy: {
+					description: """
+						Determines whether or not to replace invalid UTF-8 sequences instead of failing.
+
+						When true, invalid UTF-8 sequences are replaced with the [`U+FFFD REPLACEMENT CHARACTER`][U+FFFD].
+
+						[U+FFFD]: https://en.wikipedia.org/wiki/Specials_(Unicode_block)#Replacement_character
+						"""
+					required: false
+					type: bool: default: true
+				}
+			}
+			native_json: {
+				description:   "Vector's native JSON-specific decoding options."
+				relevant_when: "codec = \"native_json\""
+				required:      false
+				type: object: options: lossy: {
+					description: """
+						Determines whether or not to replace invalid UTF-8 sequences instead of failing.
+
+						When true, invalid UTF-8 sequences are replaced with the [`U+FFFD REPLACEMENT CHARACTER`][U+FFFD].
+
+						[U+FFFD]: https://en.wikipedia.org/wiki/Specials_(Unicode_block)#Replacement_character
+						"""
+					required: false
+					type: bool: default: true
+				}
+			}
+			protobuf: {
+				description:   "Protobuf-specific decoding options."
+				relevant_when: "codec = \"protobuf\""
+				required:      false
+				type: object: options: {
+					desc_file: {
+						description: "Path to desc file"
+						required:    false
+						type: string: default: ""
+					}
+					message_type: {
+						description: "message type. e.g package.message"
+						required:    false
+						type: string: default: ""
+					}
+				}
+			}
+			syslog: {
+				description:   "Syslog-specific decoding options."
+				relevant_when: "codec = \"syslog\""
+				required:      false
+				type: object: options: lossy: {
+					description: """
+						Determines whether or not to replace invalid UTF-8 sequences instead of failing.
+
+						When true, invalid UTF-8 sequences are replaced with the [`U+FFFD REPLACEMENT CHARACTER`][U+FFFD].
+
+						[U+FFFD]: https://en.wikipedia.org/wiki/Specials_(Unicode_block)#Replacement_character
+						"""
+					required: false
+					type: bool: default: true
+				}
+			}
+		}
+	}
+	endpoint: {
+		description: """
+			The endpoint to which the Pulsar client should connect to.
+
+			The endpoint should specify the pulsar protocol and port.
+			"""
+		required: true
+		type: string: examples: ["pulsar://127.0.0.1:6650"]
+	}
+	framing: {
+		description: """
+			Framing configuration.
+
+			Framing handles how events are separated when encoded in a raw byte form, where each event is
+			a frame that must be prefixed, or delimited, in a way that marks where an event begins and
+			ends within the byte stream.
+			"""
+		required: false
+		type: object: options: {
+			character_delimited: {
+				description:   "Options for the character delimited decoder."
+				relevant_when: "method = \"character_delimited\""
+				required:      true
+				type: object: options: {
+					delimiter: {
+						description: "The character that delimits byte sequences."
+						required:    true
+						type: uint: {}
+					}
+					max_length: {
+						description: """
+																The maximum length of the byte buffer.
+
+																This length does *not* include the trailing delimiter.
+
+																By default, there is no maximum length enforced. If events are malformed, this can lead to
+																additional resource usage as events continue to be buffered in memory, and can potentially
+																lead to memory exhaustion in extreme cases.
+
+																If there is a risk of processing malformed data, such as logs with user-controlled input,
+																consider setting the maximum length to a reasonably large value as a safety net. This
+																ensures that processing is not actually unbounded.
+																"""
+						required: false
+						type: uint: {}
+					}
+				}
+			}
+			method: {
+				description: "The framing method."
+				required:    false
+				type: string: {
+					default: "bytes"
+					enum: {
+						bytes:               "Byte frames are passed through as-is according to the underlying I/O boundaries (for example, split between messages or stream segments)."
+						character_delimited: "Byte frames which are delimited by a chosen character."
+						length_delimited:    "Byte frames which are prefixed by an unsigned big-endian 32-bit integer indicating the length."
+						newline_delimited:   "Byte frames which are delimited by a newline character."
+						octet_counting: """
+															Byte frames according to the [octet counting][octet_counting] format.
+
+															[octet_counting]: https://tools.ietf.org/html/rfc6587#section-3.4.1
+															"""
+					}
+				}
+			}
+			newline_delimited: {
+				description:   "Options for the newline delimited decoder."
+				relevant_when: "method = \"newline_delimited\""
+				required:      false
+				type: object: options: max_length: {
+					description: """
+						The maximum length of the byte buffer.
+
+						This length does *not* include the trailing delimiter.
+
+						By default, there is no maximum length enforced. If events are malformed, this can lead to
+						additional resource usage as events continue to be buffered in memory, and can potentially
+						lead to memory exhaustion in extreme cases.
+
+						If there is a risk of processing malformed data, such as logs with user-controlled input,
+						consider setting the maximum length to a reasonably large value as a safety net. This
+						ensures that processing is not actually unbounded.
+						"""
+					required: false
+					type: uint: {}
+				}
+			}
+			octet_counting: {
+				description:   "Options for the octet counting decoder."
+				relevant_when: "method = \"octet_counting\""
+				required:      false
+				type: object: options: max_length: {
+					description: "The maximum length of the byte buffer."
+					required:    false
+					type: uint: {}
+				}
+			}
+		}
+	}
+	initial_position: {
+		description: "The position from which a new subscription starts reading messages."
+		required:    false
+		type: string: {
+			default: "latest"
+			enum: {
+				earliest: "Read all the messages retained in the topics."
+				latest:   "Read the messages published after the subscription is created."
+			}
+		}
+	}
+	key_field: {
+		description: """
+			Overrides the name of the log field used to add the message key to each event.
+
+			The value is the partition key of the Pulsar message itself.
+
+			By default, `"message_key"` is used.
+			"""
+		required: false
+		type: string: {
+			default: "message_key"
+			examples: ["message_key"]
+		}
+	}
+	properties_key: {
+		description: """
+			Overrides the name of the log field used to add the properties to each event.
+
+			The value is the properties of the Pulsar message itself.
+
+			By default, `"properties"` is used.
+			"""
+		required: false
+		type: string: {
+			default: "properties"
+			examples: ["properties"]
+		}
+	}
+	subscription_name: {
+		description: """
+			The name of the Pulsar subscription.
+
+			Consumers sharing a subscription name share the messages of the topics according to the
+			`subscription_type`.
+			"""
+		required: false
+		type: string: {
+			default: "vector"
+			examples: ["vector"]
+		}
+	}
+	subscription_type: {
+		description: "The type of the Pulsar subscription."
+		required:    false
+		type: string: {
+			default: "shared"
+			enum: {
+				exclusive: "Only a single consumer can be attached to the subscription."
+				failover: """
+					A single consumer attached to the subscription receives the messages, and another one takes
+					over when it disconnects.
+					"""
+				key_shared: """
+					The messages are distributed across the consumers attached to the subscription, with
+					messages of the same key always delivered to the same consumer.
+					"""
+				shared: "The messages are distributed across the consumers attached to the subscription."
+			}
+		}
+	}
+	topic_key: {
+		description: """
+			Overrides the name of the log field used to add the topic to each event.
+
+			The value is the topic from which the Pulsar message was consumed from.
+
+			By default, `"topic"` is used.
+			"""
+		required: false
+		type: string: {
+			default: "topic"
+			examples: ["topic"]
+		}
+	}
+	topics: {
+		description: "The Pulsar topic names to read events from."
+		required:    false
+		type: array: {
+			default: []
+			items: type: string: examples: ["persistent://public/default/logs"]
+		}
+	}
+	topics_pattern: {
+		description: """
+			Subscription to the topics of a namespace whose names match a pattern.
+
+			The topics are looked up periodically, so that new topics are subscribed to as they are
+			created.
+			"""
+		required: false
+		type: object: options: {
+			namespace: {
+				description: "The namespace in which topics are looked up."
+				required:    false
+				type: string: {
+					default: "public/default"
+					examples: ["public/default"]
+				}
+			}
+			pattern: {
+				description: """
+					The regular expression that the names of the topics must match.
+
+					Topic names are fully qualified, such as `persistent://public/default/logs`.
+					"""
+				required: true
+				type: string: examples: ["persistent://public/default/logs-.*"]
+			}
+			refresh_interval_secs: {
+				description: "The interval at which topics matching the pattern are looked up."
+				required:    false
+				type: uint: {
+					default: 60
+					unit:    "seconds"
+				}
+			}
+		}
+	}
+}
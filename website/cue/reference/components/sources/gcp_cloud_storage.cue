@@ -0,0 +1,138 @@
+package metadata
+
+components: sources: gcp_cloud_storage: {
+	title: "GCP Cloud Storage"
+
+	features: {
+		auto_generated:   true
+		acknowledgements: true
+		multiline: enabled: true
+		collect: {
+			tls: {
+				enabled:                true
+				can_verify_certificate: true
+				can_verify_hostname:    true
+				enabled_default:        true
+				enabled_by_scheme:      true
+			}
+			checkpoint: enabled: true
+			proxy: enabled:      true
+			from: service:       services.gcp_cloud_storage
+		}
+		codecs: {
+			enabled:         true
+			default_framing: "`newline_delimited`"
+		}
+	}
+
+	classes: {
+		commonly_used: false
+		deployment_roles: ["aggregator"]
+		delivery:      "at_least_once"
+		development:   "beta"
+		egress_method: "batch"
+		stateful:      true
+	}
+
+	support: {
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	installation: {
+		platform_name: null
+	}
+
+	configuration: base.components.sources.gcp_cloud_storage.configuration
+
+	output: logs: object: {
+		description: "A line from an object."
+		fields: {
+			message: {
+				description: "A line from the object."
+				required:    true
+				type: string: {
+					examples: ["53.126.150.246 - - [01/Oct/2020:11:25:58 -0400] \"GET /disintermediate HTTP/2.0\" 401 20308"]
+				}
+			}
+			timestamp: fields._current_timestamp & {
+				description: "The last modification time of the object."
+			}
+			source_type: {
+				description: "The name of the source type."
+				required:    true
+				type: string: {
+					examples: ["gcp_cloud_storage"]
+				}
+			}
+			bucket: {
+				description: "The bucket of the object the line came from."
+				required:    true
+				type: string: {
+					examples: ["my-bucket"]
+				}
+			}
+			object: {
+				description: "The name of the object the line came from."
+				required:    true
+				type: string: {
+					examples: ["logs/2024/01/01/app.log.gz"]
+				}
+			}
+		}
+	}
+
+	how_it_works: {
+		listing: {
+			title: "Listing objects"
+			body:  """
+				Every `poll_interval_secs`, the source lists the objects of the bucket whose name
+				starts with `key_prefix`, and ingests the ones that were created or modified since the
+				last ingested object, in the order of their modification time. Each object is read
+				in full before the next one, and results in one event per line (unless the
+				`multiline` or `framing` options are used).
+
+				Objects are decompressed according to the `compression` option. By default, the
+				compression scheme is determined from the `Content-Encoding` and `Content-Type` of the
+				object, as well as its name suffix (for example, `.gz`).
+				"""
+		}
+		checkpointing: {
+			title: "Checkpointing"
+			body:  """
+				The modification time of the last ingested object is persisted in the `data_dir`
+				directory, so that the source resumes where it left off after a restart. If
+				end-to-end acknowledgements are enabled, an object is only checkpointed once all of
+				its events are delivered, and objects whose delivery failed are ingested again on
+				the next listing. Objects that can't be read are retried on the following
+				listings, and skipped after three failed attempts in a row.
+
+				Only objects modified after the last ingested object are ingested, so a object that
+				appears after newer objects have been ingested, with an earlier modification time, is
+				never ingested.
+
+				Objects that are overwritten are ingested again. Since all the matching objects are
+				listed on each poll, scoping `key_prefix` to the objects to ingest keeps listings cheap.
+				"""
+		}
+	}
+
+	permissions: iam: [
+		{
+			platform: "gcp"
+			_service: "storage"
+
+			policies: [
+				{
+					_action: "objects.get"
+					required_for: ["operation"]
+				},
+				{
+					_action: "objects.list"
+					required_for: ["operation"]
+				},
+			]
+		},
+	]
+}
@@ -302,6 +302,12 @@ components: sources: internal_metrics: {
 			default_namespace: "vector"
 			tags:              _component_tags
 		}
+		buffer_compressed_bytes_total: {
+			description:       "The number of bytes written by this disk buffer after compression, when compression is enabled."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
 		buffer_events: {
 			description:       "The number of events currently in the buffer."
 			type:              "gauge"
@@ -344,6 +350,12 @@ components: sources: internal_metrics: {
 			default_namespace: "vector"
 			tags:              _component_tags
 		}
+		buffer_uncompressed_bytes_total: {
+			description:       "The number of bytes written by this disk buffer before compression, when compression is enabled."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
 		component_discarded_events_total: {
 			description:       "The number of events dropped by this component."
 			type:              "counter"
@@ -816,12 +828,58 @@ components: sources: internal_metrics: {
 			default_namespace: "vector"
 			tags:              _component_tags
 		}
+		reduce_active_groups: {
+			description:       "The number of groups currently held in memory by the `reduce` transform."
+			type:              "gauge"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
+		reduce_evictions_total: {
+			description:       "The number of groups the `reduce` transform flushed early to stay within its limits."
+			type:              "counter"
+			default_namespace: "vector"
+			tags: _component_tags & {
+				reason: {
+					description: "The limit that was exceeded."
+					required:    true
+					enum: {
+						max_bytes:  "The `max_bytes` limit was exceeded."
+						max_groups: "The `max_groups` limit was exceeded."
+					}
+				}
+			}
+		}
 		stdin_reads_failed_total: {
 			description:       "The total number of errors reading from stdin."
 			type:              "counter"
 			default_namespace: "vector"
 			tags:              _component_tags
 		}
+		tail_sample_policy_decisions_total: {
+			description:       "The number of traces each policy of the `tail_sample` transform has decided on."
+			type:              "counter"
+			default_namespace: "vector"
+			tags: _component_tags & {
+				decision: {
+					description: "Whether the trace matched the policy."
+					required:    true
+					enum: {
+						not_sampled: "The trace didn't match the policy."
+						sampled:     "The trace matched the policy."
+					}
+				}
+				policy: {
+					description: "The name of the policy."
+					required:    true
+				}
+			}
+		}
+		tail_sample_traces_evicted_total: {
+			description:       "The number of traces decided on early by the `tail_sample` transform because its buffer was full."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
 		tag_value_limit_exceeded_total: {
 			description: """
 				The total number of events discarded because the tag has been rejected after
@@ -829,7 +887,10 @@ components: sources: internal_metrics: {
 				"""
 			type:              "counter"
 			default_namespace: "vector"
-			tags:              _component_tags
+			tags:              _component_tags & {
+				metric_name: _metric_name
+				tag_key:     _tag_key
+			}
 		}
 		timestamp_parse_errors_total: {
 			description:       "The total number of errors encountered parsing [RFC 3339](\(urls.rfc_3339)) timestamps."
@@ -897,6 +958,15 @@ components: sources: internal_metrics: {
 				"""
 			type:              "counter"
 			default_namespace: "vector"
+			tags:              _component_tags & {
+				metric_name: _metric_name
+				tag_key:     _tag_key
+			}
+		}
+		wasm_module_reloads_total: {
+			description:       "The number of times the `wasm` transform has reloaded its module after the file changed."
+			type:              "counter"
+			default_namespace: "vector"
 			tags:              _component_tags
 		}
 
@@ -1028,6 +1098,10 @@ components: sources: internal_metrics: {
 			required:    true
 			examples: [_values.local_host]
 		}
+		_metric_name: {
+			description: "The name of the metric event. Only present when the component's `internal_metrics.include_extended_tags` option is enabled."
+			required:    false
+		}
 		_mode: {
 			description: "The connection mode used by the component."
 			required:    false
@@ -1062,6 +1136,10 @@ components: sources: internal_metrics: {
 			description: "The path that produced the error."
 			required:    true
 		}
+		_tag_key: {
+			description: "The key of the metric tag. Only present when the component's `internal_metrics.include_extended_tags` option is enabled."
+			required:    false
+		}
 		_reason: {
 			description: "The type of the error"
 			required:    true
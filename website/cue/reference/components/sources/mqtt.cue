@@ -0,0 +1,104 @@
+package metadata
+
+components: sources: mqtt: {
+	title: "MQTT"
+
+	features: {
+		auto_generated:   true
+		acknowledgements: true
+		collect: {
+			checkpoint: enabled: false
+			from: components._mqtt.features.collect.from
+			tls: {
+				enabled:                true
+				can_verify_certificate: true
+				can_verify_hostname:    true
+				enabled_default:        false
+				enabled_by_scheme:      false
+			}
+		}
+		multiline: enabled: false
+		codecs: {
+			enabled:         true
+			default_framing: "bytes"
+		}
+	}
+
+	classes: {
+		commonly_used: false
+		deployment_roles: ["aggregator"]
+		delivery:      "at_least_once"
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      false
+	}
+
+	support: components._mqtt.support
+
+	installation: {
+		platform_name: null
+	}
+
+	configuration: base.components.sources.mqtt.configuration
+
+	output: logs: record: {
+		description: "An individual MQTT message."
+		fields: {
+			message: {
+				description: "The raw payload of the MQTT message."
+				required:    true
+				type: string: {
+					examples: ["53.126.150.246 - - [01/Oct/2020:11:25:58 -0400] \"GET /disintermediate HTTP/2.0\" 401 20308"]
+				}
+			}
+			source_type: {
+				description: "The name of the source type."
+				required:    true
+				type: string: {
+					examples: ["mqtt"]
+				}
+			}
+			timestamp: fields._current_timestamp
+			topic: {
+				description: "The topic the MQTT message was published to."
+				required:    true
+				type: string: {
+					examples: ["devices/edge-1/logs"]
+				}
+			}
+		}
+	}
+
+	how_it_works: components._mqtt.how_it_works & {
+		topic_filters: {
+			title: "Topic filters"
+			body:  """
+				The source subscribes to each of the `topics` [topic filters](\(urls.mqtt_topics)) with
+				the configured `qos`, which is the maximum quality of service level the broker delivers
+				events with. Events published with a lower level are delivered with that level.
+
+				The subscriptions are made again each time the source connects to the broker.
+				"""
+		}
+		persistent_sessions: {
+			title: "Persistent sessions"
+			body:  """
+				When `clean_session` is disabled, the broker keeps the subscriptions of the source, and
+				queues the events published with a `qos` of `at_least_once` or `exactly_once` while the
+				source is disconnected, until it reconnects with the same `client_id`. The default
+				client ID is derived from the component ID of the source, so that it's stable across
+				restarts.
+				"""
+		}
+		acknowledgements: {
+			title: "Acknowledgements"
+			body:  """
+				Messages are only acknowledged to the broker once their events have been sent to the
+				pipeline, or once they have been delivered or rejected by the sinks when
+				acknowledgements are enabled. As MQTT has no way to reject a message, the messages
+				whose events errored aren't acknowledged, and are delivered again by the broker after
+				the source reconnects if its session is persistent.
+				"""
+		}
+	}
+}
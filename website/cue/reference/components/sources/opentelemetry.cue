@@ -39,11 +39,7 @@ components: sources: opentelemetry: {
 
 	support: {
 		requirements: []
-		warnings: [
-			"""
-				The `opentelemetry` source only supports log events at this time.
-				""",
-		]
+		warnings: []
 		notices: []
 	}
 
@@ -60,6 +56,18 @@ components: sources: opentelemetry: {
 				Received log events will go to this output stream. Use `<component_id>.logs` as an input to downstream transforms and sinks.
 				"""
 		},
+		{
+			name: "metrics"
+			description: """
+				Received metric events will go to this output stream. Use `<component_id>.metrics` as an input to downstream transforms and sinks.
+				"""
+		},
+		{
+			name: "traces"
+			description: """
+				Received trace events will go to this output stream. Use `<component_id>.traces` as an input to downstream transforms and sinks.
+				"""
+		},
 	]
 
 	output: {
@@ -195,6 +203,23 @@ components: sources: opentelemetry: {
 	}
 
 	how_it_works: {
+		metrics: {
+			title: "Metric conversion"
+			body:  """
+				OTLP metric data points are converted into Vector metrics as follows:
+
+				- Monotonic sums become counters, and non-monotonic sums become gauges.
+				- Gauges become gauges.
+				- Histograms become aggregated histograms using the explicit bucket bounds.
+				- Exponential histograms become sketches.
+				- Summaries become aggregated summaries.
+
+				Sums and histograms with delta aggregation temporality are emitted as incremental metrics,
+				while all others are emitted as absolute metrics. Data point attributes become metric tags,
+				resource attributes become tags prefixed with `resource.`, and the instrumentation scope
+				name and version are added as the `scope.name` and `scope.version` tags.
+				"""
+		}
 		tls: {
 			title: "Transport Layer Security (TLS)"
 			body:  """
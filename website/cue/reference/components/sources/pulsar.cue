@@ -0,0 +1,115 @@
+package metadata
+
+components: sources: pulsar: {
+	title: "Apache Pulsar"
+
+	features: {
+		auto_generated:   true
+		acknowledgements: true
+		collect: {
+			checkpoint: enabled: false
+			tls: enabled:        false
+			from: {
+				service: services.pulsar
+				interface: {
+					socket: {
+						api: {
+							title: "Pulsar protocol"
+							url:   urls.pulsar_protocol
+						}
+						direction: "incoming"
+						port:      6650
+						protocols: ["tcp"]
+						ssl: "disabled"
+					}
+				}
+			}
+		}
+		multiline: enabled: false
+		codecs: {
+			enabled:         true
+			default_framing: "bytes"
+		}
+	}
+
+	classes: {
+		commonly_used: false
+		deployment_roles: ["aggregator"]
+		delivery:      "at_least_once"
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      false
+	}
+
+	support: {
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	installation: {
+		platform_name: null
+	}
+
+	configuration: base.components.sources.pulsar.configuration
+
+	output: logs: record: {
+		description: "An individual Pulsar message."
+		fields: {
+			message: {
+				description: "The raw line from the Pulsar message."
+				required:    true
+				type: string: {
+					examples: ["53.126.150.246 - - [01/Oct/2020:11:25:58 -0400] \"GET /disintermediate HTTP/2.0\" 401 20308"]
+				}
+			}
+			message_key: {
+				description: "The partition key of the Pulsar message, if any."
+				required:    false
+				type: string: {
+					examples: ["device-1"]
+				}
+			}
+			properties: {
+				description: "The properties of the Pulsar message."
+				required:    true
+				type: object: {
+					examples: [{"origin": "edge"}]
+					options: {}
+				}
+			}
+			source_type: {
+				description: "The name of the source type."
+				required:    true
+				type: string: {
+					examples: ["pulsar"]
+				}
+			}
+			timestamp: fields._current_timestamp & {
+				description: "The publish time of the Pulsar message."
+			}
+			topic: {
+				description: "The Pulsar topic that the message came from."
+				required:    true
+				type: string: {
+					examples: ["persistent://public/default/logs"]
+				}
+			}
+		}
+	}
+
+	how_it_works: {
+		acknowledgements: {
+			title: "Acknowledgements"
+			body: """
+				When end-to-end acknowledgements are enabled, each message is acknowledged once all the
+				events decoded from it are delivered. Messages whose events fail to be delivered are
+				negatively acknowledged, so that the broker redelivers them, or publishes them to the
+				dead letter topic once `dead_letter_queue_policy.max_redeliver_count` is exceeded.
+
+				When they are disabled, messages are acknowledged as soon as their events are sent
+				downstream.
+				"""
+		}
+	}
+}
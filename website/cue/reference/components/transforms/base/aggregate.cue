@@ -1,11 +1,78 @@
 package metadata
 
-base: components: transforms: aggregate: configuration: interval_ms: {
-	description: """
-		The interval between flushes, in milliseconds.
-
-		During this time frame, metrics (beta) with the same series data (name, namespace, tags, and so on) are aggregated.
-		"""
-	required: false
-	type: uint: default: 10000
+base: components: transforms: aggregate: configuration: {
+	interval_ms: {
+		description: """
+			The interval between flushes, in milliseconds.
+
+			During this time frame, metrics (beta) with the same series data (name, namespace, tags, and so on) are aggregated.
+			"""
+		required: false
+		type: uint: default: 10000
+	}
+	mode: {
+		description: """
+			How metrics with the same series data are combined during a flush interval.
+
+			Not every mode applies to every metric. Metrics that the configured mode does not apply to
+			are dropped, and an error is reported.
+			"""
+		required: false
+		type: string: {
+			default: "auto"
+			enum: {
+				auto: """
+					Sums incremental metrics and keeps the latest value of absolute metrics.
+
+					Applies to all metrics.
+					"""
+				count: """
+					Counts the metrics received, emitting an incremental counter.
+
+					Applies to all metrics.
+					"""
+				diff: """
+					Emits the change between the latest value of an absolute metric and its value at the
+					previous flush, as an incremental metric.
+
+					The first time a series is seen, or when it wasn't seen in the previous interval, the
+					change is computed from its first value. If the change can't be computed, such as when a
+					counter is reset, the latest value is emitted instead.
+
+					Applies to absolute counters, gauges, sets, and aggregated histograms.
+					"""
+				latest: """
+					Keeps the latest value of absolute metrics.
+
+					Applies to absolute metrics of any type.
+					"""
+				max: """
+					Keeps the maximum value of absolute metrics.
+
+					Applies to absolute counters and gauges.
+					"""
+				mean: """
+					Computes the mean of the values of absolute metrics, emitting a gauge.
+
+					Applies to absolute counters and gauges.
+					"""
+				min: """
+					Keeps the minimum value of absolute metrics.
+
+					Applies to absolute counters and gauges.
+					"""
+				stdev: """
+					Computes the population standard deviation of the values of absolute metrics, emitting a
+					gauge.
+
+					Applies to absolute counters and gauges.
+					"""
+				sum: """
+					Sums incremental metrics.
+
+					Applies to incremental metrics of any type other than aggregated summaries.
+					"""
+			}
+		}
+	}
 }
@@ -10,6 +10,20 @@ base: components: transforms: dedupe: configuration: {
 			type: uint: default: 5000
 		}
 	}
+	data_dir: {
+		description: """
+			The directory used to persist the cache when `flush_to_disk` is enabled.
+
+			By default, the [global `data_dir` option][global_data_dir] is used.
+			Make sure the running user has write permissions to this directory.
+
+			If this directory is specified, then Vector will attempt to create it.
+
+			[global_data_dir]: https://vector.dev/docs/reference/configuration/global-options/#data_dir
+			"""
+		required: false
+		type: string: examples: ["/var/local/lib/vector/"]
+	}
 	fields: {
 		description: """
 			Options to control what fields to match against.
@@ -34,4 +48,45 @@ base: components: transforms: dedupe: configuration: {
 			}
 		}
 	}
+	flush_to_disk: {
+		description: """
+			Whether or not to persist the cache to disk.
+
+			When enabled, the cache is written to disk when the transform shuts down, and loaded back
+			when it starts, so that events seen before a restart or reload are still deduplicated
+			afterwards.
+			"""
+		required: false
+		type: bool: default: false
+	}
+	reroute_duplicates: {
+		description: """
+			Reroutes duplicate events to a named output instead of dropping them.
+
+			When enabled, duplicate events are sent to the `<transform_name>.duplicates` output.
+			"""
+		required: false
+		type: bool: default: false
+	}
+	time_settings: {
+		description: "Time-based expiration configuration for deduplication."
+		required:    false
+		type: object: options: {
+			max_age: {
+				description: """
+					The maximum amount of time an event is kept in the cache, in seconds.
+
+					Once an event is older than this, matching events are no longer considered duplicates of
+					it, and the next matching event takes its place in the cache.
+					"""
+				required: true
+				type: float: unit: "seconds"
+			}
+			refresh_on_drop: {
+				description: "Whether or not a duplicate event resets the age of the cached event it matched."
+				required:    false
+				type: bool: default: false
+			}
+		}
+	}
 }
@@ -0,0 +1,32 @@
+package metadata
+
+base: components: transforms: exclusive_route: configuration: routes: {
+	description: """
+		An ordered list of routes.
+
+		Each event is checked against the routes in order, and is only sent to the first route whose
+		condition it matches. Events that don't match any route are sent to the
+		`<transform_name>._unmatched` output.
+
+		Route names must be unique, and both `_unmatched`, as well as `_default`, are reserved
+		output names and thus cannot be used as a route name.
+		"""
+	required: true
+	type: array: items: type: object: options: {
+		condition: {
+			description: "The condition an event must match to be sent to the route."
+			required:    true
+			type: condition: {}
+		}
+		name: {
+			description: """
+				The name of the route.
+
+				The route can be referenced as an input by other components with the name
+				`<transform_name>.<name>`.
+				"""
+			required: true
+			type: string: examples: ["errors"]
+		}
+	}
+}
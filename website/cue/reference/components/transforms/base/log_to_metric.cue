@@ -42,6 +42,19 @@ base: components: transforms: log_to_metric: configuration: {
 				required:    true
 				type: string: syntax: "template"
 			}
+			buckets: {
+				description: """
+					The upper bounds of the buckets to count the value of `field` into.
+
+					When set, an aggregated histogram with these buckets is created. Otherwise, the value is
+					passed on as a distribution, leaving it to the sink to bucket it.
+
+					The bounds must be finite and sorted in ascending order.
+					"""
+				relevant_when: "type = \"histogram\""
+				required:      false
+				type: array: items: type: float: {}
+			}
 			increment_by_value: {
 				description:   "Increments the counter by the value in `field`, instead of only by `1`."
 				relevant_when: "type = \"counter\""
@@ -101,7 +114,19 @@ base: components: transforms: log_to_metric: configuration: {
 					gauge:     "A gauge."
 					histogram: "A histogram."
 					set:       "A set."
-					summary:   "A summary."
+					sketch: """
+						A sketch.
+
+						The value is inserted into a DDSketch, which can be merged with other sketches to compute
+						quantiles across events.
+						"""
+					summary: """
+						A summary.
+
+						The value is inserted into a distribution, whose quantiles are computed once it has been
+						merged across events, for example according to the `quantiles` option of the
+						`prometheus_exporter` and `prometheus_remote_write` sinks.
+						"""
 				}
 			}
 		}
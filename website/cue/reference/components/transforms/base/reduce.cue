@@ -1,6 +1,20 @@
 package metadata
 
 base: components: transforms: reduce: configuration: {
+	data_dir: {
+		description: """
+			The directory used to persist groups when `persist_state` is enabled.
+
+			By default, the [global `data_dir` option][global_data_dir] is used.
+			Make sure the running user has write permissions to this directory.
+
+			If this directory is specified, then Vector will attempt to create it.
+
+			[global_data_dir]: https://vector.dev/docs/reference/configuration/global-options/#data_dir
+			"""
+		required: false
+		type: string: examples: ["/var/local/lib/vector/"]
+	}
 	ends_when: {
 		description: """
 			A condition used to distinguish the final event of a transaction.
@@ -11,6 +25,17 @@ base: components: transforms: reduce: configuration: {
 		required: false
 		type: condition: {}
 	}
+	eviction_policy: {
+		description: "The policy used to choose which group to flush early when a limit is exceeded."
+		required:    false
+		type: string: {
+			default: "oldest"
+			enum: {
+				largest: "Flushes the group holding the most bytes."
+				oldest:  "Flushes the group that received its first event the earliest."
+			}
+		}
+	}
 	expire_after_ms: {
 		description: """
 			The maximum period of time to wait after the last event is received, in milliseconds, before
@@ -47,11 +72,31 @@ base: components: transforms: reduce: configuration: {
 			items: type: string: examples: ["request_id", "user_id", "transaction_id"]
 		}
 	}
+	max_bytes: {
+		description: """
+			The maximum number of bytes to hold in memory across all groups at once.
+
+			The size of a group is estimated from the in-memory size of the events added to it. When
+			this limit is exceeded, groups are flushed early, as chosen by `eviction_policy`, until
+			the total size is back under it.
+			"""
+		required: false
+		type: uint: unit: "bytes"
+	}
 	max_events: {
 		description: "The maximum number of events to group together."
 		required:    false
 		type: uint: {}
 	}
+	max_groups: {
+		description: """
+			The maximum number of groups to hold in memory at once.
+
+			When this limit is exceeded, groups are flushed early, as chosen by `eviction_policy`.
+			"""
+		required: false
+		type: uint: {}
+	}
 	merge_strategies: {
 		description: """
 			A map of field names to custom merge strategies.
@@ -90,6 +135,17 @@ base: components: transforms: reduce: configuration: {
 			}
 		}
 	}
+	persist_state: {
+		description: """
+			Whether or not to persist the groups in progress to disk when the transform shuts down.
+
+			When enabled, groups that are still in progress when Vector stops, or when the transform is
+			reloaded, are written to disk instead of being flushed. They are restored when the
+			transform starts again, so that the events received afterwards are still reduced with them.
+			"""
+		required: false
+		type: bool: default: false
+	}
 	starts_when: {
 		description: """
 			A condition used to distinguish the first event of a transaction.
@@ -12,6 +12,34 @@ base: components: transforms: tag_cardinality_limit: configuration: {
 		required:      false
 		type: uint: default: 5120
 	}
+	excluded_tags: {
+		description: """
+			Tag keys that are never limited.
+
+			Values of these tags are not tracked, and are always accepted.
+			"""
+		required: false
+		type: array: {
+			default: []
+			items: type: string: examples: ["host"]
+		}
+	}
+	internal_metrics: {
+		description: "Configuration of internal metrics for the `tag_cardinality_limit` transform."
+		required:    false
+		type: object: options: include_extended_tags: {
+			description: """
+				Whether or not to include the `metric_name` and `tag_key` tags on the
+				`tag_value_limit_exceeded_total` and `value_limit_reached_total` internal metrics.
+
+				This reports which metric and tag hit its limit. Note that this defaults to false because
+				these tags have potentially unbounded cardinality. Only set this to true if you know that
+				the number of unique metric names and tag keys is bounded.
+				"""
+			required: false
+			type: bool: default: false
+		}
+	}
 	limit_exceeded_action: {
 		description: """
 			Possible actions to take when an event arrives that would exceed the cardinality limit for one
@@ -46,6 +74,63 @@ base: components: transforms: tag_cardinality_limit: configuration: {
 				"""
 		}
 	}
+	per_metric_limits: {
+		description: """
+			Overrides of `value_limit` and `limit_exceeded_action` for specific metrics, keyed by
+			metric name.
+
+			The tags of a metric listed here are tracked separately from those of every other metric,
+			so its values neither count towards nor are limited by the values seen on other metrics.
+			"""
+		required: false
+		type: object: options: "*": {
+			description: "An override for a specific metric."
+			required:    true
+			type: object: options: {
+				limit_exceeded_action: {
+					description: """
+						The action to take when an event of this metric would exceed the cardinality limit.
+
+						If left unspecified, the global `limit_exceeded_action` is used.
+						"""
+					required: false
+					type: string: enum: {
+						drop_event: "Drop the entire event itself."
+						drop_tag:   "Drop the tag(s) that would exceed the configured limit."
+					}
+				}
+				value_limit: {
+					description: """
+						How many distinct values to accept for any given key of this metric.
+
+						If left unspecified, the global `value_limit` is used.
+						"""
+					required: false
+					type: uint: {}
+				}
+			}
+		}
+	}
+	per_tag_limits: {
+		description: """
+			Overrides of `value_limit` for specific tags, keyed by tag key.
+
+			These take precedence over `value_limit`, whether it is set globally or in
+			`per_metric_limits`. A limit of `0` rejects every value of the tag.
+			"""
+		required: false
+		type: object: {
+			examples: [{
+				endpoint: 1000
+				user_id:  10
+			}]
+			options: "*": {
+				description: "The value limit for the tag."
+				required:    true
+				type: uint: {}
+			}
+		}
+	}
 	value_limit: {
 		description: "How many distinct values to accept for any given key."
 		required:    false
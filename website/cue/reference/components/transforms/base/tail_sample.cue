@@ -0,0 +1,149 @@
+package metadata
+
+base: components: transforms: tail_sample: configuration: {
+	complete_when: {
+		description: """
+			A condition used to distinguish the final event of a trace.
+
+			If this condition resolves to `true` for an event, the trace is considered complete and a
+			decision is made right away, without waiting for `decision_wait_secs`.
+			"""
+		required: false
+		type: condition: {}
+	}
+	decision_cache_size: {
+		description: """
+			The maximum number of decisions to remember.
+
+			Events arriving after the decision for their trace has been made follow that decision, as
+			long as it is remembered. Otherwise, they start a new trace.
+			"""
+		required: false
+		type: uint: default: 10000
+	}
+	decision_wait_secs: {
+		description: """
+			The maximum period of time to wait after the last span of a trace is received, in seconds,
+			before the trace is considered complete.
+			"""
+		required: false
+		type: float: {
+			default: 30.0
+			unit:    "seconds"
+		}
+	}
+	max_buffered_bytes: {
+		description: """
+			The maximum number of bytes of events to buffer, across all traces.
+
+			Once the limit is reached, a decision is made early for the traces that have been idle the
+			longest, until the buffered events fit within the limit again.
+			"""
+		required: false
+		type: uint: {
+			default: 104857600
+			unit:    "bytes"
+		}
+	}
+	policies: {
+		description: """
+			The sampling policies.
+
+			A trace is sampled if it matches at least one of the policies. All policies are evaluated
+			for every trace, so that each of them reports its own decisions.
+			"""
+		required: true
+		type: array: items: type: object: options: {
+			key: {
+				description: """
+					The name of the attribute.
+
+					The attribute is looked up in the `meta` object of each span, and then at the root of
+					the span.
+					"""
+				relevant_when: "type = \"attribute\""
+				required:      true
+				type: string: examples: ["http.status_code"]
+			}
+			name: {
+				description: """
+					The name of the policy.
+
+					This is used as the `policy` tag of the `tail_sample_policy_decisions_total` internal
+					metric.
+					"""
+				required: true
+				type: string: examples: ["errors", "slow_requests"]
+			}
+			rate: {
+				description:   "The fraction of traces to sample, between `0.0` and `1.0`."
+				relevant_when: "type = \"probabilistic\""
+				required:      true
+				type: float: examples: [0.1]
+			}
+			threshold_ms: {
+				description: """
+					The minimum duration of a span, in milliseconds.
+
+					Span durations are expected in nanoseconds.
+					"""
+				relevant_when: "type = \"latency\""
+				required:      true
+				type: uint: examples: [500]
+			}
+			type: {
+				description: "The type of policy."
+				required:    true
+				type: string: enum: {
+					attribute: "Samples traces with at least one span with a matching attribute."
+					error:     "Samples traces with at least one span whose `error` field is set."
+					latency:   "Samples traces with at least one span whose `duration` reaches a threshold."
+					probabilistic: """
+						Samples a fraction of traces.
+
+						The decision is derived from a hash of the trace ID, so that every instance of Vector
+						makes the same decision for a given trace.
+						"""
+				}
+			}
+			values: {
+				description: """
+					The values the attribute is matched against.
+
+					If left empty, any span that has the attribute matches.
+					"""
+				relevant_when: "type = \"attribute\""
+				required:      false
+				type: array: {
+					default: []
+					items: type: string: examples: ["500", "503"]
+				}
+			}
+		}
+	}
+	spans_field: {
+		description: """
+			The field holding the spans of an event.
+
+			If the event doesn't have this field, the event itself is treated as a single span.
+			"""
+		required: false
+		type: string: {
+			default: "spans"
+			examples: ["spans"]
+		}
+	}
+	trace_id_field: {
+		description: """
+			The field holding the ID of the trace an event belongs to.
+
+			If the event doesn't have this field, the `trace_id` of its first span is used instead.
+			Events without a trace ID are forwarded without being sampled.
+			"""
+		required: false
+		type: string: {
+			default: "trace_id"
+			examples: ["trace_id"]
+		}
+	}
+}
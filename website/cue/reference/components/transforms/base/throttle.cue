@@ -38,14 +38,107 @@ base: components: transforms: throttle: configuration: {
 			syntax: "template"
 		}
 	}
+	reroute_dropped: {
+		description: """
+			Whether or not to send throttled events to the `dropped` output.
+
+			If false, throttled events are discarded.
+			"""
+		required: false
+		type: bool: default: false
+	}
 	threshold: {
 		description: """
 			The number of events allowed for a given bucket per configured `window_secs`.
 
-			Each unique key has its own `threshold`.
+			Each unique key has its own `threshold`, which is resolved when the bucket is first seen,
+			and kept until the bucket has been idle for twice `window_secs`.
+
+			At least one of `threshold` and `threshold_bytes` must be set.
 			"""
-		required: true
-		type: uint: {}
+		required: false
+		type: {
+			object: options: {
+				default: {
+					description: """
+						The threshold used for buckets that have no row in the table.
+
+						If left unspecified, such buckets are not rate limited.
+						"""
+					required: false
+					type: uint: {}
+				}
+				key_column: {
+					description: "The column matched against the bucket key, as rendered from `key_field`."
+					required:    true
+					type: string: examples: ["tenant"]
+				}
+				table: {
+					description: "The name of the enrichment table to look the threshold up in."
+					required:    true
+					type: string: examples: ["tenant_limits"]
+				}
+				value_column: {
+					description: """
+						The column holding the threshold.
+
+						The column must hold a positive integer. If it doesn't, an error is logged and `default`
+						is used instead.
+						"""
+					required: true
+					type: string: examples: ["events_per_window"]
+				}
+			}
+			string: syntax: "template"
+			uint: examples: [100]
+		}
+	}
+	threshold_bytes: {
+		description: """
+			The number of bytes allowed for a given bucket per configured `window_secs`.
+
+			The size of an event is its estimated JSON-encoded size. This is resolved in the same way
+			as `threshold`, and both limits apply when both are set, in which case a throttled event
+			counts towards neither.
+
+			An event larger than the threshold is always throttled.
+			"""
+		required: false
+		type: {
+			object: options: {
+				default: {
+					description: """
+						The threshold used for buckets that have no row in the table.
+
+						If left unspecified, such buckets are not rate limited.
+						"""
+					required: false
+					type: uint: {}
+				}
+				key_column: {
+					description: "The column matched against the bucket key, as rendered from `key_field`."
+					required:    true
+					type: string: examples: ["tenant"]
+				}
+				table: {
+					description: "The name of the enrichment table to look the threshold up in."
+					required:    true
+					type: string: examples: ["tenant_limits"]
+				}
+				value_column: {
+					description: """
+						The column holding the threshold.
+
+						The column must hold a positive integer. If it doesn't, an error is logged and `default`
+						is used instead.
+						"""
+					required: true
+					type: string: examples: ["events_per_window"]
+				}
+			}
+			string: syntax: "template"
+			uint: examples: [1000000]
+		}
 	}
 	window_secs: {
 		description: "The time window in which the configured `threshold` is applied, in seconds."
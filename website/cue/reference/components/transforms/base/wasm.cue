@@ -0,0 +1,68 @@
+package metadata
+
+base: components: transforms: wasm: configuration: {
+	hot_reload: {
+		description: """
+			Whether or not to reload the module when the file changes.
+
+			The file's modification time is checked every `reload_interval_secs`. When it changes, the
+			new module is loaded and initialized, and replaces the running instance after the shutdown
+			function of that instance has been called. If the new module fails to load, the running
+			instance is kept.
+			"""
+		required: false
+		type: bool: default: false
+	}
+	max_fuel: {
+		description: """
+			The maximum amount of fuel that each call into the module can consume.
+
+			Fuel roughly corresponds to the number of WebAssembly instructions executed. A call that
+			runs out of fuel is aborted, its event is dropped, and the instance is restarted, which
+			discards its state.
+
+			If left unspecified, calls are not limited.
+			"""
+		required: false
+		type: uint: examples: [10000000]
+	}
+	max_memory_bytes: {
+		description: """
+			The maximum size of the module's linear memory, in bytes.
+
+			Attempts to grow the memory beyond this size fail.
+
+			If left unspecified, memory is only limited by the 4GiB address space of the module.
+			"""
+		required: false
+		type: uint: {
+			examples: [67108864]
+			unit: "bytes"
+		}
+	}
+	module: {
+		description: """
+			The path to the WebAssembly module.
+
+			The module must target WASI, and implement the ABI of the `vector-wasm-sdk` crate. Both
+			binary and text modules are supported.
+			"""
+		required: true
+		type: string: examples: ["/etc/vector/transform.wasm"]
+	}
+	options: {
+		description: "Options passed to the module when it is initialized, encoded as JSON."
+		required:    false
+		type: object: examples: [{
+			field: "message"
+		}]
+	}
+	reload_interval_secs: {
+		description: "How often to check the module for changes when `hot_reload` is enabled, in seconds."
+		required:    false
+		type: float: {
+			default: 5.0
+			unit:    "seconds"
+		}
+	}
+}
@@ -0,0 +1,75 @@
+package metadata
+
+base: components: transforms: window: configuration: {
+	flush_when: {
+		description: """
+			A condition used to flush the window.
+
+			If the condition resolves to `true` for an event, the events buffered before it are
+			forwarded, followed by the event itself and up to `num_events_after` events after it.
+			"""
+		required: true
+		type: condition: {}
+	}
+	forward_when: {
+		description: """
+			A condition used to pass events through the transform without buffering them.
+
+			If the condition resolves to `true` for an event, the event is immediately forwarded,
+			without being buffered or checked against `flush_when`.
+			"""
+		required: false
+		type: condition: {}
+	}
+	group_by: {
+		description: """
+			An ordered list of fields by which to group events.
+
+			Each group with matching values for the specified keys has its own window, which is only
+			flushed by events of the same group. When no fields are specified, all events share a single
+			window.
+
+			For example, if `group_by = ["host"]`, then an error logged by one host flushes the events
+			buffered for that host only.
+			"""
+		required: false
+		type: array: {
+			default: []
+			items: type: string: examples: ["host", "request_id"]
+		}
+	}
+	max_windows: {
+		description: """
+			The maximum number of windows to keep, one for each group of events.
+
+			Once the limit is reached, the window of the group that has gone the longest without
+			receiving an event is discarded to make room for a new one.
+			"""
+		required: false
+		type: uint: default: 10000
+	}
+	num_events_after: {
+		description: "The maximum number of events to forward after the event that matched `flush_when`."
+		required:    false
+		type: uint: default: 0
+	}
+	num_events_before: {
+		description: """
+			The maximum number of events to keep before the event that matched `flush_when`.
+
+			Once the window is full, the oldest event in it is dropped for every new event.
+			"""
+		required: false
+		type: uint: default: 100
+	}
+	sample_rate: {
+		description: """
+			The rate at which events discarded from a window are forwarded anyway, expressed as `1/N`.
+
+			For example, `sample_rate = 10` forwards 1 out of every 10 events that are pushed out of a
+			window without having been flushed. If left unspecified, these events are all dropped.
+			"""
+		required: false
+		type: uint: examples: [10]
+	}
+}
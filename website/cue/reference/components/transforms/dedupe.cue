@@ -93,4 +93,11 @@ components: transforms: dedupe: {
 				"""
 		}
 	}
+
+	outputs: [
+		{
+			name:        "duplicates"
+			description: "Duplicate events are sent to the `<transform_name>.duplicates` output when `reroute_duplicates` is enabled."
+		},
+	]
 }
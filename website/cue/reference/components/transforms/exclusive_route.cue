@@ -0,0 +1,73 @@
+package metadata
+
+components: transforms: exclusive_route: {
+	title: "Exclusive Route"
+
+	description: """
+		Splits a stream of events into mutually exclusive sub-streams based on an ordered list of
+		conditions. Each event is only sent to the first route whose condition it matches.
+		"""
+
+	classes: {
+		commonly_used: false
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      false
+	}
+
+	features: {
+		route: {}
+	}
+
+	support: {
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: base.components.transforms.exclusive_route.configuration
+
+	input: {
+		logs: true
+		metrics: {
+			counter:      true
+			distribution: true
+			gauge:        true
+			histogram:    true
+			set:          true
+			summary:      true
+		}
+		traces: true
+	}
+
+	examples: [
+		{
+			title: "Split by log level"
+
+			configuration: {
+				routes: [
+					{name: "errors", condition: #".level == "error""#},
+					{name: "important", condition: #".level == "error" || .level == "warn""#},
+				]
+			}
+
+			input: log: {
+				level: "error"
+			}
+			output: log: {
+				level: "error"
+			}
+		},
+	]
+
+	outputs: [
+		{
+			name:        "<route_name>"
+			description: "Each route can be referenced as an input by other components with the name `<transform_name>.<route_name>`. The number of events each route received is reported by the `component_sent_events_total` metric, tagged with the route name as the `output`."
+		},
+		{
+			name:        "_unmatched"
+			description: "Events that don't match any route are sent to the `<transform_name>._unmatched` output."
+		},
+	]
+}
@@ -145,6 +145,8 @@ components: transforms: reduce: {
 	]
 
 	telemetry: metrics: {
+		reduce_active_groups:       components.sources.internal_metrics.output.metrics.reduce_active_groups
+		reduce_evictions_total:     components.sources.internal_metrics.output.metrics.reduce_evictions_total
 		stale_events_flushed_total: components.sources.internal_metrics.output.metrics.stale_events_flushed_total
 	}
 }
@@ -171,6 +171,28 @@ components: transforms: tag_cardinality_limit: {
 				"""
 		}
 
+		overrides: {
+			title: "Per-metric and per-tag overrides"
+			body: """
+				The global `value_limit` and `limit_exceeded_action` can be overridden for
+				specific metrics with `per_metric_limits`. Each overridden metric tracks the
+				values of its tags on its own, so a high-value metric can be given a larger
+				limit without its values being rejected because other metrics already hit the
+				limit for the same tag key.
+
+				The limit for specific tag keys, such as a noisy `user_id`, can be set with
+				`per_tag_limits`, which takes precedence over both the global and the
+				per-metric limits. Tags listed in `excluded_tags` are never limited.
+
+				Overrides apply in both `exact` and `probabilistic` mode. In `probabilistic`
+				mode every key uses the same `cache_size_per_key`, so a larger limit also means
+				a higher false positive rate.
+
+				To find out which metric and tag hit its limit, set
+				`internal_metrics.include_extended_tags` to `true`.
+				"""
+		}
+
 		restarts: {
 			title: "Restarts"
 			body: """
@@ -0,0 +1,66 @@
+package metadata
+
+components: transforms: tail_sample: {
+	title: "Tail Sample"
+
+	description: """
+		Buffers the spans of each trace until the trace is complete, and then keeps or drops the
+		whole trace based on sampling policies, such as whether it contains an error or a slow span.
+		"""
+
+	classes: {
+		commonly_used: false
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      true
+	}
+
+	features: {
+		filter: {}
+	}
+
+	support: {
+		requirements: []
+		warnings: [
+			"""
+				Traces are kept in memory until a decision is made, which delays them by up to
+				`decision_wait_secs`. Spans buffered when Vector stops are decided on right away.
+				""",
+		]
+		notices: []
+	}
+
+	configuration: base.components.transforms.tail_sample.configuration
+
+	input: {
+		logs:    false
+		metrics: null
+		traces:  true
+	}
+
+	telemetry: metrics: {
+		tail_sample_policy_decisions_total: components.sources.internal_metrics.output.metrics.tail_sample_policy_decisions_total
+		tail_sample_traces_evicted_total:   components.sources.internal_metrics.output.metrics.tail_sample_traces_evicted_total
+	}
+
+	how_it_works: {
+		decisions: {
+			title: "Decisions"
+			body: """
+				Events are grouped by trace ID. A decision is made for a trace once no span has been
+				received for it for `decision_wait_secs`, or as soon as an event matches `complete_when`.
+				The trace is then forwarded as a whole if it matches at least one policy, and dropped
+				otherwise. Spans arriving after the decision follow it, as long as the decision is still
+				within the last `decision_cache_size` ones.
+				"""
+		}
+		memory: {
+			title: "Memory Usage"
+			body: """
+				The buffered traces are limited to `max_buffered_bytes`. When the limit is reached, a
+				decision is made early for the traces that have been idle the longest, which is tracked by
+				the `tail_sample_traces_evicted_total` metric.
+				"""
+		}
+	}
+}
@@ -73,6 +73,13 @@ components: transforms: throttle: {
 		},
 	]
 
+	outputs: [
+		{
+			name:        "dropped"
+			description: "Throttled events are sent to the `<transform_name>.dropped` output when `reroute_dropped` is enabled."
+		},
+	]
+
 	how_it_works: {
 		rate_limiting: {
 			title: "Rate Limiting"
@@ -107,7 +114,24 @@ components: transforms: throttle: {
 						The rate limiter will allow up to `threshold` number of events through and drop any further events
 						for that particular bucket when the rate limiter is at capacity. Any event passed when the rate
 						limiter is at capacity will be discarded and tracked by an `events_discarded_total` metric tagged
-						by the bucket's `key`.
+						by the bucket's `key`, unless `reroute_dropped` is enabled, in which case it is sent to the
+						`dropped` output instead.
+						"""
+				},
+				{
+					title: "Per-bucket Thresholds"
+					body: """
+						Instead of a fixed number, `threshold` can be a template rendered against the first event of a
+						bucket, or a lookup in an enrichment table keyed on the bucket key. The threshold is resolved when
+						a bucket is first seen, and kept until the bucket has been idle for twice the `window_secs`.
+						"""
+				},
+				{
+					title: "Byte Limits"
+					body: """
+						`threshold_bytes` limits the estimated JSON-encoded size of the events in a bucket, rather than
+						their number. Each event consumes as many cells as it has bytes. When both `threshold` and
+						`threshold_bytes` are set, an event must fit within both limits to pass through.
 						"""
 				},
 			]
@@ -0,0 +1,92 @@
+package metadata
+
+components: transforms: wasm: {
+	title: "WebAssembly"
+
+	description: """
+		Modify events using a [WebAssembly](\(urls.wasm)) module targeting WASI, such as a
+		transform written in Rust with the `vector-wasm-sdk` crate.
+		"""
+
+	classes: {
+		commonly_used: false
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      true
+	}
+
+	features: {
+		program: {
+			runtime: {
+				name:    "WebAssembly"
+				url:     urls.wasm
+				version: null
+			}
+		}
+	}
+
+	support: {
+		requirements: [
+			"""
+				The transform isn't included in the default builds of Vector. Vector must be built
+				with the `transforms-wasm` feature to use it.
+				""",
+		]
+		warnings: [
+			"""
+				Each event is encoded to and decoded from protobuf when crossing into the module. We
+				recommend that you use the [`remap` transform](\(urls.vector_remap_transform)) whenever
+				possible.
+				""",
+		]
+		notices: []
+	}
+
+	configuration: base.components.transforms.wasm.configuration
+
+	input: {
+		logs: true
+		metrics: {
+			counter:      true
+			distribution: true
+			gauge:        true
+			histogram:    true
+			set:          true
+			summary:      true
+		}
+		traces: true
+	}
+
+	telemetry: metrics: {
+		wasm_module_reloads_total: components.sources.internal_metrics.output.metrics.wasm_module_reloads_total
+	}
+
+	how_it_works: {
+		abi: {
+			title: "ABI"
+			body: """
+				Events are passed to the module as protobuf-encoded `EventWrapper` messages, as
+				defined by Vector's native event protocol, and the module sends events back by calling
+				the `emit` function imported from the `vector` module. The `vector-wasm-sdk` crate
+				implements this ABI, so a transform written in Rust only needs to implement its
+				`Transform` trait and be compiled for the `wasm32-wasi` target.
+				"""
+		}
+		state: {
+			title: "State"
+			body: """
+				A single instance of the module is kept for the lifetime of the transform, so any
+				state it holds is preserved between events. The instance is replaced, and its state
+				lost, when the module is reloaded, or when a call traps, such as when it runs out of
+				fuel.
+				"""
+		}
+		limits: {
+			title: "Resource Limits"
+			body: """
+				`max_fuel` bounds the work done by each call, and `max_memory_bytes` bounds the size of
+				the module's memory. The event being processed by a call that fails is dropped.
+				"""
+		}
+	}
+}
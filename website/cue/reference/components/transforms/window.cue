@@ -0,0 +1,62 @@
+package metadata
+
+components: transforms: window: {
+	title: "Window"
+
+	description: """
+		Keeps a sliding window of recent events, and only forwards them when an event matching a
+		condition is seen. This is useful to, for example, only ship the debug logs that lead up to
+		an error.
+		"""
+
+	classes: {
+		commonly_used: false
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      true
+	}
+
+	features: {
+		filter: {}
+	}
+
+	support: {
+		requirements: []
+		warnings: [
+			"""
+				A window is kept in memory for every distinct `group_by` value, up to `max_windows`, so
+				grouping by a field with many unique values can use a lot of memory.
+				""",
+		]
+		notices: []
+	}
+
+	configuration: base.components.transforms.window.configuration
+
+	input: {
+		logs:    true
+		metrics: null
+		traces:  false
+	}
+
+	examples: [
+		{
+			title: "Flush debug logs before an error"
+			configuration: {
+				flush_when:        #".level == "error""#
+				num_events_before: 2
+			}
+			input: [
+				{log: {level: "debug", message: "Opening connection"}},
+				{log: {level: "debug", message: "Sending request"}},
+				{log: {level: "debug", message: "Waiting for response"}},
+				{log: {level: "error", message: "Request timed out"}},
+			]
+			output: [
+				{log: {level: "debug", message: "Sending request"}},
+				{log: {level: "debug", message: "Waiting for response"}},
+				{log: {level: "error", message: "Request timed out"}},
+			]
+		},
+	]
+}
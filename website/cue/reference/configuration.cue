@@ -449,7 +449,7 @@ configuration: {
 			common: false
 			description: """
 				Configuration options to retrieve secrets from external backend in order to avoid storing secrets in plaintext
-				in Vector config. Currently, only the exec backend is supported. Multiple backends can be configured. To signify
+				in Vector config. The exec, file, and directory backends are supported. Multiple backends can be configured. To signify
 				Vector that it should look for a secret to retrieve use the `SECRET[<backend_name>.<secret_key>]`. This placeholder
 				will then be replaced by the secret retrieved from the relevant backend.
 				"""
@@ -504,6 +504,64 @@ configuration: {
 						}
 					}
 				}
+				file: {
+					required: true
+					description: """
+						Read secrets from a JSON file.
+
+						The file must contain a JSON object mapping each secret key to its value:
+
+						```json
+						{
+							"secret1": "secret_value",
+							"secret2": "another_secret_value"
+						}
+						```
+
+						The file is read when Vector starts or if Vector receives a `SIGHUP` signal triggering its
+						configuration reload process, so rotated secrets are picked up without a restart.
+						"""
+					type: object: options: {
+						path: {
+							description: "The path of the JSON file to read secrets from."
+							required:    true
+							type: string: {
+								examples: ["/path/to/secrets.json"]
+							}
+						}
+						remove_trailing_newline: {
+							description: "Whether or not to remove trailing newlines from the secret values."
+							required:    false
+							common:      false
+							type: bool: default: false
+						}
+					}
+				}
+				directory: {
+					required: true
+					description: """
+						Read secrets from a directory, where each file is named after a secret key and contains
+						the secret value. This is the layout used by Kubernetes when mounting a `Secret` as a volume.
+
+						The files are read when Vector starts or if Vector receives a `SIGHUP` signal triggering its
+						configuration reload process, so rotated secrets are picked up without a restart.
+						"""
+					type: object: options: {
+						path: {
+							description: "The path of the directory to read secrets from."
+							required:    true
+							type: string: {
+								examples: ["/var/run/secrets/vector"]
+							}
+						}
+						remove_trailing_newline: {
+							description: "Whether or not to remove trailing newlines from the secret values."
+							required:    false
+							common:      false
+							type: bool: default: false
+						}
+					}
+				}
 			}
 		}
 
@@ -634,8 +692,9 @@ configuration: {
 				sensitive token are configured in a dedicated section (`secret`). In the rest of the configuration you should use
 				the `SECRET[<backend_name>.<secret_key>]` notation to interpolate the secret. Interpolation will happen immediately after
 				environment variables interpolation. While Vector supports multiple commands to retrieve secrets, a
-				secret backend cannot use the secret interpolation feature for its own configuration. Currently the only supported
-				kind of secret backend is the `exec` one that runs an external command to retrieve secrets.
+				secret backend cannot use the secret interpolation feature for its own configuration. The supported kinds of
+				secret backend are `exec`, which runs an external command to retrieve secrets, `file`, which reads secrets from
+				a JSON file, and `directory`, which reads each secret from its own file.
 
 				The following example shows a simple configuration with two backends defined:
 
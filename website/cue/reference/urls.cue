@@ -359,6 +359,8 @@ urls: {
 	mongodb:                                    "https://www.mongodb.com"
 	mongodb_command_server_status:              "https://docs.mongodb.com/manual/reference/command/serverStatus/"
 	mongodb_connection_string_uri_format:       "https://docs.mongodb.com/manual/reference/connection-string/"
+	mqtt:                                       "https://mqtt.org/"
+	mqtt_topics:                                "https://www.hivemq.com/blog/mqtt-essentials-part-5-mqtt-topics-best-practices/"
 	musl_builder_docker_image:                  "\(vector_repo)/blob/master/scripts/ci-docker-images/builder-x86_64-unknown-linux-musl/Dockerfile"
 	native_proto_schema:                        "\(vector_repo)/blob/master/lib/vector-core/proto/event.proto"
 	native_json_schema:                         "\(vector_repo)/blob/master/lib/codecs/tests/data/native_encoding/schema.cue"
@@ -442,6 +444,7 @@ urls: {
 	rfc_6891:                                   "https://tools.ietf.org/html/rfc6891"
 	rhel:                                       "https://www.redhat.com/en/technologies/linux-platforms/enterprise-linux"
 	rpm:                                        "https://rpm.org/"
+	rumqttc:                                    "\(github)/bytebeamio/rumqtt"
 	rust:                                       "https://www.rust-lang.org/"
 	rust_date_time:                             "https://docs.rs/chrono/latest/chrono/struct.DateTime.html"
 	rust_grok_library:                          "\(github)/daschl/grok"